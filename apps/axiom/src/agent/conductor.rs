@@ -0,0 +1,57 @@
+//! Conductor planning flow: a single structured completion instead of the
+//! tool loop, validated (and if needed re-requested) against the plan schema.
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::llm::json_repair;
+use crate::llm::usage::Usage;
+use crate::llm::{JsonSchemaFormat, LlmProvider, Message, MessageContent};
+use crate::prompts::conductor::plan_schema;
+
+/// Attempts before giving up on a plan that won't validate.
+const MAX_ATTEMPTS: usize = 2;
+
+/// Ask the model for a plan. Returns the validated plan and the usage of all
+/// attempts combined.
+pub async fn request_plan(client: &dyn LlmProvider, mut messages: Vec<Message>) -> Result<(Value, Usage)> {
+    let format = JsonSchemaFormat {
+        name: "conductor_plan".to_string(),
+        schema: plan_schema(),
+    };
+    let mut usage = Usage::default();
+
+    let mut attempt = 1;
+    loop {
+        let response = client.chat_structured(messages.clone(), &format).await?;
+        if let Some(u) = response.usage {
+            usage.input_tokens += u.input_tokens;
+            usage.output_tokens += u.output_tokens;
+        }
+
+        let text = response.message.text();
+        match json_repair::parse_validated(&text, &format.schema) {
+            Ok(plan) => return Ok((plan, usage)),
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                tracing::info!("Plan rejected (attempt {}): {}", attempt, e);
+                messages.push(Message {
+                    role: "assistant".to_string(),
+                    content: Some(MessageContent::Text(text)),
+                    tool_calls: None,
+                    tool_call_id: None,
+                });
+                messages.push(Message {
+                    role: "user".to_string(),
+                    content: Some(MessageContent::Text(format!(
+                        "That plan is not valid: {}\nReply with the corrected JSON plan only.",
+                        e
+                    ))),
+                    tool_calls: None,
+                    tool_call_id: None,
+                });
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
@@ -1,5 +1,8 @@
+pub mod conductor;
 pub mod profiles;
 
+pub use axiom_agent_core::history;
+
 pub use crate::types::AgentProfile; // Re-export as public
 
-pub use profiles::get_default_agents;
+pub use profiles::{get_default_agents, get_local_agents};
@@ -1,3 +1,4 @@
+use crate::llm::{ProviderKind, ReasoningEffort, SamplingParams};
 use crate::types::AgentProfile;
 
 pub fn get_default_agents() -> Vec<AgentProfile> {
@@ -5,38 +6,75 @@ pub fn get_default_agents() -> Vec<AgentProfile> {
         AgentProfile {
             name: "General Assistant".to_string(),
             description: "Balanced for everyday tasks.".to_string(),
+            provider: ProviderKind::OpenAiCompatible,
             model: "gemini-2.5-flash".to_string(),
             research_mode: "Smart Hybrid".to_string(),
             context_mode: "General".to_string(),
             avatar_path: "bot.png".to_string(),
             system_prompt: "You are Axiom, a helpful AI assistant. You are capable, honest, and efficient.".to_string(),
+            sampling: SamplingParams::default(),
+            allowed_tools: None,
+            color: Some([120, 200, 80]),
         },
         AgentProfile {
             name: "Bevy Architect".to_string(),
             description: "Expert in Bevy 0.18 (Future).".to_string(),
+            provider: ProviderKind::OpenAiCompatible,
             model: "gemini-2.5-pro".to_string(),
             research_mode: "Smart Hybrid".to_string(),
             context_mode: "Bevy 0.18 (Future)".to_string(),
             avatar_path: "bevy.png".to_string(),
             system_prompt: "You are a Senior Graphics Engineer specializing in Bevy Engine. You prefer ECS patterns and strict Rust type safety.".to_string(),
+            sampling: SamplingParams { temperature: Some(0.2), ..Default::default() },
+            allowed_tools: None,
+            color: Some([104, 144, 240]),
         },
         AgentProfile {
             name: "Pokemon Professor".to_string(),
             description: "Fast responses for pokedex queries.".to_string(),
+            provider: ProviderKind::OpenAiCompatible,
             model: "gemini-2.5-flash".to_string(),
             research_mode: "Fast".to_string(),
             context_mode: "Pokemon Gen9".to_string(),
             avatar_path: "pokemon.png".to_string(),
             system_prompt: "You are Professor Oak. You study Pokemon and help trainers complete their Pokedex.".to_string(),
+            sampling: SamplingParams::default(),
+            allowed_tools: None,
+            color: Some([248, 208, 48]),
         },
         AgentProfile {
             name: "Deep Researcher".to_string(),
             description: "Thorough web search and verification.".to_string(),
+            provider: ProviderKind::OpenAiCompatible,
             model: "gemini-2.5-pro".to_string(),
             research_mode: "Deep Research".to_string(),
             context_mode: "General".to_string(),
             avatar_path: "research.png".to_string(),
             system_prompt: "You are a Deep Research Specialist. Your goal is to find, verify, and synthesize information from multiple sources.".to_string(),
+            sampling: SamplingParams { reasoning_effort: Some(ReasoningEffort::High), ..Default::default() },
+            allowed_tools: None,
+            color: Some([160, 64, 160]),
         },
     ]
 }
+
+/// One profile per model pulled into the local Ollama server, so the editor
+/// can run fully offline.
+pub fn get_local_agents(models: &[String]) -> Vec<AgentProfile> {
+    models
+        .iter()
+        .map(|model| AgentProfile {
+            name: format!("Local: {}", model),
+            description: format!("Runs {} on the local Ollama server.", model),
+            provider: ProviderKind::Ollama,
+            model: model.clone(),
+            research_mode: "Fast".to_string(),
+            context_mode: "General".to_string(),
+            avatar_path: "local.png".to_string(),
+            system_prompt: "You are Axiom, a helpful AI assistant running locally. You are capable, honest, and efficient.".to_string(),
+            sampling: SamplingParams::default(),
+            allowed_tools: None,
+            color: None,
+        })
+        .collect()
+}
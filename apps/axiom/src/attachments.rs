@@ -0,0 +1,123 @@
+//! Files attached to the next chat message.
+//!
+//! Dropped or pasted images become vision parts, text files are inlined as
+//! fenced snippets, and glTF models are held back so they can be uploaded to
+//! the running game instead of being sent to the model.
+
+use crate::llm::image::PreparedImage;
+use crate::llm::{ContentPart, MessageContent};
+use anyhow::{bail, Context, Result};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+/// Larger text files are refused rather than blowing up the context.
+const MAX_TEXT_BYTES: u64 = 256 * 1024;
+const MODEL_EXTENSIONS: &[&str] = &["glb", "gltf"];
+
+pub struct PendingImage {
+    pub part: ContentPart,
+    pub texture: egui::TextureHandle,
+}
+
+pub struct TextAttachment {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+#[derive(Default)]
+pub struct Attachments {
+    pub images: Vec<PendingImage>,
+    pub files: Vec<TextAttachment>,
+    /// Dropped models, offered for upload to the game.
+    pub models: Vec<PathBuf>,
+}
+
+impl Attachments {
+    /// Whether anything would be sent with the message.
+    pub fn has_content(&self) -> bool {
+        !self.images.is_empty() || !self.files.is_empty()
+    }
+
+    pub fn add_image(&mut self, ctx: &egui::Context, prepared: PreparedImage) {
+        let size = [prepared.preview.width() as usize, prepared.preview.height() as usize];
+        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, prepared.preview.as_raw());
+        let texture = ctx.load_texture(
+            format!("pending_image_{}", self.images.len()),
+            color_image,
+            egui::TextureOptions::default(),
+        );
+        self.images.push(PendingImage { part: prepared.part, texture });
+    }
+
+    /// Attach a dropped file according to its type.
+    pub fn add_path(&mut self, ctx: &egui::Context, path: &Path) -> Result<()> {
+        if crate::llm::image::is_image_path(path) {
+            self.add_image(ctx, crate::llm::image::from_path(path)?);
+        } else if is_model_path(path) {
+            if !self.models.iter().any(|p| p == path) {
+                self.models.push(path.to_path_buf());
+            }
+        } else {
+            self.files.push(read_text(path)?);
+        }
+        Ok(())
+    }
+
+    /// Build the message from `text` and the sendable attachments, which are
+    /// removed. File paths are shown relative to `root` when inside it.
+    pub fn take_content(&mut self, text: &str, root: &Path) -> MessageContent {
+        let mut text = text.to_string();
+        for file in self.files.drain(..) {
+            let shown = file.path.strip_prefix(root).unwrap_or(&file.path);
+            let language = file.path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            let fence = crate::export::fence(&file.content);
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&format!(
+                "📎 `{}`:\n{}{}\n{}\n{}",
+                shown.display(),
+                fence,
+                language,
+                file.content.trim_end(),
+                fence
+            ));
+        }
+
+        if self.images.is_empty() {
+            return MessageContent::Text(text);
+        }
+        let mut parts = Vec::new();
+        if !text.is_empty() {
+            parts.push(ContentPart {
+                r#type: "text".to_string(),
+                text: Some(text),
+                image_url: None,
+            });
+        }
+        parts.extend(self.images.drain(..).map(|image| image.part));
+        MessageContent::Parts(parts)
+    }
+}
+
+pub fn is_model_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| MODEL_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// A UTF-8 text file small enough to send to the model.
+pub fn read_text(path: &Path) -> Result<TextAttachment> {
+    let size = std::fs::metadata(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?
+        .len();
+    if size > MAX_TEXT_BYTES {
+        bail!("{} is too large to attach ({} KB, limit {} KB)", path.display(), size / 1024, MAX_TEXT_BYTES / 1024);
+    }
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    if bytes.contains(&0) {
+        bail!("{} looks like a binary file; only text files, images and models can be attached", path.display());
+    }
+    let content = String::from_utf8(bytes).with_context(|| format!("{} is not UTF-8 text", path.display()))?;
+    Ok(TextAttachment { path: path.to_path_buf(), content })
+}
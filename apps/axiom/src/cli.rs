@@ -0,0 +1,315 @@
+//! `axiom-cli`: run an agent from the terminal.
+//!
+//! Uses the editor's agents, prompts and tools on the same runtime, without
+//! the UI, so agents can be driven from scripts and CI. The reply streams to
+//! stdout; tool activity and errors go to stderr. Credentials come from the
+//! environment, `.env` and `axiom.toml`, not from the editor's settings file.
+//!
+//! `--run` executes an automation script instead, with no model involved.
+
+use anyhow::{anyhow, bail, Context, Result};
+use axiom_agent_core::{AgentEvent, AgentRuntime};
+use bevy_ai_editor::{agent, llm, project, prompts, session, tools, types};
+use llm::{create_provider, Message, MessageContent, ProviderKind};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use types::AgentProfile;
+
+const USAGE: &str = "\
+Usage: axiom-cli [OPTIONS] [PROMPT]...
+       axiom-cli [-p DIR] --run FILE [--var NAME=VALUE]...
+
+Runs PROMPT as one agent turn in the project. Without PROMPT or --script,
+the prompt is read from stdin. --run executes a YAML or JSON automation
+script's tool calls instead, without a model.
+
+Options:
+  -p, --project DIR     Project folder (default: the current directory)
+  -a, --agent NAME      Agent profile (default: the project's current one)
+  -m, --model MODEL     Use this model instead of the profile's
+      --provider NAME   openai, anthropic, gemini or ollama
+  -s, --script FILE     Run each blank-line separated paragraph of FILE as a
+                        turn of one conversation (\"-\" reads stdin)
+      --max-turns N     Model requests allowed per turn (default: 50)
+  -r, --run FILE        Run an automation script
+      --var NAME=VALUE  Set a script variable; VALUE is parsed as JSON if it
+                        can be, else taken as text
+  -q, --quiet           Print only the replies, not tool activity
+  -v, --verbose         Log at info level
+  -h, --help            Show this help";
+
+/// Tool arguments and results are cut to this in the activity log.
+const MAX_ACTIVITY_CHARS: usize = 300;
+
+#[derive(Default)]
+struct Options {
+    project: Option<PathBuf>,
+    agent: Option<String>,
+    model: Option<String>,
+    provider: Option<ProviderKind>,
+    script: Option<String>,
+    max_turns: Option<usize>,
+    run: Option<PathBuf>,
+    vars: serde_json::Map<String, serde_json::Value>,
+    quiet: bool,
+    verbose: bool,
+    prompt: Vec<String>,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>> {
+        let mut options = Options::default();
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| anyhow!("{} needs a value", name));
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "-p" | "--project" => options.project = Some(value(&arg)?.into()),
+                "-a" | "--agent" => options.agent = Some(value(&arg)?),
+                "-m" | "--model" => options.model = Some(value(&arg)?),
+                "--provider" => {
+                    let name = value(&arg)?;
+                    options.provider = Some(ProviderKind::from_name(&name).ok_or_else(|| anyhow!("Unknown provider `{}`", name))?);
+                }
+                "-s" | "--script" => options.script = Some(value(&arg)?),
+                "--max-turns" => {
+                    let n = value(&arg)?;
+                    options.max_turns = Some(n.parse().with_context(|| format!("Invalid --max-turns `{}`", n))?);
+                }
+                "-r" | "--run" => options.run = Some(value(&arg)?.into()),
+                "--var" => {
+                    let var = value(&arg)?;
+                    let (name, text) = var.split_once('=').ok_or_else(|| anyhow!("--var needs NAME=VALUE, got `{}`", var))?;
+                    let value = serde_json::from_str(text).unwrap_or_else(|_| serde_json::Value::String(text.to_string()));
+                    options.vars.insert(name.trim().to_string(), value);
+                }
+                "-q" | "--quiet" => options.quiet = true,
+                "-v" | "--verbose" => options.verbose = true,
+                "--" => options.prompt.extend(args.by_ref()),
+                flag if flag.starts_with('-') && flag != "-" => bail!("Unknown option `{}`\n\n{}", flag, USAGE),
+                _ => options.prompt.push(arg),
+            }
+        }
+        Ok(Some(options))
+    }
+
+    /// The user messages to send, in order.
+    fn turns(&self) -> Result<Vec<String>> {
+        let text = match &self.script {
+            Some(path) => {
+                if !self.prompt.is_empty() {
+                    bail!("Pass either a prompt or --script, not both");
+                }
+                let text = if path == "-" {
+                    read_stdin()?
+                } else {
+                    std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+                };
+                return Ok(paragraphs(&text));
+            }
+            None if !self.prompt.is_empty() => self.prompt.join(" "),
+            None => read_stdin()?,
+        };
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            bail!("Nothing to send\n\n{}", USAGE);
+        }
+        Ok(vec![text])
+    }
+}
+
+fn read_stdin() -> Result<String> {
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text).context("Failed to read stdin")?;
+    Ok(text)
+}
+
+fn paragraphs(text: &str) -> Vec<String> {
+    text.split("\n\n")
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty() && !p.lines().all(|l| l.trim_start().starts_with('#')))
+        .collect()
+}
+
+/// `--agent`, else the project's current profile, else the default one.
+fn pick_profile(options: &Options, root: &std::path::Path, default_model: &str) -> Result<AgentProfile> {
+    let mut profiles = agent::get_default_agents();
+    profiles.extend(agent::get_local_agents(&llm::ollama::discover_models()));
+    let mut current = None;
+    if let Some(mut saved) = session::Session::load(root) {
+        saved.merge_profiles(profiles, &[]);
+        current = Some(saved.current_profile);
+        profiles = saved.profiles;
+    }
+
+    let mut profile = match &options.agent {
+        Some(name) => profiles
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| {
+                let names: Vec<&str> = profiles.iter().map(|p| p.name.as_str()).collect();
+                anyhow!("No agent named `{}`. Available: {}", name, names.join(", "))
+            })?,
+        None => current
+            .and_then(|name| profiles.iter().find(|p| p.name == name).cloned())
+            .unwrap_or_else(|| {
+                let mut profile = AgentProfile::default();
+                if !default_model.trim().is_empty() {
+                    profile.model = default_model.trim().to_string();
+                }
+                profile
+            }),
+    };
+    if let Some(model) = &options.model {
+        profile.model = model.clone();
+    }
+    if let Some(provider) = options.provider {
+        profile.provider = provider;
+    }
+    Ok(profile)
+}
+
+fn clip(text: &str) -> String {
+    let text = text.trim();
+    if text.chars().count() > MAX_ACTIVITY_CHARS {
+        format!("{}…", text.chars().take(MAX_ACTIVITY_CHARS).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+/// `--run`: the script's tool calls, without a model. Edits aren't reviewed.
+fn run_automation(options: &Options, path: &std::path::Path) -> Result<()> {
+    use axiom_agent_core::automation::{AutomationEvent, AutomationRunner, Script};
+
+    if !options.prompt.is_empty() || options.script.is_some() {
+        bail!("--run takes no prompt or --script");
+    }
+    let script = Script::load(path)?;
+    let (tx, _rx) = std::sync::mpsc::channel();
+    let tools = tools::get_every_tool(tx);
+    let report = AutomationRunner::new(&tools).run(&script, options.vars.clone(), |event| match event {
+        AutomationEvent::ToolCall { step, name, arguments } if !options.quiet => {
+            eprintln!("→ {} {} {}", step, name, clip(&arguments));
+        }
+        AutomationEvent::ToolResult { step, name, output } => match output {
+            Ok(output) => println!("{} {}: {}", step, name, output.trim()),
+            Err(e) => eprintln!("✘ {} {}: {}", step, name, e),
+        },
+        AutomationEvent::Skipped { step } if !options.quiet => eprintln!("– {} skipped", step),
+        _ => {}
+    })?;
+    if !options.quiet {
+        eprintln!("{}", report.summary());
+    }
+    Ok(())
+}
+
+async fn run(options: Options) -> Result<()> {
+    let root = match &options.project {
+        Some(root) => root.clone(),
+        None => std::env::current_dir()?,
+    };
+    // Loading the project moves into it
+    let script = options.run.as_ref().map(std::path::absolute).transpose()?;
+    let project = project::Project::load(&root)?;
+    if let Some(path) = script {
+        // The bevy tools start their own runtime, which can't nest in ours
+        return std::thread::scope(|s| s.spawn(|| run_automation(&options, &path)).join())
+            .unwrap_or_else(|_| Err(anyhow!("Automation panicked")));
+    }
+    let turns = options.turns()?;
+    let profile = pick_profile(&options, &project.root, &project.config.llm.default_model)?;
+
+    let provider = create_provider(profile.provider, &profile.model, &profile.sampling)
+        .context("Failed to create the provider")?;
+    tracing::info!("Agent {} using {} model {}", profile.name, provider.kind().label(), provider.model());
+
+    // Tools only send on it to ask for edit reviews, which the CLI doesn't do
+    let (tx, _rx) = std::sync::mpsc::channel();
+    let tools = tools::get_tools_for_profile(&profile, tx);
+    let mut runtime = AgentRuntime::new(provider, tools);
+    if let Some(max_turns) = options.max_turns {
+        runtime = runtime.with_max_turns(max_turns);
+    }
+
+    let base = prompts::get_system_prompt(&profile.research_mode, &profile.context_mode, &profile.system_prompt);
+    let mut messages = vec![Message {
+        role: "system".to_string(),
+        content: Some(MessageContent::Text(prompts::agent_prompt(&base, &project.root))),
+        tool_calls: None,
+        tool_call_id: None,
+    }];
+    prompts::template::resolve_game_status(&mut messages).await;
+
+    let (mut input_tokens, mut output_tokens) = (0u64, 0u64);
+    for (i, turn) in turns.iter().enumerate() {
+        if turns.len() > 1 && !options.quiet {
+            eprintln!("── Turn {}/{}: {}", i + 1, turns.len(), clip(turn.lines().next().unwrap_or_default()));
+        }
+        messages.push(Message {
+            role: "user".to_string(),
+            content: Some(MessageContent::Text(turn.clone())),
+            tool_calls: None,
+            tool_call_id: None,
+        });
+
+        let mut stdout = std::io::stdout();
+        let result = runtime
+            .run(&mut messages, |event| match event {
+                AgentEvent::Text(text) => {
+                    let _ = write!(stdout, "{}", text);
+                    let _ = stdout.flush();
+                }
+                AgentEvent::Usage(usage) => {
+                    input_tokens += usage.input_tokens;
+                    output_tokens += usage.output_tokens;
+                }
+                AgentEvent::ToolCall { name, arguments } if !options.quiet => {
+                    eprintln!("\n→ {} {}", name, clip(&arguments));
+                }
+                AgentEvent::ToolResult { name, output } if !options.quiet => {
+                    eprintln!("← {} {}", name, clip(&output));
+                }
+                AgentEvent::ContextTrimmed { sent, total } if !options.quiet => {
+                    eprintln!("Context trimmed: sending {} of {} messages", sent, total);
+                }
+                AgentEvent::StreamError(e) => eprintln!("⚠ {}", e),
+                _ => {}
+            })
+            .await;
+        println!();
+        result.with_context(|| format!("Turn {} failed", i + 1))?;
+    }
+
+    if !options.quiet && input_tokens + output_tokens > 0 {
+        eprintln!("Tokens: {} in, {} out", input_tokens, output_tokens);
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return;
+        }
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            std::process::exit(2);
+        }
+    };
+
+    dotenv::dotenv().ok();
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_max_level(if options.verbose { tracing::Level::INFO } else { tracing::Level::WARN })
+        .init();
+
+    if let Err(e) = run(options).await {
+        eprintln!("Error: {:#}", e);
+        std::process::exit(1);
+    }
+}
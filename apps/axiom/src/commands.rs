@@ -0,0 +1,198 @@
+//! Editor commands reachable from the command palette and keyboard shortcuts.
+//!
+//! Shortcuts are strings like `Ctrl+Shift+N`; `Ctrl` means Cmd on macOS.
+//! Overrides are stored in the settings by command id, and an empty string
+//! disables a shortcut.
+
+use eframe::egui;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    OpenPalette,
+    OpenProject,
+    NewChannel,
+    ClearChat,
+    ClearScene,
+    Undo,
+    Redo,
+    RunTests,
+    RunGame,
+    StopGame,
+    RestartGame,
+    ConnectToGame,
+    RunAutomation,
+    StopGeneration,
+    ExportChat,
+    TogglePreview,
+    ToggleConsole,
+    ToggleTodos,
+    ToggleAssets,
+    ToggleMetrics,
+    ToggleSceneHistory,
+    ToggleUndoHistory,
+    ToggleProfiler,
+    OpenSettings,
+    OpenProfiles,
+}
+
+impl Command {
+    pub const ALL: [Command; 25] = [
+        Command::OpenPalette,
+        Command::OpenProject,
+        Command::NewChannel,
+        Command::ClearChat,
+        Command::ClearScene,
+        Command::Undo,
+        Command::Redo,
+        Command::RunTests,
+        Command::RunGame,
+        Command::StopGame,
+        Command::RestartGame,
+        Command::ConnectToGame,
+        Command::RunAutomation,
+        Command::StopGeneration,
+        Command::ExportChat,
+        Command::TogglePreview,
+        Command::ToggleConsole,
+        Command::ToggleTodos,
+        Command::ToggleAssets,
+        Command::ToggleMetrics,
+        Command::ToggleSceneHistory,
+        Command::ToggleUndoHistory,
+        Command::ToggleProfiler,
+        Command::OpenSettings,
+        Command::OpenProfiles,
+    ];
+
+    /// Key in the settings' shortcut table.
+    pub fn id(self) -> &'static str {
+        match self {
+            Command::OpenPalette => "open_palette",
+            Command::OpenProject => "open_project",
+            Command::NewChannel => "new_channel",
+            Command::ClearChat => "clear_chat",
+            Command::ClearScene => "clear_scene",
+            Command::Undo => "undo",
+            Command::Redo => "redo",
+            Command::RunTests => "run_tests",
+            Command::RunGame => "run_game",
+            Command::StopGame => "stop_game",
+            Command::RestartGame => "restart_game",
+            Command::ConnectToGame => "connect_to_game",
+            Command::RunAutomation => "run_automation",
+            Command::StopGeneration => "stop_generation",
+            Command::ExportChat => "export_chat",
+            Command::TogglePreview => "toggle_preview",
+            Command::ToggleConsole => "toggle_console",
+            Command::ToggleTodos => "toggle_todos",
+            Command::ToggleAssets => "toggle_assets",
+            Command::ToggleMetrics => "toggle_metrics",
+            Command::ToggleSceneHistory => "toggle_scene_history",
+            Command::ToggleUndoHistory => "toggle_undo_history",
+            Command::ToggleProfiler => "toggle_profiler",
+            Command::OpenSettings => "open_settings",
+            Command::OpenProfiles => "open_profiles",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::OpenPalette => "Command palette",
+            Command::OpenProject => "Open project…",
+            Command::NewChannel => "New channel",
+            Command::ClearChat => "Clear chat",
+            Command::ClearScene => "Clear game scene",
+            Command::Undo => "Undo scene change",
+            Command::Redo => "Redo scene change",
+            Command::RunTests => "Run tests (cargo test)",
+            Command::RunGame => "Build and run the game",
+            Command::StopGame => "Stop the game",
+            Command::RestartGame => "Rebuild and restart the game",
+            Command::ConnectToGame => "Connect to game…",
+            Command::RunAutomation => "Run automation script…",
+            Command::StopGeneration => "Stop generating",
+            Command::ExportChat => "Export chat…",
+            Command::TogglePreview => "Toggle game preview",
+            Command::ToggleConsole => "Toggle console",
+            Command::ToggleTodos => "Toggle todo panel",
+            Command::ToggleAssets => "Toggle asset browser",
+            Command::ToggleMetrics => "Toggle metrics dashboard",
+            Command::ToggleSceneHistory => "Toggle scene history",
+            Command::ToggleUndoHistory => "Toggle undo history",
+            Command::ToggleProfiler => "Toggle profiler",
+            Command::OpenSettings => "Settings",
+            Command::OpenProfiles => "Agent profiles",
+        }
+    }
+
+    /// Destructive commands have no default shortcut.
+    pub fn default_shortcut(self) -> &'static str {
+        match self {
+            Command::OpenPalette => "Ctrl+K",
+            Command::OpenProject => "Ctrl+O",
+            Command::NewChannel => "Ctrl+Shift+N",
+            Command::Undo => "Ctrl+Z",
+            Command::Redo => "Ctrl+Shift+Z",
+            Command::RunTests => "Ctrl+Shift+T",
+            Command::RunGame => "F5",
+            Command::StopGame => "Shift+F5",
+            Command::RestartGame => "Ctrl+Shift+F5",
+            Command::StopGeneration => "Ctrl+Period",
+            Command::ExportChat => "Ctrl+Shift+E",
+            Command::TogglePreview => "Ctrl+Shift+G",
+            Command::ToggleConsole => "Ctrl+J",
+            Command::ToggleTodos => "Ctrl+Shift+D",
+            Command::ToggleAssets => "Ctrl+Shift+A",
+            Command::ToggleUndoHistory => "Ctrl+Shift+H",
+            Command::ToggleProfiler => "Ctrl+Shift+P",
+            Command::OpenSettings => "Ctrl+Comma",
+            Command::ClearChat
+            | Command::ClearScene
+            | Command::RunAutomation
+            | Command::ConnectToGame
+            | Command::ToggleMetrics
+            | Command::ToggleSceneHistory
+            | Command::OpenProfiles => "",
+        }
+    }
+
+    /// Left to a focused text field, where the same keys edit the text.
+    pub fn yields_to_text(self) -> bool {
+        matches!(self, Command::Undo | Command::Redo)
+    }
+}
+
+/// Parse `Ctrl+Shift+K` style shortcuts; `None` if empty or invalid.
+pub fn parse_shortcut(text: &str) -> Option<egui::KeyboardShortcut> {
+    let mut modifiers = egui::Modifiers::NONE;
+    let mut key = None;
+    for part in text.split('+').map(str::trim) {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "cmd" | "command" => modifiers = modifiers | egui::Modifiers::COMMAND,
+            "shift" => modifiers = modifiers | egui::Modifiers::SHIFT,
+            "alt" | "option" => modifiers = modifiers | egui::Modifiers::ALT,
+            _ if key.is_none() => key = Some(egui::Key::from_name(part)?),
+            _ => return None,
+        }
+    }
+    Some(egui::KeyboardShortcut::new(modifiers, key?))
+}
+
+/// The first command whose shortcut was pressed this frame, consuming it.
+pub fn pressed(ctx: &egui::Context, shortcut_for: impl Fn(Command) -> String) -> Option<Command> {
+    let typing = ctx.wants_keyboard_input();
+    let mut shortcuts: Vec<(Command, egui::KeyboardShortcut)> = Command::ALL
+        .into_iter()
+        .filter(|command| !(typing && command.yields_to_text()))
+        .filter_map(|command| Some((command, parse_shortcut(&shortcut_for(command))?)))
+        .collect();
+    // Ctrl+Z also matches Ctrl+Shift+Z, so try the one with more modifiers first
+    shortcuts.sort_by_key(|(_, shortcut)| {
+        let m = shortcut.modifiers;
+        std::cmp::Reverse([m.alt, m.shift, m.command || m.ctrl || m.mac_cmd].iter().filter(|&&on| on).count())
+    });
+    shortcuts
+        .into_iter()
+        .find(|(_, shortcut)| ctx.input_mut(|i| i.consume_shortcut(shortcut)))
+        .map(|(command, _)| command)
+}
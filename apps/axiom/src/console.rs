@@ -0,0 +1,166 @@
+//! Logging setup and the in-app console feed.
+//!
+//! Everything goes through `tracing`: stderr gets the usual formatted output,
+//! and `ConsoleLayer` queues each event for the console panel, which drains
+//! the queue once per frame (`drain`). `AXIOM_LOG` overrides the default
+//! filter, e.g. `AXIOM_LOG=info,bevy_bridge_core=trace`. Events logged with
+//! `notify = true` are also shown as toasts.
+
+use chrono::{DateTime, Local};
+use eframe::egui;
+use std::fmt::Write as _;
+use std::sync::{Mutex, OnceLock};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+/// Events kept for the console between frames; older ones are dropped.
+const MAX_QUEUED: usize = 5000;
+
+#[derive(Clone)]
+pub struct LogEntry {
+    pub time: DateTime<Local>,
+    pub level: Level,
+    /// Short origin shown in the console, e.g. "llm", "tools", "brp".
+    pub source: String,
+    pub message: String,
+    /// Logged with `notify = true`; also shown as a toast.
+    pub notify: bool,
+}
+
+fn queue() -> &'static Mutex<Vec<LogEntry>> {
+    static QUEUE: OnceLock<Mutex<Vec<LogEntry>>> = OnceLock::new();
+    QUEUE.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+static REPAINT: OnceLock<egui::Context> = OnceLock::new();
+
+/// Install the global subscriber. Call once, before anything logs.
+pub fn init() {
+    // BRP traffic is only logged per request at debug level; the console
+    // keeps it, stderr doesn't
+    let console_filter = env_filter().unwrap_or_else(|| default_filter(Level::DEBUG));
+    let stderr_filter = env_filter().unwrap_or_else(|| default_filter(Level::INFO));
+
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr).with_filter(stderr_filter))
+        .with(ConsoleLayer.with_filter(console_filter))
+        .init();
+}
+
+fn default_filter(brp_level: Level) -> Targets {
+    Targets::new()
+        .with_default(Level::WARN)
+        .with_target("bevy_ai_editor", Level::INFO)
+        .with_target("axiom_agent_core", Level::INFO)
+        .with_target("bevy_bridge_core", brp_level)
+        .with_target("tool", Level::INFO)
+        .with_target("game", Level::INFO)
+}
+
+fn env_filter() -> Option<Targets> {
+    let spec = std::env::var("AXIOM_LOG").ok()?;
+    match spec.parse() {
+        Ok(filter) => Some(filter),
+        Err(e) => {
+            eprintln!("Ignoring invalid AXIOM_LOG '{}': {}", spec, e);
+            None
+        }
+    }
+}
+
+/// Repaint the UI when new entries arrive, so an open console stays live.
+pub fn set_repaint_context(ctx: egui::Context) {
+    let _ = REPAINT.set(ctx);
+}
+
+/// Entries logged since the last call, oldest first.
+pub fn drain() -> Vec<LogEntry> {
+    std::mem::take(&mut *queue().lock().unwrap())
+}
+
+/// Console label for a `tracing` target: module paths are shortened to the
+/// part that says where the event came from.
+fn source_of(target: &str) -> String {
+    let mut parts = target.split("::");
+    let krate = parts.next().unwrap_or(target);
+    let module = parts.next();
+    match (krate, module) {
+        ("bevy_bridge_core", _) => "brp".to_string(),
+        ("axiom_agent_core", Some("llm")) => "llm".to_string(),
+        ("axiom_agent_core", _) => "agent".to_string(),
+        ("bevy_ai_editor", Some(module)) => module.to_string(),
+        ("bevy_ai_editor", None) => "app".to_string(),
+        (other, _) => other.to_string(),
+    }
+}
+
+struct ConsoleLayer;
+
+impl<S: Subscriber> Layer<S> for ConsoleLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        // Records bridged from the `log` crate carry their target as a field
+        let target = visitor.log_target.as_deref().unwrap_or(metadata.target());
+        let entry = LogEntry {
+            time: Local::now(),
+            level: *metadata.level(),
+            source: source_of(target),
+            message: visitor.message,
+            notify: visitor.notify,
+        };
+
+        {
+            let mut queue = queue().lock().unwrap();
+            if queue.len() >= MAX_QUEUED {
+                queue.remove(0);
+            }
+            queue.push(entry);
+        }
+        if let Some(ctx) = REPAINT.get() {
+            ctx.request_repaint();
+        }
+    }
+}
+
+/// Formats the `message` field followed by any other fields as `key=value`.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    log_target: Option<String>,
+    notify: bool,
+}
+
+impl Visit for MessageVisitor {
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        if field.name() == "notify" {
+            self.notify = value;
+        } else {
+            let _ = write!(self.message, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.insert_str(0, value);
+        } else if field.name() == "log.target" {
+            self.log_target = Some(value.to_string());
+        } else if !field.name().starts_with("log.") {
+            let _ = write!(self.message, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message.insert_str(0, &format!("{:?}", value));
+        } else if !field.name().starts_with("log.") {
+            let _ = write!(self.message, " {}={:?}", field.name(), value);
+        }
+    }
+}
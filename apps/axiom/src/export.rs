@@ -0,0 +1,179 @@
+//! Export a channel's chat to Markdown or JSON.
+//!
+//! Tool calls and results (kept in the history as "System" log entries) are
+//! written out as structured entries, and pasted images are decoded into an
+//! `<name>_files/` directory next to the export and linked from it.
+
+use crate::llm::MessageContent;
+use crate::types::ChannelState;
+use anyhow::{Context, Result};
+use base64::prelude::*;
+use serde::Serialize;
+use std::path::Path;
+
+pub const TOOL_CALL_PREFIX: &str = "Executing tool: ";
+pub const TOOL_RESULT_PREFIX: &str = "Tool result: ";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    /// From the file extension; anything but `.json` is Markdown.
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => ExportFormat::Json,
+            _ => ExportFormat::Markdown,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Entry {
+    Message {
+        role: String,
+        text: String,
+        /// Paths relative to the export file.
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        images: Vec<String>,
+    },
+    ToolCall { tool: String, arguments: String },
+    ToolResult { tool: String, output: String },
+    Log { text: String },
+    Error { text: String },
+}
+
+#[derive(Serialize)]
+struct Export<'a> {
+    channel: &'a str,
+    name: &'a str,
+    exported_at: String,
+    entries: Vec<Entry>,
+}
+
+/// Split a "<prefix><name> <separator><rest>" log line.
+fn split_tool_line<'a>(text: &'a str, prefix: &str, separator: &str) -> Option<(&'a str, &'a str)> {
+    let rest = text.strip_prefix(prefix)?;
+    Some(rest.split_once(separator).unwrap_or((rest, "")))
+}
+
+/// Parse a "System" history entry written for a tool call.
+pub fn parse_tool_call(text: &str) -> Option<(&str, &str)> {
+    split_tool_line(text, TOOL_CALL_PREFIX, " args: ")
+}
+
+/// Parse a "System" history entry written for a tool result.
+pub fn parse_tool_result(text: &str) -> Option<(&str, &str)> {
+    split_tool_line(text, TOOL_RESULT_PREFIX, "\n")
+}
+
+/// Write `channel` to `path`, in the format its extension names.
+pub fn export_channel(channel: &ChannelState, path: &Path) -> Result<()> {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "chat".to_string());
+    let files_dir_name = format!("{}_files", stem);
+    let files_dir = path.with_file_name(&files_dir_name);
+
+    let mut entries = Vec::new();
+    for (msg_idx, (role, content)) in channel.history.iter().enumerate() {
+        let entry = match content {
+            MessageContent::Text(text) if role == "System" => {
+                if let Some((tool, arguments)) = parse_tool_call(text) {
+                    Entry::ToolCall { tool: tool.to_string(), arguments: arguments.to_string() }
+                } else if let Some((tool, output)) = parse_tool_result(text) {
+                    Entry::ToolResult { tool: tool.to_string(), output: output.to_string() }
+                } else {
+                    Entry::Log { text: text.clone() }
+                }
+            }
+            MessageContent::Text(text) if role == "Error" => Entry::Error { text: text.clone() },
+            MessageContent::Text(text) => Entry::Message { role: role.clone(), text: text.clone(), images: Vec::new() },
+            MessageContent::Parts(parts) => {
+                let mut texts = Vec::new();
+                let mut images = Vec::new();
+                for (part_idx, part) in parts.iter().enumerate() {
+                    if let Some(text) = &part.text {
+                        texts.push(text.as_str());
+                    }
+                    if let Some((mime, data)) = part.image_url.as_ref().and_then(|u| u.as_base64()) {
+                        let ext = mime.strip_prefix("image/").unwrap_or("png").replace("jpeg", "jpg");
+                        let name = format!("image-{}-{}.{}", msg_idx, part_idx, ext);
+                        save_image(&files_dir.join(&name), data)?;
+                        images.push(format!("{}/{}", files_dir_name, name));
+                    }
+                }
+                Entry::Message { role: role.clone(), text: texts.join("\n"), images }
+            }
+        };
+        entries.push(entry);
+    }
+
+    let export = Export {
+        channel: &channel.id,
+        name: &channel.name,
+        exported_at: chrono::Local::now().to_rfc3339(),
+        entries,
+    };
+    let data = match ExportFormat::from_path(path) {
+        ExportFormat::Json => serde_json::to_string_pretty(&export)?,
+        ExportFormat::Markdown => to_markdown(&export),
+    };
+    std::fs::write(path, data).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+fn save_image(path: &Path, data: &str) -> Result<()> {
+    let clean: String = data.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = BASE64_STANDARD.decode(clean).context("Invalid image data")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(path, bytes).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// A code fence longer than any backtick run in `text`.
+pub fn fence(text: &str) -> String {
+    let longest = text
+        .split(|c| c != '`')
+        .map(str::len)
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn code_block(out: &mut String, language: &str, text: &str) {
+    let fence = fence(text);
+    out.push_str(&format!("{}{}\n{}\n{}\n\n", fence, language, text.trim_end(), fence));
+}
+
+fn to_markdown(export: &Export) -> String {
+    let mut out = format!("# {}\n\n_Exported {}_\n\n", export.name, export.exported_at);
+
+    for entry in &export.entries {
+        match entry {
+            Entry::Message { role, text, images } => {
+                out.push_str(&format!("### {}\n\n", role));
+                if !text.trim().is_empty() {
+                    out.push_str(text.trim_end());
+                    out.push_str("\n\n");
+                }
+                for (i, image) in images.iter().enumerate() {
+                    out.push_str(&format!("![image {}]({})\n\n", i + 1, image.replace(' ', "%20")));
+                }
+            }
+            Entry::ToolCall { tool, arguments } => {
+                out.push_str(&format!("**🔧 Tool call:** `{}`\n\n", tool));
+                code_block(&mut out, "json", arguments);
+            }
+            Entry::ToolResult { tool, output } => {
+                out.push_str(&format!("<details><summary>Result: {}</summary>\n\n", tool));
+                code_block(&mut out, "", output);
+                out.push_str("</details>\n\n");
+            }
+            Entry::Log { text } => out.push_str(&format!("> {}\n\n", text.replace('\n', "\n> "))),
+            Entry::Error { text } => out.push_str(&format!("> **Error:** {}\n\n", text.replace('\n', "\n> "))),
+        }
+    }
+    out
+}
@@ -0,0 +1,180 @@
+//! Building and running the user's game from the editor.
+//!
+//! The game is built with `cargo build` using the `[game]` settings of
+//! `axiom.toml`, then its executable is started directly so the process the
+//! editor holds is the game and not cargo. Its output goes to the console
+//! under the `game` target, and its pid to `.axiom/game.toml` for the
+//! debugger MCP server.
+
+use anyhow::{bail, Context, Result};
+use axiom_config::{GameLaunch, GameSettings};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+
+/// What the top panel shows about the game.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GameStatus {
+    /// Not started from the editor.
+    Idle,
+    Building,
+    /// Started from the editor; whether its BRP endpoint answered the last ping.
+    Running { reachable: Option<bool> },
+    /// Exited or failed to build, with the reason.
+    Stopped(String),
+}
+
+pub struct GameProcess {
+    child: Child,
+    pub launch: GameLaunch,
+}
+
+/// Build the game in `root` and start it.
+pub fn launch(root: &Path, settings: &GameSettings) -> Result<GameProcess> {
+    let (program, manifest_dir) = build(root, settings)?;
+    tracing::info!(target: "game", "Starting {}", program.display());
+    let mut child = Command::new(&program)
+        .args(&settings.args)
+        .current_dir(root)
+        // Bevy resolves `assets/` against it when set
+        .env("CARGO_MANIFEST_DIR", &manifest_dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", program.display()))?;
+    if let Some(stdout) = child.stdout.take() {
+        forward_output(stdout);
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_output(stderr);
+    }
+
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    let launch = GameLaunch { pid: child.id(), program, started_at };
+    if let Err(e) = launch.save(root) {
+        tracing::warn!("{:#}", e);
+    }
+    Ok(GameProcess { child, launch })
+}
+
+impl GameProcess {
+    /// The exit status once the game has exited.
+    pub fn exited(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+
+    pub fn stop(mut self, root: &Path) {
+        if let Err(e) = self.child.kill() {
+            tracing::debug!("Failed to kill the game: {}", e);
+        }
+        let _ = self.child.wait();
+        forget(root);
+    }
+}
+
+/// Remove the launch record once the game is gone.
+pub fn forget(root: &Path) {
+    if let Err(e) = GameLaunch::clear(root) {
+        tracing::warn!("Failed to clear the game launch record: {:#}", e);
+    }
+}
+
+/// `cargo build` the game, returning its executable and package directory.
+fn build(root: &Path, settings: &GameSettings) -> Result<(PathBuf, PathBuf)> {
+    let mut cargo = Command::new("cargo");
+    cargo.args(["build", "--message-format=json-render-diagnostics"]).current_dir(root);
+    if settings.release {
+        cargo.arg("--release");
+    }
+    if !settings.package.trim().is_empty() {
+        cargo.args(["-p", settings.package.trim()]);
+    }
+    if !settings.bin.trim().is_empty() {
+        cargo.args(["--bin", settings.bin.trim()]);
+    }
+    if !settings.features.is_empty() {
+        cargo.args(["--features", &settings.features.join(",")]);
+    }
+    tracing::info!(target: "game", "Building the game: {:?}", cargo);
+
+    let mut child = cargo
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Failed to run cargo build")?;
+    // Progress and rendered errors
+    let stderr = child.stderr.take().map(forward_output);
+
+    let mut executables = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if let Some(found) = executable(&line) {
+                executables.push(found);
+            }
+        }
+    }
+    let status = child.wait().context("Failed to wait for cargo build")?;
+    if let Some(stderr) = stderr {
+        let _ = stderr.join();
+    }
+    if !status.success() {
+        bail!("cargo build failed ({}); see the console", status);
+    }
+    match executables.len() {
+        0 => bail!("cargo build produced no executable; set [game] package or bin in axiom.toml"),
+        1 => Ok(executables.remove(0)),
+        _ => bail!("cargo build produced several executables; set [game] bin in axiom.toml"),
+    }
+}
+
+/// The executable and package directory of a `compiler-artifact` message
+/// for a binary.
+fn executable(line: &str) -> Option<(PathBuf, PathBuf)> {
+    let message: serde_json::Value = serde_json::from_str(line).ok()?;
+    if message["reason"] != "compiler-artifact" {
+        return None;
+    }
+    let is_bin = message["target"]["kind"].as_array()?.iter().any(|kind| kind == "bin");
+    if !is_bin {
+        return None;
+    }
+    let program = PathBuf::from(message["executable"].as_str()?);
+    let manifest_dir = Path::new(message["manifest_path"].as_str()?).parent()?.to_path_buf();
+    Some((program, manifest_dir))
+}
+
+/// Log each line of `output` to the console under the `game` target.
+fn forward_output(output: impl Read + Send + 'static) -> std::thread::JoinHandle<()> {
+    std::thread::spawn(move || {
+        for line in BufReader::new(output).lines().map_while(Result::ok) {
+            let line = strip_ansi(&line);
+            if !line.trim().is_empty() {
+                tracing::info!(target: "game", "{}", line);
+            }
+        }
+    })
+}
+
+/// Drop terminal color codes, which the console can't show.
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences end with a letter
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,27 @@
+//! The editor's agents, tools, project handling and UI, shared by the
+//! `bevy_ai_editor` and `axiom-cli` binaries.
+
+pub use axiom_agent_core::llm;
+pub mod attachments;
+pub mod commands;
+pub mod console;
+pub mod export;
+pub mod game;
+pub mod metrics;
+pub mod settings;
+pub mod prompts;
+pub mod personas;
+pub mod project;
+pub mod scene_history;
+pub mod semantic;
+pub mod session;
+pub mod spending;
+pub mod vector_store;
+pub mod watcher;
+pub mod tools;
+pub mod agent;
+pub mod types;
+pub mod ui;
+#[cfg(feature = "voice")]
+pub mod voice;
+// mod simulation; // Removed
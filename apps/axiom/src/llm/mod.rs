@@ -1,7 +0,0 @@
-pub mod gemini;
-
-pub use gemini::{
-    GeminiClient, Message, MessageContent, ContentPart, ImageUrl,
-    StreamEvent,
-    ToolCall, FunctionCall
-};
@@ -1,40 +1,73 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use eframe::egui;
+use std::collections::HashMap;
 use std::sync::mpsc::{channel, Receiver, Sender};
 use tokio::runtime::Runtime;
-use base64::prelude::*;
-use std::io::Cursor;
 use std::process::Command;
-use serde_json::Value;
-
-mod llm;
-mod prompts;
-mod tools;
-mod agent;
-mod types;
-mod ui;
-// mod simulation; // Removed
-
-use crate::llm::{GeminiClient, Message, MessageContent, ContentPart, ImageUrl, StreamEvent, ToolCall, FunctionCall};
-use crate::tools::Tool; // Import Tool trait only
-use crate::agent::{AgentProfile, get_default_agents};
-use crate::types::{AsyncMessage, ChannelState};
-use futures_util::StreamExt;
+
+use bevy_ai_editor::{
+    agent, attachments, commands, console, export, game, llm, metrics, personas, project, prompts, scene_history, session,
+    settings, spending, tools, types, ui, watcher,
+};
+#[cfg(feature = "voice")]
+use bevy_ai_editor::voice;
+
+use llm::{create_provider, LlmProvider, Message, MessageContent};
+use axiom_agent_core::AgentEvent;
+use agent::{AgentProfile, get_default_agents, get_local_agents};
+use types::{AsyncMessage, ChannelState};
 
 // Import UI modules
-use crate::ui::{top_panel, sidebar, input, chat, file_tree};
+use ui::{top_panel, sidebar, input, chat, file_tree};
+
+/// How often a running generation is snapshotted to the session file.
+const AUTOSAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+/// How often the game started from the editor is pinged over BRP.
+const GAME_PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3);
 
 struct AxiomApp {
-    api_key: String,
-    
     // Current Active Configuration
     current_profile: AgentProfile,
     available_profiles: Vec<AgentProfile>,
+    // Built-in profiles deleted in the profile editor
+    removed_profiles: Vec<String>,
+    profile_editor: ui::profiles::ProfileEditorState,
+    palette: ui::palette::PaletteState,
+    todos: ui::todos::TodoPanelState,
+    assets: ui::assets::AssetBrowserState,
+    metrics_panel: ui::metrics::MetricsPanelState,
+    scene_history: ui::scene_history::SceneHistoryState,
+    undo_history: ui::undo_history::UndoHistoryState,
+    profiler: ui::profiler::ProfilerState,
+    toasts: ui::toasts::Toasts,
+    /// Whether the last BRP request from the UI reached the game; `None`
+    /// until the first one.
+    game_reachable: Option<bool>,
+    /// The game started from the editor, while it runs.
+    game: Option<game::GameProcess>,
+    game_status: game::GameStatus,
+    /// When the running game's BRP endpoint was last pinged, and whether
+    /// that ping is still out.
+    last_game_ping: std::time::Instant,
+    game_ping_pending: bool,
+    /// Round trip of the last successful ping.
+    game_latency: Option<std::time::Duration>,
+    connection: ui::connection::ConnectionState,
+    file_preview: ui::file_preview::FilePreviewState,
+    watcher: watcher::Watcher,
     
     // Channels
     channels: std::collections::HashMap<String, ChannelState>,
     active_channel_id: String,
+
+    // Session persistence (.axiom/session.json under the project root)
+    project: project::Project,
+    /// Go back to the project picker after this frame.
+    open_project_requested: bool,
+
+    // Token / cost totals for the running session
+    usage: llm::usage::UsageTracker,
     
     // Mission Control State (Removed)
     // sub_agents: std::collections::HashMap<String, SubAgentState>,
@@ -44,14 +77,50 @@ struct AxiomApp {
 
     // Chat & Input State
     input_text: String,
-    pending_image: Option<String>, 
-    preview_texture: Option<egui::TextureHandle>, 
+    attachments: attachments::Attachments,
+    /// Channel and history index of the user message being edited in the
+    /// input box.
+    editing_message: Option<(String, usize)>,
+    /// Usage of the requests behind each reply, by channel and history index.
+    message_usage: HashMap<String, HashMap<usize, llm::usage::UsageTotals>>,
+    /// Prompt plus reply tokens of each channel's latest request.
+    context_tokens: HashMap<String, u64>,
+    /// Avatars and colors for chat roles that aren't profiles.
+    personas: personas::PersonaRegistry,
+    /// The chat is shown in its own window.
+    chat_detached: bool,
     clipboard: Option<arboard::Clipboard>,
     
     // App State
     is_loading: bool,
     waiting_for_screenshot: bool,
-    client: Option<GeminiClient>,
+    client: Option<std::sync::Arc<dyn LlmProvider>>,
+    // Running agent task; aborting it drops the HTTP stream and any partial tool calls
+    generation: Option<tokio::task::AbortHandle>,
+    // Tool calls of the running generation that haven't returned yet
+    running_tools: Vec<String>,
+    // Generation output not yet written to the session file
+    session_dirty: bool,
+    last_autosave: std::time::Instant,
+    model_picker: ui::input::ModelPickerState,
+    game_preview: ui::preview::GamePreviewState,
+    console: ui::console::ConsoleState,
+    // Persisted editor settings and the window editing them
+    settings: settings::Settings,
+    settings_window: ui::settings::SettingsState,
+    // Agent file edits waiting for Approve/Reject
+    pending_reviews: Vec<tools::review::PendingReview>,
+    #[cfg(feature = "voice")]
+    voice: voice::VoiceState,
+    // Set while the provider's circuit breaker is open
+    provider_unavailable_until: Option<std::time::Instant>,
+    // A spending budget was reached: the message, and whether a request is
+    // waiting to start once the user confirms
+    budget_blocked: Option<(String, bool)>,
+    // The user chose to keep spending past the budget this session
+    budget_confirmed: bool,
+    // The budget warning was shown this session
+    budget_warned: bool,
     // sim_started: bool, // Removed
     // multi_agent_mode: bool, // Removed
     
@@ -64,52 +133,35 @@ struct AxiomApp {
     rt: Runtime,
 
     // Conductor State (Removed)
-    // active_plan: Option<crate::types::Plan>,
+    // active_plan: Option<types::Plan>,
 }
 
 
 impl AxiomApp {
-    fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        egui_extras::install_image_loaders(&cc.egui_ctx);
-
-        let mut fonts = egui::FontDefinitions::default();
-        
-        #[cfg(target_os = "windows")]
-        let font_path = "C:/Windows/Fonts/msyh.ttc";
-        
-        #[cfg(target_os = "linux")]
-        let font_path = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+    fn new(ctx: &egui::Context, settings: settings::Settings, project: project::Project) -> Self {
+    let (tx, rx) = channel();
+        let rt = Runtime::new().expect("Failed to create Tokio runtime");
         
-        #[cfg(target_os = "macos")]
-        let font_path = "/System/Library/Fonts/PingFang.ttc";
+        let clipboard = arboard::Clipboard::new().ok();
 
-        if let Ok(data) = std::fs::read(font_path) {
-            fonts.font_data.insert(
-                "MicrosoftYaHei".to_owned(),
-                egui::FontData::from_owned(data),
-            );
-            fonts.families.entry(egui::FontFamily::Proportional).or_default().insert(0, "MicrosoftYaHei".to_owned());
-            fonts.families.entry(egui::FontFamily::Monospace).or_default().insert(0, "MicrosoftYaHei".to_owned());
-            cc.egui_ctx.set_fonts(fonts);
+        // Discover local Ollama models so they can be picked like any other agent
+        let local_agents = get_local_agents(&llm::ollama::discover_models());
+        if !local_agents.is_empty() {
+            tracing::info!("Found {} local model(s)", local_agents.len());
         }
 
-    let (tx, rx) = channel();
-        let rt = Runtime::new().expect("Failed to create Tokio runtime");
-        
-        // Initialize dotenv
-        dotenv::dotenv().ok();
-        
-        // Remove hardcoded key fallback to prevent leakage
-        let api_key = std::env::var("GEMINI_API_KEY").unwrap_or_default();
+        let mut assigned_agents = vec!["General Assistant".to_string()];
+        assigned_agents.extend(local_agents.iter().map(|p| p.name.clone()));
 
-        let clipboard = arboard::Clipboard::new().ok();
+        let mut available_profiles = get_default_agents();
+        available_profiles.extend(local_agents);
 
         let mut channels = std::collections::HashMap::new();
         channels.insert("global".to_string(), ChannelState {
             id: "global".to_string(),
             name: "🌐 Global".to_string(),
             history: Vec::new(),
-            assigned_agents: vec!["General Assistant".to_string()],
+            assigned_agents: assigned_agents.clone(),
         });
         /*
         channels.insert("backend".to_string(), ChannelState {
@@ -138,21 +190,99 @@ impl AxiomApp {
         });
         */
 
-        Self {
-            api_key,
-            current_profile: AgentProfile::default(),
-            available_profiles: get_default_agents(),
+        // Restore the previous session for this project, if any
+        let project_root = project.root.clone();
+        let mut current_profile = AgentProfile::default();
+        let default_model = Some(settings.default_model.trim())
+            .filter(|m| !m.is_empty())
+            .unwrap_or_else(|| project.config.llm.default_model.trim());
+        if !default_model.is_empty() {
+            current_profile.model = default_model.to_string();
+        }
+        let mut active_channel_id = "global".to_string();
+        let mut removed_profiles = Vec::new();
+        if let Some(mut saved) = session::Session::load(&project_root) {
+            tracing::info!("Restored {} channel(s) from {}", saved.channels.len(), session::Session::path(&project_root).display());
+            saved.merge_profiles(available_profiles, &assigned_agents);
+            if let Some(profile) = saved.profiles.iter().find(|p| p.name == saved.current_profile) {
+                current_profile = profile.clone();
+            }
+            if saved.channels.contains_key(&saved.active_channel_id) {
+                active_channel_id = saved.active_channel_id;
+            }
+            // Left over from a run that didn't stop cleanly
+            if let Some(in_flight) = saved.in_flight.take() {
+                tracing::warn!("Recovering from an interrupted session in channel {}", in_flight.channel_id);
+                let note = in_flight.recovery_note();
+                let channel_id = if saved.channels.contains_key(&in_flight.channel_id) { &in_flight.channel_id } else { "global" };
+                if let Some(channel) = saved.channels.get_mut(channel_id) {
+                    channel.history.push(("System".to_string(), MessageContent::Text(note)));
+                }
+            }
+            available_profiles = saved.profiles;
+            removed_profiles = saved.removed_profiles;
+            channels = saved.channels;
+        }
+
+        let file_tree_state = ui::file_tree::FileTreeState::default();
+        let file_tree_root = file_tree_state.root_path.clone();
+        let mut app = Self {
+            current_profile,
+            available_profiles,
+            removed_profiles,
+            profile_editor: ui::profiles::ProfileEditorState::default(),
+            palette: ui::palette::PaletteState::default(),
+            todos: ui::todos::TodoPanelState::default(),
+            toasts: ui::toasts::Toasts::default(),
+            game_reachable: None,
+            game: None,
+            game_status: game::GameStatus::Idle,
+            last_game_ping: std::time::Instant::now(),
+            game_ping_pending: false,
+            game_latency: None,
+            connection: ui::connection::ConnectionState::default(),
+            file_preview: ui::file_preview::FilePreviewState::default(),
+            watcher: watcher::Watcher::spawn(file_tree_root, ctx.clone()),
             channels,
-            active_channel_id: "global".to_string(),
+            active_channel_id,
+            usage: llm::usage::UsageTracker::new(llm::usage::PriceTable::load(&project_root))
+                .with_today(spending::load()),
+            assets: ui::assets::AssetBrowserState::new(project.asset_dirs()),
+            metrics_panel: ui::metrics::MetricsPanelState::default(),
+            scene_history: ui::scene_history::SceneHistoryState::default(),
+            undo_history: ui::undo_history::UndoHistoryState::default(),
+            profiler: ui::profiler::ProfilerState::default(),
+            project,
+            open_project_requested: false,
             // sub_agents: std::collections::HashMap::new(),
-            file_tree_state: ui::file_tree::FileTreeState::default(),
+            file_tree_state,
             input_text: String::new(),
-            pending_image: None,
-            preview_texture: None,
+            attachments: Default::default(),
+            editing_message: None,
+            message_usage: HashMap::new(),
+            context_tokens: HashMap::new(),
+            personas: personas::PersonaRegistry::load(),
+            chat_detached: false,
             clipboard,
             is_loading: false,
             waiting_for_screenshot: false,
             client: None,
+            generation: None,
+            running_tools: Vec::new(),
+            session_dirty: false,
+            last_autosave: std::time::Instant::now(),
+            model_picker: ui::input::ModelPickerState::default(),
+            game_preview: ui::preview::GamePreviewState::default(),
+            console: ui::console::ConsoleState::default(),
+            settings,
+            settings_window: ui::settings::SettingsState::default(),
+            pending_reviews: Vec::new(),
+            #[cfg(feature = "voice")]
+            voice: voice::VoiceState::new(),
+            provider_unavailable_until: None,
+            budget_blocked: None,
+            budget_confirmed: false,
+            budget_warned: false,
             // sim_started: false,
             // multi_agent_mode: false,
             image_textures: std::collections::HashMap::new(),
@@ -160,88 +290,293 @@ impl AxiomApp {
             rx,
             rt,
             // active_plan: None,
+        };
+        app.refresh_models();
+        app
+    }
+
+    /// List the models the current profile's provider serves, in the background.
+    fn refresh_models(&mut self) {
+        let profile = &self.current_profile;
+        let provider = match create_provider(profile.provider, &profile.model, &profile.sampling) {
+            Ok(provider) => provider,
+            Err(e) => {
+                tracing::warn!("Failed to init provider: {}", e);
+                return;
+            }
+        };
+
+        self.model_picker.loading = true;
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let models = match provider.list_models().await {
+                Ok(models) => models,
+                Err(e) => {
+                    tracing::warn!("Failed to list {} models: {}", provider.kind().label(), e);
+                    Vec::new()
+                }
+            };
+            let _ = tx.send(AsyncMessage::Models { provider: provider.kind(), models });
+        });
+    }
+
+    /// Switch the current profile (and its saved copy) to another model.
+    fn select_model(&mut self, model: String) {
+        self.current_profile.model = model.clone();
+        if let Some(profile) = self.available_profiles.iter_mut().find(|p| p.name == self.current_profile.name) {
+            profile.model = model;
         }
+        self.client = None;
+        self.save_session();
+    }
+
+    /// Save edited settings and switch to them; the provider client is
+    /// rebuilt on the next request. The workspace root applies on restart.
+    fn apply_settings(&mut self, ctx: &egui::Context, settings: settings::Settings) {
+        if let Err(e) = settings.save() {
+            self.push_error(format!("{:#}", e));
+        }
+        settings.apply_env();
+        // axiom.toml fills in what the settings leave empty
+        self.project.apply_env();
+        settings.apply_appearance(ctx);
+        axiom_agent_core::metrics::set_enabled(settings.metrics_enabled);
+        // New limits warn and block afresh
+        if settings.budget() != self.settings.budget() {
+            self.budget_confirmed = false;
+            self.budget_warned = false;
+        }
+        self.settings = settings;
+        self.client = None;
+        self.provider_unavailable_until = None;
+        self.refresh_models();
+    }
+
+    /// Make `profile` the active one; its client is created on the next request.
+    fn use_profile(&mut self, profile: AgentProfile) {
+        self.current_profile = profile;
+        self.client = None;
+        self.refresh_models();
+    }
+
+    /// Store a profile from the editor. `original` is the name it had before
+    /// (renames carry over to channel assignments), `None` for a new one.
+    fn save_profile(&mut self, original: Option<String>, profile: AgentProfile) {
+        let was_current = original.as_deref() == Some(self.current_profile.name.as_str());
+        let existing = original
+            .as_ref()
+            .and_then(|name| self.available_profiles.iter().position(|p| &p.name == name));
+        match existing {
+            Some(idx) => self.available_profiles[idx] = profile.clone(),
+            None => {
+                self.available_profiles.push(profile.clone());
+                if let Some(global) = self.channels.get_mut("global") {
+                    global.assigned_agents.push(profile.name.clone());
+                }
+            }
+        }
+
+        if let Some(original) = original.filter(|name| *name != profile.name) {
+            for channel in self.channels.values_mut() {
+                for agent in channel.assigned_agents.iter_mut().filter(|a| **a == original) {
+                    *agent = profile.name.clone();
+                }
+            }
+            self.removed_profiles.push(original);
+        }
+        self.removed_profiles.retain(|name| *name != profile.name);
+
+        if was_current {
+            self.use_profile(profile);
+        }
+        self.save_session();
+    }
+
+    fn delete_profile(&mut self, name: String) {
+        self.available_profiles.retain(|p| p.name != name);
+        for channel in self.channels.values_mut() {
+            channel.assigned_agents.retain(|a| *a != name);
+        }
+        if self.current_profile.name == name {
+            let next = self.available_profiles.first().cloned().unwrap_or_default();
+            self.use_profile(next);
+        }
+        self.removed_profiles.push(name);
+        self.save_session();
+    }
+
+    fn save_session(&mut self) {
+        let in_flight = self.generation.is_some().then(|| session::InFlight {
+            channel_id: self.active_channel_id.clone(),
+            profile: self.current_profile.name.clone(),
+            tools: self.running_tools.clone(),
+            pending_edits: self.pending_reviews.iter().map(|r| r.edit.path.clone()).collect(),
+        });
+        let session = session::Session::new(
+            &self.active_channel_id,
+            &self.current_profile,
+            &self.available_profiles,
+            &self.removed_profiles,
+            &self.channels,
+            in_flight,
+        );
+        if let Err(e) = session.save(&self.project.root) {
+            tracing::warn!("Failed to save: {}", e);
+        }
+        self.session_dirty = false;
+        self.last_autosave = std::time::Instant::now();
     }
 
     fn paste_from_clipboard(&mut self, ctx: &egui::Context) -> bool {
         if let Some(clipboard) = &mut self.clipboard {
-            match clipboard.get_image() {
-                Ok(image_data) => {
-                    let width = image_data.width;
-                    let height = image_data.height;
-                    let bytes = image_data.bytes.into_owned();
-                    
-                    if let Some(img_buffer) = image::RgbaImage::from_raw(width as u32, height as u32, bytes.clone()) {
-                        let mut png_bytes: Vec<u8> = Vec::new();
-                        if let Ok(_) = img_buffer.write_to(&mut Cursor::new(&mut png_bytes), image::ImageFormat::Png) {
-                            let base64_string = BASE64_STANDARD.encode(&png_bytes);
-                            self.pending_image = Some(base64_string);
-
-                            let color_image = egui::ColorImage::from_rgba_unmultiplied(
-                                [width, height],
-                                &bytes,
-                            );
-                            
-                            self.preview_texture = Some(ctx.load_texture(
-                                "pending_image",
-                                color_image,
-                                egui::TextureOptions::default()
-                            ));
-                            return true;
-                        }
+            if let Ok(image_data) = clipboard.get_image() {
+                let width = image_data.width as u32;
+                let height = image_data.height as u32;
+                match llm::image::from_rgba(width, height, image_data.bytes.into_owned()) {
+                    Ok(prepared) => {
+                        self.attachments.add_image(ctx, prepared);
+                        return true;
                     }
-                },
-                Err(_) => {}
+                    Err(e) => tracing::warn!("Failed to read clipboard image: {}", e),
+                }
             }
         }
         false
     }
 
+    /// Start recording, or stop and transcribe into the input box.
+    #[cfg(feature = "voice")]
+    fn toggle_recording(&mut self) {
+        let Some(recorder) = self.voice.recorder.take() else {
+            match voice::Recorder::start() {
+                Ok(recorder) => self.voice.recorder = Some(recorder),
+                Err(e) => self.push_error(format!("{:#}", e)),
+            }
+            return;
+        };
+
+        let wav = match recorder.finish() {
+            Ok(wav) => wav,
+            Err(e) => return self.push_error(format!("{:#}", e)),
+        };
+        self.voice.transcribing = true;
+        let config = self.voice.config.clone();
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let result = voice::stt::transcribe(&config, wav).await.map_err(|e| format!("{:#}", e));
+            let _ = tx.send(AsyncMessage::Transcript(result));
+        });
+    }
+
+    /// Read the latest reply aloud if enabled.
+    #[cfg(feature = "voice")]
+    fn speak_last_reply(&self) {
+        if !self.voice.speak_replies {
+            return;
+        }
+        let Some(text) = self.channels.get(&self.active_channel_id).and_then(|channel| {
+            channel.history.iter().rev().find_map(|(role, content)| match content {
+                MessageContent::Text(text) if *role == self.current_profile.name => Some(text.clone()),
+                _ => None,
+            })
+        }) else {
+            return;
+        };
+
+        let config = self.voice.config.clone();
+        let speaker = self.voice.speaker.clone();
+        self.rt.spawn(async move {
+            match voice::tts::synthesize(&config, &text).await {
+                Ok(audio) => speaker.play(audio),
+                Err(e) => tracing::warn!("{:#}", e),
+            }
+        });
+    }
+
+    fn push_error(&mut self, message: String) {
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("Error".to_string(), MessageContent::Text(message)));
+        }
+    }
+
+    /// Attach every file dropped onto the window.
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| !i.raw.hovered_files.is_empty()) {
+            let screen = ctx.screen_rect();
+            let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop_overlay")));
+            painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(160));
+            painter.text(
+                screen.center(),
+                egui::Align2::CENTER_CENTER,
+                "Drop images, text files or .glb models to attach",
+                egui::FontId::proportional(22.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        let dropped = ctx.input(|i| i.raw.dropped_files.clone());
+        for path in dropped.iter().filter_map(|f| f.path.as_ref()) {
+            if let Err(e) = self.attachments.add_path(ctx, path) {
+                self.push_error(format!("{:#}", e));
+            }
+        }
+    }
+
+    /// Upload a dropped model to the game at the origin.
+    fn upload_model(&mut self, idx: usize) {
+        if idx >= self.attachments.models.len() {
+            return;
+        }
+        let path = self.attachments.models.remove(idx);
+        let args = serde_json::json!({ "local_path": path.display().to_string(), "translation": [0.0, 0.0, 0.0] });
+        self.run_tool_in_background(Box::new(tools::bevy::BevyUploadAssetTool), args, "Upload model");
+    }
+
     fn send_message(&mut self, force: bool) {
         let text = self.input_text.trim().to_string();
-        println!("[DEBUG] send_message called. force={}, text_len={}, pending_image={}", force, text.len(), self.pending_image.is_some());
+        let has_attachments = self.attachments.has_content();
+        tracing::debug!("send_message called. force={}, text_len={}, attachments={}", force, text.len(), has_attachments);
         
-        if !force && text.is_empty() && self.pending_image.is_none() { 
-            println!("[DEBUG] send_message aborted: empty input and not forced");
+        if !force && text.is_empty() && !has_attachments { 
+            tracing::debug!("send_message aborted: empty input and not forced");
             return; 
         }
 
-        let content = if let Some(img_base64) = &self.pending_image {
-            let mut parts = Vec::new();
-            if !text.is_empty() {
-                parts.push(ContentPart {
-                    r#type: "text".to_string(),
-                    text: Some(text.clone()),
-                    image_url: None,
-                });
-            }
-            parts.push(ContentPart {
-                r#type: "image_url".to_string(),
-                text: None,
-                image_url: Some(ImageUrl {
-                    url: format!("data:image/png;base64,{}", img_base64),
-                }),
-            });
-            MessageContent::Parts(parts)
-        } else {
-            MessageContent::Text(text.clone())
-        };
+        let content = self.attachments.take_content(&text, &self.project.root);
+        let editing = self.editing_message.take();
 
-        if !text.is_empty() || self.pending_image.is_some() {
+        if !text.is_empty() || has_attachments {
             if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+                if let Some((_, idx)) = editing.filter(|(id, _)| *id == channel.id) {
+                    channel.history.truncate(idx);
+                    forget_usage_from(&mut self.message_usage, &channel.id, idx);
+                }
                 channel.history.push(("Cats2333".to_string(), content.clone()));
             }
+            self.save_session();
         }
         
         self.input_text.clear();
-        self.pending_image = None;
-        self.preview_texture = None;
+        self.generate();
+    }
+
+    /// Ask the current profile to answer the active channel's history.
+    fn generate(&mut self) {
+        if !self.budget_confirmed {
+            if let llm::usage::BudgetStatus::Exceeded(message) = self.usage.budget_status(&self.settings.budget()) {
+                self.budget_blocked = Some((message, true));
+                return;
+            }
+        }
         self.is_loading = true;
 
         // Initialize client if not ready
         if self.client.is_none() {
-             match GeminiClient::new(self.api_key.clone(), self.current_profile.model.clone()) {
-                Ok(c) => self.client = Some(c),
+             match create_provider(self.current_profile.provider, &self.current_profile.model, &self.current_profile.sampling) {
+                Ok(c) => {
+                    tracing::info!("Using {} provider, model {}", c.kind().label(), c.model());
+                    self.client = Some(c);
+                }
                 Err(e) => {
                     if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
                         channel.history.push(("System".to_string(), MessageContent::Text(format!("Failed to init client: {}", e))));
@@ -255,8 +590,8 @@ impl AxiomApp {
         let client = self.client.as_ref().unwrap().clone();
         let tx = self.tx.clone();
         
-        let mut system_prompt = if self.active_channel_id == "planning" {
-            crate::prompts::conductor::CONDUCTOR_PROMPT.to_string()
+        let system_prompt = if self.active_channel_id == "planning" {
+            prompts::conductor::CONDUCTOR_PROMPT.to_string()
         } else {
             prompts::get_system_prompt(
                 &self.current_profile.research_mode,
@@ -270,216 +605,901 @@ impl AxiomApp {
         if self.multi_agent_mode {
             system_prompt.push_str("\n\nCRITICAL: Multi-Agent Mode is ACTIVE. You are an Orchestrator. Do NOT execute complex implementation tasks yourself. You MUST use the 'task' tool to delegate backend, frontend, database, or devops tasks to specialized sub-agents. Your role is to plan and delegate.");
         }
-        */
-        
-        // --- Working Directory Injection ---
-        let cwd = self.file_tree_state.root_path.display().to_string();
-        system_prompt.push_str(&format!("\n\nCurrent Working Directory: {}\nIMPORTANT: All file operations (read/write/run) should be relative to this directory unless absolute path is specified.", cwd));
-        system_prompt.push_str("\n\n**BEVY ASSET RULE**: When generating assets (images, models, etc.) for Bevy, you MUST write them to the `assets/` subdirectory within the working directory. When spawning these assets via `bevy_spawn_scene` or `bevy_spawn`, use the path RELATIVE to the `assets/` folder (e.g., if you wrote 'assets/models/cube.glb', the spawn path is 'models/cube.glb').");
-        system_prompt.push_str("\n\n**CRITICAL: BINARY ASSET HANDLING**\nIf the user asks to spawn or use a specific local file (like a .glb, .png, etc.) that is provided in the Context (marked as [BINARY ASSET AVAILABLE]), you **MUST NOT** use `bevy_spawn_primitive` or `bevy_spawn_scene`. \n\nINSTEAD, you **MUST** use the `bevy_upload_asset` tool.\n- `local_path`: Use the absolute path provided in the context (usually in `apps/axiom/resources/...`).\n- `translation`: Use the user's requested position.\n\nExample: User says 'spawn this glb', and context shows `D:/.../dragon.glb`. Call `bevy_upload_asset(local_path='D:/.../dragon.glb', translation=[0,0,0])`. Do NOT try to read the file content or simulate it.");
-        
-        // Inject Road Engineering Rules
-        system_prompt.push_str("\n\n");
-        system_prompt.push_str(include_str!("prompts/road_engineer.md"));
-        
-        let mut messages: Vec<Message> = Vec::new();
-        
-        if !system_prompt.is_empty() {
-            println!("[DEBUG] Adding System Prompt (len={})", system_prompt.len());
-            messages.push(Message {
-                role: "system".to_string(),
-                content: Some(MessageContent::Text(system_prompt)),
-                tool_calls: None,
-                tool_call_id: None,
-            });
+        */
+        
+        let system_prompt = prompts::agent_prompt(&system_prompt, &self.file_tree_state.root_path);
+        
+        let mut messages: Vec<Message> = Vec::new();
+        
+        if !system_prompt.is_empty() {
+            tracing::debug!("Adding System Prompt (len={})", system_prompt.len());
+            messages.push(Message {
+                role: "system".to_string(),
+                content: Some(MessageContent::Text(system_prompt)),
+                tool_calls: None,
+                tool_call_id: None,
+            });
+        }
+
+        let current_history = if let Some(channel) = self.channels.get(&self.active_channel_id) {
+            &channel.history
+        } else {
+            // Should not happen, but safe fallback
+            return;
+        };
+
+        tracing::debug!("Building message history. Count={}", current_history.len());
+        for (role, content) in current_history {
+            let api_role = match role.as_str() {
+                "Cats2333" => "user",
+                "System" | "Error" => "system", 
+                _ => "assistant", 
+            };
+            
+            if role == "System" || role == "Error" { continue; }
+
+            // Drop image parts for text-only providers
+            let content = match content {
+                MessageContent::Parts(parts) if !client.supports_vision() => MessageContent::Text(
+                    parts.iter().filter_map(|p| p.text.clone()).collect::<Vec<_>>().join("\n"),
+                ),
+                other => other.clone(),
+            };
+
+            messages.push(Message {
+                role: api_role.to_string(),
+                content: Some(content),
+                tool_calls: None,
+                tool_call_id: None,
+            });
+        }
+
+        let history = agent::history::HistoryManager::for_model(client.model());
+        let profile_name = self.current_profile.name.clone();
+
+        // The Conductor answers with a schema-constrained plan, not tool calls
+        if self.active_channel_id == "planning" {
+            let mut messages = history.fit(&messages, 0);
+            let task = self.rt.handle().spawn(async move {
+                prompts::template::resolve_game_status(&mut messages).await;
+                match agent::conductor::request_plan(client.as_ref(), messages).await {
+                    Ok((plan, usage)) => {
+                        let _ = tx.send(AsyncMessage::Usage { profile: profile_name, model: client.model().to_string(), usage });
+                        let pretty = serde_json::to_string_pretty(&plan).unwrap_or_default();
+                        let _ = tx.send(AsyncMessage::Response(MessageContent::Text(format!("```json\n{}\n```", pretty))));
+                    }
+                    Err(e) => {
+                        let _ = tx.send(AsyncMessage::from_error(&e.context("Planning failed")));
+                    }
+                }
+            });
+            self.generation = Some(task.abort_handle());
+            self.running_tools.clear();
+            return;
+        }
+
+        let tools = tools::get_tools_for_profile(&self.current_profile, tx.clone());
+        let runtime = axiom_agent_core::AgentRuntime::new(client.clone(), tools)
+            .with_reviewer(std::sync::Arc::new(tools::review::ChannelReviewer::new(tx.clone())));
+
+        let rt_handle = self.rt.handle().clone();
+        let task = rt_handle.spawn(async move {
+            prompts::template::resolve_game_status(&mut messages).await;
+            let model = client.model().to_string();
+            let result = runtime.run(&mut messages, |event| {
+                let message = match event {
+                    AgentEvent::Text(text) => AsyncMessage::StreamText(text),
+                    AgentEvent::Usage(usage) => AsyncMessage::Usage {
+                        profile: profile_name.clone(),
+                        model: model.clone(),
+                        usage,
+                    },
+                    AgentEvent::ContextTrimmed { sent, total } => {
+                        AsyncMessage::Log(format!("Context trimmed: sending {} of {} messages", sent, total))
+                    }
+                    AgentEvent::ToolCall { name, arguments } => {
+                        AsyncMessage::Log(format!("{}{} args: {}", export::TOOL_CALL_PREFIX, name, arguments))
+                    }
+                    AgentEvent::ToolResult { name, output } => {
+                        AsyncMessage::Log(format!("{}{}\n{}", export::TOOL_RESULT_PREFIX, name, output))
+                    }
+                    AgentEvent::StreamError(e) => AsyncMessage::Error(e),
+                };
+                let _ = tx.send(message);
+            }).await;
+
+            match result {
+                Ok(()) => {
+                    let _ = tx.send(AsyncMessage::Done);
+                }
+                Err(e) => {
+                    let _ = tx.send(AsyncMessage::from_error(&e));
+                }
+            }
+        });
+        self.generation = Some(task.abort_handle());
+        self.running_tools.clear();
+    }
+
+    /// Ask the game for the latest screenshot if the preview panel is due one.
+    /// Toast when the game goes away or comes back.
+    fn set_game_reachable(&mut self, reachable: bool) {
+        match (self.game_reachable, reachable) {
+            (Some(false), true) => self.toasts.push(ui::toasts::ToastKind::Success, "Connected to the game"),
+            (Some(true), false) => self.toasts.push(ui::toasts::ToastKind::Warning, "Lost connection to the game"),
+            (None, false) => self.toasts.push(ui::toasts::ToastKind::Warning, "The game is not reachable over BRP"),
+            _ => {}
+        }
+        self.game_reachable = Some(reachable);
+    }
+
+    /// Build and start the game on a worker thread, stopping the running one
+    /// first.
+    fn run_game(&mut self) {
+        if self.game_status == game::GameStatus::Building {
+            return;
+        }
+        self.stop_game();
+        self.game_status = game::GameStatus::Building;
+        let root = self.project.root.clone();
+        let settings = self.project.config.game.clone();
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            let result = game::launch(&root, &settings).map_err(|e| format!("{:#}", e));
+            let _ = tx.send(AsyncMessage::GameLaunched(result));
+        });
+    }
+
+    fn stop_game(&mut self) {
+        if let Some(game) = self.game.take() {
+            game.stop(&self.project.root);
+            self.game_status = game::GameStatus::Stopped("Stopped from the editor".to_string());
+        }
+    }
+
+    /// Notice the game started from the editor exiting.
+    fn watch_game(&mut self, ctx: &egui::Context) {
+        let Some(game) = &mut self.game else {
+            return;
+        };
+        if let Some(status) = game.exited() {
+            self.game = None;
+            game::forget(&self.project.root);
+            let message = format!("The game exited ({})", status);
+            self.toasts.push(ui::toasts::ToastKind::Warning, &format!("{}; restart it from the top bar", message));
+            self.game_status = game::GameStatus::Stopped(message);
+            return;
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs(1));
+    }
+
+    /// Ping the BRP endpoint while there is a game to watch, so a hang or a
+    /// dropped connection shows up, and the latency with it.
+    fn ping_game(&mut self, ctx: &egui::Context) {
+        let watching = self.game.is_some() || self.connection.open || self.game_reachable == Some(true);
+        if !watching {
+            return;
+        }
+        if !self.game_ping_pending && self.last_game_ping.elapsed() >= GAME_PING_INTERVAL {
+            self.game_ping_pending = true;
+            self.last_game_ping = std::time::Instant::now();
+            let tx = self.tx.clone();
+            let ctx = ctx.clone();
+            self.rt.spawn(async move {
+                let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+                let latency = bevy_bridge_core::ops::ping::ping(&client)
+                    .await
+                    .ok()
+                    .map(|ping| std::time::Duration::from_secs_f64(ping.latency_ms / 1000.0));
+                let _ = tx.send(AsyncMessage::GamePing(latency));
+                ctx.request_repaint();
+            });
+        }
+        ctx.request_repaint_after(GAME_PING_INTERVAL);
+    }
+
+    /// Point BRP at another game and ping it right away.
+    fn connect_to_game(&mut self, ctx: &egui::Context, endpoint: String, token: String) {
+        let mut settings = self.settings.clone();
+        settings.brp_endpoint = endpoint.clone();
+        settings.brp_token = token;
+        self.apply_settings(ctx, settings);
+        self.game_reachable = None;
+        self.game_latency = None;
+        self.last_game_ping = std::time::Instant::now().checked_sub(GAME_PING_INTERVAL).unwrap_or_else(std::time::Instant::now);
+        self.toasts.push(ui::toasts::ToastKind::Info, &format!("Connecting to {}", endpoint));
+    }
+
+    fn scan_for_games(&mut self) {
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let result = bevy_bridge_core::discovery::discover(std::time::Duration::from_secs(3))
+                .await
+                .map_err(|e| format!("Failed to listen for games: {}", e));
+            let _ = tx.send(AsyncMessage::GamesDiscovered(result));
+        });
+    }
+
+    fn refresh_remote_assets(&mut self) {
+        self.assets.loading = true;
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+            let result = bevy_bridge_core::ops::assets::list_assets(&client)
+                .await
+                .map_err(|e| format!("Game not reachable: {}", e));
+            let _ = tx.send(AsyncMessage::RemoteAssets(result));
+        });
+    }
+
+    /// Spawn at the origin: project models are uploaded first, cached ones
+    /// are loaded by the game directly.
+    fn spawn_asset(&mut self, asset: ui::assets::AssetRef) {
+        match asset {
+            ui::assets::AssetRef::Project(path) => {
+                let args = serde_json::json!({ "local_path": path.display().to_string(), "translation": [0.0, 0.0, 0.0] });
+                self.run_tool_in_background(Box::new(tools::bevy::BevyUploadAssetTool), args, "Upload model");
+            }
+            ui::assets::AssetRef::Remote(path) => {
+                let tx = self.tx.clone();
+                self.rt.spawn(async move {
+                    let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+                    let result = bevy_bridge_core::ops::assets::spawn_cached(&client, &path, [0.0, 0.0, 0.0])
+                        .await
+                        .map(|spawned| format!("Spawned {} as entity {}", path, spawned.entity_id))
+                        .map_err(|e| format!("Failed to spawn {}: {}", path, e));
+                    let _ = tx.send(AsyncMessage::ActionFinished(result));
+                });
+            }
+        }
+    }
+
+    fn poll_game_preview(&mut self, ctx: &egui::Context) {
+        if self.game_preview.take_due_request() {
+            let tx = self.tx.clone();
+            let ctx = ctx.clone();
+            self.rt.spawn(async move {
+                let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+                let result = bevy_bridge_core::ops::screenshot::screenshot(&client)
+                    .await
+                    .map_err(|e| format!("Game not reachable: {}", e));
+                let _ = tx.send(AsyncMessage::GameScreenshot(result));
+                ctx.request_repaint();
+            });
+        }
+        if let Some(delay) = self.game_preview.repaint_in() {
+            ctx.request_repaint_after(delay);
+        }
+    }
+
+    /// "Apply to file…" on a chat code block: pick the target (defaulting to
+    /// the fence's path hint) and write it through the `write_file` tool.
+    fn apply_code_block(&mut self, code: String, path: Option<String>) {
+        let root = &self.file_tree_state.root_path;
+        let mut dialog = rfd::FileDialog::new().set_title("Apply code block to file");
+        match path.as_deref().map(|p| root.join(p)) {
+            Some(hint) => {
+                if let Some(dir) = hint.parent() {
+                    dialog = dialog.set_directory(dir);
+                }
+                if let Some(name) = hint.file_name() {
+                    dialog = dialog.set_file_name(name.to_string_lossy());
+                }
+            }
+            None => dialog = dialog.set_directory(root),
+        }
+        let Some(target) = dialog.save_file() else {
+            return;
+        };
+
+        let args = serde_json::json!({
+            "path": target.to_string_lossy(),
+            "content": code,
+        });
+        let message = match tools::execute_checked(&tools::WriteFileTool, args) {
+            Ok(result) => result,
+            Err(e) => format!("Failed to apply code block: {}", e),
+        };
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("System".to_string(), MessageContent::Text(message)));
+        }
+        self.save_session();
+    }
+
+    fn resolve_review(&mut self, idx: usize, choice: ui::review::ReviewChoice) {
+        if idx >= self.pending_reviews.len() {
+            return;
+        }
+        let review = self.pending_reviews.remove(idx);
+        let approved = !matches!(choice, ui::review::ReviewChoice::Reject);
+        let note = if approved {
+            format!("✅ Approved {} on {}", review.tool, review.edit.path)
+        } else {
+            format!("❌ Rejected {} on {}", review.tool, review.edit.path)
+        };
+
+        if let ui::review::ReviewChoice::ApproveAll = choice {
+            tools::review::set_auto_approve(true);
+            // Release everything else that is already waiting
+            for other in self.pending_reviews.drain(..) {
+                other.decide(true);
+            }
+        }
+        review.decide(approved);
+
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("System".to_string(), MessageContent::Text(note)));
+        }
+    }
+
+    /// Save the active channel to a Markdown or JSON file picked by the user.
+    fn export_chat(&mut self) {
+        let Some(channel) = self.channels.get(&self.active_channel_id) else {
+            return;
+        };
+        let file_name = format!("{}-{}", channel.id, chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export chat")
+            .set_directory(&self.project.root)
+            .set_file_name(format!("{}.md", file_name))
+            .add_filter("Markdown", &["md"])
+            .add_filter("JSON", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+
+        let message = match export::export_channel(channel, &path) {
+            Ok(()) => format!("Exported chat to {}", path.display()),
+            Err(e) => format!("Export failed: {:#}", e),
+        };
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("System".to_string(), MessageContent::Text(message)));
+        }
+    }
+
+    /// Save the metrics snapshot as JSON.
+    fn export_metrics(&mut self) {
+        let file_name = format!("axiom-metrics-{}.json", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export metrics")
+            .set_directory(&self.project.root)
+            .set_file_name(file_name)
+            .add_filter("JSON", &["json"])
+            .save_file()
+        else {
+            return;
+        };
+        match metrics::export(&path) {
+            Ok(()) => self.toasts.push(ui::toasts::ToastKind::Success, &format!("Exported metrics to {}", path.display())),
+            Err(e) => self.toasts.push(ui::toasts::ToastKind::Error, &format!("{:#}", e)),
+        }
+    }
+
+    /// Export what the editor spawned in the game and save it as `name`.
+    fn save_scene_snapshot(&mut self, name: String) {
+        self.scene_history.busy = true;
+        let root = self.project.root.clone();
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+            let result = match bevy_bridge_core::ops::scene::export(&client).await {
+                Ok(entities) => {
+                    let snapshot = scene_history::SceneSnapshot::new(&name, entities);
+                    scene_history::save(&root, &snapshot)
+                        .map(|()| format!("Saved snapshot {} ({} entities)", name, snapshot.entities.len()))
+                        .map_err(|e| format!("{:#}", e))
+                }
+                Err(e) => Err(format!("Failed to export the scene: {}", e)),
+            };
+            let _ = tx.send(AsyncMessage::SceneHistory(result));
+        });
+    }
+
+    /// Replace what the editor spawned in the game with `snapshot`.
+    fn restore_scene_snapshot(&mut self, snapshot: scene_history::SceneSnapshot) {
+        self.scene_history.busy = true;
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+            let result = bevy_bridge_core::ops::scene::restore(&client, &snapshot.entities)
+                .await
+                .map(|count| format!("Restored snapshot {} ({} entities)", snapshot.name, count))
+                .map_err(|e| format!("Failed to restore {}: {}", snapshot.name, e));
+            let _ = tx.send(AsyncMessage::SceneHistory(result));
+        });
+    }
+
+    /// Fetch the game's undo journal for the history panel.
+    fn refresh_undo_history(&mut self) {
+        self.undo_history.busy = true;
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+            let result = bevy_bridge_core::ops::history::history(&client).await.map_err(|e| e.to_string());
+            let _ = tx.send(AsyncMessage::UndoHistory(result));
+        });
+    }
+
+    /// Undo (`forward` false) or redo the latest scene change in the game.
+    fn step_undo_history(&mut self, forward: bool) {
+        self.undo_history.busy = true;
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+            let (verb, result) = if forward {
+                ("Redid", bevy_bridge_core::ops::history::redo(&client).await)
+            } else {
+                ("Undid", bevy_bridge_core::ops::history::undo(&client).await)
+            };
+            match result {
+                Ok(step) => {
+                    let _ = tx.send(AsyncMessage::ActionFinished(Ok(format!("{} {}", verb, step.label))));
+                    let _ = tx.send(AsyncMessage::UndoHistory(Ok(step.history)));
+                }
+                Err(e) => {
+                    let _ = tx.send(AsyncMessage::ActionFinished(Err(e.to_string())));
+                    let history = bevy_bridge_core::ops::history::history(&client).await.map_err(|e| e.to_string());
+                    let _ = tx.send(AsyncMessage::UndoHistory(history));
+                }
+            }
+        });
+    }
+
+    /// Send a profiler request to the game for the profiler panel.
+    fn profile_game(&mut self, action: ui::profiler::ProfilerAction) {
+        self.profiler.busy = true;
+        let tx = self.tx.clone();
+        self.rt.spawn(async move {
+            use bevy_bridge_core::ops::profile;
+            let client = bevy_bridge_core::BrpClient::new(bevy_bridge_core::BrpConfig::from_env());
+            let result = match action {
+                ui::profiler::ProfilerAction::Start => profile::start(&client, None).await,
+                ui::profiler::ProfilerAction::Stop => profile::stop(&client).await,
+                _ => profile::read(&client).await,
+            };
+            let _ = tx.send(AsyncMessage::Profile(result.map_err(|e| e.to_string())));
+        });
+    }
+
+    /// Put a user message back into the input for editing.
+    fn edit_message(&mut self, idx: usize) {
+        let Some(channel) = self.channels.get(&self.active_channel_id) else {
+            return;
+        };
+        if let Some((_, MessageContent::Text(text))) = channel.history.get(idx) {
+            self.input_text = text.clone();
+            self.editing_message = Some((self.active_channel_id.clone(), idx));
+        }
+    }
+
+    /// Drop the turn that ended at `idx` and answer the last user message
+    /// before it again.
+    fn regenerate(&mut self, idx: usize) {
+        let Some(channel) = self.channels.get_mut(&self.active_channel_id) else {
+            return;
+        };
+        let Some(user_idx) = channel.history[..idx.min(channel.history.len())]
+            .iter()
+            .rposition(|(role, _)| role == "Cats2333")
+        else {
+            return;
+        };
+        channel.history.truncate(user_idx + 1);
+        forget_usage_from(&mut self.message_usage, &channel.id, user_idx + 1);
+        self.editing_message = None;
+        self.save_session();
+        self.generate();
+    }
+
+    /// Add an empty channel for every profile and switch to it.
+    fn new_channel(&mut self) {
+        let n = (1..).find(|n| !self.channels.contains_key(&format!("chat-{}", n))).unwrap_or(1);
+        let id = format!("chat-{}", n);
+        self.channels.insert(id.clone(), ChannelState {
+            id: id.clone(),
+            name: format!("💬 Chat {}", n),
+            history: Vec::new(),
+            assigned_agents: self.available_profiles.iter().map(|p| p.name.clone()).collect(),
+        });
+        self.active_channel_id = id;
+        self.save_session();
+    }
+
+    /// Run a tool on a worker thread and report its result to the chat. The
+    /// bevy tools drive their own runtime, so they can't run on ours.
+    fn run_tool_in_background(&self, tool: Box<dyn tools::Tool>, args: serde_json::Value, label: &'static str) {
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            let result = tools::execute_checked(tool.as_ref(), args)
+                .map(|output| format!("{}: {}", label, output))
+                .map_err(|e| format!("{} failed: {:#}", label, e));
+            let _ = tx.send(AsyncMessage::ActionFinished(result));
+        });
+    }
+
+    /// Run `cargo test` in the project and post the outcome to the chat.
+    fn run_tests(&mut self) {
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("System".to_string(), MessageContent::Text("Running cargo test…".to_string())));
+        }
+        let root = self.project.root.clone();
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            let result = match Command::new("cargo").arg("test").current_dir(&root).output() {
+                Ok(output) => {
+                    let log = format!("{}{}", String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr));
+                    // The summary lines are at the end
+                    let tail: Vec<&str> = log.lines().rev().take(40).collect();
+                    let tail = tail.into_iter().rev().collect::<Vec<_>>().join("\n");
+                    if output.status.success() {
+                        Ok(format!("Tests passed\n{}", tail))
+                    } else {
+                        Err(format!("Tests failed ({})\n{}", output.status, tail))
+                    }
+                }
+                Err(e) => Err(format!("Failed to run cargo test: {}", e)),
+            };
+            let _ = tx.send(AsyncMessage::ActionFinished(result));
+        });
+    }
+
+    /// Pick a YAML or JSON automation script and run it on a worker thread.
+    /// Edits go through the review cards like the agent's.
+    fn run_automation(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Run automation script")
+            .set_directory(&self.project.root)
+            .add_filter("Automation script", &["yaml", "yml", "json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let script = match axiom_agent_core::automation::Script::load(&path) {
+            Ok(script) => script,
+            Err(e) => {
+                self.toasts.push(ui::toasts::ToastKind::Error, &format!("{:#}", e));
+                return;
+            }
+        };
+        let name = script.name.clone().unwrap_or_else(|| path.file_name().unwrap_or_default().to_string_lossy().to_string());
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("System".to_string(), MessageContent::Text(format!("Running automation {}…", name))));
+        }
+
+        let tx = self.tx.clone();
+        std::thread::spawn(move || {
+            let tools = tools::get_every_tool(tx.clone());
+            let reviewer = tools::review::ChannelReviewer::new(tx.clone());
+            let runner = axiom_agent_core::automation::AutomationRunner::new(&tools).with_reviewer(&reviewer);
+            let mut log = Vec::new();
+            let result = runner.run(&script, serde_json::Map::new(), |event| {
+                use axiom_agent_core::automation::AutomationEvent;
+                match event {
+                    AutomationEvent::ToolResult { step, name, output: Ok(_) } => log.push(format!("✔ {} {}", step, name)),
+                    AutomationEvent::ToolResult { step, name, output: Err(e) } => log.push(format!("✘ {} {}: {}", step, name, e)),
+                    AutomationEvent::Skipped { step } => log.push(format!("– {} skipped", step)),
+                    AutomationEvent::ToolCall { .. } => {}
+                }
+            });
+            let result = match result {
+                Ok(report) => Ok(format!("Automation {} finished: {}\n{}", name, report.summary(), log.join("\n"))),
+                Err(e) => Err(format!("Automation {} failed: {:#}\n{}", name, e, log.join("\n"))),
+            };
+            let _ = tx.send(AsyncMessage::ActionFinished(result));
+        });
+    }
+
+    fn run_command(&mut self, command: commands::Command) {
+        match command {
+            commands::Command::OpenPalette => self.palette.toggle(),
+            commands::Command::OpenProject => self.open_project_requested = true,
+            commands::Command::NewChannel => self.new_channel(),
+            commands::Command::ClearChat => {
+                if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+                    channel.history.clear();
+                }
+                self.message_usage.remove(&self.active_channel_id);
+                self.context_tokens.remove(&self.active_channel_id);
+                self.save_session();
+            }
+            commands::Command::ClearScene => {
+                self.run_tool_in_background(Box::new(tools::bevy::BevyClearSceneTool), serde_json::Value::Null, "Clear scene");
+            }
+            commands::Command::Undo => self.step_undo_history(false),
+            commands::Command::Redo => self.step_undo_history(true),
+            commands::Command::RunTests => self.run_tests(),
+            commands::Command::RunGame | commands::Command::RestartGame => self.run_game(),
+            commands::Command::StopGame => self.stop_game(),
+            commands::Command::ConnectToGame => {
+                let config = bevy_bridge_core::BrpConfig::from_env();
+                self.connection.show(&config.endpoint, config.token.as_deref().unwrap_or_default());
+            }
+            commands::Command::RunAutomation => self.run_automation(),
+            commands::Command::StopGeneration => {
+                if self.is_loading || self.generation.is_some() {
+                    self.stop_generation();
+                }
+                #[cfg(feature = "voice")]
+                self.voice.speaker.stop();
+            }
+            commands::Command::ExportChat => self.export_chat(),
+            commands::Command::TogglePreview => {
+                self.game_preview.open = !self.game_preview.open;
+                if self.game_preview.open {
+                    self.game_preview.request_now();
+                }
+            }
+            commands::Command::ToggleConsole => self.console.open = !self.console.open,
+            commands::Command::ToggleTodos => self.todos.open = !self.todos.open,
+            commands::Command::ToggleAssets => {
+                self.assets.open = !self.assets.open;
+                if self.assets.open {
+                    self.refresh_remote_assets();
+                }
+            }
+            commands::Command::ToggleMetrics => self.metrics_panel.toggle(),
+            commands::Command::ToggleSceneHistory => self.scene_history.toggle(),
+            commands::Command::ToggleUndoHistory => self.undo_history.toggle(),
+            commands::Command::ToggleProfiler => self.profiler.toggle(),
+            commands::Command::OpenSettings => self.settings_window.open_with(&self.settings),
+            commands::Command::OpenProfiles => self.profile_editor.open_with(&self.current_profile),
+        }
+    }
+
+    /// Cancel the running generation. Output already streamed stays in the
+    /// chat; tool calls still being assembled are discarded with the task.
+    fn stop_generation(&mut self) {
+        self.abort_generation("Stopped by user");
+    }
+
+    fn abort_generation(&mut self, note: &str) {
+        if let Some(handle) = self.generation.take() {
+            handle.abort();
+        }
+        // Dropping the requests rejects them, unblocking the waiting tools
+        self.pending_reviews.clear();
+        self.is_loading = false;
+        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+            channel.history.push(("System".to_string(), MessageContent::Text(note.to_string())));
+        }
+        self.save_session();
+    }
+
+    /// Warn near a spending budget; at the limit, stop the running
+    /// generation and hold further requests until the user confirms.
+    fn check_budget(&mut self) {
+        if self.budget_confirmed {
+            return;
+        }
+        match self.usage.budget_status(&self.settings.budget()) {
+            llm::usage::BudgetStatus::Exceeded(message) => {
+                if self.generation.is_some() {
+                    self.abort_generation(&format!("Stopped: {}", message));
+                }
+                if self.budget_blocked.is_none() {
+                    self.budget_blocked = Some((message, false));
+                }
+            }
+            llm::usage::BudgetStatus::Warning(message) if !self.budget_warned => {
+                self.budget_warned = true;
+                self.toasts.push(ui::toasts::ToastKind::Warning, &message);
+            }
+            _ => {}
         }
+    }
 
-        let current_history = if let Some(channel) = self.channels.get(&self.active_channel_id) {
-            &channel.history
-        } else {
-            // Should not happen, but safe fallback
+    fn resolve_budget(&mut self, choice: chat::BudgetChoice) {
+        let Some((_, waiting)) = self.budget_blocked.take() else {
             return;
         };
-
-        println!("[DEBUG] Building message history. Count={}", current_history.len());
-        for (role, content) in current_history {
-            let api_role = match role.as_str() {
-                "Cats2333" => "user",
-                "System" | "Error" => "system", 
-                _ => "assistant", 
-            };
-            
-            if role == "System" || role == "Error" { continue; }
-
-            messages.push(Message {
-                role: api_role.to_string(),
-                content: Some(content.clone()),
-                tool_calls: None,
-                tool_call_id: None,
-            });
+        if choice == chat::BudgetChoice::Continue {
+            self.budget_confirmed = true;
+            if waiting {
+                self.generate();
+            }
         }
+    }
 
-        let profile_name = self.current_profile.name.clone();
-        let tools_schema: Vec<Value> = tools::get_tools_for_profile(&profile_name, tx.clone())
-            .iter()
-            .map(|t| t.schema())
-            .collect();
+    /// Contents of the file explorer, docked or in its own window.
+    fn render_files(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        self.watcher.watch(&self.file_tree_state.root_path);
+        match file_tree::render_file_tree(ui, &mut self.file_tree_state, self.watcher.revision()) {
+            file_tree::FileTreeAction::Preview(path) => self.file_preview.show_file(&path),
+            file_tree::FileTreeAction::SendPath(path) => {
+                if !self.input_text.is_empty() && !self.input_text.ends_with(' ') {
+                    self.input_text.push(' ');
+                }
+                self.input_text.push_str(&path.display().to_string());
+            }
+            file_tree::FileTreeAction::Read(path) => {
+                if let Err(e) = self.attachments.add_path(ctx, &path) {
+                    self.push_error(format!("{:#}", e));
+                }
+            }
+            file_tree::FileTreeAction::DiffWithGit(path) => self.file_preview.show_diff(&path),
+            file_tree::FileTreeAction::UploadToGame(path) => {
+                let args = serde_json::json!({ "local_path": path.display().to_string(), "translation": [0.0, 0.0, 0.0] });
+                self.run_tool_in_background(Box::new(tools::bevy::BevyUploadAssetTool), args, "Upload model");
+            }
+            file_tree::FileTreeAction::None => {}
+        }
+        
+        ui.add_space(10.0);
+        ui.separator();
+        ui.add_space(10.0);
 
-        let rt_handle = self.rt.handle().clone();
-        rt_handle.spawn(async move {
-            let mut turn_count = 0;
-            const MAX_TURNS: i32 = 50;
+        if !self.file_tree_state.selected_files.is_empty() {
+            if ui.button(egui::RichText::new("🚀 Ingest Context").strong().color(egui::Color32::GREEN)).clicked() {
+                let mut targets = Vec::new();
+                let mut references = Vec::new();
 
-            loop {
-                if turn_count >= MAX_TURNS {
-                    let _ = tx.send(AsyncMessage::Error("Max turns exceeded".to_string()));
-                    break;
-                }
-                turn_count += 1;
+                for path in &self.file_tree_state.selected_files {
+                    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+                    let is_binary = matches!(extension.as_str(), "glb" | "gltf" | "png" | "jpg" | "jpeg" | "wav" | "ogg" | "mp3");
 
-                match client.chat_completion_stream(messages.clone(), Some(tools_schema.clone())).await {
-                    Ok(mut stream) => {
-                        let mut full_text = String::new();
-                        // let is_planning_channel = messages.iter().any(|m| m.role == "system" && m.content.as_ref().map_or(false, |c| match c { MessageContent::Text(t) => t.contains("Conductor Agent"), _ => false }));
+                    if is_binary {
+                        // For binary assets, provide the path but NOT the content
+                        // Strong hint to use the upload tool
+                        let entry = format!("`{}`: [BINARY ASSET AVAILABLE]. To spawn this in Bevy, you MUST use the 'bevy_upload_asset' tool with this 'local_path'.\n", path.display());
+                        references.push(entry);
+                    } else if let Ok(content) = std::fs::read_to_string(path) {
+                        let is_modify = *self.file_tree_state.selection_modes.get(path).unwrap_or(&false);
+                        let entry = format!("`{}`:\n```rust\n{}\n```\n", path.display(), content);
                         
-                        struct ToolBuilder {
-                            #[allow(dead_code)]
-                            index: i32,
-                            id: Option<String>,
-                            r#type: String, 
-                            name: Option<String>,
-                            args: String,
+                        if is_modify {
+                            targets.push(entry);
+                        } else {
+                            references.push(entry);
                         }
-                        let mut tool_buffer: std::collections::HashMap<i32, ToolBuilder> = std::collections::HashMap::new();
+                    }
+                }
 
-                        while let Some(result) = stream.next().await {
-                            match result {
-                                Ok(StreamEvent::TextChunk(text)) => {
-                                    let _ = tx.send(AsyncMessage::StreamText(text.clone()));
-                                    full_text.push_str(&text);
-                                }
-                                Ok(StreamEvent::ToolCallChunk(tc)) => {
-                                    let entry = tool_buffer.entry(tc.index).or_insert(ToolBuilder {
-                                        index: tc.index,
-                                        id: None,
-                                        r#type: "function".to_string(),
-                                        name: None,
-                                        args: String::new(),
-                                    });
-                                    
-                                    if let Some(id) = tc.id { entry.id = Some(id); }
-                                    if let Some(t) = tc.r#type { entry.r#type = t; }
-                                    if let Some(f) = tc.function {
-                                        if let Some(n) = f.name { 
-                                            if let Some(curr) = &mut entry.name {
-                                                curr.push_str(&n);
-                                            } else {
-                                                entry.name = Some(n);
-                                            }
-                                        }
-                                        if let Some(a) = f.arguments { entry.args.push_str(&a); }
-                                    }
-                                }
-                                Ok(StreamEvent::Done) => {}
-                                Err(e) => {
-                                    let _ = tx.send(AsyncMessage::Error(e.to_string()));
-                                }
-                            }
-                        }
+                let mut prompt = String::from("## 📂 Active Context Ingestion\n\n");
+                if !targets.is_empty() {
+                    prompt.push_str("### ✏️ TARGETS (Please Modify these):\n");
+                    for t in targets { prompt.push_str(&t); prompt.push('\n'); }
+                }
+                if !references.is_empty() {
+                    prompt.push_str("### 📖 REFERENCES (Read-Only Context):\n");
+                    for r in references { prompt.push_str(&r); prompt.push('\n'); }
+                }
+                prompt.push_str("\n**INSTRUCTION**: Use the Reference files to guide your changes to the Target files.");
 
-                        if !tool_buffer.is_empty() {
-                            let mut tool_calls = Vec::new();
-                            let mut indices: Vec<i32> = tool_buffer.keys().cloned().collect();
-                            indices.sort();
-                            
-                            for idx in indices {
-                                if let Some(builder) = tool_buffer.get(&idx) {
-                                    if let Some(name) = &builder.name {
-                                        tool_calls.push(ToolCall {
-                                            id: builder.id.clone().unwrap_or_else(|| format!("call_{}", idx)),
-                                            r#type: builder.r#type.clone(),
-                                            function: FunctionCall {
-                                                name: name.clone(),
-                                                arguments: builder.args.clone(),
-                                            },
-                                        });
-                                    }
-                                }
-                            }
+                if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+                    channel.history.push(("System".to_string(), MessageContent::Text(prompt)));
+                }
+            }
+        }
+    }
 
-                            messages.push(Message {
-                                role: "assistant".to_string(),
-                                content: if !full_text.is_empty() { Some(MessageContent::Text(full_text.clone())) } else { None },
-                                tool_calls: Some(tool_calls.clone()),
-                                tool_call_id: None,
-                            });
-
-                            let all_tools = crate::tools::get_tools_for_profile(&profile_name, tx.clone());
-                            for tool_call in tool_calls {
-                                let _ = tx.send(AsyncMessage::Log(format!("Executing tool: {} args: {}", tool_call.function.name, tool_call.function.arguments)));
-                                
-                                let mut result_content = String::new();
-                                let mut found = false;
-                                
-                                for tool in &all_tools {
-                                    if tool.name() == tool_call.function.name {
-                                        found = true;
-                                        match serde_json::from_str::<serde_json::Value>(&tool_call.function.arguments) {
-                                            Ok(args_val) => {
-                                                match tool.execute(args_val) {
-                                                    Ok(res) => result_content = res,
-                                                    Err(e) => result_content = format!("Error executing tool: {}", e),
-                                                }
-                                            },
-                                            Err(e) => result_content = format!("Error parsing arguments JSON: {}", e),
-                                        }
-                                        break;
-                                    }
-                                }
-                                if !found {
-                                    result_content = format!("Error: Tool '{}' not found", tool_call.function.name);
-                                }
+    /// Header, history and review cards of the active channel.
+    fn render_chat_panel(&mut self, ctx: &egui::Context, ui: &mut egui::Ui) {
+        let mut apply_code = None;
+        let mut review_choice = None;
+        let mut budget_choice = None;
+        let mut stop = false;
+        let mut chat_edit = None;
+        let context = self
+            .context_tokens
+            .get(&self.active_channel_id)
+            .map(|&used| (used, llm::tokens::context_window(&self.current_profile.model)));
+        ui.horizontal(|ui| {
+            chat::render_chat_header(ui, &self.usage, context);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui::viewport::detach_button(ui, &mut self.chat_detached);
+            });
+        });
+        ui.separator();
 
-                                messages.push(Message {
-                                    role: "tool".to_string(),
-                                    content: Some(MessageContent::Text(result_content)),
-                                    tool_calls: None,
-                                    tool_call_id: Some(tool_call.id),
-                                });
-                            }
-                            continue;
-                        }
+        egui::ScrollArea::vertical()
+            .stick_to_bottom(true)
+            .auto_shrink([false; 2])
+            .show(ui, |ui| {
+                if let Some(channel) = self.channels.get(&self.active_channel_id) {
+                    let action = chat::render_chat(
+                        ui, 
+                        ctx, 
+                        &channel.history, 
+                        &self.available_profiles, 
+                        &mut self.image_textures,
+                        &chat::ChatView {
+                            user_color: self.settings.user_color(),
+                            generating: self.generation.is_some(),
+                            message_usage: self.message_usage.get(&self.active_channel_id),
+                            personas: &self.personas,
+                        },
+                    );
 
-                        if !full_text.is_empty() {
-                            messages.push(Message {
-                                role: "assistant".to_string(),
-                                content: Some(MessageContent::Text(full_text)),
-                                tool_calls: None,
-                                tool_call_id: None,
-                            });
-                            let _ = tx.send(AsyncMessage::Done);
-                            break;
-                        } else {
-                            let _ = tx.send(AsyncMessage::Done);
-                            break;
-                        }
+                    match action {
+                        chat::ChatAction::ApplyCode { code, path } => apply_code = Some((code, path)),
+                        chat::ChatAction::StopGeneration => stop = true,
+                        chat::ChatAction::EditMessage(idx) => chat_edit = Some((idx, false)),
+                        chat::ChatAction::Regenerate(idx) => chat_edit = Some((idx, true)),
+                        chat::ChatAction::None => {}
                     }
-                    Err(e) => {
-                        let _ = tx.send(AsyncMessage::Error(e.to_string()));
-                        break;
+
+                    review_choice = ui::review::render_review_cards(ui, &self.pending_reviews);
+                    if let Some((message, _)) = &self.budget_blocked {
+                        budget_choice = chat::render_budget_card(ui, message);
                     }
                 }
-            }
-        });
+            });
+
+        if stop {
+            self.run_command(commands::Command::StopGeneration);
+        }
+        match chat_edit {
+            Some((idx, true)) => self.regenerate(idx),
+            Some((idx, false)) => self.edit_message(idx),
+            None => {}
+        }
+        if let Some((code, path)) = apply_code {
+            self.apply_code_block(code, path);
+        }
+        if let Some((idx, choice)) = review_choice {
+            self.resolve_review(idx, choice);
+        }
+        if let Some(choice) = budget_choice {
+            self.resolve_budget(choice);
+        }
+    }
+}
+
+/// The reply being generated: the last assistant entry after the last user
+/// message, if any text arrived yet.
+fn current_reply_index(history: &[(String, MessageContent)]) -> Option<usize> {
+    let user = history.iter().rposition(|(role, _)| role == "Cats2333").unwrap_or(0);
+    history
+        .iter()
+        .rposition(|(role, _)| !matches!(role.as_str(), "Cats2333" | "System" | "Error"))
+        .filter(|&idx| idx > user)
+}
+
+fn forget_usage_from(usage: &mut HashMap<String, HashMap<usize, llm::usage::UsageTotals>>, channel_id: &str, len: usize) {
+    if let Some(usage) = usage.get_mut(channel_id) {
+        usage.retain(|&idx, _| idx < len);
     }
 }
 
 impl eframe::App for AxiomApp {
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        // Closing mid-generation is a stop, not a crash
+        if self.generation.is_some() {
+            self.stop_generation();
+        }
+        // The game started from the editor closes with it
+        self.stop_game();
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        // Checked before draining, so everything a finished task sent is
+        // handled before the generation is dropped below
+        let generation_finished = self.generation.as_ref().is_some_and(|h| h.is_finished());
+
         while let Ok(msg) = self.rx.try_recv() {
+            // A stopped task may still have output queued; drop it
+            let from_generation = matches!(
+                msg,
+                AsyncMessage::StreamText(_)
+                    | AsyncMessage::Response(_)
+                    | AsyncMessage::Log(_)
+                    | AsyncMessage::Done
+                    | AsyncMessage::Error(_)
+                    | AsyncMessage::ProviderUnavailable { .. }
+                    | AsyncMessage::ReviewEdit(_)
+            );
+            if from_generation && self.generation.is_none() {
+                continue;
+            }
+            if from_generation {
+                self.session_dirty = true;
+            }
+
             match msg {
                 AsyncMessage::StreamText(text) => {
                     self.is_loading = true;
+                    self.provider_unavailable_until = None;
                     
                     let mut append_needed = false;
                     let channel_history = &self.channels.get(&self.active_channel_id).unwrap().history;
@@ -505,90 +1525,216 @@ impl eframe::App for AxiomApp {
                     }
                 }
                 AsyncMessage::Done => {
+                    self.generation = None;
                     self.is_loading = false;
+                    #[cfg(feature = "voice")]
+                    self.speak_last_reply();
+                    self.save_session();
                 }
                 AsyncMessage::Response(content) => {
                     if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
                         channel.history.push((self.current_profile.name.clone(), content));
                     }
+                    self.generation = None;
                     self.is_loading = false;
+                    self.save_session();
                 }
                 AsyncMessage::Log(text) => {
+                     if let Some((tool, _)) = export::parse_tool_call(&text) {
+                         self.running_tools.push(tool.to_string());
+                     } else if let Some((tool, _)) = export::parse_tool_result(&text) {
+                         if let Some(idx) = self.running_tools.iter().position(|t| t == tool) {
+                             self.running_tools.remove(idx);
+                         }
+                     }
                      if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
                         channel.history.push(("System".to_string(), MessageContent::Text(text)));
                      }
                 }
+                AsyncMessage::Usage { profile, model, usage } => {
+                    let cost = self.usage.record(&profile, &model, usage);
+                    let channel_id = self.active_channel_id.clone();
+                    // A report with input tokens starts a new request
+                    let context = self.context_tokens.entry(channel_id.clone()).or_default();
+                    if usage.input_tokens > 0 {
+                        *context = usage.input_tokens + usage.output_tokens;
+                    } else {
+                        *context += usage.output_tokens;
+                    }
+                    if let Some(idx) = self.channels.get(&channel_id).and_then(|c| current_reply_index(&c.history)) {
+                        self.message_usage.entry(channel_id).or_default().entry(idx).or_default().add(usage, cost);
+                    }
+                    if cost > 0.0 {
+                        if let Err(e) = spending::save(&self.usage.today) {
+                            tracing::warn!("Failed to save spending: {:#}", e);
+                        }
+                        self.check_budget();
+                    }
+                }
+                AsyncMessage::Models { provider, models } => {
+                    // Ignore a late reply after switching to another provider
+                    if provider == self.current_profile.provider {
+                        self.model_picker.models = models;
+                    }
+                    self.model_picker.loading = false;
+                }
+                AsyncMessage::Transcript(result) => {
+                    #[cfg(feature = "voice")]
+                    {
+                        self.voice.transcribing = false;
+                    }
+                    match result {
+                        Ok(text) => {
+                            if !self.input_text.is_empty() && !self.input_text.ends_with(' ') {
+                                self.input_text.push(' ');
+                            }
+                            self.input_text.push_str(&text);
+                        }
+                        Err(e) => self.push_error(e),
+                    }
+                }
+                AsyncMessage::ReviewEdit(review) => self.pending_reviews.push(review),
+                AsyncMessage::ActionFinished(result) => match result {
+                    Ok(message) => {
+                        self.toasts.push(ui::toasts::ToastKind::Success, &message);
+                        // Details such as test output go to the chat
+                        if message.contains('\n') {
+                            if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+                                channel.history.push(("System".to_string(), MessageContent::Text(message)));
+                            }
+                        }
+                        // An upload may have added to the game's cache
+                        if self.assets.open {
+                            self.refresh_remote_assets();
+                        }
+                    }
+                    Err(e) => {
+                        self.toasts.push(ui::toasts::ToastKind::Error, &e);
+                        if e.contains('\n') {
+                            self.push_error(e);
+                        }
+                    }
+                },
+                AsyncMessage::SceneHistory(result) => {
+                    self.scene_history.busy = false;
+                    self.scene_history.reload();
+                    match result {
+                        Ok(message) => self.toasts.push(ui::toasts::ToastKind::Success, &message),
+                        Err(e) => self.toasts.push(ui::toasts::ToastKind::Error, &e),
+                    }
+                }
+                AsyncMessage::UndoHistory(result) => self.undo_history.receive(result),
+                AsyncMessage::Profile(result) => self.profiler.receive(result),
+                AsyncMessage::GameLaunched(Ok(process)) => {
+                    self.toasts.push(ui::toasts::ToastKind::Success, &format!("Started the game (pid {})", process.launch.pid));
+                    self.game = Some(process);
+                    self.game_status = game::GameStatus::Running { reachable: None };
+                    // Give it time to open the BRP port
+                    self.last_game_ping = std::time::Instant::now();
+                }
+                AsyncMessage::GameLaunched(Err(e)) => {
+                    self.toasts.push(ui::toasts::ToastKind::Error, &e);
+                    self.game_status = game::GameStatus::Stopped(e);
+                }
+                AsyncMessage::GamePing(latency) => {
+                    self.game_ping_pending = false;
+                    self.game_latency = latency;
+                    let reachable = latency.is_some();
+                    match &mut self.game_status {
+                        // Still starting up until the first answer
+                        game::GameStatus::Running { reachable: status } => {
+                            if reachable || status.is_some() {
+                                *status = Some(reachable);
+                                self.set_game_reachable(reachable);
+                            }
+                        }
+                        _ => {
+                            if reachable || self.game_reachable.is_some() {
+                                self.set_game_reachable(reachable);
+                            }
+                        }
+                    }
+                }
+                AsyncMessage::GamesDiscovered(result) => self.connection.receive(result),
+                AsyncMessage::GameScreenshot(result) => {
+                    self.set_game_reachable(result.is_ok());
+                    self.game_preview.receive(ctx, result);
+                }
+                AsyncMessage::RemoteAssets(result) => {
+                    self.set_game_reachable(result.is_ok());
+                    self.assets.receive(result);
+                }
+                AsyncMessage::ProviderUnavailable { message, retry_in } => {
+                    self.toasts.push(ui::toasts::ToastKind::Warning, &message);
+                    if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
+                        channel.history.push(("Error".to_string(), MessageContent::Text(message)));
+                    }
+                    self.provider_unavailable_until = Some(std::time::Instant::now() + retry_in);
+                    self.generation = None;
+                    self.is_loading = false;
+                    self.save_session();
+                }
                 AsyncMessage::Error(err) => {
                     if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
                         channel.history.push(("Error".to_string(), MessageContent::Text(err)));
                     }
                     self.is_loading = false;
+                    self.save_session();
                 }
             }
             ctx.request_repaint();
         }
 
+        // Messages come from other threads and don't wake the UI; poll while
+        // generating so streamed tokens show up as they arrive
+        if self.generation.is_some() {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        }
+
+        // A task that failed ends without `Done`; one that panicked inside a
+        // tool also never reported the tool's result
+        if generation_finished && self.generation.take().is_some() {
+            self.is_loading = false;
+            if !self.running_tools.is_empty() {
+                let tools = std::mem::take(&mut self.running_tools).join(", ");
+                self.push_error(format!("Tool call interrupted: {}", tools));
+            }
+            self.save_session();
+        }
+        if self.session_dirty {
+            let since_save = self.last_autosave.elapsed();
+            if since_save >= AUTOSAVE_INTERVAL {
+                self.save_session();
+            } else {
+                ctx.request_repaint_after(AUTOSAVE_INTERVAL - since_save);
+            }
+        }
+
         if self.waiting_for_screenshot {
              if self.paste_from_clipboard(ctx) {
                  self.waiting_for_screenshot = false;
              }
         }
 
-        // Layout
-        egui::SidePanel::left("file_tree_panel")
-            .min_width(200.0)
-            .default_width(250.0)
-            .resizable(true)
-            .show(ctx, |ui| {
-                file_tree::render_file_tree(ui, &mut self.file_tree_state);
-                
-                ui.add_space(10.0);
-                ui.separator();
-                ui.add_space(10.0);
-
-                if !self.file_tree_state.selected_files.is_empty() {
-                    if ui.button(egui::RichText::new("🚀 Ingest Context").strong().color(egui::Color32::GREEN)).clicked() {
-                        let mut targets = Vec::new();
-                        let mut references = Vec::new();
-
-                        for path in &self.file_tree_state.selected_files {
-                            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
-                            let is_binary = matches!(extension.as_str(), "glb" | "gltf" | "png" | "jpg" | "jpeg" | "wav" | "ogg" | "mp3");
-
-                            if is_binary {
-                                // For binary assets, provide the path but NOT the content
-                                // Strong hint to use the upload tool
-                                let entry = format!("`{}`: [BINARY ASSET AVAILABLE]. To spawn this in Bevy, you MUST use the 'bevy_upload_asset' tool with this 'local_path'.\n", path.display());
-                                references.push(entry);
-                            } else if let Ok(content) = std::fs::read_to_string(path) {
-                                let is_modify = *self.file_tree_state.selection_modes.get(path).unwrap_or(&false);
-                                let entry = format!("`{}`:\n```rust\n{}\n```\n", path.display(), content);
-                                
-                                if is_modify {
-                                    targets.push(entry);
-                                } else {
-                                    references.push(entry);
-                                }
-                            }
-                        }
+        self.handle_dropped_files(ctx);
 
-                        let mut prompt = String::from("## 📂 Active Context Ingestion\n\n");
-                        if !targets.is_empty() {
-                            prompt.push_str("### ✏️ TARGETS (Please Modify these):\n");
-                            for t in targets { prompt.push_str(&t); prompt.push('\n'); }
-                        }
-                        if !references.is_empty() {
-                            prompt.push_str("### 📖 REFERENCES (Read-Only Context):\n");
-                            for r in references { prompt.push_str(&r); prompt.push('\n'); }
-                        }
-                        prompt.push_str("\n**INSTRUCTION**: Use the Reference files to guide your changes to the Target files.");
+        let settings = &self.settings;
+        if let Some(command) = commands::pressed(ctx, |command| settings.shortcut(command)) {
+            self.run_command(command);
+        }
 
-                        if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
-                            channel.history.push(("System".to_string(), MessageContent::Text(prompt)));
-                        }
-                    }
-                }
-            });
+        // Layout
+        if self.file_tree_state.detached {
+            if ui::viewport::show_detached(ctx, "file_tree", "📂 Project Files", [320.0, 640.0], |ui| self.render_files(ctx, ui)) {
+                self.file_tree_state.detached = false;
+            }
+        } else {
+            egui::SidePanel::left("file_tree_panel")
+                .min_width(200.0)
+                .default_width(250.0)
+                .resizable(true)
+                .show(ctx, |ui| self.render_files(ctx, ui));
+        }
 
         /*
         egui::SidePanel::right("right_panel")
@@ -605,8 +1751,10 @@ impl eframe::App for AxiomApp {
 
                 match action {
                     sidebar::SidebarAction::SelectProfile(profile) => {
-                        self.current_profile = profile;
+                        self.current_profile = *profile;
                         self.client = None;
+                        self.save_session();
+                        self.refresh_models();
                     }
                     sidebar::SidebarAction::CopyLog => {
                         let mut log_text = String::new();
@@ -631,23 +1779,38 @@ impl eframe::App for AxiomApp {
         */
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
+            let unavailable_for = self
+                .provider_unavailable_until
+                .and_then(|until| until.checked_duration_since(std::time::Instant::now()));
+            if unavailable_for.is_some() {
+                // Keep the countdown ticking
+                ctx.request_repaint_after(std::time::Duration::from_secs(1));
+            }
+
             let action = top_panel::render_top_panel(
                 ui, 
                 &self.active_channel_id, 
+                unavailable_for,
+                self.game_preview.open,
+                self.console.open,
+                self.todos.open,
+                self.assets.open,
+                &self.game_status,
+                self.game_latency,
             );
             
             match action {
                 top_panel::TopPanelAction::SwitchChannel(id) => {
                     self.active_channel_id = id;
                 }
-                top_panel::TopPanelAction::ClearChat => {
-                    if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
-                        channel.history.clear();
-                    }
-                }
+                top_panel::TopPanelAction::ClearChat => self.run_command(commands::Command::ClearChat),
+                top_panel::TopPanelAction::RunGame => self.run_command(commands::Command::RunGame),
+                top_panel::TopPanelAction::ConnectToGame => self.run_command(commands::Command::ConnectToGame),
+                top_panel::TopPanelAction::StopGame => self.run_command(commands::Command::StopGame),
+                top_panel::TopPanelAction::RestartGame => self.run_command(commands::Command::RestartGame),
                 // top_panel::TopPanelAction::ClearScene => {
                 //     // Directly execute the Clear Scene tool without involving the LLM
-                //     let tool = crate::tools::bevy::BevyClearSceneTool;
+                //     let tool = tools::bevy::BevyClearSceneTool;
                 //     let result = match tool.execute(serde_json::Value::Null) {
                 //         Ok(msg) => format!("✅ Scene Cleared: {}", msg),
                 //         Err(e) => format!("❌ Failed to Clear Scene: {}", e),
@@ -674,6 +1837,13 @@ impl eframe::App for AxiomApp {
                         let _ = clipboard.set_text(log_text);
                     }
                 }
+                top_panel::TopPanelAction::ExportChat => self.run_command(commands::Command::ExportChat),
+                top_panel::TopPanelAction::TogglePreview => self.run_command(commands::Command::TogglePreview),
+                top_panel::TopPanelAction::ToggleConsole => self.run_command(commands::Command::ToggleConsole),
+                top_panel::TopPanelAction::ToggleTodos => self.run_command(commands::Command::ToggleTodos),
+                top_panel::TopPanelAction::ToggleAssets => self.run_command(commands::Command::ToggleAssets),
+                top_panel::TopPanelAction::OpenSettings => self.run_command(commands::Command::OpenSettings),
+                top_panel::TopPanelAction::OpenProfiles => self.run_command(commands::Command::OpenProfiles),
                 top_panel::TopPanelAction::None => {}
             }
         });
@@ -683,19 +1853,20 @@ impl eframe::App for AxiomApp {
                 ui, 
                 &mut self.input_text, 
                 self.is_loading, 
-                &self.pending_image, 
-                &self.preview_texture,
-                &self.current_profile
+                &self.attachments,
+                &self.current_profile,
+                &self.model_picker,
+                self.editing_message.is_some(),
             );
+            #[cfg(feature = "voice")]
+            let action = match input::render_voice_controls(ui, &self.voice) {
+                input::InputAction::None => action,
+                voice_action => voice_action,
+            };
 
             match action {
                 input::InputAction::Send => self.send_message(false),
-                input::InputAction::StopLoading => {
-                    self.is_loading = false;
-                    if let Some(channel) = self.channels.get_mut(&self.active_channel_id) {
-                        channel.history.push(("System".to_string(), MessageContent::Text("Stopped by user".to_string())));
-                    }
-                }
+                input::InputAction::StopLoading => self.run_command(commands::Command::StopGeneration),
                 input::InputAction::RequestScreenshot => {
                     #[cfg(target_os = "windows")]
                     {
@@ -710,49 +1881,295 @@ impl eframe::App for AxiomApp {
                     
                     #[cfg(not(target_os = "windows"))]
                     {
-                        eprintln!("Screenshot functionality not supported on this platform");
+                        tracing::warn!("Screenshot functionality not supported on this platform");
+                    }
+                }
+                input::InputAction::RemoveAttachment(kind, idx) => match kind {
+                    input::AttachmentKind::Image if idx < self.attachments.images.len() => {
+                        self.attachments.images.remove(idx);
                     }
+                    input::AttachmentKind::File if idx < self.attachments.files.len() => {
+                        self.attachments.files.remove(idx);
+                    }
+                    input::AttachmentKind::Model if idx < self.attachments.models.len() => {
+                        self.attachments.models.remove(idx);
+                    }
+                    _ => {}
+                },
+                input::InputAction::UploadModel(idx) => self.upload_model(idx),
+                input::InputAction::CancelEdit => {
+                    self.editing_message = None;
+                    self.input_text.clear();
                 }
-                input::InputAction::ClearPendingImage => {
-                    self.pending_image = None;
-                    self.preview_texture = None;
+                #[cfg(feature = "voice")]
+                input::InputAction::ToggleRecording => self.toggle_recording(),
+                #[cfg(feature = "voice")]
+                input::InputAction::ToggleSpeakReplies => {
+                    self.voice.speak_replies = !self.voice.speak_replies;
+                    if !self.voice.speak_replies {
+                        self.voice.speaker.stop();
+                    }
                 }
+                input::InputAction::SelectModel(model) => self.select_model(model),
+                input::InputAction::RefreshModels => self.refresh_models(),
                 input::InputAction::None => {}
             }
         });
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::vertical()
-                .stick_to_bottom(true)
-                .auto_shrink([false; 2])
-                .show(ui, |ui| {
-                    if let Some(channel) = self.channels.get(&self.active_channel_id) {
-                        let action = chat::render_chat(
-                            ui, 
-                            ctx, 
-                            &channel.history, 
-                            &self.available_profiles, 
-                            &mut self.image_textures,
-                        );
-
-                        match action {
-                            chat::ChatAction::None => {}
-                        }
+        for entry in self.console.poll() {
+            let kind = match entry.level {
+                tracing::Level::ERROR => ui::toasts::ToastKind::Error,
+                tracing::Level::WARN => ui::toasts::ToastKind::Warning,
+                _ => ui::toasts::ToastKind::Info,
+            };
+            self.toasts.push(kind, &entry.message);
+        }
+        ui::console::render_console(ctx, &mut self.console);
+        match ui::assets::render_asset_browser(ctx, &mut self.assets, self.watcher.revision()) {
+            ui::assets::AssetAction::RefreshRemote => self.refresh_remote_assets(),
+            ui::assets::AssetAction::Spawn(asset) => self.spawn_asset(asset),
+            ui::assets::AssetAction::None => {}
+        }
+
+        match ui::metrics::render_metrics_panel(ctx, &mut self.metrics_panel) {
+            ui::metrics::MetricsAction::SetEnabled(enabled) => {
+                let settings = settings::Settings { metrics_enabled: enabled, ..self.settings.clone() };
+                self.apply_settings(ctx, settings);
+            }
+            ui::metrics::MetricsAction::Export => self.export_metrics(),
+            ui::metrics::MetricsAction::None => {}
+        }
+
+        match ui::scene_history::render_scene_history(ctx, &mut self.scene_history, &self.project.root) {
+            ui::scene_history::SceneHistoryAction::Save(name) => self.save_scene_snapshot(name),
+            ui::scene_history::SceneHistoryAction::Restore(snapshot) => self.restore_scene_snapshot(snapshot),
+            ui::scene_history::SceneHistoryAction::Delete(name) => {
+                if let Err(e) = scene_history::delete(&self.project.root, &name) {
+                    self.toasts.push(ui::toasts::ToastKind::Error, &format!("{:#}", e));
+                }
+                self.scene_history.reload();
+            }
+            ui::scene_history::SceneHistoryAction::None => {}
+        }
+
+        match ui::undo_history::render_undo_history(ctx, &mut self.undo_history) {
+            ui::undo_history::UndoHistoryAction::Undo => self.step_undo_history(false),
+            ui::undo_history::UndoHistoryAction::Redo => self.step_undo_history(true),
+            ui::undo_history::UndoHistoryAction::Refresh => self.refresh_undo_history(),
+            ui::undo_history::UndoHistoryAction::None => {}
+        }
+        match ui::profiler::render_profiler(ctx, &mut self.profiler) {
+            ui::profiler::ProfilerAction::None => {}
+            action => self.profile_game(action),
+        }
+
+        if let ui::settings::SettingsAction::Save(settings) = ui::settings::render_settings(ctx, &mut self.settings_window) {
+            self.apply_settings(ctx, *settings);
+        }
+
+        match ui::profiles::render_profile_editor(
+            ctx,
+            &mut self.profile_editor,
+            &self.available_profiles,
+            &self.current_profile.name,
+            &self.settings.default_model,
+        ) {
+            ui::profiles::ProfileAction::Save { original, profile } => self.save_profile(original, *profile),
+            ui::profiles::ProfileAction::Delete(name) => self.delete_profile(name),
+            ui::profiles::ProfileAction::Use(name) => {
+                if let Some(profile) = self.available_profiles.iter().find(|p| p.name == name).cloned() {
+                    self.use_profile(profile);
+                    self.save_session();
+                }
+            }
+            ui::profiles::ProfileAction::None => {}
+        }
+
+        let settings = &self.settings;
+        let palette_action = ui::palette::render_palette(
+            ctx,
+            &mut self.palette,
+            &self.available_profiles,
+            &self.channels,
+            &|command| settings.shortcut(command),
+        );
+        match palette_action {
+            ui::palette::PaletteAction::Run(command) => self.run_command(command),
+            ui::palette::PaletteAction::UseProfile(name) => {
+                if let Some(profile) = self.available_profiles.iter().find(|p| p.name == name).cloned() {
+                    self.use_profile(profile);
+                    self.save_session();
+                }
+            }
+            ui::palette::PaletteAction::SwitchChannel(id) => {
+                self.active_channel_id = id;
+                self.save_session();
+            }
+            ui::palette::PaletteAction::None => {}
+        }
+
+        if self.todos.open {
+            self.todos.poll();
+            ctx.request_repaint_after(std::time::Duration::from_secs(1));
+        }
+        if let ui::todos::TodoAction::Save = ui::todos::render_todo_panel(ctx, &mut self.todos) {
+            if let Err(e) = self.todos.save() {
+                self.push_error(format!("{:#}", e));
+            }
+        }
+
+        ui::file_preview::render_file_preview(ctx, &mut self.file_preview);
+
+        match ui::preview::render_game_preview(ctx, &mut self.game_preview) {
+            ui::preview::PreviewAction::Refresh => self.game_preview.request_now(),
+            ui::preview::PreviewAction::Spawn(asset) => self.spawn_asset(asset),
+            ui::preview::PreviewAction::None => {}
+        }
+        self.poll_game_preview(ctx);
+        self.watch_game(ctx);
+        self.ping_game(ctx);
+        match ui::connection::render_connection(ctx, &mut self.connection, self.game_latency, self.game_reachable) {
+            ui::connection::ConnectionAction::Connect { endpoint, token } => self.connect_to_game(ctx, endpoint, token),
+            ui::connection::ConnectionAction::Scan => self.scan_for_games(),
+            ui::connection::ConnectionAction::None => {}
+        }
+
+        ui::toasts::render_toasts(ctx, &mut self.toasts);
+
+        if self.chat_detached {
+            egui::CentralPanel::default().show(ctx, |ui| {
+                ui.centered_and_justified(|ui| {
+                    if ui.button("💬 The chat is in its own window. Dock it back").clicked() {
+                        self.chat_detached = false;
                     }
                 });
-        });
+            });
+            if ui::viewport::show_detached(ctx, "chat", "💬 Chat", [720.0, 800.0], |ui| self.render_chat_panel(ctx, ui)) {
+                self.chat_detached = false;
+            }
+        } else {
+            egui::CentralPanel::default().show(ctx, |ui| self.render_chat_panel(ctx, ui));
+        }
+    }
+}
+
+/// One-time setup of the egui context: image loaders, fonts, appearance.
+fn setup_context(ctx: &egui::Context, settings: &settings::Settings) {
+    egui_extras::install_image_loaders(ctx);
+
+    let mut fonts = egui::FontDefinitions::default();
+    
+    #[cfg(target_os = "windows")]
+    let font_path = "C:/Windows/Fonts/msyh.ttc";
+    
+    #[cfg(target_os = "linux")]
+    let font_path = "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf";
+    
+    #[cfg(target_os = "macos")]
+    let font_path = "/System/Library/Fonts/PingFang.ttc";
+
+    if let Ok(data) = std::fs::read(font_path) {
+        fonts.font_data.insert(
+            "MicrosoftYaHei".to_owned(),
+            egui::FontData::from_owned(data),
+        );
+        fonts.families.entry(egui::FontFamily::Proportional).or_default().insert(0, "MicrosoftYaHei".to_owned());
+        fonts.families.entry(egui::FontFamily::Monospace).or_default().insert(0, "MicrosoftYaHei".to_owned());
+        ctx.set_fonts(fonts);
+    }
+
+    console::set_repaint_context(ctx.clone());
+    settings.apply_appearance(ctx);
+}
+
+/// Shows the project picker until a project is open, then the editor.
+enum Launcher {
+    Picking { settings: Box<settings::Settings>, picker: ui::project_picker::ProjectPickerState },
+    Running(Box<AxiomApp>),
+}
+
+impl Launcher {
+    fn open(&mut self, ctx: &egui::Context, settings: settings::Settings, root: &std::path::Path) {
+        match project::Project::open(root) {
+            Ok(project) => {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(format!("{} - Bevy AI Editor", project.name())));
+                *self = Launcher::Running(Box::new(AxiomApp::new(ctx, settings, project)));
+            }
+            Err(e) => {
+                tracing::warn!("{:#}", e);
+                let mut picker = ui::project_picker::ProjectPickerState::new(std::env::current_dir().unwrap_or_default());
+                picker.error = Some(format!("{:#}", e));
+                *self = Launcher::Picking { settings: Box::new(settings), picker };
+            }
+        }
+    }
+}
+
+impl eframe::App for Launcher {
+    fn on_exit(&mut self, gl: Option<&eframe::glow::Context>) {
+        if let Launcher::Running(app) = self {
+            app.on_exit(gl);
+        }
+        if let Err(e) = metrics::save_session() {
+            tracing::warn!("Failed to save metrics: {:#}", e);
+        }
+    }
+
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        match self {
+            Launcher::Picking { settings, picker } => {
+                if let Some(root) = ui::project_picker::render_project_picker(ctx, picker) {
+                    let settings = (**settings).clone();
+                    self.open(ctx, settings, &root);
+                }
+            }
+            Launcher::Running(app) => {
+                app.update(ctx, frame);
+                if app.open_project_requested {
+                    if app.generation.is_some() {
+                        app.stop_generation();
+                    }
+                    app.stop_game();
+                    app.save_session();
+                    let picker = ui::project_picker::ProjectPickerState::new(app.project.root.clone());
+                    *self = Launcher::Picking { settings: Box::new(app.settings.clone()), picker };
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Title("Bevy AI Editor".to_string()));
+                }
+            }
+        }
     }
 }
 
 fn main() -> eframe::Result<()> {
+    // Saved settings win over .env, which only fills what they leave unset
+    let settings = settings::Settings::load();
+    if let Ok(settings) = &settings {
+        settings.apply_env();
+    }
+    // Before logging starts, so AXIOM_LOG can come from .env
+    dotenv::dotenv().ok();
+    console::init();
+
+    let settings = settings.unwrap_or_else(|e| {
+        tracing::warn!("{:#}, using defaults", e);
+        settings::Settings::default()
+    });
+    metrics::init(settings.metrics_enabled);
+    // A folder on the command line, else the configured startup project,
+    // else the picker
+    let startup_project = std::env::args()
+        .nth(1)
+        .or_else(|| Some(settings.workspace_root.trim().to_string()).filter(|root| !root.is_empty()))
+        .map(std::path::PathBuf::from);
+
     let base_url = std::env::var("GEMINI_BASE_URL").unwrap_or_else(|_| "http://127.0.0.1:8045".to_string());
     
     if !base_url.contains("127.0.0.1") && !base_url.contains("localhost") {
         if std::env::var("HTTPS_PROXY").is_err() && std::env::var("https_proxy").is_err() {
-            println!("Warning: Connecting to non-localhost endpoint without proxy. Set HTTPS_PROXY in .env if needed.");
+            tracing::warn!("Connecting to non-localhost endpoint without proxy. Set HTTPS_PROXY in .env if needed.");
         }
     } else {
-        println!("Targeting localhost ({}). Skipping default proxy setup.", base_url);
+        tracing::info!("Targeting localhost ({}). Skipping default proxy setup.", base_url);
     }
 
     let options = eframe::NativeOptions {
@@ -765,6 +2182,17 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Bevy AI Editor",
         options,
-        Box::new(|cc| Ok(Box::new(AxiomApp::new(cc)))),
+        Box::new(move |cc| {
+            setup_context(&cc.egui_ctx, &settings);
+            let launch_dir = std::env::current_dir().unwrap_or_default();
+            let mut launcher = Launcher::Picking {
+                settings: Box::new(settings.clone()),
+                picker: ui::project_picker::ProjectPickerState::new(launch_dir),
+            };
+            if let Some(root) = startup_project {
+                launcher.open(&cc.egui_ctx, settings, &root);
+            }
+            Ok(Box::new(launcher))
+        }),
     )
 }
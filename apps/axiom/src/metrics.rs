@@ -0,0 +1,61 @@
+//! The editor's side of the opt-in metrics in `axiom_agent_core::metrics`.
+//!
+//! BRP requests are fed in through the bridge's observer. Each session's
+//! final snapshot is appended to `metrics.jsonl` in the user config
+//! directory, so the dashboard can compare against earlier sessions.
+
+use anyhow::{Context, Result};
+use axiom_agent_core::metrics::{self, Snapshot};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Sessions kept in the history file.
+const MAX_SESSIONS: usize = 50;
+
+/// Start recording if `enabled`, and route BRP timings into the registry.
+pub fn init(enabled: bool) {
+    bevy_bridge_core::client::set_rpc_observer(|method, elapsed, ok| {
+        metrics::record(metrics::BRP, method, elapsed, ok);
+    });
+    metrics::set_enabled(enabled);
+}
+
+pub fn history_path() -> PathBuf {
+    axiom_config::config_dir().join("metrics.jsonl")
+}
+
+/// Earlier sessions, oldest first; unreadable lines are skipped.
+pub fn load_history() -> Vec<Snapshot> {
+    std::fs::read_to_string(history_path())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+/// Append this session to the history, dropping the oldest beyond
+/// [`MAX_SESSIONS`]. Does nothing while recording is off.
+pub fn save_session() -> Result<()> {
+    if !metrics::is_enabled() {
+        return Ok(());
+    }
+    let mut sessions = load_history();
+    sessions.push(metrics::snapshot());
+    let skip = sessions.len().saturating_sub(MAX_SESSIONS);
+
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let mut file = std::fs::File::create(&path).with_context(|| format!("Failed to write {}", path.display()))?;
+    for session in &sessions[skip..] {
+        writeln!(file, "{}", serde_json::to_string(session)?)?;
+    }
+    Ok(())
+}
+
+/// Write the current snapshot as pretty JSON.
+pub fn export(path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(&metrics::snapshot())?;
+    std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -0,0 +1,94 @@
+//! Avatars and name colors for chat roles.
+//!
+//! Agent profiles come first. Other roles (the user, system messages,
+//! characters an agent plays) are matched against personas: the built-in
+//! list in `resources/personas.json`, extended or overridden by
+//! `personas.json` next to the settings file. A persona matches a role that
+//! contains its name or one of its aliases, ignoring case.
+
+use crate::agent::AgentProfile;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const BUILTIN: &str = include_str!("../resources/personas.json");
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Persona {
+    pub name: String,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// File name under `assets/avatars`.
+    #[serde(default)]
+    pub avatar: Option<String>,
+    #[serde(default)]
+    pub color: Option<[u8; 3]>,
+}
+
+impl Persona {
+    fn matches(&self, role: &str) -> bool {
+        let role = role.to_lowercase();
+        std::iter::once(&self.name)
+            .chain(&self.aliases)
+            .any(|name| !name.is_empty() && role.contains(&name.to_lowercase()))
+    }
+}
+
+#[derive(Default)]
+pub struct PersonaRegistry {
+    personas: Vec<Persona>,
+}
+
+impl PersonaRegistry {
+    pub fn path() -> PathBuf {
+        crate::settings::Settings::path().with_file_name("personas.json")
+    }
+
+    /// The built-in personas plus the user's file; a user entry replaces the
+    /// built-in one with the same name.
+    pub fn load() -> Self {
+        let mut personas: Vec<Persona> = serde_json::from_str(BUILTIN).expect("built-in personas.json is valid");
+        match Self::load_user() {
+            Ok(user) => {
+                for persona in user {
+                    personas.retain(|p| p.name != persona.name);
+                    // Checked first, so user personas win over broader built-in matches
+                    personas.insert(0, persona);
+                }
+            }
+            Err(e) => tracing::warn!("Ignoring personas file: {:#}", e),
+        }
+        Self { personas }
+    }
+
+    fn load_user() -> Result<Vec<Persona>> {
+        let path = Self::path();
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        serde_json::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn find(&self, role: &str) -> Option<&Persona> {
+        self.personas.iter().find(|p| p.matches(role))
+    }
+
+    /// Avatar file name for `role`, falling back to `<role>.png`.
+    pub fn avatar(&self, role: &str, profiles: &[AgentProfile]) -> String {
+        if let Some(profile) = profiles.iter().find(|p| p.name == role) {
+            return profile.avatar_path.clone();
+        }
+        self.find(role)
+            .and_then(|p| p.avatar.clone())
+            .unwrap_or_else(|| format!("{}.png", role))
+    }
+
+    pub fn color(&self, role: &str, profiles: &[AgentProfile]) -> Option<[u8; 3]> {
+        match profiles.iter().find(|p| p.name == role) {
+            Some(profile) => profile.color,
+            None => self.find(role).and_then(|p| p.color),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! The project the editor works on.
+//!
+//! A project is a directory. While it is open it is also the process working
+//! directory, since tools resolve relative paths against it. Its settings come
+//! from `axiom.toml` in the root layered over the global one (see
+//! [`axiom_config`]), tool plugins from `.axiom/plugins/`, and the recently
+//! opened projects are kept in `recent_projects.json` beside the settings.
+
+use anyhow::{bail, Context, Result};
+use axiom_config::AxiomConfig;
+use std::path::{Path, PathBuf};
+
+/// Recent projects kept for the picker.
+const MAX_RECENT: usize = 10;
+
+pub struct Project {
+    pub root: PathBuf,
+    pub config: AxiomConfig,
+}
+
+impl Project {
+    /// [`Self::load`] and remember `root` as recent.
+    pub fn open(root: &Path) -> Result<Self> {
+        let project = Self::load(root)?;
+        if let Err(e) = remember(&project.root) {
+            tracing::warn!("Failed to update recent projects: {:#}", e);
+        }
+        Ok(project)
+    }
+
+    /// Enter `root` and load its config and plugins.
+    pub fn load(root: &Path) -> Result<Self> {
+        if !root.is_dir() {
+            bail!("{} is not a folder", root.display());
+        }
+        let root = root.canonicalize().with_context(|| format!("Failed to open {}", root.display()))?;
+        std::env::set_current_dir(&root).with_context(|| format!("Failed to open {}", root.display()))?;
+        let config = AxiomConfig::load(Some(&root))?;
+
+        let project = Self { root, config };
+        project.apply_env();
+        crate::tools::set_plugins(axiom_agent_core::plugin::discover(&project.plugin_dirs()));
+        tracing::info!("Opened project {}", project.root.display());
+        Ok(project)
+    }
+
+    pub fn name(&self) -> String {
+        self.root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.root.display().to_string())
+    }
+
+    /// Global plugins first, so the project's can't shadow them.
+    pub fn plugin_dirs(&self) -> Vec<PathBuf> {
+        vec![
+            axiom_config::config_dir().join("plugins"),
+            self.root.join(".axiom").join("plugins"),
+        ]
+    }
+
+    pub fn asset_dirs(&self) -> Vec<PathBuf> {
+        self.config.project.asset_dirs.iter().map(|dir| self.root.join(dir)).collect()
+    }
+
+    /// Export the config for what the settings leave empty; call again after
+    /// the settings re-export theirs.
+    pub fn apply_env(&self) {
+        self.config.apply_env();
+        crate::tools::set_permissions(self.config.tools.clone());
+    }
+}
+
+fn recent_path() -> PathBuf {
+    axiom_config::config_dir().join("recent_projects.json")
+}
+
+/// Most recently opened first; folders that no longer exist are skipped.
+pub fn recent() -> Vec<PathBuf> {
+    let Ok(data) = std::fs::read_to_string(recent_path()) else {
+        return Vec::new();
+    };
+    let paths: Vec<PathBuf> = serde_json::from_str(&data).unwrap_or_default();
+    paths.into_iter().filter(|p| p.is_dir()).collect()
+}
+
+fn remember(root: &Path) -> Result<()> {
+    let mut paths = recent();
+    paths.retain(|p| p != root);
+    paths.insert(0, root.to_path_buf());
+    paths.truncate(MAX_RECENT);
+
+    let path = recent_path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, serde_json::to_vec_pretty(&paths)?).with_context(|| format!("Failed to write {}", path.display()))
+}
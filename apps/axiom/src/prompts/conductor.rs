@@ -33,3 +33,37 @@ You MUST output a valid JSON block describing the plan. Do not write code yourse
 4. Keep the plan concise (max 5-7 steps).
 5. **PROJECT ROOT**: If the user asks for a NEW project (e.g. "Create Cyber-Ecom"), you MUST create a dedicated subdirectory for it (e.g. `cyber-ecom/`). Do NOT write files directly to the root workspace. All file paths in your plan MUST start with this project directory (e.g. `cyber-ecom/backend/src/main.rs`).
 "#;
+
+/// Schema the Conductor's plan is requested and validated against.
+pub fn plan_schema() -> serde_json::Value {
+    serde_json::json!({
+        "type": "object",
+        "properties": {
+            "goal": { "type": "string" },
+            "steps": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "id": { "type": "string" },
+                        "agent_role": { "type": "string" },
+                        "description": { "type": "string" },
+                        "tools": {
+                            "type": "array",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "tool": { "type": "string" },
+                                    "parameters": { "type": "object" }
+                                },
+                                "required": ["tool", "parameters"]
+                            }
+                        }
+                    },
+                    "required": ["id", "agent_role", "description", "tools"]
+                }
+            }
+        },
+        "required": ["goal", "steps"]
+    })
+}
@@ -1,3 +1,6 @@
+/// Context modes a profile can pick, as offered in the profile editor.
+pub const CONTEXT_MODES: &[&str] = &["General", "Bevy 0.18 (Future)", "Bevy Editor", "Pokemon Gen9", "Gym Leaders"];
+
 pub fn get_context_prompt(context_name: &str) -> &'static str {
     match context_name {
         "Bevy 0.18 (Future)" => {
@@ -1,8 +1,14 @@
 pub mod conductor;
 pub mod contexts;
+pub mod template;
+
+use std::path::Path;
 
 pub const SYSTEM_BEAST: &str = include_str!("system_beast.md");
 
+/// Research modes understood by `get_system_prompt`.
+pub const RESEARCH_MODES: &[&str] = &["Fast", "Smart Hybrid", "Deep Research"];
+
 pub fn get_system_prompt(research_mode: &str, context_mode: &str, profile_prompt: &str) -> String {
     let base_prompt = SYSTEM_BEAST;
     let context_prompt = contexts::get_context_prompt(context_mode);
@@ -47,3 +53,25 @@ pub fn get_system_prompt(research_mode: &str, context_mode: &str, profile_prompt
         base_prompt, mode_instruction, context_prompt, role_instruction
     )
 }
+
+/// `base` plus the working directory, the asset and road-building rules and
+/// the live environment, rendered for `root`. `{{game_status}}` is left for
+/// `template::resolve_game_status` since it needs a round-trip to the game.
+pub fn agent_prompt(base: &str, root: &Path) -> String {
+    let mut system_prompt = base.to_string();
+
+    // --- Working Directory Injection ---
+    let cwd = root.display().to_string();
+    system_prompt.push_str(&format!("\n\nCurrent Working Directory: {}\nIMPORTANT: All file operations (read/write/run) should be relative to this directory unless absolute path is specified.", cwd));
+    system_prompt.push_str("\n\n**BEVY ASSET RULE**: When generating assets (images, models, etc.) for Bevy, you MUST write them to the `assets/` subdirectory within the working directory. When spawning these assets via `bevy_spawn_scene` or `bevy_spawn`, use the path RELATIVE to the `assets/` folder (e.g., if you wrote 'assets/models/cube.glb', the spawn path is 'models/cube.glb').");
+    system_prompt.push_str("\n\n**CRITICAL: BINARY ASSET HANDLING**\nIf the user asks to spawn or use a specific local file (like a .glb, .png, etc.) that is provided in the Context (marked as [BINARY ASSET AVAILABLE]), you **MUST NOT** use `bevy_spawn_primitive` or `bevy_spawn_scene`. \n\nINSTEAD, you **MUST** use the `bevy_upload_asset` tool.\n- `local_path`: Use the absolute path provided in the context (usually in `apps/axiom/resources/...`).\n- `translation`: Use the user's requested position.\n\nExample: User says 'spawn this glb', and context shows `D:/.../dragon.glb`. Call `bevy_upload_asset(local_path='D:/.../dragon.glb', translation=[0,0,0])`. Do NOT try to read the file content or simulate it.");
+
+    // Inject Road Engineering Rules
+    system_prompt.push_str("\n\n");
+    system_prompt.push_str(include_str!("road_engineer.md"));
+
+    // --- Live Editor State ---
+    system_prompt.push_str("\n\n");
+    system_prompt.push_str(template::ENVIRONMENT);
+    template::PromptVars::collect(root).render(&system_prompt)
+}
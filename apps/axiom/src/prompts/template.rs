@@ -0,0 +1,206 @@
+//! `{{variable}}` substitution for prompts.
+//!
+//! Variables describe live editor state (project, OS, workspace layout, Bevy
+//! version, game connection) and are filled in when a request is built, so
+//! both the built-in prompts and profile prompts can refer to them.
+//! Placeholders with no value are left untouched, which also lets a prompt be
+//! rendered in stages (`game_status` is only known once the game was pinged).
+
+use crate::llm::{Message, MessageContent};
+use bevy_bridge_core::{ops, BrpClient, BrpConfig};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+pub const ENVIRONMENT: &str = include_str!("environment.md");
+
+/// Directories never worth showing the model.
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "dist", "build"];
+const TREE_MAX_ENTRIES: usize = 60;
+
+#[derive(Debug, Clone, Default)]
+pub struct PromptVars {
+    vars: HashMap<String, String>,
+}
+
+impl PromptVars {
+    /// Everything that can be read from disk without blocking on the network.
+    pub fn collect(workspace: &Path) -> Self {
+        let mut vars = Self::default();
+        vars.set("project_name", project_name(workspace));
+        vars.set("workspace_root", workspace.display().to_string());
+        vars.set("os", format!("{} ({})", std::env::consts::OS, std::env::consts::ARCH));
+        vars.set("bevy_version", bevy_version(workspace).unwrap_or_else(|| "unknown".to_string()));
+        vars.set("workspace_tree", workspace_tree(workspace));
+        vars.set("todos", todo_summary());
+        vars
+    }
+
+    pub fn set(&mut self, name: &str, value: impl Into<String>) {
+        self.vars.insert(name.to_string(), value.into());
+    }
+
+    /// Replace every `{{name}}` (whitespace inside the braces allowed) that has a value.
+    pub fn render(&self, template: &str) -> String {
+        let mut out = String::with_capacity(template.len());
+        let mut rest = template;
+
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let value = after
+                .find("}}")
+                .and_then(|end| self.vars.get(after[..end].trim()).map(|v| (end, v)));
+
+            match value {
+                Some((end, value)) => {
+                    out.push_str(value);
+                    rest = &after[end + 2..];
+                }
+                None => {
+                    out.push_str("{{");
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+/// `[package] name` from the workspace's Cargo.toml, else the directory name.
+fn project_name(workspace: &Path) -> String {
+    let from_manifest = std::fs::read_to_string(workspace.join("Cargo.toml"))
+        .ok()
+        .and_then(|manifest| {
+            let package = manifest.split("[package]").nth(1)?;
+            let line = package.lines().find(|l| l.trim_start().starts_with("name"))?;
+            Some(line.split('=').nth(1)?.trim().trim_matches('"').to_string())
+        });
+
+    from_manifest.unwrap_or_else(|| {
+        workspace
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "unknown".to_string())
+    })
+}
+
+/// The resolved version from Cargo.lock, else the requirement in Cargo.toml.
+fn bevy_version(workspace: &Path) -> Option<String> {
+    if let Ok(lock) = std::fs::read_to_string(workspace.join("Cargo.lock")) {
+        let mut lines = lock.lines();
+        while let Some(line) = lines.next() {
+            if line.trim() == r#"name = "bevy""# {
+                let version = lines.next()?.trim().strip_prefix("version = ")?;
+                return Some(version.trim_matches('"').to_string());
+            }
+        }
+    }
+
+    let manifest = std::fs::read_to_string(workspace.join("Cargo.toml")).ok()?;
+    let line = manifest.lines().find(|l| {
+        let l = l.trim_start();
+        l.starts_with("bevy ") || l.starts_with("bevy=")
+    })?;
+    let value = line.split_once('=')?.1;
+    // `bevy = "0.15"` or `bevy = { version = "0.15", ... }`
+    let version = match value.find("version") {
+        Some(i) => value[i..].split('"').nth(1)?,
+        None => value.split('"').nth(1)?,
+    };
+    Some(version.to_string())
+}
+
+/// Two levels of the workspace, directories first, hidden entries skipped.
+fn workspace_tree(workspace: &Path) -> String {
+    let mut lines = Vec::new();
+    list_dir(workspace, 0, &mut lines);
+    if lines.len() > TREE_MAX_ENTRIES {
+        let hidden = lines.len() - TREE_MAX_ENTRIES;
+        lines.truncate(TREE_MAX_ENTRIES);
+        lines.push(format!("... ({} more)", hidden));
+    }
+    if lines.is_empty() {
+        "(empty)".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn list_dir(dir: &Path, depth: usize, lines: &mut Vec<String>) {
+    let Ok(read) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = read
+        .flatten()
+        .filter(|e| !e.file_name().to_string_lossy().starts_with('.'))
+        .map(|e| (e.path().is_dir(), e.file_name().to_string_lossy().to_string(), e.path()))
+        .collect();
+    entries.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    for (is_dir, name, path) in entries {
+        let indent = "  ".repeat(depth);
+        if is_dir {
+            lines.push(format!("{}{}/", indent, name));
+            if depth == 0 && !SKIP_DIRS.contains(&name.as_str()) {
+                list_dir(&path, depth + 1, lines);
+            }
+        } else {
+            lines.push(format!("{}{}", indent, name));
+        }
+    }
+}
+
+/// The shared todo list, one line per item.
+fn todo_summary() -> String {
+    match crate::tools::todo::load() {
+        Ok(todos) if todos.is_empty() => "(empty)".to_string(),
+        Ok(todos) => todos
+            .iter()
+            .map(|t| format!("- [{}] {} ({}, {}, id {})", if t.is_active() { " " } else { "x" }, t.content, t.status, t.priority, t.id))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(e) => format!("(unreadable: {})", e),
+    }
+}
+
+/// Whether a game with the remote plugin answers on the BRP endpoint.
+pub async fn game_status() -> String {
+    let config = BrpConfig { timeout: Duration::from_millis(500), ..BrpConfig::from_env() };
+    let endpoint = config.endpoint.clone();
+    let client = BrpClient::new(config);
+
+    match ops::ping::ping(&client).await {
+        Ok(ping) => {
+            let versions: Vec<String> = [("Bevy", ping.bevy_version), ("bevy_ai_remote", ping.plugin_version)]
+                .into_iter()
+                .filter_map(|(name, version)| Some(format!("{} {}", name, version?)))
+                .collect();
+            if versions.is_empty() {
+                format!("connected ({})", endpoint)
+            } else {
+                format!("connected ({}, {})", endpoint, versions.join(", "))
+            }
+        }
+        Err(_) => format!("not running (nothing answering on {})", endpoint),
+    }
+}
+
+/// Fill in the `game_status` placeholder left in the system messages.
+pub async fn resolve_game_status(messages: &mut [Message]) {
+    let pending = messages
+        .iter()
+        .any(|m| m.role == "system" && m.text().contains("{{game_status}}"));
+    if !pending {
+        return;
+    }
+
+    let mut vars = PromptVars::default();
+    vars.set("game_status", game_status().await);
+    for message in messages.iter_mut().filter(|m| m.role == "system") {
+        if let Some(MessageContent::Text(text)) = &mut message.content {
+            *text = vars.render(text);
+        }
+    }
+}
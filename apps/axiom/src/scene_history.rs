@@ -0,0 +1,56 @@
+//! Named snapshots of what the editor spawned in the game, one JSON file
+//! each in `<project>/.axiom/scenes/`, to compare and restore later.
+
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+pub use bevy_bridge_core::types::SceneSnapshot;
+
+pub fn dir(root: &Path) -> PathBuf {
+    root.join(".axiom").join("scenes")
+}
+
+/// File for a snapshot name; names differing only in punctuation share it.
+fn path(root: &Path, name: &str) -> PathBuf {
+    let stem: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    dir(root).join(format!("{}.json", stem))
+}
+
+/// Saved snapshots, newest first. Unreadable files are skipped.
+pub fn list(root: &Path) -> Vec<SceneSnapshot> {
+    let Ok(entries) = std::fs::read_dir(dir(root)) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<SceneSnapshot> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let data = std::fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&data)
+                .inspect_err(|e| tracing::warn!("Failed to parse {}: {}", path.display(), e))
+                .ok()
+        })
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created));
+    snapshots
+}
+
+/// Write `snapshot`, replacing one saved under the same name.
+pub fn save(root: &Path, snapshot: &SceneSnapshot) -> Result<()> {
+    let path = path(root, &snapshot.name);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, serde_json::to_vec_pretty(snapshot)?)
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+pub fn delete(root: &Path, name: &str) -> Result<()> {
+    let path = path(root, name);
+    std::fs::remove_file(&path).with_context(|| format!("Failed to delete {}", path.display()))
+}
@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Lines per chunk. Small enough that a hit points at one function or impl block.
+const CHUNK_LINES: usize = 60;
+/// Lines shared between consecutive chunks so definitions aren't cut in half.
+const CHUNK_OVERLAP: usize = 10;
+/// Files larger than this are skipped (generated code, lockfiles, minified assets).
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+const INDEXED_EXTENSIONS: &[&str] = &[
+    "rs", "toml", "md", "wgsl", "ron", "json", "py", "js", "ts", "glsl", "txt",
+];
+
+#[derive(Debug, Clone)]
+pub struct Chunk {
+    pub path: String,
+    pub start_line: usize, // 1-based, inclusive
+    pub end_line: usize,   // 1-based, inclusive
+    pub text: String,
+}
+
+/// Split a file's content into overlapping line windows.
+pub fn chunk_file(path: &str, content: &str) -> Vec<Chunk> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut chunks = Vec::new();
+    if lines.is_empty() {
+        return chunks;
+    }
+
+    let step = CHUNK_LINES - CHUNK_OVERLAP;
+    let mut start = 0;
+    loop {
+        let end = (start + CHUNK_LINES).min(lines.len());
+        let text = lines[start..end].join("\n");
+        if !text.trim().is_empty() {
+            chunks.push(Chunk {
+                path: path.to_string(),
+                start_line: start + 1,
+                end_line: end,
+                text,
+            });
+        }
+        if end == lines.len() {
+            break;
+        }
+        start += step;
+    }
+
+    chunks
+}
+
+/// Recursively collect indexable source files, skipping hidden dirs and `target`.
+pub fn collect_source_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    collect_into(root, &mut files);
+    files.sort();
+    files
+}
+
+fn collect_into(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name == "target" || name == "node_modules" {
+            continue;
+        }
+
+        if path.is_dir() {
+            collect_into(&path, files);
+        } else if is_indexable(&path) {
+            files.push(path);
+        }
+    }
+}
+
+fn is_indexable(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    if !INDEXED_EXTENSIONS.contains(&extension.as_str()) {
+        return false;
+    }
+    fs::metadata(path)
+        .map(|m| m.len() <= MAX_FILE_BYTES)
+        .unwrap_or(false)
+}
@@ -0,0 +1,168 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use super::chunker::{chunk_file, collect_source_files};
+use crate::llm::embeddings::EmbeddingClient;
+use crate::vector_store::VectorStore;
+
+const INDEX_DIR: &str = ".axiom";
+const INDEX_FILE: &str = "semantic_index.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FileStamp {
+    modified: u64,
+    len: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ChunkMeta {
+    path: String,
+    start_line: usize,
+    end_line: usize,
+    text: String,
+}
+
+/// Vector index for one workspace root, plus the file stamps used to detect
+/// which files need re-embedding.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CodeIndex {
+    files: HashMap<String, FileStamp>,
+    store: VectorStore<ChunkMeta>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub text: String,
+}
+
+impl CodeIndex {
+    pub fn index_path(root: &Path) -> PathBuf {
+        root.join(INDEX_DIR).join(INDEX_FILE)
+    }
+
+    /// Load the index for `root`, or an empty one if none exists (or it was built
+    /// with a different embedding model, whose vectors aren't comparable).
+    pub fn load(root: &Path, model: &str) -> Self {
+        let path = Self::index_path(root);
+        let loaded = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<CodeIndex>(&s).ok());
+
+        match loaded {
+            Some(index) if index.store.model() == model => index,
+            _ => CodeIndex {
+                files: HashMap::new(),
+                store: VectorStore::new(model),
+            },
+        }
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::index_path(root);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&path, serde_json::to_string(self)?)
+            .map_err(|e| anyhow!("Failed to write index {:?}: {}", path, e))
+    }
+
+    /// Re-embed files that changed since the last run and drop deleted ones.
+    /// Returns the number of files (re)indexed.
+    pub fn update(&mut self, root: &Path, client: &EmbeddingClient) -> Result<usize> {
+        let files = collect_source_files(root);
+        let mut current: HashMap<String, FileStamp> = HashMap::new();
+        let mut changed: Vec<(String, PathBuf)> = Vec::new();
+
+        for file in files {
+            let rel = file
+                .strip_prefix(root)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let Ok(meta) = fs::metadata(&file) else {
+                continue;
+            };
+            let stamp = FileStamp {
+                modified: meta
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                len: meta.len(),
+            };
+
+            let unchanged = self
+                .files
+                .get(&rel)
+                .is_some_and(|old| old.modified == stamp.modified && old.len == stamp.len);
+            if !unchanged {
+                changed.push((rel.clone(), file));
+            }
+            current.insert(rel, stamp);
+        }
+
+        // Drop entries for deleted or changed files; changed ones are re-added below.
+        let changed_set: std::collections::HashSet<&String> =
+            changed.iter().map(|(rel, _)| rel).collect();
+        self.store
+            .retain(|m| current.contains_key(&m.path) && !changed_set.contains(&m.path));
+
+        let mut chunks = Vec::new();
+        for (rel, file) in &changed {
+            if let Ok(content) = fs::read_to_string(file) {
+                chunks.extend(chunk_file(rel, &content));
+            }
+        }
+
+        if !chunks.is_empty() {
+            tracing::info!("Embedding {} chunks from {} files", chunks.len(), changed.len());
+            let texts: Vec<String> = chunks
+                .iter()
+                .map(|c| format!("{}:{}-{}\n{}", c.path, c.start_line, c.end_line, c.text))
+                .collect();
+            let vectors = client.embed(&texts)?;
+
+            for (chunk, vector) in chunks.into_iter().zip(vectors) {
+                self.store.push(
+                    vector,
+                    ChunkMeta {
+                        path: chunk.path,
+                        start_line: chunk.start_line,
+                        end_line: chunk.end_line,
+                        text: chunk.text,
+                    },
+                );
+            }
+        }
+
+        self.files = current;
+        Ok(changed.len())
+    }
+
+    pub fn chunk_count(&self) -> usize {
+        self.store.len()
+    }
+
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<SearchHit> {
+        self.store
+            .search(query, top_k)
+            .into_iter()
+            .map(|(score, m)| SearchHit {
+                path: m.path.clone(),
+                start_line: m.start_line,
+                end_line: m.end_line,
+                score,
+                text: m.text.clone(),
+            })
+            .collect()
+    }
+}
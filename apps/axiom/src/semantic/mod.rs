@@ -0,0 +1,11 @@
+//! Embedding-backed code index.
+//!
+//! Source files under a workspace root are split into overlapping line chunks,
+//! embedded through the configured provider (`llm::embeddings`) and kept in a
+//! `VectorStore` under `.axiom/semantic_index.json`. The `semantic_search`
+//! tool embeds the query and ranks chunks by cosine similarity.
+
+pub mod chunker;
+pub mod index;
+
+pub use index::CodeIndex;
@@ -0,0 +1,138 @@
+//! Per-project session persistence.
+//!
+//! Channels (with their full chat history, including tool-call log entries
+//! and pasted images), agent profiles (as edited in the profile editor) and
+//! the current selection are written to `<project>/.axiom/session.json`,
+//! where the project is the directory Axiom was launched from, and restored
+//! on the next start.
+//!
+//! The session is also autosaved while a generation runs, together with what
+//! was in flight (`InFlight`). A clean stop clears that, so finding it on
+//! load means the editor crashed or was killed mid-task.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::types::{AgentProfile, ChannelState};
+
+const SESSION_VERSION: u32 = 1;
+
+/// A generation that was running when the session was saved.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct InFlight {
+    pub channel_id: String,
+    pub profile: String,
+    /// Tool calls started but not finished.
+    pub tools: Vec<String>,
+    /// Files with edits waiting for approval.
+    pub pending_edits: Vec<String>,
+}
+
+impl InFlight {
+    /// Chat note explaining what a crash interrupted.
+    pub fn recovery_note(&self) -> String {
+        let mut note = format!("⚠ The editor closed unexpectedly while {} was working.", self.profile);
+        if !self.tools.is_empty() {
+            note.push_str(&format!(" Interrupted tool calls: {}.", self.tools.join(", ")));
+        }
+        if !self.pending_edits.is_empty() {
+            note.push_str(&format!(" Edits not applied: {}.", self.pending_edits.join(", ")));
+        }
+        note
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    pub active_channel_id: String,
+    pub current_profile: String,
+    pub profiles: Vec<AgentProfile>,
+    /// Built-in profiles the user deleted, so they aren't merged back in.
+    #[serde(default)]
+    pub removed_profiles: Vec<String>,
+    pub channels: HashMap<String, ChannelState>,
+    #[serde(default)]
+    pub in_flight: Option<InFlight>,
+}
+
+impl Session {
+    pub fn new(
+        active_channel_id: &str,
+        current_profile: &AgentProfile,
+        profiles: &[AgentProfile],
+        removed_profiles: &[String],
+        channels: &HashMap<String, ChannelState>,
+        in_flight: Option<InFlight>,
+    ) -> Self {
+        Self {
+            version: SESSION_VERSION,
+            active_channel_id: active_channel_id.to_string(),
+            current_profile: current_profile.name.clone(),
+            profiles: profiles.to_vec(),
+            removed_profiles: removed_profiles.to_vec(),
+            channels: channels.clone(),
+            in_flight,
+        }
+    }
+
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(".axiom").join("session.json")
+    }
+
+    /// `None` if there is no saved session or it can't be read; a broken
+    /// session file should never keep the editor from starting.
+    pub fn load(root: &Path) -> Option<Self> {
+        let path = Self::path(root);
+        let data = std::fs::read_to_string(&path).ok()?;
+        match serde_json::from_str::<Session>(&data) {
+            Ok(session) if session.version == SESSION_VERSION => Some(session),
+            Ok(session) => {
+                tracing::warn!("Ignoring {} (version {})", path.display(), session.version);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("Failed to parse {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, root: &Path) -> Result<()> {
+        let path = Self::path(root);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        // Write then rename so a crash mid-write can't truncate the session
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Merge freshly built profiles (defaults, discovered local models) into
+    /// the saved ones. Saved profiles win; new ones are appended and assigned
+    /// to the global channel like they would be on a fresh start. Profiles
+    /// the user deleted stay deleted.
+    pub fn merge_profiles(&mut self, fresh: Vec<AgentProfile>, global_agents: &[String]) {
+        for profile in fresh {
+            if self.profiles.iter().any(|p| p.name == profile.name) || self.removed_profiles.contains(&profile.name) {
+                continue;
+            }
+            if global_agents.contains(&profile.name) {
+                if let Some(global) = self.channels.get_mut("global") {
+                    if !global.assigned_agents.contains(&profile.name) {
+                        global.assigned_agents.push(profile.name.clone());
+                    }
+                }
+            }
+            self.profiles.push(profile);
+        }
+    }
+}
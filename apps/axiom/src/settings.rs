@@ -0,0 +1,216 @@
+//! Editor settings, persisted per user.
+//!
+//! Stored as `settings.json` in the user config directory (e.g.
+//! `~/.config/axiom/` on Linux) since it holds API keys. Connection settings
+//! are exported as the environment variables the providers and the BRP client
+//! already read, so a value set here takes precedence over `.env`, the shell
+//! and `axiom.toml`; empty fields leave the environment alone.
+
+use crate::commands::Command;
+use anyhow::{Context, Result};
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+    System,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::Dark, Theme::Light, Theme::System];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+            Theme::System => "Follow system",
+        }
+    }
+
+    /// As written in `axiom.toml`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "dark" => Some(Theme::Dark),
+            "light" => Some(Theme::Light),
+            "system" => Some(Theme::System),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, ctx: &egui::Context) {
+        ctx.set_theme(match self {
+            Theme::Dark => egui::ThemePreference::Dark,
+            Theme::Light => egui::ThemePreference::Light,
+            Theme::System => egui::ThemePreference::System,
+        });
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub gemini_api_key: String,
+    pub gemini_base_url: String,
+    pub anthropic_api_key: String,
+    pub anthropic_base_url: String,
+    pub google_api_key: String,
+    pub gemini_native_base_url: String,
+    pub ollama_base_url: String,
+    pub brp_endpoint: String,
+    pub brp_timeout_ms: String,
+    /// Shared secret for a game that requires one; see `bevy_ai_remote`.
+    pub brp_token: String,
+    pub retry_budget_secs: String,
+    /// Model for the default profile on a fresh start.
+    pub default_model: String,
+    /// Project opened at start; empty shows the project picker.
+    pub workspace_root: String,
+    pub theme: Theme,
+    /// Replaces the theme's selection and link color.
+    pub accent: Option<[u8; 3]>,
+    /// Name color of your own messages; falls back to the accent.
+    pub user_color: Option<[u8; 3]>,
+    /// Record tool, model and BRP timings for the metrics dashboard.
+    pub metrics_enabled: bool,
+    /// LLM spending in USD after which requests wait for confirmation.
+    pub session_budget_usd: Option<f64>,
+    pub daily_budget_usd: Option<f64>,
+    /// Shortcut overrides by command id; see [`crate::commands`].
+    pub shortcuts: BTreeMap<String, String>,
+}
+
+/// A setting exported as an environment variable.
+pub struct EnvField {
+    pub label: &'static str,
+    pub var: &'static str,
+    /// Masked in the settings panel.
+    pub secret: bool,
+    pub get: fn(&mut Settings) -> &mut String,
+}
+
+pub const API_KEYS: &[EnvField] = &[
+    EnvField { label: "OpenAI-compatible key", var: "GEMINI_API_KEY", secret: true, get: |s| &mut s.gemini_api_key },
+    EnvField { label: "Anthropic key", var: "ANTHROPIC_API_KEY", secret: true, get: |s| &mut s.anthropic_api_key },
+    EnvField { label: "Google key", var: "GOOGLE_API_KEY", secret: true, get: |s| &mut s.google_api_key },
+];
+
+pub const BASE_URLS: &[EnvField] = &[
+    EnvField { label: "OpenAI-compatible URL", var: "GEMINI_BASE_URL", secret: false, get: |s| &mut s.gemini_base_url },
+    EnvField { label: "Anthropic URL", var: "ANTHROPIC_BASE_URL", secret: false, get: |s| &mut s.anthropic_base_url },
+    EnvField { label: "Gemini URL", var: "GEMINI_NATIVE_BASE_URL", secret: false, get: |s| &mut s.gemini_native_base_url },
+    EnvField { label: "Ollama URL", var: "OLLAMA_BASE_URL", secret: false, get: |s| &mut s.ollama_base_url },
+];
+
+pub const BRP: &[EnvField] = &[
+    EnvField { label: "BRP endpoint", var: "BRP_ENDPOINT", secret: false, get: |s| &mut s.brp_endpoint },
+    EnvField { label: "BRP token", var: "BRP_TOKEN", secret: true, get: |s| &mut s.brp_token },
+];
+
+pub const TIMEOUTS: &[EnvField] = &[
+    EnvField { label: "BRP timeout (ms)", var: "BRP_TIMEOUT_MS", secret: false, get: |s| &mut s.brp_timeout_ms },
+    EnvField { label: "LLM retry budget (s)", var: "AXIOM_RETRY_BUDGET_SECS", secret: false, get: |s| &mut s.retry_budget_secs },
+];
+
+impl Settings {
+    pub fn path() -> PathBuf {
+        axiom_config::config_dir().join("settings.json")
+    }
+
+    /// Defaults from the global `axiom.toml` if there is no settings file yet.
+    pub fn load() -> Result<Self> {
+        let path = Self::path();
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(Self::from_config(&axiom_config::AxiomConfig::load(None)?))
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        serde_json::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    fn from_config(config: &axiom_config::AxiomConfig) -> Self {
+        Self {
+            default_model: config.llm.default_model.clone(),
+            theme: config.ui.theme.as_deref().and_then(Theme::from_name).unwrap_or_default(),
+            accent: config.ui.accent,
+            ..Self::default()
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        // API keys: keep the file private to the user
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = std::fs::set_permissions(&tmp, std::fs::Permissions::from_mode(0o600));
+        }
+        std::fs::rename(&tmp, &path)
+            .with_context(|| format!("Failed to replace {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Export the non-empty connection settings to the environment. Clients
+    /// created afterwards pick them up.
+    pub fn apply_env(&self) {
+        let mut settings = self.clone();
+        for field in API_KEYS.iter().chain(BASE_URLS).chain(BRP).chain(TIMEOUTS) {
+            let value = (field.get)(&mut settings).trim();
+            if !value.is_empty() {
+                std::env::set_var(field.var, value);
+            }
+        }
+    }
+
+    /// Apply the theme, and the accent color to both the light and dark style.
+    pub fn apply_appearance(&self, ctx: &egui::Context) {
+        self.theme.apply(ctx);
+        for (theme, defaults) in [(egui::Theme::Dark, egui::Visuals::dark()), (egui::Theme::Light, egui::Visuals::light())] {
+            ctx.style_mut_of(theme, |style| {
+                let visuals = &mut style.visuals;
+                visuals.selection.bg_fill = defaults.selection.bg_fill;
+                visuals.hyperlink_color = defaults.hyperlink_color;
+                visuals.widgets.hovered.bg_stroke.color = defaults.widgets.hovered.bg_stroke.color;
+                if let Some([r, g, b]) = self.accent {
+                    let accent = egui::Color32::from_rgb(r, g, b);
+                    visuals.selection.bg_fill = accent.gamma_multiply(0.6);
+                    visuals.hyperlink_color = accent;
+                    visuals.widgets.hovered.bg_stroke.color = accent;
+                }
+            });
+        }
+    }
+
+    pub fn user_color(&self) -> egui::Color32 {
+        match self.user_color.or(self.accent) {
+            Some([r, g, b]) => egui::Color32::from_rgb(r, g, b),
+            None => egui::Color32::LIGHT_BLUE,
+        }
+    }
+
+    pub fn budget(&self) -> axiom_agent_core::llm::usage::Budget {
+        axiom_agent_core::llm::usage::Budget { session_usd: self.session_budget_usd, daily_usd: self.daily_budget_usd }
+    }
+
+    /// The shortcut bound to `command`, falling back to its default.
+    pub fn shortcut(&self, command: Command) -> String {
+        self.shortcuts
+            .get(command.id())
+            .cloned()
+            .unwrap_or_else(|| command.default_shortcut().to_string())
+    }
+}
@@ -0,0 +1,34 @@
+//! The day's LLM spending, kept in `spending.json` in the user config
+//! directory so the daily budget holds across sessions and projects.
+
+use anyhow::{Context, Result};
+use axiom_agent_core::llm::usage::DailySpend;
+use std::path::PathBuf;
+
+pub fn path() -> PathBuf {
+    axiom_config::config_dir().join("spending.json")
+}
+
+/// Today's total so far; a fresh one if the file is missing or from an
+/// earlier day.
+pub fn load() -> DailySpend {
+    let Ok(data) = std::fs::read_to_string(path()) else {
+        return DailySpend::default();
+    };
+    match serde_json::from_str::<DailySpend>(&data) {
+        Ok(spend) if spend.date == chrono::Local::now().date_naive() => spend,
+        Ok(_) => DailySpend::default(),
+        Err(e) => {
+            tracing::warn!("Failed to parse {}: {}", path().display(), e);
+            DailySpend::default()
+        }
+    }
+}
+
+pub fn save(spend: &DailySpend) -> Result<()> {
+    let path = path();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    std::fs::write(&path, serde_json::to_vec(spend)?).with_context(|| format!("Failed to write {}", path.display()))
+}
@@ -8,11 +8,18 @@ use std::sync::{Arc, Mutex};
 
 pub struct BatchTool {
     tx: Sender<AsyncMessage>,
+    /// The profile's `allowed_tools`; batched calls can't reach past them.
+    allowed: Option<Vec<String>>,
 }
 
 impl BatchTool {
     pub fn new(tx: Sender<AsyncMessage>) -> Self {
-        Self { tx }
+        Self { tx, allowed: None }
+    }
+
+    pub fn with_allowed(mut self, allowed: Option<Vec<String>>) -> Self {
+        self.allowed = allowed;
+        self
     }
 }
 
@@ -63,7 +70,11 @@ impl Tool for BatchTool {
         let tx = self.tx.clone();
 
         // Wrap tools in Arc for sharing across threads
-        let available_tools = Arc::new(crate::tools::get_all_tools(tx.clone()));
+        let available_tools = Arc::new(match &self.allowed {
+            Some(allowed) => crate::tools::get_allowed_tools(allowed, tx.clone()),
+            None => crate::tools::get_all_tools(tx.clone()),
+        });
+        let reviewer = super::review::ChannelReviewer::new(tx.clone());
 
         // Use Arc<Mutex<Vec<_>>> to collect results thread-safely
         let results = Arc::new(Mutex::new(Vec::new()));
@@ -83,7 +94,7 @@ impl Tool for BatchTool {
 
                 let result_entry =
                     if let Some(tool) = available_tools.iter().find(|t| t.name() == tool_name) {
-                        match tool.execute(params) {
+                        match super::execute_reviewed(tool.as_ref(), params, Some(&reviewer)) {
                             Ok(output) => json!({
                                 "tool": tool_name,
                                 "status": "success",
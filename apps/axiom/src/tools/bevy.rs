@@ -1,18 +1,15 @@
 use crate::tools::Tool;
 use anyhow::{anyhow, Result};
-use bevy_bridge_core::{BrpClient, BrpConfig, ops};
+use bevy_bridge_core::types::{ClearTarget, PrimitiveMaterial, SpawnRequest, UploadOptions};
+use bevy_bridge_core::{BrpClientBlocking, BrpConfig, ops};
 use glam::Quat;
 use serde_json::{json, Value};
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
-use tokio::runtime::Runtime;
 
-const BEVY_RPC_URL: &str = "http://127.0.0.1:15721";
-
-fn get_bridge_client() -> Result<BrpClient> {
-    let config = BrpConfig::from_env();
-    Ok(BrpClient::new(config))
+fn get_bridge_client() -> Result<BrpClientBlocking> {
+    Ok(BrpClientBlocking::from_env())
 }
 
 /// Tool to upload a local file to Bevy via BRP and spawn it
@@ -24,7 +21,7 @@ impl Tool for BevyUploadAssetTool {
     }
 
     fn description(&self) -> String {
-        "Upload a local asset file (e.g., .glb) to Bevy and spawn it. Encodes file as Base64 and sends via 'AxiomRemoteAsset'.".to_string()
+        "Upload a local asset file (e.g., .glb) to Bevy and spawn it. Sends the file as Base64, in chunks when large, and skips the transfer when the game already has it.".to_string()
     }
 
     fn schema(&self) -> Value {
@@ -57,6 +54,14 @@ impl Tool for BevyUploadAssetTool {
                             "minItems": 3,
                             "maxItems": 3,
                             "description": "[x, y, z] rotation in Euler angles (Degrees). e.g. [0, 90, 0] to rotate 90 deg around Y axis."
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Optional name to find the entity by later (e.g. 'player_start')."
+                        },
+                        "group": {
+                            "type": "string",
+                            "description": "Optional group to clear the entity with later (e.g. 'level1')."
                         }
                     },
                     "required": ["local_path", "translation"]
@@ -67,7 +72,6 @@ impl Tool for BevyUploadAssetTool {
 
     fn execute(&self, args: Value) -> Result<String> {
         let client = get_bridge_client()?;
-        let rt = Runtime::new()?;
         
         let local_path = args
             .get("local_path")
@@ -78,6 +82,9 @@ impl Tool for BevyUploadAssetTool {
             .get("relative_path")
             .and_then(|v| v.as_str());
 
+        let name = args.get("name").and_then(|v| v.as_str());
+        let group = args.get("group").and_then(|v| v.as_str());
+
         let t = args
             .get("translation")
             .and_then(|v| v.as_array())
@@ -126,8 +133,8 @@ impl Tool for BevyUploadAssetTool {
                     .join(name);
 
                 if fallback_models.exists() {
-                    println!(
-                        "[BevyTool] Path not found, falling back to: {:?}",
+                    tracing::info!(
+                        "Path not found, falling back to: {:?}",
                         fallback_models
                     );
                     abs_path = fallback_models;
@@ -140,8 +147,8 @@ impl Tool for BevyUploadAssetTool {
                         .join(name);
 
                     if fallback_resources.exists() {
-                        println!(
-                            "[BevyTool] Path not found, falling back to: {:?}",
+                        tracing::info!(
+                            "Path not found, falling back to: {:?}",
                             fallback_resources
                         );
                         abs_path = fallback_resources;
@@ -161,25 +168,27 @@ impl Tool for BevyUploadAssetTool {
         let mut buffer = Vec::new();
         file.read_to_end(&mut buffer)?;
 
-        println!(
-            "[BevyTool] Uploading {} ({} bytes) ...",
+        tracing::info!(
+            "Uploading {} ({} bytes) ...",
             filename,
             buffer.len()
         );
 
         // Call bridge_core operation
-        let response = rt.block_on(async {
-            ops::upload::upload(
-                &client,
+        let response = client
+            .block_on(ops::upload::upload(
+                client.client(),
                 &filename,
                 &buffer,
-                relative_path,
-                [tx, ty, tz],
-                [rotation_quat.x, rotation_quat.y, rotation_quat.z, rotation_quat.w],
-            )
-            .await
-        })
-        .map_err(|e| anyhow!("Bridge error: {}", e))?;
+                &UploadOptions {
+                    subdir: relative_path.map(str::to_string),
+                    translation: [tx, ty, tz],
+                    rotation: [rotation_quat.x, rotation_quat.y, rotation_quat.z, rotation_quat.w],
+                    name: name.map(str::to_string),
+                    group: group.map(str::to_string),
+                },
+            ))
+            .map_err(|e| anyhow!("Bridge error: {}", e))?;
 
         Ok(format!(
             "Uploaded and Spawned {}. Entity ID: {}",
@@ -226,7 +235,6 @@ impl Tool for BevyRpcTool {
 
     fn execute(&self, args: Value) -> Result<String> {
         let client = get_bridge_client()?;
-        let rt = Runtime::new()?;
         
         let method = args
             .get("method")
@@ -235,10 +243,9 @@ impl Tool for BevyRpcTool {
 
         let params = args.get("params").cloned();
 
-        let result = rt.block_on(async {
-            ops::raw::raw(&client, method, params).await
-        })
-        .map_err(|e| anyhow!("Bridge error: {}", e))?;
+        let result = client
+            .block_on(ops::raw::raw(client.client(), method, params))
+            .map_err(|e| anyhow!("Bridge error: {}", e))?;
 
         if let Some(error) = result.get("error") {
             Err(anyhow!("Bevy RPC Error: {}", error))
@@ -353,7 +360,7 @@ impl Tool for BevySpawnSceneTool {
             }
         });
 
-        match ureq::post(BEVY_RPC_URL).send_json(payload) {
+        match ureq::post(&BrpConfig::from_env().endpoint).send_json(payload) {
             Ok(res) => {
                 let body: Value = res.into_json()?;
                 Ok(serde_json::to_string_pretty(&body)?)
@@ -380,29 +387,91 @@ impl Tool for BevyClearSceneTool {
             "type": "function",
             "function": {
                 "name": "bevy_clear_scene",
-                "description": "Clear the scene by despawning all entities.",
+                "description": "Clear the scene by despawning all entities, or only those in a group.",
                 "parameters": {
                     "type": "object",
-                    "properties": {},
+                    "properties": {
+                        "group": {
+                            "type": "string",
+                            "description": "Optional group to clear instead of everything (e.g. 'level1')."
+                        }
+                    },
                     "required": []
                 }
             }
         })
     }
 
-    fn execute(&self, _args: Value) -> Result<String> {
+    fn execute(&self, args: Value) -> Result<String> {
         let client = get_bridge_client()?;
-        let rt = Runtime::new()?;
+
+        let target = match args.get("group").and_then(|v| v.as_str()) {
+            Some(group) => ClearTarget::Group(group.to_string()),
+            None => ClearTarget::All,
+        };
         
-        let response = rt.block_on(async {
-            ops::clear::clear(&client, bevy_bridge_core::types::ClearTarget::All).await
-        })
-        .map_err(|e| anyhow!("Bridge error: {}", e))?;
+        let response = client
+            .block_on(ops::clear::clear(client.client(), target))
+            .map_err(|e| anyhow!("Bridge error: {}", e))?;
 
         Ok(format!("Cleared {} entities.", response.entities_removed))
     }
 }
 
+/// Tool to record and summarize the game's frame timings
+pub struct BevyProfileTool;
+
+impl Tool for BevyProfileTool {
+    fn name(&self) -> String {
+        "bevy_profile".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Profile the running game's frames to answer performance questions with real data.".to_string()
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": "bevy_profile",
+                "description": "Record the game's frame timings. Call with action 'start', let the game run or make the change to measure, then 'read' or 'stop'. Returns average and worst frame time, FPS, entity counts at the first and last frame, and the slowest schedules and systems (per-frame averages).",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "action": { "type": "string", "enum": ["start", "stop", "read"], "description": "'start' drops earlier frames" },
+                        "capacity": { "type": "integer", "description": "Frames the game keeps once started (default 600)" },
+                        "top": { "type": "integer", "description": "How many of the slowest systems to list (default 10)" }
+                    },
+                    "required": ["action"]
+                }
+            }
+        })
+    }
+
+    fn execute(&self, args: Value) -> Result<String> {
+        let client = get_bridge_client()?;
+        let capacity = args["capacity"].as_u64().map(|c| c as usize);
+        let top = args["top"].as_u64().map_or(10, |t| t as usize);
+
+        let capture = client
+            .block_on(async {
+                match args["action"].as_str().unwrap_or("read") {
+                    "start" => ops::profile::start(client.client(), capacity).await,
+                    "stop" => ops::profile::stop(client.client()).await,
+                    _ => ops::profile::read(client.client()).await,
+                }
+            })
+            .map_err(|e| anyhow!("Bridge error: {}", e))?;
+
+        Ok(serde_json::to_string_pretty(&json!({
+            "recording": capture.recording,
+            "system_timing": capture.system_timing,
+            "summary": capture.summary(top),
+        }))?)
+    }
+}
+
 /// Helper tool to Spawn a Primitive Cube easily
 pub struct BevySpawnPrimitiveTool;
 
@@ -435,6 +504,31 @@ impl Tool for BevySpawnPrimitiveTool {
                             "minItems": 3,
                             "maxItems": 3,
                             "description": "[x, y, z] position"
+                        },
+                        "color": {
+                            "type": "array",
+                            "items": { "type": "number" },
+                            "minItems": 3,
+                            "maxItems": 4,
+                            "description": "sRGB [r, g, b] or [r, g, b, a], each 0 to 1. Beige if omitted."
+                        },
+                        "metallic": { "type": "number", "description": "0 (dielectric) to 1 (metal)." },
+                        "roughness": { "type": "number", "description": "0 (glossy) to 1 (matte)." },
+                        "emissive": {
+                            "type": "array",
+                            "items": { "type": "number" },
+                            "minItems": 3,
+                            "maxItems": 3,
+                            "description": "Linear [r, g, b] glow; values above 1 glow brighter."
+                        },
+                        "unlit": { "type": "boolean", "description": "Ignore lighting and show the color as is." },
+                        "name": {
+                            "type": "string",
+                            "description": "Optional name to find the entity by later (e.g. 'player_start')."
+                        },
+                        "group": {
+                            "type": "string",
+                            "description": "Optional group to clear the entity with later (e.g. 'level1')."
                         }
                     },
                     "required": ["type", "translation"]
@@ -445,7 +539,6 @@ impl Tool for BevySpawnPrimitiveTool {
 
     fn execute(&self, args: Value) -> Result<String> {
         let client = get_bridge_client()?;
-        let rt = Runtime::new()?;
         
         let t = args
             .get("translation")
@@ -461,17 +554,27 @@ impl Tool for BevySpawnPrimitiveTool {
             .and_then(|v| v.as_str())
             .unwrap_or("cube");
 
-        let response = rt.block_on(async {
-            ops::spawn::spawn(
-                &client,
-                primitive_type,
-                [tx, ty, tz],
-                [0.0, 0.0, 0.0, 1.0],
-                [1.0, 1.0, 1.0],
-            )
-            .await
-        })
-        .map_err(|e| anyhow!("Bridge error: {}", e))?;
+        let name = args.get("name").and_then(|v| v.as_str());
+        let group = args.get("group").and_then(|v| v.as_str());
+
+        // The material fields sit next to `type` and `translation`
+        let material: PrimitiveMaterial =
+            serde_json::from_value(args.clone()).map_err(|e| anyhow!("Invalid material: {}", e))?;
+
+        let response = client
+            .block_on(ops::spawn::spawn(
+                client.client(),
+                &SpawnRequest {
+                    primitive: primitive_type.to_string(),
+                    position: [tx, ty, tz],
+                    rotation: [0.0, 0.0, 0.0, 1.0],
+                    scale: [1.0, 1.0, 1.0],
+                    material,
+                    name: name.map(str::to_string),
+                    group: group.map(str::to_string),
+                },
+            ))
+            .map_err(|e| anyhow!("Bridge error: {}", e))?;
 
         Ok(format!("Spawned {}. Entity ID: {}", primitive_type, response.entity_id))
     }
@@ -107,7 +107,7 @@ fn get_or_init_session() -> Result<std::sync::MutexGuard<'static, LspSession>> {
                         }
                     }
                     Err(e) => {
-                        eprintln!("LSP Reader Error: {}", e);
+                        tracing::warn!("LSP Reader Error: {}", e);
                         // If EOF, break
                         break;
                     }
@@ -4,12 +4,16 @@ pub mod bevy;
 pub mod locks;
 pub mod lsp;
 pub mod multiedit;
+pub mod review;
 pub mod search;
+pub mod semantic_search;
 pub mod shell;
 pub mod todo;
 
-use crate::types::AsyncMessage;
+use crate::types::{AgentProfile, AsyncMessage};
 use anyhow::{anyhow, Result};
+use axiom_agent_core::plugin::PluginManifest;
+use axiom_config::ToolPermissions;
 use bevy::{
     BevyClearSceneTool, BevyRpcTool, BevySpawnPrimitiveTool, BevySpawnSceneTool,
     BevyUploadAssetTool,
@@ -17,14 +21,9 @@ use bevy::{
 use serde_json::{json, Value};
 use std::fs;
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, RwLock};
 
-pub trait Tool: Send + Sync {
-    fn name(&self) -> String;
-    #[allow(dead_code)]
-    fn description(&self) -> String;
-    fn schema(&self) -> Value;
-    fn execute(&self, args: Value) -> Result<String>;
-}
+pub use axiom_agent_core::tool::{execute_checked, execute_reviewed, FileEdit, Tool};
 
 // ... (Other standard tools: ReadFileTool, WriteFileTool, etc.)
 // Re-implementing them briefly since I overwrote the file.
@@ -101,6 +100,15 @@ impl Tool for WriteFileTool {
         fs::write(path, content).map_err(|e| anyhow!("Failed to write: {}", e))?;
         Ok(format!("File written to {}", path))
     }
+    fn preview_edit(&self, args: &Value) -> Option<Result<FileEdit>> {
+        let path = args.get("path")?.as_str()?;
+        let content = args.get("content")?.as_str()?;
+        Some(Ok(FileEdit {
+            path: path.to_string(),
+            before: fs::read_to_string(path).unwrap_or_default(),
+            after: content.to_string(),
+        }))
+    }
 }
 
 pub struct EditFileTool;
@@ -145,41 +153,117 @@ impl Tool for EditFileTool {
 
         let _guard = locks::acquire_lock(path)?;
         let content = fs::read_to_string(path).map_err(|e| anyhow!("Read fail: {}", e))?;
-        if !content.contains(old_s) {
-            return Err(anyhow!("old_string not found"));
-        }
-        let new_content = content.replace(old_s, new_s);
+        let new_content = replace_in(&content, old_s, new_s)?;
         fs::write(path, new_content).map_err(|e| anyhow!("Write fail: {}", e))?;
         Ok(format!("Edited {}", path))
     }
+    fn preview_edit(&self, args: &Value) -> Option<Result<FileEdit>> {
+        let path = args.get("path")?.as_str()?;
+        let old_s = args.get("old_string")?.as_str()?;
+        let new_s = args.get("new_string")?.as_str()?;
+        Some((|| {
+            let before = fs::read_to_string(path).map_err(|e| anyhow!("Read fail: {}", e))?;
+            let after = replace_in(&before, old_s, new_s)?;
+            Ok(FileEdit { path: path.to_string(), before, after })
+        })())
+    }
+}
+
+fn replace_in(content: &str, old_s: &str, new_s: &str) -> Result<String> {
+    if !content.contains(old_s) {
+        return Err(anyhow!("old_string not found"));
+    }
+    Ok(content.replace(old_s, new_s))
+}
+
+/// Tools only the "Bevy Editor Companion" profile gets unless a profile
+/// lists them in `allowed_tools`.
+const COMPANION_TOOLS: [&str; 2] = ["bevy_rpc", "bevy_spawn_scene"];
+
+/// Tool permissions from the open project's `axiom.toml`.
+static PERMISSIONS: RwLock<Option<ToolPermissions>> = RwLock::new(None);
+
+/// Applies to toolsets built afterwards.
+pub fn set_permissions(permissions: ToolPermissions) {
+    *PERMISSIONS.write().unwrap_or_else(|e| e.into_inner()) = Some(permissions);
+}
+
+/// Plugins found for the open project.
+static PLUGINS: RwLock<Vec<Arc<PluginManifest>>> = RwLock::new(Vec::new());
+
+/// Their tools join every toolset built afterwards.
+pub fn set_plugins(plugins: Vec<Arc<PluginManifest>>) {
+    *PLUGINS.write().unwrap_or_else(|e| e.into_inner()) = plugins;
 }
 
-pub fn get_tools_for_profile(profile_name: &str, tx: Sender<AsyncMessage>) -> Vec<Box<dyn Tool>> {
+/// Every tool a profile can be given, plugins included. `allowed` restricts
+/// what `batch_run` may call. Tools the project denies are left out.
+fn registry(tx: Sender<AsyncMessage>, allowed: Option<Vec<String>>) -> Vec<Box<dyn Tool>> {
     let mut tools: Vec<Box<dyn Tool>> = vec![
         Box::new(ReadFileTool),
         Box::new(WriteFileTool),
         Box::new(EditFileTool),
         Box::new(search::GlobTool),
+        Box::new(semantic_search::SemanticSearchTool),
         Box::new(todo::TodoReadTool),
         Box::new(todo::TodoWriteTool),
         Box::new(ast_grep::AstGrepTool),
-        Box::new(batch::BatchTool::new(tx.clone())),
+        Box::new(batch::BatchTool::new(tx.clone()).with_allowed(allowed)),
         Box::new(multiedit::MultiEditTool),
         Box::new(lsp::LspTool),
         Box::new(shell::ShellTool),
         Box::new(bevy::BevyUploadAssetTool), // Now available to all agents
         Box::new(bevy::BevyClearSceneTool),  // New: Clear Scene
+        Box::new(bevy::BevyProfileTool),
                                              // Box::new(bevy::BevySpawnPrimitiveTool), // Temporarily disabled to force asset upload workflow
+        Box::new(bevy::BevyRpcTool),
+        Box::new(bevy::BevySpawnSceneTool),
     ];
+    // Built-in tools keep their names
+    for plugin in PLUGINS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        for tool in plugin.clone().into_tools() {
+            if !tools.iter().any(|t| t.name() == tool.name()) {
+                tools.push(tool);
+            }
+        }
+    }
+    if let Some(permissions) = &*PERMISSIONS.read().unwrap_or_else(|e| e.into_inner()) {
+        tools.retain(|t| permissions.allows(&t.name()));
+    }
+    tools
+}
 
-    if profile_name == "Bevy Editor Companion" {
-        tools.push(Box::new(bevy::BevyRpcTool));
-        tools.push(Box::new(bevy::BevySpawnSceneTool));
+/// The profile's `allowed_tools`, or its default toolset if it has none.
+pub fn get_tools_for_profile(profile: &AgentProfile, tx: Sender<AsyncMessage>) -> Vec<Box<dyn Tool>> {
+    if let Some(allowed) = &profile.allowed_tools {
+        return get_allowed_tools(allowed, tx);
+    }
+    let mut tools = registry(tx, None);
+    if profile.name != "Bevy Editor Companion" {
+        tools.retain(|t| !COMPANION_TOOLS.contains(&t.name().as_str()));
     }
+    tools
+}
 
+pub fn get_allowed_tools(allowed: &[String], tx: Sender<AsyncMessage>) -> Vec<Box<dyn Tool>> {
+    let mut tools = registry(tx, Some(allowed.to_vec()));
+    tools.retain(|t| allowed.contains(&t.name()));
     tools
 }
 
+/// Names of every tool, for the profile editor.
+pub fn tool_names() -> Vec<String> {
+    let (tx, _) = std::sync::mpsc::channel();
+    registry(tx, None).iter().map(|t| t.name()).collect()
+}
+
+/// Every registered tool, for automation scripts.
+pub fn get_every_tool(tx: Sender<AsyncMessage>) -> Vec<Box<dyn Tool>> {
+    registry(tx, None)
+}
+
+/// The default toolset, used by `batch_run` when its profile has no
+/// `allowed_tools`.
 pub fn get_all_tools(tx: Sender<AsyncMessage>) -> Vec<Box<dyn Tool>> {
-    get_tools_for_profile("General", tx)
+    get_tools_for_profile(&AgentProfile::default(), tx)
 }
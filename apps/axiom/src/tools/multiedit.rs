@@ -1,4 +1,4 @@
-use crate::tools::Tool;
+use crate::tools::{FileEdit, Tool};
 use anyhow::{anyhow, Result};
 use serde_json::{json, Value};
 use std::fs;
@@ -60,36 +60,9 @@ impl Tool for MultiEditTool {
         // Acquire lock before reading and writing
         let _guard = crate::tools::locks::acquire_lock(path)?;
 
-        let mut content = fs::read_to_string(path)
+        let content = fs::read_to_string(path)
             .map_err(|e| anyhow!("Failed to read file '{}': {}", path, e))?;
-
-        // Apply edits in memory first
-        for (i, edit) in edits.iter().enumerate() {
-            let old_str = edit
-                .get("old_string")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Edit #{}: Missing 'old_string'", i))?;
-
-            let new_str = edit
-                .get("new_string")
-                .and_then(|v| v.as_str())
-                .ok_or_else(|| anyhow!("Edit #{}: Missing 'new_string'", i))?;
-
-            let replace_all = edit
-                .get("replace_all")
-                .and_then(|v| v.as_bool())
-                .unwrap_or(false);
-
-            if !content.contains(old_str) {
-                return Err(anyhow!("Edit #{}: 'old_string' not found in content", i));
-            }
-
-            if replace_all {
-                content = content.replace(old_str, new_str);
-            } else {
-                content = content.replacen(old_str, new_str, 1);
-            }
-        }
+        let content = apply_edits(content, edits)?;
 
         // Write back only if all succeeded
         fs::write(path, content).map_err(|e| anyhow!("Failed to write file '{}': {}", path, e))?;
@@ -100,4 +73,47 @@ impl Tool for MultiEditTool {
             path
         ))
     }
+
+    fn preview_edit(&self, args: &Value) -> Option<Result<FileEdit>> {
+        let path = args.get("path")?.as_str()?;
+        let edits = args.get("edits")?.as_array()?;
+        Some((|| {
+            let before = fs::read_to_string(path)
+                .map_err(|e| anyhow!("Failed to read file '{}': {}", path, e))?;
+            let after = apply_edits(before.clone(), edits)?;
+            Ok(FileEdit { path: path.to_string(), before, after })
+        })())
+    }
+}
+
+/// Apply `edits` in order, in memory. Fails without partial results if any
+/// `old_string` is missing.
+fn apply_edits(mut content: String, edits: &[Value]) -> Result<String> {
+    for (i, edit) in edits.iter().enumerate() {
+        let old_str = edit
+            .get("old_string")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Edit #{}: Missing 'old_string'", i))?;
+
+        let new_str = edit
+            .get("new_string")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Edit #{}: Missing 'new_string'", i))?;
+
+        let replace_all = edit
+            .get("replace_all")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        if !content.contains(old_str) {
+            return Err(anyhow!("Edit #{}: 'old_string' not found in content", i));
+        }
+
+        if replace_all {
+            content = content.replace(old_str, new_str);
+        } else {
+            content = content.replacen(old_str, new_str, 1);
+        }
+    }
+    Ok(content)
 }
@@ -0,0 +1,71 @@
+//! Approval of agent file edits from the chat.
+//!
+//! `ChannelReviewer` sends each proposed edit to the UI as a diff card and
+//! blocks the tool until the user approves or rejects it. Edit permission is
+//! `ask` by default; `AXIOM_EDIT_APPROVAL=auto`, or "Approve all" on a card,
+//! lets edits through without asking for the rest of the session.
+
+use crate::types::AsyncMessage;
+use axiom_agent_core::tool::{EditReviewer, FileEdit};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Sender};
+use std::sync::OnceLock;
+
+fn auto_approve_flag() -> &'static AtomicBool {
+    static AUTO_APPROVE: OnceLock<AtomicBool> = OnceLock::new();
+    AUTO_APPROVE.get_or_init(|| {
+        let auto = std::env::var("AXIOM_EDIT_APPROVAL").is_ok_and(|v| v.trim().eq_ignore_ascii_case("auto"));
+        AtomicBool::new(auto)
+    })
+}
+
+pub fn auto_approve() -> bool {
+    auto_approve_flag().load(Ordering::Relaxed)
+}
+
+pub fn set_auto_approve(enabled: bool) {
+    auto_approve_flag().store(enabled, Ordering::Relaxed);
+}
+
+/// An edit waiting for the user. Dropping it without `decide` rejects the edit.
+pub struct PendingReview {
+    pub tool: String,
+    pub edit: FileEdit,
+    reply: Sender<bool>,
+}
+
+impl PendingReview {
+    pub fn decide(self, approved: bool) {
+        let _ = self.reply.send(approved);
+    }
+}
+
+pub struct ChannelReviewer {
+    tx: Sender<AsyncMessage>,
+}
+
+impl ChannelReviewer {
+    pub fn new(tx: Sender<AsyncMessage>) -> Self {
+        Self { tx }
+    }
+}
+
+impl EditReviewer for ChannelReviewer {
+    fn review(&self, tool: &str, edit: &FileEdit) -> bool {
+        if auto_approve() {
+            return true;
+        }
+
+        let (reply, decision) = channel();
+        let pending = PendingReview {
+            tool: tool.to_string(),
+            edit: edit.clone(),
+            reply,
+        };
+        if self.tx.send(AsyncMessage::ReviewEdit(pending)).is_err() {
+            return false;
+        }
+        // The UI drops the request (rejecting it) when the generation is stopped
+        decision.recv().unwrap_or(false)
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::llm::embeddings::EmbeddingClient;
+use crate::semantic::CodeIndex;
+use crate::tools::Tool;
+
+/// Maximum characters of each hit's snippet returned to the model.
+const SNIPPET_CHARS: usize = 1200;
+
+pub struct SemanticSearchTool;
+
+impl Tool for SemanticSearchTool {
+    fn name(&self) -> String {
+        "semantic_search".to_string()
+    }
+
+    fn description(&self) -> String {
+        "Find code by meaning using an embedding index of the workspace. Use when grep/glob patterns fail.".to_string()
+    }
+
+    fn schema(&self) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": "semantic_search",
+                "description": "Search the workspace by meaning (e.g. 'where are road tiles rotated'). Builds/updates an embedding index on first use.",
+                "parameters": {
+                    "type": "object",
+                    "properties": {
+                        "query": {
+                            "type": "string",
+                            "description": "Natural-language description of the code you are looking for"
+                        },
+                        "path": {
+                            "type": "string",
+                            "description": "Workspace root to index (default: current directory)"
+                        },
+                        "top_k": {
+                            "type": "integer",
+                            "description": "Number of results to return (default 8)"
+                        }
+                    },
+                    "required": ["query"]
+                }
+            }
+        })
+    }
+
+    fn execute(&self, args: Value) -> Result<String> {
+        let query = args
+            .get("query")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| anyhow!("Missing 'query' argument"))?;
+
+        let root = match args.get("path").and_then(|v| v.as_str()) {
+            Some(p) => PathBuf::from(p),
+            None => std::env::current_dir()?,
+        };
+        if !root.is_dir() {
+            return Err(anyhow!("Not a directory: {}", root.display()));
+        }
+
+        let top_k = args
+            .get("top_k")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(8)
+            .clamp(1, 50) as usize;
+
+        let client = EmbeddingClient::from_env()?;
+        let mut index = CodeIndex::load(&root, &client.model);
+        let reindexed = index.update(&root, &client)?;
+        if reindexed > 0 {
+            index.save(&root)?;
+        }
+
+        if index.chunk_count() == 0 {
+            return Ok("No indexable source files found.".to_string());
+        }
+
+        let query_vec = client
+            .embed(&[query.to_string()])?
+            .pop()
+            .ok_or_else(|| anyhow!("Empty embedding response for query"))?;
+
+        let hits = index.search(&query_vec, top_k);
+        let mut result = String::new();
+        for hit in hits {
+            let snippet: String = hit.text.chars().take(SNIPPET_CHARS).collect();
+            result.push_str(&format!(
+                "{}:{}-{} (score {:.3})\n```\n{}\n```\n\n",
+                hit.path, hit.start_line, hit.end_line, hit.score, snippet
+            ));
+        }
+
+        Ok(result)
+    }
+}
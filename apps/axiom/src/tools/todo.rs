@@ -6,6 +6,12 @@ use std::path::Path;
 
 use crate::tools::Tool;
 
+/// Shared by the todo tools and the todo panel, relative to the workspace.
+pub const TODO_FILE: &str = "todos.json";
+
+pub const STATUSES: [&str; 4] = ["pending", "in_progress", "completed", "cancelled"];
+pub const PRIORITIES: [&str; 3] = ["high", "medium", "low"];
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TodoItem {
     pub id: String,
@@ -14,6 +20,30 @@ pub struct TodoItem {
     pub priority: String, // high, medium, low
 }
 
+impl TodoItem {
+    pub fn is_active(&self) -> bool {
+        self.status != "completed" && self.status != "cancelled"
+    }
+}
+
+/// The current list; empty if there is none yet.
+pub fn load() -> Result<Vec<TodoItem>> {
+    let content = match fs::read_to_string(TODO_FILE) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(anyhow!("Failed to read {}: {}", TODO_FILE, e)),
+    };
+    serde_json::from_str(&content).map_err(|e| anyhow!("Invalid {}: {}", TODO_FILE, e))
+}
+
+/// Replace the list. Written to a temp file first so a reader never sees a
+/// half-written list.
+pub fn save(todos: &[TodoItem]) -> Result<()> {
+    let tmp = format!("{}.tmp", TODO_FILE);
+    fs::write(&tmp, serde_json::to_string_pretty(todos)?)?;
+    fs::rename(&tmp, TODO_FILE).map_err(|e| anyhow!("Failed to write {}: {}", TODO_FILE, e))
+}
+
 pub struct TodoReadTool;
 
 impl Tool for TodoReadTool {
@@ -41,13 +71,12 @@ impl Tool for TodoReadTool {
     }
 
     fn execute(&self, _args: Value) -> Result<String> {
-        let path = "todos.json";
-        if !Path::new(path).exists() {
+        if !Path::new(TODO_FILE).exists() {
             return Ok("No todo list found. Use 'todowrite' to create one.".to_string());
         }
 
         let content =
-            fs::read_to_string(path).map_err(|e| anyhow!("Failed to read todos.json: {}", e))?;
+            fs::read_to_string(TODO_FILE).map_err(|e| anyhow!("Failed to read {}: {}", TODO_FILE, e))?;
 
         Ok(content)
     }
@@ -101,13 +130,9 @@ impl Tool for TodoWriteTool {
         let todos: Vec<TodoItem> = serde_json::from_value(todos_val.clone())
             .map_err(|e| anyhow!("Invalid todo format: {}", e))?;
 
-        let json_output = serde_json::to_string_pretty(&todos)?;
-        fs::write("todos.json", json_output)?;
+        save(&todos)?;
 
-        let active_count = todos
-            .iter()
-            .filter(|t| t.status != "completed" && t.status != "cancelled")
-            .count();
+        let active_count = todos.iter().filter(|t| t.is_active()).count();
         Ok(format!(
             "Todo list updated. {} active tasks remaining.",
             active_count
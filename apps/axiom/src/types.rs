@@ -1,6 +1,9 @@
-use crate::llm::MessageContent;
+use crate::llm::retry::ProviderUnavailable;
+use crate::llm::usage::Usage;
+use crate::llm::{MessageContent, ProviderKind, SamplingParams};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ChannelState {
     pub id: String,                             // Unique ID (e.g., "global", "backend")
     pub name: String,                           // Display Name (e.g., "🌐 Global", "🦀 Backend")
@@ -19,15 +22,22 @@ impl Default for ChannelState {
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AgentProfile {
     pub name: String,
     pub description: String,
     pub avatar_path: String,   // e.g., "bevy.png"
+    pub provider: ProviderKind, // Which API the model is served through
     pub model: String,         // e.g., "gemini-pro"
     pub research_mode: String, // "Fast", "Smart Hybrid", "Deep Research"
     pub context_mode: String,  // "General", "Bevy", "Pokemon"
     pub system_prompt: String, // The actual prompt
+    #[serde(default)]
+    pub sampling: SamplingParams, // temperature, top_p, max_tokens, stop, reasoning effort
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>, // Tool names; None = the default toolset
+    #[serde(default)]
+    pub color: Option<[u8; 3]>, // Name color in the chat; None = derived from the name
 }
 
 impl Default for AgentProfile {
@@ -36,10 +46,14 @@ impl Default for AgentProfile {
             name: "Axiom".to_string(),
             description: "Default AI Assistant".to_string(),
             avatar_path: "system.png".to_string(),
+            provider: ProviderKind::OpenAiCompatible,
             model: "gemini-2.5-flash".to_string(),
             research_mode: "Smart Hybrid".to_string(),
             context_mode: "General".to_string(),
             system_prompt: "".to_string(),
+            sampling: SamplingParams::default(),
+            allowed_tools: None,
+            color: None,
         }
     }
 }
@@ -51,4 +65,48 @@ pub enum AsyncMessage {
     Done,
     Log(String),
     Error(String),
+    /// Token usage reported by the provider for one request.
+    Usage { profile: String, model: String, usage: Usage },
+    /// Result of listing the models a provider serves.
+    Models { provider: ProviderKind, models: Vec<String> },
+    /// Speech-to-text result for the chat input.
+    Transcript(Result<String, String>),
+    /// Result of polling the game for a preview screenshot (`None` while the
+    /// first capture is still pending).
+    GameScreenshot(Result<Option<bevy_bridge_core::types::ScreenshotResponse>, String>),
+    /// Result of listing the game's `_remote_cache` for the asset browser.
+    RemoteAssets(Result<Vec<bevy_bridge_core::types::RemoteAsset>, String>),
+    /// A tool wants to modify a file and is waiting for the user's decision.
+    ReviewEdit(crate::tools::review::PendingReview),
+    /// Result of a background action started from the UI, such as a model
+    /// upload or a test run.
+    ActionFinished(Result<String, String>),
+    /// A scene snapshot was saved or restored.
+    SceneHistory(Result<String, String>),
+    /// The game's undo journal, after a refresh, undo or redo.
+    UndoHistory(Result<bevy_bridge_core::types::History, String>),
+    /// The game was built and started, or failed to.
+    GameLaunched(Result<crate::game::GameProcess, String>),
+    /// Round trip of a BRP ping, `None` if the game didn't answer.
+    GamePing(Option<std::time::Duration>),
+    /// Games found announcing themselves on the local network.
+    GamesDiscovered(Result<Vec<bevy_bridge_core::discovery::DiscoveredGame>, String>),
+    /// The game's profiler capture, after starting, stopping or reading it.
+    Profile(Result<bevy_bridge_core::types::ProfileCapture, String>),
+    /// The provider's circuit breaker is open; requests fail fast until then.
+    ProviderUnavailable { message: String, retry_in: std::time::Duration },
+}
+
+impl AsyncMessage {
+    /// `Error`, or `ProviderUnavailable` when the failure came from an open
+    /// circuit breaker.
+    pub fn from_error(e: &anyhow::Error) -> Self {
+        match e.downcast_ref::<ProviderUnavailable>() {
+            Some(unavailable) => AsyncMessage::ProviderUnavailable {
+                message: unavailable.to_string(),
+                retry_in: unavailable.retry_in,
+            },
+            None => AsyncMessage::Error(format!("{:#}", e)),
+        }
+    }
 }
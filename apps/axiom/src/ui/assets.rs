@@ -0,0 +1,258 @@
+use bevy_bridge_core::types::RemoteAsset;
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+const EXTENSIONS: &[&str] = &["glb", "gltf", "png", "jpg", "jpeg"];
+const TILE_SIZE: f32 = 72.0;
+
+/// An asset that can be spawned, and the payload dragged onto the game preview.
+#[derive(Clone, Debug)]
+pub enum AssetRef {
+    /// A file in the project, uploaded before spawning.
+    Project(PathBuf),
+    /// A file already in the game's `_remote_cache`, relative to it.
+    Remote(String),
+}
+
+impl AssetRef {
+    fn is_model(&self) -> bool {
+        match self {
+            AssetRef::Project(path) => crate::attachments::is_model_path(path),
+            AssetRef::Remote(path) => crate::attachments::is_model_path(Path::new(path)),
+        }
+    }
+}
+
+pub enum AssetAction {
+    RefreshRemote,
+    Spawn(AssetRef),
+    None,
+}
+
+struct CachedAsset {
+    path: String,
+    size: u64,
+    image: Option<egui::load::Bytes>,
+}
+
+/// Project assets on disk plus what the game has cached from uploads.
+pub struct AssetBrowserState {
+    pub open: bool,
+    /// Shown in its own window instead of the bottom panel.
+    pub detached: bool,
+    /// The project's asset folders.
+    roots: Vec<PathBuf>,
+    project: Vec<PathBuf>,
+    /// Watcher revision the project list was read at.
+    revision: Option<u64>,
+    remote: Vec<CachedAsset>,
+    pub loading: bool,
+    error: Option<String>,
+    filter: String,
+}
+
+impl AssetBrowserState {
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            open: false,
+            detached: false,
+            roots,
+            project: Vec::new(),
+            revision: None,
+            remote: Vec::new(),
+            loading: false,
+            error: None,
+            filter: String::new(),
+        }
+    }
+
+    pub fn receive(&mut self, result: Result<Vec<RemoteAsset>, String>) {
+        self.loading = false;
+        match result {
+            Ok(assets) => {
+                self.remote = assets
+                    .into_iter()
+                    .map(|asset| CachedAsset { path: asset.path, size: asset.size, image: asset.image.map(Into::into) })
+                    .collect();
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    fn rescan(&mut self) {
+        self.project.clear();
+        let mut stack = self.roots.clone();
+        while let Some(dir) = stack.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let name = entry.file_name();
+                // The game's own copy is listed under "In game"
+                if name.to_string_lossy().starts_with('.') || name == "_remote_cache" {
+                    continue;
+                }
+                if path.is_dir() {
+                    stack.push(path);
+                } else if has_asset_extension(&path) {
+                    self.project.push(path);
+                }
+            }
+        }
+        self.project.sort();
+    }
+}
+
+fn has_asset_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// A thumbnail; models can be dragged and have a spawn button.
+fn tile(ui: &mut egui::Ui, asset: AssetRef, name: &str, image: Option<egui::Image>, hover: String) -> Option<AssetRef> {
+    let id = egui::Id::new(("asset_tile", format!("{:?}", asset)));
+    let is_model = asset.is_model();
+    let mut spawn = None;
+
+    ui.vertical(|ui| {
+        ui.set_width(TILE_SIZE);
+        let draw = |ui: &mut egui::Ui| {
+            ui.vertical_centered(|ui| {
+                match image {
+                    Some(image) => {
+                        ui.add(image.fit_to_exact_size(egui::vec2(TILE_SIZE - 8.0, TILE_SIZE - 24.0)).rounding(4.0));
+                    }
+                    None => {
+                        ui.add_sized([TILE_SIZE - 8.0, TILE_SIZE - 24.0], egui::Label::new(egui::RichText::new("🧊").size(32.0)));
+                    }
+                }
+                ui.add(egui::Label::new(egui::RichText::new(name).small()).truncate());
+            })
+        };
+        let response = if is_model {
+            ui.dnd_drag_source(id, asset.clone(), draw).response
+        } else {
+            draw(ui).response
+        };
+        response.on_hover_text(hover);
+        if is_model && ui.small_button("⬆ Spawn").on_hover_text("Spawn at the origin").clicked() {
+            spawn = Some(asset);
+        }
+    });
+    spawn
+}
+
+/// `revision` comes from the workspace watcher; the project list is reread
+/// when it changes.
+pub fn render_asset_browser(ctx: &egui::Context, state: &mut AssetBrowserState, revision: u64) -> AssetAction {
+    let mut action = AssetAction::None;
+    if !state.open {
+        return action;
+    }
+    if state.revision != Some(revision) {
+        state.revision = Some(revision);
+        state.rescan();
+    }
+
+    if state.detached {
+        let closed = crate::ui::viewport::show_detached(ctx, "asset_browser", "🗃 Assets", [720.0, 480.0], |ui| {
+            render_contents(ui, state, &mut action)
+        });
+        if closed {
+            state.detached = false;
+        }
+        return action;
+    }
+    egui::TopBottomPanel::bottom("asset_browser_panel")
+        .resizable(true)
+        .default_height(220.0)
+        .min_height(120.0)
+        .show(ctx, |ui| render_contents(ui, state, &mut action));
+
+    action
+}
+
+fn render_contents(ui: &mut egui::Ui, state: &mut AssetBrowserState, action: &mut AssetAction) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("🗃 Assets").strong());
+        ui.separator();
+        ui.add(egui::TextEdit::singleline(&mut state.filter).hint_text("🔍 Filter").desired_width(140.0));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            crate::ui::viewport::detach_button(ui, &mut state.detached);
+            if ui.add_enabled(!state.loading, egui::Button::new("🔄")).on_hover_text("Reload the project folder and the game's asset cache").clicked() {
+                state.rescan();
+                *action = AssetAction::RefreshRemote;
+            }
+            if state.loading {
+                ui.spinner();
+            }
+        });
+    });
+    ui.label(egui::RichText::new("Drag a model onto the game preview, or click ⬆ Spawn, to spawn it at the origin.").weak().small());
+    ui.separator();
+
+    let filter = state.filter.to_lowercase();
+    let matches = |name: &str| filter.is_empty() || name.to_lowercase().contains(&filter);
+
+    egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+        egui::CollapsingHeader::new(format!("📂 Project ({})", state.project.len()))
+            .default_open(true)
+            .show(ui, |ui| {
+                if state.project.is_empty() {
+                    let roots: Vec<String> = state.roots.iter().map(|r| r.display().to_string()).collect();
+                    ui.label(egui::RichText::new(format!("No models or images under {}", roots.join(", "))).weak())
+                        .on_hover_text("Asset folders are set by project.asset_dirs in axiom.toml");
+                }
+                ui.horizontal_wrapped(|ui| {
+                    for path in &state.project {
+                        let shown = state
+                            .roots
+                            .iter()
+                            .find_map(|root| path.strip_prefix(root).ok())
+                            .unwrap_or(path)
+                            .display()
+                            .to_string();
+                        if !matches(&shown) {
+                            continue;
+                        }
+                        let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                        let image = crate::llm::image::is_image_path(path).then(|| {
+                            egui::Image::from_uri(format!("file://{}", path.display().to_string().replace('\\', "/")))
+                        });
+                        if let Some(asset) = tile(ui, AssetRef::Project(path.clone()), &name, image, shown) {
+                            *action = AssetAction::Spawn(asset);
+                        }
+                    }
+                });
+            });
+
+        egui::CollapsingHeader::new(format!("🎮 In game ({})", state.remote.len()))
+            .default_open(true)
+            .show(ui, |ui| {
+                if let Some(error) = &state.error {
+                    ui.colored_label(egui::Color32::from_rgb(255, 140, 0), format!("⚠ {}", error));
+                } else if state.remote.is_empty() {
+                    ui.label(egui::RichText::new("Nothing uploaded to the game yet.").weak());
+                }
+                ui.horizontal_wrapped(|ui| {
+                    for asset in &state.remote {
+                        if !matches(&asset.path) {
+                            continue;
+                        }
+                        let name = asset.path.rsplit('/').next().unwrap_or(&asset.path).to_string();
+                        let image = asset.image.clone().map(|bytes| {
+                            egui::Image::from_bytes(format!("bytes://remote_cache/{}?{}", asset.path, asset.size), bytes)
+                        });
+                        let hover = format!("_remote_cache/{} ({} KB)", asset.path, asset.size.div_ceil(1024));
+                        if let Some(asset) = tile(ui, AssetRef::Remote(asset.path.clone()), &name, image, hover) {
+                            *action = AssetAction::Spawn(asset);
+                        }
+                    }
+                });
+            });
+    });
+}
@@ -1,5 +1,7 @@
 use crate::agent::AgentProfile;
+use crate::llm::usage::{UsageTotals, UsageTracker};
 use crate::llm::MessageContent;
+use crate::personas::PersonaRegistry;
 // use crate::types::{Plan, PlanStatus}; // Removed
 use base64::prelude::*;
 use eframe::egui;
@@ -7,17 +9,133 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 
 pub enum ChatAction {
+    /// Write a code block to a file; `path` is the hint from the fence, if any.
+    ApplyCode { code: String, path: Option<String> },
+    StopGeneration,
+    /// Load a user message into the input; sending replaces it and drops
+    /// everything after it.
+    EditMessage(usize),
+    /// Answer again from the user message before this entry, dropping the
+    /// entries after that message.
+    Regenerate(usize),
     None,
 }
 
+/// Share of the context window above which the header warns.
+const CONTEXT_WARNING: f32 = 0.8;
+
+/// Per-frame state the chat is drawn with.
+pub struct ChatView<'a> {
+    pub user_color: egui::Color32,
+    pub generating: bool,
+    /// Tokens and cost of the requests behind each reply, by history index.
+    pub message_usage: Option<&'a HashMap<usize, UsageTotals>>,
+    pub personas: &'a PersonaRegistry,
+}
+
+/// Session cost, and how full the context window was on the last request.
+pub fn render_chat_header(ui: &mut egui::Ui, usage: &UsageTracker, context: Option<(u64, usize)>) {
+    ui.horizontal(|ui| {
+        render_cost_meter(ui, usage);
+
+        let Some((used, window)) = context else {
+            return;
+        };
+        let fill = used as f32 / window.max(1) as f32;
+        ui.separator();
+        ui.label(egui::RichText::new("Context").weak());
+        let color = if fill >= CONTEXT_WARNING { egui::Color32::from_rgb(255, 140, 0) } else { ui.visuals().selection.bg_fill };
+        ui.add(
+            egui::ProgressBar::new(fill.min(1.0))
+                .desired_width(120.0)
+                .fill(color)
+                .text(format!("{:.1}k / {}k", used as f64 / 1000.0, window / 1000)),
+        );
+        if fill >= CONTEXT_WARNING {
+            ui.label(egui::RichText::new("⚠ Near the context limit; older turns will be trimmed").color(color))
+                .on_hover_text("Start a new channel or clear the chat to keep full history in view.");
+        }
+    });
+}
+
+fn render_cost_meter(ui: &mut egui::Ui, usage: &UsageTracker) {
+    let session = &usage.session;
+    let label = ui.label(
+        egui::RichText::new(format!(
+            "💰 ${:.4} · {:.1}k tok",
+            session.cost_usd,
+            session.total_tokens() as f64 / 1000.0
+        ))
+        .weak(),
+    );
+
+    let mut profiles: Vec<_> = usage.per_profile.iter().collect();
+    profiles.sort_by(|a, b| a.0.cmp(b.0));
+    let mut details = format!(
+        "Session: {} in ({} cached) / {} out tokens",
+        session.input_tokens, session.cached_input_tokens, session.output_tokens
+    );
+    for (name, totals) in profiles {
+        details.push_str(&format!(
+            "\n{}: ${:.4} ({} in / {} out)",
+            name, totals.cost_usd, totals.input_tokens, totals.output_tokens
+        ));
+    }
+    details.push_str(&format!("\nToday: ${:.4}", usage.today.on(chrono::Local::now().date_naive())));
+    label.on_hover_text(details);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum BudgetChoice {
+    Continue,
+    Cancel,
+}
+
+/// Card asking whether to keep making model requests past a budget.
+pub fn render_budget_card(ui: &mut egui::Ui, message: &str) -> Option<BudgetChoice> {
+    let mut choice = None;
+    ui.group(|ui| {
+        ui.label(egui::RichText::new("💰 Budget reached").strong().color(egui::Color32::GOLD));
+        ui.label(format!("{}. Model requests are on hold.", message));
+        ui.horizontal(|ui| {
+            if ui.button("Continue anyway").on_hover_text("Allow further requests for the rest of this session").clicked() {
+                choice = Some(BudgetChoice::Continue);
+            }
+            if ui.button("Cancel").clicked() {
+                choice = Some(BudgetChoice::Cancel);
+            }
+        });
+    });
+    choice
+}
+
+/// Name color for a chat role: the configured profile or persona color,
+/// otherwise a hue derived from the name so each role stays recognizable.
+fn role_color(ui: &egui::Ui, role: &str, profiles: &[AgentProfile], view: &ChatView) -> egui::Color32 {
+    match role {
+        "Cats2333" => view.user_color,
+        "System" | "Error" => ui.visuals().error_fg_color,
+        _ => match view.personas.color(role, profiles) {
+            Some([r, g, b]) => egui::Color32::from_rgb(r, g, b),
+            None => {
+                let hash = role.bytes().fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32));
+                let value = if ui.visuals().dark_mode { 0.95 } else { 0.6 };
+                egui::ecolor::Hsva::new((hash % 360) as f32 / 360.0, 0.6, value, 1.0).into()
+            }
+        },
+    }
+}
+
 pub fn render_chat(
     ui: &mut egui::Ui,
     ctx: &egui::Context,
     chat_history: &Vec<(String, MessageContent)>,
     available_profiles: &[AgentProfile],
     image_textures: &mut HashMap<(usize, usize), egui::TextureHandle>,
+    view: &ChatView,
 ) -> ChatAction {
-    let action = ChatAction::None;
+    let mut action = ChatAction::None;
+    let generating = view.generating;
 
     ui.vertical(|ui| {
         ui.add_space(10.0);
@@ -27,37 +145,7 @@ pub fn render_chat(
         for (msg_idx, (role, content)) in chat_history.iter().enumerate() {
             ui.group(|ui| {
                 ui.horizontal(|ui| {
-                    // Avatar Logic
-                    let role_lower = role.to_lowercase();
-                    let avatar_path = if role_lower.contains("cats2333") {
-                        "assets/avatars/cat.png".to_string()
-                    } else if role_lower.contains("system") || role_lower.contains("error") {
-                        "assets/avatars/system.png".to_string()
-                    } else if role_lower.contains("brock") || role_lower.contains("小刚") {
-                        "assets/avatars/Brock.png".to_string()
-                    } else if role_lower.contains("misty") || role_lower.contains("小霞") {
-                        "assets/avatars/Misty.png".to_string()
-                    } else if role_lower.contains("surge") || role_lower.contains("马志士") {
-                        "assets/avatars/Surge.png".to_string()
-                    } else if role_lower.contains("erika") || role_lower.contains("莉佳") {
-                        "assets/avatars/Erika.png".to_string()
-                    } else if role_lower.contains("koga") || role_lower.contains("阿桔") {
-                        "assets/avatars/Koga.png".to_string()
-                    } else if role_lower.contains("sabrina") || role_lower.contains("娜姿") {
-                        "assets/avatars/Sabrina.png".to_string()
-                    } else if role_lower.contains("blaine") || role_lower.contains("夏伯") {
-                        "assets/avatars/Blaine.png".to_string()
-                    } else if role_lower.contains("giovanni") || role_lower.contains("坂木") {
-                        "assets/avatars/Giovanni.png".to_string()
-                    } else {
-                        // Dynamic lookup for Agent profiles
-                        if let Some(profile) = available_profiles.iter().find(|p| p.name == *role) {
-                            format!("assets/avatars/{}", profile.avatar_path)
-                        } else {
-                            format!("assets/avatars/{}.png", role)
-                        }
-                    };
-
+                    let avatar_path = format!("assets/avatars/{}", view.personas.avatar(role, available_profiles));
                     let abs_path = current_dir.join(&avatar_path);
 
                     if abs_path.exists() {
@@ -86,49 +174,38 @@ pub fn render_chat(
                         }
                     }
 
-                    // Role Name Colors
-                    let role_lower_color = role.to_lowercase();
-                    let color = if role_lower_color.contains("cats2333") {
-                        egui::Color32::LIGHT_BLUE
-                    } else if role_lower_color.contains("system")
-                        || role_lower_color.contains("error")
-                    {
-                        egui::Color32::RED
-                    } else if role_lower_color.contains("brock")
-                        || role_lower_color.contains("小刚")
-                    {
-                        egui::Color32::from_rgb(168, 168, 120) // Rock Gray/Brown
-                    } else if role_lower_color.contains("misty")
-                        || role_lower_color.contains("小霞")
-                    {
-                        egui::Color32::from_rgb(104, 144, 240) // Water Blue
-                    } else if role_lower_color.contains("surge")
-                        || role_lower_color.contains("马志士")
-                    {
-                        egui::Color32::from_rgb(248, 208, 48) // Electric Yellow
-                    } else if role_lower_color.contains("erika")
-                        || role_lower_color.contains("莉佳")
-                    {
-                        egui::Color32::from_rgb(120, 200, 80) // Grass Green
-                    } else if role_lower_color.contains("koga") || role_lower_color.contains("阿桔")
-                    {
-                        egui::Color32::from_rgb(160, 64, 160) // Poison Purple
-                    } else if role_lower_color.contains("sabrina")
-                        || role_lower_color.contains("娜姿")
-                    {
-                        egui::Color32::from_rgb(248, 88, 136) // Psychic Pink
-                    } else if role_lower_color.contains("blaine")
-                        || role_lower_color.contains("夏伯")
-                    {
-                        egui::Color32::from_rgb(240, 128, 48) // Fire Orange
-                    } else if role_lower_color.contains("giovanni")
-                        || role_lower_color.contains("坂木")
-                    {
-                        egui::Color32::from_rgb(224, 192, 104) // Ground Brown
-                    } else {
-                        egui::Color32::from_rgb(255, 105, 180) // Hot Pink for others
-                    };
+                    let color = role_color(ui, role, available_profiles, view);
                     ui.label(egui::RichText::new(role).strong().color(color));
+
+                    if let Some(totals) = view.message_usage.and_then(|usage| usage.get(&msg_idx)) {
+                        ui.label(
+                            egui::RichText::new(format!("{} ↑ {} ↓ · ${:.4}", totals.input_tokens, totals.output_tokens, totals.cost_usd))
+                                .weak()
+                                .small(),
+                        )
+                        .on_hover_text(format!(
+                            "Input {} ({} cached), output {} tokens, over every request behind this reply",
+                            totals.input_tokens, totals.cached_input_tokens, totals.output_tokens
+                        ));
+                    }
+
+                    if !generating {
+                        let is_last = msg_idx + 1 == chat_history.len();
+                        let is_assistant = !matches!(role.as_str(), "Cats2333" | "System" | "Error");
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if role == "Cats2333" && matches!(content, MessageContent::Text(_))
+                                && ui.small_button("✏").on_hover_text("Edit and regenerate from here").clicked()
+                            {
+                                action = ChatAction::EditMessage(msg_idx);
+                            }
+                            if role == "Error" && ui.small_button("🔄 Retry").clicked() {
+                                action = ChatAction::Regenerate(msg_idx);
+                            }
+                            if is_assistant && is_last && ui.small_button("🔄").on_hover_text("Regenerate").clicked() {
+                                action = ChatAction::Regenerate(msg_idx);
+                            }
+                        });
+                    }
                 });
 
                 match content {
@@ -152,14 +229,22 @@ pub fn render_chat(
                                         .color(egui::Color32::LIGHT_BLUE),
                                 );
                             });
-                        } else {
-                            ui.label(text);
+                        } else if let Some((tool, output)) = crate::export::parse_tool_result(text).filter(|_| role == "System") {
+                            egui::CollapsingHeader::new(egui::RichText::new(format!("Result: {}", tool)).weak())
+                                .id_salt(("tool_result", msg_idx))
+                                .show(ui, |ui| {
+                                    ui.label(egui::RichText::new(output).monospace());
+                                });
+                        } else if let Some((code, path)) = super::code_block::render_text(ui, text) {
+                            action = ChatAction::ApplyCode { code, path };
                         }
                     }
                     MessageContent::Parts(parts) => {
                         for (part_idx, part) in parts.iter().enumerate() {
                             if let Some(text) = &part.text {
-                                ui.label(text);
+                                if let Some((code, path)) = super::code_block::render_text(ui, text) {
+                                    action = ChatAction::ApplyCode { code, path };
+                                }
                             }
                             if let Some(image_url) = &part.image_url {
                                 let texture_key = (msg_idx, part_idx);
@@ -167,18 +252,10 @@ pub fn render_chat(
                                 // Load texture if not in cache
                                 if !image_textures.contains_key(&texture_key) {
                                     // Attempt to decode base64 image
-                                    let clean_url = image_url.url.trim();
-                                    let base64_data = if let Some(data) =
-                                        clean_url.strip_prefix("data:image/png;base64,")
-                                    {
-                                        Some(data)
-                                    } else if let Some(data) =
-                                        clean_url.strip_prefix("data:image/jpeg;base64,")
-                                    {
-                                        Some(data)
-                                    } else {
-                                        None
-                                    };
+                                    let base64_data = image_url
+                                        .as_base64()
+                                        .filter(|(mime, _)| mime.starts_with("image/"))
+                                        .map(|(_, data)| data);
 
                                     if let Some(data) = base64_data {
                                         let clean_data: String =
@@ -230,6 +307,25 @@ pub fn render_chat(
             ui.add_space(5.0);
         }
 
+        if generating {
+            // Tokens are appended to the last message as they arrive
+            let streaming = chat_history
+                .last()
+                .is_some_and(|(role, _)| !matches!(role.as_str(), "Cats2333" | "System" | "Error"));
+            ui.horizontal(|ui| {
+                ui.spinner();
+                let status = if streaming { "Generating…" } else { "Waiting for the model…" };
+                ui.label(egui::RichText::new(status).weak());
+                if ui
+                    .button(egui::RichText::new("⏹ Stop").color(egui::Color32::RED))
+                    .on_hover_text("Cancel the response; text received so far is kept")
+                    .clicked()
+                {
+                    action = ChatAction::StopGeneration;
+                }
+            });
+        }
+
         // --- Render Active Plan Card (Removed in Single-Agent Mode) ---
     });
 
@@ -0,0 +1,132 @@
+//! Fenced code blocks in chat messages: highlighted, with Copy and Apply.
+
+use eframe::egui;
+use egui_extras::syntax_highlighting::{highlight, CodeTheme};
+
+/// A piece of message text: prose, or the contents of a ``` fence.
+#[derive(Debug, PartialEq)]
+pub enum Segment<'a> {
+    Text(&'a str),
+    Code {
+        language: &'a str,
+        /// File path given after the language (```` ```rust src/main.rs ````), if any.
+        path: Option<&'a str>,
+        code: &'a str,
+    },
+}
+
+/// Split `text` at fenced code blocks. An unterminated fence (still
+/// streaming) runs to the end of the text.
+pub fn split_fenced(text: &str) -> Vec<Segment<'_>> {
+    let mut segments = Vec::new();
+    let mut rest = text;
+
+    while let Some(start) = find_fence(rest) {
+        if start > 0 {
+            segments.push(Segment::Text(&rest[..start]));
+        }
+        let after_fence = &rest[start + 3..];
+        let (info, body) = after_fence.split_once('\n').unwrap_or((after_fence, ""));
+        let (code, remainder) = match find_fence(body) {
+            // Whatever follows the closing fence on its line is dropped
+            Some(end) => (&body[..end], body[end..].split_once('\n').map_or("", |(_, r)| r)),
+            None => (body, ""),
+        };
+
+        let mut info_words = info.split_whitespace();
+        let first = info_words.next().unwrap_or("");
+        // Also accept ```rust:src/main.rs
+        let (language, path) = match first.split_once(':') {
+            Some((language, path)) if !path.is_empty() => (language, Some(path)),
+            _ => (first, info_words.next()),
+        };
+
+        segments.push(Segment::Code {
+            language,
+            path,
+            code: code.trim_end_matches([' ', '\t']).strip_suffix('\n').unwrap_or(code),
+        });
+        rest = remainder;
+    }
+
+    if !rest.is_empty() {
+        segments.push(Segment::Text(rest));
+    }
+    segments
+}
+
+/// Byte offset of the next ``` at the start of a line.
+fn find_fence(text: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.trim_start().starts_with("```") {
+            return Some(offset + (line.len() - line.trim_start().len()));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Render message text, drawing code blocks with highlighting. Returns the
+/// code block whose "Apply to file…" button was clicked, with its path hint.
+pub fn render_text(ui: &mut egui::Ui, text: &str) -> Option<(String, Option<String>)> {
+    let mut apply = None;
+
+    for segment in split_fenced(text) {
+        match segment {
+            Segment::Text(text) => {
+                let text = text.trim_start_matches('\n').trim_end();
+                if !text.is_empty() {
+                    ui.label(text);
+                }
+            }
+            Segment::Code { language, path, code } => {
+                if render_code_block(ui, language, path, code) {
+                    apply = Some((code.to_string(), path.map(str::to_string)));
+                }
+            }
+        }
+    }
+    apply
+}
+
+/// Returns true when "Apply to file…" was clicked.
+fn render_code_block(ui: &mut egui::Ui, language: &str, path: Option<&str>, code: &str) -> bool {
+    let mut apply = false;
+    let theme = CodeTheme::from_style(ui.style());
+
+    egui::Frame::none()
+        .fill(ui.visuals().extreme_bg_color)
+        .rounding(4.0)
+        .inner_margin(6.0)
+        .show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let title = match path {
+                    Some(path) => format!("{} · {}", language, path),
+                    None => language.to_string(),
+                };
+                ui.label(egui::RichText::new(title).weak().small());
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    // Diffs need a patch tool, not a plain overwrite
+                    let is_diff = matches!(language, "diff" | "patch");
+                    if !is_diff && ui.small_button("📝 Apply to file…").on_hover_text("Write this block to a file").clicked() {
+                        apply = true;
+                    }
+                    if ui.small_button("📋 Copy").clicked() {
+                        ui.ctx().copy_text(code.to_string());
+                    }
+                });
+            });
+
+            let language = if language.is_empty() { "rs" } else { language };
+            let job = highlight(ui.ctx(), ui.style(), &theme, code, language);
+            egui::ScrollArea::horizontal()
+                .id_salt(ui.next_auto_id())
+                .show(ui, |ui| {
+                    ui.add(egui::Label::new(job).selectable(true).extend());
+                });
+        });
+
+    apply
+}
@@ -0,0 +1,148 @@
+use bevy_bridge_core::discovery::DiscoveredGame;
+use eframe::egui;
+use std::time::Duration;
+
+pub enum ConnectionAction {
+    /// Use this endpoint and token for BRP.
+    Connect { endpoint: String, token: String },
+    /// Listen for games announcing themselves on the network.
+    Scan,
+    None,
+}
+
+#[derive(Default)]
+pub struct ConnectionState {
+    pub open: bool,
+    host: String,
+    port: String,
+    token: String,
+    pub found: Vec<DiscoveredGame>,
+    pub scanning: bool,
+    pub error: Option<String>,
+}
+
+impl ConnectionState {
+    /// Open on the endpoint and token in use.
+    pub fn show(&mut self, endpoint: &str, token: &str) {
+        let (host, port) = split_endpoint(endpoint);
+        self.host = host;
+        self.port = port;
+        self.token = token.to_string();
+        self.error = None;
+        self.open = true;
+    }
+
+    pub fn receive(&mut self, result: Result<Vec<DiscoveredGame>, String>) {
+        self.scanning = false;
+        match result {
+            Ok(found) => {
+                self.found = found;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+/// Host and port of an `http://host:port` endpoint.
+fn split_endpoint(endpoint: &str) -> (String, String) {
+    let rest = endpoint.trim().trim_start_matches("http://").trim_start_matches("https://");
+    let rest = rest.split('/').next().unwrap_or_default();
+    match rest.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host.to_string(), port.to_string()),
+        _ => (rest.to_string(), bevy_bridge_core::config::DEFAULT_PORT.to_string()),
+    }
+}
+
+/// `latency` and `reachable` are from the last ping of the current endpoint.
+pub fn render_connection(
+    ctx: &egui::Context,
+    state: &mut ConnectionState,
+    latency: Option<Duration>,
+    reachable: Option<bool>,
+) -> ConnectionAction {
+    let mut action = ConnectionAction::None;
+    if !state.open {
+        return action;
+    }
+
+    let mut open = true;
+    egui::Window::new("🔌 Connect to game")
+        .open(&mut open)
+        .default_width(380.0)
+        .show(ctx, |ui| {
+            egui::Grid::new("connection_grid").num_columns(2).show(ui, |ui| {
+                ui.label("Host");
+                ui.add(egui::TextEdit::singleline(&mut state.host).hint_text("127.0.0.1 or 192.168.1.40"));
+                ui.end_row();
+                ui.label("Port");
+                ui.add(egui::TextEdit::singleline(&mut state.port).desired_width(80.0));
+                ui.end_row();
+                ui.label("Token");
+                ui.add(egui::TextEdit::singleline(&mut state.token).password(true).hint_text("if the game requires one"));
+                ui.end_row();
+            });
+
+            ui.horizontal(|ui| {
+                let port = state.port.trim().parse::<u16>();
+                let valid = !state.host.trim().is_empty() && port.is_ok();
+                if ui.add_enabled(valid, egui::Button::new("Connect")).clicked() {
+                    if let Ok(port) = port {
+                        action = ConnectionAction::Connect {
+                            endpoint: format!("http://{}:{}", state.host.trim(), port),
+                            token: state.token.trim().to_string(),
+                        };
+                    }
+                }
+                match (reachable, latency) {
+                    (Some(true), Some(latency)) => {
+                        ui.label(format!("Connected, {} ms round trip", latency.as_millis()));
+                    }
+                    (Some(false), _) => {
+                        ui.colored_label(egui::Color32::from_rgb(230, 80, 80), "Not reachable");
+                    }
+                    _ => {}
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong("Games on the network");
+                if state.scanning {
+                    ui.spinner();
+                } else if ui.button("🔍 Scan").on_hover_text("Listen for games started with BRP_BIND").clicked() {
+                    state.scanning = true;
+                    action = ConnectionAction::Scan;
+                }
+            });
+            if let Some(error) = &state.error {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), error);
+            }
+            if state.found.is_empty() && !state.scanning {
+                ui.label(egui::RichText::new("None found yet. Games listening beyond localhost announce themselves every few seconds.").weak());
+            }
+            let mut picked = None;
+            for game in &state.found {
+                ui.horizontal(|ui| {
+                    if ui.button("Use").clicked() {
+                        picked = Some(game.endpoint.clone());
+                    }
+                    ui.label(&game.name);
+                    ui.label(egui::RichText::new(&game.endpoint).weak());
+                    if game.token_required {
+                        ui.label("🔒").on_hover_text("Requires the game's token");
+                    }
+                });
+            }
+            if let Some(endpoint) = picked {
+                let (host, port) = split_endpoint(&endpoint);
+                state.host = host;
+                state.port = port;
+            }
+        });
+
+    if !open {
+        state.open = false;
+    }
+    action
+}
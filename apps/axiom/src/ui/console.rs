@@ -0,0 +1,153 @@
+use crate::console::LogEntry;
+use eframe::egui;
+use std::collections::{BTreeSet, VecDeque};
+use tracing::Level;
+
+/// Entries kept in the panel; the oldest are dropped first.
+const MAX_ENTRIES: usize = 5000;
+
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+/// Bottom log panel fed by `console::drain`.
+pub struct ConsoleState {
+    pub open: bool,
+    /// Shown in its own window instead of the bottom panel.
+    pub detached: bool,
+    entries: VecDeque<LogEntry>,
+    /// Every source seen so far, for the filter combo box.
+    sources: BTreeSet<String>,
+    /// Shown levels, in `LEVELS` order.
+    show_levels: [bool; 5],
+    /// `None` shows all sources.
+    source: Option<String>,
+    search: String,
+    autoscroll: bool,
+}
+
+impl Default for ConsoleState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            detached: false,
+            entries: VecDeque::new(),
+            sources: BTreeSet::new(),
+            show_levels: [true, true, true, false, false],
+            source: None,
+            search: String::new(),
+            autoscroll: true,
+        }
+    }
+}
+
+impl ConsoleState {
+    /// Take in entries logged since the last frame; returns those logged
+    /// with `notify = true`.
+    pub fn poll(&mut self) -> Vec<LogEntry> {
+        let mut notify = Vec::new();
+        for entry in crate::console::drain() {
+            if entry.notify {
+                notify.push(entry.clone());
+            }
+            if !self.sources.contains(&entry.source) {
+                self.sources.insert(entry.source.clone());
+            }
+            if self.entries.len() >= MAX_ENTRIES {
+                self.entries.pop_front();
+            }
+            self.entries.push_back(entry);
+        }
+        notify
+    }
+
+    fn level_shown(&self, level: Level) -> bool {
+        LEVELS.iter().position(|l| *l == level).is_some_and(|i| self.show_levels[i])
+    }
+
+    fn matches(&self, entry: &LogEntry, search: &str) -> bool {
+        self.level_shown(entry.level)
+            && self.source.as_ref().is_none_or(|s| *s == entry.source)
+            && (search.is_empty() || entry.message.to_lowercase().contains(search))
+    }
+}
+
+fn level_color(level: Level) -> egui::Color32 {
+    match level {
+        Level::ERROR => egui::Color32::RED,
+        Level::WARN => egui::Color32::from_rgb(255, 140, 0),
+        Level::INFO => egui::Color32::LIGHT_BLUE,
+        _ => egui::Color32::GRAY,
+    }
+}
+
+pub fn render_console(ctx: &egui::Context, state: &mut ConsoleState) {
+    if !state.open {
+        return;
+    }
+
+    if state.detached {
+        if crate::ui::viewport::show_detached(ctx, "console", "🖥 Console", [900.0, 320.0], |ui| render_contents(ui, state)) {
+            state.detached = false;
+        }
+        return;
+    }
+    egui::TopBottomPanel::bottom("console_panel")
+        .resizable(true)
+        .default_height(200.0)
+        .min_height(80.0)
+        .show(ctx, |ui| render_contents(ui, state));
+}
+
+fn render_contents(ui: &mut egui::Ui, state: &mut ConsoleState) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new("🖥 Console").strong());
+        ui.separator();
+
+        for (level, shown) in LEVELS.iter().zip(state.show_levels.iter_mut()) {
+            ui.checkbox(shown, egui::RichText::new(level.as_str()).color(level_color(*level)));
+        }
+        ui.separator();
+
+        egui::ComboBox::from_id_salt("console_source")
+            .selected_text(state.source.as_deref().unwrap_or("All sources"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut state.source, None, "All sources");
+                for source in &state.sources {
+                    ui.selectable_value(&mut state.source, Some(source.clone()), source);
+                }
+            });
+
+        ui.add(egui::TextEdit::singleline(&mut state.search).hint_text("🔍 Search").desired_width(160.0));
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            crate::ui::viewport::detach_button(ui, &mut state.detached);
+            if ui.button("🗑 Clear").clicked() {
+                state.entries.clear();
+            }
+            ui.checkbox(&mut state.autoscroll, "Autoscroll");
+        });
+    });
+    ui.separator();
+
+    let search = state.search.to_lowercase();
+    let visible: Vec<&LogEntry> = state.entries.iter().filter(|e| state.matches(e, &search)).collect();
+    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+
+    egui::ScrollArea::vertical()
+        .auto_shrink([false; 2])
+        .stick_to_bottom(state.autoscroll)
+        .show_rows(ui, row_height, visible.len(), |ui, range| {
+            for entry in &visible[range] {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(entry.time.format("%H:%M:%S%.3f").to_string()).monospace().weak());
+                    ui.label(
+                        egui::RichText::new(format!("{:<5}", entry.level.as_str()))
+                            .monospace()
+                            .color(level_color(entry.level)),
+                    );
+                    ui.label(egui::RichText::new(format!("[{}]", entry.source)).monospace().weak());
+                    ui.add(egui::Label::new(egui::RichText::new(&entry.message).monospace()).truncate())
+                        .on_hover_text(&entry.message);
+                });
+            }
+        });
+}
@@ -0,0 +1,113 @@
+use anyhow::{bail, Context, Result};
+use eframe::egui;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+enum Body {
+    Text(String),
+    Image,
+    Model,
+    Diff { head: String, current: String },
+    Error(String),
+}
+
+/// Window showing a file from the explorer, or its diff against git HEAD.
+pub struct FilePreviewState {
+    pub open: bool,
+    path: PathBuf,
+    body: Body,
+}
+
+impl Default for FilePreviewState {
+    fn default() -> Self {
+        Self { open: false, path: PathBuf::new(), body: Body::Text(String::new()) }
+    }
+}
+
+impl FilePreviewState {
+    pub fn show_file(&mut self, path: &Path) {
+        self.body = if crate::llm::image::is_image_path(path) {
+            Body::Image
+        } else if crate::attachments::is_model_path(path) {
+            Body::Model
+        } else {
+            match crate::attachments::read_text(path) {
+                Ok(file) => Body::Text(file.content),
+                Err(e) => Body::Error(format!("{:#}", e)),
+            }
+        };
+        self.path = path.to_path_buf();
+        self.open = true;
+    }
+
+    pub fn show_diff(&mut self, path: &Path) {
+        self.body = match git_head_version(path) {
+            Ok(head) => {
+                let current = std::fs::read_to_string(path).unwrap_or_default();
+                Body::Diff { head, current }
+            }
+            Err(e) => Body::Error(format!("{:#}", e)),
+        };
+        self.path = path.to_path_buf();
+        self.open = true;
+    }
+}
+
+/// The file as committed in HEAD of the repository containing it.
+fn git_head_version(path: &Path) -> Result<String> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        bail!("Not a file: {}", path.display());
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("show")
+        .arg(format!("HEAD:./{}", name.to_string_lossy()))
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    String::from_utf8(output.stdout).context("The committed version is not UTF-8 text")
+}
+
+pub fn render_file_preview(ctx: &egui::Context, state: &mut FilePreviewState) {
+    if !state.open {
+        return;
+    }
+
+    let title = match state.body {
+        Body::Diff { .. } => format!("± {}", state.path.display()),
+        _ => format!("👁 {}", state.path.display()),
+    };
+    let mut open = state.open;
+    egui::Window::new(title)
+        .id(egui::Id::new("file_preview"))
+        .open(&mut open)
+        .default_size([640.0, 480.0])
+        .show(ctx, |ui| match &mut state.body {
+            Body::Text(text) => {
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut text.as_str())
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+            }
+            Body::Image => {
+                let uri = format!("file://{}", state.path.display().to_string().replace('\\', "/"));
+                egui::ScrollArea::both().show(ui, |ui| {
+                    ui.add(egui::Image::from_uri(uri).max_width(ui.available_width()));
+                });
+            }
+            Body::Model => {
+                ui.label("3D model; use ⬆ Upload to game from the context menu to view it in the game.");
+            }
+            Body::Diff { head, current } => crate::ui::review::render_text_diff(ui, head, current),
+            Body::Error(error) => {
+                ui.colored_label(egui::Color32::RED, error.as_str());
+            }
+        });
+    state.open = open;
+}
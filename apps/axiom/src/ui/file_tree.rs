@@ -1,8 +1,19 @@
 use eframe::egui;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+pub enum FileTreeAction {
+    Preview(PathBuf),
+    /// Append the path to the chat input.
+    SendPath(PathBuf),
+    /// Attach the file to the next message.
+    Read(PathBuf),
+    DiffWithGit(PathBuf),
+    UploadToGame(PathBuf),
+    None,
+}
+
 pub struct FileTreeState {
     pub root_path: PathBuf,
     pub input_path: String, // Buffer for the path input box
@@ -12,6 +23,11 @@ pub struct FileTreeState {
     // If a file is in 'selected_files', we check this map.
     // true = Modify (Target), false = Reference (Context only)
     pub selection_modes: std::collections::HashMap<PathBuf, bool>,
+    /// Directory listings (path, is_dir), dropped when the watcher reports a change.
+    listings: HashMap<PathBuf, Vec<(PathBuf, bool)>>,
+    revision: u64,
+    /// Shown in its own window instead of the left panel.
+    pub detached: bool,
 }
 
 impl Default for FileTreeState {
@@ -40,12 +56,28 @@ impl Default for FileTreeState {
             selected_files: HashSet::new(),
             expanded_paths: expanded,
             selection_modes: std::collections::HashMap::new(),
+            listings: HashMap::new(),
+            revision: 0,
+            detached: false,
         }
     }
 }
 
-pub fn render_file_tree(ui: &mut egui::Ui, state: &mut FileTreeState) {
-    ui.heading("📂 Project Files");
+/// `revision` comes from the workspace watcher; cached listings are reread
+/// when it changes.
+pub fn render_file_tree(ui: &mut egui::Ui, state: &mut FileTreeState, revision: u64) -> FileTreeAction {
+    if revision != state.revision {
+        state.revision = revision;
+        state.listings.clear();
+    }
+    let mut action = FileTreeAction::None;
+
+    ui.horizontal(|ui| {
+        ui.heading("📂 Project Files");
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            crate::ui::viewport::detach_button(ui, &mut state.detached);
+        });
+    });
     ui.separator();
 
     // Root Path Input Area
@@ -89,21 +121,38 @@ pub fn render_file_tree(ui: &mut egui::Ui, state: &mut FileTreeState) {
         .show(ui, |ui| {
             let root = state.root_path.clone();
             if root.exists() {
-                render_path_node(ui, &root, state);
+                render_path_node(ui, &root, true, state, &mut action);
             } else {
                 ui.label("Invalid root path");
             }
         });
+
+    action
 }
 
-fn render_path_node(ui: &mut egui::Ui, path: &Path, state: &mut FileTreeState) {
+fn list_dir(path: &Path) -> Vec<(PathBuf, bool)> {
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut entries: Vec<(PathBuf, bool)> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            !name.starts_with('.') && name != "target"
+        })
+        .map(|e| (e.path(), e.path().is_dir()))
+        .collect();
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.file_name().cmp(&b.0.file_name())));
+    entries
+}
+
+fn render_path_node(ui: &mut egui::Ui, path: &Path, is_dir: bool, state: &mut FileTreeState, action: &mut FileTreeAction) {
     let file_name = path
         .file_name()
         .unwrap_or_default()
         .to_string_lossy()
         .to_string();
 
-    let is_dir = path.is_dir();
     let path_buf = path.to_path_buf();
 
     if is_dir {
@@ -124,24 +173,13 @@ fn render_path_node(ui: &mut egui::Ui, path: &Path, state: &mut FileTreeState) {
 
         if is_expanded {
             ui.indent(path.to_string_lossy(), |ui| {
-                if let Ok(entries) = fs::read_dir(path) {
-                    let mut entries_vec: Vec<_> = entries.filter_map(|e| e.ok()).collect();
-                    entries_vec.sort_by(|a, b| {
-                        let a_dir = a.path().is_dir();
-                        let b_dir = b.path().is_dir();
-                        if a_dir == b_dir {
-                            a.file_name().cmp(&b.file_name())
-                        } else {
-                            b_dir.cmp(&a_dir)
-                        }
-                    });
-
-                    for entry in entries_vec {
-                        let name = entry.file_name().to_string_lossy().to_string();
-                        if !name.starts_with('.') && name != "target" {
-                            render_path_node(ui, &entry.path(), state);
-                        }
-                    }
+                let entries = state
+                    .listings
+                    .entry(path_buf.clone())
+                    .or_insert_with(|| list_dir(path))
+                    .clone();
+                for (entry, entry_is_dir) in entries {
+                    render_path_node(ui, &entry, entry_is_dir, state, action);
                 }
             });
         }
@@ -152,7 +190,7 @@ fn render_path_node(ui: &mut egui::Ui, path: &Path, state: &mut FileTreeState) {
             let is_selected = state.selected_files.contains(&path_buf);
             let mut checked = is_selected;
 
-            if ui.checkbox(&mut checked, &file_name).changed() {
+            if ui.checkbox(&mut checked, "").on_hover_text("Include in 🚀 Ingest Context").changed() {
                 if checked {
                     state.selected_files.insert(path_buf.clone());
                     // Default to Reference mode (safer) if new
@@ -184,6 +222,28 @@ fn render_path_node(ui: &mut egui::Ui, path: &Path, state: &mut FileTreeState) {
                     state.selection_modes.insert(path_buf.clone(), !is_modify);
                 }
             }
+
+            let name = ui
+                .add(egui::Label::new(&file_name).sense(egui::Sense::click()))
+                .on_hover_text("Click to preview, right-click for more");
+            if name.clicked() {
+                *action = FileTreeAction::Preview(path_buf.clone());
+            }
+            name.context_menu(|ui| {
+                let mut pick = |ui: &mut egui::Ui, label: &str, chosen: FileTreeAction| {
+                    if ui.button(label).clicked() {
+                        *action = chosen;
+                        ui.close_menu();
+                    }
+                };
+                pick(ui, "👁 Preview", FileTreeAction::Preview(path_buf.clone()));
+                pick(ui, "💬 Send path to chat", FileTreeAction::SendPath(path_buf.clone()));
+                pick(ui, "📎 Attach to message", FileTreeAction::Read(path_buf.clone()));
+                pick(ui, "± Diff vs git HEAD", FileTreeAction::DiffWithGit(path_buf.clone()));
+                if crate::attachments::is_model_path(&path_buf) {
+                    pick(ui, "⬆ Upload to game", FileTreeAction::UploadToGame(path_buf.clone()));
+                }
+            });
         });
     }
 }
@@ -1,21 +1,46 @@
 use crate::agent::AgentProfile;
+use crate::attachments::Attachments;
 use eframe::egui;
 
 pub enum InputAction {
     Send,
     StopLoading,
     RequestScreenshot,
-    ClearPendingImage,
+    RemoveAttachment(AttachmentKind, usize),
+    /// Upload the dropped model at this index to the game.
+    UploadModel(usize),
+    CancelEdit,
+    SelectModel(String),
+    RefreshModels,
+    #[cfg(feature = "voice")]
+    ToggleRecording,
+    #[cfg(feature = "voice")]
+    ToggleSpeakReplies,
     None,
 }
 
+#[derive(Clone, Copy)]
+pub enum AttachmentKind {
+    Image,
+    File,
+    Model,
+}
+
+/// Models served by the current profile's provider.
+#[derive(Default)]
+pub struct ModelPickerState {
+    pub models: Vec<String>,
+    pub loading: bool,
+}
+
 pub fn render_input_panel(
     ui: &mut egui::Ui,
     input_text: &mut String,
     is_loading: bool,
-    pending_image: &Option<String>,
-    preview_texture: &Option<egui::TextureHandle>,
+    attachments: &Attachments,
     current_profile: &AgentProfile,
+    model_picker: &ModelPickerState,
+    editing: bool,
 ) -> InputAction {
     let mut action = InputAction::None;
 
@@ -23,31 +48,21 @@ pub fn render_input_panel(
     ui.add_space(5.0);
 
     ui.vertical(|ui| {
-        // Show pending image preview if any
-        let mut should_clear = false;
-        if let Some(texture) = preview_texture {
+        if editing {
             ui.horizontal(|ui| {
-                ui.label("Image attached");
-                // Scale height to 80px (User requested smaller)
-                let size = texture.size_vec2();
-                let scale = 80.0 / size.y;
-                ui.image((texture.id(), size * scale));
-                if ui.button("❌").clicked() {
-                    should_clear = true;
-                }
-            });
-        } else if pending_image.is_some() {
-            // Fallback if we have base64 but no texture
-            ui.horizontal(|ui| {
-                ui.label("Image attached (No preview)");
-                if ui.button("❌").clicked() {
-                    should_clear = true;
+                ui.label(
+                    egui::RichText::new("✏ Editing a message; sending replaces it and everything after it")
+                        .small()
+                        .color(egui::Color32::GOLD),
+                );
+                if ui.small_button("❌").on_hover_text("Cancel editing").clicked() {
+                    action = InputAction::CancelEdit;
                 }
             });
         }
 
-        if should_clear {
-            action = InputAction::ClearPendingImage;
+        if let Some(attachment_action) = render_attachments(ui, attachments) {
+            action = attachment_action;
         }
 
         ui.horizontal(|ui| {
@@ -64,6 +79,7 @@ pub fn render_input_panel(
                 ui.spinner();
                 if ui
                     .button(egui::RichText::new("⏹").color(egui::Color32::RED))
+                    .on_hover_text("Stop generating")
                     .clicked()
                 {
                     action = InputAction::StopLoading;
@@ -81,11 +97,40 @@ pub fn render_input_panel(
         });
 
         // Identity label moved to bottom
-        ui.label(
-            egui::RichText::new(format!("🎭 Identity: {}", current_profile.name))
-                .small()
-                .weak(),
-        );
+        ui.horizontal(|ui| {
+            ui.label(
+                egui::RichText::new(format!("🎭 Identity: {}", current_profile.name))
+                    .small()
+                    .weak(),
+            );
+
+            ui.add_space(10.0);
+            egui::ComboBox::from_id_salt("model_picker")
+                .selected_text(egui::RichText::new(&current_profile.model).small())
+                .show_ui(ui, |ui| {
+                    if model_picker.models.is_empty() {
+                        ui.label(egui::RichText::new("No models listed").weak());
+                    }
+                    for model in &model_picker.models {
+                        if ui
+                            .selectable_label(*model == current_profile.model, model)
+                            .clicked()
+                        {
+                            action = InputAction::SelectModel(model.clone());
+                        }
+                    }
+                });
+
+            if model_picker.loading {
+                ui.spinner();
+            } else if ui
+                .small_button("🔄")
+                .on_hover_text("Refresh models from the provider")
+                .clicked()
+            {
+                action = InputAction::RefreshModels;
+            }
+        });
     });
 
     // Add some spacing at the bottom to lift it up
@@ -93,3 +138,76 @@ pub fn render_input_panel(
 
     action
 }
+
+/// Microphone and read-aloud toggles, shown when built with `voice`.
+#[cfg(feature = "voice")]
+pub fn render_voice_controls(ui: &mut egui::Ui, voice: &crate::voice::VoiceState) -> InputAction {
+    let mut action = InputAction::None;
+
+    ui.horizontal(|ui| {
+        let recording = voice.recorder.is_some();
+        let mic = if recording {
+            egui::RichText::new("⏺ Recording… click to send").color(egui::Color32::RED)
+        } else {
+            egui::RichText::new("🎤 Dictate")
+        };
+        if ui.add_enabled(!voice.transcribing, egui::Button::new(mic)).clicked() {
+            action = InputAction::ToggleRecording;
+        }
+        if voice.transcribing {
+            ui.spinner();
+        }
+
+        let mut speak = voice.speak_replies;
+        if ui.checkbox(&mut speak, "🔊 Read replies aloud").changed() {
+            action = InputAction::ToggleSpeakReplies;
+        }
+    });
+
+    action
+}
+
+/// Chips for everything attached to the next message.
+fn render_attachments(ui: &mut egui::Ui, attachments: &Attachments) -> Option<InputAction> {
+    let mut action = None;
+
+    if !attachments.images.is_empty() {
+        ui.horizontal_wrapped(|ui| {
+            for (i, image) in attachments.images.iter().enumerate() {
+                // Scale height to 80px
+                let size = image.texture.size_vec2();
+                let scale = 80.0 / size.y;
+                ui.image((image.texture.id(), size * scale));
+                if ui.small_button("❌").clicked() {
+                    action = Some(InputAction::RemoveAttachment(AttachmentKind::Image, i));
+                }
+            }
+        });
+    }
+
+    for (i, file) in attachments.files.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let name = file.path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            ui.label(format!("📎 {} ({} lines)", name, file.content.lines().count()))
+                .on_hover_text(file.path.display().to_string());
+            if ui.small_button("❌").clicked() {
+                action = Some(InputAction::RemoveAttachment(AttachmentKind::File, i));
+            }
+        });
+    }
+
+    for (i, path) in attachments.models.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let name = path.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+            ui.label(format!("📦 {}", name)).on_hover_text(path.display().to_string());
+            if ui.small_button("⬆ Upload to game").on_hover_text("Spawn it at the origin of the running game").clicked() {
+                action = Some(InputAction::UploadModel(i));
+            }
+            if ui.small_button("❌").clicked() {
+                action = Some(InputAction::RemoveAttachment(AttachmentKind::Model, i));
+            }
+        });
+    }
+
+    action
+}
@@ -0,0 +1,160 @@
+use axiom_agent_core::metrics::{self, Metric, Snapshot};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+const CATEGORIES: &[(&str, &str)] = &[
+    (metrics::TOOL, "🔧 Tools"),
+    (metrics::LLM, "🧠 Model requests"),
+    (metrics::LLM_FIRST_TOKEN, "⏱ Time to first token"),
+    (metrics::BRP, "🎮 BRP requests"),
+];
+
+pub enum MetricsAction {
+    /// Turn recording on or off in the settings.
+    SetEnabled(bool),
+    Export,
+    None,
+}
+
+#[derive(Default)]
+pub struct MetricsPanelState {
+    pub open: bool,
+    snapshot: Option<Snapshot>,
+    refreshed: Option<Instant>,
+    /// Earlier sessions, read when the panel opens.
+    history: Option<Vec<Snapshot>>,
+}
+
+impl MetricsPanelState {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.history = None;
+    }
+}
+
+fn format_secs(secs: u64) -> String {
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m {}s", secs / 60, secs % 60),
+        _ => format!("{}h {}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+fn format_ms(ms: f64) -> String {
+    if ms >= 1000.0 {
+        format!("{:.2}s", ms / 1000.0)
+    } else {
+        format!("{:.0}ms", ms)
+    }
+}
+
+fn metric_grid(ui: &mut egui::Ui, id: &str, rows: &[&Metric]) {
+    egui::Grid::new(id).num_columns(7).striped(true).spacing([12.0, 4.0]).show(ui, |ui| {
+        for header in ["Name", "Calls", "Errors", "Mean", "p50", "p95", "Max"] {
+            ui.strong(header);
+        }
+        ui.end_row();
+        for metric in rows {
+            ui.label(&metric.name);
+            ui.label(metric.count.to_string());
+            let errors = format!("{} ({:.0}%)", metric.errors, metric.error_rate() * 100.0);
+            if metric.errors > 0 {
+                ui.colored_label(egui::Color32::from_rgb(255, 140, 0), errors);
+            } else {
+                ui.label(egui::RichText::new(errors).weak());
+            }
+            for ms in [metric.mean_ms, metric.p50_ms, metric.p95_ms, metric.max_ms] {
+                ui.label(format_ms(ms));
+            }
+            ui.end_row();
+        }
+    });
+}
+
+pub fn render_metrics_panel(ctx: &egui::Context, state: &mut MetricsPanelState) -> MetricsAction {
+    let mut action = MetricsAction::None;
+    if !state.open {
+        return action;
+    }
+    let enabled = metrics::is_enabled();
+    if enabled && state.refreshed.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+        state.snapshot = Some(metrics::snapshot());
+        state.refreshed = Some(Instant::now());
+    }
+    ctx.request_repaint_after(REFRESH_INTERVAL);
+    let history = state.history.get_or_insert_with(crate::metrics::load_history);
+
+    let mut open = true;
+    egui::Window::new("📈 Metrics")
+        .open(&mut open)
+        .default_size([560.0, 420.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                let mut recording = enabled;
+                if ui.checkbox(&mut recording, "Record metrics").on_hover_text("Kept on this machine; saved with the settings").changed() {
+                    action = MetricsAction::SetEnabled(recording);
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.add_enabled(enabled, egui::Button::new("💾 Export…")).clicked() {
+                        action = MetricsAction::Export;
+                    }
+                    if ui.add_enabled(enabled, egui::Button::new("🗑 Reset")).on_hover_text("Clear this session's samples").clicked() {
+                        metrics::reset();
+                        state.refreshed = None;
+                    }
+                });
+            });
+            ui.separator();
+
+            if !enabled {
+                ui.label(
+                    egui::RichText::new("Recording is off. Turn it on to time tool calls, model requests and BRP calls in this editor.")
+                        .weak(),
+                );
+                return;
+            }
+            let Some(snapshot) = &state.snapshot else {
+                return;
+            };
+
+            ui.horizontal(|ui| {
+                ui.label(format!("Session: {}", format_secs(snapshot.session_secs)));
+                let lengths: Vec<u64> = history.iter().map(|s| s.session_secs).collect();
+                if !lengths.is_empty() {
+                    let mean = lengths.iter().sum::<u64>() / lengths.len() as u64;
+                    ui.label(
+                        egui::RichText::new(format!(
+                            "· {} earlier session(s), {} on average, longest {}",
+                            lengths.len(),
+                            format_secs(mean),
+                            format_secs(lengths.iter().copied().max().unwrap_or_default())
+                        ))
+                        .weak(),
+                    );
+                }
+            });
+
+            egui::ScrollArea::vertical().auto_shrink([false; 2]).show(ui, |ui| {
+                if snapshot.metrics.is_empty() {
+                    ui.label(egui::RichText::new("Nothing recorded yet.").weak());
+                }
+                for (category, title) in CATEGORIES {
+                    let rows: Vec<&Metric> = snapshot.metrics.iter().filter(|m| m.category == *category).collect();
+                    if rows.is_empty() {
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(egui::RichText::new(*title).strong())
+                        .id_salt(("metrics", *category))
+                        .default_open(true)
+                        .show(ui, |ui| metric_grid(ui, &format!("metrics_grid_{}", category), &rows));
+                }
+            });
+        });
+
+    if !open {
+        state.open = false;
+    }
+    action
+}
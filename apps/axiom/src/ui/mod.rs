@@ -1,5 +1,23 @@
+pub mod assets;
 pub mod chat;
+pub mod code_block;
+pub mod connection;
+pub mod console;
+pub mod file_preview;
 pub mod file_tree;
 pub mod input;
+pub mod metrics;
+pub mod palette;
+pub mod preview;
+pub mod profiler;
+pub mod profiles;
+pub mod project_picker;
+pub mod review;
+pub mod scene_history;
+pub mod settings;
 pub mod sidebar;
+pub mod toasts;
+pub mod todos;
 pub mod top_panel;
+pub mod undo_history;
+pub mod viewport;
@@ -0,0 +1,163 @@
+use crate::agent::AgentProfile;
+use crate::commands::Command;
+use crate::types::ChannelState;
+use eframe::egui;
+use std::collections::HashMap;
+
+#[derive(Clone)]
+pub enum PaletteAction {
+    Run(Command),
+    UseProfile(String),
+    SwitchChannel(String),
+    None,
+}
+
+#[derive(Default)]
+pub struct PaletteState {
+    pub open: bool,
+    query: String,
+    selected: usize,
+}
+
+impl PaletteState {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+}
+
+struct Entry {
+    label: String,
+    hint: String,
+    action: PaletteAction,
+}
+
+/// Case-insensitive subsequence match, so "nch" finds "New channel".
+fn matches(label: &str, query: &str) -> bool {
+    let mut label = label.chars().flat_map(char::to_lowercase);
+    query
+        .chars()
+        .flat_map(char::to_lowercase)
+        .filter(|c| !c.is_whitespace())
+        .all(|q| label.any(|c| c == q))
+}
+
+fn entries(
+    ctx: &egui::Context,
+    profiles: &[AgentProfile],
+    channels: &HashMap<String, ChannelState>,
+    shortcut_for: &dyn Fn(Command) -> String,
+) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Command::ALL
+        .into_iter()
+        .filter(|&command| command != Command::OpenPalette)
+        .map(|command| Entry {
+            label: command.label().to_string(),
+            hint: crate::commands::parse_shortcut(&shortcut_for(command))
+                .map(|shortcut| ctx.format_shortcut(&shortcut))
+                .unwrap_or_default(),
+            action: PaletteAction::Run(command),
+        })
+        .collect();
+
+    entries.extend(profiles.iter().map(|profile| Entry {
+        label: format!("Switch agent: {}", profile.name),
+        hint: profile.model.clone(),
+        action: PaletteAction::UseProfile(profile.name.clone()),
+    }));
+
+    let mut channels: Vec<_> = channels.values().collect();
+    channels.sort_by(|a, b| a.name.cmp(&b.name));
+    entries.extend(channels.into_iter().map(|channel| Entry {
+        label: format!("Go to channel: {}", channel.name),
+        hint: String::new(),
+        action: PaletteAction::SwitchChannel(channel.id.clone()),
+    }));
+    entries
+}
+
+pub fn render_palette(
+    ctx: &egui::Context,
+    state: &mut PaletteState,
+    profiles: &[AgentProfile],
+    channels: &HashMap<String, ChannelState>,
+    shortcut_for: &dyn Fn(Command) -> String,
+) -> PaletteAction {
+    let mut action = PaletteAction::None;
+    if !state.open {
+        return action;
+    }
+
+    let matching: Vec<Entry> = entries(ctx, profiles, channels, shortcut_for)
+        .into_iter()
+        .filter(|entry| matches(&entry.label, &state.query))
+        .collect();
+
+    let (up, down, enter, escape) = ctx.input_mut(|i| {
+        (
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowUp),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::ArrowDown),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Enter),
+            i.consume_key(egui::Modifiers::NONE, egui::Key::Escape),
+        )
+    });
+    if up {
+        state.selected = state.selected.saturating_sub(1);
+    }
+    if down {
+        state.selected += 1;
+    }
+    state.selected = state.selected.min(matching.len().saturating_sub(1));
+
+    let mut chosen = enter.then_some(state.selected);
+    egui::Window::new("command_palette")
+        .title_bar(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+        .fixed_size([460.0, 0.0])
+        .show(ctx, |ui| {
+            let search = ui.add(
+                egui::TextEdit::singleline(&mut state.query)
+                    .hint_text("Type a command, agent or channel…")
+                    .desired_width(f32::INFINITY),
+            );
+            search.request_focus();
+            if search.changed() {
+                state.selected = 0;
+            }
+            ui.separator();
+
+            egui::ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                if matching.is_empty() {
+                    ui.label(egui::RichText::new("No matches").weak());
+                }
+                for (i, entry) in matching.iter().enumerate() {
+                    let selected = i == state.selected;
+                    let response = ui
+                        .horizontal(|ui| {
+                            let response = ui.selectable_label(selected, &entry.label);
+                            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                                ui.label(egui::RichText::new(&entry.hint).weak().small());
+                            });
+                            response
+                        })
+                        .inner;
+                    if selected && (up || down) {
+                        response.scroll_to_me(None);
+                    }
+                    if response.clicked() {
+                        chosen = Some(i);
+                    }
+                }
+            });
+        });
+
+    if let Some(entry) = chosen.and_then(|i| matching.get(i)) {
+        action = entry.action.clone();
+    }
+    if escape || chosen.is_some() {
+        state.open = false;
+    }
+    action
+}
@@ -0,0 +1,201 @@
+use crate::ui::assets::AssetRef;
+use bevy_bridge_core::types::ScreenshotResponse;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Delay before asking again when the game had no capture ready yet.
+const PENDING_RETRY: Duration = Duration::from_millis(300);
+
+pub enum PreviewAction {
+    Refresh,
+    /// An asset from the browser was dropped on the preview.
+    Spawn(AssetRef),
+    None,
+}
+
+/// Live screenshots of the running game, fetched over BRP (`axiom.screenshot`).
+pub struct GamePreviewState {
+    pub open: bool,
+    /// Shown in its own window instead of the side panel.
+    pub detached: bool,
+    /// Seconds between automatic refreshes; 0 disables them.
+    pub interval_secs: f32,
+    texture: Option<egui::TextureHandle>,
+    size: [u32; 2],
+    captured_at: Option<Instant>,
+    /// When the next automatic request is due; `None` when refreshing manually.
+    next_request: Option<Instant>,
+    refresh_requested: bool,
+    in_flight: bool,
+    error: Option<String>,
+}
+
+impl Default for GamePreviewState {
+    fn default() -> Self {
+        Self {
+            open: false,
+            detached: false,
+            interval_secs: 2.0,
+            texture: None,
+            size: [0, 0],
+            captured_at: None,
+            next_request: None,
+            refresh_requested: true,
+            in_flight: false,
+            error: None,
+        }
+    }
+}
+
+impl GamePreviewState {
+    /// Whether a new screenshot should be requested now. Marks the request as
+    /// in flight; call `receive` with the result.
+    pub fn take_due_request(&mut self) -> bool {
+        if !self.open || self.in_flight {
+            return false;
+        }
+        let due = self.refresh_requested || self.next_request.is_some_and(|at| Instant::now() >= at);
+        if due {
+            self.refresh_requested = false;
+            self.in_flight = true;
+        }
+        due
+    }
+
+    pub fn receive(&mut self, ctx: &egui::Context, result: Result<Option<ScreenshotResponse>, String>) {
+        self.in_flight = false;
+        match result {
+            Ok(Some(shot)) => {
+                match image::load_from_memory(&shot.png) {
+                    Ok(decoded) => {
+                        let rgba = decoded.to_rgba8();
+                        let size = [rgba.width() as usize, rgba.height() as usize];
+                        let color_image = egui::ColorImage::from_rgba_unmultiplied(size, rgba.as_flat_samples().as_slice());
+                        match &mut self.texture {
+                            Some(texture) => texture.set(color_image, egui::TextureOptions::LINEAR),
+                            None => {
+                                self.texture = Some(ctx.load_texture("game_preview", color_image, egui::TextureOptions::LINEAR));
+                            }
+                        }
+                        self.size = [shot.width, shot.height];
+                        self.captured_at = Some(Instant::now());
+                        self.error = None;
+                    }
+                    Err(e) => self.set_error(format!("Invalid screenshot: {}", e)),
+                }
+                self.schedule_next();
+            }
+            // The game only starts capturing on the first request
+            Ok(None) => self.next_request = Some(Instant::now() + PENDING_RETRY),
+            Err(e) => {
+                self.set_error(e);
+                self.schedule_next();
+            }
+        }
+    }
+
+    /// Keep the latest error for the panel; only log it when it changes so a
+    /// stopped game doesn't flood the console on every refresh.
+    fn set_error(&mut self, error: String) {
+        if self.error.as_ref() != Some(&error) {
+            tracing::warn!(target: "game", "{}", error);
+        }
+        self.error = Some(error);
+    }
+
+    /// Fetch again as soon as possible (manual refresh, panel opened).
+    pub fn request_now(&mut self) {
+        self.refresh_requested = true;
+    }
+
+    fn schedule_next(&mut self) {
+        self.next_request = (self.interval_secs > 0.0)
+            .then(|| Instant::now() + Duration::from_secs_f32(self.interval_secs));
+    }
+
+    /// Time until the next automatic request, for scheduling a repaint.
+    pub fn repaint_in(&self) -> Option<Duration> {
+        if !self.open || self.in_flight || self.refresh_requested {
+            return None;
+        }
+        self.next_request.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+}
+
+pub fn render_game_preview(ctx: &egui::Context, state: &mut GamePreviewState) -> PreviewAction {
+    let mut action = PreviewAction::None;
+    if !state.open {
+        return action;
+    }
+
+    if state.detached {
+        let closed = crate::ui::viewport::show_detached(ctx, "game_preview", "🎮 Game Preview", [640.0, 480.0], |ui| {
+            render_contents(ui, state, &mut action)
+        });
+        if closed {
+            state.detached = false;
+        }
+        return action;
+    }
+    egui::SidePanel::right("game_preview_panel")
+        .min_width(240.0)
+        .default_width(360.0)
+        .resizable(true)
+        .show(ctx, |ui| render_contents(ui, state, &mut action));
+
+    action
+}
+
+fn render_contents(ui: &mut egui::Ui, state: &mut GamePreviewState, action: &mut PreviewAction) {
+    ui.horizontal(|ui| {
+        ui.heading(egui::RichText::new("🎮 Game Preview").size(14.0).strong());
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            crate::ui::viewport::detach_button(ui, &mut state.detached);
+            if ui.add_enabled(!state.in_flight, egui::Button::new("🔄")).on_hover_text("Refresh now").clicked() {
+                *action = PreviewAction::Refresh;
+            }
+        });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Every");
+        let changed = ui
+            .add(egui::DragValue::new(&mut state.interval_secs).range(0.0..=60.0).speed(0.1).suffix(" s"))
+            .on_hover_text("0 = refresh manually only")
+            .changed();
+        if changed {
+            state.schedule_next();
+        }
+    });
+    ui.separator();
+
+    if let Some(error) = &state.error {
+        ui.colored_label(egui::Color32::from_rgb(255, 140, 0), format!("⚠ {}", error));
+    }
+
+    match &state.texture {
+        Some(texture) => {
+            let available = ui.available_width();
+            let [w, h] = [state.size[0].max(1) as f32, state.size[1].max(1) as f32];
+            let size = egui::vec2(available, available * h / w);
+            let response = ui.image((texture.id(), size));
+            if response.dnd_hover_payload::<AssetRef>().is_some() {
+                ui.painter().rect_stroke(response.rect, 4.0, egui::Stroke::new(2.0, ui.visuals().selection.bg_fill));
+            }
+            if let Some(asset) = response.dnd_release_payload::<AssetRef>() {
+                *action = PreviewAction::Spawn((*asset).clone());
+            }
+            if let Some(at) = state.captured_at {
+                ui.label(
+                    egui::RichText::new(format!("{}×{} · {}s ago", state.size[0], state.size[1], at.elapsed().as_secs()))
+                        .weak()
+                        .small(),
+                );
+            }
+        }
+        None if state.in_flight || state.error.is_none() => {
+            ui.spinner();
+        }
+        None => {}
+    }
+}
@@ -0,0 +1,259 @@
+use bevy_bridge_core::types::{FrameTiming, ProfileCapture, Timing};
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// While recording, the capture is read this often.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+/// 60 FPS, drawn across the frame chart.
+const BUDGET_MS: f64 = 1000.0 / 60.0;
+const CHART_HEIGHT: f32 = 60.0;
+const ROW_HEIGHT: f32 = 20.0;
+
+pub enum ProfilerAction {
+    Start,
+    Stop,
+    Refresh,
+    None,
+}
+
+#[derive(Default)]
+pub struct ProfilerState {
+    pub open: bool,
+    pub capture: Option<ProfileCapture>,
+    pub error: Option<String>,
+    /// A request to the game is running.
+    pub busy: bool,
+    refreshed: Option<Instant>,
+    /// Frame shown in the flame graph; `None` shows the averages.
+    selected: Option<u64>,
+}
+
+impl ProfilerState {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.refreshed = None;
+    }
+
+    pub fn receive(&mut self, result: Result<ProfileCapture, String>) {
+        self.busy = false;
+        match result {
+            Ok(capture) => {
+                if !capture.frames.iter().any(|f| Some(f.frame) == self.selected) {
+                    self.selected = None;
+                }
+                self.capture = Some(capture);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+/// Stable color per name, so a system keeps its color between frames.
+fn color(name: &str) -> egui::Color32 {
+    let hash = name.bytes().fold(0u32, |h, b| h.wrapping_mul(31).wrapping_add(b as u32));
+    let hue = (hash % 360) as f32 / 360.0;
+    egui::ecolor::Hsva::new(hue, 0.45, 0.85, 1.0).into()
+}
+
+/// Last path segment of a system name, which is what fits in a bar.
+fn short_name(name: &str) -> &str {
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// One bar of the flame graph, with its name when it fits and a tooltip.
+fn bar(ui: &mut egui::Ui, rect: egui::Rect, timing: &Timing, hovered: &mut Option<String>, pointer: Option<egui::Pos2>) {
+    let painter = ui.painter();
+    painter.rect_filled(rect.shrink(0.5), 2.0, color(&timing.name));
+    let label = format!("{} {:.2} ms", short_name(&timing.name), timing.ms);
+    let galley = painter.layout_no_wrap(label, egui::FontId::proportional(11.0), egui::Color32::BLACK);
+    if galley.size().x + 6.0 < rect.width() {
+        painter.galley(rect.left_center() + egui::vec2(3.0, -galley.size().y / 2.0), galley, egui::Color32::BLACK);
+    }
+    if pointer.is_some_and(|p| rect.contains(p)) {
+        *hovered = Some(format!("{}\n{:.3} ms", timing.name, timing.ms));
+    }
+}
+
+/// Frame, schedules below it in the order they ran, and each schedule's
+/// systems below that. Systems that ran in parallel can add up to more than
+/// their schedule; they are squeezed to fit.
+fn flame_graph(ui: &mut egui::Ui, total_ms: f64, schedules: &[Timing], systems: &[Timing]) {
+    let width = ui.available_width();
+    let rows = if systems.is_empty() { 2.0 } else { 3.0 };
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, ROW_HEIGHT * rows), egui::Sense::hover());
+    let pointer = response.hover_pos();
+    let mut hovered = None;
+    let scale = rect.width() as f64 / total_ms.max(f64::EPSILON);
+    let row = |i: f32, x: f32, w: f32| {
+        egui::Rect::from_min_size(egui::pos2(x, rect.top() + ROW_HEIGHT * i), egui::vec2(w.max(1.0), ROW_HEIGHT))
+    };
+
+    let frame = Timing { name: "Frame".to_string(), ms: total_ms, schedule: None };
+    bar(ui, row(0.0, rect.left(), rect.width()), &frame, &mut hovered, pointer);
+    let mut x = rect.left();
+    for schedule in schedules {
+        let w = (schedule.ms * scale) as f32;
+        bar(ui, row(1.0, x, w), schedule, &mut hovered, pointer);
+
+        let mut own: Vec<&Timing> = systems.iter().filter(|s| s.schedule.as_deref() == Some(&schedule.name)).collect();
+        own.sort_by(|a, b| b.ms.total_cmp(&a.ms));
+        let sum: f64 = own.iter().map(|s| s.ms).sum();
+        let fit = if sum > schedule.ms { schedule.ms / sum } else { 1.0 };
+        let mut sx = x;
+        for system in own {
+            let sw = (system.ms * fit * scale) as f32;
+            if sw >= 1.0 {
+                bar(ui, row(2.0, sx, sw), system, &mut hovered, pointer);
+            }
+            sx += sw;
+        }
+        x += w;
+    }
+    if let Some(text) = hovered {
+        response.on_hover_text_at_pointer(text);
+    }
+}
+
+/// Total time of each frame as a bar; clicking one selects it.
+fn frame_chart(ui: &mut egui::Ui, frames: &[FrameTiming], selected: &mut Option<u64>) {
+    let width = ui.available_width();
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(width, CHART_HEIGHT), egui::Sense::click());
+    let painter = ui.painter();
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+    if frames.is_empty() {
+        return;
+    }
+    let max_ms = frames.iter().map(|f| f.total_ms).fold(BUDGET_MS * 1.5, f64::max);
+    let bar_width = rect.width() / frames.len() as f32;
+    let y = |ms: f64| rect.bottom() - (ms / max_ms) as f32 * rect.height();
+
+    let pointer = response.hover_pos();
+    let mut hovered = None;
+    for (i, frame) in frames.iter().enumerate() {
+        let x = rect.left() + i as f32 * bar_width;
+        let bar = egui::Rect::from_min_max(egui::pos2(x, y(frame.total_ms)), egui::pos2(x + bar_width.max(1.0), rect.bottom()));
+        let color = if Some(frame.frame) == *selected {
+            ui.visuals().selection.bg_fill
+        } else if frame.total_ms > BUDGET_MS {
+            egui::Color32::from_rgb(230, 120, 60)
+        } else {
+            egui::Color32::from_rgb(100, 180, 100)
+        };
+        painter.rect_filled(bar, 0.0, color);
+        if pointer.is_some_and(|p| p.x >= x && p.x < x + bar_width) {
+            hovered = Some(frame);
+        }
+    }
+    painter.hline(rect.x_range(), y(BUDGET_MS), egui::Stroke::new(1.0, egui::Color32::from_gray(140)));
+
+    if let Some(frame) = hovered {
+        if response.clicked() {
+            *selected = Some(frame.frame);
+        }
+        response.on_hover_text_at_pointer(format!(
+            "Frame {}: {:.2} ms, {} entities\nClick to show it below",
+            frame.frame, frame.total_ms, frame.entities
+        ));
+    }
+}
+
+pub fn render_profiler(ctx: &egui::Context, state: &mut ProfilerState) -> ProfilerAction {
+    let mut action = ProfilerAction::None;
+    if !state.open {
+        return action;
+    }
+    let recording = state.capture.as_ref().is_some_and(|c| c.recording);
+    if (recording || state.capture.is_none())
+        && !state.busy
+        && state.refreshed.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL)
+    {
+        state.refreshed = Some(Instant::now());
+        action = ProfilerAction::Refresh;
+    }
+    if recording {
+        ctx.request_repaint_after(REFRESH_INTERVAL);
+    }
+
+    let mut open = true;
+    egui::Window::new("⏱ Profiler")
+        .open(&mut open)
+        .default_size([640.0, 320.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                if recording {
+                    if ui.add_enabled(!state.busy, egui::Button::new("■ Stop")).clicked() {
+                        action = ProfilerAction::Stop;
+                    }
+                } else if ui
+                    .add_enabled(!state.busy, egui::Button::new("⏺ Record"))
+                    .on_hover_text("Record the game's frame timings, dropping the previous capture")
+                    .clicked()
+                {
+                    action = ProfilerAction::Start;
+                }
+                if state.busy {
+                    ui.spinner();
+                }
+                if let Some(capture) = &state.capture {
+                    let summary = capture.summary(0);
+                    if summary.frames > 0 {
+                        ui.label(format!(
+                            "{} frames · avg {:.2} ms ({:.0} FPS) · worst {:.2} ms · entities {} → {}",
+                            summary.frames, summary.avg_frame_ms, summary.fps, summary.max_frame_ms, summary.entities.0, summary.entities.1
+                        ));
+                    }
+                }
+            });
+            ui.separator();
+
+            if let Some(error) = &state.error {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), error);
+                return;
+            }
+            let Some(capture) = &state.capture else {
+                return;
+            };
+            if capture.frames.is_empty() {
+                let hint = if capture.recording { "Waiting for frames…" } else { "Record to capture frame timings from the game." };
+                ui.label(egui::RichText::new(hint).weak());
+                return;
+            }
+
+            frame_chart(ui, &capture.frames, &mut state.selected);
+            ui.add_space(6.0);
+            match state.selected.and_then(|n| capture.frames.iter().find(|f| f.frame == n)) {
+                Some(frame) => {
+                    ui.horizontal(|ui| {
+                        ui.strong(format!("Frame {}", frame.frame));
+                        if ui.small_button("Show averages").clicked() {
+                            state.selected = None;
+                        }
+                    });
+                    flame_graph(ui, frame.total_ms, &frame.schedules, &frame.systems);
+                }
+                None => {
+                    ui.strong("Average frame");
+                    let summary = capture.summary(usize::MAX);
+                    // Summaries rank by time; show schedules in the order they run
+                    let order = &capture.frames[capture.frames.len() - 1].schedules;
+                    let mut schedules = summary.schedules;
+                    schedules.sort_by_key(|s| order.iter().position(|o| o.name == s.name).unwrap_or(usize::MAX));
+                    flame_graph(ui, summary.avg_frame_ms, &schedules, &summary.systems);
+                }
+            }
+            if !capture.system_timing {
+                ui.label(
+                    egui::RichText::new("Only schedules are timed. For systems, build the game with bevy's `trace` feature and set `bevy_ai_remote::profile_layer` as `LogPlugin::custom_layer`.")
+                        .weak()
+                        .small(),
+                );
+            }
+        });
+
+    if !open {
+        state.open = false;
+    }
+    action
+}
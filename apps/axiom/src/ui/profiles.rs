@@ -0,0 +1,270 @@
+use crate::agent::AgentProfile;
+use crate::llm::ProviderKind;
+use crate::prompts::{contexts::CONTEXT_MODES, RESEARCH_MODES};
+use eframe::egui;
+
+pub enum ProfileAction {
+    /// `original` is the name of the edited profile, `None` for a new one.
+    Save { original: Option<String>, profile: Box<AgentProfile> },
+    Delete(String),
+    Use(String),
+    None,
+}
+
+/// The profile editor window. Edits go to a draft until Save.
+#[derive(Default)]
+pub struct ProfileEditorState {
+    pub open: bool,
+    /// Name of the saved profile the draft came from; `None` for a new one.
+    editing: Option<String>,
+    draft: AgentProfile,
+    error: Option<String>,
+    tool_names: Vec<String>,
+    avatars: Vec<String>,
+}
+
+impl ProfileEditorState {
+    pub fn open_with(&mut self, profile: &AgentProfile) {
+        self.tool_names = crate::tools::tool_names();
+        self.avatars = list_avatars();
+        self.edit(profile);
+        self.open = true;
+    }
+
+    fn edit(&mut self, profile: &AgentProfile) {
+        self.editing = Some(profile.name.clone());
+        self.draft = profile.clone();
+        self.error = None;
+    }
+
+    fn edit_new(&mut self, draft: AgentProfile) {
+        self.editing = None;
+        self.draft = draft;
+        self.error = None;
+    }
+}
+
+/// Image files under `assets/avatars`, where the chat looks up avatars.
+fn list_avatars() -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir("assets/avatars") else {
+        return Vec::new();
+    };
+    let mut avatars: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".png") || name.ends_with(".jpg"))
+        .collect();
+    avatars.sort();
+    avatars
+}
+
+fn combo(ui: &mut egui::Ui, id: &str, value: &mut String, options: &[&str]) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(value.as_str())
+        .show_ui(ui, |ui| {
+            for option in options {
+                ui.selectable_value(value, option.to_string(), *option);
+            }
+        });
+}
+
+pub fn render_profile_editor(
+    ctx: &egui::Context,
+    state: &mut ProfileEditorState,
+    profiles: &[AgentProfile],
+    current_profile: &str,
+    default_model: &str,
+) -> ProfileAction {
+    let mut action = ProfileAction::None;
+    if !state.open {
+        return action;
+    }
+
+    let mut open = state.open;
+    egui::Window::new("🎭 Agent Profiles")
+        .open(&mut open)
+        .default_size([720.0, 520.0])
+        .show(ctx, |ui| {
+            ui.horizontal_top(|ui| {
+                // Profile list
+                ui.vertical(|ui| {
+                    ui.set_width(180.0);
+                    egui::ScrollArea::vertical().id_salt("profile_list").max_height(420.0).show(ui, |ui| {
+                        for profile in profiles {
+                            let label = if profile.name == current_profile {
+                                format!("▶ {}", profile.name)
+                            } else {
+                                profile.name.clone()
+                            };
+                            let selected = state.editing.as_deref() == Some(profile.name.as_str());
+                            if ui.selectable_label(selected, label).on_hover_text(&profile.description).clicked() {
+                                state.edit(profile);
+                            }
+                        }
+                    });
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("➕ New").clicked() {
+                            let mut draft = AgentProfile {
+                                name: String::new(),
+                                description: String::new(),
+                                avatar_path: "bot.png".to_string(),
+                                ..Default::default()
+                            };
+                            if !default_model.trim().is_empty() {
+                                draft.model = default_model.trim().to_string();
+                            }
+                            state.edit_new(draft);
+                        }
+                        if ui.button("📄 Duplicate").clicked() {
+                            let mut draft = state.draft.clone();
+                            draft.name = format!("{} (copy)", draft.name);
+                            state.edit_new(draft);
+                        }
+                    });
+                });
+
+                ui.separator();
+
+                // Profile form
+                ui.vertical(|ui| {
+                    egui::ScrollArea::vertical().id_salt("profile_form").max_height(440.0).show(ui, |ui| {
+                        render_form(ui, state);
+                    });
+                });
+            });
+
+            ui.separator();
+            if let Some(error) = &state.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.horizontal(|ui| {
+                if ui.button("💾 Save").clicked() {
+                    let name = state.draft.name.trim().to_string();
+                    let taken = profiles
+                        .iter()
+                        .any(|p| p.name == name && state.editing.as_deref() != Some(name.as_str()));
+                    if name.is_empty() {
+                        state.error = Some("Name must not be empty".to_string());
+                    } else if taken {
+                        state.error = Some(format!("A profile named '{}' already exists", name));
+                    } else {
+                        state.draft.name = name.clone();
+                        action = ProfileAction::Save {
+                            original: state.editing.replace(name),
+                            profile: Box::new(state.draft.clone()),
+                        };
+                        state.error = None;
+                    }
+                }
+
+                if let Some(name) = state.editing.clone() {
+                    if ui.add_enabled(name != current_profile, egui::Button::new("✔ Use")).clicked() {
+                        action = ProfileAction::Use(name.clone());
+                    }
+                    if ui.button(egui::RichText::new("🗑 Delete").color(egui::Color32::RED)).clicked() {
+                        action = ProfileAction::Delete(name);
+                        let next = profiles.iter().find(|p| Some(&p.name) != state.editing.as_ref()).cloned();
+                        match next {
+                            Some(next) => state.edit(&next),
+                            None => state.edit_new(AgentProfile::default()),
+                        }
+                    }
+                }
+            });
+        });
+
+    state.open = state.open && open;
+    action
+}
+
+fn render_form(ui: &mut egui::Ui, state: &mut ProfileEditorState) {
+    let draft = &mut state.draft;
+
+    egui::Grid::new("profile_form_grid").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+        ui.label("Name");
+        ui.text_edit_singleline(&mut draft.name);
+        ui.end_row();
+
+        ui.label("Description");
+        ui.text_edit_singleline(&mut draft.description);
+        ui.end_row();
+
+        ui.label("Provider");
+        egui::ComboBox::from_id_salt("profile_provider")
+            .selected_text(draft.provider.label())
+            .show_ui(ui, |ui| {
+                for kind in ProviderKind::ALL {
+                    ui.selectable_value(&mut draft.provider, kind, kind.label());
+                }
+            });
+        ui.end_row();
+
+        ui.label("Model");
+        ui.text_edit_singleline(&mut draft.model);
+        ui.end_row();
+
+        ui.label("Research mode");
+        combo(ui, "profile_research", &mut draft.research_mode, RESEARCH_MODES);
+        ui.end_row();
+
+        ui.label("Context mode");
+        combo(ui, "profile_context", &mut draft.context_mode, CONTEXT_MODES);
+        ui.end_row();
+
+        ui.label("Avatar");
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut draft.avatar_path).desired_width(140.0));
+            if !state.avatars.is_empty() {
+                egui::ComboBox::from_id_salt("profile_avatar")
+                    .selected_text("Pick…")
+                    .show_ui(ui, |ui| {
+                        for avatar in &state.avatars {
+                            ui.selectable_value(&mut draft.avatar_path, avatar.clone(), avatar);
+                        }
+                    });
+            }
+        });
+        ui.end_row();
+
+        ui.label("Name color");
+        crate::ui::settings::optional_color(ui, &mut draft.color, [255, 105, 180], "Derived from the name");
+        ui.end_row();
+    });
+
+    ui.add_space(5.0);
+    ui.label("System prompt");
+    ui.add(
+        egui::TextEdit::multiline(&mut draft.system_prompt)
+            .desired_rows(6)
+            .desired_width(f32::INFINITY)
+            .hint_text("Role and instructions; {{variables}} are filled in per request"),
+    );
+
+    ui.add_space(5.0);
+    let mut default_tools = draft.allowed_tools.is_none();
+    if ui
+        .checkbox(&mut default_tools, "Default tools")
+        .on_hover_text("Uncheck to choose exactly which tools this profile may call")
+        .changed()
+    {
+        draft.allowed_tools = (!default_tools).then(|| state.tool_names.clone());
+    }
+    if let Some(allowed) = &mut draft.allowed_tools {
+        egui::Grid::new("profile_tools").num_columns(3).show(ui, |ui| {
+            for (i, tool) in state.tool_names.iter().enumerate() {
+                let mut enabled = allowed.contains(tool);
+                if ui.checkbox(&mut enabled, tool).changed() {
+                    if enabled {
+                        allowed.push(tool.clone());
+                    } else {
+                        allowed.retain(|t| t != tool);
+                    }
+                }
+                if i % 3 == 2 {
+                    ui.end_row();
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,61 @@
+use eframe::egui;
+use std::path::PathBuf;
+
+/// Start screen listing recent projects.
+pub struct ProjectPickerState {
+    pub recent: Vec<PathBuf>,
+    /// Directory the editor was started in.
+    pub launch_dir: PathBuf,
+    pub error: Option<String>,
+}
+
+impl ProjectPickerState {
+    pub fn new(launch_dir: PathBuf) -> Self {
+        Self { recent: crate::project::recent(), launch_dir, error: None }
+    }
+}
+
+/// The folder the user chose, if any.
+pub fn render_project_picker(ctx: &egui::Context, state: &ProjectPickerState) -> Option<PathBuf> {
+    let mut chosen = None;
+    egui::CentralPanel::default().show(ctx, |ui| {
+        ui.vertical_centered(|ui| {
+            ui.add_space(60.0);
+            ui.heading("Open a project");
+            ui.label(egui::RichText::new("Chat history, todos and project settings are kept per project folder.").weak());
+            ui.add_space(20.0);
+
+            if let Some(error) = &state.error {
+                ui.colored_label(egui::Color32::RED, error);
+                ui.add_space(10.0);
+            }
+
+            ui.set_max_width(520.0);
+            if !state.recent.is_empty() {
+                ui.label(egui::RichText::new("Recent").strong());
+                for path in &state.recent {
+                    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                    let text = format!("📁 {}   {}", name, path.display());
+                    if ui.add_sized([ui.available_width(), 28.0], egui::Button::new(text)).clicked() {
+                        chosen = Some(path.clone());
+                    }
+                }
+                ui.add_space(20.0);
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("📂 Open folder…").clicked() {
+                    chosen = rfd::FileDialog::new().set_directory(&state.launch_dir).pick_folder();
+                }
+                if ui
+                    .button("Use the launch directory")
+                    .on_hover_text(state.launch_dir.display().to_string())
+                    .clicked()
+                {
+                    chosen = Some(state.launch_dir.clone());
+                }
+            });
+        });
+    });
+    chosen
+}
@@ -0,0 +1,227 @@
+use crate::tools::review::PendingReview;
+use eframe::egui;
+
+/// Unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 2;
+/// Above this many line pairs the diff falls back to "everything changed".
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+pub enum ReviewChoice {
+    Approve,
+    ApproveAll,
+    Reject,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum RowKind {
+    Same,
+    Removed,
+    Added,
+    Changed,
+}
+
+/// One side-by-side row: line numbers are 1-based.
+struct DiffRow<'a> {
+    kind: RowKind,
+    left: Option<(usize, &'a str)>,
+    right: Option<(usize, &'a str)>,
+}
+
+enum Op {
+    Same(usize, usize),
+    Remove(usize),
+    Add(usize),
+}
+
+/// Line diff (LCS) of `before` and `after`, paired up side by side.
+fn line_diff<'a>(before: &'a str, after: &'a str) -> Vec<DiffRow<'a>> {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+
+    // Trim the common prefix/suffix so the table only covers the changed middle
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut ops: Vec<Op> = (0..prefix).map(|i| Op::Same(i, i)).collect();
+    if a_mid.len() * b_mid.len() > MAX_DIFF_CELLS {
+        ops.extend((0..a_mid.len()).map(|i| Op::Remove(prefix + i)));
+        ops.extend((0..b_mid.len()).map(|j| Op::Add(prefix + j)));
+    } else {
+        let (n, m) = (a_mid.len(), b_mid.len());
+        let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+        for i in (0..n).rev() {
+            for j in (0..m).rev() {
+                lcs[i][j] = if a_mid[i] == b_mid[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+        let (mut i, mut j) = (0, 0);
+        while i < n || j < m {
+            if i < n && j < m && a_mid[i] == b_mid[j] {
+                ops.push(Op::Same(prefix + i, prefix + j));
+                i += 1;
+                j += 1;
+            } else if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                ops.push(Op::Add(prefix + j));
+                j += 1;
+            } else {
+                ops.push(Op::Remove(prefix + i));
+                i += 1;
+            }
+        }
+    }
+    ops.extend((0..suffix).map(|k| Op::Same(a.len() - suffix + k, b.len() - suffix + k)));
+
+    // Pair runs of removals and additions into "changed" rows
+    let mut rows = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let flush = |rows: &mut Vec<DiffRow<'a>>, removed: &mut Vec<usize>, added: &mut Vec<usize>| {
+        for k in 0..removed.len().max(added.len()) {
+            let left = removed.get(k).map(|&i| (i + 1, a[i]));
+            let right = added.get(k).map(|&j| (j + 1, b[j]));
+            let kind = match (left, right) {
+                (Some(_), Some(_)) => RowKind::Changed,
+                (Some(_), None) => RowKind::Removed,
+                _ => RowKind::Added,
+            };
+            rows.push(DiffRow { kind, left, right });
+        }
+        removed.clear();
+        added.clear();
+    };
+    for op in ops {
+        match op {
+            Op::Remove(i) => removed.push(i),
+            Op::Add(j) => added.push(j),
+            Op::Same(i, j) => {
+                flush(&mut rows, &mut removed, &mut added);
+                rows.push(DiffRow {
+                    kind: RowKind::Same,
+                    left: Some((i + 1, a[i])),
+                    right: Some((j + 1, b[j])),
+                });
+            }
+        }
+    }
+    flush(&mut rows, &mut removed, &mut added);
+    rows
+}
+
+/// Cards for edits awaiting approval. Returns the index and choice of a
+/// clicked button.
+pub fn render_review_cards(ui: &mut egui::Ui, pending: &[PendingReview]) -> Option<(usize, ReviewChoice)> {
+    let mut choice = None;
+
+    for (idx, review) in pending.iter().enumerate() {
+        ui.group(|ui| {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new("✋ Review edit").strong().color(egui::Color32::GOLD));
+                ui.label(egui::RichText::new(&review.tool).monospace());
+                ui.label(&review.edit.path);
+            });
+
+            let rows = line_diff(&review.edit.before, &review.edit.after);
+            let (added, removed) = rows.iter().fold((0, 0), |(a, r), row| match row.kind {
+                RowKind::Added => (a + 1, r),
+                RowKind::Removed => (a, r + 1),
+                RowKind::Changed => (a + 1, r + 1),
+                RowKind::Same => (a, r),
+            });
+
+            egui::CollapsingHeader::new(format!("+{} −{}", added, removed))
+                .id_salt(("review_diff", idx, &review.edit.path))
+                .default_open(true)
+                .show(ui, |ui| render_diff(ui, &rows));
+
+            ui.horizontal(|ui| {
+                if ui.button(egui::RichText::new("✅ Approve").color(egui::Color32::GREEN)).clicked() {
+                    choice = Some((idx, ReviewChoice::Approve));
+                }
+                if ui.button(egui::RichText::new("❌ Reject").color(egui::Color32::RED)).clicked() {
+                    choice = Some((idx, ReviewChoice::Reject));
+                }
+                if ui
+                    .button("Approve all")
+                    .on_hover_text("Apply this and every further edit this session without asking")
+                    .clicked()
+                {
+                    choice = Some((idx, ReviewChoice::ApproveAll));
+                }
+            });
+        });
+        ui.add_space(5.0);
+    }
+
+    choice
+}
+
+/// Side-by-side diff of two texts, collapsed around the changes.
+pub fn render_text_diff(ui: &mut egui::Ui, before: &str, after: &str) {
+    let rows = line_diff(before, after);
+    if rows.iter().all(|row| row.kind == RowKind::Same) {
+        ui.label(egui::RichText::new("No changes").weak());
+    } else {
+        render_diff(ui, &rows);
+    }
+}
+
+fn render_diff(ui: &mut egui::Ui, rows: &[DiffRow<'_>]) {
+    // Rows within CONTEXT_LINES of a change are shown; the rest collapse to "⋯"
+    let changed: Vec<usize> = rows.iter().enumerate().filter(|(_, r)| r.kind != RowKind::Same).map(|(i, _)| i).collect();
+    let visible = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= CONTEXT_LINES);
+
+    let removed_bg = egui::Color32::from_rgba_unmultiplied(200, 60, 60, 40);
+    let added_bg = egui::Color32::from_rgba_unmultiplied(60, 200, 90, 40);
+    let cell = |ui: &mut egui::Ui, side: Option<(usize, &str)>, bg: Option<egui::Color32>| {
+        let text = match side {
+            Some((line, text)) => format!("{:>4} {}", line, text),
+            None => String::new(),
+        };
+        let mut rich = egui::RichText::new(text).monospace();
+        if let Some(bg) = bg {
+            rich = rich.background_color(bg);
+        }
+        ui.add(egui::Label::new(rich).extend());
+    };
+
+    egui::ScrollArea::both()
+        .id_salt(ui.next_auto_id())
+        .max_height(300.0)
+        .show(ui, |ui| {
+            egui::Grid::new(ui.next_auto_id()).striped(false).num_columns(2).show(ui, |ui| {
+                let mut skipped = false;
+                for (i, row) in rows.iter().enumerate() {
+                    if !visible(i) {
+                        if !skipped {
+                            ui.label(egui::RichText::new("⋯").weak());
+                            ui.label(egui::RichText::new("⋯").weak());
+                            ui.end_row();
+                        }
+                        skipped = true;
+                        continue;
+                    }
+                    skipped = false;
+
+                    let (left_bg, right_bg) = match row.kind {
+                        RowKind::Same => (None, None),
+                        RowKind::Removed => (Some(removed_bg), None),
+                        RowKind::Added => (None, Some(added_bg)),
+                        RowKind::Changed => (Some(removed_bg), Some(added_bg)),
+                    };
+                    cell(ui, row.left, left_bg);
+                    cell(ui, row.right, right_bg);
+                    ui.end_row();
+                }
+            });
+        });
+}
@@ -0,0 +1,165 @@
+use crate::scene_history::{self, SceneSnapshot};
+use bevy_bridge_core::ops::scene::compare;
+use eframe::egui;
+use std::path::Path;
+
+const ADDED: egui::Color32 = egui::Color32::from_rgb(120, 200, 80);
+const REMOVED: egui::Color32 = egui::Color32::from_rgb(230, 80, 80);
+const MODIFIED: egui::Color32 = egui::Color32::from_rgb(240, 180, 60);
+
+pub enum SceneHistoryAction {
+    /// Export the game's scene under this name.
+    Save(String),
+    Restore(SceneSnapshot),
+    Delete(String),
+    None,
+}
+
+#[derive(Default)]
+pub struct SceneHistoryState {
+    pub open: bool,
+    /// Read from disk when the window opens or a snapshot changes.
+    snapshots: Option<Vec<SceneSnapshot>>,
+    new_name: String,
+    /// Names of the snapshots being compared.
+    from: Option<String>,
+    to: Option<String>,
+    /// A save or restore is running.
+    pub busy: bool,
+}
+
+impl SceneHistoryState {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.reload();
+    }
+
+    pub fn reload(&mut self) {
+        self.snapshots = None;
+    }
+}
+
+fn position(translation: [f32; 3]) -> String {
+    format!("({:.2}, {:.2}, {:.2})", translation[0], translation[1], translation[2])
+}
+
+fn snapshot_picker(ui: &mut egui::Ui, id: &str, selected: &mut Option<String>, snapshots: &[SceneSnapshot]) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(selected.as_deref().unwrap_or("Pick a snapshot"))
+        .show_ui(ui, |ui| {
+            for snapshot in snapshots {
+                ui.selectable_value(selected, Some(snapshot.name.clone()), &snapshot.name);
+            }
+        });
+}
+
+fn render_diff(ui: &mut egui::Ui, from: &SceneSnapshot, to: &SceneSnapshot) {
+    let diff = compare(&from.entities, &to.entities);
+    ui.label(egui::RichText::new(diff.summary()).weak());
+    if diff.is_empty() {
+        ui.label("No differences.");
+        return;
+    }
+    for entity in &diff.added {
+        ui.colored_label(ADDED, format!("+ {} at {}", entity.label(), position(entity.transform.translation)));
+    }
+    for entity in &diff.removed {
+        ui.colored_label(REMOVED, format!("− {} at {}", entity.label(), position(entity.transform.translation)));
+    }
+    for modified in &diff.modified {
+        let text = if modified.moved() {
+            format!(
+                "↔ {} {} → {}",
+                modified.before.label(),
+                position(modified.before.transform.translation),
+                position(modified.after.transform.translation)
+            )
+        } else {
+            // Type paths shortened to the type, e.g. `AxiomPrimitive`
+            let components: Vec<&str> =
+                modified.changes.iter().map(|c| c.component.rsplit("::").next().unwrap_or(&c.component)).collect();
+            format!("~ {} ({})", modified.before.label(), components.join(", "))
+        };
+        ui.colored_label(MODIFIED, text);
+    }
+}
+
+pub fn render_scene_history(ctx: &egui::Context, state: &mut SceneHistoryState, root: &Path) -> SceneHistoryAction {
+    let mut action = SceneHistoryAction::None;
+    if !state.open {
+        return action;
+    }
+    let snapshots = state.snapshots.get_or_insert_with(|| scene_history::list(root));
+
+    let mut open = true;
+    egui::Window::new("🕘 Scene history")
+        .open(&mut open)
+        .default_size([520.0, 460.0])
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut state.new_name).hint_text("Snapshot name").desired_width(220.0));
+                let save = ui
+                    .add_enabled(!state.busy, egui::Button::new("📸 Save snapshot"))
+                    .on_hover_text("Export the primitives and models the editor spawned");
+                if save.clicked() {
+                    let name = state.new_name.trim();
+                    let name = if name.is_empty() {
+                        chrono::Local::now().format("Snapshot %Y-%m-%d %H:%M:%S").to_string()
+                    } else {
+                        name.to_string()
+                    };
+                    action = SceneHistoryAction::Save(name);
+                    state.new_name.clear();
+                }
+                if state.busy {
+                    ui.spinner();
+                }
+            });
+            ui.separator();
+
+            if snapshots.is_empty() {
+                ui.label(egui::RichText::new("No snapshots yet. Save one to restore or compare the scene later.").weak());
+                return;
+            }
+            egui::ScrollArea::vertical().id_salt("scene_snapshots").max_height(180.0).show(ui, |ui| {
+                egui::Grid::new("scene_snapshot_grid").num_columns(4).striped(true).spacing([12.0, 4.0]).show(ui, |ui| {
+                    for snapshot in snapshots.iter() {
+                        ui.label(&snapshot.name);
+                        ui.label(egui::RichText::new(snapshot.created.format("%Y-%m-%d %H:%M").to_string()).weak());
+                        ui.label(format!("{} entities", snapshot.entities.len()));
+                        ui.horizontal(|ui| {
+                            let restore = ui
+                                .add_enabled(!state.busy, egui::Button::new("↩ Restore").small())
+                                .on_hover_text("Replace everything the editor spawned with this snapshot");
+                            if restore.clicked() {
+                                action = SceneHistoryAction::Restore(snapshot.clone());
+                            }
+                            if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                action = SceneHistoryAction::Delete(snapshot.name.clone());
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.label("Compare");
+                snapshot_picker(ui, "scene_diff_from", &mut state.from, snapshots);
+                ui.label("→");
+                snapshot_picker(ui, "scene_diff_to", &mut state.to, snapshots);
+            });
+            let find = |name: &Option<String>| snapshots.iter().find(|s| Some(&s.name) == name.as_ref());
+            if let (Some(from), Some(to)) = (find(&state.from), find(&state.to)) {
+                egui::ScrollArea::vertical().id_salt("scene_diff").auto_shrink([false, true]).show(ui, |ui| {
+                    render_diff(ui, from, to);
+                });
+            }
+        });
+
+    if !open {
+        state.open = false;
+    }
+    action
+}
@@ -0,0 +1,213 @@
+use crate::commands::{parse_shortcut, Command};
+use crate::settings::{EnvField, Settings, Theme, API_KEYS, BASE_URLS, BRP, TIMEOUTS};
+use eframe::egui;
+
+pub enum SettingsAction {
+    Save(Box<Settings>),
+    None,
+}
+
+/// The settings window edits a copy; nothing changes until Save.
+#[derive(Default)]
+pub struct SettingsState {
+    pub open: bool,
+    draft: Settings,
+    reveal_keys: bool,
+}
+
+impl SettingsState {
+    pub fn open_with(&mut self, current: &Settings) {
+        self.draft = current.clone();
+        self.open = true;
+    }
+}
+
+fn env_grid(ui: &mut egui::Ui, id: &str, fields: &[EnvField], draft: &mut Settings, reveal: bool) {
+    egui::Grid::new(id).num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+        for field in fields {
+            ui.label(field.label).on_hover_text(field.var);
+            ui.add(
+                egui::TextEdit::singleline((field.get)(draft))
+                    .password(field.secret && !reveal)
+                    .hint_text(format!("${}", field.var))
+                    .desired_width(320.0),
+            );
+            ui.end_row();
+        }
+    });
+}
+
+pub fn render_settings(ctx: &egui::Context, state: &mut SettingsState) -> SettingsAction {
+    let mut action = SettingsAction::None;
+    if !state.open {
+        return action;
+    }
+
+    let mut open = true;
+    egui::Window::new("⚙ Settings")
+        .open(&mut open)
+        .collapsible(false)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.label(
+                egui::RichText::new(format!("Saved to {}. Empty fields fall back to .env / environment variables.", Settings::path().display()))
+                    .weak()
+                    .small(),
+            );
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                ui.strong("API keys");
+                ui.checkbox(&mut state.reveal_keys, "Show");
+            });
+            env_grid(ui, "settings_keys", API_KEYS, &mut state.draft, state.reveal_keys);
+            ui.separator();
+
+            ui.strong("Base URLs");
+            env_grid(ui, "settings_urls", BASE_URLS, &mut state.draft, false);
+            ui.separator();
+
+            ui.strong("Game connection");
+            env_grid(ui, "settings_brp", BRP, &mut state.draft, false);
+            ui.separator();
+
+            ui.strong("Timeouts");
+            env_grid(ui, "settings_timeouts", TIMEOUTS, &mut state.draft, false);
+            ui.separator();
+
+            ui.strong("Editor");
+            egui::Grid::new("settings_editor").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+                ui.label("Default model");
+                ui.add(
+                    egui::TextEdit::singleline(&mut state.draft.default_model)
+                        .hint_text("gemini-2.5-flash")
+                        .desired_width(320.0),
+                );
+                ui.end_row();
+
+                ui.label("Startup project");
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut state.draft.workspace_root)
+                            .hint_text("Show the project picker")
+                            .desired_width(250.0),
+                    )
+                    .on_hover_text("Opened at start instead of the project picker. Switch projects with Ctrl+O");
+                    if ui.button("📂").clicked() {
+                        if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                            state.draft.workspace_root = folder.display().to_string();
+                        }
+                    }
+                });
+                ui.end_row();
+
+                ui.label("Theme");
+                egui::ComboBox::from_id_salt("settings_theme")
+                    .selected_text(state.draft.theme.label())
+                    .show_ui(ui, |ui| {
+                        for theme in Theme::ALL {
+                            ui.selectable_value(&mut state.draft.theme, theme, theme.label());
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Accent color");
+                optional_color(ui, &mut state.draft.accent, [100, 180, 255], "Theme default");
+                ui.end_row();
+
+                ui.label("Your name color");
+                optional_color(ui, &mut state.draft.user_color, [173, 216, 230], "Same as accent");
+                ui.end_row();
+
+                ui.label("Session budget");
+                optional_amount(ui, &mut state.draft.session_budget_usd, 5.0, "Unlimited");
+                ui.end_row();
+
+                ui.label("Daily budget");
+                optional_amount(ui, &mut state.draft.daily_budget_usd, 20.0, "Unlimited");
+                ui.end_row();
+
+                ui.label("Metrics");
+                ui.checkbox(&mut state.draft.metrics_enabled, "Record performance metrics")
+                    .on_hover_text("Tool, model and BRP timings for the metrics dashboard. Kept on this machine");
+                ui.end_row();
+            });
+
+            ui.separator();
+            egui::CollapsingHeader::new(egui::RichText::new("Keyboard shortcuts").strong())
+                .id_salt("settings_shortcuts")
+                .show(ui, |ui| shortcut_grid(ui, &mut state.draft));
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                if ui.button("💾 Save").clicked() {
+                    action = SettingsAction::Save(Box::new(state.draft.clone()));
+                    state.open = false;
+                }
+                if ui.button("Cancel").clicked() {
+                    state.open = false;
+                }
+            });
+        });
+
+    if !open {
+        state.open = false;
+    }
+    action
+}
+
+fn shortcut_grid(ui: &mut egui::Ui, draft: &mut Settings) {
+    ui.label(egui::RichText::new("e.g. Ctrl+Shift+N; Ctrl is Cmd on macOS. Leave empty to disable.").weak().small());
+    egui::Grid::new("settings_shortcut_grid").num_columns(2).spacing([10.0, 6.0]).show(ui, |ui| {
+        for command in Command::ALL {
+            ui.label(command.label());
+            let mut value = draft.shortcut(command);
+            let valid = value.trim().is_empty() || parse_shortcut(&value).is_some();
+            let mut edit = egui::TextEdit::singleline(&mut value).desired_width(160.0);
+            if !valid {
+                edit = edit.text_color(egui::Color32::RED);
+            }
+            if ui.add(edit).changed() {
+                draft.shortcuts.insert(command.id().to_string(), value);
+            }
+            ui.end_row();
+        }
+    });
+}
+
+/// A color that can be left unset; `fallback` seeds the picker when enabled.
+/// A USD limit; at 80% a warning shows, at 100% requests wait for confirmation.
+fn optional_amount(ui: &mut egui::Ui, amount: &mut Option<f64>, fallback: f64, unset_label: &str) {
+    ui.horizontal(|ui| {
+        let mut limited = amount.is_some();
+        if ui.checkbox(&mut limited, "Limit").changed() {
+            *amount = limited.then_some(fallback);
+        }
+        match amount {
+            Some(usd) => {
+                ui.add(egui::DragValue::new(usd).prefix("$").speed(0.1).range(0.01..=10_000.0).max_decimals(2))
+                    .on_hover_text("Warns at 80%; at the limit, model requests wait until you confirm");
+            }
+            None => {
+                ui.label(egui::RichText::new(unset_label).weak());
+            }
+        }
+    });
+}
+
+pub fn optional_color(ui: &mut egui::Ui, color: &mut Option<[u8; 3]>, fallback: [u8; 3], unset_label: &str) {
+    ui.horizontal(|ui| {
+        let mut custom = color.is_some();
+        if ui.checkbox(&mut custom, "Custom").changed() {
+            *color = custom.then_some(fallback);
+        }
+        match color {
+            Some(rgb) => {
+                ui.color_edit_button_srgb(rgb);
+            }
+            None => {
+                ui.label(egui::RichText::new(unset_label).weak());
+            }
+        }
+    });
+}
@@ -4,7 +4,7 @@ use eframe::egui;
 use std::collections::HashMap;
 
 pub enum SidebarAction {
-    SelectProfile(AgentProfile),
+    SelectProfile(Box<AgentProfile>),
     CopyLog,
     None,
 }
@@ -58,7 +58,7 @@ pub fn render_sidebar(
             );
 
             if btn.clicked() {
-                action = SidebarAction::SelectProfile(profile.clone());
+                action = SidebarAction::SelectProfile(Box::new(profile.clone()));
             }
 
             // Tooltip description
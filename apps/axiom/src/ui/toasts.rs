@@ -0,0 +1,115 @@
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// Older toasts are dropped beyond this.
+const MAX_TOASTS: usize = 5;
+/// Longer texts are cut; the full message is in the chat or the console.
+const MAX_CHARS: usize = 200;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ToastKind {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl ToastKind {
+    fn icon(self) -> &'static str {
+        match self {
+            ToastKind::Info => "ℹ",
+            ToastKind::Success => "✔",
+            ToastKind::Warning => "⚠",
+            ToastKind::Error => "❌",
+        }
+    }
+
+    fn color(self) -> egui::Color32 {
+        match self {
+            ToastKind::Info => egui::Color32::LIGHT_BLUE,
+            ToastKind::Success => egui::Color32::from_rgb(120, 200, 80),
+            ToastKind::Warning => egui::Color32::from_rgb(255, 140, 0),
+            ToastKind::Error => egui::Color32::from_rgb(230, 80, 80),
+        }
+    }
+
+    fn lifetime(self) -> Duration {
+        match self {
+            ToastKind::Info | ToastKind::Success => Duration::from_secs(4),
+            ToastKind::Warning => Duration::from_secs(6),
+            ToastKind::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+struct Toast {
+    kind: ToastKind,
+    text: String,
+    shown_at: Instant,
+}
+
+/// Transient notifications in the bottom-right corner.
+#[derive(Default)]
+pub struct Toasts {
+    items: Vec<Toast>,
+}
+
+impl Toasts {
+    /// Only the first line is shown. A repeat of a visible toast restarts its
+    /// timer instead of stacking.
+    pub fn push(&mut self, kind: ToastKind, text: &str) {
+        let mut text = text.lines().next().unwrap_or_default().trim().to_string();
+        if text.chars().count() > MAX_CHARS {
+            text = text.chars().take(MAX_CHARS).collect::<String>() + "…";
+        }
+        if let Some(toast) = self.items.iter_mut().find(|t| t.kind == kind && t.text == text) {
+            toast.shown_at = Instant::now();
+            return;
+        }
+        if self.items.len() >= MAX_TOASTS {
+            self.items.remove(0);
+        }
+        self.items.push(Toast { kind, text, shown_at: Instant::now() });
+    }
+}
+
+pub fn render_toasts(ctx: &egui::Context, toasts: &mut Toasts) {
+    toasts.items.retain(|t| t.shown_at.elapsed() < t.kind.lifetime());
+    if toasts.items.is_empty() {
+        return;
+    }
+
+    let mut dismiss = None;
+    let area = egui::Area::new(egui::Id::new("toasts"))
+        .anchor(egui::Align2::RIGHT_BOTTOM, egui::vec2(-12.0, -56.0))
+        .order(egui::Order::Foreground)
+        .show(ctx, |ui| {
+            ui.set_max_width(320.0);
+            for (i, toast) in toasts.items.iter().enumerate().rev() {
+                let color = toast.kind.color();
+                egui::Frame::popup(ui.style()).stroke(egui::Stroke::new(1.0, color)).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(toast.kind.icon()).color(color));
+                        ui.add(egui::Label::new(&toast.text).wrap());
+                        if ui.small_button("✕").clicked() {
+                            dismiss = Some(i);
+                        }
+                    });
+                });
+                ui.add_space(4.0);
+            }
+        });
+
+    if let Some(i) = dismiss {
+        toasts.items.remove(i);
+    }
+    // Hovering keeps them open
+    if area.response.contains_pointer() {
+        for toast in &mut toasts.items {
+            toast.shown_at = Instant::now();
+        }
+    }
+    if let Some(next) = toasts.items.iter().map(|t| t.kind.lifetime().saturating_sub(t.shown_at.elapsed())).min() {
+        ctx.request_repaint_after(next);
+    }
+}
@@ -0,0 +1,173 @@
+use crate::tools::todo::{self, TodoItem, PRIORITIES, STATUSES};
+use eframe::egui;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub enum TodoAction {
+    /// The user changed the list; write it back for the agent.
+    Save,
+    None,
+}
+
+/// Live view of `todos.json`, reloaded when the agent rewrites it.
+#[derive(Default)]
+pub struct TodoPanelState {
+    pub open: bool,
+    pub items: Vec<TodoItem>,
+    pub error: Option<String>,
+    modified: Option<SystemTime>,
+    last_poll: Option<Instant>,
+    new_item: String,
+}
+
+impl TodoPanelState {
+    /// Reload if the file changed since the last read.
+    pub fn poll(&mut self) {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let modified = std::fs::metadata(todo::TODO_FILE).and_then(|m| m.modified()).ok();
+        if modified == self.modified {
+            return;
+        }
+        self.modified = modified;
+        match todo::load() {
+            Ok(items) => {
+                self.items = items;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(format!("{:#}", e)),
+        }
+    }
+
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        todo::save(&self.items)?;
+        // Don't reload our own write
+        self.modified = std::fs::metadata(todo::TODO_FILE).and_then(|m| m.modified()).ok();
+        Ok(())
+    }
+
+    fn next_id(&self) -> String {
+        let max = self.items.iter().filter_map(|t| t.id.parse::<u32>().ok()).max().unwrap_or(0);
+        (max + 1).to_string()
+    }
+}
+
+fn status_color(status: &str) -> egui::Color32 {
+    match status {
+        "in_progress" => egui::Color32::from_rgb(100, 160, 255),
+        "completed" => egui::Color32::from_rgb(120, 200, 80),
+        "cancelled" => egui::Color32::GRAY,
+        _ => egui::Color32::from_rgb(240, 180, 60),
+    }
+}
+
+fn priority_color(priority: &str) -> egui::Color32 {
+    match priority {
+        "high" => egui::Color32::from_rgb(230, 80, 80),
+        "low" => egui::Color32::GRAY,
+        _ => egui::Color32::from_rgb(240, 180, 60),
+    }
+}
+
+/// A small colored label that cycles through `options` when clicked.
+fn chip(ui: &mut egui::Ui, value: &mut String, options: &[&str], color: egui::Color32) -> bool {
+    let text = egui::RichText::new(value.replace('_', " ")).small().color(color);
+    let clicked = ui
+        .add(egui::Button::new(text).small().stroke(egui::Stroke::new(1.0, color)))
+        .on_hover_text("Click to change")
+        .clicked();
+    if clicked {
+        let idx = options.iter().position(|o| o == value).map_or(0, |i| (i + 1) % options.len());
+        *value = options[idx].to_string();
+    }
+    clicked
+}
+
+pub fn render_todo_panel(ctx: &egui::Context, state: &mut TodoPanelState) -> TodoAction {
+    if !state.open {
+        return TodoAction::None;
+    }
+
+    let mut changed = false;
+    egui::SidePanel::right("todo_panel")
+        .resizable(true)
+        .default_width(280.0)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.heading("✅ Todos");
+                let active = state.items.iter().filter(|t| t.is_active()).count();
+                ui.label(egui::RichText::new(format!("{} active", active)).weak());
+            });
+            if let Some(error) = &state.error {
+                ui.colored_label(egui::Color32::RED, error);
+            }
+            ui.separator();
+
+            let mut remove = None;
+            egui::ScrollArea::vertical().auto_shrink([false, true]).max_height(ui.available_height() - 40.0).show(ui, |ui| {
+                if state.items.is_empty() {
+                    ui.label(egui::RichText::new("No tasks yet. Add one below or ask the agent to plan.").weak());
+                }
+                for (i, item) in state.items.iter_mut().enumerate() {
+                    ui.horizontal_wrapped(|ui| {
+                        let mut done = item.status == "completed";
+                        if ui.checkbox(&mut done, "").changed() {
+                            item.status = if done { "completed" } else { "pending" }.to_string();
+                            changed = true;
+                        }
+                        let mut text = egui::RichText::new(&item.content);
+                        if !item.is_active() {
+                            text = text.strikethrough().weak();
+                        }
+                        ui.label(text);
+                    });
+                    ui.horizontal(|ui| {
+                        ui.add_space(24.0);
+                        let color = status_color(&item.status);
+                        changed |= chip(ui, &mut item.status, &STATUSES, color);
+                        let color = priority_color(&item.priority);
+                        changed |= chip(ui, &mut item.priority, &PRIORITIES, color);
+                        if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                            remove = Some(i);
+                        }
+                    });
+                    ui.add_space(4.0);
+                }
+            });
+            if let Some(i) = remove {
+                state.items.remove(i);
+                changed = true;
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let input = ui.add(
+                    egui::TextEdit::singleline(&mut state.new_item)
+                        .hint_text("New task")
+                        .desired_width(ui.available_width() - 30.0),
+                );
+                let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if (ui.button("➕").clicked() || submitted) && !state.new_item.trim().is_empty() {
+                    let id = state.next_id();
+                    state.items.push(TodoItem {
+                        id,
+                        content: state.new_item.trim().to_string(),
+                        status: "pending".to_string(),
+                        priority: "medium".to_string(),
+                    });
+                    state.new_item.clear();
+                    changed = true;
+                }
+            });
+        });
+
+    if changed {
+        TodoAction::Save
+    } else {
+        TodoAction::None
+    }
+}
@@ -1,14 +1,38 @@
+use crate::game::GameStatus;
 use eframe::egui;
+use std::time::Duration;
 
 pub enum TopPanelAction {
     SwitchChannel(String),
     ClearChat,
     // ClearScene, // Hidden per user request
     CopyLog,
+    ExportChat,
+    TogglePreview,
+    ToggleConsole,
+    ToggleTodos,
+    ToggleAssets,
+    OpenSettings,
+    OpenProfiles,
+    RunGame,
+    StopGame,
+    RestartGame,
+    ConnectToGame,
     None,
 }
 
-pub fn render_top_panel(ui: &mut egui::Ui, active_channel_id: &str) -> TopPanelAction {
+#[allow(clippy::too_many_arguments)]
+pub fn render_top_panel(
+    ui: &mut egui::Ui,
+    active_channel_id: &str,
+    provider_unavailable_for: Option<Duration>,
+    preview_open: bool,
+    console_open: bool,
+    todos_open: bool,
+    assets_open: bool,
+    game_status: &GameStatus,
+    game_latency: Option<Duration>,
+) -> TopPanelAction {
     let mut action = TopPanelAction::None;
 
     ui.horizontal(|ui| {
@@ -17,6 +41,18 @@ pub fn render_top_panel(ui: &mut egui::Ui, active_channel_id: &str) -> TopPanelA
         });
 
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            if ui.button("⚙ Settings").clicked() {
+                action = TopPanelAction::OpenSettings;
+            }
+
+            ui.add_space(5.0);
+
+            if ui.button("🎭 Profiles").clicked() {
+                action = TopPanelAction::OpenProfiles;
+            }
+
+            ui.add_space(5.0);
+
             if ui.button("🗑️ Clear Chat").clicked() {
                 action = TopPanelAction::ClearChat;
             }
@@ -32,6 +68,106 @@ pub fn render_top_panel(ui: &mut egui::Ui, active_channel_id: &str) -> TopPanelA
             if ui.button("📋 Copy Log").clicked() {
                 action = TopPanelAction::CopyLog;
             }
+
+            ui.add_space(5.0);
+
+            if ui
+                .button("📤 Export")
+                .on_hover_text("Save this channel as Markdown or JSON, with tool calls and images")
+                .clicked()
+            {
+                action = TopPanelAction::ExportChat;
+            }
+
+            ui.add_space(5.0);
+
+            if ui
+                .selectable_label(preview_open, "🎮 Preview")
+                .on_hover_text("Show live screenshots of the running game")
+                .clicked()
+            {
+                action = TopPanelAction::TogglePreview;
+            }
+
+            ui.add_space(5.0);
+
+            if ui
+                .selectable_label(console_open, "🖥 Console")
+                .on_hover_text("Show app, tool and BRP logs")
+                .clicked()
+            {
+                action = TopPanelAction::ToggleConsole;
+            }
+
+            ui.add_space(5.0);
+
+            if ui
+                .selectable_label(todos_open, "✅ Todos")
+                .on_hover_text("Show the task list shared with the agent")
+                .clicked()
+            {
+                action = TopPanelAction::ToggleTodos;
+            }
+
+            ui.add_space(5.0);
+
+            if ui
+                .selectable_label(assets_open, "🗃 Assets")
+                .on_hover_text("Browse project assets and what the game has cached, and spawn models")
+                .clicked()
+            {
+                action = TopPanelAction::ToggleAssets;
+            }
+
+            ui.add_space(10.0);
+            ui.separator();
+            if ui.button("🔌").on_hover_text("Connect to a game, also on another machine").clicked() {
+                action = TopPanelAction::ConnectToGame;
+            }
+            if let Some(latency) = game_latency {
+                ui.label(egui::RichText::new(format!("📶 {} ms", latency.as_millis())).weak())
+                    .on_hover_text("BRP round trip to the game");
+            }
+            match game_status {
+                GameStatus::Idle => {
+                    if ui.button("▶ Run game").on_hover_text("Build with cargo and start the game").clicked() {
+                        action = TopPanelAction::RunGame;
+                    }
+                }
+                GameStatus::Building => {
+                    ui.label("Building the game…");
+                    ui.spinner();
+                }
+                GameStatus::Running { reachable } => {
+                    if ui.button("⟳ Restart").on_hover_text("Rebuild and restart the game").clicked() {
+                        action = TopPanelAction::RestartGame;
+                    }
+                    if ui.button("■ Stop").clicked() {
+                        action = TopPanelAction::StopGame;
+                    }
+                    if *reachable == Some(false) {
+                        ui.label(egui::RichText::new("⚠ Game not responding").color(egui::Color32::from_rgb(255, 140, 0)))
+                            .on_hover_text("The game is running but its BRP endpoint stopped answering.");
+                    } else {
+                        ui.label("🎮 Running");
+                    }
+                }
+                GameStatus::Stopped(reason) => {
+                    if ui.button("⟳ Restart").on_hover_text("Rebuild and restart the game").clicked() {
+                        action = TopPanelAction::RestartGame;
+                    }
+                    ui.label(egui::RichText::new("Game stopped").weak()).on_hover_text(reason);
+                }
+            }
+
+            if let Some(remaining) = provider_unavailable_for {
+                ui.add_space(10.0);
+                ui.label(
+                    egui::RichText::new(format!("⚠ Provider unavailable ({}s)", remaining.as_secs() + 1))
+                        .color(egui::Color32::from_rgb(255, 140, 0)),
+                )
+                .on_hover_text("Requests failed repeatedly; new requests are paused until the cooldown ends.");
+            }
         });
     });
 
@@ -0,0 +1,101 @@
+use bevy_bridge_core::types::History;
+use eframe::egui;
+use std::time::{Duration, Instant};
+
+/// The game's journal also changes when the agent edits the scene.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+
+pub enum UndoHistoryAction {
+    Undo,
+    Redo,
+    Refresh,
+    None,
+}
+
+#[derive(Default)]
+pub struct UndoHistoryState {
+    pub open: bool,
+    pub history: Option<History>,
+    pub error: Option<String>,
+    /// A request to the game is running.
+    pub busy: bool,
+    refreshed: Option<Instant>,
+}
+
+impl UndoHistoryState {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.refreshed = None;
+    }
+
+    pub fn receive(&mut self, result: Result<History, String>) {
+        self.busy = false;
+        match result {
+            Ok(history) => {
+                self.history = Some(history);
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+}
+
+pub fn render_undo_history(ctx: &egui::Context, state: &mut UndoHistoryState) -> UndoHistoryAction {
+    let mut action = UndoHistoryAction::None;
+    if !state.open {
+        return action;
+    }
+    if !state.busy && state.refreshed.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+        state.refreshed = Some(Instant::now());
+        action = UndoHistoryAction::Refresh;
+    }
+    ctx.request_repaint_after(REFRESH_INTERVAL);
+
+    let mut open = true;
+    egui::Window::new("↶ Undo history")
+        .open(&mut open)
+        .default_size([360.0, 400.0])
+        .show(ctx, |ui| {
+            let (can_undo, can_redo) =
+                state.history.as_ref().map_or((false, false), |h| (h.can_undo(), h.can_redo()));
+            ui.horizontal(|ui| {
+                if ui.add_enabled(can_undo && !state.busy, egui::Button::new("↶ Undo")).clicked() {
+                    action = UndoHistoryAction::Undo;
+                }
+                if ui.add_enabled(can_redo && !state.busy, egui::Button::new("↷ Redo")).clicked() {
+                    action = UndoHistoryAction::Redo;
+                }
+                if state.busy {
+                    ui.spinner();
+                }
+            });
+            ui.separator();
+
+            if let Some(error) = &state.error {
+                ui.colored_label(egui::Color32::from_rgb(230, 80, 80), error);
+                return;
+            }
+            let Some(history) = &state.history else {
+                return;
+            };
+            if history.entries.is_empty() {
+                ui.label(egui::RichText::new("Nothing to undo. Spawning, moving or clearing entities from the editor adds steps here.").weak());
+                return;
+            }
+            egui::ScrollArea::vertical().auto_shrink([false, true]).show(ui, |ui| {
+                for (i, entry) in history.entries.iter().enumerate().rev() {
+                    let text = format!("{}  ({} change{})", entry.label, entry.changes, if entry.changes == 1 { "" } else { "s" });
+                    if i < history.cursor {
+                        ui.label(text);
+                    } else {
+                        ui.label(egui::RichText::new(text).weak().italics()).on_hover_text("Undone; redo to apply it again");
+                    }
+                }
+            });
+        });
+
+    if !open {
+        state.open = false;
+    }
+    action
+}
@@ -0,0 +1,41 @@
+//! Panels popped out of the main window into their own OS windows.
+
+use eframe::egui;
+
+/// Header button that pops a panel out, or docks it back.
+pub fn detach_button(ui: &mut egui::Ui, detached: &mut bool) {
+    let (text, hint) = if *detached {
+        ("Dock", "Move back into the main window")
+    } else {
+        ("Pop out", "Open in a separate window")
+    };
+    if ui.small_button(text).on_hover_text(hint).clicked() {
+        *detached = !*detached;
+    }
+}
+
+/// Draw `contents` in a separate window titled `title`; returns true once
+/// the user closed it. Backends without multiple viewports get a floating
+/// egui window instead.
+pub fn show_detached(
+    ctx: &egui::Context,
+    id: &str,
+    title: &str,
+    size: [f32; 2],
+    mut contents: impl FnMut(&mut egui::Ui),
+) -> bool {
+    let builder = egui::ViewportBuilder::default().with_title(title).with_inner_size(size);
+    ctx.show_viewport_immediate(egui::ViewportId::from_hash_of(id), builder, |ctx, class| {
+        if class == egui::ViewportClass::Embedded {
+            let mut open = true;
+            egui::Window::new(title)
+                .id(egui::Id::new(id))
+                .open(&mut open)
+                .default_size(size)
+                .show(ctx, |ui| contents(ui));
+            return !open;
+        }
+        egui::CentralPanel::default().show(ctx, |ui| contents(ui));
+        ctx.input(|i| i.viewport().close_requested())
+    })
+}
@@ -0,0 +1,72 @@
+//! Flat-file vector store with brute-force cosine search.
+//!
+//! Good enough for a few tens of thousands of entries, which covers a game
+//! project's sources and docs. Each store records the embedding model it was
+//! built with; vectors from different models aren't comparable, so a store
+//! for another model loads as empty.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorEntry<M> {
+    pub vector: Vec<f32>,
+    pub meta: M,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorStore<M> {
+    model: String,
+    entries: Vec<VectorEntry<M>>,
+}
+
+impl<M> VectorStore<M> {
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn push(&mut self, vector: Vec<f32>, meta: M) {
+        self.entries.push(VectorEntry { vector, meta });
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&M) -> bool) {
+        self.entries.retain(|e| keep(&e.meta));
+    }
+
+    /// The `top_k` entries most similar to `query`, best first.
+    pub fn search(&self, query: &[f32], top_k: usize) -> Vec<(f32, &M)> {
+        let mut hits: Vec<(f32, &M)> = self
+            .entries
+            .iter()
+            .map(|e| (cosine_similarity(query, &e.vector), &e.meta))
+            .collect();
+
+        hits.sort_by(|a, b| b.0.total_cmp(&a.0));
+        hits.truncate(top_k);
+        hits
+    }
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
@@ -0,0 +1,69 @@
+//! Optional voice input and output (`--features voice`).
+//!
+//! Speech-to-text records the default microphone and sends the clip to an
+//! OpenAI-compatible `/audio/transcriptions` endpoint, or to a local
+//! whisper.cpp server when `AXIOM_WHISPER_URL` is set. Text-to-speech reads
+//! replies aloud through `/audio/speech`.
+
+pub mod recorder;
+pub mod speaker;
+pub mod stt;
+pub mod tts;
+
+pub use recorder::Recorder;
+pub use speaker::Speaker;
+
+/// Endpoint settings shared by STT and TTS.
+///
+/// `AXIOM_VOICE_BASE_URL` / `AXIOM_VOICE_API_KEY`, falling back to the
+/// chat endpoint's `GEMINI_BASE_URL` / `GEMINI_API_KEY`.
+#[derive(Clone, Debug)]
+pub struct VoiceConfig {
+    pub base_url: String,
+    pub api_key: String,
+    /// whisper.cpp server root (e.g. `http://127.0.0.1:8080`); overrides `base_url` for STT.
+    pub whisper_url: Option<String>,
+    pub stt_model: String,
+    pub tts_model: String,
+    pub tts_voice: String,
+}
+
+impl VoiceConfig {
+    pub fn from_env() -> Self {
+        let var = |key: &str| std::env::var(key).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            base_url: var("AXIOM_VOICE_BASE_URL")
+                .or_else(|| var("GEMINI_BASE_URL"))
+                .unwrap_or_else(|| "https://api.openai.com/v1".to_string()),
+            api_key: var("AXIOM_VOICE_API_KEY")
+                .or_else(|| var("GEMINI_API_KEY"))
+                .unwrap_or_default(),
+            whisper_url: var("AXIOM_WHISPER_URL"),
+            stt_model: var("AXIOM_STT_MODEL").unwrap_or_else(|| "whisper-1".to_string()),
+            tts_model: var("AXIOM_TTS_MODEL").unwrap_or_else(|| "tts-1".to_string()),
+            tts_voice: var("AXIOM_TTS_VOICE").unwrap_or_else(|| "alloy".to_string()),
+        }
+    }
+}
+
+/// Voice state held by the app: the active recording and playback.
+pub struct VoiceState {
+    pub config: VoiceConfig,
+    pub recorder: Option<Recorder>,
+    pub speaker: Speaker,
+    pub transcribing: bool,
+    /// Read every finished reply aloud.
+    pub speak_replies: bool,
+}
+
+impl VoiceState {
+    pub fn new() -> Self {
+        Self {
+            config: VoiceConfig::from_env(),
+            recorder: None,
+            speaker: Speaker::new(),
+            transcribing: false,
+            speak_replies: false,
+        }
+    }
+}
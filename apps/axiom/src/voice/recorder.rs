@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Context, Result};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SampleFormat, Stream};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+
+/// Records mono audio from the default input device until stopped.
+///
+/// Holds the cpal stream, which is not `Send` on every platform, so it lives
+/// on the UI thread; only the finished WAV clip crosses into async tasks.
+pub struct Recorder {
+    stream: Stream,
+    samples: Arc<Mutex<Vec<f32>>>,
+    sample_rate: u32,
+}
+
+impl Recorder {
+    pub fn start() -> Result<Self> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or_else(|| anyhow!("No microphone found"))?;
+        let config = device
+            .default_input_config()
+            .context("Failed to query microphone config")?;
+
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels() as usize;
+        let samples = Arc::new(Mutex::new(Vec::new()));
+        let sink = samples.clone();
+        let on_error = |e| tracing::warn!("Input stream error: {}", e);
+
+        // Downmix to mono as samples arrive
+        let stream = match config.sample_format() {
+            SampleFormat::F32 => device.build_input_stream(
+                &config.into(),
+                move |data: &[f32], _: &_| push_mono(&sink, data.chunks(channels).map(mean)),
+                on_error,
+                None,
+            ),
+            SampleFormat::I16 => device.build_input_stream(
+                &config.into(),
+                move |data: &[i16], _: &_| {
+                    push_mono(&sink, data.chunks(channels).map(|f| mean_by(f, |s| s as f32 / i16::MAX as f32)))
+                },
+                on_error,
+                None,
+            ),
+            SampleFormat::U16 => device.build_input_stream(
+                &config.into(),
+                move |data: &[u16], _: &_| {
+                    push_mono(&sink, data.chunks(channels).map(|f| mean_by(f, |s| s as f32 / 32768.0 - 1.0)))
+                },
+                on_error,
+                None,
+            ),
+            other => return Err(anyhow!("Unsupported microphone sample format: {:?}", other)),
+        }
+        .context("Failed to open microphone")?;
+
+        stream.play().context("Failed to start recording")?;
+        Ok(Self { stream, samples, sample_rate })
+    }
+
+    /// Stop recording and return the clip as a 16-bit mono WAV file.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        drop(self.stream);
+        let samples = std::mem::take(&mut *self.samples.lock().unwrap_or_else(|e| e.into_inner()));
+        if samples.is_empty() {
+            return Err(anyhow!("No audio was recorded"));
+        }
+
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut wav = Vec::new();
+        let mut writer = hound::WavWriter::new(Cursor::new(&mut wav), spec)?;
+        for sample in samples {
+            writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+        }
+        writer.finalize()?;
+        Ok(wav)
+    }
+}
+
+fn push_mono(sink: &Mutex<Vec<f32>>, frames: impl Iterator<Item = f32>) {
+    sink.lock().unwrap_or_else(|e| e.into_inner()).extend(frames);
+}
+
+fn mean(frame: &[f32]) -> f32 {
+    mean_by(frame, |s| s)
+}
+
+fn mean_by<T: Copy>(frame: &[T], to_f32: impl Fn(T) -> f32) -> f32 {
+    frame.iter().map(|&s| to_f32(s)).sum::<f32>() / frame.len().max(1) as f32
+}
@@ -0,0 +1,63 @@
+use std::io::Cursor;
+use std::sync::mpsc::{channel, Sender};
+
+enum Command {
+    Play(Vec<u8>),
+    Stop,
+}
+
+/// Plays encoded audio clips on a dedicated thread.
+///
+/// The rodio output stream has to stay on the thread that opened it; this
+/// handle is `Send` and cheap to clone into async tasks. A new clip replaces
+/// the one playing.
+#[derive(Clone)]
+pub struct Speaker {
+    tx: Sender<Command>,
+}
+
+impl Speaker {
+    pub fn new() -> Self {
+        let (tx, rx) = channel::<Command>();
+
+        std::thread::spawn(move || {
+            let (_stream, handle) = match rodio::OutputStream::try_default() {
+                Ok(output) => output,
+                Err(e) => {
+                    tracing::warn!("No audio output: {}", e);
+                    return;
+                }
+            };
+            let mut sink: Option<rodio::Sink> = None;
+
+            for command in rx {
+                if let Some(current) = sink.take() {
+                    current.stop();
+                }
+                let Command::Play(bytes) = command else {
+                    continue;
+                };
+
+                let decoded = rodio::Decoder::new(Cursor::new(bytes));
+                match (decoded, rodio::Sink::try_new(&handle)) {
+                    (Ok(source), Ok(new_sink)) => {
+                        new_sink.append(source);
+                        sink = Some(new_sink);
+                    }
+                    (Err(e), _) => tracing::warn!("Failed to decode speech: {}", e),
+                    (_, Err(e)) => tracing::warn!("Failed to open audio sink: {}", e),
+                }
+            }
+        });
+
+        Self { tx }
+    }
+
+    pub fn play(&self, audio: Vec<u8>) {
+        let _ = self.tx.send(Command::Play(audio));
+    }
+
+    pub fn stop(&self) {
+        let _ = self.tx.send(Command::Stop);
+    }
+}
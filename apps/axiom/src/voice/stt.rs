@@ -0,0 +1,40 @@
+use anyhow::{anyhow, Context, Result};
+use reqwest::multipart::{Form, Part};
+use serde_json::Value;
+
+use super::VoiceConfig;
+
+/// Transcribe a WAV clip, via whisper.cpp if configured, else the
+/// OpenAI-compatible transcription endpoint.
+pub async fn transcribe(config: &VoiceConfig, wav: Vec<u8>) -> Result<String> {
+    let file = Part::bytes(wav)
+        .file_name("speech.wav")
+        .mime_str("audio/wav")?;
+    let client = reqwest::Client::new();
+
+    let request = match &config.whisper_url {
+        Some(whisper) => {
+            let form = Form::new().part("file", file).text("response_format", "json");
+            client.post(format!("{}/inference", whisper.trim_end_matches('/'))).multipart(form)
+        }
+        None => {
+            let form = Form::new().part("file", file).text("model", config.stt_model.clone());
+            client
+                .post(format!("{}/audio/transcriptions", config.base_url.trim_end_matches('/')))
+                .header("Authorization", format!("Bearer {}", config.api_key))
+                .multipart(form)
+        }
+    };
+
+    let response = request.send().await.context("Failed to send transcription request")?;
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(anyhow!("Transcription error: {}", error_text));
+    }
+
+    let body: Value = response.json().await.context("Failed to parse transcription response")?;
+    body["text"]
+        .as_str()
+        .map(|t| t.trim().to_string())
+        .ok_or_else(|| anyhow!("Transcription response has no text"))
+}
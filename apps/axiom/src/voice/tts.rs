@@ -0,0 +1,42 @@
+use anyhow::{anyhow, Context, Result};
+use serde_json::json;
+
+use super::VoiceConfig;
+
+/// Longest text sent for speech; the endpoint rejects more than 4096 chars.
+const MAX_SPEECH_CHARS: usize = 4000;
+
+/// Synthesize `text` through `/audio/speech`, returning a WAV clip.
+pub async fn synthesize(config: &VoiceConfig, text: &str) -> Result<Vec<u8>> {
+    let input: String = speakable(text).chars().take(MAX_SPEECH_CHARS).collect();
+    if input.trim().is_empty() {
+        return Err(anyhow!("Nothing to read aloud"));
+    }
+
+    let response = reqwest::Client::new()
+        .post(format!("{}/audio/speech", config.base_url.trim_end_matches('/')))
+        .header("Authorization", format!("Bearer {}", config.api_key))
+        .json(&json!({
+            "model": config.tts_model,
+            "voice": config.tts_voice,
+            "input": input,
+            "response_format": "wav",
+        }))
+        .send()
+        .await
+        .context("Failed to send speech request")?;
+
+    if !response.status().is_success() {
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+        return Err(anyhow!("Speech error: {}", error_text));
+    }
+    Ok(response.bytes().await?.to_vec())
+}
+
+/// Drop fenced code blocks; reading source code aloud helps nobody.
+fn speakable(text: &str) -> String {
+    text.split("```")
+        .step_by(2)
+        .collect::<Vec<_>>()
+        .join(" (code omitted) ")
+}
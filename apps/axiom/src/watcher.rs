@@ -0,0 +1,98 @@
+//! Polling file watcher.
+//!
+//! A background thread fingerprints the watched tree (paths, sizes and
+//! modification times) every second and bumps a revision counter, waking the
+//! UI, when it changes. Hidden entries and `target/` are skipped.
+
+use eframe::egui;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Stop fingerprinting huge trees after this many entries.
+const MAX_ENTRIES: usize = 20_000;
+
+struct Shared {
+    root: Mutex<PathBuf>,
+    revision: AtomicU64,
+}
+
+pub struct Watcher {
+    shared: Arc<Shared>,
+}
+
+impl Watcher {
+    /// The thread ends once the watcher is dropped.
+    pub fn spawn(root: PathBuf, ctx: egui::Context) -> Self {
+        let shared = Arc::new(Shared { root: Mutex::new(root), revision: AtomicU64::new(0) });
+        let weak = Arc::downgrade(&shared);
+        std::thread::spawn(move || {
+            let mut last = None;
+            loop {
+                let Some(shared) = weak.upgrade() else {
+                    return;
+                };
+                let root = shared.root.lock().unwrap().clone();
+                let print = fingerprint(&root);
+                if last.is_some_and(|last| last != print) {
+                    shared.revision.fetch_add(1, Ordering::Relaxed);
+                    ctx.request_repaint();
+                }
+                last = Some(print);
+                drop(shared);
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+        Self { shared }
+    }
+
+    /// Watch `root` instead; counts as a change.
+    pub fn watch(&self, root: &Path) {
+        let mut current = self.shared.root.lock().unwrap();
+        if *current != root {
+            *current = root.to_path_buf();
+            self.shared.revision.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Increases whenever something under the root changed.
+    pub fn revision(&self) -> u64 {
+        self.shared.revision.load(Ordering::Relaxed)
+    }
+}
+
+fn fingerprint(root: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let mut stack = vec![root.to_path_buf()];
+    let mut seen = 0;
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || name == "target" {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            entry.path().hash(&mut hasher);
+            meta.len().hash(&mut hasher);
+            meta.modified().ok().hash(&mut hasher);
+            if meta.is_dir() {
+                stack.push(entry.path());
+            }
+            seen += 1;
+            if seen >= MAX_ENTRIES {
+                return hasher.finish();
+            }
+        }
+    }
+    hasher.finish()
+}
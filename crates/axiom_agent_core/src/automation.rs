@@ -0,0 +1,581 @@
+//! Declarative automation: fixed sequences of tool calls, without a model.
+//!
+//! A script is YAML or JSON. Arguments can use `${name}` variables, from the
+//! script's `vars`, a `set` step or a step's `save`d output; `${name.a.0}`
+//! reads into JSON values and JSON tool output. A string that is exactly one
+//! `${...}` keeps the variable's type. Steps can be guarded by `if`, and
+//! `foreach` repeats its `steps` for each item of a list:
+//!
+//! ```yaml
+//! vars:
+//!   model: assets/models/tree.glb
+//!   ring: [[4, 0, 0], [0, 0, 4], [-4, 0, 0], [0, 0, -4]]
+//! steps:
+//!   - tool: bevy_clear_scene
+//!   - foreach: ${ring}
+//!     as: position
+//!     steps:
+//!       - tool: bevy_upload_asset
+//!         args: { local_path: "${model}", translation: "${position}" }
+//!         save: spawned
+//!   - tool: write_file
+//!     args: { path: spawned.txt, content: "${spawned}" }
+//!     if: { var: spawned, exists: true }
+//! ```
+//!
+//! Every tool name is checked before anything runs. A failing step stops the
+//! script unless it sets `continue_on_error`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+use crate::tool::{execute_reviewed, EditReviewer, Tool};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Script {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub vars: Map<String, Value>,
+    pub steps: Vec<Step>,
+}
+
+/// One of `tool`, `set` or `foreach`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Step {
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub args: Value,
+    /// Variable that receives the tool's output.
+    pub save: Option<String>,
+    #[serde(default)]
+    pub continue_on_error: bool,
+    /// Variables to assign.
+    pub set: Option<Map<String, Value>>,
+    /// A list, usually `${var}`, to run `steps` for.
+    pub foreach: Option<Value>,
+    /// Name of the current item in a `foreach`; `item` by default. Its
+    /// position is in `index`.
+    #[serde(rename = "as")]
+    pub item: Option<String>,
+    #[serde(default)]
+    pub steps: Vec<Step>,
+    #[serde(rename = "if")]
+    pub condition: Option<Condition>,
+}
+
+/// Holds when every check given holds; with none, when `var` is set and
+/// not empty, `false`, `null` or `0`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    pub var: String,
+    pub exists: Option<bool>,
+    pub equals: Option<Value>,
+    pub not_equals: Option<Value>,
+    pub contains: Option<String>,
+    pub not_contains: Option<String>,
+}
+
+/// Progress reported while a script runs. `step` is the 1-based position,
+/// e.g. `2.3` for the third step inside the second.
+#[derive(Debug, Clone)]
+pub enum AutomationEvent {
+    ToolCall { step: String, name: String, arguments: String },
+    ToolResult { step: String, name: String, output: Result<String, String> },
+    Skipped { step: String },
+}
+
+#[derive(Debug, Default)]
+pub struct AutomationReport {
+    pub calls: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    /// Variables at the end, including saved outputs.
+    pub vars: Map<String, Value>,
+}
+
+impl AutomationReport {
+    pub fn summary(&self) -> String {
+        format!("{} tool call(s), {} failed, {} step(s) skipped", self.calls, self.failed, self.skipped)
+    }
+}
+
+impl Script {
+    /// YAML, or JSON for `.json` files.
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let is_json = path.extension().is_some_and(|e| e.eq_ignore_ascii_case("json"));
+        let script = if is_json { Self::from_json(&text) } else { Self::from_yaml(&text) };
+        script.with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn from_yaml(text: &str) -> Result<Self> {
+        Ok(serde_yaml::from_str(text)?)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// Check the step structure and that every tool exists in `tools`.
+    pub fn validate(&self, tools: &[Box<dyn Tool>]) -> Result<()> {
+        let mut problems = Vec::new();
+        validate_steps(&self.steps, "", tools, &mut problems);
+        if !problems.is_empty() {
+            bail!("Invalid script:\n- {}", problems.join("\n- "));
+        }
+        Ok(())
+    }
+}
+
+fn validate_steps(steps: &[Step], prefix: &str, tools: &[Box<dyn Tool>], problems: &mut Vec<String>) {
+    for (i, step) in steps.iter().enumerate() {
+        let label = format!("{}{}", prefix, i + 1);
+        let kinds = [step.tool.is_some(), step.set.is_some(), step.foreach.is_some()];
+        match kinds.iter().filter(|k| **k).count() {
+            1 => {}
+            0 => problems.push(format!("step {} needs one of `tool`, `set` or `foreach`", label)),
+            _ => problems.push(format!("step {} has more than one of `tool`, `set` and `foreach`", label)),
+        }
+        if let Some(tool) = &step.tool {
+            if !tools.iter().any(|t| &t.name() == tool) {
+                problems.push(format!("step {}: unknown tool `{}`", label, tool));
+            }
+        }
+        if step.foreach.is_some() {
+            if step.steps.is_empty() {
+                problems.push(format!("step {}: `foreach` has no `steps`", label));
+            }
+            validate_steps(&step.steps, &format!("{}.", label), tools, problems);
+        } else if !step.steps.is_empty() {
+            problems.push(format!("step {}: only `foreach` can have `steps`", label));
+        }
+    }
+}
+
+pub struct AutomationRunner<'a> {
+    tools: &'a [Box<dyn Tool>],
+    reviewer: Option<&'a dyn EditReviewer>,
+}
+
+impl<'a> AutomationRunner<'a> {
+    pub fn new(tools: &'a [Box<dyn Tool>]) -> Self {
+        Self { tools, reviewer: None }
+    }
+
+    /// Ask `reviewer` before any tool modifies a file.
+    pub fn with_reviewer(mut self, reviewer: &'a dyn EditReviewer) -> Self {
+        self.reviewer = Some(reviewer);
+        self
+    }
+
+    /// Run `script` with `vars` overriding its own. Fails on the first step
+    /// that fails without `continue_on_error`.
+    pub fn run<F>(&self, script: &Script, vars: Map<String, Value>, mut on_event: F) -> Result<AutomationReport>
+    where
+        F: FnMut(AutomationEvent),
+    {
+        script.validate(self.tools)?;
+        let mut report = AutomationReport { vars: script.vars.clone(), ..Default::default() };
+        report.vars.extend(vars);
+        self.run_steps(&script.steps, "", &mut report, &mut on_event)?;
+        Ok(report)
+    }
+
+    fn run_steps(
+        &self,
+        steps: &[Step],
+        prefix: &str,
+        report: &mut AutomationReport,
+        on_event: &mut dyn FnMut(AutomationEvent),
+    ) -> Result<()> {
+        for (i, step) in steps.iter().enumerate() {
+            let label = format!("{}{}", prefix, i + 1);
+            if let Some(condition) = &step.condition {
+                if !condition.holds(&report.vars)? {
+                    report.skipped += 1;
+                    on_event(AutomationEvent::Skipped { step: label });
+                    continue;
+                }
+            }
+
+            if let Some(set) = &step.set {
+                for (name, value) in set {
+                    let value = substitute(value, &report.vars).with_context(|| format!("Step {}", label))?;
+                    report.vars.insert(name.clone(), value);
+                }
+            } else if let Some(list) = &step.foreach {
+                let list = substitute(list, &report.vars).with_context(|| format!("Step {}", label))?;
+                let items = match as_json(&list) {
+                    Value::Array(items) => items,
+                    other => bail!("Step {}: `foreach` needs a list, got {}", label, other),
+                };
+                let name = step.item.clone().unwrap_or_else(|| "item".to_string());
+                for (index, item) in items.into_iter().enumerate() {
+                    report.vars.insert(name.clone(), item);
+                    report.vars.insert("index".to_string(), json!(index));
+                    self.run_steps(&step.steps, &format!("{}.", label), report, on_event)?;
+                }
+            } else if let Some(name) = &step.tool {
+                self.call(step, name, &label, report, on_event)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn call(
+        &self,
+        step: &Step,
+        name: &str,
+        label: &str,
+        report: &mut AutomationReport,
+        on_event: &mut dyn FnMut(AutomationEvent),
+    ) -> Result<()> {
+        let tool = self
+            .tools
+            .iter()
+            .find(|t| t.name() == name)
+            .ok_or_else(|| anyhow!("Unknown tool `{}`", name))?;
+        let args = substitute(&step.args, &report.vars).with_context(|| format!("Step {}", label))?;
+        on_event(AutomationEvent::ToolCall {
+            step: label.to_string(),
+            name: name.to_string(),
+            arguments: args.to_string(),
+        });
+
+        report.calls += 1;
+        let result = execute_reviewed(tool.as_ref(), args, self.reviewer).map_err(|e| format!("{:#}", e));
+        on_event(AutomationEvent::ToolResult {
+            step: label.to_string(),
+            name: name.to_string(),
+            output: result.clone(),
+        });
+
+        match result {
+            Ok(output) => {
+                if let Some(var) = &step.save {
+                    report.vars.insert(var.clone(), Value::String(output.clone()));
+                }
+                report.vars.insert("last".to_string(), Value::String(output));
+                Ok(())
+            }
+            Err(e) => {
+                report.failed += 1;
+                if let Some(var) = &step.save {
+                    report.vars.remove(var);
+                }
+                if step.continue_on_error {
+                    Ok(())
+                } else {
+                    Err(anyhow!("Step {} (`{}`) failed: {}", label, name, e))
+                }
+            }
+        }
+    }
+}
+
+impl Condition {
+    fn holds(&self, vars: &Map<String, Value>) -> Result<bool> {
+        let value = lookup(&self.var, vars);
+        if let Some(exists) = self.exists {
+            if value.is_some() != exists {
+                return Ok(false);
+            }
+        }
+        let text = value.as_ref().map(display);
+        let expect = |expected: &Value| -> Result<String> { Ok(display(&substitute(expected, vars)?)) };
+
+        if let Some(expected) = &self.equals {
+            if text.as_deref() != Some(expect(expected)?.as_str()) {
+                return Ok(false);
+            }
+        }
+        if let Some(expected) = &self.not_equals {
+            if text.as_deref() == Some(expect(expected)?.as_str()) {
+                return Ok(false);
+            }
+        }
+        if let Some(needle) = &self.contains {
+            if !text.as_deref().is_some_and(|t| t.contains(needle.as_str())) {
+                return Ok(false);
+            }
+        }
+        if let Some(needle) = &self.not_contains {
+            if text.as_deref().is_some_and(|t| t.contains(needle.as_str())) {
+                return Ok(false);
+            }
+        }
+
+        let has_checks = self.exists.is_some()
+            || self.equals.is_some()
+            || self.not_equals.is_some()
+            || self.contains.is_some()
+            || self.not_contains.is_some();
+        Ok(has_checks || value.is_some_and(|v| truthy(&v)))
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64() != Some(0.0),
+        Value::String(s) => !s.trim().is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+    }
+}
+
+/// Strings as they are, anything else as JSON.
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Strings holding JSON (such as tool output) parsed, anything else as is.
+fn as_json(value: &Value) -> Value {
+    match value {
+        Value::String(s) => serde_json::from_str(s.trim()).unwrap_or_else(|_| value.clone()),
+        other => other.clone(),
+    }
+}
+
+/// `name` or `name.key.0`; `None` if any part is missing.
+fn lookup(path: &str, vars: &Map<String, Value>) -> Option<Value> {
+    let mut parts = path.trim().split('.');
+    let mut value = vars.get(parts.next()?)?.clone();
+    for part in parts {
+        value = match as_json(&value) {
+            Value::Object(map) => map.get(part)?.clone(),
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?.clone(),
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Replace `${...}` references in every string inside `value`.
+fn substitute(value: &Value, vars: &Map<String, Value>) -> Result<Value> {
+    Ok(match value {
+        Value::String(text) => {
+            let trimmed = text.trim();
+            let whole = trimmed
+                .strip_prefix("${")
+                .and_then(|rest| rest.strip_suffix('}'))
+                .filter(|inner| !inner.contains("${"));
+            match whole {
+                Some(path) => lookup(path, vars).ok_or_else(|| anyhow!("Unknown variable `{}`", path))?,
+                None => Value::String(interpolate(text, vars)?),
+            }
+        }
+        Value::Array(items) => Value::Array(items.iter().map(|v| substitute(v, vars)).collect::<Result<_>>()?),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), substitute(v, vars)?)))
+                .collect::<Result<_>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn interpolate(text: &str, vars: &Map<String, Value>) -> Result<String> {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| anyhow!("Unclosed `${{` in \"{}\"", text))? + start;
+        let path = &rest[start + 2..end];
+        let value = lookup(path, vars).ok_or_else(|| anyhow!("Unknown variable `{}`", path))?;
+        out.push_str(&display(&value));
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records its arguments and answers with them as JSON; fails when
+    /// asked to via `"fail": true`.
+    struct EchoTool {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<Value>>>,
+    }
+
+    impl Tool for EchoTool {
+        fn name(&self) -> String {
+            self.name.to_string()
+        }
+        fn description(&self) -> String {
+            "Echo".to_string()
+        }
+        fn schema(&self) -> Value {
+            json!({ "type": "function", "function": { "name": self.name, "parameters": { "type": "object" } } })
+        }
+        fn execute(&self, args: Value) -> Result<String> {
+            self.calls.lock().unwrap().push(args.clone());
+            if args["fail"] == json!(true) {
+                bail!("asked to fail");
+            }
+            Ok(args.to_string())
+        }
+    }
+
+    type Calls = Arc<Mutex<Vec<Value>>>;
+
+    fn tools() -> (Vec<Box<dyn Tool>>, Calls) {
+        let calls = Calls::default();
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(EchoTool { name: "echo", calls: calls.clone() }),
+            Box::new(EchoTool { name: "spawn", calls: calls.clone() }),
+        ];
+        (tools, calls)
+    }
+
+    fn run(yaml: &str) -> (Result<AutomationReport>, Vec<Value>, Vec<String>) {
+        let (tools, calls) = tools();
+        let mut events = Vec::new();
+        let script = Script::from_yaml(yaml).unwrap();
+        let result = AutomationRunner::new(&tools).run(&script, Map::new(), |event| {
+            events.push(match event {
+                AutomationEvent::ToolCall { step, name, .. } => format!("{} {}", step, name),
+                AutomationEvent::ToolResult { step, output, .. } => format!("{} {}", step, if output.is_ok() { "ok" } else { "err" }),
+                AutomationEvent::Skipped { step } => format!("{} skipped", step),
+            })
+        });
+        let calls = calls.lock().unwrap().clone();
+        (result, calls, events)
+    }
+
+    #[test]
+    fn substitutes_variables_keeping_types() {
+        let (result, calls, _) = run(
+            r#"
+vars:
+  model: tree.glb
+  position: [1, 0, 2]
+steps:
+  - tool: spawn
+    args: { path: "models/${model}", translation: "${position}", x: "${position.0}" }
+"#,
+        );
+        result.unwrap();
+        assert_eq!(calls, vec![json!({ "path": "models/tree.glb", "translation": [1, 0, 2], "x": 1 })]);
+    }
+
+    #[test]
+    fn foreach_and_saved_json_output() {
+        let (result, calls, events) = run(
+            r#"
+vars:
+  ring: [[4, 0, 0], [-4, 0, 0]]
+steps:
+  - foreach: ${ring}
+    as: pos
+    steps:
+      - tool: spawn
+        args: { translation: "${pos}", n: "${index}" }
+        save: spawned
+  - tool: echo
+    args: { last_x: "${spawned.translation.0}" }
+"#,
+        );
+        let report = result.unwrap();
+        assert_eq!(report.calls, 3);
+        assert_eq!(calls[1], json!({ "translation": [-4, 0, 0], "n": 1 }));
+        assert_eq!(calls[2], json!({ "last_x": -4 }));
+        assert_eq!(events[..2], ["1.1 spawn", "1.1 ok"]);
+    }
+
+    #[test]
+    fn conditions_skip_steps() {
+        let (result, calls, events) = run(
+            r#"
+vars:
+  mode: fast
+steps:
+  - tool: echo
+    args: { status: ready }
+    save: result
+  - tool: echo
+    args: { n: 1 }
+    if: { var: result, contains: ready }
+  - tool: echo
+    args: { n: 2 }
+    if: { var: mode, equals: slow }
+  - tool: echo
+    args: { n: 3 }
+    if: { var: missing, exists: false }
+  - tool: echo
+    args: { n: 4 }
+    if: { var: missing }
+"#,
+        );
+        let report = result.unwrap();
+        assert_eq!(report.skipped, 2);
+        let ns: Vec<_> = calls.iter().filter_map(|c| c.get("n").cloned()).collect();
+        assert_eq!(ns, vec![json!(1), json!(3)]);
+        assert!(events.contains(&"3 skipped".to_string()));
+    }
+
+    #[test]
+    fn failures_stop_unless_continued() {
+        let (result, calls, _) = run(
+            r#"
+steps:
+  - tool: echo
+    args: { fail: true }
+    continue_on_error: true
+  - tool: echo
+    args: { fail: true }
+  - tool: echo
+"#,
+        );
+        let err = result.unwrap_err();
+        assert!(err.to_string().starts_with("Step 2 (`echo`) failed: asked to fail"), "{}", err);
+        assert_eq!(calls.len(), 2);
+    }
+
+    #[test]
+    fn validation_runs_before_any_step() {
+        let (result, calls, _) = run(
+            r#"
+steps:
+  - tool: echo
+  - tool: teleport
+  - set: { a: 1 }
+    tool: echo
+  - foreach: [1, 2]
+"#,
+        );
+        let err = result.unwrap_err().to_string();
+        assert!(err.contains("step 2: unknown tool `teleport`"), "{}", err);
+        assert!(err.contains("step 3 has more than one"), "{}", err);
+        assert!(err.contains("step 4: `foreach` has no `steps`"), "{}", err);
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    fn unknown_variables_are_errors() {
+        let (result, _, _) = run("steps:\n  - tool: echo\n    args: { path: \"a/${nope}\" }\n");
+        assert!(format!("{:#}", result.unwrap_err()).contains("Unknown variable `nope`"));
+    }
+
+    #[test]
+    fn json_scripts_parse() {
+        let script = Script::from_json(r#"{ "vars": { "n": 2 }, "steps": [{ "set": { "m": "${n}" } }] }"#).unwrap();
+        let (tools, _) = tools();
+        let report = AutomationRunner::new(&tools).run(&script, Map::new(), |_| {}).unwrap();
+        assert_eq!(report.vars["m"], json!(2));
+    }
+}
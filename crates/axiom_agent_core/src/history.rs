@@ -0,0 +1,250 @@
+//! Keeps outgoing requests inside the model's context window.
+//!
+//! The agent loop accumulates tool results quickly (file reads, scene dumps),
+//! so long sessions used to fail with a provider-side overflow. Before each
+//! request the history is fitted to the budget in two passes:
+//!
+//! 1. Tool results older than the most recent turns are cut down to a stub.
+//! 2. If that is not enough, the oldest turns are dropped and replaced by a
+//!    short note listing what the user asked for in them.
+//!
+//! System messages and the most recent turns are never touched.
+
+use crate::llm::tokens;
+use crate::llm::{Message, MessageContent};
+
+/// Turns at the end of the conversation that are always sent verbatim.
+const KEEP_RECENT_TURNS: usize = 3;
+
+/// Tokens held back for the model's reply.
+const OUTPUT_RESERVE: usize = 8_192;
+
+/// Characters kept from an old tool result when it is compacted.
+const COMPACT_TOOL_RESULT_CHARS: usize = 300;
+
+/// Lines of the trimmed-turns note.
+const MAX_SUMMARY_LINES: usize = 20;
+
+pub struct HistoryManager {
+    budget: usize,
+}
+
+impl HistoryManager {
+    pub fn for_model(model: &str) -> Self {
+        let window = tokens::context_window(model);
+        Self {
+            budget: window.saturating_sub(OUTPUT_RESERVE.min(window / 4)),
+        }
+    }
+
+    /// Returns the messages to send. `fixed_tokens` covers anything sent
+    /// alongside them, i.e. the tool schemas.
+    pub fn fit(&self, messages: &[Message], fixed_tokens: usize) -> Vec<Message> {
+        let budget = self.budget.saturating_sub(fixed_tokens);
+        if tokens::count_messages(messages) <= budget {
+            return messages.to_vec();
+        }
+
+        let split = messages.iter().position(|m| m.role != "system").unwrap_or(messages.len());
+        let (system, rest) = messages.split_at(split);
+        let mut turns = group_turns(rest);
+        let protected = turns.len().saturating_sub(KEEP_RECENT_TURNS);
+
+        // Pass 1: compact old tool results
+        for turn in turns.iter_mut().take(protected) {
+            for message in turn.iter_mut().filter(|m| m.role == "tool") {
+                compact_tool_result(message);
+            }
+        }
+
+        let system_tokens = tokens::count_messages(system);
+        let mut total = system_tokens + turns.iter().map(|t| tokens::count_messages(t)).sum::<usize>();
+
+        // Pass 2: drop the oldest turns, always keeping the latest one
+        let mut dropped = Vec::new();
+        while total > budget && turns.len() > 1 {
+            let turn = turns.remove(0);
+            total -= tokens::count_messages(&turn);
+            dropped.extend(turn);
+        }
+
+        let mut out = system.to_vec();
+        if !dropped.is_empty() {
+            tracing::info!("Trimmed {} message(s) to fit {} tokens", dropped.len(), self.budget);
+            out.push(trimmed_note(&dropped));
+        }
+        out.extend(turns.into_iter().flatten());
+        out
+    }
+}
+
+/// A turn is a user or assistant message followed by the tool results that
+/// answer it; tool results must never be separated from their call.
+fn group_turns(messages: &[Message]) -> Vec<Vec<Message>> {
+    let mut turns: Vec<Vec<Message>> = Vec::new();
+    for message in messages {
+        match turns.last_mut() {
+            Some(turn) if message.role == "tool" => turn.push(message.clone()),
+            _ => turns.push(vec![message.clone()]),
+        }
+    }
+    turns
+}
+
+fn compact_tool_result(message: &mut Message) {
+    let text = message.text();
+    if text.len() <= COMPACT_TOOL_RESULT_CHARS {
+        return;
+    }
+
+    let mut end = COMPACT_TOOL_RESULT_CHARS;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    message.content = Some(MessageContent::Text(format!(
+        "{}\n... [older tool output truncated, {} chars omitted]",
+        &text[..end],
+        text.len() - end
+    )));
+}
+
+fn trimmed_note(dropped: &[Message]) -> Message {
+    let requests: Vec<String> = dropped
+        .iter()
+        .filter(|m| m.role == "user")
+        .filter_map(|m| m.text().lines().find(|l| !l.trim().is_empty()).map(|l| l.trim().to_string()))
+        .map(|line| {
+            if line.chars().count() > 120 {
+                format!("{}...", line.chars().take(120).collect::<String>())
+            } else {
+                line
+            }
+        })
+        .collect();
+
+    let mut note = format!(
+        "[Context note] {} earlier message(s) were removed to fit the context window.",
+        dropped.len()
+    );
+    if !requests.is_empty() {
+        note.push_str(" The user had asked:\n");
+        let skip = requests.len().saturating_sub(MAX_SUMMARY_LINES);
+        for request in &requests[skip..] {
+            note.push_str(&format!("- {}\n", request));
+        }
+    }
+
+    Message {
+        role: "user".to_string(),
+        content: Some(MessageContent::Text(note)),
+        tool_calls: None,
+        tool_call_id: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::{FunctionCall, ToolCall};
+
+    fn message(role: &str, text: &str) -> Message {
+        Message {
+            role: role.to_string(),
+            content: Some(MessageContent::Text(text.to_string())),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    fn call(id: &str) -> Message {
+        Message {
+            tool_calls: Some(vec![ToolCall {
+                id: id.to_string(),
+                r#type: "function".to_string(),
+                function: FunctionCall { name: "read_file".to_string(), arguments: "{}".to_string() },
+            }]),
+            ..message("assistant", "")
+        }
+    }
+
+    fn result(id: &str, text: &str) -> Message {
+        Message { tool_call_id: Some(id.to_string()), ..message("tool", text) }
+    }
+
+    #[test]
+    fn test_fit_drops_whole_turns_and_notes_requests() {
+        let big = "line of output\n".repeat(300);
+        let messages = vec![
+            message("system", "You are an editor agent."),
+            message("user", "first request\nwith details"),
+            call("c1"),
+            result("c1", &big),
+            message("user", "second request"),
+            call("c2"),
+            result("c2", "ok"),
+            message("user", "third request"),
+            message("assistant", "done"),
+        ];
+        // Room for the last turns and the result of `c2`, but not its call
+        let budget = tokens::count_messages(&messages[..1]) + tokens::count_messages(&messages[6..]);
+        let history = HistoryManager { budget };
+
+        let out = history.fit(&messages, 0);
+        let roles: Vec<&str> = out.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "user", "user", "assistant"]);
+        assert_eq!(out[0].text(), "You are an editor agent.");
+        let note = out[1].text();
+        assert!(note.starts_with("[Context note] 6 earlier message(s)"), "{}", note);
+        assert!(note.ends_with("- first request\n- second request\n"), "{}", note);
+        assert_eq!(out[2].text(), "third request");
+    }
+
+    #[test]
+    fn test_fit_compacts_only_older_tool_results() {
+        let big = "line of output\n".repeat(300);
+        let messages = vec![
+            message("system", "You are an editor agent."),
+            message("user", "read both"),
+            call("c1"),
+            result("c1", &big),
+            message("user", "again"),
+            call("c2"),
+            result("c2", &big),
+            message("user", "thanks"),
+        ];
+        let history = HistoryManager { budget: tokens::count_messages(&messages) - 1 };
+
+        let out = history.fit(&messages, 0);
+        assert_eq!(out.len(), messages.len());
+        assert!(out[3].text().starts_with(&big[..COMPACT_TOOL_RESULT_CHARS]));
+        assert!(out[3].text().ends_with("[older tool output truncated, 4200 chars omitted]"));
+        assert_eq!(out[6].text(), big);
+    }
+
+    #[test]
+    fn test_fit_leaves_history_within_budget() {
+        let messages = vec![message("system", "s"), message("user", "hi")];
+        let out = HistoryManager { budget: 1_000 }.fit(&messages, 0);
+        assert_eq!(out.len(), 2);
+    }
+
+    #[test]
+    fn test_group_turns_keeps_tool_results_with_their_call() {
+        let messages = vec![message("user", "go"), call("c1"), result("c1", "a"), result("c1", "b"), message("assistant", "done")];
+        let turns = group_turns(&messages);
+        let sizes: Vec<usize> = turns.iter().map(Vec::len).collect();
+        assert_eq!(sizes, [1, 3, 1]);
+    }
+
+    #[test]
+    fn test_compact_tool_result_cuts_on_char_boundary() {
+        // The cut at 300 bytes falls inside a three-byte euro sign
+        let text = format!("a{}", "€".repeat(200));
+        let mut message = result("c1", &text);
+        compact_tool_result(&mut message);
+        assert_eq!(
+            message.text(),
+            format!("a{}\n... [older tool output truncated, 303 chars omitted]", "€".repeat(99))
+        );
+    }
+}
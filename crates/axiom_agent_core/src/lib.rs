@@ -0,0 +1,18 @@
+//! Axiom Agent Core
+//!
+//! The provider-neutral LLM layer and the chat → tool call → tool result loop,
+//! without any UI. The editor drives it from a background task; a CLI or a
+//! test can drive it the same way by handing it a provider and a tool set and
+//! consuming the `AgentEvent`s it emits.
+
+pub mod automation;
+pub mod history;
+pub mod llm;
+pub mod metrics;
+pub mod plugin;
+pub mod runtime;
+pub mod tool;
+
+// Re-export commonly used types
+pub use runtime::{AgentEvent, AgentRuntime};
+pub use tool::{execute_checked, execute_reviewed, EditReviewer, FileEdit, InvalidArguments, Tool};
@@ -0,0 +1,410 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::provider::{function_declarations, split_system, EventStream, LlmProvider, ProviderKind};
+use super::openai::model_ids;
+use super::retry::{self, RetryPolicy};
+use super::sse::SseStream;
+use super::types::{
+    ChatResponse, FunctionCall, JsonSchemaFormat, Message, MessageContent, SamplingParams, StreamDeltaFunction,
+    StreamDeltaToolCall, StreamEvent, ToolCall,
+};
+use super::usage::Usage;
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const MAX_TOKENS: u32 = 8192;
+
+/// Client for the Anthropic Messages API.
+#[derive(Clone)]
+pub struct AnthropicClient {
+    api_key: String,
+    base_url: String,
+    model: String,
+    client: Client,
+    sampling: SamplingParams,
+    retry: RetryPolicy,
+}
+
+impl AnthropicClient {
+    pub fn new(api_key: String, base_url: String, model: String) -> Result<Self> {
+        let client = Client::builder()
+            .build()
+            .context("Failed to build reqwest client")?;
+
+        Ok(Self {
+            api_key,
+            base_url,
+            model,
+            client,
+            sampling: SamplingParams::default(),
+            retry: RetryPolicy::from_env(),
+        })
+    }
+
+    pub fn with_sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// `ANTHROPIC_BASE_URL` / `ANTHROPIC_API_KEY`.
+    pub fn from_env(model: &str) -> Result<Self> {
+        let base_url = std::env::var("ANTHROPIC_BASE_URL")
+            .unwrap_or_else(|_| "https://api.anthropic.com/v1".to_string());
+        let api_key = std::env::var("ANTHROPIC_API_KEY").unwrap_or_default();
+        Self::new(api_key, base_url, model.to_string())
+    }
+
+    fn build_request(&self, messages: Vec<Message>, tools: Option<Vec<Value>>, stream: bool) -> Value {
+        let (system, messages) = split_system(messages);
+
+        let mut body = json!({
+            "model": self.model,
+            "max_tokens": self.sampling.max_tokens.unwrap_or(MAX_TOKENS),
+            "messages": to_anthropic_messages(&messages),
+        });
+        // The system prompt and tool schemas are identical on every turn, so
+        // mark them as cache breakpoints. The cache prefix runs tools, then
+        // system, then messages.
+        if let Some(system) = system {
+            body["system"] = json!([{ "type": "text", "text": system, "cache_control": cache_control() }]);
+        }
+        if let Some(tools) = tools.filter(|t| !t.is_empty()) {
+            let mut tools = to_anthropic_tools(&tools);
+            if let Some(last) = tools.last_mut() {
+                last["cache_control"] = cache_control();
+            }
+            body["tools"] = json!(tools);
+        }
+        if let Some(temperature) = self.sampling.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = self.sampling.top_p {
+            body["top_p"] = json!(top_p);
+        }
+        if !self.sampling.stop.is_empty() {
+            body["stop_sequences"] = json!(self.sampling.stop);
+        }
+        // reasoning_effort is not mapped: extended thinking requires echoing
+        // thinking blocks back alongside tool results, which the shared
+        // OpenAI-shaped history doesn't carry.
+        if stream {
+            body["stream"] = json!(true);
+        }
+        body
+    }
+
+    async fn complete(&self, body: &Value) -> Result<ChatResponse> {
+        let response: Value = self.post(body).await?
+            .json()
+            .await
+            .context("Failed to parse Anthropic response")?;
+
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for block in response["content"].as_array().into_iter().flatten() {
+            match block["type"].as_str() {
+                Some("text") => text.push_str(block["text"].as_str().unwrap_or_default()),
+                Some("tool_use") => tool_calls.push(ToolCall {
+                    id: block["id"].as_str().unwrap_or_default().to_string(),
+                    r#type: "function".to_string(),
+                    function: FunctionCall {
+                        name: block["name"].as_str().unwrap_or_default().to_string(),
+                        arguments: block["input"].to_string(),
+                    },
+                }),
+                _ => {}
+            }
+        }
+
+        let usage = serde_json::from_value::<AnthropicUsage>(response["usage"].clone())
+            .ok()
+            .map(Usage::from);
+
+        Ok(ChatResponse {
+            message: Message {
+                role: "assistant".to_string(),
+                content: Some(MessageContent::Text(text)),
+                tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
+                tool_call_id: None,
+            },
+            usage,
+        })
+    }
+
+    async fn post(&self, body: &Value) -> Result<reqwest::Response> {
+        let url = format!("{}/messages", self.base_url.trim_end_matches('/'));
+        tracing::debug!("Sending Anthropic request to: {}", url);
+
+        let response = retry::send(&self.retry, &self.base_url, || {
+            self.client
+                .post(&url)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", ANTHROPIC_VERSION)
+                .json(body)
+        })
+        .await
+        .context("Failed to send request to Anthropic API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Anthropic API error: {}", error_text));
+        }
+        Ok(response)
+    }
+}
+
+/// Marks the end of a cacheable prompt prefix (5 minute TTL).
+fn cache_control() -> Value {
+    json!({ "type": "ephemeral" })
+}
+
+fn to_anthropic_content(content: &Option<MessageContent>) -> Vec<Value> {
+    match content {
+        Some(MessageContent::Text(text)) if !text.is_empty() => vec![json!({ "type": "text", "text": text })],
+        Some(MessageContent::Parts(parts)) => parts
+            .iter()
+            .filter_map(|part| {
+                if let Some(text) = &part.text {
+                    return Some(json!({ "type": "text", "text": text }));
+                }
+                let (mime, data) = part.image_url.as_ref()?.as_base64()?;
+                Some(json!({
+                    "type": "image",
+                    "source": { "type": "base64", "media_type": mime, "data": data }
+                }))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// OpenAI function schemas become `{name, description, input_schema}`.
+fn to_anthropic_tools(tools: &[Value]) -> Vec<Value> {
+    function_declarations(tools)
+        .into_iter()
+        .map(|f| {
+            json!({
+                "name": f["name"],
+                "description": f["description"],
+                "input_schema": f.get("parameters").cloned().unwrap_or(json!({ "type": "object" })),
+            })
+        })
+        .collect()
+}
+
+/// Assistant tool calls become `tool_use` blocks and tool messages become
+/// `tool_result` blocks in a user turn. Roles must alternate, so consecutive
+/// same-role messages (e.g. several tool results) are merged.
+fn to_anthropic_messages(messages: &[Message]) -> Vec<Value> {
+    let mut out: Vec<Value> = Vec::new();
+
+    for message in messages {
+        let (role, content) = match message.role.as_str() {
+            "assistant" => {
+                let mut content = to_anthropic_content(&message.content);
+                for call in message.tool_calls.iter().flatten() {
+                    let input: Value = serde_json::from_str(&call.function.arguments).unwrap_or(json!({}));
+                    content.push(json!({
+                        "type": "tool_use",
+                        "id": call.id,
+                        "name": call.function.name,
+                        "input": input,
+                    }));
+                }
+                ("assistant", content)
+            }
+            "tool" => (
+                "user",
+                vec![json!({
+                    "type": "tool_result",
+                    "tool_use_id": message.tool_call_id.clone().unwrap_or_default(),
+                    "content": message.text(),
+                })],
+            ),
+            _ => ("user", to_anthropic_content(&message.content)),
+        };
+
+        if content.is_empty() {
+            continue;
+        }
+
+        if let Some(last) = out.last_mut() {
+            if last["role"] == role {
+                if let Some(existing) = last["content"].as_array_mut() {
+                    existing.extend(content);
+                    continue;
+                }
+            }
+        }
+        out.push(json!({ "role": role, "content": content }));
+    }
+
+    out
+}
+
+/// Server-sent events of the Messages streaming API.
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AnthropicEvent {
+    MessageStart { message: StartMessage },
+    ContentBlockStart { index: i32, content_block: ContentBlock },
+    ContentBlockDelta { index: i32, delta: BlockDelta },
+    ContentBlockStop,
+    MessageDelta { usage: Option<AnthropicUsage> },
+    MessageStop,
+    Ping,
+    Error { error: Value },
+}
+
+#[derive(Deserialize, Debug)]
+struct StartMessage {
+    usage: Option<AnthropicUsage>,
+}
+
+/// `message_start` carries the input count, `message_delta` the output count.
+#[derive(Deserialize, Debug, Default)]
+struct AnthropicUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+impl From<AnthropicUsage> for Usage {
+    fn from(u: AnthropicUsage) -> Self {
+        Usage::new(
+            u.input_tokens + u.cache_creation_input_tokens + u.cache_read_input_tokens,
+            u.output_tokens,
+        )
+        .with_cached(u.cache_read_input_tokens)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    Text,
+    ToolUse { id: String, name: String },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta { text: String },
+    InputJsonDelta { partial_json: String },
+    #[serde(other)]
+    Other,
+}
+
+/// `tool_use` blocks stream their input as JSON fragments, keyed by block
+/// index, which lines up with how the agent loop accumulates OpenAI deltas.
+fn parse_anthropic_event(_event: &str, data: &str) -> Result<Vec<StreamEvent>> {
+    // Unknown event types are skipped, as the API docs ask clients to do
+    let Ok(event) = serde_json::from_str::<AnthropicEvent>(data) else {
+        return Ok(Vec::new());
+    };
+
+    let events = match event {
+        AnthropicEvent::ContentBlockStart { index, content_block: ContentBlock::ToolUse { id, name } } => {
+            vec![StreamEvent::ToolCallChunk(StreamDeltaToolCall::complete(index, id, name, String::new()))]
+        }
+        AnthropicEvent::ContentBlockDelta { delta: BlockDelta::TextDelta { text }, .. } => {
+            vec![StreamEvent::TextChunk(text)]
+        }
+        AnthropicEvent::ContentBlockDelta { index, delta: BlockDelta::InputJsonDelta { partial_json } } => {
+            vec![StreamEvent::ToolCallChunk(StreamDeltaToolCall {
+                index,
+                id: None,
+                r#type: None,
+                function: Some(StreamDeltaFunction {
+                    name: None,
+                    arguments: Some(partial_json),
+                }),
+            })]
+        }
+        AnthropicEvent::MessageStart { message: StartMessage { usage: Some(usage) } }
+        | AnthropicEvent::MessageDelta { usage: Some(usage) } => {
+            vec![StreamEvent::Usage(usage.into())]
+        }
+        AnthropicEvent::MessageStop => vec![StreamEvent::Done],
+        AnthropicEvent::Error { error } => {
+            return Err(anyhow::anyhow!("Anthropic stream error: {}", error));
+        }
+        _ => Vec::new(),
+    };
+    Ok(events)
+}
+
+#[async_trait]
+impl LlmProvider for AnthropicClient {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Anthropic
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/models?limit=100", self.base_url.trim_end_matches('/'));
+        let response = self.client
+            .get(&url)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION)
+            .send()
+            .await
+            .context("Failed to send request to Anthropic API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Anthropic API error: {}", error_text));
+        }
+
+        let body: Value = response.json().await.context("Failed to parse Anthropic models response")?;
+        Ok(model_ids(&body["data"]))
+    }
+
+    async fn chat(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+        self.complete(&self.build_request(messages, tools, false)).await
+    }
+
+    async fn chat_structured(&self, messages: Vec<Message>, format: &JsonSchemaFormat) -> Result<ChatResponse> {
+        // No JSON mode; force a single tool whose input schema is the format
+        let tool = json!({
+            "type": "function",
+            "function": {
+                "name": format.name,
+                "description": "Respond with a JSON object matching this schema.",
+                "parameters": format.schema,
+            }
+        });
+        let mut body = self.build_request(messages, Some(vec![tool]), false);
+        body["tool_choice"] = json!({ "type": "tool", "name": format.name });
+
+        let mut response = self.complete(&body).await?;
+        if let Some(call) = response.message.tool_calls.take().and_then(|calls| calls.into_iter().next()) {
+            response.message.content = Some(MessageContent::Text(call.function.arguments));
+        }
+        Ok(response)
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<Value>>,
+    ) -> Result<EventStream> {
+        let body = self.build_request(messages, tools, true);
+        let response = self.post(&body).await?;
+
+        Ok(Box::pin(SseStream::new(response.bytes_stream(), Box::new(parse_anthropic_event))))
+    }
+}
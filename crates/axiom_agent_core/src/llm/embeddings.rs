@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use std::future::Future;
+use std::sync::Arc;
+
+use super::provider::{create_provider, LlmProvider, ProviderKind};
+use super::types::SamplingParams;
+
+/// Inputs sent per embeddings request.
+const EMBED_BATCH_SIZE: usize = 64;
+
+/// Synchronous front for `LlmProvider::embed`.
+///
+/// Tools run synchronously inside the agent's tokio task, so requests are
+/// driven with `block_in_place` on the current runtime rather than a nested one.
+#[derive(Clone)]
+pub struct EmbeddingClient {
+    provider: Arc<dyn LlmProvider>,
+    pub model: String,
+}
+
+impl EmbeddingClient {
+    /// `AXIOM_EMBEDDING_PROVIDER` (`openai`, `gemini` or `ollama`; defaults to
+    /// the OpenAI-compatible endpoint) and `AXIOM_EMBEDDING_MODEL`.
+    pub fn from_env() -> Result<Self> {
+        let kind = std::env::var("AXIOM_EMBEDDING_PROVIDER")
+            .ok()
+            .and_then(|name| ProviderKind::from_name(&name))
+            .unwrap_or_default();
+        let model = std::env::var("AXIOM_EMBEDDING_MODEL").unwrap_or_else(|_| {
+            match kind {
+                ProviderKind::Ollama => "nomic-embed-text",
+                _ => "text-embedding-004",
+            }
+            .to_string()
+        });
+
+        let provider = create_provider(kind, &model, &SamplingParams::default())?;
+        Ok(Self { provider, model })
+    }
+
+    pub fn embed(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(inputs.len());
+
+        for batch in inputs.chunks(EMBED_BATCH_SIZE) {
+            let batch_vectors = block_on(self.provider.embed(&self.model, batch))??;
+            if batch_vectors.len() != batch.len() {
+                return Err(anyhow!(
+                    "Embedding count mismatch: sent {}, got {}",
+                    batch.len(),
+                    batch_vectors.len()
+                ));
+            }
+            vectors.extend(batch_vectors);
+        }
+
+        Ok(vectors)
+    }
+}
+
+fn block_on<F: Future>(future: F) -> Result<F::Output> {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) => Ok(tokio::task::block_in_place(|| handle.block_on(future))),
+        Err(_) => {
+            let rt = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+            Ok(rt.block_on(future))
+        }
+    }
+}
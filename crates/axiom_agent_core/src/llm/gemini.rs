@@ -0,0 +1,380 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+
+use super::provider::{function_declarations, split_system, EventStream, LlmProvider, ProviderKind};
+use super::retry::{self, RetryPolicy};
+use super::sse::SseStream;
+use super::types::{
+    ChatResponse, FunctionCall, JsonSchemaFormat, Message, MessageContent, ReasoningEffort, SamplingParams,
+    StreamDeltaToolCall, StreamEvent, ToolCall,
+};
+use super::usage::Usage;
+
+/// Client for the native Google Generative Language API (`generateContent`).
+#[derive(Clone)]
+pub struct GeminiClient {
+    api_key: String,
+    base_url: String,
+    model: String,
+    client: Client,
+    sampling: SamplingParams,
+    retry: RetryPolicy,
+}
+
+impl GeminiClient {
+    pub fn new(api_key: String, base_url: String, model: String) -> Result<Self> {
+        let client = Client::builder()
+            .build()
+            .context("Failed to build reqwest client")?;
+
+        Ok(Self {
+            api_key,
+            base_url,
+            model,
+            client,
+            sampling: SamplingParams::default(),
+            retry: RetryPolicy::from_env(),
+        })
+    }
+
+    pub fn with_sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// `GEMINI_NATIVE_BASE_URL` / `GOOGLE_API_KEY` (falls back to `GEMINI_API_KEY`).
+    pub fn from_env(model: &str) -> Result<Self> {
+        let base_url = std::env::var("GEMINI_NATIVE_BASE_URL")
+            .unwrap_or_else(|_| "https://generativelanguage.googleapis.com/v1beta".to_string());
+        let api_key = std::env::var("GOOGLE_API_KEY")
+            .or_else(|_| std::env::var("GEMINI_API_KEY"))
+            .unwrap_or_default();
+        Self::new(api_key, base_url, model.to_string())
+    }
+
+    fn url(&self, method: &str) -> String {
+        format!("{}/models/{}:{}", self.base_url.trim_end_matches('/'), self.model, method)
+    }
+
+    fn build_request(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Value {
+        let (system, messages) = split_system(messages);
+
+        let mut body = json!({ "contents": to_gemini_contents(&messages) });
+        if let Some(system) = system {
+            body["systemInstruction"] = json!({ "parts": [{ "text": system }] });
+        }
+        if let Some(tools) = tools.filter(|t| !t.is_empty()) {
+            body["tools"] = json!([{ "functionDeclarations": function_declarations(&tools) }]);
+        }
+
+        let config = generation_config(&self.sampling);
+        if config.as_object().is_some_and(|c| !c.is_empty()) {
+            body["generationConfig"] = config;
+        }
+        body
+    }
+
+    async fn complete(&self, body: Value) -> Result<ChatResponse> {
+        let url = self.url("generateContent");
+        tracing::debug!("Sending Gemini request to: {}", url);
+
+        let response: Value = self.post(&url, &body).await?
+            .json()
+            .await
+            .context("Failed to parse Gemini response")?;
+
+        let (text, calls) = parse_candidate(&response);
+        let tool_calls: Vec<ToolCall> = calls
+            .into_iter()
+            .map(|(name, arguments)| ToolCall {
+                id: new_call_id(),
+                r#type: "function".to_string(),
+                function: FunctionCall { name, arguments },
+            })
+            .collect();
+
+        Ok(ChatResponse {
+            message: Message {
+                role: "assistant".to_string(),
+                content: Some(MessageContent::Text(text)),
+                tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
+                tool_call_id: None,
+            },
+            usage: parse_usage(&response),
+        })
+    }
+
+    async fn post(&self, url: &str, body: &Value) -> Result<reqwest::Response> {
+        let response = retry::send(&self.retry, &self.base_url, || {
+            self.client
+                .post(url)
+                .header("x-goog-api-key", &self.api_key)
+                .json(body)
+        })
+        .await
+        .context("Failed to send request to Gemini API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Gemini API error: {}", error_text));
+        }
+        Ok(response)
+    }
+}
+
+fn generation_config(sampling: &SamplingParams) -> Value {
+    let mut config = json!({});
+    if let Some(temperature) = sampling.temperature {
+        config["temperature"] = json!(temperature);
+    }
+    if let Some(top_p) = sampling.top_p {
+        config["topP"] = json!(top_p);
+    }
+    if let Some(max_tokens) = sampling.max_tokens {
+        config["maxOutputTokens"] = json!(max_tokens);
+    }
+    if !sampling.stop.is_empty() {
+        config["stopSequences"] = json!(sampling.stop);
+    }
+    if let Some(effort) = sampling.reasoning_effort {
+        let budget = match effort {
+            ReasoningEffort::Low => 1024,
+            ReasoningEffort::Medium => 8192,
+            ReasoningEffort::High => 24576,
+        };
+        config["thinkingConfig"] = json!({ "thinkingBudget": budget });
+    }
+    config
+}
+
+/// Gemini has no tool call ids; recover the function name for a tool result
+/// from the assistant message that issued the call.
+fn tool_name_for(messages: &[Message], tool_call_id: &str) -> String {
+    messages
+        .iter()
+        .filter_map(|m| m.tool_calls.as_ref())
+        .flatten()
+        .find(|c| c.id == tool_call_id)
+        .map(|c| c.function.name.clone())
+        .unwrap_or_else(|| tool_call_id.to_string())
+}
+
+fn to_gemini_parts(content: &Option<MessageContent>) -> Vec<Value> {
+    match content {
+        Some(MessageContent::Text(text)) if !text.is_empty() => vec![json!({ "text": text })],
+        Some(MessageContent::Parts(parts)) => parts
+            .iter()
+            .filter_map(|part| {
+                if let Some(text) = &part.text {
+                    return Some(json!({ "text": text }));
+                }
+                let (mime, data) = part.image_url.as_ref()?.as_base64()?;
+                Some(json!({ "inlineData": { "mimeType": mime, "data": data } }))
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn to_gemini_contents(messages: &[Message]) -> Vec<Value> {
+    let mut contents: Vec<Value> = Vec::new();
+
+    for message in messages {
+        let (role, parts) = match message.role.as_str() {
+            "assistant" => {
+                let mut parts = to_gemini_parts(&message.content);
+                for call in message.tool_calls.iter().flatten() {
+                    let args: Value = serde_json::from_str(&call.function.arguments).unwrap_or(json!({}));
+                    parts.push(json!({ "functionCall": { "name": call.function.name, "args": args } }));
+                }
+                ("model", parts)
+            }
+            "tool" => {
+                let id = message.tool_call_id.clone().unwrap_or_default();
+                let part = json!({
+                    "functionResponse": {
+                        "name": tool_name_for(messages, &id),
+                        "response": { "content": message.text() }
+                    }
+                });
+                ("user", vec![part])
+            }
+            _ => ("user", to_gemini_parts(&message.content)),
+        };
+
+        if parts.is_empty() {
+            continue;
+        }
+
+        // Gemini expects alternating turns; merge consecutive same-role entries.
+        if let Some(last) = contents.last_mut() {
+            if last["role"] == role {
+                if let Some(existing) = last["parts"].as_array_mut() {
+                    existing.extend(parts);
+                    continue;
+                }
+            }
+        }
+        contents.push(json!({ "role": role, "parts": parts }));
+    }
+
+    contents
+}
+
+/// Pull text and function calls out of one `GenerateContentResponse`.
+fn parse_candidate(response: &Value) -> (String, Vec<(String, String)>) {
+    let mut text = String::new();
+    let mut calls = Vec::new();
+
+    let parts = response["candidates"][0]["content"]["parts"].as_array();
+    for part in parts.into_iter().flatten() {
+        if let Some(t) = part["text"].as_str() {
+            text.push_str(t);
+        }
+        if let Some(call) = part.get("functionCall") {
+            let name = call["name"].as_str().unwrap_or_default().to_string();
+            let args = call.get("args").cloned().unwrap_or(json!({})).to_string();
+            calls.push((name, args));
+        }
+    }
+    (text, calls)
+}
+
+/// `usageMetadata` is cumulative over a stream, so only the last chunk's counts matter.
+fn parse_usage(response: &Value) -> Option<Usage> {
+    let meta = response.get("usageMetadata")?;
+    let usage = Usage::new(
+        meta["promptTokenCount"].as_u64().unwrap_or(0),
+        meta["candidatesTokenCount"].as_u64().unwrap_or(0) + meta["thoughtsTokenCount"].as_u64().unwrap_or(0),
+    )
+    // Implicit caching of repeated prefixes is automatic on Gemini 2.5
+    .with_cached(meta["cachedContentTokenCount"].as_u64().unwrap_or(0));
+    (!usage.is_empty()).then_some(usage)
+}
+
+fn new_call_id() -> String {
+    format!("call_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[async_trait]
+impl LlmProvider for GeminiClient {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Gemini
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Only models that support `generateContent`; embedding models are left out.
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/models?pageSize=1000", self.base_url.trim_end_matches('/'));
+        let response = self.client
+            .get(&url)
+            .header("x-goog-api-key", &self.api_key)
+            .send()
+            .await
+            .context("Failed to send request to Gemini API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Gemini API error: {}", error_text));
+        }
+
+        let body: Value = response.json().await.context("Failed to parse Gemini models response")?;
+        let mut models: Vec<String> = body["models"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|m| {
+                m["supportedGenerationMethods"]
+                    .as_array()
+                    .is_some_and(|methods| methods.iter().any(|x| x == "generateContent"))
+            })
+            .filter_map(|m| m["name"].as_str())
+            .map(|name| name.trim_start_matches("models/").to_string())
+            .collect();
+        models.sort();
+        Ok(models)
+    }
+
+    async fn chat(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+        self.complete(self.build_request(messages, tools)).await
+    }
+
+    async fn chat_structured(&self, messages: Vec<Message>, format: &JsonSchemaFormat) -> Result<ChatResponse> {
+        let mut body = self.build_request(messages, None);
+        body["generationConfig"]["responseMimeType"] = json!("application/json");
+        body["generationConfig"]["responseJsonSchema"] = format.schema.clone();
+        self.complete(body).await
+    }
+
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/models/{}:batchEmbedContents", self.base_url.trim_end_matches('/'), model);
+        let requests: Vec<Value> = inputs
+            .iter()
+            .map(|text| json!({ "model": format!("models/{}", model), "content": { "parts": [{ "text": text }] } }))
+            .collect();
+
+        let response: Value = self.post(&url, &json!({ "requests": requests })).await?
+            .json()
+            .await
+            .context("Failed to parse Gemini embeddings response")?;
+
+        response["embeddings"]
+            .as_array()
+            .ok_or_else(|| anyhow::anyhow!("Gemini embeddings response has no `embeddings`"))?
+            .iter()
+            .map(|e| {
+                serde_json::from_value(e["values"].clone())
+                    .context("Malformed Gemini embedding values")
+            })
+            .collect()
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<Value>>,
+    ) -> Result<EventStream> {
+        let url = format!("{}?alt=sse", self.url("streamGenerateContent"));
+        tracing::debug!("Sending Gemini STREAM request to: {}", url);
+
+        let body = self.build_request(messages, tools);
+        let response = self.post(&url, &body).await?;
+
+        // Function calls arrive whole; number them across chunks.
+        let mut next_index = 0;
+        let parser = move |_event: &str, data: &str| -> Result<Vec<StreamEvent>> {
+            let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+                return Ok(Vec::new());
+            };
+
+            let (text, calls) = parse_candidate(&chunk);
+            let mut events = Vec::new();
+            if !text.is_empty() {
+                events.push(StreamEvent::TextChunk(text));
+            }
+            for (name, arguments) in calls {
+                events.push(StreamEvent::ToolCallChunk(StreamDeltaToolCall::complete(
+                    next_index,
+                    new_call_id(),
+                    name,
+                    arguments,
+                )));
+                next_index += 1;
+            }
+            if chunk["candidates"][0]["finishReason"].is_string() {
+                if let Some(usage) = parse_usage(&chunk) {
+                    events.push(StreamEvent::Usage(usage));
+                }
+                events.push(StreamEvent::Done);
+            }
+            Ok(events)
+        };
+
+        Ok(Box::pin(SseStream::new(response.bytes_stream(), Box::new(parser))))
+    }
+}
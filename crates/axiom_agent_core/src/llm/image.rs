@@ -0,0 +1,103 @@
+//! Turning local images into vision message parts.
+//!
+//! Images are downscaled so the long edge fits `MAX_DIMENSION` (larger inputs
+//! are resized by the providers anyway and only cost upload time and tokens),
+//! then sent as PNG when they have transparency and JPEG otherwise. Small PNG
+//! and JPEG files are passed through untouched.
+
+use anyhow::{anyhow, Context, Result};
+use base64::prelude::*;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use std::io::Cursor;
+use std::path::Path;
+
+use super::types::{ContentPart, ImageUrl};
+
+/// Long-edge limit; matches what Claude and GPT-4o process without resizing.
+pub const MAX_DIMENSION: u32 = 1568;
+/// Files at most this big (and within `MAX_DIMENSION`) are sent as-is.
+const PASSTHROUGH_BYTES: usize = 1024 * 1024;
+const JPEG_QUALITY: u8 = 85;
+
+/// File extensions accepted for attachment (drag-and-drop filter).
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp", "bmp"];
+
+/// An image ready to send, plus the pixels for an in-app preview.
+pub struct PreparedImage {
+    pub part: ContentPart,
+    pub preview: RgbaImage,
+}
+
+pub fn is_image_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| IMAGE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+}
+
+/// Load, downscale and encode the image at `path`.
+pub fn from_path(path: &Path) -> Result<PreparedImage> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    from_bytes(&bytes).with_context(|| format!("Failed to load image {}", path.display()))
+}
+
+/// Encoded image bytes of any format the `image` crate can decode; the
+/// format is sniffed from the content, not trusted from a file name.
+pub fn from_bytes(bytes: &[u8]) -> Result<PreparedImage> {
+    let format = image::guess_format(bytes).context("Unrecognized image format")?;
+    let img = image::load_from_memory_with_format(bytes, format)?;
+
+    let passthrough = matches!(format, ImageFormat::Png | ImageFormat::Jpeg)
+        && bytes.len() <= PASSTHROUGH_BYTES
+        && img.width().max(img.height()) <= MAX_DIMENSION;
+    if passthrough {
+        let part = data_url_part(format.to_mime_type(), bytes);
+        return Ok(PreparedImage { part, preview: img.to_rgba8() });
+    }
+
+    encode(img)
+}
+
+/// Raw RGBA pixels, e.g. from the clipboard.
+pub fn from_rgba(width: u32, height: u32, pixels: Vec<u8>) -> Result<PreparedImage> {
+    let buffer = RgbaImage::from_raw(width, height, pixels)
+        .ok_or_else(|| anyhow!("Pixel buffer does not match {}x{}", width, height))?;
+    encode(DynamicImage::ImageRgba8(buffer))
+}
+
+fn encode(img: DynamicImage) -> Result<PreparedImage> {
+    let img = if img.width().max(img.height()) > MAX_DIMENSION {
+        img.resize(MAX_DIMENSION, MAX_DIMENSION, image::imageops::FilterType::Triangle)
+    } else {
+        img
+    };
+
+    let mut bytes = Vec::new();
+    let mime = if has_transparency(&img) {
+        img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+        ImageFormat::Png.to_mime_type()
+    } else {
+        let rgb = img.to_rgb8();
+        let encoder = image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, JPEG_QUALITY);
+        rgb.write_with_encoder(encoder)?;
+        ImageFormat::Jpeg.to_mime_type()
+    };
+
+    Ok(PreparedImage {
+        part: data_url_part(mime, &bytes),
+        preview: img.to_rgba8(),
+    })
+}
+
+fn has_transparency(img: &DynamicImage) -> bool {
+    img.color().has_alpha() && img.to_rgba8().pixels().any(|p| p.0[3] < 255)
+}
+
+fn data_url_part(mime: &str, bytes: &[u8]) -> ContentPart {
+    ContentPart {
+        r#type: "image_url".to_string(),
+        text: None,
+        image_url: Some(ImageUrl {
+            url: format!("data:{};base64,{}", mime, BASE64_STANDARD.encode(bytes)),
+        }),
+    }
+}
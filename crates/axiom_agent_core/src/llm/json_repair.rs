@@ -0,0 +1,314 @@
+//! Lenient JSON parsing and schema checking for model output.
+//!
+//! Even with a schema-constrained request, replies can come back wrapped in a
+//! Markdown fence, with comments or trailing commas, or cut off by the token
+//! limit. The helpers here recover what they can and then check the result
+//! against the requested schema so callers get a clear error instead of a
+//! half-valid plan.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Parse `text` as JSON, repairing common model mistakes, and validate it
+/// against `schema`.
+pub fn parse_validated(text: &str, schema: &Value) -> Result<Value> {
+    let value = parse_lenient(text)?;
+    let errors = validate(&value, schema);
+    if !errors.is_empty() {
+        bail!("JSON does not match schema:\n- {}", errors.join("\n- "));
+    }
+    Ok(value)
+}
+
+/// Parse as-is, then from the first fenced/braced block, then after repair.
+pub fn parse_lenient(text: &str) -> Result<Value> {
+    if let Ok(value) = serde_json::from_str(text.trim()) {
+        return Ok(value);
+    }
+
+    let Some(candidate) = extract_json(text) else {
+        bail!("No JSON object found in response");
+    };
+    if let Ok(value) = serde_json::from_str(candidate) {
+        return Ok(value);
+    }
+
+    let repaired = repair(candidate);
+    serde_json::from_str(&repaired).map_err(|e| anyhow::anyhow!("Invalid JSON after repair: {}", e))
+}
+
+/// The contents of the first ```json fence, or the text from the first `{`
+/// or `[` onwards.
+fn extract_json(text: &str) -> Option<&str> {
+    if let Some(start) = text.find("```") {
+        let after = &text[start + 3..];
+        let body_start = after.find('\n').map(|i| i + 1).unwrap_or(0);
+        let body = &after[body_start..];
+        let body = body.find("```").map(|end| &body[..end]).unwrap_or(body);
+        if body.trim_start().starts_with(['{', '[']) {
+            return Some(body.trim());
+        }
+    }
+
+    let start = text.find(['{', '['])?;
+    Some(text[start..].trim())
+}
+
+/// Drop comments and trailing commas, terminate an unfinished string, drop
+/// a key left without a value and close any brackets left open by a
+/// truncated reply.
+fn repair(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    // Where the object member being read began, until its value starts; a
+    // reply cut off before then loses the member from here
+    let mut member_start: Option<usize> = None;
+    let mut awaiting_value = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+
+        if c == '/' && chars.peek() == Some(&'/') {
+            // Line comment
+            for next in chars.by_ref() {
+                if next == '\n' {
+                    out.push('\n');
+                    break;
+                }
+            }
+            continue;
+        }
+        if c == '/' && chars.peek() == Some(&'*') {
+            // Block comment
+            chars.next();
+            let mut previous = ' ';
+            for next in chars.by_ref() {
+                if previous == '*' && next == '/' {
+                    break;
+                }
+                previous = next;
+            }
+            continue;
+        }
+        if awaiting_value && !c.is_whitespace() {
+            awaiting_value = false;
+            member_start = None;
+        }
+
+        match c {
+            '"' => {
+                in_string = true;
+                out.push(c);
+            }
+            ':' => {
+                awaiting_value = true;
+                out.push(c);
+            }
+            ',' => {
+                if stack.last() == Some(&'}') {
+                    member_start = Some(out.len());
+                }
+                out.push(c);
+            }
+            '{' => {
+                stack.push('}');
+                out.push(c);
+                member_start = Some(out.len());
+            }
+            '[' => {
+                stack.push(']');
+                out.push(c);
+            }
+            '}' | ']' => {
+                member_start = None;
+                strip_trailing_comma(&mut out);
+                if stack.last() == Some(&c) {
+                    stack.pop();
+                }
+                out.push(c);
+                if stack.is_empty() {
+                    // Ignore anything after the top-level value
+                    break;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+
+    if let Some(start) = member_start {
+        // A truncated key, or a key with no value yet
+        out.truncate(start);
+    } else if in_string {
+        if escaped {
+            out.pop();
+        }
+        out.push('"');
+    }
+    strip_trailing_comma(&mut out);
+    while let Some(close) = stack.pop() {
+        out.push(close);
+    }
+    out
+}
+
+fn strip_trailing_comma(out: &mut String) {
+    let trimmed = out.trim_end().len();
+    if out[..trimmed].ends_with(',') {
+        out.truncate(trimmed - 1);
+    }
+}
+
+/// Check `value` against the subset of JSON Schema used for structured
+/// outputs: `type`, `properties`, `required`, `items` and `enum`.
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    validate_at(value, schema, "$", &mut errors);
+    errors
+}
+
+fn validate_at(value: &Value, schema: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type").and_then(Value::as_str) {
+        let ok = match expected {
+            "object" => value.is_object(),
+            "array" => value.is_array(),
+            "string" => value.is_string(),
+            "number" => value.is_number(),
+            "integer" => value.is_i64() || value.is_u64(),
+            "boolean" => value.is_boolean(),
+            "null" => value.is_null(),
+            _ => true,
+        };
+        if !ok {
+            errors.push(format!("{}: expected {}, got {}", path, expected, type_name(value)));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!("{}: {} is not one of {}", path, value, Value::Array(allowed.clone())));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        for key in schema.get("required").and_then(Value::as_array).into_iter().flatten() {
+            if let Some(key) = key.as_str() {
+                if !object.contains_key(key) {
+                    errors.push(format!("{}: missing required field `{}`", path, key));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, sub_schema) in properties {
+                if let Some(sub_value) = object.get(key) {
+                    validate_at(sub_value, sub_schema, &format!("{}.{}", path, key), errors);
+                }
+            }
+        }
+    }
+
+    if let (Some(items), Some(item_schema)) = (value.as_array(), schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            validate_at(item, item_schema, &format!("{}[{}]", path, i), errors);
+        }
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parses_fenced_json() {
+        let text = "Here's the plan:\n```json\n{\"steps\": [1, 2]}\n```\nLet me know.";
+        assert_eq!(parse_lenient(text).unwrap(), json!({ "steps": [1, 2] }));
+        assert_eq!(extract_json("Sure: [1, 2]"), Some("[1, 2]"));
+        assert!(parse_lenient("No JSON here").is_err());
+    }
+
+    #[test]
+    fn test_drops_trailing_commas_and_comments() {
+        let text = "{\n  \"a\": 1, // the first\n  \"b\": [1, 2,], /* spare */\n}";
+        assert_eq!(parse_lenient(text).unwrap(), json!({ "a": 1, "b": [1, 2] }));
+        // Comment markers inside strings are kept
+        assert_eq!(repair(r#"{"url": "http://x/*y*/"}"#), r#"{"url": "http://x/*y*/"}"#);
+    }
+
+    #[test]
+    fn test_repairs_truncation_in_a_string() {
+        assert_eq!(parse_lenient(r#"{"name": "Cra"#).unwrap(), json!({ "name": "Cra" }));
+        assert_eq!(parse_lenient(r#"{"steps": ["spawn", "pai"#).unwrap(), json!({ "steps": ["spawn", "pai"] }));
+        assert_eq!(parse_lenient(r#"{"path": "C:\"#).unwrap(), json!({ "path": "C:" }));
+    }
+
+    #[test]
+    fn test_repairs_truncation_in_a_key() {
+        assert_eq!(parse_lenient(r#"{"a": 1, "na"#).unwrap(), json!({ "a": 1 }));
+        assert_eq!(parse_lenient(r#"{"a": {"b": 1, "c"#).unwrap(), json!({ "a": { "b": 1 } }));
+        assert_eq!(parse_lenient(r#"{"na"#).unwrap(), json!({}));
+    }
+
+    #[test]
+    fn test_repairs_truncation_after_a_key() {
+        assert_eq!(parse_lenient(r#"{"a": 1, "x,y":"#).unwrap(), json!({ "a": 1 }));
+        assert_eq!(parse_lenient(r#"{"a": 1, "{b}": "#).unwrap(), json!({ "a": 1 }));
+        assert_eq!(parse_lenient(r#"{"a": [{"b": 1}, {"c":"#).unwrap(), json!({ "a": [{ "b": 1 }, {}] }));
+        assert_eq!(parse_lenient(r#"{"a": 1,"#).unwrap(), json!({ "a": 1 }));
+    }
+
+    #[test]
+    fn test_validates_against_the_schema() {
+        let schema = json!({
+            "type": "object",
+            "required": ["action", "count"],
+            "properties": {
+                "action": { "type": "string", "enum": ["spawn", "clear"] },
+                "count": { "type": "integer" },
+                "tags": { "type": "array", "items": { "type": "string" } }
+            }
+        });
+        let valid = parse_validated(r#"{"action": "spawn", "count": 2, "tags": ["a"]}"#, &schema).unwrap();
+        assert_eq!(valid["count"], 2);
+
+        let errors = validate(&json!({ "action": "paint", "count": 1.5, "tags": ["a", 3] }), &schema);
+        assert_eq!(
+            errors,
+            vec![
+                r#"$.action: "paint" is not one of ["spawn","clear"]"#.to_string(),
+                "$.count: expected integer, got number".to_string(),
+                "$.tags[1]: expected string, got integer".to_string(),
+            ]
+        );
+        assert_eq!(validate(&json!({}), &schema).len(), 2);
+        assert_eq!(validate(&json!([]), &schema), vec!["$: expected object, got array".to_string()]);
+
+        let error = parse_validated(r#"{"action": "spawn"}"#, &schema).unwrap_err().to_string();
+        assert!(error.contains("missing required field `count`"), "{}", error);
+    }
+}
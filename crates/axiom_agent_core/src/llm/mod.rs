@@ -0,0 +1,21 @@
+pub mod anthropic;
+pub mod embeddings;
+pub mod gemini;
+pub mod image;
+pub mod json_repair;
+pub mod ollama;
+pub mod openai;
+pub mod provider;
+pub mod replay;
+pub mod retry;
+pub mod sse;
+pub mod tokens;
+pub mod types;
+pub mod usage;
+
+pub use provider::{create_provider, LlmProvider, ProviderKind};
+pub use types::{
+    Message, MessageContent, ContentPart, ImageUrl, SamplingParams, ReasoningEffort, JsonSchemaFormat,
+    StreamEvent,
+    ToolCall, FunctionCall
+};
@@ -0,0 +1,336 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::provider::{EventStream, LlmProvider, ProviderKind};
+use super::retry::{self, RetryPolicy};
+use super::sse::SseStream;
+use super::types::{
+    ChatResponse, FunctionCall, JsonSchemaFormat, Message, MessageContent, SamplingParams, StreamDeltaToolCall,
+    StreamEvent, ToolCall,
+};
+use super::usage::Usage;
+
+const DEFAULT_BASE_URL: &str = "http://127.0.0.1:11434";
+
+/// Client for a local Ollama server (`/api/chat`).
+#[derive(Clone)]
+pub struct OllamaClient {
+    base_url: String,
+    model: String,
+    client: Client,
+    sampling: SamplingParams,
+    retry: RetryPolicy,
+}
+
+fn base_url_from_env() -> String {
+    std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string())
+}
+
+/// Names of the models pulled into the local Ollama server (`/api/tags`).
+/// Returns an empty list when the server isn't running.
+pub fn discover_models() -> Vec<String> {
+    let url = format!("{}/api/tags", base_url_from_env().trim_end_matches('/'));
+    let response = ureq::get(&url)
+        .timeout(Duration::from_millis(800))
+        .call();
+
+    match response.map(|r| r.into_json()) {
+        Ok(Ok(body)) => tag_names(&body),
+        _ => Vec::new(),
+    }
+}
+
+fn tag_names(body: &Value) -> Vec<String> {
+    body["models"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m["name"].as_str().map(str::to_string))
+        .collect()
+}
+
+impl OllamaClient {
+    pub fn new(base_url: String, model: String) -> Result<Self> {
+        let client = Client::builder()
+            .build()
+            .context("Failed to build reqwest client")?;
+
+        Ok(Self {
+            base_url,
+            model,
+            client,
+            sampling: SamplingParams::default(),
+            retry: RetryPolicy::from_env(),
+        })
+    }
+
+    pub fn with_sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// `OLLAMA_BASE_URL`, defaulting to the standard local port. No key needed.
+    pub fn from_env(model: &str) -> Result<Self> {
+        Self::new(base_url_from_env(), model.to_string())
+    }
+
+    fn build_request(&self, messages: &[Message], tools: Option<&Vec<Value>>, stream: bool) -> Value {
+        let mut body = json!({
+            "model": self.model,
+            "messages": messages.iter().map(to_ollama_message).collect::<Vec<_>>(),
+            "stream": stream,
+        });
+        if let Some(tools) = tools.filter(|t| !t.is_empty()) {
+            body["tools"] = json!(tools);
+        }
+
+        let mut options = json!({});
+        if let Some(temperature) = self.sampling.temperature {
+            options["temperature"] = json!(temperature);
+        }
+        if let Some(top_p) = self.sampling.top_p {
+            options["top_p"] = json!(top_p);
+        }
+        if let Some(max_tokens) = self.sampling.max_tokens {
+            options["num_predict"] = json!(max_tokens);
+        }
+        if !self.sampling.stop.is_empty() {
+            options["stop"] = json!(self.sampling.stop);
+        }
+        if options.as_object().is_some_and(|o| !o.is_empty()) {
+            body["options"] = options;
+        }
+        // Ollama only has an on/off switch for thinking models
+        if self.sampling.reasoning_effort.is_some() {
+            body["think"] = json!(true);
+        }
+        body
+    }
+
+    /// POST `/api/chat`. Ollama rejects `tools` outright for models without
+    /// tool support, so retry once without them rather than failing the turn.
+    /// `format` is a JSON schema the reply must follow (structured outputs).
+    async fn post(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<Value>>,
+        format: Option<&Value>,
+        stream: bool,
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}/api/chat", self.base_url.trim_end_matches('/'));
+        tracing::debug!("Sending Ollama request to: {}", url);
+
+        let mut tools = tools;
+        loop {
+            let mut body = self.build_request(&messages, tools.as_ref(), stream);
+            if let Some(format) = format {
+                body["format"] = format.clone();
+            }
+            let response = retry::send(&self.retry, &self.base_url, || self.client.post(&url).json(&body))
+                .await
+                .context("Failed to reach Ollama (is `ollama serve` running?)")?;
+
+            if response.status().is_success() {
+                return Ok(response);
+            }
+
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            if tools.is_some() && error_text.contains("does not support tools") {
+                tracing::warn!("{} does not support tools, retrying without them", self.model);
+                tools = None;
+                continue;
+            }
+            return Err(anyhow::anyhow!("Ollama API error: {}", error_text));
+        }
+    }
+}
+
+/// Ollama takes images as a bare base64 list next to the text, and tool call
+/// arguments as JSON objects rather than strings.
+fn to_ollama_message(message: &Message) -> Value {
+    let (text, images) = match &message.content {
+        Some(MessageContent::Parts(parts)) => {
+            let text = parts.iter().filter_map(|p| p.text.clone()).collect::<Vec<_>>().join("\n");
+            let images: Vec<&str> = parts
+                .iter()
+                .filter_map(|p| p.image_url.as_ref()?.as_base64().map(|(_, data)| data))
+                .collect();
+            (text, images)
+        }
+        _ => (message.text(), Vec::new()),
+    };
+
+    let mut out = json!({ "role": message.role, "content": text });
+    if !images.is_empty() {
+        out["images"] = json!(images);
+    }
+    if let Some(calls) = &message.tool_calls {
+        out["tool_calls"] = calls
+            .iter()
+            .map(|c| {
+                let args: Value = serde_json::from_str(&c.function.arguments).unwrap_or(json!({}));
+                json!({ "function": { "name": c.function.name, "arguments": args } })
+            })
+            .collect();
+    }
+    out
+}
+
+/// Tool calls from `message.tool_calls`, with arguments re-serialized to strings.
+fn parse_tool_calls(message: &Value) -> Vec<(String, String)> {
+    message["tool_calls"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|call| {
+            let name = call["function"]["name"].as_str().unwrap_or_default().to_string();
+            let args = match &call["function"]["arguments"] {
+                Value::String(s) => s.clone(),
+                Value::Null => "{}".to_string(),
+                other => other.to_string(),
+            };
+            (name, args)
+        })
+        .collect()
+}
+
+/// Counts are only present on the final (`done`) response.
+fn parse_usage(response: &Value) -> Option<Usage> {
+    let usage = Usage::new(
+        response["prompt_eval_count"].as_u64().unwrap_or(0),
+        response["eval_count"].as_u64().unwrap_or(0),
+    );
+    (!usage.is_empty()).then_some(usage)
+}
+
+fn parse_response(response: &Value) -> ChatResponse {
+    let message = &response["message"];
+    let tool_calls: Vec<ToolCall> = parse_tool_calls(message)
+        .into_iter()
+        .map(|(name, arguments)| ToolCall {
+            id: new_call_id(),
+            r#type: "function".to_string(),
+            function: FunctionCall { name, arguments },
+        })
+        .collect();
+
+    ChatResponse {
+        message: Message {
+            role: "assistant".to_string(),
+            content: Some(MessageContent::Text(message["content"].as_str().unwrap_or_default().to_string())),
+            tool_calls: if tool_calls.is_empty() { None } else { Some(tool_calls) },
+            tool_call_id: None,
+        },
+        usage: parse_usage(response),
+    }
+}
+
+fn new_call_id() -> String {
+    format!("call_{}", uuid::Uuid::new_v4().simple())
+}
+
+#[async_trait]
+impl LlmProvider for OllamaClient {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::Ollama
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/api/tags", self.base_url.trim_end_matches('/'));
+        let body: Value = self.client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to reach Ollama (is `ollama serve` running?)")?
+            .json()
+            .await
+            .context("Failed to parse Ollama model list")?;
+        let mut models = tag_names(&body);
+        models.sort();
+        Ok(models)
+    }
+
+    async fn chat(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+        let response: Value = self.post(messages, tools, None, false).await?
+            .json()
+            .await
+            .context("Failed to parse Ollama response")?;
+        Ok(parse_response(&response))
+    }
+
+    async fn chat_structured(&self, messages: Vec<Message>, format: &JsonSchemaFormat) -> Result<ChatResponse> {
+        let response: Value = self.post(messages, None, Some(&format.schema), false).await?
+            .json()
+            .await
+            .context("Failed to parse Ollama response")?;
+        Ok(parse_response(&response))
+    }
+
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/api/embed", self.base_url.trim_end_matches('/'));
+        let response = self.client
+            .post(&url)
+            .json(&json!({ "model": model, "input": inputs }))
+            .send()
+            .await
+            .context("Failed to reach Ollama (is `ollama serve` running?)")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Ollama embeddings error: {}", error_text));
+        }
+
+        let body: Value = response.json().await.context("Failed to parse Ollama embeddings response")?;
+        serde_json::from_value(body["embeddings"].clone()).context("Malformed Ollama embeddings response")
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<Value>>,
+    ) -> Result<EventStream> {
+        let response = self.post(messages, tools, None, true).await?;
+
+        // Tool calls arrive whole (usually in the final chunk) and without ids.
+        let mut next_index = 0;
+        let parser = move |_event: &str, data: &str| -> Result<Vec<StreamEvent>> {
+            let Ok(chunk) = serde_json::from_str::<Value>(data) else {
+                return Ok(Vec::new());
+            };
+            if let Some(error) = chunk["error"].as_str() {
+                return Err(anyhow::anyhow!("Ollama stream error: {}", error));
+            }
+
+            let mut events = Vec::new();
+            let message = &chunk["message"];
+            if let Some(text) = message["content"].as_str().filter(|t| !t.is_empty()) {
+                events.push(StreamEvent::TextChunk(text.to_string()));
+            }
+            for (name, arguments) in parse_tool_calls(message) {
+                events.push(StreamEvent::ToolCallChunk(StreamDeltaToolCall::complete(
+                    next_index,
+                    new_call_id(),
+                    name,
+                    arguments,
+                )));
+                next_index += 1;
+            }
+            if chunk["done"].as_bool() == Some(true) {
+                if let Some(usage) = parse_usage(&chunk) {
+                    events.push(StreamEvent::Usage(usage));
+                }
+                events.push(StreamEvent::Done);
+            }
+            Ok(events)
+        };
+
+        Ok(Box::pin(SseStream::ndjson(response.bytes_stream(), Box::new(parser))))
+    }
+}
@@ -0,0 +1,373 @@
+use anyhow::{Context as _, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use super::provider::{EventStream, LlmProvider, ProviderKind};
+use super::retry::{self, RetryPolicy};
+use super::sse::SseStream;
+use super::types::{ChatResponse, JsonSchemaFormat, Message, SamplingParams, StreamDeltaToolCall, StreamEvent};
+use super::usage::Usage;
+
+/// Client for OpenAI-compatible `/chat/completions` endpoints.
+#[derive(Clone)]
+pub struct OpenAiClient {
+    api_key: String,
+    base_url: String,
+    model: String,
+    client: Client,
+    sampling: SamplingParams,
+    retry: RetryPolicy,
+}
+
+#[derive(Serialize)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<Message>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    top_p: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    stop: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prompt_cache_key: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(dead_code)]
+struct ChatCompletionResponse {
+    choices: Vec<Choice>,
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[allow(dead_code)]
+struct Choice {
+    message: Message,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct OpenAiUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u64,
+}
+
+impl From<OpenAiUsage> for Usage {
+    fn from(u: OpenAiUsage) -> Self {
+        let cached = u.prompt_tokens_details.map(|d| d.cached_tokens).unwrap_or(0);
+        Usage::new(u.prompt_tokens, u.completion_tokens).with_cached(cached)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamChunk {
+    #[serde(default)]
+    choices: Vec<StreamChoice>,
+    usage: Option<OpenAiUsage>,
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Deserialize, Debug)]
+struct StreamDelta {
+    content: Option<String>,
+    tool_calls: Option<Vec<StreamDeltaToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl ChatCompletionRequest {
+    /// OpenAI caches prompt prefixes automatically; a key derived from the
+    /// static prefix (system prompt and tool schemas) routes every turn of a
+    /// conversation to the same cache. Only sent to api.openai.com since
+    /// other compatible endpoints may reject unknown fields.
+    fn with_cache_key(mut self, base_url: &str) -> Self {
+        if !base_url.contains("api.openai.com") {
+            return self;
+        }
+        let mut hasher = DefaultHasher::new();
+        self.model.hash(&mut hasher);
+        for message in self.messages.iter().filter(|m| m.role == "system") {
+            message.text().hash(&mut hasher);
+        }
+        for tool in self.tools.iter().flatten() {
+            tool.to_string().hash(&mut hasher);
+        }
+        self.prompt_cache_key = Some(format!("axiom-{:016x}", hasher.finish()));
+        self
+    }
+}
+
+impl OpenAiClient {
+    pub fn new(api_key: String, base_url: String, model: String) -> Result<Self> {
+        let builder = Client::builder();
+
+        tracing::debug!("OpenAiClient::new called");
+
+        // We only configure proxy if explicitly set, otherwise we trust the local rotation proxy
+        // which the user provided (http://127.0.0.1:8045).
+        // Since that local proxy is an OpenAI adapter, we likely don't need an upstream HTTPS_PROXY for it
+        // unless it's running on a different machine (unlikely for 127.0.0.1).
+
+        if let Ok(proxy_url) = std::env::var("HTTPS_PROXY").or_else(|_| std::env::var("https_proxy")) {
+             // Only apply if the target isn't localhost/127.0.0.1, OR if the user really wants it.
+             // But usually for local dev we don't proxy localhost.
+             // Assuming the user might have set it for other things.
+             // Let's just log it for now.
+             tracing::debug!("HTTPS_PROXY env var found: {}", proxy_url);
+        }
+
+        let client = builder
+            .build()
+            .context("Failed to build reqwest client")?;
+
+        Ok(Self {
+            api_key,
+            base_url,
+            model,
+            client,
+            sampling: SamplingParams::default(),
+            retry: RetryPolicy::from_env(),
+        })
+    }
+
+    pub fn with_sampling(mut self, sampling: SamplingParams) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// `GEMINI_BASE_URL` / `GEMINI_API_KEY`, defaulting to the local proxy.
+    pub fn from_env(model: &str) -> Result<Self> {
+        let base_url = std::env::var("GEMINI_BASE_URL").unwrap_or_else(|_| "http://127.0.0.1:8045/v1".to_string());
+        let api_key = std::env::var("GEMINI_API_KEY").unwrap_or_default();
+        Self::new(api_key, base_url, model.to_string())
+    }
+
+    fn request(&self, messages: Vec<Message>, tools: Option<Vec<Value>>, stream: bool) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: self.model.clone(),
+            messages,
+            tools,
+            stream: stream.then_some(true),
+            stream_options: stream.then(|| serde_json::json!({ "include_usage": true })),
+            temperature: self.sampling.temperature,
+            top_p: self.sampling.top_p,
+            max_tokens: self.sampling.max_tokens,
+            stop: self.sampling.stop.clone(),
+            reasoning_effort: self.sampling.reasoning_effort.map(|e| e.as_str()),
+            response_format: None,
+            prompt_cache_key: None,
+        }
+        .with_cache_key(&self.base_url)
+    }
+
+    async fn complete(&self, request_body: ChatCompletionRequest) -> Result<ChatResponse> {
+        let url = self.completions_url();
+
+        tracing::debug!("Sending OpenAI-compatible request to: {}", url);
+
+        let response = retry::send(&self.retry, &self.base_url, || {
+            self.client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&request_body)
+        })
+        .await
+        .context("Failed to send request to API")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("API error: {}", error_text));
+        }
+
+        let response_body: ChatCompletionResponse = response.json().await
+            .context("Failed to parse API response")?;
+
+        let usage = response_body.usage.map(Usage::from);
+        let message = response_body
+            .choices
+            .into_iter()
+            .next()
+            .map(|c| c.message)
+            .ok_or_else(|| anyhow::anyhow!("API returned no choices"))?;
+
+        Ok(ChatResponse { message, usage })
+    }
+
+    fn completions_url(&self) -> String {
+        format!("{}/chat/completions", self.base_url.trim_end_matches('/'))
+    }
+}
+
+/// OpenAI stream chunks carry either a text delta or a tool call delta.
+fn parse_openai_chunk(_event: &str, data: &str) -> Result<Vec<StreamEvent>> {
+    if data == "[DONE]" {
+        return Ok(vec![StreamEvent::Done]);
+    }
+
+    // Parse OpenAI-compatible Stream Response; ignore malformed chunks
+    let Ok(chunk) = serde_json::from_str::<StreamChunk>(data) else {
+        return Ok(Vec::new());
+    };
+
+    let mut events = Vec::new();
+    // Sent in a final chunk with no choices when `include_usage` is set
+    if let Some(usage) = chunk.usage {
+        events.push(StreamEvent::Usage(usage.into()));
+    }
+    if let Some(choice) = chunk.choices.into_iter().next() {
+        if let Some(content) = choice.delta.content {
+            if !content.is_empty() {
+                events.push(StreamEvent::TextChunk(content));
+            }
+        }
+        if let Some(tool_calls) = choice.delta.tool_calls {
+            events.extend(tool_calls.into_iter().map(StreamEvent::ToolCallChunk));
+        }
+    }
+    Ok(events)
+}
+
+/// `id`s of a `{"data": [{"id": ...}]}` model list, sorted.
+pub(crate) fn model_ids(data: &Value) -> Vec<String> {
+    let mut ids: Vec<String> = data
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|m| m["id"].as_str().map(str::to_string))
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiClient {
+    fn kind(&self) -> ProviderKind {
+        ProviderKind::OpenAiCompatible
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<Value>>,
+    ) -> Result<EventStream> {
+        let url = self.completions_url();
+
+        tracing::debug!("Sending OpenAI-compatible STREAM request to: {}", url);
+
+        let request_body = self.request(messages, tools, true);
+
+        let response = retry::send(&self.retry, &self.base_url, || {
+            self.client
+                .post(&url)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&request_body)
+        })
+        .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("API error ({}): {}", status, error_text));
+        }
+
+        tracing::debug!("Successfully sent stream request");
+        Ok(Box::pin(SseStream::new(response.bytes_stream(), Box::new(parse_openai_chunk))))
+    }
+
+    async fn chat(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+        self.complete(self.request(messages, tools, false)).await
+    }
+
+    async fn chat_structured(&self, messages: Vec<Message>, format: &JsonSchemaFormat) -> Result<ChatResponse> {
+        let mut request_body = self.request(messages, None, false);
+        request_body.response_format = Some(serde_json::json!({
+            "type": "json_schema",
+            "json_schema": { "name": format.name, "schema": format.schema },
+        }));
+        self.complete(request_body).await
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/models", self.base_url.trim_end_matches('/'));
+        let response = self.client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send()
+            .await
+            .context("Failed to send models request")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Models API error: {}", error_text));
+        }
+
+        let body: Value = response.json().await.context("Failed to parse models response")?;
+        Ok(model_ids(&body["data"]))
+    }
+
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let url = format!("{}/embeddings", self.base_url.trim_end_matches('/'));
+        let response = self.client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .json(&serde_json::json!({ "model": model, "input": inputs }))
+            .send()
+            .await
+            .context("Failed to send embeddings request")?;
+
+        if !response.status().is_success() {
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow::anyhow!("Embeddings API error: {}", error_text));
+        }
+
+        let body: EmbeddingResponse = response.json().await
+            .context("Failed to parse embeddings response")?;
+        let mut data = body.data;
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
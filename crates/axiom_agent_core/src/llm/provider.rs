@@ -0,0 +1,149 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use super::types::{ChatResponse, JsonSchemaFormat, Message, SamplingParams, StreamEvent};
+use super::replay::{self, RecordingProvider, ReplayProvider};
+use super::{anthropic::AnthropicClient, gemini::GeminiClient, ollama::OllamaClient, openai::OpenAiClient};
+
+pub type EventStream = Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>;
+
+/// Which wire protocol a profile talks to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProviderKind {
+    /// Any `/chat/completions` endpoint (OpenAI, one-api proxies, Gemini's OpenAI shim).
+    #[default]
+    OpenAiCompatible,
+    /// Anthropic Messages API.
+    Anthropic,
+    /// Google Generative Language API (`generateContent`).
+    Gemini,
+    /// Local Ollama server (`/api/chat`).
+    Ollama,
+}
+
+impl ProviderKind {
+    pub const ALL: [ProviderKind; 4] = [
+        ProviderKind::OpenAiCompatible,
+        ProviderKind::Anthropic,
+        ProviderKind::Gemini,
+        ProviderKind::Ollama,
+    ];
+
+    /// Parse a provider name as used in env configuration.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "openai" | "openai-compatible" | "openai_compatible" => Some(ProviderKind::OpenAiCompatible),
+            "anthropic" => Some(ProviderKind::Anthropic),
+            "gemini" | "google" => Some(ProviderKind::Gemini),
+            "ollama" => Some(ProviderKind::Ollama),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenAiCompatible => "OpenAI-compatible",
+            ProviderKind::Anthropic => "Anthropic",
+            ProviderKind::Gemini => "Gemini",
+            ProviderKind::Ollama => "Ollama",
+        }
+    }
+}
+
+/// A chat model backend. Messages and tool schemas are always passed in the
+/// OpenAI shape (see `llm::types`); implementations translate as needed.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    fn kind(&self) -> ProviderKind;
+
+    fn model(&self) -> &str;
+
+    /// Whether tool schemas are forwarded to the model.
+    fn supports_tools(&self) -> bool {
+        true
+    }
+
+    /// Whether image parts are forwarded to the model.
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    /// Non-streaming completion returning the assistant message and usage.
+    #[allow(dead_code)]
+    async fn chat(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<ChatResponse>;
+
+    /// Non-streaming completion constrained to a JSON schema. The JSON is
+    /// returned as the message text; run it through `json_repair` before use,
+    /// since not every backend enforces the schema strictly.
+    async fn chat_structured(&self, messages: Vec<Message>, format: &JsonSchemaFormat) -> Result<ChatResponse>;
+
+    /// Models the endpoint serves, sorted by name, for the model picker.
+    async fn list_models(&self) -> Result<Vec<String>> {
+        Err(anyhow!("{} does not support listing models", self.kind().label()))
+    }
+
+    /// Embed `inputs` with the given embedding model, one vector per input.
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        let _ = (model, inputs);
+        Err(anyhow!("{} does not provide an embeddings endpoint", self.kind().label()))
+    }
+
+    /// Streaming completion. Providers that report usage emit `StreamEvent::Usage`.
+    async fn chat_stream(
+        &self,
+        messages: Vec<Message>,
+        tools: Option<Vec<Value>>,
+    ) -> Result<EventStream>;
+}
+
+/// Build the provider for a profile. Credentials and base URLs come from the
+/// environment (see `.env.example`), as do recording and replay (`replay.rs`).
+pub fn create_provider(kind: ProviderKind, model: &str, sampling: &SamplingParams) -> Result<Arc<dyn LlmProvider>> {
+    let mode = replay::mode_from_env();
+    if let replay::Mode::Replay(path) = &mode {
+        return Ok(Arc::new(ReplayProvider::new(path, kind, model)?));
+    }
+
+    let sampling = sampling.clone();
+    let provider: Arc<dyn LlmProvider> = match kind {
+        ProviderKind::OpenAiCompatible => Arc::new(OpenAiClient::from_env(model)?.with_sampling(sampling)),
+        ProviderKind::Anthropic => Arc::new(AnthropicClient::from_env(model)?.with_sampling(sampling)),
+        ProviderKind::Gemini => Arc::new(GeminiClient::from_env(model)?.with_sampling(sampling)),
+        ProviderKind::Ollama => Arc::new(OllamaClient::from_env(model)?.with_sampling(sampling)),
+    };
+    match mode {
+        replay::Mode::Record => Ok(Arc::new(RecordingProvider::new(provider))),
+        _ => Ok(provider),
+    }
+}
+
+/// Extract the text of all `system` messages, joined, and return the rest.
+/// Anthropic and Gemini take the system prompt as a separate request field.
+pub(crate) fn split_system(messages: Vec<Message>) -> (Option<String>, Vec<Message>) {
+    let mut system = Vec::new();
+    let mut rest = Vec::new();
+    for message in messages {
+        if message.role == "system" {
+            system.push(message.text());
+        } else {
+            rest.push(message);
+        }
+    }
+
+    let system = if system.is_empty() {
+        None
+    } else {
+        Some(system.join("\n\n"))
+    };
+    (system, rest)
+}
+
+/// Tool schemas are stored as `{"type": "function", "function": {...}}`; return the inner objects.
+pub(crate) fn function_declarations(tools: &[Value]) -> Vec<&Value> {
+    tools.iter().filter_map(|t| t.get("function")).collect()
+}
@@ -0,0 +1,490 @@
+//! Recording and replaying provider traffic.
+//!
+//! With `AXIOM_RECORD=1` every request made through `create_provider` is
+//! appended, together with the streamed events or response, to
+//! `.axiom/recordings/<timestamp>.jsonl`. With `AXIOM_REPLAY=<file>` the
+//! providers are replaced by one that answers from such a recording in order,
+//! so the agent loop (tool-call assembly, tool execution, follow-up turns)
+//! can be driven deterministically without a live API.
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use futures_util::Stream;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::task::{Context as TaskContext, Poll};
+
+use super::provider::{EventStream, LlmProvider, ProviderKind};
+use super::types::{ChatResponse, JsonSchemaFormat, Message, StreamEvent};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Method {
+    Chat,
+    ChatStructured,
+    ChatStream,
+}
+
+/// One request and what came back.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Exchange {
+    pub timestamp: String,
+    pub provider: ProviderKind,
+    pub model: String,
+    pub method: Method,
+    pub messages: Vec<Message>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<Value>,
+    /// Streamed events, in order (`ChatStream`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<StreamEvent>,
+    /// Non-streaming result (`Chat`, `ChatStructured`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<ChatResponse>,
+    /// Request or stream error, if it failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The stream was dropped before it finished (e.g. stopped by the user).
+    #[serde(default)]
+    pub incomplete: bool,
+}
+
+impl Exchange {
+    fn new(provider: &dyn LlmProvider, method: Method, messages: &[Message], tools: Option<&Vec<Value>>) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            provider: provider.kind(),
+            model: provider.model().to_string(),
+            method,
+            messages: messages.to_vec(),
+            tools: tools.cloned(),
+            schema: None,
+            events: Vec::new(),
+            response: None,
+            error: None,
+            incomplete: false,
+        }
+    }
+}
+
+/// `AXIOM_REPLAY` if set, else whether `AXIOM_RECORD` asks for recording.
+pub enum Mode {
+    Live,
+    Record,
+    Replay(PathBuf),
+}
+
+pub fn mode_from_env() -> Mode {
+    if let Ok(path) = std::env::var("AXIOM_REPLAY") {
+        if !path.trim().is_empty() {
+            return Mode::Replay(PathBuf::from(path));
+        }
+    }
+    match std::env::var("AXIOM_RECORD").as_deref() {
+        Ok("1") | Ok("true") => Mode::Record,
+        _ => Mode::Live,
+    }
+}
+
+// --- Recording ---
+
+/// One file per run, shared by every provider the run creates.
+fn recording_file() -> Result<&'static Mutex<File>> {
+    static FILE: OnceLock<Mutex<File>> = OnceLock::new();
+    if let Some(file) = FILE.get() {
+        return Ok(file);
+    }
+
+    let dir = Path::new(".axiom").join("recordings");
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    let path = dir.join(format!("{}.jsonl", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    tracing::info!("Recording provider traffic to {}", path.display());
+    Ok(FILE.get_or_init(|| Mutex::new(file)))
+}
+
+/// Append `exchange` to `file`, or to the run's file if `None`.
+fn write_exchange(file: Option<&Mutex<File>>, exchange: &Exchange) {
+    let file = match file {
+        Some(file) => Ok(file),
+        None => recording_file(),
+    };
+    let result = file.and_then(|file| {
+        let line = serde_json::to_string(exchange)?;
+        let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(file, "{}", line)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to record exchange: {:#}", e);
+    }
+}
+
+/// Wraps a provider and records everything that passes through it.
+pub struct RecordingProvider {
+    inner: Arc<dyn LlmProvider>,
+    /// Where exchanges go; `None` for the run's file under `.axiom/recordings`.
+    file: Option<Arc<Mutex<File>>>,
+}
+
+impl RecordingProvider {
+    pub fn new(inner: Arc<dyn LlmProvider>) -> Self {
+        Self { inner, file: None }
+    }
+
+    fn write(&self, exchange: &Exchange) {
+        write_exchange(self.file.as_deref(), exchange);
+    }
+}
+
+/// Tees stream events into an `Exchange`, written when the stream ends or is dropped.
+struct RecordingStream {
+    inner: EventStream,
+    exchange: Option<Exchange>,
+    file: Option<Arc<Mutex<File>>>,
+}
+
+impl Stream for RecordingStream {
+    type Item = Result<StreamEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        let polled = self.inner.as_mut().poll_next(cx);
+        match &polled {
+            Poll::Ready(Some(item)) => {
+                if let Some(exchange) = self.exchange.as_mut() {
+                    match item {
+                        Ok(event) => exchange.events.push(event.clone()),
+                        Err(e) => exchange.error = Some(format!("{:#}", e)),
+                    }
+                }
+            }
+            Poll::Ready(None) => {
+                if let Some(exchange) = self.exchange.take() {
+                    write_exchange(self.file.as_deref(), &exchange);
+                }
+            }
+            Poll::Pending => {}
+        }
+        polled
+    }
+}
+
+impl Drop for RecordingStream {
+    fn drop(&mut self) {
+        if let Some(mut exchange) = self.exchange.take() {
+            exchange.incomplete = true;
+            write_exchange(self.file.as_deref(), &exchange);
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RecordingProvider {
+    fn kind(&self) -> ProviderKind {
+        self.inner.kind()
+    }
+
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn supports_tools(&self) -> bool {
+        self.inner.supports_tools()
+    }
+
+    fn supports_vision(&self) -> bool {
+        self.inner.supports_vision()
+    }
+
+    async fn chat(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+        let mut exchange = Exchange::new(self.inner.as_ref(), Method::Chat, &messages, tools.as_ref());
+        let result = self.inner.chat(messages, tools).await;
+        match &result {
+            Ok(response) => exchange.response = Some(response.clone()),
+            Err(e) => exchange.error = Some(format!("{:#}", e)),
+        }
+        self.write(&exchange);
+        result
+    }
+
+    async fn chat_structured(&self, messages: Vec<Message>, format: &JsonSchemaFormat) -> Result<ChatResponse> {
+        let mut exchange = Exchange::new(self.inner.as_ref(), Method::ChatStructured, &messages, None);
+        exchange.schema = Some(format.schema.clone());
+        let result = self.inner.chat_structured(messages, format).await;
+        match &result {
+            Ok(response) => exchange.response = Some(response.clone()),
+            Err(e) => exchange.error = Some(format!("{:#}", e)),
+        }
+        self.write(&exchange);
+        result
+    }
+
+    async fn list_models(&self) -> Result<Vec<String>> {
+        self.inner.list_models().await
+    }
+
+    async fn embed(&self, model: &str, inputs: &[String]) -> Result<Vec<Vec<f32>>> {
+        self.inner.embed(model, inputs).await
+    }
+
+    async fn chat_stream(&self, messages: Vec<Message>, tools: Option<Vec<Value>>) -> Result<EventStream> {
+        let mut exchange = Exchange::new(self.inner.as_ref(), Method::ChatStream, &messages, tools.as_ref());
+        match self.inner.chat_stream(messages, tools).await {
+            Ok(inner) => Ok(Box::pin(RecordingStream { inner, exchange: Some(exchange), file: self.file.clone() })),
+            Err(e) => {
+                exchange.error = Some(format!("{:#}", e));
+                self.write(&exchange);
+                Err(e)
+            }
+        }
+    }
+}
+
+// --- Replay ---
+
+/// Exchanges not yet replayed. Shared so clients rebuilt mid-run (profile
+/// switch, sub-agents) continue from the same position.
+fn replay_queue(path: &Path) -> Result<Arc<Mutex<VecDeque<Exchange>>>> {
+    static QUEUE: OnceLock<Arc<Mutex<VecDeque<Exchange>>>> = OnceLock::new();
+    if let Some(queue) = QUEUE.get() {
+        return Ok(queue.clone());
+    }
+
+    let exchanges = load_exchanges(path)?;
+    tracing::info!("Loaded {} exchange(s) from {}", exchanges.len(), path.display());
+    Ok(QUEUE.get_or_init(|| Arc::new(Mutex::new(exchanges))).clone())
+}
+
+fn load_exchanges(path: &Path) -> Result<VecDeque<Exchange>> {
+    let file = File::open(path).with_context(|| format!("Failed to open recording {}", path.display()))?;
+    let mut exchanges = VecDeque::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let exchange: Exchange = serde_json::from_str(&line)
+            .with_context(|| format!("{}:{}: invalid exchange", path.display(), i + 1))?;
+        exchanges.push_back(exchange);
+    }
+    Ok(exchanges)
+}
+
+/// Answers requests from a recording instead of the network.
+pub struct ReplayProvider {
+    kind: ProviderKind,
+    model: String,
+    queue: Arc<Mutex<VecDeque<Exchange>>>,
+}
+
+impl ReplayProvider {
+    pub fn new(path: &Path, kind: ProviderKind, model: &str) -> Result<Self> {
+        Ok(Self {
+            kind,
+            model: model.to_string(),
+            queue: replay_queue(path)?,
+        })
+    }
+
+    /// The next recorded exchange, which must be for the same kind of call.
+    fn next(&self, method: Method, messages: &[Message]) -> Result<Exchange> {
+        let exchange = self
+            .queue
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pop_front()
+            .ok_or_else(|| anyhow!("Replay exhausted: no recorded exchange left for {:?}", method))?;
+
+        if exchange.method != method {
+            return Err(anyhow!(
+                "Replay out of sync: request is {:?} but the recording has {:?} ({})",
+                method,
+                exchange.method,
+                exchange.timestamp
+            ));
+        }
+        if exchange.messages.len() != messages.len() {
+            tracing::warn!(
+                "Request diverged from recording ({} messages, recorded {})",
+                messages.len(),
+                exchange.messages.len()
+            );
+        }
+        Ok(exchange)
+    }
+
+    fn response(exchange: Exchange) -> Result<ChatResponse> {
+        match (exchange.response, exchange.error) {
+            (Some(response), _) => Ok(response),
+            (None, Some(error)) => Err(anyhow!("{}", error)),
+            (None, None) => Err(anyhow!("Recorded exchange has no response")),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for ReplayProvider {
+    fn kind(&self) -> ProviderKind {
+        self.kind
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn chat(&self, messages: Vec<Message>, _tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+        Self::response(self.next(Method::Chat, &messages)?)
+    }
+
+    async fn chat_structured(&self, messages: Vec<Message>, _format: &JsonSchemaFormat) -> Result<ChatResponse> {
+        Self::response(self.next(Method::ChatStructured, &messages)?)
+    }
+
+    async fn chat_stream(&self, messages: Vec<Message>, _tools: Option<Vec<Value>>) -> Result<EventStream> {
+        let exchange = self.next(Method::ChatStream, &messages)?;
+        if exchange.events.is_empty() {
+            if let Some(error) = exchange.error {
+                return Err(anyhow!("{}", error));
+            }
+        }
+
+        let mut items: Vec<Result<StreamEvent>> = exchange.events.into_iter().map(Ok).collect();
+        if let Some(error) = exchange.error {
+            items.push(Err(anyhow!("{}", error)));
+        }
+        Ok(Box::pin(futures_util::stream::iter(items)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::types::{StreamDeltaFunction, StreamDeltaToolCall};
+    use crate::llm::MessageContent;
+    use crate::{AgentEvent, AgentRuntime, Tool};
+    use serde_json::json;
+
+    /// Streams the next scripted turn, standing in for a live API.
+    struct ScriptedProvider {
+        turns: Mutex<VecDeque<Vec<StreamEvent>>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn kind(&self) -> ProviderKind {
+            ProviderKind::OpenAiCompatible
+        }
+
+        fn model(&self) -> &str {
+            "scripted"
+        }
+
+        async fn chat(&self, _messages: Vec<Message>, _tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+            Err(anyhow!("not scripted"))
+        }
+
+        async fn chat_structured(&self, _messages: Vec<Message>, _format: &JsonSchemaFormat) -> Result<ChatResponse> {
+            Err(anyhow!("not scripted"))
+        }
+
+        async fn chat_stream(&self, _messages: Vec<Message>, _tools: Option<Vec<Value>>) -> Result<EventStream> {
+            let events = self.turns.lock().unwrap().pop_front().ok_or_else(|| anyhow!("script exhausted"))?;
+            Ok(Box::pin(futures_util::stream::iter(events.into_iter().map(Ok))))
+        }
+    }
+
+    struct EchoTool;
+
+    impl Tool for EchoTool {
+        fn name(&self) -> String {
+            "echo".to_string()
+        }
+        fn description(&self) -> String {
+            "Echo the text back.".to_string()
+        }
+        fn schema(&self) -> Value {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": "echo",
+                    "description": "Echo the text back.",
+                    "parameters": { "type": "object", "properties": { "text": { "type": "string" } } }
+                }
+            })
+        }
+        fn execute(&self, args: Value) -> Result<String> {
+            Ok(args["text"].as_str().unwrap_or_default().to_string())
+        }
+    }
+
+    fn tool_call_chunk(id: Option<&str>, name: Option<&str>, args: &str) -> StreamEvent {
+        StreamEvent::ToolCallChunk(StreamDeltaToolCall {
+            index: 0,
+            id: id.map(str::to_string),
+            r#type: None,
+            function: Some(StreamDeltaFunction { name: name.map(str::to_string), arguments: Some(args.to_string()) }),
+        })
+    }
+
+    /// Run the agent loop on "ping" and return the transcript and events.
+    async fn run(provider: Arc<dyn LlmProvider>) -> (Value, Vec<String>) {
+        let runtime = AgentRuntime::new(provider, vec![Box::new(EchoTool)]);
+        let mut messages = vec![Message {
+            role: "user".to_string(),
+            content: Some(MessageContent::Text("ping".to_string())),
+            tool_calls: None,
+            tool_call_id: None,
+        }];
+        let mut events = Vec::new();
+        runtime.run(&mut messages, |e: AgentEvent| events.push(format!("{:?}", e))).await.unwrap();
+        (serde_json::to_value(&messages).unwrap(), events)
+    }
+
+    #[tokio::test]
+    async fn test_replay_reproduces_recorded_run() {
+        let path = std::env::temp_dir().join(format!("axiom_replay_{}.jsonl", uuid::Uuid::new_v4()));
+        let live = ScriptedProvider {
+            turns: Mutex::new(VecDeque::from([
+                vec![
+                    tool_call_chunk(Some("call_1"), Some("echo"), "{\"text\":"),
+                    tool_call_chunk(None, None, "\"pong\"}"),
+                    StreamEvent::Done,
+                ],
+                vec![StreamEvent::TextChunk("done".to_string()), StreamEvent::Done],
+            ])),
+        };
+        let file = OpenOptions::new().create(true).append(true).open(&path).unwrap();
+        let recording = RecordingProvider { inner: Arc::new(live), file: Some(Arc::new(Mutex::new(file))) };
+        let (recorded, recorded_events) = run(Arc::new(recording)).await;
+
+        let exchanges = load_exchanges(&path).unwrap();
+        assert_eq!(exchanges.len(), 2);
+        assert!(exchanges.iter().all(|e| e.method == Method::ChatStream && !e.incomplete));
+
+        let replay = ReplayProvider {
+            kind: ProviderKind::OpenAiCompatible,
+            model: "scripted".to_string(),
+            queue: Arc::new(Mutex::new(exchanges)),
+        };
+        let (replayed, replayed_events) = run(Arc::new(replay)).await;
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replayed, recorded);
+        assert_eq!(replayed_events, recorded_events);
+        assert_eq!(replayed[1]["tool_calls"][0]["function"]["arguments"], "{\"text\":\"pong\"}");
+        assert_eq!(replayed[2]["content"], "pong");
+        assert_eq!(replayed[3]["content"], "done");
+    }
+}
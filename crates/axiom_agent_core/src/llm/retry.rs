@@ -0,0 +1,278 @@
+//! Retry with backoff and a per-endpoint circuit breaker for LLM requests.
+//!
+//! `send` retries rate limits (429), server errors (5xx) and network failures
+//! according to a `RetryPolicy`. Every outcome is reported to a circuit
+//! breaker shared by all clients of the same endpoint; once it trips, requests
+//! fail fast with `ProviderUnavailable` until the cooldown has passed, which
+//! the UI shows instead of a generic error.
+
+use anyhow::{anyhow, Result};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on every further retry.
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Random spread applied to each delay, as a fraction (0.2 = ±20%).
+    pub jitter: f64,
+    /// Total time a request may spend waiting between attempts.
+    pub budget: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_secs(2),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            budget: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Defaults overridden by `AXIOM_RETRY_MAX`, `AXIOM_RETRY_BASE_MS` and
+    /// `AXIOM_RETRY_BUDGET_SECS`.
+    pub fn from_env() -> Self {
+        let mut policy = Self::default();
+        if let Some(max) = env_parse("AXIOM_RETRY_MAX") {
+            policy.max_retries = max;
+        }
+        if let Some(ms) = env_parse("AXIOM_RETRY_BASE_MS") {
+            policy.base_delay = Duration::from_millis(ms);
+        }
+        if let Some(secs) = env_parse("AXIOM_RETRY_BUDGET_SECS") {
+            policy.budget = Duration::from_secs(secs);
+        }
+        policy
+    }
+
+    /// Backoff before retry number `retry` (0-based), with jitter.
+    pub fn delay_for(&self, retry: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(2u32.saturating_pow(retry));
+        let capped = exp.min(self.max_delay).as_secs_f64();
+        let spread = capped * self.jitter * (fastrand::f64() * 2.0 - 1.0);
+        Duration::from_secs_f64((capped + spread).max(0.0))
+    }
+}
+
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    std::env::var(key).ok()?.trim().parse().ok()
+}
+
+/// Returned (inside `anyhow::Error`) while an endpoint's breaker is open.
+#[derive(Debug, Clone)]
+pub struct ProviderUnavailable {
+    pub endpoint: String,
+    pub retry_in: Duration,
+}
+
+impl fmt::Display for ProviderUnavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Provider unavailable ({}): too many consecutive failures, retrying in {}s",
+            self.endpoint,
+            self.retry_in.as_secs().max(1)
+        )
+    }
+}
+
+impl std::error::Error for ProviderUnavailable {}
+
+/// Consecutive requests that exhausted their retries before the breaker opens.
+const FAILURE_THRESHOLD: u32 = 3;
+/// How long an open breaker rejects requests before letting one through.
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[derive(Default)]
+struct Breaker {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// Breakers are keyed by endpoint, not client, so they survive the client
+/// being rebuilt on a profile switch.
+fn breakers() -> &'static Mutex<HashMap<String, Breaker>> {
+    static BREAKERS: OnceLock<Mutex<HashMap<String, Breaker>>> = OnceLock::new();
+    BREAKERS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// `now` is passed in so tests can step past the cooldown.
+fn check_breaker(endpoint: &str, now: Instant) -> Result<()> {
+    let breakers = breakers().lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(until) = breakers.get(endpoint).and_then(|b| b.open_until) {
+        if until > now {
+            return Err(ProviderUnavailable {
+                endpoint: endpoint.to_string(),
+                retry_in: until - now,
+            }
+            .into());
+        }
+        // Cooldown over: half-open, let this request probe the endpoint
+    }
+    Ok(())
+}
+
+/// `reachable` is true for any answer that isn't a transient failure; a 401
+/// is a configuration problem, not an outage.
+fn record(endpoint: &str, reachable: bool, now: Instant) {
+    let mut breakers = breakers().lock().unwrap_or_else(|e| e.into_inner());
+    let breaker = breakers.entry(endpoint.to_string()).or_default();
+    if reachable {
+        *breaker = Breaker::default();
+        return;
+    }
+    breaker.failures += 1;
+    if breaker.failures >= FAILURE_THRESHOLD {
+        tracing::warn!("{} failed {} times in a row, pausing requests", endpoint, breaker.failures);
+        breaker.open_until = Some(now + COOLDOWN);
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// `Retry-After` in seconds, if the server sent one.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let secs: u64 = response.headers().get("retry-after")?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(secs))
+}
+
+/// Send the request built by `build`, retrying transient failures.
+///
+/// Non-retryable responses (e.g. 400, 401) are returned as-is so callers keep
+/// their provider-specific error messages; so is the last retryable one once
+/// retries or the budget run out.
+pub async fn send<F>(policy: &RetryPolicy, endpoint: &str, build: F) -> Result<Response>
+where
+    F: Fn() -> RequestBuilder,
+{
+    check_breaker(endpoint, Instant::now())?;
+
+    let mut waited = Duration::ZERO;
+    let mut retry = 0;
+    loop {
+        let (delay, reason) = match build().send().await {
+            Ok(response) if !is_retryable(response.status()) => {
+                record(endpoint, true, Instant::now());
+                return Ok(response);
+            }
+            Ok(response) => {
+                let delay = retry_after(&response).unwrap_or_else(|| policy.delay_for(retry));
+                if retry >= policy.max_retries || waited + delay > policy.budget {
+                    record(endpoint, false, Instant::now());
+                    return Ok(response);
+                }
+                // Once per request, so the UI can tell the user why it's slow
+                if retry == 0 && response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    tracing::warn!(notify = true, "{} is rate limiting requests, retrying", endpoint);
+                }
+                (delay, response.status().to_string())
+            }
+            Err(e) => {
+                let delay = policy.delay_for(retry);
+                if retry >= policy.max_retries || waited + delay > policy.budget {
+                    record(endpoint, false, Instant::now());
+                    return Err(anyhow!("Network error: {}", e));
+                }
+                (delay, e.to_string())
+            }
+        };
+
+        tracing::info!(
+            "{} ({}), retry {}/{} in {:.1}s",
+            endpoint,
+            reason,
+            retry + 1,
+            policy.max_retries,
+            delay.as_secs_f64()
+        );
+        sleep(delay).await;
+        waited += delay;
+        retry += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unavailable(result: Result<()>) -> ProviderUnavailable {
+        result.unwrap_err().downcast::<ProviderUnavailable>().unwrap()
+    }
+
+    #[test]
+    fn test_delay_for_doubles_up_to_the_cap() {
+        let policy = RetryPolicy { jitter: 0.0, ..Default::default() };
+        assert_eq!(policy.delay_for(0), Duration::from_secs(2));
+        assert_eq!(policy.delay_for(3), Duration::from_secs(16));
+        assert_eq!(policy.delay_for(4), Duration::from_secs(30));
+        assert_eq!(policy.delay_for(64), Duration::from_secs(30));
+
+        let jittered = RetryPolicy::default().delay_for(10);
+        assert!(jittered >= Duration::from_secs(24) && jittered <= Duration::from_secs(36), "{:?}", jittered);
+    }
+
+    // Breakers are global, so each test uses its own endpoint
+
+    #[test]
+    fn test_breaker_opens_after_the_threshold() {
+        let endpoint = "test://opens";
+        let now = Instant::now();
+        for _ in 1..FAILURE_THRESHOLD {
+            record(endpoint, false, now);
+        }
+        assert!(check_breaker(endpoint, now).is_ok());
+
+        record(endpoint, false, now);
+        let error = unavailable(check_breaker(endpoint, now));
+        assert_eq!(error.endpoint, endpoint);
+        assert_eq!(error.retry_in, COOLDOWN);
+    }
+
+    #[test]
+    fn test_breaker_resets_on_a_reachable_answer() {
+        let endpoint = "test://resets";
+        let now = Instant::now();
+        for _ in 1..FAILURE_THRESHOLD {
+            record(endpoint, false, now);
+        }
+        record(endpoint, true, now);
+        for _ in 1..FAILURE_THRESHOLD {
+            record(endpoint, false, now);
+        }
+        assert!(check_breaker(endpoint, now).is_ok());
+    }
+
+    #[test]
+    fn test_breaker_half_opens_after_the_cooldown() {
+        let endpoint = "test://half-open";
+        let opened = Instant::now();
+        for _ in 0..FAILURE_THRESHOLD {
+            record(endpoint, false, opened);
+        }
+        let almost = opened + COOLDOWN - Duration::from_secs(1);
+        assert_eq!(unavailable(check_breaker(endpoint, almost)).retry_in, Duration::from_secs(1));
+
+        // One probe goes through; if it fails too the breaker opens again
+        let probe = opened + COOLDOWN;
+        assert!(check_breaker(endpoint, probe).is_ok());
+        record(endpoint, false, probe);
+        assert!(check_breaker(endpoint, probe).is_err());
+
+        let later = probe + COOLDOWN;
+        record(endpoint, true, later);
+        assert!(check_breaker(endpoint, later).is_ok());
+    }
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use futures_util::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use super::types::StreamEvent;
+
+/// Turns one SSE `data:` payload (plus the preceding `event:` name, if any)
+/// into zero or more stream events. Providers keep their own parsing state in
+/// the closure.
+pub type SseParser = Box<dyn FnMut(&str, &str) -> Result<Vec<StreamEvent>> + Send>;
+
+/// Line-oriented Server-Sent Events decoder shared by all providers.
+pub struct SseStream<S> {
+    inner: S,
+    buffer: Vec<u8>,
+    event: String,
+    pending: VecDeque<StreamEvent>,
+    parser: SseParser,
+    /// Treat every non-empty line as a payload (newline-delimited JSON, no `data:` prefix).
+    ndjson: bool,
+}
+
+impl<S> SseStream<S> {
+    pub fn new(inner: S, parser: SseParser) -> Self {
+        Self {
+            inner,
+            buffer: Vec::new(),
+            event: String::new(),
+            pending: VecDeque::new(),
+            parser,
+            ndjson: false,
+        }
+    }
+
+    /// Decoder for newline-delimited JSON bodies (Ollama).
+    pub fn ndjson(inner: S, parser: SseParser) -> Self {
+        Self {
+            ndjson: true,
+            ..Self::new(inner, parser)
+        }
+    }
+}
+
+impl<S, B> Stream for SseStream<S>
+where
+    S: Stream<Item = reqwest::Result<B>> + Unpin,
+    B: AsRef<[u8]>,
+{
+    type Item = Result<StreamEvent>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Poll::Ready(Some(Ok(event)));
+            }
+
+            // Check buffer for newline
+            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line_bytes = self.buffer.drain(..pos + 1).collect::<Vec<u8>>();
+                let line_str = String::from_utf8_lossy(&line_bytes[..line_bytes.len() - 1]).to_string();
+                let line = line_str.trim();
+
+                if self.ndjson {
+                    if !line.is_empty() {
+                        match (self.parser)("", line) {
+                            Ok(events) => self.pending.extend(events),
+                            Err(e) => return Poll::Ready(Some(Err(e))),
+                        }
+                    }
+                } else if line.is_empty() {
+                    // Blank line terminates an SSE event
+                    self.event.clear();
+                } else if let Some(name) = line.strip_prefix("event:") {
+                    self.event = name.trim().to_string();
+                } else if let Some(data) = line.strip_prefix("data:") {
+                    let data = data.trim_start();
+                    let event = self.event.clone();
+                    match (self.parser)(&event, data) {
+                        Ok(events) => self.pending.extend(events),
+                        Err(e) => return Poll::Ready(Some(Err(e))),
+                    }
+                }
+                continue;
+            }
+
+            // No newline found, pull more data
+            match Pin::new(&mut self.inner).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => {
+                    self.buffer.extend_from_slice(chunk.as_ref());
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    return Poll::Ready(Some(Err(anyhow::Error::from(e))));
+                }
+                Poll::Ready(None) => {
+                    return Poll::Ready(None);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
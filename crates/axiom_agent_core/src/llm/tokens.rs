@@ -0,0 +1,86 @@
+//! Token estimation for request budgeting.
+//!
+//! Every provider tokenizes differently; `o200k_base` is close enough for all
+//! of them to decide when a conversation needs trimming.
+
+use std::sync::OnceLock;
+use tiktoken_rs::CoreBPE;
+
+use super::types::{Message, MessageContent};
+
+/// Flat cost charged for an image part. Providers bill images by resolution;
+/// this is in the range of a downscaled screenshot.
+const IMAGE_TOKENS: usize = 1000;
+
+/// Per-message framing overhead (role markers etc.).
+const MESSAGE_OVERHEAD: usize = 4;
+
+fn bpe() -> Option<&'static CoreBPE> {
+    static BPE: OnceLock<Option<CoreBPE>> = OnceLock::new();
+    BPE.get_or_init(|| tiktoken_rs::o200k_base().ok()).as_ref()
+}
+
+pub fn count_text(text: &str) -> usize {
+    match bpe() {
+        Some(bpe) => bpe.encode_with_special_tokens(text).len(),
+        // ~4 characters per token for English and code
+        None => text.len().div_ceil(4),
+    }
+}
+
+pub fn count_message(message: &Message) -> usize {
+    let content = match &message.content {
+        Some(MessageContent::Text(text)) => count_text(text),
+        Some(MessageContent::Parts(parts)) => parts
+            .iter()
+            .map(|p| match (&p.text, &p.image_url) {
+                (Some(text), _) => count_text(text),
+                (None, Some(_)) => IMAGE_TOKENS,
+                (None, None) => 0,
+            })
+            .sum(),
+        None => 0,
+    };
+
+    let tool_calls: usize = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .map(|c| count_text(&c.function.name) + count_text(&c.function.arguments))
+        .sum();
+
+    MESSAGE_OVERHEAD + content + tool_calls
+}
+
+pub fn count_messages(messages: &[Message]) -> usize {
+    messages.iter().map(count_message).sum()
+}
+
+/// Tool schemas are sent with every request and count against the window.
+pub fn count_tools(tools: &[serde_json::Value]) -> usize {
+    tools.iter().map(|t| count_text(&t.to_string())).sum()
+}
+
+/// Context window for a model, in tokens. `AXIOM_CONTEXT_WINDOW` overrides
+/// the built-in table (useful for local models with a custom `num_ctx`).
+pub fn context_window(model: &str) -> usize {
+    if let Some(window) = std::env::var("AXIOM_CONTEXT_WINDOW").ok().and_then(|v| v.parse().ok()) {
+        return window;
+    }
+
+    let model = model.to_lowercase();
+    if model.starts_with("gemini") {
+        1_000_000
+    } else if model.starts_with("claude") {
+        200_000
+    } else if model.starts_with("gpt-4.1") {
+        1_000_000
+    } else if model.starts_with("gpt-4o") || model.starts_with("gpt-4-turbo") || model.starts_with("o1") || model.starts_with("o3") || model.starts_with("o4") {
+        128_000
+    } else if model.starts_with("gpt-5") {
+        400_000
+    } else {
+        // Ollama's default num_ctx is small; be conservative for unknown models
+        8_192
+    }
+}
@@ -0,0 +1,160 @@
+//! Provider-neutral chat types.
+//!
+//! These follow the OpenAI chat-completions shape, which is what the agent loop
+//! and chat history are built around. Each provider converts to and from its
+//! own wire format.
+
+use serde::{Deserialize, Serialize};
+
+use super::usage::Usage;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Text(String),
+    Parts(Vec<ContentPart>),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContentPart {
+    pub r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<ImageUrl>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ImageUrl {
+    pub url: String,
+}
+
+impl ImageUrl {
+    /// Split a `data:<mime>;base64,<data>` URL into its MIME type and payload.
+    pub fn as_base64(&self) -> Option<(&str, &str)> {
+        let rest = self.url.trim().strip_prefix("data:")?;
+        let (mime, data) = rest.split_once(";base64,")?;
+        Some((mime, data))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Message {
+    pub role: String,
+    pub content: Option<MessageContent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+impl Message {
+    /// Plain-text view of the content, ignoring image parts.
+    pub fn text(&self) -> String {
+        match &self.content {
+            Some(MessageContent::Text(t)) => t.clone(),
+            Some(MessageContent::Parts(parts)) => parts
+                .iter()
+                .filter_map(|p| p.text.clone())
+                .collect::<Vec<_>>()
+                .join("\n"),
+            None => String::new(),
+        }
+    }
+}
+
+/// Per-profile generation settings. `None` leaves the provider default.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SamplingParams {
+    pub temperature: Option<f32>,
+    pub top_p: Option<f32>,
+    pub max_tokens: Option<u32>,
+    pub stop: Vec<String>,
+    pub reasoning_effort: Option<ReasoningEffort>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReasoningEffort::Low => "low",
+            ReasoningEffort::Medium => "medium",
+            ReasoningEffort::High => "high",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolCall {
+    pub id: String,
+    pub r#type: String,
+    pub function: FunctionCall,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FunctionCall {
+    pub name: String,
+    pub arguments: String,
+}
+
+// Stream types
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub enum StreamEvent {
+    TextChunk(String),
+    ToolCallChunk(StreamDeltaToolCall),
+    Usage(Usage),
+    Done,
+}
+
+/// JSON schema a structured completion must conform to.
+#[derive(Debug, Clone)]
+pub struct JsonSchemaFormat {
+    /// Identifier sent to the provider (`[a-zA-Z0-9_-]`).
+    pub name: String,
+    pub schema: serde_json::Value,
+}
+
+/// Result of a non-streaming completion.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatResponse {
+    pub message: Message,
+    pub usage: Option<Usage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamDeltaToolCall {
+    #[allow(dead_code)]
+    pub index: i32,
+    pub id: Option<String>,
+    pub r#type: Option<String>,
+    pub function: Option<StreamDeltaFunction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamDeltaFunction {
+    pub name: Option<String>,
+    pub arguments: Option<String>,
+}
+
+impl StreamDeltaToolCall {
+    /// A complete tool call delivered in one piece (providers that don't stream arguments).
+    pub fn complete(index: i32, id: String, name: String, arguments: String) -> Self {
+        Self {
+            index,
+            id: Some(id),
+            r#type: Some("function".to_string()),
+            function: Some(StreamDeltaFunction {
+                name: Some(name),
+                arguments: Some(arguments),
+            }),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Token usage and cost accounting.
+//!
+//! Providers report usage as `StreamEvent::Usage`; the UI thread feeds each
+//! report into a `UsageTracker`, which keeps session, per-profile and daily
+//! totals priced through a `PriceTable`. A `Budget` caps what the session and
+//! the day may cost.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Tokens reported for (part of) one request. Reports are additive: a
+/// provider may send input and output counts in separate events.
+/// `cached_input_tokens` is the part of `input_tokens` read from the
+/// provider's prompt cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+}
+
+impl Usage {
+    pub fn new(input_tokens: u64, output_tokens: u64) -> Self {
+        Self { input_tokens, output_tokens, cached_input_tokens: 0 }
+    }
+
+    pub fn with_cached(mut self, cached_input_tokens: u64) -> Self {
+        self.cached_input_tokens = cached_input_tokens.min(self.input_tokens);
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input_tokens == 0 && self.output_tokens == 0
+    }
+}
+
+/// USD per million tokens. `cached_input` is the price of prompt-cache reads;
+/// without it cached tokens are billed as regular input.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ModelPrice {
+    pub input: f64,
+    pub output: f64,
+    #[serde(default)]
+    pub cached_input: Option<f64>,
+}
+
+/// Prices keyed by model name prefix; the longest matching prefix wins.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PriceTable {
+    pub models: HashMap<String, ModelPrice>,
+}
+
+impl Default for PriceTable {
+    fn default() -> Self {
+        let models = [
+            ("gemini-2.5-pro", 1.25, 10.0, 0.31),
+            ("gemini-2.5-flash", 0.30, 2.50, 0.075),
+            ("gemini-2.0-flash", 0.10, 0.40, 0.025),
+            ("claude-opus", 15.0, 75.0, 1.50),
+            ("claude-sonnet", 3.0, 15.0, 0.30),
+            ("claude-3-5-haiku", 0.80, 4.0, 0.08),
+            ("gpt-4o-mini", 0.15, 0.60, 0.075),
+            ("gpt-4o", 2.50, 10.0, 1.25),
+            ("gpt-4.1-mini", 0.40, 1.60, 0.10),
+            ("gpt-4.1", 2.0, 8.0, 0.50),
+        ];
+        Self {
+            models: models
+                .into_iter()
+                .map(|(name, input, output, cached)| {
+                    (name.to_string(), ModelPrice { input, output, cached_input: Some(cached) })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl PriceTable {
+    /// Built-in prices overlaid with `<project>/.axiom/prices.json`, which has
+    /// the same shape: `{"models": {"my-model": {"input": 1.0, "output": 2.0}}}`.
+    pub fn load(root: &Path) -> Self {
+        let mut table = Self::default();
+        let path = root.join(".axiom").join("prices.json");
+        if let Ok(data) = std::fs::read_to_string(&path) {
+            match serde_json::from_str::<PriceTable>(&data) {
+                Ok(custom) => table.models.extend(custom.models),
+                Err(e) => tracing::warn!("Failed to parse {}: {}", path.display(), e),
+            }
+        }
+        table
+    }
+
+    pub fn price_for(&self, model: &str) -> Option<ModelPrice> {
+        self.models
+            .iter()
+            .filter(|(prefix, _)| model.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, price)| *price)
+    }
+
+    /// Cost in USD; unknown models (e.g. local ones) are free.
+    pub fn cost(&self, model: &str, usage: Usage) -> f64 {
+        self.price_for(model)
+            .map(|p| {
+                let cached = usage.cached_input_tokens as f64;
+                let uncached = (usage.input_tokens - usage.cached_input_tokens) as f64;
+                (uncached * p.input + cached * p.cached_input.unwrap_or(p.input) + usage.output_tokens as f64 * p.output)
+                    / 1_000_000.0
+            })
+            .unwrap_or(0.0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UsageTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cached_input_tokens: u64,
+    pub cost_usd: f64,
+}
+
+impl UsageTotals {
+    pub fn add(&mut self, usage: Usage, cost: f64) {
+        self.input_tokens += usage.input_tokens;
+        self.output_tokens += usage.output_tokens;
+        self.cached_input_tokens += usage.cached_input_tokens;
+        self.cost_usd += cost;
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+}
+
+/// What was spent on one calendar day, across sessions.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DailySpend {
+    pub date: chrono::NaiveDate,
+    pub cost_usd: f64,
+}
+
+impl Default for DailySpend {
+    fn default() -> Self {
+        Self { date: chrono::Local::now().date_naive(), cost_usd: 0.0 }
+    }
+}
+
+impl DailySpend {
+    /// Add `cost` on `date`, starting over on a new day.
+    pub fn add(&mut self, date: chrono::NaiveDate, cost: f64) {
+        if date != self.date {
+            *self = Self { date, cost_usd: 0.0 };
+        }
+        self.cost_usd += cost;
+    }
+
+    /// Spent on `date`; zero if this is an earlier day's total.
+    pub fn on(&self, date: chrono::NaiveDate) -> f64 {
+        if date == self.date { self.cost_usd } else { 0.0 }
+    }
+}
+
+/// Spending limits in USD; `None` is unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Budget {
+    pub session_usd: Option<f64>,
+    pub daily_usd: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BudgetStatus {
+    Ok,
+    /// Past [`Budget::WARN_AT`] of a limit.
+    Warning(String),
+    /// A limit is reached; further requests need the user's go-ahead.
+    Exceeded(String),
+}
+
+impl Budget {
+    /// Share of a limit at which to warn.
+    pub const WARN_AT: f64 = 0.8;
+
+    pub fn check(&self, session_usd: f64, today_usd: f64) -> BudgetStatus {
+        let limits = [("session", self.session_usd, session_usd), ("daily", self.daily_usd, today_usd)];
+        let mut status = BudgetStatus::Ok;
+        for (name, limit, spent) in limits {
+            let Some(limit) = limit.filter(|l| *l > 0.0) else {
+                continue;
+            };
+            let text = format!("${:.2} of the ${:.2} {} budget spent", spent, limit, name);
+            if spent >= limit {
+                return BudgetStatus::Exceeded(text);
+            }
+            if spent >= limit * Self::WARN_AT && status == BudgetStatus::Ok {
+                status = BudgetStatus::Warning(text);
+            }
+        }
+        status
+    }
+}
+
+pub struct UsageTracker {
+    prices: PriceTable,
+    pub session: UsageTotals,
+    pub per_profile: HashMap<String, UsageTotals>,
+    pub today: DailySpend,
+}
+
+impl UsageTracker {
+    pub fn new(prices: PriceTable) -> Self {
+        Self {
+            prices,
+            session: UsageTotals::default(),
+            per_profile: HashMap::new(),
+            today: DailySpend::default(),
+        }
+    }
+
+    /// Continue from the day's spending so far, e.g. from an earlier session.
+    pub fn with_today(mut self, today: DailySpend) -> Self {
+        self.today = today;
+        self
+    }
+
+    /// Returns the cost of this report.
+    pub fn record(&mut self, profile: &str, model: &str, usage: Usage) -> f64 {
+        let cost = self.prices.cost(model, usage);
+        self.session.add(usage, cost);
+        self.per_profile.entry(profile.to_string()).or_default().add(usage, cost);
+        self.today.add(chrono::Local::now().date_naive(), cost);
+        cost
+    }
+
+    pub fn budget_status(&self, budget: &Budget) -> BudgetStatus {
+        budget.check(self.session.cost_usd, self.today.on(chrono::Local::now().date_naive()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_warns_then_blocks() {
+        let budget = Budget { session_usd: Some(1.0), daily_usd: Some(5.0) };
+        assert_eq!(budget.check(0.5, 0.5), BudgetStatus::Ok);
+        assert!(matches!(budget.check(0.85, 0.85), BudgetStatus::Warning(text) if text.contains("session")));
+        assert!(matches!(budget.check(0.1, 4.5), BudgetStatus::Warning(text) if text.contains("daily")));
+        // Any exceeded limit wins over a warning
+        assert!(matches!(budget.check(0.9, 5.0), BudgetStatus::Exceeded(text) if text.contains("daily")));
+        assert!(matches!(budget.check(1.0, 1.0), BudgetStatus::Exceeded(text) if text.contains("session")));
+        assert_eq!(Budget::default().check(100.0, 100.0), BudgetStatus::Ok);
+    }
+
+    #[test]
+    fn daily_spend_starts_over_each_day() {
+        let monday = chrono::NaiveDate::from_ymd_opt(2025, 3, 3).unwrap();
+        let tuesday = monday.succ_opt().unwrap();
+        let mut spend = DailySpend { date: monday, cost_usd: 0.0 };
+        spend.add(monday, 1.5);
+        spend.add(monday, 0.5);
+        assert_eq!(spend.on(monday), 2.0);
+        assert_eq!(spend.on(tuesday), 0.0);
+        spend.add(tuesday, 0.25);
+        assert_eq!(spend, DailySpend { date: tuesday, cost_usd: 0.25 });
+    }
+}
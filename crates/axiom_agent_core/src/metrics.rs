@@ -0,0 +1,202 @@
+//! Opt-in timings of the editor's own work: tool calls, model requests and
+//! BRP calls, so regressions show up as numbers instead of impressions.
+//!
+//! Nothing is recorded until the host calls [`set_enabled`], and nothing
+//! leaves the process unless it exports a [`snapshot`] itself.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Tool executions, by tool name.
+pub const TOOL: &str = "tool";
+/// Whole streamed model requests, by model.
+pub const LLM: &str = "llm";
+/// Time until the first streamed event, by model.
+pub const LLM_FIRST_TOKEN: &str = "llm_first_token";
+/// BRP requests, by method.
+pub const BRP: &str = "brp";
+
+/// Samples kept per metric for percentiles.
+const RECENT_SAMPLES: usize = 200;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry { stats: BTreeMap::new() });
+static STARTED: OnceLock<(Instant, DateTime<Utc>)> = OnceLock::new();
+
+/// Aggregates for one category and name.
+#[derive(Default)]
+struct Stat {
+    count: u64,
+    errors: u64,
+    total: Duration,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+struct Registry {
+    stats: BTreeMap<(String, String), Stat>,
+}
+
+impl Registry {
+    fn record(&mut self, category: &str, name: &str, elapsed: Duration, ok: bool) {
+        let stat = self.stats.entry((category.to_string(), name.to_string())).or_default();
+        stat.count += 1;
+        if !ok {
+            stat.errors += 1;
+        }
+        stat.total += elapsed;
+        stat.max = stat.max.max(elapsed);
+        if stat.recent.len() == RECENT_SAMPLES {
+            stat.recent.pop_front();
+        }
+        stat.recent.push_back(elapsed);
+    }
+
+    fn metrics(&self) -> Vec<Metric> {
+        self.stats
+            .iter()
+            .map(|((category, name), stat)| {
+                let mut recent: Vec<Duration> = stat.recent.iter().copied().collect();
+                recent.sort();
+                let percentile = |p: f64| {
+                    let index = ((recent.len() as f64 - 1.0) * p).round() as usize;
+                    recent.get(index).map_or(0.0, |d| millis(*d))
+                };
+                Metric {
+                    category: category.clone(),
+                    name: name.clone(),
+                    count: stat.count,
+                    errors: stat.errors,
+                    mean_ms: millis(stat.total) / stat.count.max(1) as f64,
+                    p50_ms: percentile(0.5),
+                    p95_ms: percentile(0.95),
+                    max_ms: millis(stat.max),
+                }
+            })
+            .collect()
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Metric {
+    pub category: String,
+    pub name: String,
+    pub count: u64,
+    pub errors: u64,
+    pub mean_ms: f64,
+    /// Over the most recent samples.
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+impl Metric {
+    pub fn error_rate(&self) -> f64 {
+        self.errors as f64 / self.count.max(1) as f64
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub session_started: DateTime<Utc>,
+    pub session_secs: u64,
+    pub taken_at: DateTime<Utc>,
+    pub metrics: Vec<Metric>,
+}
+
+/// Start or stop recording. The session clock starts at the first call.
+pub fn set_enabled(enabled: bool) {
+    STARTED.get_or_init(|| (Instant::now(), Utc::now()));
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Add one sample; a no-op while recording is off.
+pub fn record(category: &str, name: &str, elapsed: Duration, ok: bool) {
+    if is_enabled() {
+        REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).record(category, name, elapsed, ok);
+    }
+}
+
+/// Run `f` and record how long it took and whether it returned `Ok`.
+pub fn time<T, E>(category: &str, name: &str, f: impl FnOnce() -> Result<T, E>) -> Result<T, E> {
+    if !is_enabled() {
+        return f();
+    }
+    let started = Instant::now();
+    let result = f();
+    record(category, name, started.elapsed(), result.is_ok());
+    result
+}
+
+/// Everything recorded so far, by category and name.
+pub fn snapshot() -> Snapshot {
+    let (started, session_started) = *STARTED.get_or_init(|| (Instant::now(), Utc::now()));
+    Snapshot {
+        session_started,
+        session_secs: started.elapsed().as_secs(),
+        taken_at: Utc::now(),
+        metrics: REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).metrics(),
+    }
+}
+
+/// Drop every sample; the session clock keeps running.
+pub fn reset() {
+    REGISTRY.lock().unwrap_or_else(|e| e.into_inner()).stats.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_per_category_and_name() {
+        let mut registry = Registry { stats: BTreeMap::new() };
+        for ms in 1..=100 {
+            registry.record(TOOL, "read_file", Duration::from_millis(ms), ms % 10 != 0);
+        }
+        registry.record(BRP, "world.query", Duration::from_millis(5), true);
+
+        let metrics = registry.metrics();
+        assert_eq!(metrics.len(), 2);
+        let brp = &metrics[0];
+        assert_eq!((brp.category.as_str(), brp.name.as_str(), brp.count), (BRP, "world.query", 1));
+        assert_eq!(brp.p95_ms, 5.0);
+
+        let tool = &metrics[1];
+        assert_eq!(tool.count, 100);
+        assert_eq!(tool.errors, 10);
+        assert_eq!(tool.error_rate(), 0.1);
+        assert_eq!(tool.mean_ms, 50.5);
+        assert_eq!(tool.p50_ms, 51.0);
+        assert_eq!(tool.p95_ms, 95.0);
+        assert_eq!(tool.max_ms, 100.0);
+    }
+
+    #[test]
+    fn percentiles_use_recent_samples() {
+        let mut registry = Registry { stats: BTreeMap::new() };
+        for _ in 0..RECENT_SAMPLES {
+            registry.record(LLM, "gpt-4o", Duration::from_secs(10), true);
+        }
+        for _ in 0..RECENT_SAMPLES {
+            registry.record(LLM, "gpt-4o", Duration::from_secs(1), true);
+        }
+
+        let metric = &registry.metrics()[0];
+        assert_eq!(metric.count, 2 * RECENT_SAMPLES as u64);
+        assert_eq!(metric.p95_ms, 1000.0);
+        assert_eq!(metric.max_ms, 10_000.0);
+    }
+}
@@ -0,0 +1,318 @@
+//! Tools contributed by external programs.
+//!
+//! A plugin is a JSON manifest naming a command and the tools it provides,
+//! so a tool can be written in any language without touching the host. Each
+//! call starts the command with the manifest's `args`, writes one JSON request
+//! to its stdin and reads one JSON response from its stdout:
+//!
+//! ```text
+//! → {"tool": "greet", "arguments": {"name": "Ferris"}}
+//! ← {"output": "Hello, Ferris"}        or        {"error": "..."}
+//! ```
+//!
+//! The process runs in the host's working directory. A relative `command`
+//! containing a path separator is resolved against the manifest's folder;
+//! a bare name is looked up on `PATH`.
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::tool::Tool;
+
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginManifest {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Per call; the process is killed after this.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    pub tools: Vec<PluginToolSpec>,
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PluginToolSpec {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the arguments.
+    #[serde(default = "empty_parameters")]
+    pub parameters: Value,
+}
+
+fn empty_parameters() -> Value {
+    json!({ "type": "object", "properties": {} })
+}
+
+#[derive(Deserialize)]
+struct PluginResponse {
+    output: Option<String>,
+    error: Option<String>,
+}
+
+impl PluginManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let mut manifest: Self =
+            serde_json::from_str(&data).with_context(|| format!("Failed to parse {}", path.display()))?;
+        if manifest.tools.is_empty() {
+            bail!("{} declares no tools", path.display());
+        }
+        let command = Path::new(&manifest.command);
+        if command.is_relative() && command.components().count() > 1 {
+            if let Some(dir) = path.parent() {
+                manifest.command = dir.join(command).to_string_lossy().to_string();
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// One tool per entry in `tools`.
+    pub fn into_tools(self: Arc<Self>) -> Vec<Box<dyn Tool>> {
+        (0..self.tools.len())
+            .map(|index| Box::new(PluginTool { plugin: self.clone(), index }) as Box<dyn Tool>)
+            .collect()
+    }
+
+    fn call(&self, tool: &str, arguments: Value) -> Result<String> {
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start plugin `{}` ({})", self.name, self.command))?;
+
+        let request = json!({ "tool": tool, "arguments": arguments });
+        if let Some(mut stdin) = child.stdin.take() {
+            // A plugin that exits without reading is reported by its response
+            let _ = writeln!(stdin, "{}", request);
+        }
+        // Drained on threads so a chatty plugin can't block on a full pipe
+        let stdout = read_on_thread(child.stdout.take());
+        let stderr = read_on_thread(child.stderr.take());
+
+        let deadline = Instant::now() + Duration::from_secs(self.timeout_secs);
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                bail!("Plugin `{}` timed out after {}s", self.name, self.timeout_secs);
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        };
+        let stdout = stdout.join().unwrap_or_default();
+        let stderr = stderr.join().unwrap_or_default();
+
+        let response = stdout.lines().rev().find_map(|line| serde_json::from_str::<PluginResponse>(line).ok());
+        match response {
+            Some(PluginResponse { error: Some(error), .. }) => Err(anyhow!(error)),
+            Some(PluginResponse { output: Some(output), .. }) => Ok(output),
+            _ if !status.success() => Err(anyhow!("Plugin `{}` failed ({}): {}", self.name, status, stderr.trim())),
+            _ => Err(anyhow!("Plugin `{}` returned no response for `{}`", self.name, tool)),
+        }
+    }
+}
+
+fn read_on_thread(pipe: Option<impl Read + Send + 'static>) -> std::thread::JoinHandle<String> {
+    std::thread::spawn(move || {
+        let mut text = String::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_string(&mut text);
+        }
+        text
+    })
+}
+
+/// Manifests (`*.json`) in `dirs`, in order. Invalid ones are logged and
+/// skipped, as are tools whose name an earlier plugin already took.
+pub fn discover(dirs: &[PathBuf]) -> Vec<Arc<PluginManifest>> {
+    let mut plugins: Vec<Arc<PluginManifest>> = Vec::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let mut manifest = match PluginManifest::load(&path) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    tracing::warn!("Skipping plugin: {:#}", e);
+                    continue;
+                }
+            };
+            manifest.tools.retain(|spec| {
+                let taken = plugins.iter().flat_map(|p| &p.tools).any(|t| t.name == spec.name);
+                if taken {
+                    tracing::warn!("Plugin `{}`: tool `{}` is already provided by another plugin", manifest.name, spec.name);
+                }
+                !taken
+            });
+            if manifest.tools.is_empty() {
+                continue;
+            }
+            tracing::info!("Loaded plugin `{}` with {} tool(s) from {}", manifest.name, manifest.tools.len(), path.display());
+            plugins.push(Arc::new(manifest));
+        }
+    }
+    plugins
+}
+
+struct PluginTool {
+    plugin: Arc<PluginManifest>,
+    index: usize,
+}
+
+impl PluginTool {
+    fn spec(&self) -> &PluginToolSpec {
+        &self.plugin.tools[self.index]
+    }
+}
+
+impl Tool for PluginTool {
+    fn name(&self) -> String {
+        self.spec().name.clone()
+    }
+
+    fn description(&self) -> String {
+        self.spec().description.clone()
+    }
+
+    fn schema(&self) -> Value {
+        let spec = self.spec();
+        json!({
+            "type": "function",
+            "function": {
+                "name": spec.name,
+                "description": spec.description,
+                "parameters": spec.parameters
+            }
+        })
+    }
+
+    fn execute(&self, args: Value) -> Result<String> {
+        self.plugin.call(&self.spec().name, args)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_manifest(dir: &Path, file: &str, manifest: Value) -> PathBuf {
+        std::fs::create_dir_all(dir).unwrap();
+        let path = dir.join(file);
+        std::fs::write(&path, manifest.to_string()).unwrap();
+        path
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("axiom_plugin_{}_{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn shell_plugin(script: &str, timeout_secs: u64) -> Arc<PluginManifest> {
+        Arc::new(PluginManifest {
+            name: "test".to_string(),
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            timeout_secs,
+            tools: vec![PluginToolSpec {
+                name: "echo".to_string(),
+                description: "Echo".to_string(),
+                parameters: empty_parameters(),
+            }],
+        })
+    }
+
+    #[test]
+    fn load_resolves_relative_commands_against_the_manifest() {
+        let dir = temp_dir("load");
+        let path = write_manifest(
+            &dir,
+            "tools.json",
+            json!({
+                "name": "tools",
+                "command": "./bin/tools",
+                "tools": [{ "name": "greet", "description": "Say hello" }]
+            }),
+        );
+
+        let manifest = PluginManifest::load(&path).unwrap();
+        assert_eq!(Path::new(&manifest.command), dir.join("./bin/tools"));
+        assert_eq!(manifest.timeout_secs, DEFAULT_TIMEOUT_SECS);
+
+        let tools = Arc::new(manifest).into_tools();
+        assert_eq!(tools[0].name(), "greet");
+        assert_eq!(tools[0].schema()["function"]["parameters"]["type"], "object");
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn discover_skips_invalid_manifests_and_duplicate_tools() {
+        let dir = temp_dir("discover");
+        let tool = json!([{ "name": "greet", "description": "Say hello" }]);
+        write_manifest(&dir, "a.json", json!({ "name": "a", "command": "a", "tools": tool }));
+        write_manifest(&dir, "b.json", json!({ "name": "b", "command": "b", "tools": tool }));
+        write_manifest(&dir, "c.json", json!({ "name": "c", "tools": tool }));
+        std::fs::write(dir.join("notes.txt"), "not a manifest").unwrap();
+
+        let plugins = discover(&[dir.clone(), dir.join("missing")]);
+        let names: Vec<_> = plugins.iter().map(|p| (p.name.as_str(), p.tools.len())).collect();
+        assert_eq!(names, vec![("a", 1)]);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn call_round_trips_json() {
+        let script = r#"read request; echo "log line"
+            case "$request" in *'"tool":"echo"'*) ;; *) echo '{"error":"wrong tool"}'; exit ;; esac
+            case "$request" in *'"x":1'*) echo '{"output":"got x"}' ;; *) echo '{"error":"no x"}' ;; esac"#;
+        let plugin = shell_plugin(script, 10);
+        let tool = &plugin.into_tools()[0];
+        assert_eq!(tool.execute(json!({ "x": 1 })).unwrap(), "got x");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn call_reports_plugin_errors() {
+        let plugin = shell_plugin(r#"read request; echo '{"error":"bad input"}'"#, 10);
+        let err = plugin.call("echo", json!({})).unwrap_err();
+        assert_eq!(err.to_string(), "bad input");
+
+        let plugin = shell_plugin("echo boom >&2; exit 3", 10);
+        let err = plugin.call("echo", json!({})).unwrap_err();
+        assert!(err.to_string().contains("boom"), "{}", err);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn call_times_out() {
+        let plugin = shell_plugin("sleep 5", 1);
+        let err = plugin.call("echo", json!({})).unwrap_err();
+        assert!(err.to_string().contains("timed out"), "{}", err);
+    }
+}
@@ -0,0 +1,486 @@
+//! The agent loop: stream a completion, run the tool calls it asks for, feed
+//! the results back, and repeat until the model answers without tools.
+//!
+//! `AgentRuntime::run` is headless: it works on a plain `Vec<Message>` and
+//! reports progress through a callback, leaving rendering, logging and
+//! persistence to the host.
+
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::history::HistoryManager;
+use crate::llm::usage::Usage;
+use crate::llm::{FunctionCall, LlmProvider, Message, MessageContent, StreamEvent, ToolCall};
+use crate::metrics;
+use crate::tool::{execute_reviewed, EditRejected, EditReviewer, InvalidArguments, Tool};
+
+/// Default cap on model requests per `run`.
+pub const DEFAULT_MAX_TURNS: usize = 50;
+
+/// Progress reported while the loop runs.
+#[derive(Debug, Clone)]
+pub enum AgentEvent {
+    /// Streamed assistant text.
+    Text(String),
+    /// Token usage reported by the provider for the current request.
+    Usage(Usage),
+    /// The history was trimmed to fit the context window.
+    ContextTrimmed { sent: usize, total: usize },
+    /// A tool call is about to run.
+    ToolCall { name: String, arguments: String },
+    /// A tool call finished; `output` is what the model will see.
+    ToolResult { name: String, output: String },
+    /// The stream reported an error; the turn continues with what arrived.
+    StreamError(String),
+}
+
+/// Accumulates one streamed assistant turn: text plus tool calls, whose
+/// fragments arrive keyed by index.
+#[derive(Default)]
+pub struct TurnBuilder {
+    text: String,
+    tool_calls: HashMap<i32, ToolCallBuilder>,
+}
+
+struct ToolCallBuilder {
+    id: Option<String>,
+    r#type: String,
+    name: Option<String>,
+    args: String,
+}
+
+impl TurnBuilder {
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    pub fn push_event(&mut self, event: &StreamEvent) {
+        match event {
+            StreamEvent::TextChunk(text) => self.push_text(text),
+            StreamEvent::ToolCallChunk(tc) => {
+                let entry = self.tool_calls.entry(tc.index).or_insert(ToolCallBuilder {
+                    id: None,
+                    r#type: "function".to_string(),
+                    name: None,
+                    args: String::new(),
+                });
+
+                if let Some(id) = &tc.id {
+                    entry.id = Some(id.clone());
+                }
+                if let Some(t) = &tc.r#type {
+                    entry.r#type = t.clone();
+                }
+                if let Some(f) = &tc.function {
+                    if let Some(n) = &f.name {
+                        entry.name.get_or_insert_with(String::new).push_str(n);
+                    }
+                    if let Some(a) = &f.arguments {
+                        entry.args.push_str(a);
+                    }
+                }
+            }
+            StreamEvent::Usage(_) | StreamEvent::Done => {}
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Completed tool calls in index order. Fragments that never got a name
+    /// are dropped.
+    pub fn tool_calls(&self) -> Vec<ToolCall> {
+        let mut indices: Vec<i32> = self.tool_calls.keys().cloned().collect();
+        indices.sort();
+
+        indices
+            .into_iter()
+            .filter_map(|idx| {
+                let builder = &self.tool_calls[&idx];
+                let name = builder.name.clone()?;
+                Some(ToolCall {
+                    id: builder.id.clone().unwrap_or_else(|| format!("call_{}", idx)),
+                    r#type: builder.r#type.clone(),
+                    function: FunctionCall {
+                        name,
+                        // Anthropic streams no input fragments for argument-less tools
+                        arguments: if builder.args.is_empty() { "{}".to_string() } else { builder.args.clone() },
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+pub struct AgentRuntime {
+    provider: Arc<dyn LlmProvider>,
+    tools: Vec<Box<dyn Tool>>,
+    reviewer: Option<Arc<dyn EditReviewer>>,
+    history: HistoryManager,
+    max_turns: usize,
+}
+
+impl AgentRuntime {
+    pub fn new(provider: Arc<dyn LlmProvider>, tools: Vec<Box<dyn Tool>>) -> Self {
+        let history = HistoryManager::for_model(provider.model());
+        Self {
+            provider,
+            tools,
+            reviewer: None,
+            history,
+            max_turns: DEFAULT_MAX_TURNS,
+        }
+    }
+
+    pub fn with_max_turns(mut self, max_turns: usize) -> Self {
+        self.max_turns = max_turns;
+        self
+    }
+
+    /// Ask `reviewer` before any tool modifies a file.
+    pub fn with_reviewer(mut self, reviewer: Arc<dyn EditReviewer>) -> Self {
+        self.reviewer = Some(reviewer);
+        self
+    }
+
+    pub fn provider(&self) -> &Arc<dyn LlmProvider> {
+        &self.provider
+    }
+
+    /// Tool schemas sent with each request, or `None` if the provider can't use them.
+    pub fn tool_schemas(&self) -> Option<Vec<Value>> {
+        if self.provider.supports_tools() {
+            Some(self.tools.iter().map(|t| t.schema()).collect())
+        } else {
+            None
+        }
+    }
+
+    /// Run the loop on `messages` until the model answers without tool calls.
+    /// Assistant turns and tool results are appended to `messages`, so the
+    /// caller holds the full transcript afterwards, also on error.
+    pub async fn run<F>(&self, messages: &mut Vec<Message>, mut on_event: F) -> Result<()>
+    where
+        F: FnMut(AgentEvent),
+    {
+        let tools_schema = self.tool_schemas();
+        let tools_tokens = tools_schema.as_deref().map(crate::llm::tokens::count_tools).unwrap_or(0);
+
+        for _ in 0..self.max_turns {
+            let request_messages = self.history.fit(messages, tools_tokens);
+            if request_messages.len() != messages.len() {
+                on_event(AgentEvent::ContextTrimmed {
+                    sent: request_messages.len(),
+                    total: messages.len(),
+                });
+            }
+
+            let started = Instant::now();
+            let model = self.provider.model().to_string();
+            let mut stream = match self.provider.chat_stream(request_messages, tools_schema.clone()).await {
+                Ok(stream) => stream,
+                Err(e) => {
+                    metrics::record(metrics::LLM, &model, started.elapsed(), false);
+                    return Err(e);
+                }
+            };
+            let mut turn = TurnBuilder::default();
+            let mut first_event = true;
+            let mut stream_ok = true;
+            while let Some(result) = stream.next().await {
+                if first_event {
+                    first_event = false;
+                    metrics::record(metrics::LLM_FIRST_TOKEN, &model, started.elapsed(), result.is_ok());
+                }
+                stream_ok &= result.is_ok();
+                match result {
+                    Ok(StreamEvent::TextChunk(text)) => {
+                        turn.push_text(&text);
+                        on_event(AgentEvent::Text(text));
+                    }
+                    Ok(StreamEvent::Usage(usage)) => on_event(AgentEvent::Usage(usage)),
+                    Ok(event) => turn.push_event(&event),
+                    Err(e) => on_event(AgentEvent::StreamError(e.to_string())),
+                }
+            }
+            metrics::record(metrics::LLM, &model, started.elapsed(), stream_ok);
+
+            let tool_calls = turn.tool_calls();
+            let text = (!turn.text().is_empty()).then(|| MessageContent::Text(turn.text().to_string()));
+
+            if tool_calls.is_empty() {
+                if text.is_some() {
+                    messages.push(Message {
+                        role: "assistant".to_string(),
+                        content: text,
+                        tool_calls: None,
+                        tool_call_id: None,
+                    });
+                }
+                return Ok(());
+            }
+
+            messages.push(Message {
+                role: "assistant".to_string(),
+                content: text,
+                tool_calls: Some(tool_calls.clone()),
+                tool_call_id: None,
+            });
+
+            for tool_call in tool_calls {
+                on_event(AgentEvent::ToolCall {
+                    name: tool_call.function.name.clone(),
+                    arguments: tool_call.function.arguments.clone(),
+                });
+
+                let output = self.execute(&tool_call.function);
+                on_event(AgentEvent::ToolResult {
+                    name: tool_call.function.name.clone(),
+                    output: output.clone(),
+                });
+
+                messages.push(Message {
+                    role: "tool".to_string(),
+                    content: Some(MessageContent::Text(output)),
+                    tool_calls: None,
+                    tool_call_id: Some(tool_call.id),
+                });
+            }
+        }
+
+        Err(anyhow!("Max turns exceeded"))
+    }
+
+    /// Run one tool call. Failures become the result text so the model can react.
+    fn execute(&self, call: &FunctionCall) -> String {
+        tracing::info!(target: "tool", "{} {}", call.name, call.arguments);
+        let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) else {
+            tracing::warn!(target: "tool", "{}: no such tool", call.name);
+            return format!("Error: Tool '{}' not found", call.name);
+        };
+        let result = serde_json::from_str::<Value>(&call.arguments)
+            .map_err(|e| format!("Error parsing arguments JSON: {}", e))
+            .and_then(|args| match execute_reviewed(tool.as_ref(), args, self.reviewer.as_deref()) {
+                Ok(output) => Ok(output),
+                Err(e) if e.is::<InvalidArguments>() || e.is::<EditRejected>() => Err(e.to_string()),
+                Err(e) => Err(format!("Error executing tool: {}", e)),
+            });
+        match result {
+            Ok(output) => {
+                tracing::info!(target: "tool", "{} returned {} bytes", call.name, output.len());
+                output
+            }
+            Err(error) => {
+                tracing::warn!(target: "tool", "{}: {}", call.name, error);
+                error
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::provider::{EventStream, ProviderKind};
+    use crate::llm::types::{ChatResponse, JsonSchemaFormat, StreamDeltaToolCall};
+    use async_trait::async_trait;
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Answers each `chat_stream` with the next scripted list of events.
+    struct ScriptedProvider {
+        turns: Mutex<Vec<Vec<StreamEvent>>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(mut turns: Vec<Vec<StreamEvent>>) -> Arc<Self> {
+            turns.reverse();
+            Arc::new(Self { turns: Mutex::new(turns) })
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedProvider {
+        fn kind(&self) -> ProviderKind {
+            ProviderKind::OpenAiCompatible
+        }
+
+        fn model(&self) -> &str {
+            "scripted"
+        }
+
+        async fn chat(&self, _messages: Vec<Message>, _tools: Option<Vec<Value>>) -> Result<ChatResponse> {
+            Err(anyhow!("not scripted"))
+        }
+
+        async fn chat_structured(&self, _messages: Vec<Message>, _format: &JsonSchemaFormat) -> Result<ChatResponse> {
+            Err(anyhow!("not scripted"))
+        }
+
+        async fn chat_stream(&self, _messages: Vec<Message>, _tools: Option<Vec<Value>>) -> Result<EventStream> {
+            let events = self.turns.lock().unwrap().pop().ok_or_else(|| anyhow!("script exhausted"))?;
+            Ok(Box::pin(futures_util::stream::iter(events.into_iter().map(Ok))))
+        }
+    }
+
+    struct EchoTool;
+
+    impl Tool for EchoTool {
+        fn name(&self) -> String {
+            "echo".to_string()
+        }
+        fn description(&self) -> String {
+            "Echo the text back.".to_string()
+        }
+        fn schema(&self) -> Value {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": "echo",
+                    "description": "Echo the text back.",
+                    "parameters": {
+                        "type": "object",
+                        "properties": { "text": { "type": "string" } },
+                        "required": ["text"]
+                    }
+                }
+            })
+        }
+        fn execute(&self, args: Value) -> Result<String> {
+            Ok(args["text"].as_str().unwrap_or_default().to_string())
+        }
+    }
+
+    fn user(text: &str) -> Message {
+        Message {
+            role: "user".to_string(),
+            content: Some(MessageContent::Text(text.to_string())),
+            tool_calls: None,
+            tool_call_id: None,
+        }
+    }
+
+    fn tool_call_chunk(index: i32, id: Option<&str>, name: Option<&str>, args: &str) -> StreamEvent {
+        StreamEvent::ToolCallChunk(StreamDeltaToolCall {
+            index,
+            id: id.map(str::to_string),
+            r#type: None,
+            function: Some(crate::llm::types::StreamDeltaFunction {
+                name: name.map(str::to_string),
+                arguments: Some(args.to_string()),
+            }),
+        })
+    }
+
+    #[test]
+    fn test_turn_builder_joins_fragments() {
+        let mut turn = TurnBuilder::default();
+        turn.push_event(&tool_call_chunk(1, Some("b"), Some("echo"), ""));
+        turn.push_event(&tool_call_chunk(0, Some("a"), Some("echo"), "{\"text\":"));
+        turn.push_event(&tool_call_chunk(0, None, None, "\"hi\"}"));
+        turn.push_event(&StreamEvent::TextChunk("ok".to_string()));
+
+        let calls = turn.tool_calls();
+        assert_eq!(turn.text(), "ok");
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].id, "a");
+        assert_eq!(calls[0].function.arguments, "{\"text\":\"hi\"}");
+        assert_eq!(calls[1].function.arguments, "{}");
+    }
+
+    #[tokio::test]
+    async fn test_run_executes_tools_until_answer() {
+        let provider = ScriptedProvider::new(vec![
+            vec![tool_call_chunk(0, Some("call_1"), Some("echo"), "{\"text\":\"pong\"}"), StreamEvent::Done],
+            vec![StreamEvent::TextChunk("done".to_string()), StreamEvent::Done],
+        ]);
+        let runtime = AgentRuntime::new(provider, vec![Box::new(EchoTool)]);
+
+        let mut messages = vec![user("ping")];
+        let mut events = Vec::new();
+        runtime.run(&mut messages, |e| events.push(e)).await.unwrap();
+
+        let roles: Vec<&str> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "tool", "assistant"]);
+        assert_eq!(messages[2].text(), "pong");
+        assert_eq!(messages[3].text(), "done");
+        assert!(events.iter().any(|e| matches!(e, AgentEvent::ToolCall { name, .. } if name == "echo")));
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_invalid_arguments_to_model() {
+        let provider = ScriptedProvider::new(vec![
+            vec![tool_call_chunk(0, Some("call_1"), Some("echo"), "{\"text\":3}")],
+            vec![StreamEvent::TextChunk("sorry".to_string())],
+        ]);
+        let runtime = AgentRuntime::new(provider, vec![Box::new(EchoTool)]);
+
+        let mut messages = vec![user("ping")];
+        runtime.run(&mut messages, |_| {}).await.unwrap();
+
+        assert!(messages[2].text().starts_with("Invalid arguments for `echo`"));
+    }
+
+    struct RejectAll;
+
+    impl EditReviewer for RejectAll {
+        fn review(&self, _tool: &str, _edit: &crate::tool::FileEdit) -> bool {
+            false
+        }
+    }
+
+    /// Echo that pretends to write its text to `out.txt`.
+    struct EditingEcho;
+
+    impl Tool for EditingEcho {
+        fn name(&self) -> String {
+            EchoTool.name()
+        }
+        fn description(&self) -> String {
+            EchoTool.description()
+        }
+        fn schema(&self) -> Value {
+            EchoTool.schema()
+        }
+        fn execute(&self, args: Value) -> Result<String> {
+            EchoTool.execute(args)
+        }
+        fn preview_edit(&self, args: &Value) -> Option<Result<crate::tool::FileEdit>> {
+            Some(Ok(crate::tool::FileEdit {
+                path: "out.txt".to_string(),
+                before: String::new(),
+                after: args["text"].as_str()?.to_string(),
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_rejected_edit_to_model() {
+        let provider = ScriptedProvider::new(vec![
+            vec![tool_call_chunk(0, Some("call_1"), Some("echo"), "{\"text\":\"pong\"}")],
+            vec![StreamEvent::TextChunk("ok".to_string())],
+        ]);
+        let runtime = AgentRuntime::new(provider, vec![Box::new(EditingEcho)]).with_reviewer(Arc::new(RejectAll));
+
+        let mut messages = vec![user("ping")];
+        runtime.run(&mut messages, |_| {}).await.unwrap();
+
+        assert!(messages[2].text().starts_with("The user rejected the edit to out.txt"));
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_at_max_turns() {
+        let looping = || vec![tool_call_chunk(0, Some("call"), Some("echo"), "{\"text\":\"again\"}")];
+        let provider = ScriptedProvider::new(vec![looping(), looping(), looping()]);
+        let runtime = AgentRuntime::new(provider, vec![Box::new(EchoTool)]).with_max_turns(2);
+
+        let mut messages = vec![user("ping")];
+        let err = runtime.run(&mut messages, |_| {}).await.unwrap_err();
+        assert_eq!(err.to_string(), "Max turns exceeded");
+    }
+}
@@ -0,0 +1,124 @@
+//! The tool interface the agent loop calls into.
+//!
+//! Tools are synchronous and receive their arguments as JSON; the loop runs
+//! them through `execute_checked` so a malformed call comes back to the model
+//! as a correctable error instead of reaching the tool. Tools that modify
+//! files can describe the change up front (`preview_edit`) so a host-provided
+//! `EditReviewer` can approve or reject it first.
+
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::fmt;
+
+use crate::llm::json_repair;
+
+pub trait Tool: Send + Sync {
+    fn name(&self) -> String;
+    #[allow(dead_code)]
+    fn description(&self) -> String;
+    fn schema(&self) -> Value;
+    fn execute(&self, args: Value) -> Result<String>;
+
+    /// The file change `args` would make, for tools that modify files. An
+    /// `Err` (e.g. `old_string` not found) is reported without asking.
+    fn preview_edit(&self, args: &Value) -> Option<Result<FileEdit>> {
+        let _ = args;
+        None
+    }
+}
+
+/// A file change proposed by a tool, as full before/after contents.
+#[derive(Debug, Clone)]
+pub struct FileEdit {
+    pub path: String,
+    /// Empty when the file doesn't exist yet.
+    pub before: String,
+    pub after: String,
+}
+
+/// Decides whether a proposed file edit may be applied. May block (e.g.
+/// waiting for the user); it is called from the thread running the tool.
+pub trait EditReviewer: Send + Sync {
+    fn review(&self, tool: &str, edit: &FileEdit) -> bool;
+}
+
+/// Returned when the reviewer turned an edit down.
+#[derive(Debug)]
+pub struct EditRejected {
+    pub path: String,
+}
+
+impl fmt::Display for EditRejected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The user rejected the edit to {}. The file was not changed; ask how to proceed or try a different change.",
+            self.path
+        )
+    }
+}
+
+impl std::error::Error for EditRejected {}
+
+/// Arguments that don't match a tool's parameter schema. The message lists
+/// every problem so the model can fix them all in one retry.
+#[derive(Debug)]
+pub struct InvalidArguments {
+    pub tool: String,
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for InvalidArguments {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invalid arguments for `{}`:", self.tool)?;
+        for problem in &self.problems {
+            writeln!(f, "- {}", problem)?;
+        }
+        write!(f, "Call the tool again with arguments matching its parameter schema.")
+    }
+}
+
+impl std::error::Error for InvalidArguments {}
+
+/// The `parameters` object of a tool schema (`{"type": "function", "function": {...}}`).
+fn parameters_schema(schema: &Value) -> Option<&Value> {
+    schema.get("function").and_then(|f| f.get("parameters"))
+}
+
+/// Validate `args` against the tool's schema, then run it. Tools can rely on
+/// required fields being present and correctly typed.
+pub fn execute_checked(tool: &dyn Tool, args: Value) -> Result<String> {
+    let args = validate(tool, args)?;
+    crate::metrics::time(crate::metrics::TOOL, &tool.name(), || tool.execute(args))
+}
+
+/// `execute_checked`, with file edits passed by `reviewer` first.
+pub fn execute_reviewed(tool: &dyn Tool, args: Value, reviewer: Option<&dyn EditReviewer>) -> Result<String> {
+    let args = validate(tool, args)?;
+    if let Some(reviewer) = reviewer {
+        if let Some(edit) = tool.preview_edit(&args) {
+            let edit = edit?;
+            if !reviewer.review(&tool.name(), &edit) {
+                return Err(EditRejected { path: edit.path }.into());
+            }
+        }
+    }
+    crate::metrics::time(crate::metrics::TOOL, &tool.name(), || tool.execute(args))
+}
+
+fn validate(tool: &dyn Tool, args: Value) -> Result<Value> {
+    // Argument-less calls sometimes arrive as `null`
+    let args = if args.is_null() { json!({}) } else { args };
+
+    if let Some(parameters) = parameters_schema(&tool.schema()) {
+        let problems = json_repair::validate(&args, parameters);
+        if !problems.is_empty() {
+            return Err(InvalidArguments {
+                tool: tool.name(),
+                problems,
+            }
+            .into());
+        }
+    }
+    Ok(args)
+}
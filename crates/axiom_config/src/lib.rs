@@ -0,0 +1,463 @@
+//! Axiom Config
+//!
+//! The layered `axiom.toml` shared by the editor and the MCP servers. The
+//! global file in the user config directory (e.g. `~/.config/axiom/` on
+//! Linux) is read first, then `axiom.toml` in the project root; a project
+//! value replaces the global one key by key.
+//!
+//! Connection values are exported as the environment variables the providers,
+//! the BRP client and the debugger already read. Variables set by the shell,
+//! `.env` or the editor settings are left alone, so those still win.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const FILE_NAME: &str = "axiom.toml";
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct AxiomConfig {
+    pub llm: LlmSettings,
+    pub brp: BrpSettings,
+    pub debugger: DebuggerSettings,
+    pub tools: ToolPermissions,
+    pub ui: UiSettings,
+    pub project: ProjectSettings,
+    pub game: GameSettings,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct LlmSettings {
+    /// Model for the default profile on a fresh start.
+    pub default_model: String,
+    /// OpenAI-compatible endpoint.
+    pub openai: ProviderSettings,
+    pub anthropic: ProviderSettings,
+    /// Native Gemini API.
+    pub gemini: ProviderSettings,
+    pub ollama: ProviderSettings,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ProviderSettings {
+    pub api_key: String,
+    pub base_url: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct BrpSettings {
+    pub endpoint: String,
+    pub timeout_ms: Option<u64>,
+    /// Shared secret the game's `bevy_ai_remote` plugin requires, if any.
+    pub token: String,
+    /// PEM file of a CA to trust for an `https://` endpoint.
+    pub tls_ca: String,
+    /// Accept the endpoint's certificate even if it's self-signed.
+    pub tls_insecure: bool,
+    /// Requests per second sent to the game, at most.
+    pub max_rps: Option<f64>,
+    /// Requests to the game in flight at once, at most.
+    pub max_concurrent: Option<usize>,
+    /// Gzip request params larger than this many bytes.
+    pub compress_above: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DebuggerSettings {
+    /// CodeLLDB adapter used by `debugger_mcp_server`.
+    pub adapter_path: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EditApproval {
+    /// Edits wait for review in the editor.
+    Review,
+    Auto,
+}
+
+impl EditApproval {
+    fn as_str(self) -> &'static str {
+        match self {
+            EditApproval::Review => "review",
+            EditApproval::Auto => "auto",
+        }
+    }
+}
+
+/// Which agent tools may run, on top of each profile's own tool list.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ToolPermissions {
+    /// Empty allows every tool.
+    pub allow: Vec<String>,
+    /// Wins over `allow`.
+    pub deny: Vec<String>,
+    pub edit_approval: Option<EditApproval>,
+}
+
+impl ToolPermissions {
+    pub fn allows(&self, tool: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|t| t == tool)) && !self.deny.iter().any(|t| t == tool)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    /// `dark`, `light` or `system`.
+    pub theme: Option<String>,
+    pub accent: Option<[u8; 3]>,
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ProjectSettings {
+    /// Folders shown in the asset browser, relative to the project root.
+    pub asset_dirs: Vec<String>,
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self { asset_dirs: vec!["assets".to_string()] }
+    }
+}
+
+/// How the editor builds and runs the user's game.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GameSettings {
+    /// `cargo -p`; empty builds the workspace's default package.
+    pub package: String,
+    /// `cargo --bin`, for packages with several binaries.
+    pub bin: String,
+    pub features: Vec<String>,
+    pub release: bool,
+    /// Passed to the game itself.
+    pub args: Vec<String>,
+    /// Let `debugger_mcp_server` attach to every game the editor launches.
+    pub auto_attach_debugger: bool,
+}
+
+/// The game process the editor last launched, shared with the MCP servers
+/// through `.axiom/game.toml` in the project root.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GameLaunch {
+    pub pid: u32,
+    /// The built executable.
+    pub program: PathBuf,
+    /// Seconds since the Unix epoch.
+    pub started_at: u64,
+}
+
+impl GameLaunch {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".axiom").join("game.toml")
+    }
+
+    /// `None` when no game is running from the editor.
+    pub fn load(project_root: &Path) -> Result<Option<Self>> {
+        let path = Self::path(project_root);
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        toml::from_str(&data).map(Some).with_context(|| format!("Failed to parse {}", path.display()))
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        std::fs::write(&path, toml::to_string(self)?).with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn clear(project_root: &Path) -> Result<()> {
+        match std::fs::remove_file(Self::path(project_root)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Axiom's folder in the user config directory, e.g. `~/.config/axiom/` on
+/// Linux; the editor keeps its settings there too.
+pub fn config_dir() -> PathBuf {
+    dirs::config_dir().unwrap_or_else(|| PathBuf::from(".axiom")).join("axiom")
+}
+
+/// Variables exported by the last [`AxiomConfig::apply_env`], with their values.
+static EXPORTED: Mutex<BTreeMap<&'static str, String>> = Mutex::new(BTreeMap::new());
+
+impl AxiomConfig {
+    /// `axiom.toml` in [`config_dir`].
+    pub fn global_path() -> PathBuf {
+        config_dir().join(FILE_NAME)
+    }
+
+    /// The global file, then the one in `project_root`; missing files are skipped.
+    pub fn load(project_root: Option<&Path>) -> Result<Self> {
+        let mut layers = Vec::new();
+        let paths = std::iter::once(Self::global_path()).chain(project_root.map(|root| root.join(FILE_NAME)));
+        for path in paths {
+            if let Some(layer) = read_layer(&path)? {
+                tracing::debug!("Loaded {}", path.display());
+                layers.push(layer);
+            }
+        }
+        Self::from_layers(layers)
+    }
+
+    /// Later layers win.
+    pub fn from_layers(layers: impl IntoIterator<Item = toml::Table>) -> Result<Self> {
+        let mut merged = toml::Table::new();
+        for layer in layers {
+            merge(&mut merged, layer);
+        }
+        toml::Value::Table(merged).try_into().context("Invalid axiom.toml")
+    }
+
+    /// Non-empty values as the environment variables that carry them.
+    pub fn env_vars(&self) -> Vec<(&'static str, String)> {
+        let llm = &self.llm;
+        let mut vars = vec![
+            ("GEMINI_API_KEY", llm.openai.api_key.clone()),
+            ("GEMINI_BASE_URL", llm.openai.base_url.clone()),
+            ("ANTHROPIC_API_KEY", llm.anthropic.api_key.clone()),
+            ("ANTHROPIC_BASE_URL", llm.anthropic.base_url.clone()),
+            ("GOOGLE_API_KEY", llm.gemini.api_key.clone()),
+            ("GEMINI_NATIVE_BASE_URL", llm.gemini.base_url.clone()),
+            ("OLLAMA_BASE_URL", llm.ollama.base_url.clone()),
+            ("BRP_ENDPOINT", self.brp.endpoint.clone()),
+            ("BRP_TOKEN", self.brp.token.clone()),
+            ("BRP_TLS_CA", self.brp.tls_ca.clone()),
+            ("CODELLDB_ADAPTER_PATH", self.debugger.adapter_path.clone()),
+        ];
+        if let Some(timeout) = self.brp.timeout_ms {
+            vars.push(("BRP_TIMEOUT_MS", timeout.to_string()));
+        }
+        if self.brp.tls_insecure {
+            vars.push(("BRP_TLS_INSECURE", "1".to_string()));
+        }
+        if let Some(rate) = self.brp.max_rps {
+            vars.push(("BRP_MAX_RPS", rate.to_string()));
+        }
+        if let Some(count) = self.brp.max_concurrent {
+            vars.push(("BRP_MAX_CONCURRENT", count.to_string()));
+        }
+        if let Some(bytes) = self.brp.compress_above {
+            vars.push(("BRP_COMPRESS_ABOVE", bytes.to_string()));
+        }
+        if let Some(approval) = self.tools.edit_approval {
+            vars.push(("AXIOM_EDIT_APPROVAL", approval.as_str().to_string()));
+        }
+        vars.retain(|(_, value)| !value.trim().is_empty());
+        vars.iter_mut().for_each(|(_, value)| *value = value.trim().to_string());
+        vars
+    }
+
+    /// Export [`Self::env_vars`] that nothing else has set. Values exported
+    /// by an earlier call are replaced, or removed if this config lacks them,
+    /// so reloading for another project doesn't leave the old ones behind.
+    pub fn apply_env(&self) {
+        let vars = self.env_vars();
+        let mut exported = EXPORTED.lock().unwrap_or_else(|e| e.into_inner());
+        // Forget variables changed by someone else since
+        exported.retain(|var, value| std::env::var(var).is_ok_and(|current| current == *value));
+        exported.retain(|var, _| {
+            let keep = vars.iter().any(|(v, _)| v == var);
+            if !keep {
+                std::env::remove_var(var);
+            }
+            keep
+        });
+        for (var, value) in vars {
+            if std::env::var_os(var).is_some() && !exported.contains_key(var) {
+                continue;
+            }
+            std::env::set_var(var, &value);
+            exported.insert(var, value);
+        }
+    }
+}
+
+fn read_layer(path: &Path) -> Result<Option<toml::Table>> {
+    let data = match std::fs::read_to_string(path) {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    };
+    data.parse().map(Some).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+/// Tables merge recursively; anything else in `over` replaces `base`.
+fn merge(base: &mut toml::Table, over: toml::Table) {
+    for (key, value) in over {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(over)) => merge(base, over),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layer(text: &str) -> toml::Table {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn defaults_without_layers() {
+        let config = AxiomConfig::from_layers([]).unwrap();
+        assert_eq!(config, AxiomConfig::default());
+        assert_eq!(config.project.asset_dirs, vec!["assets"]);
+        assert!(config.env_vars().is_empty());
+    }
+
+    #[test]
+    fn project_layer_overrides_by_key() {
+        let global = layer(
+            r#"
+            [llm]
+            default_model = "gpt-4o"
+            [llm.anthropic]
+            api_key = "global-key"
+            base_url = "https://api.anthropic.com"
+            [brp]
+            endpoint = "http://127.0.0.1:15721"
+            "#,
+        );
+        let project = layer(
+            r#"
+            [llm.anthropic]
+            api_key = "project-key"
+            [brp]
+            timeout_ms = 5000
+            [project]
+            asset_dirs = ["assets", "art"]
+            [game]
+            features = ["dev"]
+            auto_attach_debugger = true
+            "#,
+        );
+
+        let config = AxiomConfig::from_layers([global, project]).unwrap();
+        assert_eq!(config.llm.default_model, "gpt-4o");
+        assert_eq!(config.llm.anthropic.api_key, "project-key");
+        assert_eq!(config.llm.anthropic.base_url, "https://api.anthropic.com");
+        assert_eq!(config.brp.endpoint, "http://127.0.0.1:15721");
+        assert_eq!(config.brp.timeout_ms, Some(5000));
+        assert_eq!(config.project.asset_dirs, vec!["assets", "art"]);
+        assert_eq!(config.game.features, vec!["dev"]);
+        assert!(config.game.auto_attach_debugger);
+        assert!(!config.game.release);
+    }
+
+    #[test]
+    fn invalid_values_are_rejected() {
+        let err = AxiomConfig::from_layers([layer("[tools]\nedit_approval = \"sometimes\"")]).unwrap_err();
+        assert!(format!("{:#}", err).contains("axiom.toml"));
+    }
+
+    #[test]
+    fn env_vars_skip_empty_values() {
+        let config = AxiomConfig::from_layers([layer(
+            r#"
+            [llm.ollama]
+            base_url = " http://localhost:11434 "
+            [llm.openai]
+            api_key = ""
+            [brp]
+            timeout_ms = 250
+            token = "s3cret"
+            tls_insecure = true
+            max_rps = 20
+            [tools]
+            edit_approval = "auto"
+            "#,
+        )])
+        .unwrap();
+
+        assert_eq!(
+            config.env_vars(),
+            vec![
+                ("OLLAMA_BASE_URL", "http://localhost:11434".to_string()),
+                ("BRP_TOKEN", "s3cret".to_string()),
+                ("BRP_TIMEOUT_MS", "250".to_string()),
+                ("BRP_TLS_INSECURE", "1".to_string()),
+                ("BRP_MAX_RPS", "20".to_string()),
+                ("AXIOM_EDIT_APPROVAL", "auto".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn game_launch_round_trips() {
+        let root = std::env::temp_dir().join(format!("axiom_config_launch_{}", std::process::id()));
+        assert_eq!(GameLaunch::load(&root).unwrap(), None);
+
+        let launch = GameLaunch { pid: 4242, program: root.join("target/debug/game"), started_at: 1_700_000_000 };
+        launch.save(&root).unwrap();
+        assert_eq!(GameLaunch::load(&root).unwrap(), Some(launch));
+
+        GameLaunch::clear(&root).unwrap();
+        GameLaunch::clear(&root).unwrap();
+        assert_eq!(GameLaunch::load(&root).unwrap(), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn tool_permissions() {
+        let permissions = ToolPermissions::default();
+        assert!(permissions.allows("shell"));
+
+        let permissions = ToolPermissions {
+            allow: vec!["read".to_string(), "shell".to_string()],
+            deny: vec!["shell".to_string()],
+            edit_approval: None,
+        };
+        assert!(permissions.allows("read"));
+        assert!(!permissions.allows("shell"));
+        assert!(!permissions.allows("write"));
+    }
+
+    #[test]
+    fn apply_env_keeps_user_values_and_replaces_its_own() {
+        let with_path = |path: &str| {
+            let mut config = AxiomConfig::default();
+            config.debugger.adapter_path = path.to_string();
+            config
+        };
+
+        std::env::remove_var("CODELLDB_ADAPTER_PATH");
+        with_path("/first/codelldb").apply_env();
+        assert_eq!(std::env::var("CODELLDB_ADAPTER_PATH").unwrap(), "/first/codelldb");
+
+        // Ours, so another project replaces it
+        with_path("/second/codelldb").apply_env();
+        assert_eq!(std::env::var("CODELLDB_ADAPTER_PATH").unwrap(), "/second/codelldb");
+
+        // And one without it removes it
+        AxiomConfig::default().apply_env();
+        assert!(std::env::var("CODELLDB_ADAPTER_PATH").is_err());
+
+        // Set by the user: left alone
+        std::env::set_var("CODELLDB_ADAPTER_PATH", "/user/codelldb");
+        with_path("/third/codelldb").apply_env();
+        assert_eq!(std::env::var("CODELLDB_ADAPTER_PATH").unwrap(), "/user/codelldb");
+        std::env::remove_var("CODELLDB_ADAPTER_PATH");
+    }
+}
@@ -0,0 +1,207 @@
+//! Axiom E2E
+//!
+//! Test harness running a headless Bevy app with `BevyAiRemotePlugin` on a
+//! background thread, so the bridge ops and the MCP servers can be tested
+//! against a real game and the resulting world inspected directly.
+//!
+//! Each test binary shares one game through [`game`], which also serializes
+//! the tests; they start from an empty scene with [`TestGame::reset`]. The
+//! plugin writes uploads relative to the working directory, so starting the
+//! game moves the process into a fresh temporary game root. The game
+//! requires [`TOKEN`], so every test also goes through the token check.
+
+use bevy::app::ScheduleRunnerPlugin;
+use bevy::prelude::*;
+use bevy::scene::ScenePlugin;
+use bevy::log::tracing_subscriber::filter::LevelFilter;
+use bevy::log::tracing_subscriber::layer::SubscriberExt;
+use bevy::log::tracing_subscriber::Registry;
+use bevy_ai_remote::{AxiomAuthToken, AxiomRemoteAddress, AxiomSpawned, BevyAiRemotePlugin};
+use bevy_bridge_core::types::EntityId;
+use bevy_bridge_core::{BrpClient, BrpConfig};
+use bevy_remote::RemotePlugin;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the game to start or a condition to hold.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Shared secret the game requires on every BRP request.
+pub const TOKEN: &str = "axiom-e2e-token";
+
+type WorldCommand = Box<dyn FnOnce(&mut World) + Send>;
+
+/// Closures sent by the test thread, run once per frame.
+#[derive(Resource)]
+struct WorldCommands(Mutex<Receiver<WorldCommand>>);
+
+fn run_world_commands(world: &mut World) {
+    let commands: Vec<WorldCommand> = {
+        let receiver = world.resource::<WorldCommands>().0.lock().unwrap_or_else(|e| e.into_inner());
+        receiver.try_iter().collect()
+    };
+    for command in commands {
+        command(world);
+    }
+}
+
+pub struct TestGame {
+    root: PathBuf,
+    endpoint: String,
+    client: BrpClient,
+    runtime: tokio::runtime::Runtime,
+    commands: Mutex<Sender<WorldCommand>>,
+}
+
+/// The game shared by this test binary, started on first use. Holding the
+/// guard keeps other tests from changing the scene meanwhile.
+pub fn game() -> MutexGuard<'static, TestGame> {
+    static GAME: OnceLock<Mutex<TestGame>> = OnceLock::new();
+    GAME.get_or_init(|| Mutex::new(TestGame::start()))
+        .lock()
+        // A failed test doesn't break the game for the others
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+impl TestGame {
+    /// Start the game in a new temporary root and wait until BRP answers.
+    pub fn start() -> Self {
+        let root = std::env::temp_dir().join(format!("axiom_e2e_{}", std::process::id()));
+        std::fs::create_dir_all(root.join("assets")).expect("create game root");
+        std::env::set_current_dir(&root).expect("enter game root");
+
+        let port = free_port();
+        let (sender, receiver) = mpsc::channel();
+        let assets = root.join("assets").to_string_lossy().to_string();
+        std::thread::Builder::new()
+            .name("bevy_game".to_string())
+            .spawn(move || {
+                let mut app = App::new();
+                // What `LogPlugin` does with the layer, without its output
+                let logs = bevy_ai_remote::log_layer(&mut app).expect("log layer");
+                let subscriber = Registry::default().with(logs).with(LevelFilter::INFO);
+                bevy::log::tracing::subscriber::set_global_default(subscriber).expect("install the log layer");
+                app.insert_resource(AxiomAuthToken(TOKEN.to_string()))
+                    .insert_resource(AxiomRemoteAddress { address: Ipv4Addr::LOCALHOST.into(), port, announce: false })
+                    .add_plugins((
+                        MinimalPlugins.set(ScheduleRunnerPlugin::run_loop(Duration::from_millis(5))),
+                        TransformPlugin,
+                        AssetPlugin { file_path: assets, ..default() },
+                        ScenePlugin,
+                        RemotePlugin::default(),
+                        BevyAiRemotePlugin::default(),
+                    ))
+                    // Normally from the render plugins, which need a GPU
+                    .init_asset::<Mesh>()
+                    .init_asset::<StandardMaterial>()
+                    // Registered automatically only with bevy's default features
+                    .register_type::<Transform>()
+                    .insert_resource(WorldCommands(Mutex::new(receiver)))
+                    .add_systems(Last, run_world_commands)
+                    .run();
+            })
+            .expect("spawn game thread");
+
+        let endpoint = format!("http://127.0.0.1:{}", port);
+        let client = BrpClient::new(BrpConfig::new(endpoint.clone(), Duration::from_secs(5)).with_token(TOKEN));
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().expect("tokio runtime");
+        let game = Self { root, endpoint, client, runtime, commands: Mutex::new(sender) };
+        game.wait_until_ready();
+        game
+    }
+
+    fn wait_until_ready(&self) {
+        let started = Instant::now();
+        while self.block_on(bevy_bridge_core::ops::ping::ping(&self.client)).is_err() {
+            assert!(started.elapsed() < TIMEOUT, "the game didn't answer on {}", self.endpoint);
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+
+    pub fn client(&self) -> &BrpClient {
+        &self.client
+    }
+
+    /// Run a bridge op to completion.
+    pub fn block_on<F: std::future::Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// The BRP endpoint, for servers started by a test.
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// The game's working directory; uploads land in `assets/_remote_cache`.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Run `f` on the game's world at the end of the next frame.
+    pub fn with_world<R: Send + 'static>(&self, f: impl FnOnce(&mut World) -> R + Send + 'static) -> R {
+        let (sender, receiver) = mpsc::channel();
+        let command: WorldCommand = Box::new(move |world| {
+            let _ = sender.send(f(world));
+        });
+        self.commands.lock().unwrap_or_else(|e| e.into_inner()).send(command).expect("game is running");
+        receiver.recv_timeout(TIMEOUT).expect("game ran the command")
+    }
+
+    /// Check `condition` once per frame until it holds; panics with
+    /// `what` after [`TIMEOUT`].
+    pub fn wait_for(&self, what: &str, condition: impl Fn(&mut World) -> bool + Send + Sync + Clone + 'static) {
+        let started = Instant::now();
+        while !self.with_world(condition.clone()) {
+            assert!(started.elapsed() < TIMEOUT, "timed out waiting for {}", what);
+        }
+    }
+
+    /// Despawn everything the editor spawned and empty the upload cache.
+    pub fn reset(&self) {
+        self.with_world(|world| {
+            let spawned: Vec<Entity> = world.query_filtered::<Entity, With<AxiomSpawned>>().iter(world).collect();
+            for entity in spawned {
+                world.despawn(entity);
+            }
+        });
+        let _ = std::fs::remove_dir_all(self.cache_dir());
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root.join("assets").join("_remote_cache")
+    }
+
+    /// Entities with `AxiomSpawned`.
+    pub fn spawned_count(&self) -> usize {
+        self.with_world(|world| world.query_filtered::<(), With<AxiomSpawned>>().iter(world).count())
+    }
+}
+
+/// The entity named by a bridge response's `entity_id`.
+pub fn entity(entity_id: EntityId) -> Entity {
+    Entity::from_bits(entity_id.bits())
+}
+
+fn free_port() -> u16 {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).expect("bind a free port");
+    listener.local_addr().expect("local address").port()
+}
+
+/// Path of a binary from this workspace, built first if needed.
+pub fn workspace_bin(package: &str) -> PathBuf {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = std::process::Command::new(cargo)
+        .args(["build", "--quiet", "-p", package])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .output()
+        .expect("run cargo build");
+    assert!(output.status.success(), "building {} failed:\n{}", package, String::from_utf8_lossy(&output.stderr));
+
+    // Tests run from target/<profile>/deps/
+    let exe = std::env::current_exe().expect("test executable");
+    let dir = exe.parent().and_then(Path::parent).expect("target directory");
+    dir.join(format!("{}{}", package, std::env::consts::EXE_SUFFIX))
+}
@@ -0,0 +1,960 @@
+//! `bevy_bridge_core` ops against a running game.
+
+use axiom_e2e::{entity, game, TOKEN};
+use bevy::prelude::*;
+use bevy_ai_remote::{AxiomGroup, AxiomPrimitive, AxiomProtocolVersion, AxiomRemoteAsset, AxiomSpawned};
+use bevy_bridge_core::ops;
+use bevy_bridge_core::types::{
+    ClearTarget, EntityId, Filter, LogLevel, PrimitiveMaterial, QueryOptions, SceneObject, SceneTransform,
+    SimState, SpawnRequest, UploadOptions,
+};
+use bevy_bridge_core::{BrpClient, BrpConfig, BrpError, CommandJournal, Traffic};
+use futures_util::StreamExt;
+use std::time::Duration;
+
+const IDENTITY: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+/// A plain `kind` at `position`, unrotated and unnamed.
+fn primitive(kind: &str, position: [f32; 3], scale: [f32; 3]) -> SpawnRequest {
+    SpawnRequest {
+        primitive: kind.to_string(),
+        position,
+        rotation: IDENTITY,
+        scale,
+        material: PrimitiveMaterial::default(),
+        name: None,
+        group: None,
+    }
+}
+
+/// A 1x1 PNG.
+const PIXEL_PNG: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52, 0x00, 0x00, 0x00, 0x01,
+    0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4, 0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41,
+    0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0xf0, 0x1f, 0x00, 0x05, 0x00, 0x01, 0xff, 0x89, 0x99, 0x3d, 0x1d, 0x00, 0x00,
+    0x00, 0x00, 0x49, 0x45, 0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+#[test]
+fn ping_lists_the_axiom_methods() {
+    let game = game();
+    let response = game.block_on(ops::ping::ping(game.client())).unwrap();
+    let methods = response.methods.to_string();
+    assert!(methods.contains(bevy_ai_remote::LIST_ASSETS_METHOD), "{}", methods);
+    assert!(methods.contains(bevy_ai_remote::SCREENSHOT_METHOD), "{}", methods);
+    assert!(response.method_count > 10, "{}", response.method_count);
+    assert!(response.bevy_version.as_deref().is_some_and(|v| v.starts_with("0.")), "{:?}", response.bevy_version);
+    assert_eq!(response.plugin_version, Some(bevy_ai_remote::AxiomStatus::default().plugin));
+    assert!(response.frames.is_some_and(|frames| frames > 0));
+}
+
+#[test]
+fn handshake_reports_protocol_drift() {
+    let game = game();
+    game.reset();
+    let version = game.block_on(ops::handshake::handshake(game.client())).unwrap().unwrap();
+    assert_eq!(version.protocol, bevy_ai_remote::PROTOCOL_VERSION);
+    assert_eq!(ops::handshake::PROTOCOL_VERSION, bevy_ai_remote::PROTOCOL_VERSION);
+
+    game.with_world(|world| world.resource_mut::<AxiomProtocolVersion>().protocol += 1);
+    let client = BrpClient::new(BrpConfig::new(game.endpoint(), Duration::from_secs(5)).with_token(TOKEN));
+    let spawned = game.block_on(ops::spawn::spawn(&client, &primitive("cube", [0.0; 3], [1.0; 3])));
+    game.with_world(|world| world.resource_mut::<AxiomProtocolVersion>().protocol -= 1);
+    assert!(matches!(spawned, Err(BrpError::VersionMismatch { .. })), "{:?}", spawned);
+    assert_eq!(game.spawned_count(), 0);
+}
+
+#[test]
+fn capabilities_are_discovered_once_per_client() {
+    let game = game();
+    let client = game.client().clone();
+    let capabilities = game.block_on(client.capabilities()).unwrap();
+    assert!(capabilities.supports("world.query"));
+    assert!(capabilities.supports(bevy_ai_remote::DOWNLOAD_METHOD));
+    assert_eq!(capabilities.resolve("world.despawn_entity"), Some("world.despawn_entity"));
+
+    let again = game.block_on(client.capabilities()).unwrap();
+    assert!(std::sync::Arc::ptr_eq(&capabilities, &again));
+}
+
+#[test]
+fn requests_without_the_token_are_rejected() {
+    let game = game();
+    game.reset();
+
+    for config in [
+        BrpConfig::new(game.endpoint(), Duration::from_secs(5)),
+        BrpConfig::new(game.endpoint(), Duration::from_secs(5)).with_token("wrong"),
+    ] {
+        let client = BrpClient::new(config);
+        let spawned = game.block_on(ops::spawn::spawn(&client, &primitive("cube", [0.0; 3], [1.0; 3])));
+        assert!(matches!(spawned, Err(BrpError::PermissionDenied { .. })), "{:?}", spawned);
+        assert!(game.block_on(ops::ping::ping(&client)).is_err());
+    }
+    assert_eq!(game.spawned_count(), 0);
+}
+
+#[test]
+fn spawn_hydrates_the_primitive_where_asked() {
+    let game = game();
+    game.reset();
+
+    let cube = primitive("cube", [1.0, 2.0, 3.0], [2.0, 2.0, 2.0]);
+    let request = ops::spawn::spawn(game.client(), &cube);
+    let response = game.block_on(request).unwrap();
+    let cube = entity(response.entity_id);
+    assert_eq!(response.entity_id.to_string(), format!("{}#{}", cube, cube.to_bits()));
+
+    game.wait_for("the cube's mesh", move |world| world.get::<Mesh3d>(cube).is_some());
+    let (kind, transform) = game.with_world(move |world| {
+        let entity = world.entity(cube);
+        (entity.get::<AxiomPrimitive>().unwrap().primitive_type.clone(), *entity.get::<Transform>().unwrap())
+    });
+    assert_eq!(kind, "cube");
+    assert_eq!(transform.translation, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(transform.scale, Vec3::splat(2.0));
+    assert_eq!(game.spawned_count(), 1);
+}
+
+#[test]
+fn spawn_batch_lays_out_a_row() {
+    let game = game();
+    game.reset();
+
+    let requests = (0..5)
+        .map(|i| SpawnRequest {
+            primitive: "cube".into(),
+            position: [i as f32 * 2.0, 0.0, 0.0],
+            rotation: IDENTITY,
+            scale: [1.0; 3],
+            material: PrimitiveMaterial::default(),
+            name: None,
+            group: None,
+        })
+        .collect();
+    let results = game.block_on(ops::spawn::spawn_batch(game.client(), requests)).unwrap();
+    let cubes: Vec<Entity> = results.into_iter().map(|r| entity(r.unwrap().entity_id)).collect();
+
+    let xs = game.with_world(move |world| {
+        cubes.iter().map(|&cube| world.get::<Transform>(cube).unwrap().translation.x).collect::<Vec<_>>()
+    });
+    assert_eq!(xs, vec![0.0, 2.0, 4.0, 6.0, 8.0]);
+    assert_eq!(game.spawned_count(), 5);
+}
+
+#[test]
+fn spawn_applies_the_material() {
+    let game = game();
+    game.reset();
+
+    let material = PrimitiveMaterial {
+        color: Some([1.0, 0.0, 0.0, 0.5]),
+        metallic: Some(0.9),
+        roughness: Some(0.2),
+        emissive: Some([0.0, 2.0, 0.0]),
+        unlit: true,
+    };
+    let red = SpawnRequest { material, ..primitive("cube", [0.0; 3], [1.0; 3]) };
+    let spawned = game.block_on(ops::spawn::spawn(game.client(), &red)).unwrap();
+    let plain = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    let (red, plain) = (entity(spawned.entity_id), entity(plain.entity_id));
+    game.wait_for("materials", move |world| {
+        [red, plain].iter().all(|&e| world.get::<MeshMaterial3d<StandardMaterial>>(e).is_some())
+    });
+
+    let (red, plain) = game.with_world(move |world| {
+        let materials = world.resource::<Assets<StandardMaterial>>();
+        let get = |e: Entity| materials.get(&world.get::<MeshMaterial3d<StandardMaterial>>(e).unwrap().0).unwrap().clone();
+        (get(red), get(plain))
+    });
+    assert_eq!(red.base_color, Color::srgba(1.0, 0.0, 0.0, 0.5));
+    assert_eq!((red.metallic, red.perceptual_roughness, red.unlit), (0.9, 0.2, true));
+    assert_eq!(red.emissive, LinearRgba::rgb(0.0, 2.0, 0.0));
+    assert_eq!(plain.base_color, Color::srgb(0.8, 0.7, 0.6));
+    assert!(!plain.unlit);
+}
+
+#[test]
+fn named_entities_are_found_by_name() {
+    let game = game();
+    game.reset();
+
+    let start = SpawnRequest { name: Some("player_start".into()), ..primitive("cone", [0.0; 3], [1.0; 3]) };
+    let start = game.block_on(ops::spawn::spawn(game.client(), &start)).unwrap().entity_id;
+    game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    let sign = game.block_on(ops::upload::upload(game.client(), "pixel.png", PIXEL_PNG, &UploadOptions { name: Some("sign".into()), ..Default::default() }));
+    let sign = sign.unwrap().entity_id;
+
+    assert_eq!(game.block_on(ops::find::by_name(game.client(), "player_start")).unwrap(), vec![start]);
+    assert_eq!(game.block_on(ops::find::by_name(game.client(), "sign")).unwrap(), vec![sign]);
+    assert!(game.block_on(ops::find::by_name(game.client(), "player")).unwrap().is_empty());
+    let name = game.with_world(move |world| world.get::<Name>(entity(start)).unwrap().as_str().to_string());
+    assert_eq!(name, "player_start");
+}
+
+#[test]
+fn query_returns_spawned_components() {
+    let game = game();
+    game.reset();
+    game.block_on(ops::spawn::spawn(game.client(), &primitive("sphere", [0.0, 0.0, 0.0], [1.0; 3]))).unwrap();
+
+    let request = ops::query::query(game.client(), vec!["bevy_ai_remote::AxiomPrimitive".to_string()], QueryOptions::default());
+    let response = game.block_on(request).unwrap();
+    assert_eq!(response.entities.len(), 1);
+    assert_eq!(
+        response.entities[0]["components"]["bevy_ai_remote::AxiomPrimitive"]["primitive_type"],
+        "sphere"
+    );
+}
+
+#[test]
+fn clear_all_removes_only_what_the_editor_spawned() {
+    let game = game();
+    game.reset();
+    let own = game.with_world(|world| world.spawn((Name::new("level"), Transform::default())).id());
+    for position in [[0.0, 0.0, 0.0], [4.0, 0.0, 0.0]] {
+        game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", position, [1.0; 3]))).unwrap();
+    }
+    assert_eq!(game.spawned_count(), 2);
+
+    let response = game.block_on(ops::clear::clear(game.client(), ClearTarget::All)).unwrap();
+    assert_eq!(response.entities_removed, 2);
+    assert_eq!(game.spawned_count(), 0);
+    assert!(game.with_world(move |world| world.get_entity(own).is_ok()));
+    game.with_world(move |world| world.despawn(own));
+}
+
+#[test]
+fn clear_group_leaves_the_rest() {
+    let game = game();
+    game.reset();
+    let spawn = |kind, group: Option<&str>| {
+        let request = SpawnRequest { group: group.map(str::to_string), ..primitive(kind, [0.0; 3], [1.0; 3]) };
+        game.block_on(ops::spawn::spawn(game.client(), &request)).unwrap().entity_id
+    };
+    let tower = spawn("cube", Some("level1"));
+    spawn("cone", Some("level2"));
+    spawn("sphere", None);
+    let options = UploadOptions { group: Some("level1".into()), ..Default::default() };
+    let model = ops::upload::upload(game.client(), "crate.glb", b"glTF", &options);
+    game.block_on(model).unwrap();
+
+    let group = game.with_world(move |world| world.get::<AxiomGroup>(entity(tower)).cloned());
+    assert_eq!(group, Some(AxiomGroup("level1".into())));
+
+    let response = game.block_on(ops::clear::clear(game.client(), ClearTarget::Group("level1".into()))).unwrap();
+    assert_eq!(response.entities_removed, 2);
+    assert_eq!(game.spawned_count(), 2);
+    assert!(game.with_world(move |world| world.get_entity(entity(tower)).is_err()));
+}
+
+#[test]
+fn clear_primitives_keeps_uploads() {
+    let game = game();
+    game.reset();
+    game.block_on(ops::spawn::spawn(game.client(), &primitive("cone", [0.0; 3], [1.0; 3]))).unwrap();
+    game.block_on(ops::upload::upload(game.client(), "pixel.png", PIXEL_PNG, &UploadOptions::default())).unwrap();
+
+    let response = game.block_on(ops::clear::clear(game.client(), ClearTarget::Primitives)).unwrap();
+    assert_eq!(response.entities_removed, 1);
+    let remaining = game.with_world(|world| {
+        world
+            .query_filtered::<(), (With<AxiomSpawned>, Without<AxiomPrimitive>)>()
+            .iter(world)
+            .count()
+    });
+    assert_eq!(remaining, 1);
+    assert_eq!(game.spawned_count(), 1);
+}
+
+#[test]
+fn upload_writes_the_cache_and_lists_it() {
+    let game = game();
+    game.reset();
+
+    let options = UploadOptions { subdir: Some("Textures".into()), ..Default::default() };
+    let request = ops::upload::upload(game.client(), "pixel.png", PIXEL_PNG, &options);
+    let response = game.block_on(request).unwrap();
+    let upload = entity(response.entity_id);
+
+    // Textures are written and the upload component dropped, without a scene
+    game.wait_for("the upload to be handled", move |world| world.get::<AxiomRemoteAsset>(upload).is_none());
+    let path = game.cache_dir().join("Textures").join("pixel.png");
+    assert_eq!(std::fs::read(&path).unwrap(), PIXEL_PNG);
+    assert!(game.with_world(move |world| world.get::<SceneRoot>(upload).is_none()));
+
+    let assets = game.block_on(ops::assets::list_assets(game.client())).unwrap();
+    assert_eq!(assets.len(), 1);
+    assert_eq!(assets[0].path, "Textures/pixel.png");
+    assert_eq!(assets[0].size, PIXEL_PNG.len() as u64);
+    assert_eq!(assets[0].image.as_deref(), Some(PIXEL_PNG));
+}
+
+#[test]
+fn upload_of_a_model_attaches_a_scene() {
+    let game = game();
+    game.reset();
+
+    // Never parsed: the harness has no glTF loader, and the load failing is
+    // the asset server's business
+    let options = UploadOptions { translation: [0.0, 1.0, 0.0], ..Default::default() };
+    let request = ops::upload::upload(game.client(), "crate.glb", b"glTF", &options);
+    let response = game.block_on(request).unwrap();
+    let model = entity(response.entity_id);
+
+    game.wait_for("the model's scene root", move |world| world.get::<SceneRoot>(model).is_some());
+    assert!(game.cache_dir().join("crate.glb").exists());
+
+    // And it can be spawned again from the cache without uploading
+    let response = game.block_on(ops::assets::spawn_cached(game.client(), "crate.glb", [3.0, 0.0, 0.0])).unwrap();
+    let copy = entity(response.entity_id);
+    game.wait_for("the cached copy's scene root", move |world| world.get::<SceneRoot>(copy).is_some());
+    assert_eq!(game.spawned_count(), 2);
+}
+
+#[test]
+fn chunked_uploads_are_committed_into_the_cache() {
+    let game = game();
+    game.reset();
+
+    let model = b"glTF, sent three bytes at a time";
+    let options = UploadOptions { subdir: Some("props".into()), ..Default::default() };
+    let request = ops::upload::upload_in_chunks(game.client(), "big.glb", model, &options, 3);
+    let response = game.block_on(request).unwrap();
+    let big = entity(response.entity_id);
+    game.wait_for("the chunked model's scene root", move |world| world.get::<SceneRoot>(big).is_some());
+    assert_eq!(std::fs::read(game.cache_dir().join("props").join("big.glb")).unwrap(), model);
+
+    let options = UploadOptions::default();
+    let request = ops::upload::upload_in_chunks(game.client(), "pixel.png", PIXEL_PNG, &options, 16);
+    game.block_on(request).unwrap();
+    assert_eq!(std::fs::read(game.cache_dir().join("pixel.png")).unwrap(), PIXEL_PNG);
+    let assets = game.block_on(ops::assets::list_assets(game.client())).unwrap();
+    assert_eq!(assets.len(), 2);
+
+    // A chunk that skips ahead, or a commit short of bytes, is refused
+    let chunk = |offset: u64| serde_json::json!({ "upload": "e2e", "offset": offset, "data_base64": "AAAA" });
+    let client = game.client();
+    game.block_on(client.send_rpc(ops::upload::UPLOAD_CHUNK_METHOD, Some(chunk(0)))).unwrap();
+    assert!(game.block_on(client.send_rpc(ops::upload::UPLOAD_CHUNK_METHOD, Some(chunk(7)))).is_err());
+    let commit = serde_json::json!({ "upload": "e2e", "size": 6, "filename": "short.bin" });
+    assert!(game.block_on(client.send_rpc(ops::upload::UPLOAD_COMMIT_METHOD, Some(commit))).is_err());
+    let escape = serde_json::json!({ "upload": "e2e", "size": 3, "filename": "x.bin", "subdir": "../.." });
+    assert!(game.block_on(client.send_rpc(ops::upload::UPLOAD_COMMIT_METHOD, Some(escape))).is_err());
+    // Absolute paths would replace the cache root when joined
+    let outside = game.root().join("escaped.bin");
+    for filename in [outside.to_string_lossy().to_string(), r"C:\escaped.bin".to_string()] {
+        let escape = serde_json::json!({ "upload": "e2e", "size": 3, "filename": filename });
+        assert!(game.block_on(client.send_rpc(ops::upload::UPLOAD_COMMIT_METHOD, Some(escape))).is_err(), "{}", filename);
+    }
+    let escape = serde_json::json!({ "upload": "e2e", "size": 3, "filename": "x.bin", "subdir": outside.parent().unwrap() });
+    assert!(game.block_on(client.send_rpc(ops::upload::UPLOAD_COMMIT_METHOD, Some(escape))).is_err());
+    assert!(!outside.exists());
+}
+
+#[test]
+fn compressed_uploads_are_inflated() {
+    let game = game();
+    game.reset();
+
+    let config = BrpConfig::new(game.endpoint(), Duration::from_secs(5)).with_token(TOKEN).with_compression(1024);
+    let client = BrpClient::new(config);
+    let mut model = b"glTF".to_vec();
+    model.resize(200_000, 0);
+    let options = UploadOptions::default();
+    let request = ops::upload::upload(&client, "padded.glb", &model, &options);
+    let response = game.block_on(request).unwrap();
+    let padded = entity(response.entity_id);
+    game.wait_for("the model's scene root", move |world| world.get::<SceneRoot>(padded).is_some());
+    assert_eq!(std::fs::read(game.cache_dir().join("padded.glb")).unwrap(), model);
+
+    let garbled = serde_json::json!({ bevy_bridge_core::compression::COMPRESSED_PARAM: "not gzip" });
+    let result = game.block_on(client.send_rpc("world.query", Some(garbled)));
+    assert!(matches!(result, Err(BrpError::JsonRpc { code: -32602, .. })), "{:?}", result);
+}
+
+#[test]
+fn repeated_uploads_reuse_the_cache() {
+    let game = game();
+    game.reset();
+
+    let first = game.block_on(ops::upload::upload(game.client(), "crate.glb", b"glTF", &UploadOptions::default())).unwrap();
+    assert!(!first.cached);
+    let again = game.block_on(ops::upload::upload(game.client(), "crate.glb", b"glTF", &UploadOptions { translation: [1.0, 0.0, 0.0], ..Default::default() })).unwrap();
+    assert!(again.cached);
+    let copy = entity(again.entity_id);
+    game.wait_for("the reused model's scene root", move |world| world.get::<SceneRoot>(copy).is_some());
+
+    // The same bytes under another name are copied within the game
+    let options = UploadOptions { subdir: Some("props".into()), ..Default::default() };
+    let renamed = ops::upload::upload(game.client(), "box.glb", b"glTF", &options);
+    assert!(game.block_on(renamed).unwrap().cached);
+    assert_eq!(std::fs::read(game.cache_dir().join("props").join("box.glb")).unwrap(), b"glTF");
+
+    let changed = game.block_on(ops::upload::upload(game.client(), "crate.glb", b"glTF 2", &UploadOptions::default())).unwrap();
+    assert!(!changed.cached);
+
+    // Chunks that don't add up to the committed digest are discarded
+    let client = game.client();
+    let chunk = serde_json::json!({ "upload": "e2e-digest", "offset": 0, "data_base64": "AAAA" });
+    game.block_on(client.send_rpc(ops::upload::UPLOAD_CHUNK_METHOD, Some(chunk))).unwrap();
+    let commit = serde_json::json!({ "upload": "e2e-digest", "size": 3, "sha256": "00", "filename": "bad.bin" });
+    assert!(game.block_on(client.send_rpc(ops::upload::UPLOAD_COMMIT_METHOD, Some(commit))).is_err());
+    assert!(!game.cache_dir().join("bad.bin").exists());
+}
+
+#[test]
+fn download_reads_files_the_game_saved() {
+    let game = game();
+    let saves = game.root().join("assets").join("saves");
+    std::fs::create_dir_all(&saves).unwrap();
+    // Bigger than one range
+    let scene: Vec<u8> = (0..5 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+    std::fs::write(saves.join("level.scn.ron"), &scene).unwrap();
+
+    let downloaded = game.block_on(ops::download::download(game.client(), "saves/level.scn.ron")).unwrap();
+    assert!(downloaded == scene, "the download differs from the file");
+
+    assert!(game.block_on(ops::download::download(game.client(), "saves/missing.ron")).is_err());
+    assert!(game.block_on(ops::download::download(game.client(), "../assets/saves/level.scn.ron")).is_err());
+    std::fs::remove_dir_all(saves).unwrap();
+}
+
+#[test]
+fn recorded_traffic_replays_without_the_game() {
+    let game = game();
+    game.reset();
+    let path = game.root().join("recording.jsonl");
+    let config = BrpConfig::new(game.endpoint(), Duration::from_secs(5)).with_token(TOKEN);
+    let recorder = BrpClient::new(config.clone().with_traffic(Traffic::Record(path.clone())));
+
+    let spawned = game.block_on(ops::spawn::spawn(&recorder, &primitive("capsule", [0.0; 3], [1.0; 3]))).unwrap();
+    let queried = game.block_on(ops::query::query(&recorder, vec!["bevy_ai_remote::AxiomPrimitive".to_string()], QueryOptions::default())).unwrap();
+    game.reset();
+    assert!(!std::fs::read_to_string(&path).unwrap().contains(TOKEN), "the token was recorded");
+
+    // Nothing listens on the replay client's endpoint
+    let replay = BrpConfig::new("http://127.0.0.1:9", Duration::from_secs(5)).with_traffic(Traffic::Replay(path));
+    let replayer = BrpClient::new(replay);
+    let respawned = game.block_on(ops::spawn::spawn(&replayer, &primitive("capsule", [0.0; 3], [1.0; 3]))).unwrap();
+    assert_eq!(respawned.entity_id, spawned.entity_id);
+    let requeried = game.block_on(ops::query::query(&replayer, vec!["bevy_ai_remote::AxiomPrimitive".to_string()], QueryOptions::default())).unwrap();
+    assert_eq!(requeried.entities, queried.entities);
+    assert_eq!(game.spawned_count(), 0);
+
+    // The recording is used up
+    assert!(game.block_on(ops::query::query(&replayer, vec![], QueryOptions::default())).is_err());
+}
+
+#[test]
+fn scene_export_restores_and_diffs() {
+    let game = game();
+    game.reset();
+    let rotated = [0.0, std::f32::consts::FRAC_1_SQRT_2, 0.0, std::f32::consts::FRAC_1_SQRT_2];
+    game.block_on(ops::spawn::spawn(game.client(), &SpawnRequest { rotation: rotated, ..primitive("cube", [1.0, 0.0, 0.0], [2.0; 3]) })).unwrap();
+    game.block_on(ops::spawn::spawn(game.client(), &primitive("sphere", [0.0, 3.0, 0.0], [1.0; 3]))).unwrap();
+    game.block_on(ops::upload::upload(game.client(), "crate.glb", b"glTF", &UploadOptions { subdir: Some("props".into()), translation: [5.0, 0.0, 0.0], ..Default::default() })).unwrap();
+    let saved = game.block_on(ops::scene::export(game.client())).unwrap();
+    assert_eq!(saved.len(), 3);
+    assert!(saved.iter().any(|e| e.object == SceneObject::Model { path: "props/crate.glb".into() }));
+
+    // Change the scene, then put the snapshot back
+    game.block_on(ops::clear::clear(game.client(), ClearTarget::Primitives)).unwrap();
+    game.block_on(ops::spawn::spawn(game.client(), &primitive("cone", [0.0; 3], [1.0; 3]))).unwrap();
+    let diff = game.block_on(ops::scene::diff(game.client(), &saved)).unwrap();
+    assert_eq!((diff.added.len(), diff.removed.len(), diff.unchanged), (1, 2, 1));
+
+    let restored = game.block_on(ops::scene::restore(game.client(), &saved)).unwrap();
+    assert_eq!(restored, 3);
+    game.wait_for("the restored model's scene root", |world| {
+        world.query_filtered::<(), (With<AxiomSpawned>, With<SceneRoot>)>().iter(world).count() == 1
+    });
+    assert_eq!(game.spawned_count(), 3);
+    let now = game.block_on(ops::scene::diff(game.client(), &saved)).unwrap();
+    assert!(now.is_empty(), "{:?}", now);
+
+    // A move and a new group show up per component
+    let live = game.block_on(ops::scene::export(game.client())).unwrap();
+    let cube = live.iter().find(|e| e.object == SceneObject::Primitive { primitive_type: "cube".into() }).unwrap();
+    game.block_on(ops::transform::set_transform(game.client(), cube.entity, Some([1.0, 1.0, 0.0]), None, None)).unwrap();
+    let diff = game.block_on(ops::scene::diff(game.client(), &saved)).unwrap();
+    assert_eq!(diff.summary(), "0 added, 0 removed, 1 modified, 2 unchanged");
+    assert!(diff.modified[0].moved());
+}
+
+#[test]
+fn command_journal_undoes_and_redoes() {
+    let game = game();
+    game.reset();
+    let client = game.client();
+    let mut journal = CommandJournal::new();
+    let cube = SpawnRequest {
+        primitive: "cube".into(),
+        position: [0.0; 3],
+        rotation: IDENTITY,
+        scale: [1.0; 3],
+        material: PrimitiveMaterial::default(),
+        name: Some("crate".into()),
+        group: None,
+    };
+    let spawned = game.block_on(journal.spawn(client, cube)).unwrap();
+    let id = spawned.entity_id;
+    game.block_on(journal.insert_component(client, id, ops::clear::GROUP, serde_json::json!("props"))).unwrap();
+    game.block_on(journal.clear(client, ClearTarget::All)).unwrap();
+    assert_eq!(game.spawned_count(), 0);
+
+    // The clear comes back under a new id, still grouped
+    game.block_on(journal.undo(client)).unwrap();
+    assert_eq!(game.spawned_count(), 1);
+    let found = game.block_on(ops::find::by_name(client, "crate")).unwrap();
+    assert_ne!(found, vec![spawned.entity_id]);
+    let grouped = game.with_world(|world| world.query::<&AxiomGroup>().iter(world).map(|g| g.0.clone()).collect::<Vec<_>>());
+    assert_eq!(grouped, vec!["props".to_string()]);
+
+    // Undoing the insert and the spawn follow it there
+    game.block_on(journal.undo(client)).unwrap();
+    assert_eq!(game.with_world(|world| world.query::<&AxiomGroup>().iter(world).count()), 0);
+    assert_eq!(game.block_on(journal.undo(client)).unwrap().as_deref(), Some("Spawn crate (cube)"));
+    assert_eq!(game.spawned_count(), 0);
+    assert!(!journal.can_undo());
+
+    game.block_on(journal.redo(client)).unwrap();
+    assert_eq!(game.block_on(ops::find::by_name(client, "crate")).unwrap().len(), 1);
+}
+
+/// Past the journal's coalescing window, so the next change is its own step.
+fn settle() {
+    std::thread::sleep(Duration::from_millis(400));
+}
+
+fn translation_of(game: &axiom_e2e::TestGame, kind: &'static str) -> Vec<Vec3> {
+    game.with_world(move |world| {
+        world
+            .query_filtered::<(&Transform, &AxiomPrimitive), With<AxiomSpawned>>()
+            .iter(world)
+            .filter(|(_, primitive)| primitive.primitive_type == kind)
+            .map(|(transform, _)| transform.translation)
+            .collect()
+    })
+}
+
+#[test]
+fn undo_and_redo_step_through_editor_changes() {
+    let game = game();
+    game.reset();
+    settle();
+
+    let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    let cube = entity(response.entity_id);
+    settle();
+    game.with_world(move |world| world.get_mut::<Transform>(cube).unwrap().translation = Vec3::new(4.0, 0.0, 0.0));
+    settle();
+
+    let history = game.block_on(ops::history::history(game.client())).unwrap();
+    let labels: Vec<&str> = history.entries.iter().rev().take(2).map(|e| e.label.as_str()).collect();
+    assert_eq!(labels, ["Move entity", "Spawn cube"]);
+    assert!(!history.can_redo());
+    let cursor = history.cursor;
+
+    let step = game.block_on(ops::history::undo(game.client())).unwrap();
+    assert_eq!(step.label, "Move entity");
+    assert_eq!(translation_of(&game, "cube"), [Vec3::ZERO]);
+    game.block_on(ops::history::undo(game.client())).unwrap();
+    assert!(translation_of(&game, "cube").is_empty());
+    game.block_on(ops::history::redo(game.client())).unwrap();
+    let step = game.block_on(ops::history::redo(game.client())).unwrap();
+    assert_eq!(translation_of(&game, "cube"), [Vec3::new(4.0, 0.0, 0.0)]);
+    assert_eq!(step.history.cursor, cursor);
+    assert!(game.block_on(ops::history::redo(game.client())).is_err());
+
+    // A clear is one step, however many entities it removed
+    game.block_on(ops::spawn::spawn(game.client(), &primitive("sphere", [0.0; 3], [1.0; 3]))).unwrap();
+    settle();
+    game.block_on(ops::clear::clear(game.client(), ClearTarget::All)).unwrap();
+    settle();
+    assert_eq!(game.spawned_count(), 0);
+    let step = game.block_on(ops::history::undo(game.client())).unwrap();
+    assert_eq!(step.label, "Despawn 2");
+    assert_eq!(game.spawned_count(), 2);
+}
+
+#[test]
+fn profiling_records_schedule_timings() {
+    let game = game();
+    game.reset();
+
+    let capture = game.block_on(ops::profile::start(game.client(), Some(50))).unwrap();
+    assert!(capture.recording);
+    assert!(capture.frames.is_empty());
+    for _ in 0..3 {
+        game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    }
+    std::thread::sleep(Duration::from_millis(300));
+
+    let capture = game.block_on(ops::profile::stop(game.client())).unwrap();
+    assert!(!capture.recording);
+    assert!(!capture.frames.is_empty() && capture.frames.len() <= 50);
+    let last = capture.frames.last().unwrap();
+    assert!(last.entities >= 3);
+    let schedules: Vec<&str> = last.schedules.iter().map(|t| t.name.as_str()).collect();
+    assert!(schedules.contains(&"Update") && schedules.contains(&"Last"), "{:?}", schedules);
+    let scheduled: f64 = last.schedules.iter().map(|t| t.ms).sum();
+    assert!(scheduled <= last.total_ms);
+
+    // Stopped: nothing more is recorded, and what was stays readable
+    std::thread::sleep(Duration::from_millis(100));
+    let read = game.block_on(ops::profile::read(game.client())).unwrap();
+    assert_eq!(read.frames.len(), capture.frames.len());
+    assert_eq!(read.summary(5).frames, capture.frames.len());
+
+    let err = game.block_on(ops::raw::raw(game.client(), ops::profile::PROFILE_METHOD, Some(serde_json::json!({ "action": "pause" }))));
+    assert!(matches!(err, Err(BrpError::JsonRpc { .. })));
+}
+
+#[test]
+fn query_filters_and_pages() {
+    let game = game();
+    game.reset();
+    for (kind, y) in [("cube", 1.0), ("sphere", 2.0), ("cube", 3.0), ("cube", -1.0)] {
+        game.block_on(ops::spawn::spawn(game.client(), &primitive(kind, [0.0, y, 0.0], [1.0; 3]))).unwrap();
+    }
+    let own = game.with_world(|world| world.spawn(Transform::default()).id());
+    const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+    let options = QueryOptions {
+        with: vec!["bevy_ai_remote::AxiomSpawned".to_string()],
+        filter: Some(
+            Filter::field("bevy_ai_remote::AxiomPrimitive.primitive_type")
+                .eq("cube")
+                .and(Filter::field("Transform.translation.y").gt(0.0)),
+        ),
+        limit: Some(1),
+        ..Default::default()
+    };
+    let response = game.block_on(ops::query::query(game.client(), vec![TRANSFORM.to_string()], options)).unwrap();
+    assert_eq!(response.total, 2);
+    assert_eq!(response.entities.len(), 1);
+    assert!(response.entities[0]["components"].get("bevy_ai_remote::AxiomPrimitive").is_none());
+    let transforms = response.deserialize_components::<SceneTransform>().unwrap();
+    assert!(transforms[0].1.translation[1] > 0.0);
+
+    let high = QueryOptions {
+        with: vec!["bevy_ai_remote::AxiomSpawned".to_string()],
+        filter: Some(Filter::field("Transform.translation.y").gt(1.5)),
+        ..Default::default()
+    };
+    let response = game.block_on(ops::query::query(game.client(), vec!["bevy_ai_remote::AxiomPrimitive".to_string()], high)).unwrap();
+    assert_eq!(response.total, 2);
+    assert!(response.entities[0]["components"].get(TRANSFORM).is_none());
+
+    let without = QueryOptions { without: vec!["bevy_ai_remote::AxiomSpawned".to_string()], ..Default::default() };
+    let response = game.block_on(ops::query::query(game.client(), vec![TRANSFORM.to_string()], without)).unwrap();
+    let entities: Vec<u64> = response.entities.iter().filter_map(|row| row["entity"].as_u64()).collect();
+    assert!(entities.contains(&own.to_bits()));
+    assert_eq!(response.total, entities.len());
+    game.with_world(move |world| world.despawn(own));
+}
+
+#[test]
+fn get_entity_reads_components() {
+    let game = game();
+    game.reset();
+    let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [1.0, 2.0, 3.0], [1.0; 3]))).unwrap();
+    let id = response.entity_id;
+    const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+    let request = ops::get::get_entity(game.client(), id, vec![TRANSFORM.to_string(), "game::Missing".to_string()]);
+    let detail = game.block_on(request).unwrap();
+    assert_eq!(detail.entity, id);
+    assert_eq!(detail.components[TRANSFORM]["translation"], serde_json::json!([1.0, 2.0, 3.0]));
+    assert!(detail.errors.contains_key("game::Missing"));
+
+    let everything = game.block_on(ops::get::get_entity(game.client(), id, Vec::new())).unwrap();
+    assert!(everything.components.contains_key(TRANSFORM));
+    assert!(everything.components.contains_key("bevy_ai_remote::AxiomPrimitive"), "{:?}", everything.components.keys());
+}
+
+#[test]
+fn screenshot_capture_reports_a_missing_window() {
+    let game = game();
+    game.reset();
+    let requests = || game.with_world(|world| world.query::<&bevy_ai_remote::AxiomScreenshotRequest>().iter(world).count());
+
+    // The test game is headless, so there's nothing to render
+    let result = game.block_on(ops::screenshot::capture(game.client(), 320, 240));
+    assert!(matches!(result, Err(BrpError::InvalidResponse(msg)) if msg.contains("no window")));
+    assert_eq!(requests(), 0);
+}
+
+#[test]
+fn raycast_picks_the_nearest_mesh() {
+    let game = game();
+    game.reset();
+    let spawn = |y: f32| {
+        let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0, y, 0.0], [1.0; 3])));
+        entity(response.unwrap().entity_id)
+    };
+    let (top, bottom) = (spawn(2.0), spawn(0.0));
+    game.wait_for("the cubes in place", move |world| {
+        [top, bottom].iter().all(|&cube| world.get::<Mesh3d>(cube).is_some())
+            && world.get::<GlobalTransform>(top).is_some_and(|t| t.translation().y == 2.0)
+    });
+
+    let hit = game.block_on(ops::pick::raycast(game.client(), [0.0, 10.0, 0.0], [0.0, -1.0, 0.0])).unwrap().unwrap();
+    assert_eq!(entity(hit.entity), top);
+    assert_eq!(hit.point, [0.0, 2.5, 0.0]);
+    assert_eq!(hit.normal, [0.0, 1.0, 0.0]);
+    assert_eq!(hit.distance, 7.5);
+
+    let from_below = game.block_on(ops::pick::raycast(game.client(), [0.0, -10.0, 0.0], [0.0, 2.0, 0.0])).unwrap().unwrap();
+    assert_eq!(entity(from_below.entity), bottom);
+    assert_eq!(from_below.normal, [0.0, -1.0, 0.0]);
+
+    let miss = ops::pick::raycast(game.client(), [5.0, 10.0, 0.0], [0.0, -1.0, 0.0]);
+    assert_eq!(game.block_on(miss).unwrap(), None);
+    let invalid = ops::pick::raycast(game.client(), [0.0; 3], [0.0; 3]);
+    assert!(game.block_on(invalid).is_err());
+}
+
+#[test]
+fn gizmos_are_drawn_until_cleared_or_expired() {
+    let game = game();
+    game.reset();
+    let client = game.client();
+    let shapes = || game.with_world(|world| world.resource::<bevy_ai_remote::AxiomGizmos>().len());
+    let (red, minute) = ([1.0, 0.0, 0.0, 1.0], Duration::from_secs(60));
+    game.block_on(ops::gizmos::clear(client, None)).unwrap();
+
+    let line = game.block_on(ops::gizmos::draw_line(client, [0.0; 3], [0.0, 2.0, 0.0], red, minute)).unwrap();
+    let aabb = game.block_on(ops::gizmos::draw_aabb(client, [-0.5; 3], [0.5; 3], red, minute)).unwrap();
+    game.block_on(ops::gizmos::draw_sphere(client, [0.0; 3], 1.0, red, minute)).unwrap();
+    assert_ne!(line, aabb);
+    assert_eq!(shapes(), 3);
+
+    assert_eq!(game.block_on(ops::gizmos::clear(client, Some(aabb))).unwrap(), 1);
+    assert_eq!(game.block_on(ops::gizmos::clear(client, Some(aabb))).unwrap(), 0);
+    assert_eq!(game.block_on(ops::gizmos::clear(client, None)).unwrap(), 2);
+
+    game.block_on(ops::gizmos::draw_sphere(client, [0.0; 3], 1.0, red, Duration::from_millis(100))).unwrap();
+    game.wait_for("the sphere to expire", |world| world.resource::<bevy_ai_remote::AxiomGizmos>().is_empty());
+
+    let invalid = client.send_rpc(ops::gizmos::DRAW_METHOD, Some(serde_json::json!({ "kind": "torus" })));
+    assert!(game.block_on(invalid).is_err());
+}
+
+#[test]
+fn simulation_pauses_steps_and_scales() {
+    let game = game();
+    let client = game.client();
+    let virtual_time = || game.with_world(|world| *world.resource::<Time<Virtual>>());
+
+    game.block_on(ops::sim::pause(client)).unwrap();
+    game.wait_for("virtual time to pause", |world| world.resource::<Time<Virtual>>().is_paused());
+    let paused_at = virtual_time().elapsed();
+    settle();
+    assert_eq!(virtual_time().elapsed(), paused_at);
+    assert!(game.block_on(ops::sim::state(client)).unwrap().paused);
+
+    game.block_on(ops::sim::step(client, 3)).unwrap();
+    game.wait_for("the steps to run", |world| {
+        world.resource::<bevy_ai_remote::AxiomSimControl>().step_frames == 0 && world.resource::<Time<Virtual>>().is_paused()
+    });
+    let stepped_to = virtual_time().elapsed();
+    assert!(stepped_to > paused_at);
+    settle();
+    assert_eq!(virtual_time().elapsed(), stepped_to);
+
+    // Resuming while a step is still running cancels it, rather than the
+    // step ending and pausing the game again
+    game.block_on(ops::sim::step(client, 2)).unwrap();
+    game.block_on(ops::sim::resume(client)).unwrap();
+    settle();
+    assert!(!virtual_time().is_paused());
+    game.block_on(ops::sim::pause(client)).unwrap();
+    game.wait_for("virtual time to pause again", |world| world.resource::<Time<Virtual>>().is_paused());
+
+    game.block_on(ops::sim::set_time_scale(client, 0.5)).unwrap();
+    game.block_on(ops::sim::resume(client)).unwrap();
+    game.wait_for("virtual time to resume", |world| !world.resource::<Time<Virtual>>().is_paused());
+    assert_eq!(virtual_time().relative_speed(), 0.5);
+    let state = game.block_on(ops::sim::state(client)).unwrap();
+    assert_eq!(state, SimState { paused: false, step_frames: 0, time_scale: 0.5 });
+
+    game.block_on(ops::sim::set_time_scale(client, 1.0)).unwrap();
+    game.wait_for("real speed", |world| world.resource::<Time<Virtual>>().relative_speed() == 1.0);
+}
+
+#[test]
+fn logs_are_tailed_by_level() {
+    let game = game();
+    game.with_world(|_| {
+        warn!(crate_id = 7, "Crate is on fire");
+        info!("Crate inspected");
+    });
+
+    let warnings = game.block_on(ops::logs::tail(game.client(), LogLevel::Warn, 50)).unwrap();
+    let fire = warnings.iter().find(|r| r.message == "Crate is on fire").expect("the warning");
+    assert_eq!((fire.level, fire.fields["crate_id"].clone()), (LogLevel::Warn, serde_json::json!(7)));
+    assert!(warnings.iter().all(|r| r.level >= LogLevel::Warn));
+
+    let newest = game.block_on(ops::logs::tail(game.client(), LogLevel::Info, 1)).unwrap();
+    assert_eq!(newest.len(), 1);
+    assert!(newest[0].seq > fire.seq);
+    let after = game.block_on(ops::logs::since(game.client(), newest[0].seq, LogLevel::Trace, 50)).unwrap();
+    assert!(after.iter().all(|r| r.seq > newest[0].seq));
+}
+
+#[test]
+fn components_are_inserted_and_removed() {
+    let game = game();
+    game.reset();
+    let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    let id = response.entity_id;
+    let cube = entity(id);
+    const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+    let transform = serde_json::json!({ "translation": [1.0, 2.0, 3.0], "rotation": IDENTITY, "scale": [1.0, 1.0, 1.0] });
+    let inserted = game.block_on(ops::components::insert_component(game.client(), id, TRANSFORM, transform)).unwrap();
+    assert_eq!(inserted.components, vec![TRANSFORM]);
+    let translation = game.with_world(move |world| world.get::<Transform>(cube).unwrap().translation);
+    assert_eq!(translation, Vec3::new(1.0, 2.0, 3.0));
+
+    let removed = game.block_on(ops::components::remove_component(game.client(), id, TRANSFORM)).unwrap();
+    assert_eq!(removed.entity, id);
+    assert!(game.with_world(move |world| world.get::<Transform>(cube).is_none()));
+
+    let missing = ops::components::remove_component(game.client(), EntityId(Entity::PLACEHOLDER.to_bits()), TRANSFORM);
+    assert!(matches!(game.block_on(missing), Err(BrpError::EntityNotFound { .. })));
+}
+
+#[test]
+fn set_transform_updates_only_the_given_parts() {
+    let game = game();
+    game.reset();
+    let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [2.0; 3]))).unwrap();
+    let id = response.entity_id;
+    let cube = entity(id);
+    let transform = || game.with_world(move |world| *world.get::<Transform>(cube).unwrap());
+
+    game.block_on(ops::transform::set_transform(game.client(), id, Some([1.0, 2.0, 3.0]), None, None)).unwrap();
+    assert_eq!(transform().translation, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(transform().scale, Vec3::splat(2.0));
+
+    let quarter_turn = [0.0, 0.70710677, 0.0, 0.70710677];
+    game.block_on(ops::transform::set_transform(game.client(), id, None, Some(quarter_turn), Some([1.0; 3]))).unwrap();
+    assert_eq!(transform().translation, Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(transform().rotation, Quat::from_array(quarter_turn));
+    assert_eq!(transform().scale, Vec3::ONE);
+
+    game.block_on(ops::transform::set_transform(game.client(), id, Some([0.0; 3]), Some(IDENTITY), Some([3.0; 3]))).unwrap();
+    assert_eq!(transform(), Transform::from_scale(Vec3::splat(3.0)));
+}
+
+#[test]
+fn reparent_and_detach_build_hierarchies() {
+    let game = game();
+    game.reset();
+    let spawn = |kind: &'static str| {
+        game.block_on(ops::spawn::spawn(game.client(), &primitive(kind, [0.0; 3], [1.0; 3]))).unwrap().entity_id
+    };
+    let (parent, child) = (spawn("cube"), spawn("sphere"));
+
+    game.block_on(ops::hierarchy::reparent(game.client(), child, parent)).unwrap();
+    assert_eq!(game.with_world(move |world| world.get::<ChildOf>(entity(child)).map(ChildOf::parent)), Some(entity(parent)));
+    let children = game.block_on(ops::hierarchy::get_children(game.client(), parent)).unwrap();
+    assert_eq!(children, vec![child]);
+
+    game.block_on(ops::hierarchy::detach(game.client(), child)).unwrap();
+    assert!(game.with_world(move |world| world.get::<ChildOf>(entity(child)).is_none()));
+    assert!(game.block_on(ops::hierarchy::get_children(game.client(), parent)).unwrap().is_empty());
+}
+
+#[test]
+fn despawn_takes_descendants_only_when_recursive() {
+    let game = game();
+    game.reset();
+    let spawn = || {
+        game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap().entity_id
+    };
+    let (root, child, grandchild) = (spawn(), spawn(), spawn());
+    game.block_on(ops::hierarchy::reparent(game.client(), child, root)).unwrap();
+    game.block_on(ops::hierarchy::reparent(game.client(), grandchild, child)).unwrap();
+
+    let response = game.block_on(ops::despawn::despawn(game.client(), root, false)).unwrap();
+    assert_eq!(response.entities_removed, 1);
+    assert_eq!(game.spawned_count(), 2);
+    assert!(game.with_world(move |world| world.get::<ChildOf>(entity(child)).is_none()));
+
+    let response = game.block_on(ops::despawn::despawn(game.client(), child, true)).unwrap();
+    assert_eq!(response.entities_removed, 2);
+    assert_eq!(game.spawned_count(), 0);
+
+    let missing = game.block_on(ops::despawn::despawn(game.client(), child, true));
+    assert!(matches!(missing, Err(BrpError::EntityNotFound { .. })), "{:?}", missing);
+}
+
+#[test]
+fn resources_are_read_and_written() {
+    let game = game();
+    const VIRTUAL_TIME: &str = "bevy_time::time::Time<bevy_time::virt::Virtual>";
+    let resources = game.block_on(ops::list::list_resources(game.client())).unwrap();
+    assert!(resources.iter().any(|r| r == VIRTUAL_TIME), "{:?}", resources);
+
+    let mut time = game.block_on(ops::resources::get_resource(game.client(), VIRTUAL_TIME)).unwrap();
+    assert_eq!(time["context"]["paused"], false);
+    time["context"]["paused"] = true.into();
+    game.block_on(ops::resources::insert_resource(game.client(), VIRTUAL_TIME, time.clone())).unwrap();
+    assert!(game.with_world(|world| world.resource::<Time<Virtual>>().is_paused()));
+
+    time["context"]["paused"] = false.into();
+    game.block_on(ops::resources::insert_resource(game.client(), VIRTUAL_TIME, time)).unwrap();
+    assert!(!game.with_world(|world| world.resource::<Time<Virtual>>().is_paused()));
+
+    let missing = game.block_on(ops::resources::get_resource(game.client(), "game::Missing"));
+    assert!(matches!(missing, Err(BrpError::JsonRpc { .. })));
+}
+
+#[test]
+fn batches_answer_each_request() {
+    let game = game();
+    game.reset();
+    let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    let id = response.entity_id;
+
+    let batch = vec![
+        ("world.despawn_entity", Some(serde_json::json!({ "entity": id }))),
+        ("world.despawn_entity", Some(serde_json::json!({ "entity": id }))),
+        ("rpc.discover", None),
+    ];
+    let results = game.block_on(game.client().send_batch(batch)).unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok(), "{:?}", results[0]);
+    assert!(matches!(results[1], Err(BrpError::EntityNotFound { .. })), "{:?}", results[1]);
+    assert!(results[2].as_ref().unwrap().get("methods").is_some());
+    assert_eq!(game.spawned_count(), 0);
+}
+
+#[test]
+fn watch_pushes_component_changes() {
+    let game = game();
+    game.reset();
+    let response = game.block_on(ops::spawn::spawn(game.client(), &primitive("cube", [0.0; 3], [1.0; 3]))).unwrap();
+    let id = response.entity_id;
+    let cube = entity(id);
+    const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+    let next = |changes: &mut ops::watch::ChangeStream| {
+        game.block_on(async { tokio::time::timeout(Duration::from_secs(5), changes.next()).await })
+            .expect("a change within 5s")
+            .expect("an open stream")
+    };
+    let mut changes = game.block_on(ops::watch::components(game.client(), id, vec![TRANSFORM.to_string()])).unwrap();
+    game.with_world(move |world| world.get_mut::<Transform>(cube).unwrap().translation.x = 4.0);
+    let moved = next(&mut changes).unwrap();
+    assert_eq!(moved.entity, id);
+    assert_eq!(moved.components[TRANSFORM]["translation"], serde_json::json!([4.0, 0.0, 0.0]));
+
+    game.with_world(move |world| world.despawn(cube));
+    assert!(matches!(next(&mut changes), Err(BrpError::EntityNotFound { .. })));
+    assert!(game.block_on(changes.next()).is_none());
+}
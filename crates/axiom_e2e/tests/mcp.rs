@@ -0,0 +1,155 @@
+//! `bevy_mcp_server` tool calls against a running game, over stdio as an
+//! MCP client would make them.
+
+use axiom_e2e::{entity, game, workspace_bin, TOKEN};
+use bevy::prelude::*;
+use bevy_ai_remote::AxiomPrimitive;
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+struct McpServer {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: u64,
+}
+
+impl McpServer {
+    fn start(endpoint: &str) -> Self {
+        let mut child = Command::new(workspace_bin("bevy_mcp_server"))
+            .env("BRP_ENDPOINT", endpoint)
+            .env("BRP_TOKEN", TOKEN)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("start bevy_mcp_server");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        let mut server = Self { child, stdin, stdout, next_id: 1 };
+
+        let info = server.request(
+            "initialize",
+            json!({
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "clientInfo": { "name": "axiom_e2e", "version": "0" }
+            }),
+        );
+        assert!(info["capabilities"]["tools"].is_object(), "{}", info);
+        server.send(json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }));
+        server
+    }
+
+    fn send(&mut self, message: Value) {
+        writeln!(self.stdin, "{}", message).unwrap();
+        self.stdin.flush().unwrap();
+    }
+
+    /// The `result` of a request; panics on a JSON-RPC error.
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        loop {
+            let mut line = String::new();
+            assert!(self.stdout.read_line(&mut line).unwrap() > 0, "server exited during {}", method);
+            let Ok(message) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            if message["id"] == json!(id) {
+                assert!(message.get("error").is_none(), "{} failed: {}", method, message);
+                return message["result"].clone();
+            }
+        }
+    }
+
+    /// A tool's structured result; panics if the tool reported an error.
+    fn call(&mut self, tool: &str, arguments: Value) -> Value {
+        let result = self.request("tools/call", json!({ "name": tool, "arguments": arguments }));
+        assert_ne!(result["isError"], json!(true), "{} failed: {}", tool, result);
+        result["structuredContent"].clone()
+    }
+}
+
+impl Drop for McpServer {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn tools_drive_the_game() {
+    let game = game();
+    game.reset();
+    let mut server = McpServer::start(game.endpoint());
+
+    let tools = server.request("tools/list", json!({}));
+    let names: Vec<&str> = tools["tools"].as_array().unwrap().iter().filter_map(|t| t["name"].as_str()).collect();
+    for tool in ["bevy_ping", "bevy_metrics", "bevy_spawn_primitive", "bevy_spawn_primitives", "bevy_upload_asset", "bevy_clear_scene", "bevy_query", "bevy_profile", "bevy_list_types", "bevy_find_by_name", "bevy_screenshot", "bevy_logs"] {
+        assert!(names.contains(&tool), "{} missing from {:?}", tool, names);
+    }
+
+    assert_eq!(server.call("bevy_ping", json!({}))["alive"], true);
+
+    let spawned = server.call("bevy_spawn_primitive", json!({ "primitive_type": "Torus", "position": [0.0, 5.0, 0.0], "name": "ring" }));
+    let torus = entity(serde_json::from_value(spawned["entity_id"].clone()).unwrap());
+    assert_eq!(server.call("bevy_find_by_name", json!({ "name": "ring" }))["entity_ids"], json!([spawned["entity_id"]]));
+    game.wait_for("the torus's mesh", move |world| world.get::<Mesh3d>(torus).is_some());
+    let (kind, translation) = game.with_world(move |world| {
+        let entity = world.entity(torus);
+        (entity.get::<AxiomPrimitive>().unwrap().primitive_type.clone(), entity.get::<Transform>().unwrap().translation)
+    });
+    assert_eq!(kind, "torus");
+    assert_eq!(translation, Vec3::new(0.0, 5.0, 0.0));
+
+    let queried = server.call("bevy_query", json!({ "components": ["bevy_ai_remote::AxiomPrimitive"] }));
+    assert_eq!(queried["entities"].as_array().unwrap().len(), 1);
+    let filtered = server.call(
+        "bevy_query",
+        json!({ "components": ["bevy_ai_remote::AxiomPrimitive"], "where": [{ "component": "bevy_ai_remote::AxiomPrimitive", "path": "primitive_type", "op": "eq", "value": "cube" }] }),
+    );
+    assert_eq!(filtered["total"], 0);
+
+    let types = server.call("bevy_list_types", json!({}));
+    let components: Vec<&str> = types["components"].as_array().unwrap().iter().filter_map(|c| c.as_str()).collect();
+    assert!(components.contains(&"bevy_ai_remote::AxiomPrimitive"), "{:?}", components);
+    assert!(components.windows(2).all(|w| w[0] < w[1]));
+    assert!(types["resources"].is_array());
+
+    let row = server.call(
+        "bevy_spawn_primitives",
+        json!({ "primitives": [
+            { "primitive_type": "sphere", "position": [0.0, 0.0, 0.0], "group": "row" },
+            { "primitive_type": "sphere", "position": [2.0, 0.0, 0.0], "group": "row" }
+        ] }),
+    );
+    assert_eq!(row["entity_ids"].as_array().unwrap().len(), 2);
+    assert_eq!(row["errors"], json!([]));
+    assert_eq!(game.spawned_count(), 3);
+
+    let cleared = server.call("bevy_clear_scene", json!({ "target": "group", "group": "row" }));
+    assert_eq!(cleared["entities_removed"], 2);
+    let cleared = server.call("bevy_clear_scene", json!({ "target": "all" }));
+    assert_eq!(cleared["entities_removed"], 1);
+    assert_eq!(game.spawned_count(), 0);
+}
+
+#[test]
+fn upload_tool_writes_the_cache() {
+    let game = game();
+    game.reset();
+    let mut server = McpServer::start(game.endpoint());
+
+    use base64::Engine as _;
+    let data = base64::engine::general_purpose::STANDARD.encode(b"glTF");
+    let uploaded = server.call(
+        "bevy_upload_asset",
+        json!({ "filename": "barrel.glb", "data_base64": data, "subdir": "props", "translation": [1.0, 0.0, 1.0] }),
+    );
+    let barrel = entity(serde_json::from_value(uploaded["entity_id"].clone()).unwrap());
+    game.wait_for("the barrel's scene root", move |world| world.get::<SceneRoot>(barrel).is_some());
+    assert_eq!(std::fs::read(game.cache_dir().join("props").join("barrel.glb")).unwrap(), b"glTF");
+}
@@ -0,0 +1,96 @@
+//! Optional shared secret for BRP requests, so a game listening beyond
+//! localhost can't be driven by anyone who reaches the port.
+//!
+//! `bevy_remote` hands only the method and params of a request to the world,
+//! not its HTTP headers, so the token travels in the params as
+//! [`TOKEN_PARAM`]. Once a token is set, [`gate_requests`] takes over the BRP
+//! mailbox: requests with the right token are passed on without it, the rest
+//! are answered with an error and never reach a method handler.
+
+use bevy::prelude::*;
+use bevy_remote::{BrpError, BrpMessage, BrpReceiver};
+use serde_json::Value;
+
+/// Params key carrying the token.
+pub const TOKEN_PARAM: &str = "axiom_token";
+
+/// Environment variable read for the token when the app doesn't insert
+/// [`AxiomAuthToken`] itself.
+pub const TOKEN_ENV: &str = "BRP_TOKEN";
+
+/// Read when [`TOKEN_ENV`] isn't set.
+pub const AUTH_TOKEN_ENV: &str = "BRP_AUTH_TOKEN";
+
+/// JSON-RPC error code answering a request without a valid token.
+pub const UNAUTHORIZED_CODE: i16 = -32001;
+
+/// The token every BRP request must carry. Insert it before adding
+/// [`crate::BevyAiRemotePlugin`], or set [`TOKEN_ENV`] (or [`AUTH_TOKEN_ENV`]).
+#[derive(Resource, Clone)]
+pub struct AxiomAuthToken(pub String);
+
+impl AxiomAuthToken {
+    pub(crate) fn from_env() -> Option<Self> {
+        [TOKEN_ENV, AUTH_TOKEN_ENV]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .map(|token| token.trim().to_string())
+            .find(|token| !token.is_empty())
+            .map(Self)
+    }
+
+    /// Compared in constant time.
+    fn matches(&self, given: &str) -> bool {
+        let (expected, given) = (self.0.as_bytes(), given.as_bytes());
+        expected.len() == given.len() && expected.iter().zip(given).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+    }
+}
+
+/// The HTTP server's end of the mailbox, and the way into the one
+/// `bevy_remote` now reads.
+pub(crate) struct Gate {
+    pub(crate) incoming: async_channel::Receiver<BrpMessage>,
+    pub(crate) accepted: async_channel::Sender<BrpMessage>,
+}
+
+impl Gate {
+    /// Take over `receiver`'s mailbox, handing `bevy_remote` a new one.
+    pub(crate) fn install(receiver: &mut BrpReceiver) -> Self {
+        let (accepted, inbox) = async_channel::unbounded();
+        let incoming = std::mem::replace(&mut **receiver, inbox);
+        Self { incoming, accepted }
+    }
+}
+
+/// Remove the token from `params`, returning it.
+fn take_token(params: &mut Option<Value>) -> Option<String> {
+    let object = params.as_mut()?.as_object_mut()?;
+    let token = object.remove(TOKEN_PARAM)?;
+    if object.is_empty() {
+        *params = None;
+    }
+    token.as_str().map(str::to_string)
+}
+
+/// Pass on the requests carrying the token; runs before `bevy_remote`
+/// processes them.
+pub(crate) fn gate_requests(
+    token: Res<AxiomAuthToken>,
+    mut receiver: ResMut<BrpReceiver>,
+    mut gate: Local<Option<Gate>>,
+) {
+    let gate = gate.get_or_insert_with(|| Gate::install(&mut receiver));
+    while let Ok(mut message) = gate.incoming.try_recv() {
+        if take_token(&mut message.params).is_some_and(|given| token.matches(&given)) {
+            let _ = gate.accepted.force_send(message);
+            continue;
+        }
+        warn!("Rejected BRP request {} without a valid token", message.method);
+        let _ = message.sender.force_send(Err(BrpError {
+            code: UNAUTHORIZED_CODE,
+            message: format!("Missing or wrong `{}`", TOKEN_PARAM),
+            data: None,
+        }));
+    }
+}
+
@@ -0,0 +1,69 @@
+//! Gzipped params from the editor, for large requests on slow links.
+//!
+//! `bevy_remote` reads request bodies as plain JSON and keeps HTTP headers
+//! from the world, so a `Content-Encoding` can't be honored. The editor
+//! instead sends the params as [`COMPRESSED_PARAM`], their JSON gzipped and
+//! base64-encoded, and [`inflate_requests`] swaps the originals back in
+//! before any method handler sees them.
+
+use crate::auth::Gate;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpReceiver};
+use flate2::read::GzDecoder;
+use serde_json::Value;
+use std::io::Read;
+
+/// Params key carrying the compressed params.
+pub const COMPRESSED_PARAM: &str = "axiom_gzip";
+
+/// Largest params accepted once inflated, so a small request can't expand
+/// into an exhausted heap.
+const MAX_INFLATED: u64 = 64 * 1024 * 1024;
+
+/// Inflate compressed params; runs before `bevy_remote` processes requests,
+/// after the token is checked.
+pub(crate) fn inflate_requests(mut receiver: ResMut<BrpReceiver>, mut gate: Local<Option<Gate>>) {
+    let gate = gate.get_or_insert_with(|| Gate::install(&mut receiver));
+    while let Ok(mut message) = gate.incoming.try_recv() {
+        match inflate(&mut message.params) {
+            Ok(()) => {
+                let _ = gate.accepted.force_send(message);
+            }
+            Err(e) => {
+                warn!("Rejected BRP request {}: {}", message.method, e);
+                let _ = message.sender.force_send(Err(BrpError {
+                    code: error_codes::INVALID_PARAMS,
+                    message: e,
+                    data: None,
+                }));
+            }
+        }
+    }
+}
+
+/// Replace compressed `params` with the originals. Anything sent beside
+/// them, such as the token, is kept.
+fn inflate(params: &mut Option<Value>) -> Result<(), String> {
+    let Some(object) = params.as_mut().and_then(Value::as_object_mut) else { return Ok(()) };
+    let Some(packed) = object.remove(COMPRESSED_PARAM) else { return Ok(()) };
+    let packed = packed.as_str().ok_or_else(|| format!("`{}` isn't a string", COMPRESSED_PARAM))?;
+    let gzipped = BASE64.decode(packed).map_err(|e| format!("Invalid base64 in `{}`: {}", COMPRESSED_PARAM, e))?;
+
+    let mut json = Vec::new();
+    GzDecoder::new(gzipped.as_slice())
+        .take(MAX_INFLATED + 1)
+        .read_to_end(&mut json)
+        .map_err(|e| format!("Invalid gzip in `{}`: {}", COMPRESSED_PARAM, e))?;
+    if json.len() as u64 > MAX_INFLATED {
+        return Err(format!("Params inflate to over {} bytes", MAX_INFLATED));
+    }
+    let mut inflated: Value = serde_json::from_slice(&json).map_err(|e| format!("Invalid compressed params: {}", e))?;
+
+    let rest = std::mem::take(object);
+    if let Some(inflated) = inflated.as_object_mut() {
+        inflated.extend(rest);
+    }
+    *params = Some(inflated);
+    Ok(())
+}
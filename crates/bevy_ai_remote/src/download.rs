@@ -0,0 +1,62 @@
+//! Reading files back out of the game's `assets` directory, such as a scene
+//! the game saved, a range at a time so big files take several requests.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Component, Path};
+
+/// BRP method reading part of a file under `assets/`.
+pub const DOWNLOAD_METHOD: &str = "axiom.download";
+
+const ASSETS_DIR: &str = "assets";
+
+/// Largest range one request answers with.
+const MAX_LENGTH: u64 = 4 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct DownloadParams {
+    path: String,
+    #[serde(default)]
+    offset: u64,
+    length: Option<u64>,
+}
+
+/// Handler for `axiom.download`: `{"path", "offset", "length"}` with `path`
+/// relative to `assets/`. Answers `{"size", "offset", "data_base64"}`, the
+/// data being at most 4 MiB from `offset`.
+pub(crate) fn download_method(In(params): In<Option<Value>>) -> BrpResult {
+    let params: DownloadParams = serde_json::from_value(params.unwrap_or_default()).map_err(|e| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+        data: None,
+    })?;
+    let relative = Path::new(&params.path);
+    if params.path.is_empty() || !relative.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(BrpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Refusing to read outside the assets directory: {}", params.path),
+            data: None,
+        });
+    }
+
+    let read = || -> std::io::Result<Value> {
+        let mut file = File::open(Path::new(ASSETS_DIR).join(relative))?;
+        let size = file.metadata()?.len();
+        let offset = params.offset.min(size);
+        let length = params.length.unwrap_or(MAX_LENGTH).min(MAX_LENGTH);
+        file.seek(SeekFrom::Start(offset))?;
+        let mut data = Vec::new();
+        file.take(length).read_to_end(&mut data)?;
+        Ok(json!({ "size": size, "offset": offset, "data_base64": BASE64.encode(data) }))
+    };
+    read().map_err(|e| BrpError {
+        code: error_codes::INTERNAL_ERROR,
+        message: format!("Failed to read {}: {}", params.path, e),
+        data: None,
+    })
+}
@@ -0,0 +1,122 @@
+//! Lines, boxes and spheres the editor draws over the scene for a while,
+//! such as to highlight what it is about to change. They're drawn with
+//! bevy's gizmos, so only in games with `GizmoPlugin`, as in `DefaultPlugins`.
+
+use bevy::math::bounding::Aabb3d;
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// BRP method adding a shape to draw.
+pub const DRAW_GIZMO_METHOD: &str = "axiom.draw_gizmo";
+
+/// BRP method removing one shape, or all of them.
+pub const CLEAR_GIZMOS_METHOD: &str = "axiom.clear_gizmos";
+
+/// Longest a shape is drawn for.
+const MAX_TTL: Duration = Duration::from_secs(3600);
+
+/// Shapes drawn for the editor until they expire.
+#[derive(Resource, Default, Debug)]
+pub struct AxiomGizmos {
+    shapes: Vec<Annotation>,
+    next_id: u64,
+}
+
+impl AxiomGizmos {
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+}
+
+#[derive(Debug)]
+struct Annotation {
+    id: u64,
+    shape: Shape,
+    color: Color,
+    /// Real time, so shapes still expire while the game is paused.
+    expires: Duration,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Shape {
+    Line { start: [f32; 3], end: [f32; 3] },
+    Aabb { min: [f32; 3], max: [f32; 3] },
+    Sphere { center: [f32; 3], radius: f32 },
+}
+
+#[derive(Deserialize)]
+struct DrawParams {
+    #[serde(flatten)]
+    shape: Shape,
+    color: [f32; 4],
+    ttl_secs: f32,
+}
+
+/// Handler for `axiom.draw_gizmo`: a `"kind"` of `line` (`"start"`, `"end"`),
+/// `aabb` (`"min"`, `"max"`) or `sphere` (`"center"`, `"radius"`), with
+/// `"color"` as sRGBA and `"ttl_secs"`. Answers `{"id"}` for
+/// `axiom.clear_gizmos`.
+pub(crate) fn draw_gizmo_method(
+    In(params): In<Option<Value>>,
+    mut gizmos: ResMut<AxiomGizmos>,
+    time: Res<Time<Real>>,
+) -> BrpResult {
+    let params: DrawParams = serde_json::from_value(params.unwrap_or_default()).map_err(|e| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+        data: None,
+    })?;
+    let ttl = Duration::try_from_secs_f32(params.ttl_secs).map_err(|_| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: format!("Invalid ttl_secs: {}", params.ttl_secs),
+        data: None,
+    })?;
+
+    gizmos.next_id += 1;
+    let id = gizmos.next_id;
+    let [red, green, blue, alpha] = params.color;
+    gizmos.shapes.push(Annotation {
+        id,
+        shape: params.shape,
+        color: Color::srgba(red, green, blue, alpha),
+        expires: time.elapsed() + ttl.min(MAX_TTL),
+    });
+    Ok(json!({ "id": id }))
+}
+
+/// Handler for `axiom.clear_gizmos`: `{"id"}` removes that shape, no params
+/// all of them. Answers `{"removed"}`.
+pub(crate) fn clear_gizmos_method(In(params): In<Option<Value>>, mut gizmos: ResMut<AxiomGizmos>) -> BrpResult {
+    let id = params.as_ref().and_then(|p| p.get("id")).and_then(Value::as_u64);
+    let before = gizmos.shapes.len();
+    gizmos.shapes.retain(|a| id.is_some_and(|id| a.id != id));
+    Ok(json!({ "removed": before - gizmos.shapes.len() }))
+}
+
+/// Forget expired shapes, whether or not they're drawn.
+pub(crate) fn expire_gizmos(mut gizmos: ResMut<AxiomGizmos>, time: Res<Time<Real>>) {
+    let now = time.elapsed();
+    gizmos.shapes.retain(|a| a.expires > now);
+}
+
+pub(crate) fn draw_gizmos(annotations: Res<AxiomGizmos>, mut gizmos: Gizmos) {
+    for annotation in &annotations.shapes {
+        match annotation.shape {
+            Shape::Line { start, end } => gizmos.line(start.into(), end.into(), annotation.color),
+            Shape::Aabb { min, max } => {
+                gizmos.aabb_3d(Aabb3d { min: min.into(), max: max.into() }, Transform::IDENTITY, annotation.color)
+            }
+            Shape::Sphere { center, radius } => {
+                gizmos.sphere(Isometry3d::from_translation(Vec3::from(center)), radius, annotation.color);
+            }
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! Journal of the changes to editor-spawned entities, so the editor can undo
+//! and redo them over BRP.
+//!
+//! Changes are found each frame by comparing the entities with
+//! [`AxiomSpawned`] against their last known state, which covers every way
+//! the editor mutates the world (spawns, transform writes, despawns, clears)
+//! without special methods. Changes arriving within [`COALESCE`] of each
+//! other form one entry, so a clear or a batch spawn is undone in one step.
+//! Undo and redo update the known state themselves and are not journaled.
+
+use crate::{AxiomCachedAsset, AxiomGroup, AxiomPrimitive, AxiomRemoteAsset, AxiomSpawned};
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// BRP method listing the journal.
+pub const HISTORY_METHOD: &str = "axiom.history";
+
+/// BRP method reverting the latest applied entry.
+pub const UNDO_METHOD: &str = "axiom.undo";
+
+/// BRP method reapplying the latest undone entry.
+pub const REDO_METHOD: &str = "axiom.redo";
+
+/// Entries kept; the oldest are dropped beyond this.
+const MAX_ENTRIES: usize = 200;
+
+/// Changes closer together than this are one entry.
+const COALESCE: Duration = Duration::from_millis(300);
+
+/// What an entity shows, enough to spawn it again.
+#[derive(Clone, Debug, PartialEq)]
+enum Kind {
+    Primitive(AxiomPrimitive),
+    /// A model in `_remote_cache`, by its path there.
+    Model(String),
+}
+
+#[derive(Clone, Debug)]
+struct EntityState {
+    kind: Kind,
+    name: Option<Name>,
+    group: Option<AxiomGroup>,
+    transform: Transform,
+}
+
+#[derive(Clone, Debug)]
+enum Change {
+    Spawned { entity: Entity, state: EntityState },
+    Despawned { entity: Entity, state: EntityState },
+    Moved { entity: Entity, before: Transform, after: Transform },
+}
+
+struct Entry {
+    id: u64,
+    label: String,
+    changes: Vec<Change>,
+}
+
+#[derive(Resource, Default)]
+pub struct AxiomJournal {
+    entries: Vec<Entry>,
+    /// Entries before this are applied; the rest can be redone.
+    cursor: usize,
+    known: HashMap<Entity, EntityState>,
+    next_id: u64,
+    last_change: Option<Instant>,
+}
+
+impl AxiomJournal {
+    fn record(&mut self, changes: Vec<Change>) {
+        let now = Instant::now();
+        let recent = self.last_change.is_some_and(|t| now - t < COALESCE);
+        self.last_change = Some(now);
+
+        if recent && self.cursor == self.entries.len() && self.cursor > 0 {
+            let entry = &mut self.entries[self.cursor - 1];
+            for change in changes {
+                merge(&mut entry.changes, change);
+            }
+            entry.label = label(&entry.changes);
+            return;
+        }
+
+        self.entries.truncate(self.cursor);
+        self.next_id += 1;
+        self.entries.push(Entry { id: self.next_id, label: label(&changes), changes });
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+        self.cursor = self.entries.len();
+    }
+
+    /// Point the journal at `new` after `old` was spawned again.
+    fn replace_entity(&mut self, old: Entity, new: Entity) {
+        for change in self.entries.iter_mut().flat_map(|e| e.changes.iter_mut()) {
+            match change {
+                Change::Spawned { entity, .. } | Change::Despawned { entity, .. } | Change::Moved { entity, .. } => {
+                    if *entity == old {
+                        *entity = new;
+                    }
+                }
+            }
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let entries: Vec<Value> = self
+            .entries
+            .iter()
+            .map(|e| json!({ "id": e.id, "label": e.label, "changes": e.changes.len() }))
+            .collect();
+        json!({ "entries": entries, "cursor": self.cursor })
+    }
+}
+
+/// Add `change` to an entry, folding repeated moves of one entity together.
+fn merge(changes: &mut Vec<Change>, change: Change) {
+    if let Change::Moved { entity, after, .. } = &change {
+        for existing in changes.iter_mut() {
+            match existing {
+                Change::Moved { entity: e, after: a, .. } if e == entity => {
+                    *a = *after;
+                    return;
+                }
+                Change::Spawned { entity: e, state } if e == entity => {
+                    state.transform = *after;
+                    return;
+                }
+                _ => {}
+            }
+        }
+    }
+    changes.push(change);
+}
+
+fn kind_name(kind: &Kind) -> &str {
+    match kind {
+        Kind::Primitive(primitive) => &primitive.primitive_type,
+        Kind::Model(name) => name,
+    }
+}
+
+/// E.g. `Spawn cube` for one change, `Spawn 3, move 1` for several.
+fn label(changes: &[Change]) -> String {
+    if let [change] = changes {
+        return match change {
+            Change::Spawned { state, .. } => format!("Spawn {}", kind_name(&state.kind)),
+            Change::Despawned { state, .. } => format!("Despawn {}", kind_name(&state.kind)),
+            Change::Moved { .. } => "Move entity".to_string(),
+        };
+    }
+    let count = |f: fn(&Change) -> bool| changes.iter().filter(|c| f(c)).count();
+    let parts: Vec<String> = [
+        ("spawn", count(|c| matches!(c, Change::Spawned { .. }))),
+        ("despawn", count(|c| matches!(c, Change::Despawned { .. }))),
+        ("move", count(|c| matches!(c, Change::Moved { .. }))),
+    ]
+    .into_iter()
+    .filter(|(_, n)| *n > 0)
+    .map(|(verb, n)| format!("{} {}", verb, n))
+    .collect();
+    let text = parts.join(", ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+fn kind_of(
+    primitive: Option<&AxiomPrimitive>,
+    cached: Option<&AxiomCachedAsset>,
+    upload: Option<&AxiomRemoteAsset>,
+) -> Option<Kind> {
+    if let Some(primitive) = primitive {
+        return Some(Kind::Primitive(primitive.clone()));
+    }
+    if let Some(cached) = cached {
+        return Some(Kind::Model(cached.path.clone()));
+    }
+    // Uploaded textures lose the component when written; models keep it
+    let upload = upload?;
+    Some(Kind::Model(match upload.subdir.as_deref().filter(|s| !s.is_empty()) {
+        Some(subdir) => format!("{}/{}", subdir, upload.filename),
+        None => upload.filename.clone(),
+    }))
+}
+
+type Tracked<'a> = (
+    Entity,
+    &'a Transform,
+    Option<&'a AxiomPrimitive>,
+    Option<&'a AxiomCachedAsset>,
+    Option<&'a AxiomRemoteAsset>,
+    Option<&'a Name>,
+    Option<&'a AxiomGroup>,
+);
+
+/// Spawned or moved since the last frame.
+type Touched = (With<AxiomSpawned>, Or<(Added<AxiomSpawned>, Changed<Transform>)>);
+
+/// Journal what changed since the last frame.
+pub(crate) fn record_changes(
+    mut journal: ResMut<AxiomJournal>,
+    changed: Query<Tracked, Touched>,
+    mut removed: RemovedComponents<AxiomSpawned>,
+) {
+    let mut changes = Vec::new();
+    for entity in removed.read() {
+        if let Some(state) = journal.known.remove(&entity) {
+            changes.push(Change::Despawned { entity, state });
+        }
+    }
+    for (entity, transform, primitive, cached, upload, name, group) in &changed {
+        match journal.known.get_mut(&entity) {
+            Some(state) if state.transform != *transform => {
+                changes.push(Change::Moved { entity, before: state.transform, after: *transform });
+                state.transform = *transform;
+            }
+            Some(_) => {}
+            None => {
+                let Some(kind) = kind_of(primitive, cached, upload) else {
+                    continue;
+                };
+                let state = EntityState { kind, name: name.cloned(), group: group.cloned(), transform: *transform };
+                journal.known.insert(entity, state.clone());
+                changes.push(Change::Spawned { entity, state });
+            }
+        }
+    }
+    if !changes.is_empty() {
+        journal.record(changes);
+    }
+}
+
+fn spawn_state(world: &mut World, state: &EntityState) -> Entity {
+    let mut entity = world.spawn((AxiomSpawned, state.transform));
+    match &state.kind {
+        Kind::Primitive(primitive) => entity.insert(primitive.clone()),
+        Kind::Model(path) => entity.insert(AxiomCachedAsset { path: path.clone() }),
+    };
+    if let Some(name) = &state.name {
+        entity.insert(name.clone());
+    }
+    if let Some(group) = &state.group {
+        entity.insert(group.clone());
+    }
+    entity.id()
+}
+
+/// Make `change` undone (`forward` false) or done again, keeping the known
+/// state in step so the result isn't journaled as a new change.
+fn apply(world: &mut World, journal: &mut AxiomJournal, change: Change, forward: bool) {
+    match (change, forward) {
+        (Change::Spawned { entity, .. }, false) | (Change::Despawned { entity, .. }, true) => {
+            journal.known.remove(&entity);
+            if let Ok(entity) = world.get_entity_mut(entity) {
+                entity.despawn();
+            }
+        }
+        (Change::Spawned { entity, state }, true) | (Change::Despawned { entity, state }, false) => {
+            let new = spawn_state(world, &state);
+            journal.known.insert(new, state);
+            journal.replace_entity(entity, new);
+        }
+        (Change::Moved { entity, before, after }, forward) => {
+            let transform = if forward { after } else { before };
+            if let Some(state) = journal.known.get_mut(&entity) {
+                state.transform = transform;
+            }
+            if let Ok(mut entity) = world.get_entity_mut(entity) {
+                entity.insert(transform);
+            }
+        }
+    }
+}
+
+fn step(world: &mut World, forward: bool) -> BrpResult {
+    world.resource_scope(|world, mut journal: Mut<AxiomJournal>| {
+        let index = if forward {
+            (journal.cursor < journal.entries.len()).then_some(journal.cursor)
+        } else {
+            journal.cursor.checked_sub(1)
+        };
+        let Some(index) = index else {
+            return Err(BrpError {
+                code: error_codes::INTERNAL_ERROR,
+                message: format!("Nothing to {}", if forward { "redo" } else { "undo" }),
+                data: None,
+            });
+        };
+
+        // Changes are read one at a time, as respawning renames entities in
+        // the ones that follow
+        let count = journal.entries[index].changes.len();
+        let order: Vec<usize> = if forward { (0..count).collect() } else { (0..count).rev().collect() };
+        for i in order {
+            let change = journal.entries[index].changes[i].clone();
+            apply(world, &mut journal, change, forward);
+        }
+        journal.cursor = if forward { index + 1 } else { index };
+        // Don't fold the next edit into the entry just stepped over
+        journal.last_change = None;
+        Ok(json!({ "label": journal.entries[index].label, "history": journal.to_json() }))
+    })
+}
+
+/// Handler for `axiom.history`: `{ entries: [{ id, label, changes }], cursor }`,
+/// oldest first; entries from `cursor` on have been undone.
+pub(crate) fn history_method(In(_params): In<Option<Value>>, journal: Res<AxiomJournal>) -> BrpResult {
+    Ok(journal.to_json())
+}
+
+/// Handler for `axiom.undo`.
+pub(crate) fn undo_method(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    step(world, false)
+}
+
+/// Handler for `axiom.redo`.
+pub(crate) fn redo_method(In(_params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    step(world, true)
+}
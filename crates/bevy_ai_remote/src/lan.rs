@@ -0,0 +1,112 @@
+//! Serving the editor on another machine, such as a desktop driving a game
+//! on a Steam Deck.
+//!
+//! By default the game only listens on localhost. [`AxiomRemoteAddress`], or
+//! [`BIND_ENV`], opens it to the network; a game listening beyond localhost
+//! then broadcasts itself over UDP so the editor can find it. [`PORT_ENV`]
+//! moves it to another port, e.g. to run two games side by side.
+
+use bevy::prelude::*;
+use serde_json::json;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::time::Duration;
+
+/// Environment variable read for the address when the app doesn't insert
+/// [`AxiomRemoteAddress`] itself: `0.0.0.0`, or with a port, `0.0.0.0:15721`.
+pub const BIND_ENV: &str = "BRP_BIND";
+
+/// Environment variable read for the port alone, overriding any other
+/// setting but an inserted [`AxiomRemoteAddress`]. The editor's
+/// `BrpConfig::from_env` reads it too.
+pub const PORT_ENV: &str = "BRP_PORT";
+
+/// UDP port the announcements are broadcast to; the editor listens on it.
+pub const DISCOVERY_PORT: u16 = 15722;
+
+/// Port the BRP server listens on unless told otherwise; the editor's
+/// default too.
+pub const DEFAULT_PORT: u16 = 15721;
+const ANNOUNCE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Where the BRP server listens. Insert it before adding
+/// [`crate::BevyAiRemotePlugin`], configure the plugin, or set [`BIND_ENV`]
+/// or [`PORT_ENV`].
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct AxiomRemoteAddress {
+    pub address: IpAddr,
+    pub port: u16,
+    /// Broadcast the game on the local network.
+    pub announce: bool,
+}
+
+impl Default for AxiomRemoteAddress {
+    fn default() -> Self {
+        Self { address: Ipv4Addr::LOCALHOST.into(), port: DEFAULT_PORT, announce: false }
+    }
+}
+
+impl AxiomRemoteAddress {
+    /// Every interface, announced on the local network.
+    pub fn lan() -> Self {
+        Self { address: Ipv4Addr::UNSPECIFIED.into(), port: DEFAULT_PORT, announce: true }
+    }
+
+    pub(crate) fn from_env() -> Option<Self> {
+        let value = std::env::var(BIND_ENV).ok()?;
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+        let parsed = match value.parse::<std::net::SocketAddr>() {
+            Ok(addr) => Some((addr.ip(), addr.port())),
+            Err(_) => value.parse::<IpAddr>().ok().map(|ip| (ip, DEFAULT_PORT)),
+        };
+        let Some((address, port)) = parsed else {
+            warn!("Ignoring {}={}: expected an address like 0.0.0.0 or 0.0.0.0:15721", BIND_ENV, value);
+            return None;
+        };
+        Some(Self { address, port, announce: !address.is_loopback() })
+    }
+
+    /// With the port from [`PORT_ENV`], if set.
+    pub(crate) fn with_env_port(mut self) -> Self {
+        let Ok(value) = std::env::var(PORT_ENV) else { return self };
+        match value.trim().parse() {
+            Ok(port) => self.port = port,
+            Err(_) => warn!("Ignoring {}={}: expected a port number", PORT_ENV, value),
+        }
+        self
+    }
+}
+
+/// Broadcast the game every couple of seconds for as long as it runs.
+pub(crate) fn start_announcing(port: u16, token_required: bool) {
+    let name = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "bevy game".to_string());
+    let announcement = json!({
+        "service": "axiom",
+        "name": name,
+        "port": port,
+        "token_required": token_required,
+    })
+    .to_string();
+
+    let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).and_then(|socket| {
+        socket.set_broadcast(true)?;
+        Ok(socket)
+    }) {
+        Ok(socket) => socket,
+        Err(e) => {
+            warn!("LAN discovery disabled: {}", e);
+            return;
+        }
+    };
+    std::thread::spawn(move || loop {
+        if let Err(e) = socket.send_to(announcement.as_bytes(), (Ipv4Addr::BROADCAST, DISCOVERY_PORT)) {
+            debug!("Failed to announce the game: {}", e);
+        }
+        std::thread::sleep(ANNOUNCE_INTERVAL);
+    });
+}
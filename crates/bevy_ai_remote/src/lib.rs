@@ -1,20 +1,71 @@
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
 use bevy::prelude::*;
-use bevy_remote::{http::RemoteHttpPlugin, RemotePlugin};
+use bevy::render::view::screenshot::{Screenshot, ScreenshotCaptured};
+use bevy_remote::{
+    http::RemoteHttpPlugin, BrpResult, RemoteLast, RemoteMethodSystemId, RemoteMethods, RemotePlugin,
+    RemoteSystems,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 #[cfg(feature = "debug_probe")]
 use std::cell::UnsafeCell;
 use std::fs::File;
-use std::io::Write;
+use std::io::{Cursor, Write};
 use std::path::Path;
 #[cfg(feature = "debug_probe")]
 use std::sync::atomic::{compiler_fence, AtomicU64, AtomicUsize, Ordering};
 
+mod auth;
+mod compression;
+mod download;
+mod gizmos;
+mod journal;
+mod lan;
+mod logs;
+mod pick;
+mod profiling;
+mod upload;
+
+pub use auth::{AxiomAuthToken, AUTH_TOKEN_ENV, TOKEN_ENV, TOKEN_PARAM, UNAUTHORIZED_CODE};
+pub use compression::COMPRESSED_PARAM;
+pub use download::DOWNLOAD_METHOD;
+pub use gizmos::{AxiomGizmos, CLEAR_GIZMOS_METHOD, DRAW_GIZMO_METHOD};
+pub use journal::{AxiomJournal, HISTORY_METHOD, REDO_METHOD, UNDO_METHOD};
+pub use lan::{AxiomRemoteAddress, BIND_ENV, DEFAULT_PORT, DISCOVERY_PORT, PORT_ENV};
+pub use logs::{axiom_layer, log_layer, AxiomLogs, LOGS_METHOD};
+pub use pick::RAYCAST_METHOD;
+pub use profiling::{profile_layer, AxiomProfiler, PROFILE_METHOD};
+pub use upload::{UPLOAD_CHUNK_METHOD, UPLOAD_COMMIT_METHOD, UPLOAD_MANIFEST_METHOD};
+
 /// Component to tag entities that should be rendered as a primitive shape.
-#[derive(Component, Reflect, Default, Debug, Serialize, Deserialize)]
-#[reflect(Component)]
+/// Only `primitive_type` is required; the material fields left out keep
+/// `StandardMaterial`'s defaults on a beige base color.
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[reflect(Component, Default)]
 pub struct AxiomPrimitive {
     pub primitive_type: String,
+    /// sRGB `[r, g, b, a]`, each 0 to 1.
+    pub color: Option<[f32; 4]>,
+    pub metallic: Option<f32>,
+    pub roughness: Option<f32>,
+    /// Linear `[r, g, b]`; above 1 glows brighter.
+    pub emissive: Option<[f32; 3]>,
+    /// Ignore lighting and show the base color as is.
+    pub unlit: bool,
+}
+
+impl AxiomPrimitive {
+    fn material(&self) -> StandardMaterial {
+        let defaults = StandardMaterial::default();
+        StandardMaterial {
+            base_color: self.color.map_or(Color::srgb(0.8, 0.7, 0.6), |[r, g, b, a]| Color::srgba(r, g, b, a)),
+            metallic: self.metallic.unwrap_or(defaults.metallic),
+            perceptual_roughness: self.roughness.unwrap_or(defaults.perceptual_roughness),
+            emissive: self.emissive.map_or(defaults.emissive, |[r, g, b]| LinearRgba::rgb(r, g, b)),
+            unlit: self.unlit,
+            ..defaults
+        }
+    }
 }
 
 /// Component to receive a Base64 encoded asset file from the Editor.
@@ -29,11 +80,151 @@ pub struct AxiomRemoteAsset {
     pub subdir: Option<String>,
 }
 
+/// Component to spawn a model that is already in `assets/_remote_cache/`.
+/// usage: spawn an entity with this component and a Transform; `path` is
+/// relative to `_remote_cache` (e.g., "road-bend.glb").
+#[derive(Component, Reflect, Default, Debug, Serialize, Deserialize)]
+#[reflect(Component)]
+pub struct AxiomCachedAsset {
+    pub path: String,
+}
+
+/// Version of the methods and components this plugin offers the editor,
+/// raised when they change incompatibly.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// The plugin's [`PROTOCOL_VERSION`], read by the editor before its first
+/// request so a stale plugin or editor is reported as such.
+#[derive(Resource, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct AxiomProtocolVersion {
+    pub protocol: u32,
+    /// The `bevy_ai_remote` crate version, for messages.
+    pub plugin: String,
+}
+
+impl Default for AxiomProtocolVersion {
+    fn default() -> Self {
+        Self { protocol: PROTOCOL_VERSION, plugin: env!("CARGO_PKG_VERSION").to_string() }
+    }
+}
+
+/// How the game is doing, read by the editor's ping. Updated every frame.
+#[derive(Resource, Reflect, Debug, Clone, Serialize, Deserialize)]
+#[reflect(Resource)]
+pub struct AxiomStatus {
+    /// The `bevy_ai_remote` crate version.
+    pub plugin: String,
+    /// Frames run since the plugin was added.
+    pub frames: u64,
+    /// Wall-clock seconds since the app started.
+    pub uptime_secs: f64,
+}
+
+impl Default for AxiomStatus {
+    fn default() -> Self {
+        Self { plugin: env!("CARGO_PKG_VERSION").to_string(), frames: 0, uptime_secs: 0.0 }
+    }
+}
+
+fn update_status(mut status: ResMut<AxiomStatus>, time: Res<Time<Real>>) {
+    status.frames += 1;
+    status.uptime_secs = time.elapsed_secs_f64();
+}
+
+/// Pausing, stepping and slowing the game from the editor, applied to
+/// `Time<Virtual>` when written. Paused, frames still run but virtual time
+/// stands still, as with `Time::<Virtual>::pause`.
+#[derive(Resource, Reflect, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[reflect(Resource, Default)]
+pub struct AxiomSimControl {
+    pub paused: bool,
+    /// Frames to run before pausing again, counted down as they run.
+    pub step_frames: u32,
+    /// Speed of virtual time relative to real time.
+    pub time_scale: f32,
+}
+
+impl Default for AxiomSimControl {
+    fn default() -> Self {
+        Self { paused: false, step_frames: 0, time_scale: 1.0 }
+    }
+}
+
+/// Runs before time updates, so the frame a change arrives for runs with it.
+fn apply_sim_control(mut control: ResMut<AxiomSimControl>, mut time: ResMut<Time<Virtual>>, mut stepping: Local<bool>) {
+    // Left alone otherwise, so the game can pause itself. The countdown is
+    // this system's own change, which it doesn't see.
+    let written = control.is_changed() && !(control.is_added() && *control == AxiomSimControl::default());
+    if !written && !*stepping {
+        return;
+    }
+    if control.time_scale.is_finite() && control.time_scale >= 0.0 {
+        time.set_relative_speed(control.time_scale);
+    } else {
+        warn!("Ignoring time scale {}", control.time_scale);
+    }
+    *stepping = control.step_frames > 0;
+    if control.step_frames > 0 {
+        control.step_frames -= 1;
+        if control.step_frames == 0 {
+            control.paused = true;
+        }
+        time.unpause();
+    } else if control.paused {
+        time.pause();
+    } else {
+        time.unpause();
+    }
+}
+
+/// BRP method returning the latest capture of the primary window.
+pub const SCREENSHOT_METHOD: &str = "axiom.screenshot";
+
+/// BRP method listing the files in `assets/_remote_cache/`.
+pub const LIST_ASSETS_METHOD: &str = "axiom.list_assets";
+
+const REMOTE_CACHE_DIR: &str = "assets/_remote_cache";
+
+/// Images up to this size are returned inline by `axiom.list_assets` so the
+/// editor can show thumbnails.
+const INLINE_IMAGE_BYTES: u64 = 256 * 1024;
+
+/// A capture of the main camera asked for over BRP: spawn an entity with it
+/// and read it back until `png_base64` or `error` is set, a frame or two
+/// later. The editor despawns it once read.
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+pub struct AxiomScreenshotRequest {
+    /// Largest size wanted, scaled down keeping the aspect ratio; 0 keeps
+    /// the window's. Once captured, the size of the image.
+    pub width: u32,
+    pub height: u32,
+    pub png_base64: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Most recent window capture, PNG-encoded, served by `axiom.screenshot`.
+#[derive(Resource, Default)]
+struct LatestScreenshot {
+    png_base64: Option<String>,
+    width: u32,
+    height: u32,
+    /// A capture has been requested and not delivered yet.
+    pending: bool,
+}
+
 /// Unified marker for all entities spawned by the Axiom editor.
 #[derive(Component, Reflect, Default, Debug)]
 #[reflect(Component)]
 pub struct AxiomSpawned;
 
+/// The group an editor spawn belongs to, e.g. `AxiomGroup("level1".into())`
+/// for one generated sub-scene, so it can be cleared on its own.
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[reflect(Component, Default, Serialize, Deserialize)]
+pub struct AxiomGroup(pub String);
+
 #[cfg(feature = "debug_probe")]
 pub const AXIOM_DEBUG_SNAPSHOT_CAPACITY: usize = 4096;
 
@@ -72,7 +263,36 @@ pub extern "C" fn axiom_debug_safe_point(frame_index: u64, entity_count: u64, sn
 }
 
 /// Add this plugin to your Bevy app to enable remote control via Axiom.
-pub struct BevyAiRemotePlugin;
+///
+/// It listens on `127.0.0.1:15721` unless configured otherwise. The
+/// environment ([`BIND_ENV`], [`PORT_ENV`]) overrides the builder methods,
+/// so copies of one build can run side by side; an inserted
+/// [`AxiomRemoteAddress`] overrides both.
+#[derive(Debug, Clone, Default)]
+pub struct BevyAiRemotePlugin {
+    address: AxiomRemoteAddress,
+}
+
+impl BevyAiRemotePlugin {
+    /// Listen on `address`, announcing the game on the local network unless
+    /// it's a loopback address.
+    pub fn with_address(mut self, address: impl Into<std::net::IpAddr>) -> Self {
+        self.address.address = address.into();
+        self.address.announce = !self.address.address.is_loopback();
+        self
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.address.port = port;
+        self
+    }
+
+    /// Whether to broadcast the game on the local network.
+    pub fn with_announce(mut self, announce: bool) -> Self {
+        self.address.announce = announce;
+        self
+    }
+}
 
 impl Plugin for BevyAiRemotePlugin {
     fn build(&self, app: &mut App) {
@@ -81,29 +301,159 @@ impl Plugin for BevyAiRemotePlugin {
             app.add_plugins(RemotePlugin::default());
         }
 
-        use std::net::IpAddr;
+        // Localhost unless the app or the environment says otherwise
+        let address = match app.world().get_resource::<AxiomRemoteAddress>() {
+            Some(address) => address.clone(),
+            None => AxiomRemoteAddress::from_env().unwrap_or_else(|| self.address.clone()).with_env_port(),
+        };
 
         // Ensure HTTP transport is enabled with correct config
         if !app.is_plugin_added::<RemoteHttpPlugin>() {
             app.add_plugins(
                 RemoteHttpPlugin::default()
-                    .with_address("127.0.0.1".parse::<IpAddr>().unwrap())
-                    .with_port(15721),
+                    .with_address(address.address)
+                    .with_port(address.port),
             );
         }
 
         // Register our custom components
         app.register_type::<AxiomPrimitive>();
         app.register_type::<AxiomRemoteAsset>();
+        app.register_type::<AxiomCachedAsset>();
         app.register_type::<AxiomSpawned>();
+        app.register_type::<AxiomGroup>();
+        app.register_type::<AxiomScreenshotRequest>();
+        // For reading hierarchies over BRP; not every app registers them
+        app.register_type::<ChildOf>();
+        app.register_type::<Children>();
+        // Spawns may be named, for the editor to find them by
+        app.register_type::<Name>();
+
+        // Checked by the editor before its first request
+        app.register_type::<AxiomProtocolVersion>();
+        app.init_resource::<AxiomProtocolVersion>();
+        app.register_type::<AxiomStatus>();
+        app.init_resource::<AxiomStatus>();
+        app.register_type::<AxiomSimControl>();
+        app.init_resource::<AxiomSimControl>();
+        app.add_systems(First, apply_sim_control.before(bevy::time::TimeSystems));
+
+        // Custom BRP methods
+        app.init_resource::<LatestScreenshot>();
+        let screenshot_system = app.world_mut().register_system(screenshot_method);
+        app.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(SCREENSHOT_METHOD, RemoteMethodSystemId::Instant(screenshot_system));
+        let list_assets_system = app.world_mut().register_system(list_assets_method);
+        app.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(LIST_ASSETS_METHOD, RemoteMethodSystemId::Instant(list_assets_system));
+
+        // Uploads too large for one request, skipped when already cached
+        app.init_resource::<upload::CacheDigests>();
+        let manifest_system = app.world_mut().register_system(upload::manifest_method);
+        let chunk_system = app.world_mut().register_system(upload::chunk_method);
+        let commit_system = app.world_mut().register_system(upload::commit_method);
+        let mut methods = app.world_mut().resource_mut::<RemoteMethods>();
+        methods.insert(UPLOAD_MANIFEST_METHOD, RemoteMethodSystemId::Instant(manifest_system));
+        methods.insert(UPLOAD_CHUNK_METHOD, RemoteMethodSystemId::Instant(chunk_system));
+        methods.insert(UPLOAD_COMMIT_METHOD, RemoteMethodSystemId::Instant(commit_system));
+
+        // Files read back from the game's assets
+        let download_system = app.world_mut().register_system(download::download_method);
+        app.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(DOWNLOAD_METHOD, RemoteMethodSystemId::Instant(download_system));
+
+        // Ray casts against the scene's meshes, to pick entities
+        let raycast_system = app.world_mut().register_system(pick::raycast_method);
+        app.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(RAYCAST_METHOD, RemoteMethodSystemId::Instant(raycast_system));
+
+        // Shapes the editor draws over the scene
+        app.init_resource::<AxiomGizmos>();
+        let draw_gizmo_system = app.world_mut().register_system(gizmos::draw_gizmo_method);
+        let clear_gizmos_system = app.world_mut().register_system(gizmos::clear_gizmos_method);
+        let mut methods = app.world_mut().resource_mut::<RemoteMethods>();
+        methods.insert(DRAW_GIZMO_METHOD, RemoteMethodSystemId::Instant(draw_gizmo_system));
+        methods.insert(CLEAR_GIZMOS_METHOD, RemoteMethodSystemId::Instant(clear_gizmos_system));
+        app.add_systems(
+            Update,
+            (
+                gizmos::expire_gizmos,
+                // `Gizmos` needs the gizmo plugin's resources
+                gizmos::draw_gizmos.run_if(resource_exists::<bevy::gizmos::config::GizmoConfigStore>),
+            )
+                .chain(),
+        );
+
+        // Undo history of editor changes
+        app.init_resource::<AxiomJournal>();
+        let history_system = app.world_mut().register_system(journal::history_method);
+        let undo_system = app.world_mut().register_system(journal::undo_method);
+        let redo_system = app.world_mut().register_system(journal::redo_method);
+        let mut methods = app.world_mut().resource_mut::<RemoteMethods>();
+        methods.insert(HISTORY_METHOD, RemoteMethodSystemId::Instant(history_system));
+        methods.insert(UNDO_METHOD, RemoteMethodSystemId::Instant(undo_system));
+        methods.insert(REDO_METHOD, RemoteMethodSystemId::Instant(redo_system));
+
+        // Frame timings, recorded while the editor profiles
+        app.init_resource::<AxiomProfiler>();
+        profiling::install(app);
+        let profile_system = app.world_mut().register_system(profiling::profile_method);
+        app.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(PROFILE_METHOD, RemoteMethodSystemId::Instant(profile_system));
+
+        // Recent log records, captured once `log_layer` is installed
+        app.init_resource::<AxiomLogs>();
+        let logs_system = app.world_mut().register_system(logs::logs_method);
+        app.world_mut()
+            .resource_mut::<RemoteMethods>()
+            .insert(LOGS_METHOD, RemoteMethodSystemId::Instant(logs_system));
 
         // Add systems
-        app.add_systems(Update, (spawn_primitives, handle_remote_assets));
+        app.add_systems(Update, (spawn_primitives, handle_remote_assets, handle_cached_assets, capture_requested));
+        app.add_systems(PostUpdate, journal::record_changes);
+        app.add_systems(Last, update_status);
+
+        // Shared secret, from the app or the environment
+        if !app.world().contains_resource::<AxiomAuthToken>() {
+            if let Some(token) = AxiomAuthToken::from_env() {
+                app.insert_resource(token);
+            }
+        }
+        app.add_systems(
+            RemoteLast,
+            auth::gate_requests
+                .run_if(resource_exists::<AxiomAuthToken>)
+                .before(RemoteSystems::ProcessRequests),
+        );
+        app.add_systems(
+            RemoteLast,
+            compression::inflate_requests
+                .after(auth::gate_requests)
+                .before(RemoteSystems::ProcessRequests),
+        );
 
         #[cfg(feature = "debug_probe")]
         app.add_systems(Update, debug_probe_safe_point_anchor);
 
-        info!("Bevy AI Remote Plugin initialized on port 15721");
+        let token_required = app.world().contains_resource::<AxiomAuthToken>();
+        if address.announce {
+            lan::start_announcing(address.port, token_required);
+        }
+        if token_required {
+            info!("Bevy AI Remote Plugin initialized on {}:{}, requiring a token", address.address, address.port);
+        } else if !address.address.is_loopback() {
+            warn!(
+                "Bevy AI Remote Plugin listening on {}:{} without a token; anyone on the network can drive the game. Set {}.",
+                address.address, address.port, TOKEN_ENV
+            );
+        } else {
+            info!("Bevy AI Remote Plugin initialized on {}:{}", address.address, address.port);
+        }
     }
 }
 
@@ -140,6 +490,137 @@ fn write_debug_probe_snapshot(snapshot: &[u8]) -> usize {
     snapshot_len
 }
 
+/// Handler for `axiom.screenshot`. Captures complete a frame or two later, so
+/// this returns the previous capture (or `{"pending": true}` on the first
+/// call) and schedules a new one for the next request.
+fn screenshot_method(
+    In(_params): In<Option<Value>>,
+    mut commands: Commands,
+    mut latest: ResMut<LatestScreenshot>,
+) -> BrpResult {
+    if !latest.pending {
+        latest.pending = true;
+        commands
+            .spawn(Screenshot::primary_window())
+            .observe(store_screenshot);
+    }
+
+    match &latest.png_base64 {
+        Some(data) => Ok(json!({
+            "png_base64": data,
+            "width": latest.width,
+            "height": latest.height,
+        })),
+        None => Ok(json!({ "pending": true })),
+    }
+}
+
+/// Handler for `axiom.list_assets`: every file under the remote cache, with
+/// small images inlined as base64.
+fn list_assets_method(In(_params): In<Option<Value>>) -> BrpResult {
+    let root = Path::new(REMOTE_CACHE_DIR);
+    let mut assets = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        // Missing until the first upload
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            let Ok(relative) = path.strip_prefix(root) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+            let is_image = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| matches!(e.to_lowercase().as_str(), "png" | "jpg" | "jpeg"));
+
+            let mut asset = json!({ "path": relative, "size": meta.len() });
+            if is_image && meta.len() <= INLINE_IMAGE_BYTES {
+                if let Ok(bytes) = std::fs::read(&path) {
+                    asset["image_base64"] = json!(BASE64.encode(bytes));
+                }
+            }
+            assets.push(asset);
+        }
+    }
+    assets.sort_by(|a, b| a["path"].as_str().cmp(&b["path"].as_str()));
+    Ok(json!({ "assets": assets }))
+}
+
+fn store_screenshot(captured: On<ScreenshotCaptured>, mut latest: ResMut<LatestScreenshot>) {
+    latest.pending = false;
+
+    match encode_png(&captured.image, 0, 0) {
+        Ok((png, width, height)) => {
+            latest.width = width;
+            latest.height = height;
+            latest.png_base64 = Some(BASE64.encode(&png));
+        }
+        Err(e) => error!("{}", e),
+    }
+}
+
+/// Capture the primary window for each new [`AxiomScreenshotRequest`].
+fn capture_requested(
+    mut commands: Commands,
+    mut requests: Query<(Entity, &mut AxiomScreenshotRequest), Added<AxiomScreenshotRequest>>,
+    windows: Query<(), With<bevy::window::PrimaryWindow>>,
+) {
+    for (entity, mut request) in &mut requests {
+        if windows.is_empty() {
+            request.error = Some("The game has no window to capture".to_string());
+            continue;
+        }
+        let (width, height) = (request.width, request.height);
+        commands.spawn(Screenshot::primary_window()).observe(
+            move |captured: On<ScreenshotCaptured>, mut requests: Query<&mut AxiomScreenshotRequest>| {
+                // Gone if the editor gave up waiting
+                let Ok(mut request) = requests.get_mut(entity) else {
+                    return;
+                };
+                match encode_png(&captured.image, width, height) {
+                    Ok((png, width, height)) => {
+                        request.width = width;
+                        request.height = height;
+                        request.png_base64 = Some(BASE64.encode(&png));
+                    }
+                    Err(e) => request.error = Some(e),
+                }
+            },
+        );
+    }
+}
+
+/// `image` as a PNG no larger than `max_width` × `max_height` (0 for no
+/// limit), with its size.
+fn encode_png(image: &Image, max_width: u32, max_height: u32) -> Result<(Vec<u8>, u32, u32), String> {
+    let mut image = image
+        .clone()
+        .try_into_dynamic()
+        .map_err(|e| format!("Failed to convert screenshot: {}", e))?;
+    let limit = |max: u32| if max == 0 { u32::MAX } else { max };
+    if image.width() > limit(max_width) || image.height() > limit(max_height) {
+        image = image.resize(limit(max_width), limit(max_height), image::imageops::FilterType::Triangle);
+    }
+    let image = image.to_rgb8();
+
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode screenshot: {}", e))?;
+    Ok((png, image.width(), image.height()))
+}
+
 fn spawn_primitives(
     mut commands: Commands,
     query: Query<(Entity, &AxiomPrimitive), Added<AxiomPrimitive>>,
@@ -148,74 +629,25 @@ fn spawn_primitives(
 ) {
     for (entity, primitive) in query.iter() {
         info!("Hydrating primitive: {:?}", primitive.primitive_type);
-        match primitive.primitive_type.to_lowercase().as_str() {
-            "cube" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Cuboid::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "sphere" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Sphere::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "capsule" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Capsule3d::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "cylinder" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Cylinder::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "cone" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Cone::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "torus" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Torus::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "plane" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Plane3d::default().mesh().size(5.0, 5.0))),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "tetrahedron" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Tetrahedron::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
-            "cuboid" => {
-                commands.entity(entity).insert((
-                    Mesh3d(meshes.add(Cuboid::default())),
-                    MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
-                    AxiomSpawned,
-                ));
-            }
+        let mesh = match primitive.primitive_type.to_lowercase().as_str() {
+            "cube" | "cuboid" => meshes.add(Cuboid::default()),
+            "sphere" => meshes.add(Sphere::default()),
+            "capsule" => meshes.add(Capsule3d::default()),
+            "cylinder" => meshes.add(Cylinder::default()),
+            "cone" => meshes.add(Cone::default()),
+            "torus" => meshes.add(Torus::default()),
+            "plane" => meshes.add(Plane3d::default().mesh().size(5.0, 5.0)),
+            "tetrahedron" => meshes.add(Tetrahedron::default()),
             _ => {
                 warn!("Unknown primitive type: {}", primitive.primitive_type);
+                continue;
             }
-        }
+        };
+        commands.entity(entity).insert((
+            Mesh3d(mesh),
+            MeshMaterial3d(materials.add(primitive.material())),
+            AxiomSpawned,
+        ));
     }
 }
 
@@ -319,3 +751,29 @@ fn handle_remote_assets(
         }
     }
 }
+
+fn handle_cached_assets(
+    mut commands: Commands,
+    query: Query<(Entity, &AxiomCachedAsset), Added<AxiomCachedAsset>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, asset) in query.iter() {
+        // Only files inside the cache
+        if asset.path.split(['/', '\\']).any(|part| part == "..") {
+            error!("Refusing to load cached asset outside the cache: {}", asset.path);
+            commands.entity(entity).despawn();
+            continue;
+        }
+        if !(asset.path.ends_with(".glb") || asset.path.ends_with(".gltf")) {
+            error!("Cached asset {} is not a model, not spawning it", asset.path);
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let scene_path = format!("_remote_cache/{}#Scene0", asset.path);
+        info!("Loading cached scene from: {}", scene_path);
+        let scene_handle: Handle<Scene> = asset_server.load(scene_path);
+        // The component stays so the scene can be exported and restored
+        commands.entity(entity).insert(SceneRoot(scene_handle));
+    }
+}
@@ -0,0 +1,202 @@
+//! The game's recent log output, kept for the editor to read back, such as
+//! the last warnings after a change. Records are captured by [`log_layer`],
+//! installed through `LogPlugin`:
+//!
+//! ```ignore
+//! DefaultPlugins.set(LogPlugin { custom_layer: bevy_ai_remote::log_layer, ..default() })
+//! ```
+//!
+//! or by [`axiom_layer`] along with the profiler's.
+
+use crate::profiling::profile_layer;
+use bevy::log::tracing::field::{Field, Visit};
+use bevy::log::tracing::{Event, Level, Subscriber};
+use bevy::log::tracing_subscriber::layer::Context;
+use bevy::log::tracing_subscriber::Layer;
+use bevy::log::BoxedLayer;
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// BRP method reading the most recent records.
+pub const LOGS_METHOD: &str = "axiom.logs";
+
+/// Records kept; older ones are dropped.
+const CAPACITY: usize = 1000;
+
+const DEFAULT_LIMIT: usize = 100;
+
+/// Ring buffer of the game's latest log records, shared with [`log_layer`].
+/// Empty unless the layer is installed.
+#[derive(Resource, Clone, Default, Debug)]
+pub struct AxiomLogs(Arc<Mutex<Logs>>);
+
+#[derive(Default, Debug)]
+struct Logs {
+    records: VecDeque<LogRecord>,
+    next_seq: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct LogRecord {
+    /// Increasing, for reading only what's new.
+    seq: u64,
+    timestamp_ms: u64,
+    level: String,
+    target: String,
+    message: String,
+    /// The event's other fields.
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    fields: Map<String, Value>,
+}
+
+impl AxiomLogs {
+    pub fn len(&self) -> usize {
+        self.lock().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Logs> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn push(&self, mut record: LogRecord) {
+        let mut logs = self.lock();
+        record.seq = logs.next_seq;
+        logs.next_seq += 1;
+        if logs.records.len() == CAPACITY {
+            logs.records.pop_front();
+        }
+        logs.records.push_back(record);
+    }
+}
+
+/// Layer capturing log records into the app's [`AxiomLogs`], for
+/// `LogPlugin::custom_layer`.
+pub fn log_layer(app: &mut App) -> Option<BoxedLayer> {
+    let logs = app.world_mut().get_resource_or_init::<AxiomLogs>().clone();
+    Some(Box::new(LogCapture(logs)))
+}
+
+/// Both [`profile_layer`] and [`log_layer`], for `LogPlugin::custom_layer`.
+pub fn axiom_layer(app: &mut App) -> Option<BoxedLayer> {
+    let layers: Vec<BoxedLayer> = [profile_layer(app), log_layer(app)].into_iter().flatten().collect();
+    Some(Box::new(layers))
+}
+
+struct LogCapture(AxiomLogs);
+
+impl<S: Subscriber> Layer<S> for LogCapture {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let metadata = event.metadata();
+        let timestamp_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+        self.0.push(LogRecord {
+            seq: 0,
+            timestamp_ms,
+            level: metadata.level().as_str().to_lowercase(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+        });
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: Map<String, Value>,
+}
+
+impl FieldVisitor {
+    fn insert(&mut self, field: &Field, value: Value) {
+        self.fields.insert(field.name().to_string(), value);
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.insert(field, json!(value));
+        }
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.insert(field, json!(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{:?}", value);
+        } else {
+            self.insert(field, json!(format!("{:?}", value)));
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LogsParams {
+    /// Least severe level returned.
+    #[serde(default)]
+    level: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+    /// Only records after this `seq`.
+    #[serde(default)]
+    after: Option<u64>,
+}
+
+/// Handler for `axiom.logs`: `{"level", "limit", "after"}`, all optional.
+/// Answers `{"records"}`, the newest `limit` records at least as severe as
+/// `level`, oldest first.
+pub(crate) fn logs_method(In(params): In<Option<Value>>, logs: Res<AxiomLogs>) -> BrpResult {
+    let params: LogsParams = serde_json::from_value(params.unwrap_or(json!({}))).map_err(|e| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+        data: None,
+    })?;
+    let level = match params.level.as_deref() {
+        Some(level) => level.parse::<Level>().map_err(|_| BrpError {
+            code: error_codes::INVALID_PARAMS,
+            message: format!("Unknown level `{}`; expected trace, debug, info, warn or error", level),
+            data: None,
+        })?,
+        None => Level::TRACE,
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT);
+
+    let logs = logs.lock();
+    let mut records: Vec<&LogRecord> = logs
+        .records
+        .iter()
+        .rev()
+        .filter(|r| params.after.is_none_or(|after| r.seq > after))
+        // Levels compare by verbosity, so more severe is smaller
+        .filter(|r| r.level.parse::<Level>().is_ok_and(|l| l <= level))
+        .take(limit)
+        .collect();
+    records.reverse();
+    Ok(json!({ "records": records }))
+}
@@ -0,0 +1,103 @@
+//! Ray casts against the meshes in the scene, for the editor to select what
+//! was clicked, or to ask what's in front of the camera.
+
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// BRP method casting a ray into the scene.
+pub const RAYCAST_METHOD: &str = "axiom.raycast";
+
+/// Closer than this along the ray counts as behind its origin.
+const EPSILON: f32 = 1e-6;
+
+#[derive(Deserialize)]
+struct RaycastParams {
+    origin: [f32; 3],
+    direction: [f32; 3],
+}
+
+/// Handler for `axiom.raycast`: `{"origin", "direction"}` in world space.
+/// Answers the nearest mesh hit as `{"entity", "point", "normal",
+/// "distance"}`, or null when the ray hits nothing.
+pub(crate) fn raycast_method(
+    In(params): In<Option<Value>>,
+    meshes: Res<Assets<Mesh>>,
+    query: Query<(Entity, &Mesh3d, &GlobalTransform, Option<&InheritedVisibility>)>,
+) -> BrpResult {
+    let params: RaycastParams = serde_json::from_value(params.unwrap_or_default()).map_err(|e| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+        data: None,
+    })?;
+    let direction = Dir3::new(Vec3::from(params.direction)).map_err(|_| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: "The ray's direction must be a non-zero vector".to_string(),
+        data: None,
+    })?;
+    let ray = Ray3d::new(Vec3::from(params.origin), direction);
+
+    let mut nearest: Option<(Entity, Hit)> = None;
+    for (entity, mesh, transform, visibility) in &query {
+        if visibility.is_some_and(|v| !v.get()) {
+            continue;
+        }
+        // Meshes still loading, or without triangles, can't be hit
+        let Some(triangles) = meshes.get(&mesh.0).and_then(|mesh| mesh.triangles().ok()) else {
+            continue;
+        };
+        for triangle in triangles {
+            let vertices = triangle.vertices.map(|v| transform.transform_point(v));
+            if let Some(hit) = intersect(ray, vertices) {
+                if nearest.as_ref().is_none_or(|(_, best)| hit.distance < best.distance) {
+                    nearest = Some((entity, hit));
+                }
+            }
+        }
+    }
+
+    Ok(nearest.map_or(Value::Null, |(entity, hit)| {
+        json!({
+            "entity": entity.to_bits(),
+            "point": ray.get_point(hit.distance).to_array(),
+            "normal": hit.normal.to_array(),
+            "distance": hit.distance,
+        })
+    }))
+}
+
+struct Hit {
+    distance: f32,
+    /// Facing back along the ray.
+    normal: Vec3,
+}
+
+/// Where `ray` crosses the triangle, from either side (Möller–Trumbore).
+fn intersect(ray: Ray3d, [a, b, c]: [Vec3; 3]) -> Option<Hit> {
+    let (ab, ac) = (b - a, c - a);
+    let p = ray.direction.cross(ac);
+    let determinant = ab.dot(p);
+    if determinant.abs() < EPSILON {
+        // Parallel, or a degenerate triangle
+        return None;
+    }
+    let inverse = 1.0 / determinant;
+    let to_origin = ray.origin - a;
+    let u = to_origin.dot(p) * inverse;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = to_origin.cross(ab);
+    let v = ray.direction.dot(q) * inverse;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = ac.dot(q) * inverse;
+    if distance < EPSILON {
+        return None;
+    }
+    let normal = ab.cross(ac).normalize();
+    let normal = if normal.dot(*ray.direction) > 0.0 { -normal } else { normal };
+    Some(Hit { distance, normal })
+}
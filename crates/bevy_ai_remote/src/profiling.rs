@@ -0,0 +1,264 @@
+//! Frame timings for performance questions, recorded while the editor has
+//! profiling on.
+//!
+//! The plugin replaces the runner of bevy's `Main` schedule with one that
+//! behaves the same but times each schedule of the main loop. Systems are
+//! timed from bevy's `system` trace spans, so per-system timings need bevy's
+//! `trace` feature and [`profile_layer`] installed through `LogPlugin`:
+//!
+//! ```ignore
+//! DefaultPlugins.set(LogPlugin { custom_layer: bevy_ai_remote::profile_layer, ..default() })
+//! ```
+
+use bevy::app::{Main, MainScheduleOrder};
+use bevy::ecs::schedule::{ExecutorKind, Schedule};
+use bevy::log::tracing::field::{Field, Visit};
+use bevy::log::tracing::span::{Attributes, Id};
+use bevy::log::tracing::Subscriber;
+use bevy::log::tracing_subscriber::layer::Context;
+use bevy::log::tracing_subscriber::registry::LookupSpan;
+use bevy::log::tracing_subscriber::Layer;
+use bevy::log::BoxedLayer;
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// BRP method starting, stopping and reading a capture.
+pub const PROFILE_METHOD: &str = "axiom.profile";
+
+/// Frames kept unless the editor asks for another amount; ten seconds at
+/// 60 FPS.
+const DEFAULT_CAPACITY: usize = 600;
+const MAX_CAPACITY: usize = 10_000;
+
+/// Read by the span layer, which has no world access.
+static RECORDING: AtomicBool = AtomicBool::new(false);
+
+/// Schedule running now, and system times recorded in the current frame.
+static CURRENT_SCHEDULE: Mutex<Option<String>> = Mutex::new(None);
+static SYSTEM_TIMES: Mutex<Vec<Timing>> = Mutex::new(Vec::new());
+
+#[derive(Clone, Debug, Serialize)]
+struct Timing {
+    name: String,
+    ms: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    schedule: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct FrameTiming {
+    frame: u64,
+    total_ms: f64,
+    entities: u32,
+    schedules: Vec<Timing>,
+    systems: Vec<Timing>,
+}
+
+/// Ring buffer of the frames recorded while profiling.
+#[derive(Resource)]
+pub struct AxiomProfiler {
+    frames: VecDeque<FrameTiming>,
+    capacity: usize,
+    frame: u64,
+}
+
+impl Default for AxiomProfiler {
+    fn default() -> Self {
+        Self { frames: VecDeque::new(), capacity: DEFAULT_CAPACITY, frame: 0 }
+    }
+}
+
+impl AxiomProfiler {
+    /// Record from the next frame on, dropping earlier frames.
+    pub fn start(&mut self, capacity: usize) {
+        self.frames.clear();
+        self.capacity = capacity.clamp(1, MAX_CAPACITY);
+        RECORDING.store(true, Ordering::Relaxed);
+    }
+
+    pub fn stop(&mut self) {
+        RECORDING.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_recording(&self) -> bool {
+        RECORDING.load(Ordering::Relaxed)
+    }
+
+    fn push(&mut self, frame: FrameTiming) {
+        while self.frames.len() >= self.capacity {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::json!({
+            "recording": self.is_recording(),
+            "system_timing": self.frames.iter().any(|f| !f.systems.is_empty()),
+            "frames": self.frames,
+        })
+    }
+}
+
+/// Swap in [`run_main`] as the runner of `Main`.
+pub(crate) fn install(app: &mut App) {
+    let mut main = Schedule::new(Main);
+    main.set_executor_kind(ExecutorKind::SingleThreaded);
+    main.add_systems(run_main);
+    app.add_schedule(main);
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// `Main::run_main`, timing each schedule while recording.
+fn run_main(world: &mut World, mut started: Local<bool>) {
+    if !*started {
+        world.resource_scope(|world, order: Mut<MainScheduleOrder>| {
+            for &label in &order.startup_labels {
+                let _ = world.try_run_schedule(label);
+            }
+        });
+        *started = true;
+    }
+
+    if !RECORDING.load(Ordering::Relaxed) {
+        world.resource_scope(|world, order: Mut<MainScheduleOrder>| {
+            for &label in &order.labels {
+                let _ = world.try_run_schedule(label);
+            }
+        });
+        return;
+    }
+
+    let frame_started = Instant::now();
+    let mut schedules = Vec::new();
+    world.resource_scope(|world, order: Mut<MainScheduleOrder>| {
+        for &label in &order.labels {
+            let name = format!("{:?}", label);
+            *CURRENT_SCHEDULE.lock().unwrap_or_else(|e| e.into_inner()) = Some(name.clone());
+            let started = Instant::now();
+            if world.try_run_schedule(label).is_ok() {
+                schedules.push(Timing { name, ms: ms(started.elapsed()), schedule: None });
+            }
+        }
+    });
+    *CURRENT_SCHEDULE.lock().unwrap_or_else(|e| e.into_inner()) = None;
+    let total_ms = ms(frame_started.elapsed());
+    let systems = std::mem::take(&mut *SYSTEM_TIMES.lock().unwrap_or_else(|e| e.into_inner()));
+
+    let entities = world.entities().len();
+    if let Some(mut profiler) = world.get_resource_mut::<AxiomProfiler>() {
+        profiler.frame += 1;
+        let frame = profiler.frame;
+        profiler.push(FrameTiming { frame, total_ms, entities, schedules, systems });
+    }
+}
+
+/// Add a system's run to the current frame; runs of the same system in the
+/// same schedule add up.
+fn record_system(name: &str, elapsed: Duration) {
+    let schedule = CURRENT_SCHEDULE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let mut times = SYSTEM_TIMES.lock().unwrap_or_else(|e| e.into_inner());
+    match times.iter_mut().find(|t| t.name == name && t.schedule == schedule) {
+        Some(timing) => timing.ms += ms(elapsed),
+        None => times.push(Timing { name: name.to_string(), ms: ms(elapsed), schedule }),
+    }
+}
+
+/// Handler for `axiom.profile`: `{"action": "start" | "stop" | "read",
+/// "capacity": frames}`, answering with the capture.
+pub(crate) fn profile_method(In(params): In<Option<Value>>, mut profiler: ResMut<AxiomProfiler>) -> BrpResult {
+    let params = params.unwrap_or_default();
+    match params.get("action").and_then(Value::as_str).unwrap_or("read") {
+        "start" => {
+            let capacity = params.get("capacity").and_then(Value::as_u64).map_or(DEFAULT_CAPACITY, |c| c as usize);
+            profiler.start(capacity);
+        }
+        "stop" => profiler.stop(),
+        "read" => {}
+        other => {
+            return Err(BrpError {
+                code: error_codes::INVALID_PARAMS,
+                message: format!("Unknown profile action `{}`; expected start, stop or read", other),
+                data: None,
+            })
+        }
+    }
+    Ok(profiler.to_json())
+}
+
+/// Layer timing bevy's `system` spans, for `LogPlugin::custom_layer`.
+pub fn profile_layer(_app: &mut App) -> Option<BoxedLayer> {
+    Some(Box::new(SystemSpans))
+}
+
+struct SystemSpans;
+
+/// Stored on each `system` span.
+struct SystemSpan {
+    name: String,
+    entered: Option<Instant>,
+}
+
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" && self.0.is_none() {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+impl<S> Layer<S> for SystemSpans
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "system" {
+            return;
+        }
+        let mut visitor = NameVisitor(None);
+        attrs.record(&mut visitor);
+        if let (Some(name), Some(span)) = (visitor.0, ctx.span(id)) {
+            span.extensions_mut().insert(SystemSpan { name, entered: None });
+        }
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !RECORDING.load(Ordering::Relaxed) {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            if let Some(system) = span.extensions_mut().get_mut::<SystemSpan>() {
+                system.entered = Some(Instant::now());
+            }
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut extensions = span.extensions_mut();
+        if let Some(system) = extensions.get_mut::<SystemSpan>() {
+            if let Some(entered) = system.entered.take() {
+                record_system(&system.name, entered.elapsed());
+            }
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Uploads too large for one request: the editor sends the file in chunks,
+//! each appended to a staging file, then commits it into the remote cache.
+//!
+//! Chunks carry the offset they start at and must arrive in order, so a
+//! lost or repeated chunk is an error rather than a corrupt file.
+//!
+//! Before sending anything the editor offers a manifest of the file's size
+//! and SHA-256; if the cache already holds those bytes no transfer is needed.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use bevy::prelude::*;
+use bevy_remote::{error_codes, BrpError, BrpResult};
+use ring::digest::{Context, SHA256};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::REMOTE_CACHE_DIR;
+
+/// BRP method checking whether the cache already holds a file.
+pub const UPLOAD_MANIFEST_METHOD: &str = "axiom.upload_manifest";
+
+/// BRP method appending a chunk to a staged upload.
+pub const UPLOAD_CHUNK_METHOD: &str = "axiom.upload_chunk";
+
+/// BRP method moving a complete staged upload into the remote cache.
+pub const UPLOAD_COMMIT_METHOD: &str = "axiom.upload_commit";
+
+/// Uploads in progress, kept out of the cache so they are never listed or
+/// loaded half-written.
+const STAGING_DIR: &str = "assets/_remote_uploads";
+
+/// SHA-256 of cached files, so a big model is hashed once rather than on
+/// every manifest. Entries are dropped when the file's size or mtime change.
+#[derive(Resource, Default)]
+pub(crate) struct CacheDigests {
+    digests: HashMap<PathBuf, (u64, SystemTime, String)>,
+}
+
+impl CacheDigests {
+    fn digest(&mut self, path: &Path) -> std::io::Result<String> {
+        let meta = std::fs::metadata(path)?;
+        let modified = meta.modified()?;
+        if let Some((len, at, digest)) = self.digests.get(path) {
+            if *len == meta.len() && *at == modified {
+                return Ok(digest.clone());
+            }
+        }
+        let digest = sha256_file(path)?;
+        self.digests.insert(path.to_path_buf(), (meta.len(), modified, digest.clone()));
+        Ok(digest)
+    }
+}
+
+#[derive(Deserialize)]
+struct ManifestParams {
+    size: u64,
+    sha256: String,
+    filename: String,
+    subdir: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChunkParams {
+    upload: String,
+    offset: u64,
+    data_base64: String,
+}
+
+#[derive(Deserialize)]
+struct CommitParams {
+    upload: String,
+    size: u64,
+    sha256: Option<String>,
+    filename: String,
+    subdir: Option<String>,
+}
+
+/// Handler for `axiom.upload_manifest`: `{"size", "sha256", "filename",
+/// "subdir"}`. Answers `{"cached": true, "path"}` when the file is in place,
+/// copying a match from elsewhere in the cache if needed, or
+/// `{"cached": false}` when the bytes must be sent.
+pub(crate) fn manifest_method(In(params): In<Option<Value>>, mut digests: ResMut<CacheDigests>) -> BrpResult {
+    let params: ManifestParams = parse(params)?;
+    let relative = cache_relative(&params.filename, params.subdir.as_deref())?;
+    let target = Path::new(REMOTE_CACHE_DIR).join(&relative);
+    let sha256 = params.sha256.to_lowercase();
+
+    let mut candidates = vec![target.clone()];
+    candidates.extend(cache_files().into_iter().filter(|path| *path != target));
+    for candidate in candidates {
+        let same_size = std::fs::metadata(&candidate).is_ok_and(|m| m.is_file() && m.len() == params.size);
+        if !same_size || digests.digest(&candidate).ok().as_deref() != Some(sha256.as_str()) {
+            continue;
+        }
+        if candidate != target {
+            if let Some(dir) = target.parent() {
+                std::fs::create_dir_all(dir).map_err(internal)?;
+            }
+            std::fs::copy(&candidate, &target).map_err(internal)?;
+            info!("Reused cached {:?} for {:?}", candidate, target);
+        }
+        return Ok(json!({ "cached": true, "path": relative }));
+    }
+    Ok(json!({ "cached": false }))
+}
+
+/// Handler for `axiom.upload_chunk`: `{"upload", "offset", "data_base64"}`.
+/// Offset 0 starts the upload over; answers with the bytes received so far.
+pub(crate) fn chunk_method(In(params): In<Option<Value>>) -> BrpResult {
+    let params: ChunkParams = parse(params)?;
+    let bytes = BASE64
+        .decode(&params.data_base64)
+        .map_err(|e| invalid(format!("Invalid base64 in chunk: {}", e)))?;
+    let path = staging_path(&params.upload)?;
+
+    let received = std::fs::metadata(&path).map_or(0, |m| m.len());
+    if params.offset != 0 && params.offset != received {
+        return Err(invalid(format!(
+            "Chunk of upload {} starts at {} but {} bytes were received",
+            params.upload, params.offset, received
+        )));
+    }
+    std::fs::create_dir_all(STAGING_DIR).map_err(internal)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(params.offset != 0)
+        .truncate(params.offset == 0)
+        .open(&path)
+        .map_err(internal)?;
+    file.write_all(&bytes).map_err(internal)?;
+    Ok(json!({ "received": params.offset + bytes.len() as u64 }))
+}
+
+/// Handler for `axiom.upload_commit`: `{"upload", "size", "sha256",
+/// "filename", "subdir"}`, the digest being optional. Answers with the file's
+/// path relative to the cache.
+pub(crate) fn commit_method(In(params): In<Option<Value>>) -> BrpResult {
+    let params: CommitParams = parse(params)?;
+    let staged = staging_path(&params.upload)?;
+    let received = std::fs::metadata(&staged)
+        .map_err(|_| invalid(format!("No chunks were received for upload {}", params.upload)))?
+        .len();
+    if received != params.size {
+        return Err(invalid(format!(
+            "Upload {} has {} of {} bytes",
+            params.upload, received, params.size
+        )));
+    }
+    if let Some(expected) = &params.sha256 {
+        let actual = sha256_file(&staged).map_err(internal)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = std::fs::remove_file(&staged);
+            return Err(invalid(format!(
+                "Upload {} has SHA-256 {} but {} was expected",
+                params.upload, actual, expected
+            )));
+        }
+    }
+
+    let relative = cache_relative(&params.filename, params.subdir.as_deref())?;
+    let target = Path::new(REMOTE_CACHE_DIR).join(&relative);
+    if let Some(dir) = target.parent() {
+        std::fs::create_dir_all(dir).map_err(internal)?;
+    }
+    std::fs::rename(&staged, &target).map_err(internal)?;
+    info!("Saved chunked upload to {:?}", target);
+    Ok(json!({ "path": relative }))
+}
+
+/// `subdir/filename`, as long as it stays inside the cache: an absolute
+/// path would replace the cache root when joined.
+fn cache_relative(filename: &str, subdir: Option<&str>) -> Result<String, BrpError> {
+    let relative = match subdir.filter(|s| !s.is_empty()) {
+        Some(subdir) => format!("{}/{}", subdir, filename),
+        None => filename.to_string(),
+    };
+    // Backslashes and drive prefixes are separators only on Windows, but are
+    // refused everywhere so a path means the same on every platform
+    let inside = !filename.is_empty()
+        && Path::new(&relative).components().all(|c| matches!(c, Component::Normal(_)))
+        && !relative.starts_with('\\')
+        && !relative.contains(':')
+        && !relative.split(['/', '\\']).any(|part| part == "..");
+    if !inside {
+        return Err(invalid(format!("Refusing to store an upload outside the cache: {}", relative)));
+    }
+    Ok(relative)
+}
+
+fn cache_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![PathBuf::from(REMOTE_CACHE_DIR)];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                stack.push(path);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut context = Context::new(&SHA256);
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        context.update(&buffer[..read]);
+    }
+    Ok(context.finish().as_ref().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn staging_path(upload: &str) -> Result<PathBuf, BrpError> {
+    let valid = !upload.is_empty()
+        && upload.len() <= 64
+        && upload.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(invalid(format!("Invalid upload id `{}`", upload)));
+    }
+    Ok(Path::new(STAGING_DIR).join(format!("{}.part", upload)))
+}
+
+fn parse<T: for<'de> Deserialize<'de>>(params: Option<Value>) -> Result<T, BrpError> {
+    serde_json::from_value(params.unwrap_or_default()).map_err(|e| invalid(e.to_string()))
+}
+
+fn invalid(message: String) -> BrpError {
+    BrpError { code: error_codes::INVALID_PARAMS, message, data: None }
+}
+
+fn internal(e: std::io::Error) -> BrpError {
+    BrpError { code: error_codes::INTERNAL_ERROR, message: e.to_string(), data: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_relative_stays_inside_the_cache() {
+        assert_eq!(cache_relative("crate.glb", Some("props")).unwrap(), "props/crate.glb");
+        assert_eq!(cache_relative("crate.glb", Some("")).unwrap(), "crate.glb");
+
+        for (filename, subdir) in [
+            ("", None),
+            ("crate.glb", Some("../..")),
+            ("..\\crate.glb", None),
+            ("/etc/crate.glb", None),
+            ("crate.glb", Some("/etc")),
+            ("C:\\crate.glb", None),
+            ("crate.glb", Some("C:")),
+            ("\\\\server\\share\\crate.glb", None),
+        ] {
+            assert!(cache_relative(filename, subdir).is_err(), "{} in {:?}", filename, subdir);
+        }
+    }
+}
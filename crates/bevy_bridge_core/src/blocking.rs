@@ -0,0 +1,124 @@
+//! A [`BrpClient`] for synchronous code, such as a tool's `execute`.
+//!
+//! Every blocking client runs its requests on one runtime shared by the
+//! process, started on first use, instead of each call building its own.
+//! Called from a task on a multi-threaded runtime, such as a tool run by the
+//! agent, the worker hands its other tasks off while it blocks. A
+//! current-thread runtime has no other worker, so calling in from one panics;
+//! use the async client there.
+
+use crate::{BrpClient, BrpConfig, Result};
+use serde_json::Value;
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::runtime::{Builder, Handle, Runtime, RuntimeFlavor};
+
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+fn runtime() -> &'static Runtime {
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .worker_threads(1)
+            .thread_name("brp-blocking")
+            .enable_all()
+            .build()
+            .expect("Failed to build the BRP runtime")
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct BrpClientBlocking {
+    client: BrpClient,
+}
+
+impl BrpClientBlocking {
+    pub fn new(config: BrpConfig) -> Self {
+        Self::from_client(BrpClient::new(config))
+    }
+
+    pub fn from_env() -> Self {
+        Self::new(BrpConfig::from_env())
+    }
+
+    /// Wrap `client`, keeping its middleware.
+    pub fn from_client(client: BrpClient) -> Self {
+        Self { client }
+    }
+
+    /// The async client, for passing to ops under [`Self::block_on`].
+    pub fn client(&self) -> &BrpClient {
+        &self.client
+    }
+
+    /// Run `future` to completion on the shared runtime, e.g.
+    /// `blocking.block_on(ops::clear::clear(blocking.client(), target))`.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match Handle::try_current() {
+            Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+                tokio::task::block_in_place(|| runtime().block_on(future))
+            }
+            _ => runtime().block_on(future),
+        }
+    }
+
+    pub fn send_rpc(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        self.block_on(self.client.send_rpc(method, params))
+    }
+
+    pub fn send_rpc_with_timeout(&self, method: &str, params: Option<Value>, timeout: Duration) -> Result<Value> {
+        self.block_on(self.client.send_rpc_with_timeout(method, params, timeout))
+    }
+
+    pub fn send_batch(&self, requests: Vec<(&str, Option<Value>)>) -> Result<Vec<Result<Value>>> {
+        self.block_on(self.client.send_batch(requests))
+    }
+
+    pub fn config(&self) -> &BrpConfig {
+        self.client.config()
+    }
+}
+
+impl Default for BrpClientBlocking {
+    fn default() -> Self {
+        Self::new(BrpConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops;
+    use crate::testing::MockBrpServer;
+    use serde_json::json;
+
+    #[test]
+    fn test_blocking_calls_share_one_runtime() {
+        let server = MockBrpServer::start();
+        server.reply("world.query", json!([{ "entity": 1, "components": {} }]));
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let blocking = BrpClientBlocking::new(server.config());
+                std::thread::spawn(move || {
+                    let entities = blocking.send_rpc("world.query", Some(json!({ "data": {} }))).unwrap();
+                    assert_eq!(entities[0]["entity"], 1);
+                    let response = blocking.block_on(ops::query::query(blocking.client(), vec![], Default::default()));
+                    assert_eq!(response.unwrap().total, 1);
+                })
+            })
+            .collect();
+        threads.into_iter().for_each(|t| t.join().unwrap());
+        assert_eq!(server.calls_to("world.query").len(), 8);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_blocking_calls_from_a_task() {
+        let server = MockBrpServer::start();
+        server.reply("rpc.discover", json!({ "methods": [] }));
+
+        let blocking = BrpClientBlocking::new(server.config());
+        let result = tokio::spawn(async move { blocking.send_rpc("rpc.discover", None) }).await.unwrap();
+        assert_eq!(result.unwrap(), json!({ "methods": [] }));
+    }
+}
@@ -0,0 +1,114 @@
+//! Which BRP methods a game has. Bevy renamed its built-in methods in 0.16
+//! (`bevy/query` became `world.query`); the ops use the new names and the
+//! client falls back to the old ones when a game only knows those.
+
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+/// Current built-in method names and what Bevy 0.15 called them.
+const LEGACY_NAMES: &[(&str, &str)] = &[
+    ("world.get_components", "bevy/get"),
+    ("world.query", "bevy/query"),
+    ("world.spawn_entity", "bevy/spawn"),
+    ("world.insert_components", "bevy/insert"),
+    ("world.remove_components", "bevy/remove"),
+    ("world.despawn_entity", "bevy/destroy"),
+    ("world.reparent_entities", "bevy/reparent"),
+    ("world.list_components", "bevy/list"),
+    ("world.mutate_components", "bevy/mutate_component"),
+    ("world.get_components+watch", "bevy/get+watch"),
+    ("world.list_components+watch", "bevy/list+watch"),
+    ("world.get_resources", "bevy/get_resource"),
+    ("world.insert_resources", "bevy/insert_resource"),
+    ("world.remove_resources", "bevy/remove_resource"),
+    ("world.mutate_resources", "bevy/mutate_resource"),
+    ("world.list_resources", "bevy/list_resources"),
+    ("registry.schema", "bevy/registry/schema"),
+];
+
+/// What a Bevy 0.15 game called `method`, if it is a renamed built-in.
+pub fn legacy_name(method: &str) -> Option<&'static str> {
+    LEGACY_NAMES.iter().find(|(current, _)| *current == method).map(|(_, legacy)| *legacy)
+}
+
+/// The methods one game answers, from `rpc.discover`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    methods: BTreeSet<String>,
+}
+
+impl Capabilities {
+    pub fn from_methods<I, S>(methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self { methods: methods.into_iter().map(Into::into).collect() }
+    }
+
+    /// From the OpenRPC document `rpc.discover` answers with.
+    pub fn from_discover(document: &Value) -> Self {
+        let names = document
+            .get("methods")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|method| method.get("name").and_then(Value::as_str));
+        Self::from_methods(names)
+    }
+
+    /// A game older than `rpc.discover` that answered `bevy/list`: it has
+    /// the legacy built-ins.
+    pub(crate) fn legacy() -> Self {
+        Self::from_methods(LEGACY_NAMES.iter().map(|(_, legacy)| *legacy))
+    }
+
+    pub fn supports(&self, method: &str) -> bool {
+        self.methods.contains(method)
+    }
+
+    /// The name this game knows `method` by, if it has it under either name.
+    pub fn resolve<'a>(&self, method: &'a str) -> Option<&'a str> {
+        if self.supports(method) {
+            return Some(method);
+        }
+        legacy_name(method).filter(|legacy| self.supports(legacy))
+    }
+
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.methods.iter().map(String::as_str)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_from_discover_reads_method_names() {
+        let document = json!({
+            "openrpc": "1.3.2",
+            "info": {},
+            "methods": [
+                { "name": "world.query", "params": [] },
+                { "name": "axiom.screenshot", "params": [] }
+            ]
+        });
+        let capabilities = Capabilities::from_discover(&document);
+        assert_eq!(capabilities.methods().collect::<Vec<_>>(), vec!["axiom.screenshot", "world.query"]);
+        assert!(capabilities.supports("world.query"));
+        assert!(!capabilities.supports("world.spawn_entity"));
+    }
+
+    #[test]
+    fn test_resolve_prefers_current_names() {
+        let current = Capabilities::from_methods(["world.query", "bevy/query"]);
+        assert_eq!(current.resolve("world.query"), Some("world.query"));
+
+        let legacy = Capabilities::legacy();
+        assert_eq!(legacy.resolve("world.query"), Some("bevy/query"));
+        assert_eq!(legacy.resolve("world.despawn_entity"), Some("bevy/destroy"));
+        assert_eq!(legacy.resolve("axiom.screenshot"), None);
+    }
+}
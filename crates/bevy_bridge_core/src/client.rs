@@ -1,13 +1,46 @@
+use crate::capabilities::{self, Capabilities};
+use crate::compression;
+use crate::limiter::Limiter;
+use crate::middleware::{BrpMiddleware, Chain, RpcRequest};
+use crate::recording::{self, Exchange, Traffic};
+use crate::types::RpcError;
 use crate::{BrpConfig, BrpError, Result};
+use reqwest::header::HeaderMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::sync::{atomic::{AtomicU64, Ordering}, Arc};
+use std::collections::HashMap;
+use std::sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Mutex, OnceLock};
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+/// Called after every request with its method, duration and whether it succeeded.
+pub type RpcObserver = fn(&str, Duration, bool);
+
+static OBSERVER: OnceLock<RpcObserver> = OnceLock::new();
+
+/// Params key carrying [`BrpConfig::token`].
+pub const TOKEN_PARAM: &str = "axiom_token";
+
+/// Install the process-wide [`RpcObserver`], e.g. to collect metrics. Only
+/// the first call takes effect; returns whether this one did.
+pub fn set_rpc_observer(observer: RpcObserver) -> bool {
+    OBSERVER.set(observer).is_ok()
+}
 
 #[derive(Debug, Clone)]
 pub struct BrpClient {
     config: BrpConfig,
     http_client: reqwest::Client,
     request_id: Arc<AtomicU64>,
+    /// Discovered on first need and shared by clones.
+    capabilities: Arc<Mutex<Option<Arc<Capabilities>>>>,
+    /// The game's protocol version was checked; shared by clones.
+    compatible: Arc<AtomicBool>,
+    middleware: Chain,
+    /// The config's rate limit, shared by clones.
+    limiter: Limiter,
+    cancel: Option<CancellationToken>,
 }
 
 #[derive(Debug, Serialize)]
@@ -31,33 +64,172 @@ struct JsonRpcResponse {
 #[serde(untagged)]
 enum ResultOrError {
     Result { result: Value },
-    Error { error: JsonRpcError },
+    Error { error: RpcError },
 }
 
-#[derive(Debug, Deserialize)]
-struct JsonRpcError {
-    code: i32,
-    message: String,
-    #[serde(default)]
-    data: Option<Value>,
+impl From<RpcError> for BrpError {
+    fn from(error: RpcError) -> Self {
+        tracing::warn!(
+            "JSON-RPC error: code={}, message={}",
+            error.code,
+            error.message
+        );
+        BrpError::from_json_rpc(error.code, error.message, error.data)
+    }
+}
+
+impl ResultOrError {
+    fn into_result(self, method: &str, id: u64) -> Result<Value> {
+        match self {
+            ResultOrError::Result { result } => {
+                tracing::debug!("JSON-RPC request successful: method={}, id={}", method, id);
+                Ok(result)
+            }
+            ResultOrError::Error { error } => Err(error.into()),
+        }
+    }
 }
 
 impl BrpClient {
     pub fn new(config: BrpConfig) -> Self {
-        let http_client = reqwest::Client::builder()
-            .timeout(config.timeout)
+        let http_client = config
+            .tls
+            .apply(reqwest::Client::builder().timeout(config.timeout))
             .build()
             .expect("Failed to build HTTP client");
 
         Self {
+            limiter: Limiter::new(&config.rate_limit),
             config,
             http_client,
             request_id: Arc::new(AtomicU64::new(1)),
+            capabilities: Arc::new(Mutex::new(None)),
+            compatible: Arc::new(AtomicBool::new(false)),
+            middleware: Chain::default(),
+            cancel: None,
         }
     }
 
+    /// Run `middleware` around every request this client and its later
+    /// clones send, after any added before it.
+    pub fn with_middleware(mut self, middleware: impl BrpMiddleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// A clone whose requests fail with [`BrpError::Cancelled`] once
+    /// `cancel` is, dropping any in flight, e.g. for a "stop" button during
+    /// an upload that takes several requests.
+    pub fn with_cancellation(&self, cancel: CancellationToken) -> Self {
+        Self { cancel: Some(cancel), ..self.clone() }
+    }
+
     pub async fn send_rpc(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        self.send_rpc_with_timeout(method, params, self.config.timeout).await
+    }
+
+    /// [`send_rpc`](Self::send_rpc) that gives up with
+    /// [`BrpError::Cancelled`] as soon as `cancel` is cancelled, rather than
+    /// waiting for the answer or the timeout.
+    pub async fn send_rpc_cancellable(&self, method: &str, params: Option<Value>, cancel: &CancellationToken) -> Result<Value> {
+        self.with_cancellation(cancel.clone()).send_rpc(method, params).await
+    }
+
+    /// [`send_rpc`](Self::send_rpc) with `timeout` instead of the configured
+    /// one, for requests known to take longer (or that should fail faster).
+    pub async fn send_rpc_with_timeout(&self, method: &str, params: Option<Value>, timeout: Duration) -> Result<Value> {
+        let started = Instant::now();
+        let result = match &self.config.traffic {
+            Traffic::Live => self.request_any_version(method, params, timeout).await,
+            Traffic::Record(path) => {
+                let result = self.request_any_version(method, params.clone(), timeout).await;
+                recording::record(path, &Exchange::new(method, params, &result, started.elapsed()));
+                result
+            }
+            Traffic::Replay(path) => recording::replay(path, method, params.as_ref()),
+        };
+        if let Some(observer) = OBSERVER.get() {
+            observer(method, started.elapsed(), result.is_ok());
+        }
+        result
+    }
+
+    /// The methods the game has, asked for once per client and its clones.
+    /// Games older than `rpc.discover` that answer `bevy/list` are taken to
+    /// have Bevy 0.15's built-ins.
+    pub async fn capabilities(&self) -> Result<Arc<Capabilities>> {
+        if let Some(known) = self.known_capabilities() {
+            return Ok(known);
+        }
+        if let Traffic::Replay(path) = &self.config.traffic {
+            return Err(BrpError::Replay(format!(
+                "capabilities are discovered from the game, not {}",
+                path.display()
+            )));
+        }
+        // Straight to `request` so discovery doesn't land in recordings
+        let timeout = self.config.timeout;
+        let capabilities = match self.request("rpc.discover", None, timeout).await {
+            Ok(document) => Capabilities::from_discover(&document),
+            Err(BrpError::MethodNotFound { .. }) => {
+                self.request("bevy/list", None, timeout).await?;
+                Capabilities::legacy()
+            }
+            Err(e) => return Err(e),
+        };
+        let capabilities = Arc::new(capabilities);
+        *self.capabilities.lock().unwrap() = Some(capabilities.clone());
+        Ok(capabilities)
+    }
+
+    /// Check the plugin's protocol version once, before the first request,
+    /// so drift shows up as [`BrpError::VersionMismatch`] rather than as
+    /// whatever the first mismatched call fails with.
+    async fn ensure_compatible(&self, timeout: Duration) -> Result<()> {
+        if self.compatible.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        crate::ops::handshake::check_with(self, timeout).await?;
+        self.compatible.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn known_capabilities(&self) -> Option<Arc<Capabilities>> {
+        self.capabilities.lock().unwrap().clone()
+    }
+
+    /// [`request`](Self::request) once the game is known to be compatible,
+    /// retried under the method's Bevy 0.15 name when the game turns out not
+    /// to know the current one.
+    async fn request_any_version(&self, method: &str, params: Option<Value>, timeout: Duration) -> Result<Value> {
+        self.ensure_compatible(timeout).await?;
+        let Some(legacy) = capabilities::legacy_name(method) else {
+            return self.request(method, params, timeout).await;
+        };
+        if let Some(known) = self.known_capabilities() {
+            let name = known.resolve(method).unwrap_or(method);
+            return self.request(name, params, timeout).await;
+        }
+        let result = self.request(method, params.clone(), timeout).await;
+        if !matches!(result, Err(BrpError::MethodNotFound { .. })) {
+            return result;
+        }
+        match self.capabilities().await {
+            Ok(known) if known.supports(legacy) => {
+                tracing::debug!("Game has no {}, using {}", method, legacy);
+                self.request(legacy, params, timeout).await
+            }
+            _ => result,
+        }
+    }
+
+    pub(crate) async fn request(&self, method: &str, params: Option<Value>, timeout: Duration) -> Result<Value> {
         let id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        // Middleware is shown the params without the token
+        let shown = if self.middleware.is_empty() { None } else { params.clone() };
+        let view = RpcRequest { method, id, params: shown.as_ref() };
+        // Added here rather than in `send_rpc` so recordings don't keep it
+        let params = self.wire_params(params);
 
         let request = JsonRpcRequest {
             jsonrpc: "2.0".to_string(),
@@ -68,47 +240,217 @@ impl BrpClient {
 
         tracing::debug!("Sending JSON-RPC request: method={}, id={}", method, id);
 
+        let mut headers = HeaderMap::new();
+        self.middleware.on_request(&view, &mut headers);
+        let mut started = Instant::now();
+        let result = self
+            .cancellable(async {
+                let _turn = self.limiter.turn().await;
+                started = Instant::now();
+                self.exchange(&request, headers, timeout).await
+            })
+            .await;
+        self.middleware.on_response(&view, result.as_ref(), started.elapsed());
+        result
+    }
+
+    /// `work`, or [`BrpError::Cancelled`] if this client's token is
+    /// cancelled first. Dropping a request closes its connection.
+    async fn cancellable<T>(&self, work: impl Future<Output = Result<T>>) -> Result<T> {
+        let Some(cancel) = &self.cancel else { return work.await };
+        tokio::select! {
+            biased;
+            _ = cancel.cancelled() => Err(BrpError::Cancelled),
+            result = work => result,
+        }
+    }
+
+    async fn exchange(&self, request: &JsonRpcRequest, headers: HeaderMap, timeout: Duration) -> Result<Value> {
         let response = self
-            .http_client
-            .post(&self.config.endpoint)
-            .json(&request)
+            .post(&self.http_client)
+            .headers(headers)
+            .timeout(timeout)
+            .json(request)
             .send()
-            .await?;
+            .await
+            .map_err(|e| transport_error(e, timeout))?;
 
         if !response.status().is_success() {
-            return Err(BrpError::InvalidResponse(format!(
-                "HTTP error: {}",
-                response.status()
-            )));
+            return Err(http_error(response.status()));
         }
 
-        let json_response: JsonRpcResponse = response.json().await?;
+        let json_response: JsonRpcResponse = response.json().await.map_err(|e| transport_error(e, timeout))?;
 
-        if json_response.id != id {
+        if json_response.id != request.id {
             return Err(BrpError::InvalidResponse(format!(
                 "Response ID mismatch: expected {}, got {}",
-                id, json_response.id
+                request.id, json_response.id
             )));
         }
 
-        match json_response.result_or_error {
-            ResultOrError::Result { result } => {
-                tracing::debug!("JSON-RPC request successful: method={}, id={}", method, id);
-                Ok(result)
+        json_response.result_or_error.into_result(&request.method, request.id)
+    }
+
+    /// Send `requests` in one HTTP round trip as a JSON-RPC batch. Each
+    /// request gets its own result, in the order given; the outer error is
+    /// for the batch as a whole not getting through.
+    ///
+    /// The game still handles one request of a batch per frame, so very
+    /// large batches are better split to stay within the timeout.
+    pub async fn send_batch(&self, requests: Vec<(&str, Option<Value>)>) -> Result<Vec<Result<Value>>> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
+        let started = Instant::now();
+        let results = match &self.config.traffic {
+            Traffic::Live => self.batch_request(&requests).await,
+            Traffic::Record(path) => {
+                let results = self.batch_request(&requests).await;
+                for (i, (method, params)) in requests.iter().enumerate() {
+                    let exchange = match &results {
+                        Ok(results) => Exchange::new(method, params.clone(), &results[i], started.elapsed()),
+                        Err(e) => Exchange::failed(method, params.clone(), e, started.elapsed()),
+                    };
+                    recording::record(path, &exchange);
+                }
+                results
             }
-            ResultOrError::Error { error } => {
-                tracing::warn!(
-                    "JSON-RPC error: code={}, message={}",
-                    error.code,
-                    error.message
-                );
-                Err(BrpError::JsonRpc {
-                    code: error.code,
-                    message: error.message,
-                    data: error.data,
-                })
+            Traffic::Replay(path) => Ok(requests
+                .iter()
+                .map(|(method, params)| recording::replay(path, method, params.as_ref()))
+                .collect()),
+        };
+        if let Some(observer) = OBSERVER.get() {
+            for (i, (method, _)) in requests.iter().enumerate() {
+                let ok = results.as_ref().is_ok_and(|results| results[i].is_ok());
+                observer(method, started.elapsed(), ok);
             }
         }
+        results
+    }
+
+    async fn batch_request(&self, requests: &[(&str, Option<Value>)]) -> Result<Vec<Result<Value>>> {
+        self.ensure_compatible(self.config.timeout).await?;
+        let batch: Vec<JsonRpcRequest> = requests
+            .iter()
+            .map(|(method, params)| JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                method: method.to_string(),
+                id: self.request_id.fetch_add(1, Ordering::Relaxed),
+                params: self.wire_params(params.clone()),
+            })
+            .collect();
+
+        tracing::debug!("Sending JSON-RPC batch of {} requests", batch.len());
+
+        let views: Vec<RpcRequest> = batch
+            .iter()
+            .zip(requests)
+            .map(|(request, (method, params))| RpcRequest { method, id: request.id, params: params.as_ref() })
+            .collect();
+        let mut headers = HeaderMap::new();
+        views.iter().for_each(|view| self.middleware.on_request(view, &mut headers));
+        let mut started = Instant::now();
+        let results = self
+            .cancellable(async {
+                let _turn = self.limiter.turn().await;
+                started = Instant::now();
+                self.exchange_batch(&batch, headers).await
+            })
+            .await;
+        for (i, view) in views.iter().enumerate() {
+            let outcome = match &results {
+                Ok(results) => results[i].as_ref(),
+                Err(e) => Err(e),
+            };
+            self.middleware.on_response(view, outcome, started.elapsed());
+        }
+        results
+    }
+
+    async fn exchange_batch(&self, batch: &[JsonRpcRequest], headers: HeaderMap) -> Result<Vec<Result<Value>>> {
+        let timeout = self.config.timeout;
+        let response = self
+            .post(&self.http_client)
+            .headers(headers)
+            .json(&batch)
+            .send()
+            .await
+            .map_err(|e| transport_error(e, timeout))?;
+
+        if !response.status().is_success() {
+            return Err(http_error(response.status()));
+        }
+
+        let body: Value = response.json().await.map_err(|e| transport_error(e, timeout))?;
+        demultiplex(batch, body)
+    }
+
+    /// Send a streaming (`+watch`) request and return the open response,
+    /// whose body is a `text/event-stream`. Streams aren't recorded and
+    /// can't be replayed.
+    pub(crate) async fn open_stream(&self, method: &str, params: Option<Value>) -> Result<reqwest::Response> {
+        if let Traffic::Replay(path) = &self.config.traffic {
+            return Err(BrpError::Replay(format!(
+                "{} streams from the game and can't be replayed from {}",
+                method,
+                path.display()
+            )));
+        }
+        self.ensure_compatible(self.config.timeout).await?;
+        let id = self.request_id.fetch_add(1, Ordering::Relaxed);
+        let mut headers = HeaderMap::new();
+        let view = RpcRequest { method, id, params: params.as_ref() };
+        self.middleware.on_request(&view, &mut headers);
+        let params = self.wire_params(params);
+        let request = JsonRpcRequest {
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            id,
+            params,
+        };
+
+        tracing::debug!("Opening JSON-RPC stream: method={}, id={}", method, id);
+
+        // Counted against the rate, but not in flight for the stream's life
+        self.limiter.turn().await;
+        // The configured timeout covers the whole body, which a stream never finishes
+        let builder = reqwest::Client::builder().connect_timeout(self.config.timeout);
+        let http_client = self.config.tls.apply(builder).build()?;
+        let response = self
+            .post(&http_client)
+            .headers(headers)
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| transport_error(e, self.config.timeout))?;
+        if !response.status().is_success() {
+            return Err(http_error(response.status()));
+        }
+        Ok(response)
+    }
+
+    /// `params` as they go over the wire: compressed if large, then with
+    /// the token.
+    fn wire_params(&self, params: Option<Value>) -> Option<Value> {
+        let params = match (params, self.config.compress_above) {
+            (Some(params), Some(threshold)) => Some(compression::compress(params, threshold)),
+            (params, _) => params,
+        };
+        match &self.config.token {
+            Some(token) => with_token(params, token),
+            None => params,
+        }
+    }
+
+    /// A POST to the endpoint, with the token as a bearer header for any
+    /// proxy in front of the game; the plugin reads the copy in the params.
+    fn post(&self, http_client: &reqwest::Client) -> reqwest::RequestBuilder {
+        let request = http_client.post(&self.config.endpoint);
+        match &self.config.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
     }
 
     pub fn config(&self) -> &BrpConfig {
@@ -116,6 +458,67 @@ impl BrpClient {
     }
 }
 
+/// Timeouts get their own variant so callers can tell a slow game from an
+/// unreachable one.
+fn transport_error(error: reqwest::Error, timeout: Duration) -> BrpError {
+    if error.is_timeout() {
+        BrpError::Timeout(timeout)
+    } else {
+        BrpError::Connection(error)
+    }
+}
+
+/// A proxy or gateway in front of the game may refuse a request outright.
+fn http_error(status: reqwest::StatusCode) -> BrpError {
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+            BrpError::PermissionDenied { message: format!("HTTP error: {}", status), data: None }
+        }
+        status => BrpError::InvalidResponse(format!("HTTP error: {}", status)),
+    }
+}
+
+/// Match the responses to a batch to its requests by id. A batch rejected as
+/// a whole comes back as a single error object instead of an array.
+fn demultiplex(batch: &[JsonRpcRequest], body: Value) -> Result<Vec<Result<Value>>> {
+    let responses: Vec<JsonRpcResponse> = match body {
+        Value::Array(_) => serde_json::from_value(body)?,
+        mut other => {
+            return Err(match other.get_mut("error").map(Value::take) {
+                Some(error) => serde_json::from_value::<RpcError>(error)?.into(),
+                None => BrpError::InvalidResponse(format!("Expected an array in reply to a batch, got {}", other)),
+            })
+        }
+    };
+    let mut by_id: HashMap<u64, ResultOrError> = responses
+        .into_iter()
+        .map(|response| (response.id, response.result_or_error))
+        .collect();
+    Ok(batch
+        .iter()
+        .map(|request| match by_id.remove(&request.id) {
+            Some(response) => response.into_result(&request.method, request.id),
+            None => Err(BrpError::InvalidResponse(format!(
+                "No response to {} (id {}) in the batch",
+                request.method, request.id
+            ))),
+        })
+        .collect())
+}
+
+/// `bevy_remote` doesn't pass HTTP headers on to the game, so the token
+/// goes in the params, where `bevy_ai_remote` checks and removes it. Array
+/// params can't carry it and are sent as they are, for the game to reject.
+fn with_token(params: Option<Value>, token: &str) -> Option<Value> {
+    let mut object = match params {
+        None => serde_json::Map::new(),
+        Some(Value::Object(object)) => object,
+        other => return other,
+    };
+    object.insert(TOKEN_PARAM.to_string(), Value::String(token.to_string()));
+    Some(Value::Object(object))
+}
+
 impl Default for BrpClient {
     fn default() -> Self {
         Self::new(BrpConfig::default())
@@ -147,4 +550,123 @@ mod tests {
         assert_eq!(client.request_id.fetch_add(1, Ordering::Relaxed), 2);
         assert_eq!(client.request_id.fetch_add(1, Ordering::Relaxed), 3);
     }
+
+    #[tokio::test]
+    async fn test_timeout_override_maps_to_timeout() {
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let client = BrpClient::new(BrpConfig::new(endpoint, Duration::from_secs(30)));
+
+        let timeout = Duration::from_millis(100);
+        let started = Instant::now();
+        let result = client.send_rpc_with_timeout("world.query", None, timeout).await;
+        assert!(matches!(result, Err(BrpError::Timeout(t)) if t == timeout), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn test_cancelling_abandons_a_request_in_flight() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        let client = BrpClient::new(BrpConfig::new(endpoint, Duration::from_secs(30)));
+
+        let cancel = CancellationToken::new();
+        let stop = cancel.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            stop.cancel();
+        });
+        let started = Instant::now();
+        let result = client.send_rpc_cancellable("world.query", None, &cancel).await;
+        assert!(matches!(result, Err(BrpError::Cancelled)), "{:?}", result);
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // Already cancelled, so nothing more is sent
+        let result = client.with_cancellation(cancel).send_rpc("world.query", None).await;
+        assert!(matches!(result, Err(BrpError::Cancelled)), "{:?}", result);
+    }
+
+    #[derive(Default)]
+    struct Trace {
+        seen: Mutex<Vec<(String, Option<Value>, bool)>>,
+    }
+
+    impl BrpMiddleware for Trace {
+        fn on_request(&self, _request: &RpcRequest<'_>, headers: &mut HeaderMap) {
+            headers.insert("x-trace", "abc".parse().unwrap());
+        }
+
+        fn on_response(&self, request: &RpcRequest<'_>, outcome: std::result::Result<&Value, &BrpError>, _elapsed: Duration) {
+            let seen = (request.method.to_string(), request.params.cloned(), outcome.is_ok());
+            self.seen.lock().unwrap().push(seen);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_sees_requests_and_adds_headers() {
+        let server = crate::testing::MockBrpServer::start();
+        server.reply("world.query", serde_json::json!([]));
+
+        let trace = Arc::new(Trace::default());
+        let client = BrpClient::new(server.config().with_token("s3cret")).with_middleware(trace.clone());
+        let params = serde_json::json!({ "entity": 1 });
+        client.send_rpc("world.query", Some(params.clone())).await.unwrap();
+
+        let query = server.calls().into_iter().find(|call| call.method == "world.query").unwrap();
+        assert_eq!(query.headers["x-trace"], "abc");
+        // The handshake went through the middleware too, and neither shows the token
+        let seen = trace.seen.lock().unwrap();
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[1], ("world.query".to_string(), Some(params), true));
+    }
+
+    #[test]
+    fn test_token_is_sent_as_bearer_header() {
+        let client = BrpClient::new(BrpConfig::default().with_token("s3cret"));
+        let request = client.post(&client.http_client).build().unwrap();
+        assert_eq!(request.headers()[reqwest::header::AUTHORIZATION], "Bearer s3cret");
+
+        let request = BrpClient::default().post(&client.http_client).build().unwrap();
+        assert!(request.headers().get(reqwest::header::AUTHORIZATION).is_none());
+    }
+
+    #[test]
+    fn test_with_token() {
+        use serde_json::json;
+
+        assert_eq!(with_token(None, "s3cret"), Some(json!({ "axiom_token": "s3cret" })));
+        assert_eq!(
+            with_token(Some(json!({ "entity": 1 })), "s3cret"),
+            Some(json!({ "entity": 1, "axiom_token": "s3cret" }))
+        );
+        assert_eq!(with_token(Some(json!([1])), "s3cret"), Some(json!([1])));
+    }
+
+    #[test]
+    fn test_demultiplex_matches_responses_by_id() {
+        use serde_json::json;
+
+        let batch: Vec<JsonRpcRequest> = (7..10)
+            .map(|id| JsonRpcRequest {
+                jsonrpc: "2.0".to_string(),
+                method: "world.despawn_entity".to_string(),
+                id,
+                params: None,
+            })
+            .collect();
+        // Out of order, one failed and one missing
+        let body = json!([
+            { "jsonrpc": "2.0", "id": 9, "error": { "code": -23401, "message": "Entity not found" } },
+            { "jsonrpc": "2.0", "id": 7, "result": null },
+        ]);
+        let results = demultiplex(&batch, body).unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &Value::Null);
+        assert!(matches!(results[1], Err(BrpError::InvalidResponse(_))));
+        assert!(matches!(results[2], Err(BrpError::EntityNotFound { .. })));
+
+        let rejected = json!({ "jsonrpc": "2.0", "id": null, "error": { "code": -32600, "message": "bad batch" } });
+        assert!(matches!(demultiplex(&batch, rejected), Err(BrpError::JsonRpc { code: -32600, .. })));
+    }
 }
@@ -0,0 +1,56 @@
+//! Gzipped params for large requests, such as uploads, on slow links.
+//!
+//! `bevy_remote` reads request bodies as plain JSON and doesn't pass HTTP
+//! headers on, so a `Content-Encoding` would never reach the game. Instead
+//! the params themselves are replaced by [`COMPRESSED_PARAM`], their JSON
+//! gzipped and base64-encoded, which `bevy_ai_remote` inflates before the
+//! method sees them.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde_json::{json, Value};
+use std::io::Write;
+
+/// Params key carrying the compressed params.
+pub const COMPRESSED_PARAM: &str = "axiom_gzip";
+
+/// `params` compressed if their JSON is over `threshold` bytes and that
+/// makes them smaller; otherwise as they are.
+pub(crate) fn compress(params: Value, threshold: usize) -> Value {
+    let json = params.to_string();
+    if json.len() <= threshold {
+        return params;
+    }
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    let Ok(gzipped) = encoder.write_all(json.as_bytes()).and_then(|_| encoder.finish()) else {
+        return params;
+    };
+    let packed = BASE64.encode(gzipped);
+    if packed.len() >= json.len() {
+        return params;
+    }
+    tracing::debug!("Compressed params from {} to {} bytes", json.len(), packed.len());
+    json!({ COMPRESSED_PARAM: packed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+
+    #[test]
+    fn test_only_large_params_are_compressed() {
+        let small = json!({ "entity": 7 });
+        assert_eq!(compress(small.clone(), 1024), small);
+
+        let large = json!({ "filename": "crate.glb", "data_base64": "A".repeat(10_000) });
+        let packed = compress(large.clone(), 1024);
+        let gzipped = BASE64.decode(packed[COMPRESSED_PARAM].as_str().unwrap()).unwrap();
+        assert!(gzipped.len() < 1024);
+        let mut json = String::new();
+        GzDecoder::new(&gzipped[..]).read_to_string(&mut json).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), large);
+    }
+}
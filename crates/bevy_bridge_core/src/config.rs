@@ -1,16 +1,145 @@
+use crate::recording::Traffic;
+use crate::Result;
+use std::path::Path;
 use std::time::Duration;
 
+/// Port `bevy_ai_remote` listens on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 15721;
+
+/// The game on this machine, listening on `port`.
+fn local_endpoint(port: u16) -> String {
+    format!("http://127.0.0.1:{}", port)
+}
+
 #[derive(Debug, Clone)]
 pub struct BrpConfig {
     pub endpoint: String,
     pub timeout: Duration,
+    /// Live, recorded or replayed; see [`crate::recording`].
+    pub traffic: Traffic,
+    /// Shared secret sent with every request, for a game whose
+    /// `bevy_ai_remote` plugin requires one. Read from `BRP_TOKEN`, or
+    /// `BRP_AUTH_TOKEN`.
+    pub token: Option<String>,
+    /// How an `https://` endpoint's certificate is checked.
+    pub tls: TlsConfig,
+    /// How fast requests may go out; unlimited by default.
+    pub rate_limit: RateLimit,
+    /// Params whose JSON is larger than this many bytes are sent gzipped;
+    /// see [`crate::compression`]. Needs `bevy_ai_remote` in the game, so
+    /// off by default. Read from `BRP_COMPRESS_ABOVE`.
+    pub compress_above: Option<usize>,
+}
+
+/// A cap on the requests a client and its clones send, so a runaway tool
+/// loop can't flood the game and tank its frame rate. Requests over it wait
+/// their turn rather than fail. A batch counts as one request.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RateLimit {
+    /// Sustained requests per second, with bursts of up to a second's worth.
+    /// Read from `BRP_MAX_RPS`.
+    pub per_second: Option<f64>,
+    /// Requests in flight at once. Read from `BRP_MAX_CONCURRENT`.
+    pub max_concurrent: Option<usize>,
+}
+
+impl RateLimit {
+    pub fn per_second(mut self, requests: f64) -> Self {
+        self.per_second = Some(requests);
+        self
+    }
+
+    pub fn max_concurrent(mut self, requests: usize) -> Self {
+        self.max_concurrent = Some(requests);
+        self
+    }
+
+    fn from_env() -> Self {
+        let var = |name: &str| std::env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+        let mut limit = Self::default();
+        if let Some(value) = var("BRP_MAX_RPS") {
+            match value.parse::<f64>() {
+                Ok(rate) if rate > 0.0 => limit.per_second = Some(rate),
+                _ => tracing::warn!("Ignoring BRP_MAX_RPS {}: not a positive number", value),
+            }
+        }
+        if let Some(value) = var("BRP_MAX_CONCURRENT") {
+            match value.parse::<usize>() {
+                Ok(count) if count > 0 => limit.max_concurrent = Some(count),
+                _ => tracing::warn!("Ignoring BRP_MAX_CONCURRENT {}: not a positive integer", value),
+            }
+        }
+        limit
+    }
+}
+
+/// Certificate checks for `https://` endpoints, such as a game behind a TLS
+/// proxy on the LAN. The system's roots are always trusted.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// Extra roots, e.g. the CA that signed the proxy's certificate. Read
+    /// from the PEM file in `BRP_TLS_CA`.
+    pub ca_certs: Vec<reqwest::Certificate>,
+    /// Accept any certificate, such as a self-signed one. The traffic is
+    /// still encrypted but the game's identity is not checked. Set by
+    /// `BRP_TLS_INSECURE=1`.
+    pub accept_invalid_certs: bool,
+}
+
+impl TlsConfig {
+    /// Also trust the certificates in a PEM file.
+    pub fn with_ca_file(self, path: impl AsRef<Path>) -> Result<Self> {
+        let pem = std::fs::read(path)?;
+        self.with_ca_pem(&pem)
+    }
+
+    /// Also trust the certificates in `pem`.
+    pub fn with_ca_pem(mut self, pem: &[u8]) -> Result<Self> {
+        let certs = reqwest::Certificate::from_pem_bundle(pem)?;
+        if certs.is_empty() {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "no PEM certificates found").into());
+        }
+        self.ca_certs.extend(certs);
+        Ok(self)
+    }
+
+    pub fn accept_invalid_certs(mut self) -> Self {
+        self.accept_invalid_certs = true;
+        self
+    }
+
+    pub(crate) fn apply(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        let builder = self
+            .ca_certs
+            .iter()
+            .fold(builder, |builder, cert| builder.add_root_certificate(cert.clone()));
+        builder.danger_accept_invalid_certs(self.accept_invalid_certs)
+    }
+
+    fn from_env() -> Self {
+        let mut tls = Self::default();
+        if let Some(path) = std::env::var("BRP_TLS_CA").ok().filter(|p| !p.trim().is_empty()) {
+            match tls.clone().with_ca_file(path.trim()) {
+                Ok(with_ca) => tls = with_ca,
+                Err(e) => tracing::warn!("Ignoring BRP_TLS_CA {}: {}", path, e),
+            }
+        }
+        tls.accept_invalid_certs = std::env::var("BRP_TLS_INSECURE")
+            .is_ok_and(|v| matches!(v.trim().to_ascii_lowercase().as_str(), "1" | "true" | "yes"));
+        tls
+    }
 }
 
 impl Default for BrpConfig {
     fn default() -> Self {
         Self {
-            endpoint: "http://127.0.0.1:15721".to_string(),
+            endpoint: local_endpoint(DEFAULT_PORT),
             timeout: Duration::from_secs(30),
+            traffic: Traffic::Live,
+            token: None,
+            tls: TlsConfig::default(),
+            rate_limit: RateLimit::default(),
+            compress_above: None,
         }
     }
 }
@@ -20,12 +149,56 @@ impl BrpConfig {
         Self {
             endpoint: endpoint.into(),
             timeout,
+            traffic: Traffic::Live,
+            token: None,
+            tls: TlsConfig::default(),
+            rate_limit: RateLimit::default(),
+            compress_above: None,
         }
     }
 
+    /// The defaults, for a game on this machine listening on `port`.
+    pub fn local(port: u16) -> Self {
+        Self { endpoint: local_endpoint(port), ..Self::default() }
+    }
+
+    pub fn with_traffic(mut self, traffic: Traffic) -> Self {
+        self.traffic = traffic;
+        self
+    }
+
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    pub fn with_tls(mut self, tls: TlsConfig) -> Self {
+        self.tls = tls;
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+
+    /// Gzip params larger than `bytes`.
+    pub fn with_compression(mut self, bytes: usize) -> Self {
+        self.compress_above = Some(bytes);
+        self
+    }
+
     pub fn from_env() -> Self {
-        let endpoint =
-            std::env::var("BRP_ENDPOINT").unwrap_or_else(|_| "http://127.0.0.1:15721".to_string());
+        // A port alone, as the game reads it too, means this machine
+        let endpoint = std::env::var("BRP_ENDPOINT").unwrap_or_else(|_| {
+            let port = std::env::var("BRP_PORT").ok().and_then(|v| {
+                v.trim()
+                    .parse::<u16>()
+                    .inspect_err(|_| tracing::warn!("Ignoring BRP_PORT={}: expected a port number", v))
+                    .ok()
+            });
+            local_endpoint(port.unwrap_or(DEFAULT_PORT))
+        });
 
         let timeout = std::env::var("BRP_TIMEOUT_MS")
             .ok()
@@ -33,7 +206,23 @@ impl BrpConfig {
             .map(Duration::from_millis)
             .unwrap_or_else(|| Duration::from_secs(30));
 
-        Self { endpoint, timeout }
+        let compress_above = std::env::var("BRP_COMPRESS_ABOVE").ok().and_then(|v| v.trim().parse::<usize>().ok());
+
+        let token = ["BRP_TOKEN", "BRP_AUTH_TOKEN"]
+            .into_iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .map(|t| t.trim().to_string())
+            .find(|t| !t.is_empty());
+
+        Self {
+            endpoint,
+            timeout,
+            traffic: Traffic::from_env(),
+            token,
+            tls: TlsConfig::from_env(),
+            rate_limit: RateLimit::from_env(),
+            compress_above,
+        }
     }
 }
 
@@ -47,7 +236,17 @@ mod tests {
     struct EnvRestoreGuard {
         _env_lock: std::sync::MutexGuard<'static, ()>,
         endpoint: Option<String>,
+        port: Option<String>,
         timeout_ms: Option<String>,
+        record: Option<String>,
+        replay: Option<String>,
+        token: Option<String>,
+        auth_token: Option<String>,
+        tls_ca: Option<String>,
+        tls_insecure: Option<String>,
+        max_rps: Option<String>,
+        max_concurrent: Option<String>,
+        compress_above: Option<String>,
     }
 
     impl EnvRestoreGuard {
@@ -57,7 +256,17 @@ mod tests {
             Self {
                 _env_lock: env_lock.lock().expect("failed to acquire env lock"),
                 endpoint: std::env::var("BRP_ENDPOINT").ok(),
+                port: std::env::var("BRP_PORT").ok(),
                 timeout_ms: std::env::var("BRP_TIMEOUT_MS").ok(),
+                record: std::env::var("BRP_RECORD").ok(),
+                replay: std::env::var("BRP_REPLAY").ok(),
+                token: std::env::var("BRP_TOKEN").ok(),
+                auth_token: std::env::var("BRP_AUTH_TOKEN").ok(),
+                tls_ca: std::env::var("BRP_TLS_CA").ok(),
+                tls_insecure: std::env::var("BRP_TLS_INSECURE").ok(),
+                max_rps: std::env::var("BRP_MAX_RPS").ok(),
+                max_concurrent: std::env::var("BRP_MAX_CONCURRENT").ok(),
+                compress_above: std::env::var("BRP_COMPRESS_ABOVE").ok(),
             }
         }
     }
@@ -69,10 +278,60 @@ mod tests {
                 None => unsafe { std::env::remove_var("BRP_ENDPOINT") },
             }
 
+            match &self.port {
+                Some(value) => unsafe { std::env::set_var("BRP_PORT", value) },
+                None => unsafe { std::env::remove_var("BRP_PORT") },
+            }
+
             match &self.timeout_ms {
                 Some(value) => unsafe { std::env::set_var("BRP_TIMEOUT_MS", value) },
                 None => unsafe { std::env::remove_var("BRP_TIMEOUT_MS") },
             }
+
+            match &self.record {
+                Some(value) => unsafe { std::env::set_var("BRP_RECORD", value) },
+                None => unsafe { std::env::remove_var("BRP_RECORD") },
+            }
+
+            match &self.replay {
+                Some(value) => unsafe { std::env::set_var("BRP_REPLAY", value) },
+                None => unsafe { std::env::remove_var("BRP_REPLAY") },
+            }
+
+            match &self.token {
+                Some(value) => unsafe { std::env::set_var("BRP_TOKEN", value) },
+                None => unsafe { std::env::remove_var("BRP_TOKEN") },
+            }
+
+            match &self.auth_token {
+                Some(value) => unsafe { std::env::set_var("BRP_AUTH_TOKEN", value) },
+                None => unsafe { std::env::remove_var("BRP_AUTH_TOKEN") },
+            }
+
+            match &self.tls_ca {
+                Some(value) => unsafe { std::env::set_var("BRP_TLS_CA", value) },
+                None => unsafe { std::env::remove_var("BRP_TLS_CA") },
+            }
+
+            match &self.tls_insecure {
+                Some(value) => unsafe { std::env::set_var("BRP_TLS_INSECURE", value) },
+                None => unsafe { std::env::remove_var("BRP_TLS_INSECURE") },
+            }
+
+            match &self.max_rps {
+                Some(value) => unsafe { std::env::set_var("BRP_MAX_RPS", value) },
+                None => unsafe { std::env::remove_var("BRP_MAX_RPS") },
+            }
+
+            match &self.max_concurrent {
+                Some(value) => unsafe { std::env::set_var("BRP_MAX_CONCURRENT", value) },
+                None => unsafe { std::env::remove_var("BRP_MAX_CONCURRENT") },
+            }
+
+            match &self.compress_above {
+                Some(value) => unsafe { std::env::set_var("BRP_COMPRESS_ABOVE", value) },
+                None => unsafe { std::env::remove_var("BRP_COMPRESS_ABOVE") },
+            }
         }
     }
 
@@ -94,11 +353,27 @@ mod tests {
     fn test_from_env_defaults() {
         let _guard = EnvRestoreGuard::acquire();
         unsafe { std::env::remove_var("BRP_ENDPOINT") };
+        unsafe { std::env::remove_var("BRP_PORT") };
         unsafe { std::env::remove_var("BRP_TIMEOUT_MS") };
+        unsafe { std::env::remove_var("BRP_RECORD") };
+        unsafe { std::env::remove_var("BRP_REPLAY") };
+        unsafe { std::env::remove_var("BRP_TOKEN") };
+        unsafe { std::env::remove_var("BRP_AUTH_TOKEN") };
+        unsafe { std::env::remove_var("BRP_TLS_CA") };
+        unsafe { std::env::remove_var("BRP_TLS_INSECURE") };
+        unsafe { std::env::remove_var("BRP_MAX_RPS") };
+        unsafe { std::env::remove_var("BRP_MAX_CONCURRENT") };
+        unsafe { std::env::remove_var("BRP_COMPRESS_ABOVE") };
 
         let config = BrpConfig::from_env();
         assert_eq!(config.endpoint, "http://127.0.0.1:15721");
         assert_eq!(config.timeout, Duration::from_secs(30));
+        assert_eq!(config.traffic, Traffic::Live);
+        assert_eq!(config.token, None);
+        assert!(config.tls.ca_certs.is_empty());
+        assert!(!config.tls.accept_invalid_certs);
+        assert_eq!(config.rate_limit, RateLimit::default());
+        assert_eq!(config.compress_above, None);
     }
 
     #[test]
@@ -106,9 +381,110 @@ mod tests {
         let _guard = EnvRestoreGuard::acquire();
         unsafe { std::env::set_var("BRP_ENDPOINT", "http://custom:9999") };
         unsafe { std::env::set_var("BRP_TIMEOUT_MS", "5000") };
+        unsafe { std::env::set_var("BRP_TOKEN", " s3cret ") };
+        unsafe { std::env::set_var("BRP_COMPRESS_ABOVE", "65536") };
 
         let config = BrpConfig::from_env();
         assert_eq!(config.endpoint, "http://custom:9999");
         assert_eq!(config.timeout, Duration::from_millis(5000));
+        assert_eq!(config.token.as_deref(), Some("s3cret"));
+        assert_eq!(config.compress_above, Some(65536));
+    }
+
+    #[test]
+    fn test_from_env_port() {
+        let _guard = EnvRestoreGuard::acquire();
+        unsafe { std::env::remove_var("BRP_ENDPOINT") };
+        unsafe { std::env::set_var("BRP_PORT", "15731") };
+        assert_eq!(BrpConfig::from_env().endpoint, "http://127.0.0.1:15731");
+        assert_eq!(BrpConfig::local(15731).endpoint, "http://127.0.0.1:15731");
+
+        unsafe { std::env::set_var("BRP_PORT", "lots") };
+        assert_eq!(BrpConfig::from_env().endpoint, "http://127.0.0.1:15721");
+
+        // A whole endpoint wins
+        unsafe { std::env::set_var("BRP_ENDPOINT", "http://custom:9999") };
+        assert_eq!(BrpConfig::from_env().endpoint, "http://custom:9999");
+    }
+
+    #[test]
+    fn test_from_env_auth_token() {
+        let _guard = EnvRestoreGuard::acquire();
+        unsafe { std::env::remove_var("BRP_TOKEN") };
+        unsafe { std::env::set_var("BRP_AUTH_TOKEN", "s3cret") };
+        assert_eq!(BrpConfig::from_env().token.as_deref(), Some("s3cret"));
+
+        // The usual name wins
+        unsafe { std::env::set_var("BRP_TOKEN", "other") };
+        assert_eq!(BrpConfig::from_env().token.as_deref(), Some("other"));
+    }
+
+    #[test]
+    fn test_from_env_traffic() {
+        let _guard = EnvRestoreGuard::acquire();
+        unsafe { std::env::remove_var("BRP_REPLAY") };
+
+        unsafe { std::env::set_var("BRP_RECORD", "1") };
+        assert!(matches!(BrpConfig::from_env().traffic, Traffic::Record(path) if path.starts_with(".axiom")));
+
+        unsafe { std::env::set_var("BRP_RECORD", "session.jsonl") };
+        assert_eq!(BrpConfig::from_env().traffic, Traffic::Record("session.jsonl".into()));
+
+        // Replay wins, as recording a replay would only copy the file
+        unsafe { std::env::set_var("BRP_REPLAY", "fixture.jsonl") };
+        assert_eq!(BrpConfig::from_env().traffic, Traffic::Replay("fixture.jsonl".into()));
+    }
+
+    #[test]
+    fn test_from_env_rate_limit() {
+        let _guard = EnvRestoreGuard::acquire();
+        unsafe { std::env::set_var("BRP_MAX_RPS", "20") };
+        unsafe { std::env::set_var("BRP_MAX_CONCURRENT", " 4 ") };
+        assert_eq!(BrpConfig::from_env().rate_limit, RateLimit::default().per_second(20.0).max_concurrent(4));
+
+        // Nonsense is reported and left unlimited
+        unsafe { std::env::set_var("BRP_MAX_RPS", "-1") };
+        unsafe { std::env::set_var("BRP_MAX_CONCURRENT", "many") };
+        assert_eq!(BrpConfig::from_env().rate_limit, RateLimit::default());
+    }
+
+    const TEST_CA: &str = "-----BEGIN CERTIFICATE-----\n\
+MIIBhzCCAS2gAwIBAgIUdN1O1xLhXPm1axkPh56GCoPmKlcwCgYIKoZIzj0EAwIw\n\
+GDEWMBQGA1UEAwwNYXhpb20tdGVzdC1jYTAgFw0yNjEwMTgwNDQzMzJaGA8yMTI2\n\
+MDkyNDA0NDMzMlowGDEWMBQGA1UEAwwNYXhpb20tdGVzdC1jYTBZMBMGByqGSM49\n\
+AgEGCCqGSM49AwEHA0IABN8QRsOEuXN8kY5yt4wfDroxTlNCQlsNZJQ02CrL9ASX\n\
+y2iOrt9DjSQ+Zj8EdVYpq1VWgkzHe+AANB/QMwlatx+jUzBRMB0GA1UdDgQWBBQO\n\
+tlLOTV/hbsa9SXnprqW6qQLB8DAfBgNVHSMEGDAWgBQOtlLOTV/hbsa9SXnprqW6\n\
+qQLB8DAPBgNVHRMBAf8EBTADAQH/MAoGCCqGSM49BAMCA0gAMEUCIQC5Xqj/O/zC\n\
+GnEjc0pxoOSiR9Tdy3MEWsRRkdtkR//DpQIgYxIk+B0ElPx+W3fbYr5qdb6RKaPk\n\
+OjSexiOte4C3ybw=\n\
+-----END CERTIFICATE-----\n";
+
+    #[test]
+    fn test_tls_ca_pem() {
+        let tls = TlsConfig::default().with_ca_pem(TEST_CA.as_bytes()).unwrap();
+        assert_eq!(tls.ca_certs.len(), 1);
+        assert!(TlsConfig::default().with_ca_pem(b"not a certificate").is_err());
+        assert!(TlsConfig::default().with_ca_file("missing-ca.pem").is_err());
+    }
+
+    #[test]
+    fn test_from_env_tls() {
+        let _guard = EnvRestoreGuard::acquire();
+        let path = std::env::temp_dir().join(format!("brp-test-ca-{}.pem", std::process::id()));
+        std::fs::write(&path, TEST_CA).unwrap();
+        unsafe { std::env::set_var("BRP_TLS_CA", &path) };
+        unsafe { std::env::set_var("BRP_TLS_INSECURE", "true") };
+        let tls = BrpConfig::from_env().tls;
+        assert_eq!(tls.ca_certs.len(), 1);
+        assert!(tls.accept_invalid_certs);
+
+        // A bad CA file is reported and skipped rather than failing the config
+        unsafe { std::env::set_var("BRP_TLS_CA", "missing-ca.pem") };
+        unsafe { std::env::set_var("BRP_TLS_INSECURE", "0") };
+        let tls = BrpConfig::from_env().tls;
+        assert!(tls.ca_certs.is_empty());
+        assert!(!tls.accept_invalid_certs);
+        let _ = std::fs::remove_file(path);
     }
 }
@@ -0,0 +1,117 @@
+//! Finding games on the local network.
+//!
+//! A game whose `bevy_ai_remote` plugin listens beyond localhost broadcasts
+//! an [`Announcement`] over UDP to [`DISCOVERY_PORT`] every couple of
+//! seconds; [`discover`] listens for them.
+
+use crate::Result;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+/// UDP port the announcements are broadcast to.
+pub const DISCOVERY_PORT: u16 = 15722;
+
+/// What a game broadcasts about itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Announcement {
+    /// Always `"axiom"`, to tell announcements from other traffic.
+    pub service: String,
+    /// The game's executable name.
+    pub name: String,
+    /// BRP HTTP port.
+    pub port: u16,
+    /// Whether requests need the shared token.
+    #[serde(default)]
+    pub token_required: bool,
+}
+
+impl Announcement {
+    pub const SERVICE: &'static str = "axiom";
+}
+
+/// A game heard from, with the endpoint to reach it at.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveredGame {
+    pub name: String,
+    pub endpoint: String,
+    pub token_required: bool,
+}
+
+/// The game behind an announcement received from `from`, if it is one.
+pub fn parse_announcement(data: &[u8], from: SocketAddr) -> Option<DiscoveredGame> {
+    let announcement: Announcement = serde_json::from_slice(data).ok()?;
+    if announcement.service != Announcement::SERVICE {
+        return None;
+    }
+    Some(DiscoveredGame {
+        name: announcement.name,
+        endpoint: format!("http://{}", SocketAddr::new(from.ip(), announcement.port)),
+        token_required: announcement.token_required,
+    })
+}
+
+/// Listen on [`DISCOVERY_PORT`] for `duration`, returning each game heard once.
+pub async fn discover(duration: Duration) -> Result<Vec<DiscoveredGame>> {
+    let socket = UdpSocket::bind(("0.0.0.0", DISCOVERY_PORT)).await?;
+    listen(&socket, duration).await
+}
+
+async fn listen(socket: &UdpSocket, duration: Duration) -> Result<Vec<DiscoveredGame>> {
+    let mut games: Vec<DiscoveredGame> = Vec::new();
+    let mut buf = [0u8; 1024];
+    let deadline = tokio::time::Instant::now() + duration;
+    while let Ok(received) = tokio::time::timeout_at(deadline, socket.recv_from(&mut buf)).await {
+        let (len, from) = received?;
+        if let Some(game) = parse_announcement(&buf[..len], from) {
+            if !games.iter().any(|g| g.endpoint == game.endpoint) {
+                tracing::debug!("Discovered {} at {}", game.name, game.endpoint);
+                games.push(game);
+            }
+        }
+    }
+    Ok(games)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn announcement(name: &str, port: u16) -> Vec<u8> {
+        serde_json::to_vec(&Announcement {
+            service: Announcement::SERVICE.to_string(),
+            name: name.to_string(),
+            port,
+            token_required: true,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn endpoint_uses_the_sender_address_and_announced_port() {
+        let from: SocketAddr = "192.168.1.40:50000".parse().unwrap();
+        let game = parse_announcement(&announcement("steamdeck_game", 15721), from).unwrap();
+        assert_eq!(game.name, "steamdeck_game");
+        assert_eq!(game.endpoint, "http://192.168.1.40:15721");
+        assert!(game.token_required);
+
+        assert_eq!(parse_announcement(b"hello", from), None);
+        let other = br#"{"service":"other","name":"x","port":1}"#;
+        assert_eq!(parse_announcement(other, from), None);
+    }
+
+    #[tokio::test]
+    async fn listen_collects_each_game_once() {
+        let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let target = socket.local_addr().unwrap();
+        let sender = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        for data in [announcement("game", 15721), announcement("game", 15721), b"noise".to_vec(), announcement("tool", 16000)] {
+            sender.send_to(&data, target).await.unwrap();
+        }
+
+        let games = listen(&socket, Duration::from_millis(300)).await.unwrap();
+        let endpoints: Vec<&str> = games.iter().map(|g| g.endpoint.as_str()).collect();
+        assert_eq!(endpoints, vec!["http://127.0.0.1:15721", "http://127.0.0.1:16000"]);
+    }
+}
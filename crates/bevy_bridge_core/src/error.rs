@@ -1,6 +1,19 @@
+use serde_json::Value;
 use std::time::Duration;
 use thiserror::Error;
 
+/// JSON-RPC error codes the game answers with that get their own variant.
+pub mod codes {
+    /// The method does not exist.
+    pub const METHOD_NOT_FOUND: i32 = -32601;
+    /// The request lacked a valid token (`bevy_ai_remote`).
+    pub const UNAUTHORIZED: i32 = -32001;
+    /// No entity with the id (`bevy_remote`).
+    pub const ENTITY_NOT_FOUND: i32 = -23401;
+    /// The component type could not be reflected or found (`bevy_remote`).
+    pub const COMPONENT_ERROR: i32 = -23402;
+}
+
 #[derive(Error, Debug)]
 pub enum BrpError {
     #[error("Connection error: {0}")]
@@ -13,9 +26,24 @@ pub enum BrpError {
     JsonRpc {
         code: i32,
         message: String,
-        data: Option<serde_json::Value>,
+        data: Option<Value>,
     },
 
+    #[error("Entity not found: {message}")]
+    EntityNotFound { message: String, data: Option<Value> },
+
+    #[error("Component not registered: {message}")]
+    ComponentNotRegistered { message: String, data: Option<Value> },
+
+    #[error("Method not found: {message}")]
+    MethodNotFound { message: String, data: Option<Value> },
+
+    #[error("Permission denied: {message}")]
+    PermissionDenied { message: String, data: Option<Value> },
+
+    #[error("Protocol version mismatch: the editor speaks {editor}, the game's bevy_ai_remote {plugin} speaks {game}; update the older one")]
+    VersionMismatch { editor: u32, game: u32, plugin: String },
+
     #[error("Deserialization error: {0}")]
     Deserialize(#[from] serde_json::Error),
 
@@ -24,26 +52,63 @@ pub enum BrpError {
 
     #[error("Invalid response: {0}")]
     InvalidResponse(String),
+
+    #[error("Replay error: {0}")]
+    Replay(String),
+
+    #[error("No game instance named '{0}'")]
+    UnknownInstance(String),
+
+    #[error("Request cancelled")]
+    Cancelled,
 }
 
 impl BrpError {
     pub fn json_rpc(code: i32, message: impl Into<String>) -> Self {
-        Self::JsonRpc {
-            code,
-            message: message.into(),
-            data: None,
-        }
+        Self::from_json_rpc(code, message.into(), None)
     }
 
     pub fn json_rpc_with_data(
         code: i32,
         message: impl Into<String>,
-        data: serde_json::Value,
+        data: Value,
     ) -> Self {
-        Self::JsonRpc {
-            code,
-            message: message.into(),
-            data: Some(data),
+        Self::from_json_rpc(code, message.into(), Some(data))
+    }
+
+    /// The variant for a JSON-RPC error object: a specific one for the
+    /// [`codes`] callers branch on, [`BrpError::JsonRpc`] otherwise.
+    pub fn from_json_rpc(code: i32, message: String, data: Option<Value>) -> Self {
+        match code {
+            codes::ENTITY_NOT_FOUND => Self::EntityNotFound { message, data },
+            codes::COMPONENT_ERROR => Self::ComponentNotRegistered { message, data },
+            codes::METHOD_NOT_FOUND => Self::MethodNotFound { message, data },
+            codes::UNAUTHORIZED => Self::PermissionDenied { message, data },
+            code => Self::JsonRpc { code, message, data },
+        }
+    }
+
+    /// Code, message and data of an error the game answered with, for
+    /// passing it on as JSON-RPC.
+    pub fn json_rpc_parts(&self) -> Option<(i32, &str, Option<&Value>)> {
+        let (code, message, data) = match self {
+            Self::JsonRpc { code, message, data } => (*code, message, data),
+            Self::EntityNotFound { message, data } => (codes::ENTITY_NOT_FOUND, message, data),
+            Self::ComponentNotRegistered { message, data } => (codes::COMPONENT_ERROR, message, data),
+            Self::MethodNotFound { message, data } => (codes::METHOD_NOT_FOUND, message, data),
+            Self::PermissionDenied { message, data } => (codes::UNAUTHORIZED, message, data),
+            _ => return None,
+        };
+        Some((code, message.as_str(), data.as_ref()))
+    }
+
+    /// Whether the same request may succeed if sent again: the game was
+    /// unreachable or slow, rather than refusing it.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Timeout(_) => true,
+            Self::Connection(e) => e.is_connect() || e.is_timeout() || e.status().is_some_and(|s| s.is_server_error()),
+            _ => false,
         }
     }
 }
@@ -97,17 +162,44 @@ mod tests {
         let timeout_err = BrpError::Timeout(Duration::from_secs(5));
         assert_eq!(timeout_err.to_string(), "Request timeout after 5s");
 
-        let json_rpc_err = BrpError::json_rpc(-32601, "Method not found");
+        let json_rpc_err = BrpError::json_rpc(-32602, "Invalid params");
         assert_eq!(
             json_rpc_err.to_string(),
-            "JSON-RPC error: -32601 - Method not found"
+            "JSON-RPC error: -32602 - Invalid params"
         );
 
+        let method_err = BrpError::json_rpc(-32601, "Method `x` not found");
+        assert_eq!(method_err.to_string(), "Method not found: Method `x` not found");
+
         let invalid_response_err = BrpError::InvalidResponse("Bad format".to_string());
         assert_eq!(
             invalid_response_err.to_string(),
             "Invalid response: Bad format"
         );
+
+        let instance_err = BrpError::UnknownInstance("server".to_string());
+        assert_eq!(instance_err.to_string(), "No game instance named 'server'");
+    }
+
+    #[test]
+    fn test_json_rpc_codes_get_their_variants() {
+        let error = |code| BrpError::json_rpc(code, "message");
+        assert!(matches!(error(codes::ENTITY_NOT_FOUND), BrpError::EntityNotFound { .. }));
+        assert!(matches!(error(codes::COMPONENT_ERROR), BrpError::ComponentNotRegistered { .. }));
+        assert!(matches!(error(codes::METHOD_NOT_FOUND), BrpError::MethodNotFound { .. }));
+        assert!(matches!(error(codes::UNAUTHORIZED), BrpError::PermissionDenied { .. }));
+        assert!(matches!(error(-23403), BrpError::JsonRpc { code: -23403, .. }));
+
+        let entity = BrpError::json_rpc_with_data(codes::ENTITY_NOT_FOUND, "Entity 5v0 does not exist", json!(5));
+        assert_eq!(entity.json_rpc_parts(), Some((codes::ENTITY_NOT_FOUND, "Entity 5v0 does not exist", Some(&json!(5)))));
+        assert_eq!(BrpError::Replay("gone".into()).json_rpc_parts(), None);
+    }
+
+    #[test]
+    fn test_only_unreachable_games_are_retryable() {
+        assert!(BrpError::Timeout(Duration::from_secs(1)).is_retryable());
+        assert!(!BrpError::json_rpc(codes::ENTITY_NOT_FOUND, "gone").is_retryable());
+        assert!(!BrpError::InvalidResponse("bad".into()).is_retryable());
     }
 
     #[test]
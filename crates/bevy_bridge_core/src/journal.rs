@@ -0,0 +1,316 @@
+//! An undo journal kept by the client, as an escape hatch for bad commands.
+//!
+//! Commands run through a [`CommandJournal`] are recorded with what it takes
+//! to revert them, and [`CommandJournal::undo`] and [`CommandJournal::redo`]
+//! step back and forth through them. Unlike the game's own journal
+//! ([`ops::history`]), it covers component inserts on any entity, but only
+//! sees commands that went through it.
+//!
+//! Despawned entities come back the way [`ops::scene::restore`] brings them
+//! back, with new ids; earlier entries are updated to follow them. Entities
+//! the scene export leaves out, such as uploaded textures, can't be brought
+//! back and aren't journaled.
+
+use crate::ops;
+use crate::ops::clear::DESPAWN_BATCH;
+use crate::ops::despawn::DESPAWN_METHOD;
+use crate::types::{
+    ClearResponse, ClearTarget, ComponentDiff, ComponentsResponse, EntityId, SceneEntity, SceneObject, SceneTransform, SpawnRequest,
+    SpawnResponse, UploadOptions, UploadResponse,
+};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+
+/// What a command did, in enough detail to do the opposite.
+#[derive(Debug, Clone, PartialEq)]
+enum Change {
+    /// Entities the command spawned, as spawned.
+    Spawned(Vec<SceneEntity>),
+    /// Entities the command despawned, as they were just before.
+    Despawned(Vec<SceneEntity>),
+    /// Components the command inserted on `entity`, with any they replaced.
+    Components { entity: EntityId, changes: Vec<ComponentDiff> },
+}
+
+/// One recorded command.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JournalEntry {
+    pub label: String,
+    change: Change,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CommandJournal {
+    entries: Vec<JournalEntry>,
+    /// Entries before this are applied; the rest have been undone.
+    cursor: usize,
+}
+
+impl CommandJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every entry, oldest first, undone ones included.
+    pub fn entries(&self) -> &[JournalEntry] {
+        &self.entries
+    }
+
+    /// How many entries are applied.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+
+    /// [`ops::spawn::spawn`] the requested primitive.
+    pub async fn spawn(&mut self, client: &BrpClient, request: SpawnRequest) -> Result<SpawnResponse> {
+        let response = ops::spawn::spawn(client, &request).await?;
+        let spawned = SceneEntity {
+            entity: response.entity_id,
+            name: request.name,
+            group: request.group,
+            object: SceneObject::Primitive { primitive_type: request.primitive },
+            transform: SceneTransform { translation: request.position, rotation: request.rotation, scale: request.scale },
+            material: request.material,
+        };
+        self.record(format!("Spawn {}", spawned.label()), Change::Spawned(vec![spawned]));
+        Ok(response)
+    }
+
+    /// [`ops::upload::upload`] the file. Only models are journaled.
+    pub async fn upload(
+        &mut self,
+        client: &BrpClient,
+        filename: &str,
+        bytes: &[u8],
+        options: &UploadOptions,
+    ) -> Result<UploadResponse> {
+        let response = ops::upload::upload(client, filename, bytes, options).await?;
+        let entity = response.entity_id;
+        let spawned: Vec<_> = ops::scene::export(client).await?.into_iter().filter(|e| e.entity == entity).collect();
+        if !spawned.is_empty() {
+            self.record(format!("Upload {}", filename), Change::Spawned(spawned));
+        }
+        Ok(response)
+    }
+
+    /// [`ops::clear::clear`] `target`, keeping what it removed.
+    pub async fn clear(&mut self, client: &BrpClient, target: ClearTarget) -> Result<ClearResponse> {
+        let before = ops::scene::export(client).await?;
+        let response = ops::clear::clear(client, target).await?;
+        let left: HashSet<EntityId> = ops::scene::export(client).await?.iter().map(|e| e.entity).collect();
+        let removed: Vec<_> = before.into_iter().filter(|e| !left.contains(&e.entity)).collect();
+        if !removed.is_empty() {
+            self.record(format!("Clear {} entities", removed.len()), Change::Despawned(removed));
+        }
+        Ok(response)
+    }
+
+    /// [`ops::components::insert_components`], keeping the values replaced.
+    pub async fn insert_components(
+        &mut self,
+        client: &BrpClient,
+        entity: EntityId,
+        components: Map<String, Value>,
+    ) -> Result<ComponentsResponse> {
+        let type_paths = components.keys().cloned().collect();
+        let current = ops::get::get_entity(client, entity, type_paths).await?;
+        let changes = components
+            .iter()
+            .map(|(component, value)| ComponentDiff {
+                component: component.clone(),
+                before: current.components.get(component).cloned(),
+                after: Some(value.clone()),
+            })
+            .collect();
+        let response = ops::components::insert_components(client, entity, components).await?;
+        let label = format!("Insert {} on {}", response.components.join(", "), entity);
+        self.record(label, Change::Components { entity, changes });
+        Ok(response)
+    }
+
+    pub async fn insert_component(
+        &mut self,
+        client: &BrpClient,
+        entity: EntityId,
+        type_path: &str,
+        value: Value,
+    ) -> Result<ComponentsResponse> {
+        let mut components = Map::new();
+        components.insert(type_path.to_string(), value);
+        self.insert_components(client, entity, components).await
+    }
+
+    /// Revert the latest applied entry, returning its label; `None` if
+    /// there is none. On an error the entry stays applied.
+    pub async fn undo(&mut self, client: &BrpClient) -> Result<Option<String>> {
+        let Some(index) = self.cursor.checked_sub(1) else { return Ok(None) };
+        self.apply(client, index, false).await?;
+        self.cursor = index;
+        Ok(Some(self.entries[index].label.clone()))
+    }
+
+    /// Reapply the latest undone entry, returning its label; `None` if
+    /// there is none.
+    pub async fn redo(&mut self, client: &BrpClient) -> Result<Option<String>> {
+        if !self.can_redo() {
+            return Ok(None);
+        }
+        let index = self.cursor;
+        self.apply(client, index, true).await?;
+        self.cursor = index + 1;
+        Ok(Some(self.entries[index].label.clone()))
+    }
+
+    fn record(&mut self, label: String, change: Change) {
+        // A new command drops whatever was undone
+        self.entries.truncate(self.cursor);
+        self.entries.push(JournalEntry { label, change });
+        self.cursor = self.entries.len();
+    }
+
+    /// Do entry `index` again (`forward`) or its opposite.
+    async fn apply(&mut self, client: &BrpClient, index: usize, forward: bool) -> Result<()> {
+        match self.entries[index].change.clone() {
+            Change::Spawned(entities) if forward => self.respawn(client, &entities).await,
+            Change::Spawned(entities) => despawn(client, &entities).await,
+            Change::Despawned(entities) if forward => despawn(client, &entities).await,
+            Change::Despawned(entities) => self.respawn(client, &entities).await,
+            Change::Components { entity, changes } => set_components(client, entity, &changes, forward).await,
+        }
+    }
+
+    /// Spawn `entities` again, pointing every entry at the new ids.
+    async fn respawn(&mut self, client: &BrpClient, entities: &[SceneEntity]) -> Result<()> {
+        for entity in entities {
+            let result = client.send_rpc("world.spawn_entity", Some(ops::scene::spawn_params(entity))).await?;
+            let new = result
+                .get("entity")
+                .and_then(Value::as_u64)
+                .map(EntityId)
+                .ok_or_else(|| BrpError::InvalidResponse("Missing 'entity' in spawn response".into()))?;
+            self.replace_entity(entity.entity, new);
+        }
+        Ok(())
+    }
+
+    fn replace_entity(&mut self, old: EntityId, new: EntityId) {
+        for entry in &mut self.entries {
+            match &mut entry.change {
+                Change::Spawned(entities) | Change::Despawned(entities) => {
+                    entities.iter_mut().filter(|e| e.entity == old).for_each(|e| e.entity = new);
+                }
+                Change::Components { entity, .. } if *entity == old => *entity = new,
+                Change::Components { .. } => {}
+            }
+        }
+    }
+}
+
+async fn despawn(client: &BrpClient, entities: &[SceneEntity]) -> Result<()> {
+    let despawns: Vec<_> = entities.iter().map(|e| (DESPAWN_METHOD, Some(json!({ "entity": e.entity })))).collect();
+    for chunk in despawns.chunks(DESPAWN_BATCH) {
+        // Ones already gone are as good as despawned
+        client.send_batch(chunk.to_vec()).await?;
+    }
+    Ok(())
+}
+
+/// Put each component back to its value after the change (`forward`) or
+/// before it, removing it where there was none.
+async fn set_components(client: &BrpClient, entity: EntityId, changes: &[ComponentDiff], forward: bool) -> Result<()> {
+    let mut insert = Map::new();
+    let mut remove = Vec::new();
+    for change in changes {
+        match if forward { &change.after } else { &change.before } {
+            Some(value) => {
+                insert.insert(change.component.clone(), value.clone());
+            }
+            None => remove.push(change.component.clone()),
+        }
+    }
+    if !insert.is_empty() {
+        ops::components::insert_components(client, entity, insert).await?;
+    }
+    if !remove.is_empty() {
+        ops::components::remove_components(client, entity, remove).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::components::{INSERT_METHOD, REMOVE_METHOD};
+    use crate::ops::get::GET_METHOD;
+    use crate::testing::MockBrpServer;
+    use crate::types::PrimitiveMaterial;
+
+    #[tokio::test]
+    async fn test_undo_and_redo_a_spawn_follow_the_new_id() {
+        let server = MockBrpServer::start();
+        server
+            .reply("world.spawn_entity", json!({ "entity": 10 }))
+            .reply("world.spawn_entity", json!({ "entity": 11 }))
+            .reply(DESPAWN_METHOD, json!(null));
+        let client = server.client();
+        let mut journal = CommandJournal::new();
+
+        let request = SpawnRequest {
+            primitive: "cube".into(),
+            position: [1.0, 2.0, 3.0],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+            material: PrimitiveMaterial::default(),
+            name: Some("crate".into()),
+            group: None,
+        };
+        journal.spawn(&client, request).await.unwrap();
+        assert_eq!(journal.undo(&client).await.unwrap().as_deref(), Some("Spawn crate (cube)"));
+        assert!(journal.undo(&client).await.unwrap().is_none());
+        journal.redo(&client).await.unwrap();
+        journal.undo(&client).await.unwrap();
+
+        assert_eq!(server.calls_to(DESPAWN_METHOD), vec![Some(json!({ "entity": 10 })), Some(json!({ "entity": 11 }))]);
+        let respawn = server.calls_to("world.spawn_entity")[1].clone().unwrap();
+        assert_eq!(respawn["components"]["bevy_ecs::name::Name"], "crate");
+        assert!(journal.can_redo() && !journal.can_undo());
+    }
+
+    #[tokio::test]
+    async fn test_undo_an_insert_restores_or_removes_each_component() {
+        let server = MockBrpServer::start();
+        server
+            .reply(
+                GET_METHOD,
+                json!({
+                    "components": { "game::Health": { "current": 10 } },
+                    "errors": { "game::Shield": { "code": -23402, "message": "Component not present" } }
+                }),
+            )
+            .reply(INSERT_METHOD, json!(null))
+            .reply(REMOVE_METHOD, json!(null));
+        let client = server.client();
+        let mut journal = CommandJournal::new();
+
+        let mut components = Map::new();
+        components.insert("game::Health".into(), json!({ "current": 3 }));
+        components.insert("game::Shield".into(), json!(5));
+        journal.insert_components(&client, EntityId(7), components).await.unwrap();
+        journal.undo(&client).await.unwrap();
+
+        assert_eq!(
+            server.calls_to(INSERT_METHOD)[1],
+            Some(json!({ "entity": 7, "components": { "game::Health": { "current": 10 } } }))
+        );
+        assert_eq!(server.calls_to(REMOVE_METHOD), vec![Some(json!({ "entity": 7, "components": ["game::Shield"] }))]);
+    }
+}
@@ -4,16 +4,36 @@
 //! Provides structured config, error handling, and high-level operations for interacting
 //! with a running Bevy game instance.
 
+pub mod blocking;
+pub mod capabilities;
+pub mod compression;
 pub mod config;
+pub mod discovery;
 pub mod error;
+pub mod journal;
+mod limiter;
+pub mod metrics;
+pub mod middleware;
 pub mod client;
 pub mod ops;
+pub mod pool;
+pub mod recording;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod types;
 
 // Re-export commonly used types
-pub use config::BrpConfig;
+pub use blocking::BrpClientBlocking;
+pub use capabilities::Capabilities;
+pub use config::{BrpConfig, RateLimit, TlsConfig};
 pub use error::BrpError;
-pub use client::BrpClient;
+pub use journal::CommandJournal;
+pub use metrics::BrpMetrics;
+pub use middleware::BrpMiddleware;
+pub use client::{BrpClient, TOKEN_PARAM};
+pub use pool::BrpClientPool;
+pub use recording::Traffic;
+pub use tokio_util::sync::CancellationToken;
 
 /// Result type alias using BrpError
 pub type Result<T> = std::result::Result<T, BrpError>;
@@ -0,0 +1,98 @@
+//! Enforces a [`RateLimit`] on the requests a client and its clones send.
+
+use crate::config::RateLimit;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Limiter {
+    bucket: Option<Arc<Mutex<Bucket>>>,
+    in_flight: Option<Arc<Semaphore>>,
+}
+
+/// Held while a request is in flight.
+pub(crate) struct Turn {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Limiter {
+    pub(crate) fn new(limit: &RateLimit) -> Self {
+        Self {
+            bucket: limit.per_second.filter(|rate| *rate > 0.0).map(|rate| Arc::new(Mutex::new(Bucket::new(rate)))),
+            in_flight: limit.max_concurrent.map(|count| Arc::new(Semaphore::new(count.max(1)))),
+        }
+    }
+
+    /// Wait until a request may go out.
+    pub(crate) async fn turn(&self) -> Turn {
+        let permit = match &self.in_flight {
+            Some(in_flight) => Some(in_flight.clone().acquire_owned().await.expect("The semaphore is never closed")),
+            None => None,
+        };
+        if let Some(bucket) = &self.bucket {
+            loop {
+                let Some(wait) = bucket.lock().unwrap().take() else { break };
+                tracing::debug!("Rate limited, waiting {:?}", wait);
+                tokio::time::sleep(wait).await;
+            }
+        }
+        Turn { _permit: permit }
+    }
+}
+
+/// A token bucket holding up to a second's worth of requests.
+#[derive(Debug)]
+struct Bucket {
+    rate: f64,
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    fn new(rate: f64) -> Self {
+        Self { rate, tokens: rate.max(1.0), refilled: Instant::now() }
+    }
+
+    /// Take a token, or say how long until there is one.
+    fn take(&mut self) -> Option<Duration> {
+        let now = Instant::now();
+        let refill = now.duration_since(self.refilled).as_secs_f64() * self.rate;
+        self.tokens = (self.tokens + refill).min(self.rate.max(1.0));
+        self.refilled = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return None;
+        }
+        Some(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_bucket_allows_a_burst_then_paces() {
+        let limiter = Limiter::new(&RateLimit::default().per_second(20.0));
+        let started = Instant::now();
+        for _ in 0..20 {
+            limiter.turn().await;
+        }
+        assert!(started.elapsed() < Duration::from_millis(100), "{:?}", started.elapsed());
+        for _ in 0..5 {
+            limiter.turn().await;
+        }
+        assert!(started.elapsed() >= Duration::from_millis(200), "{:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_requests_are_capped() {
+        let limiter = Limiter::new(&RateLimit::default().max_concurrent(1));
+        let first = limiter.turn().await;
+        let waiting = tokio::time::timeout(Duration::from_millis(50), limiter.turn()).await;
+        assert!(waiting.is_err());
+        drop(first);
+        assert!(tokio::time::timeout(Duration::from_millis(50), limiter.turn()).await.is_ok());
+    }
+}
@@ -0,0 +1,216 @@
+//! Counters for the requests a client sends, for a status panel.
+//!
+//! [`BrpMetrics`] is a [`BrpMiddleware`]: install it behind an [`Arc`] and
+//! keep the handle to read a [`BrpMetrics::snapshot`] whenever needed.
+//!
+//! ```no_run
+//! use bevy_bridge_core::{BrpClient, BrpConfig, BrpMetrics};
+//! use std::sync::Arc;
+//!
+//! let metrics = Arc::new(BrpMetrics::new());
+//! let client = BrpClient::new(BrpConfig::from_env()).with_middleware(metrics.clone());
+//! // ... later
+//! let snapshot = metrics.snapshot();
+//! ```
+
+use crate::middleware::{BrpMiddleware, RpcRequest};
+use crate::BrpError;
+use reqwest::header::HeaderMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Latencies kept per method, and overall, for percentiles.
+const RECENT_SAMPLES: usize = 500;
+
+/// Requests by method, errors, bytes sent and latency percentiles. Like all
+/// middleware it sees discovery and handshake requests too, and nothing
+/// answered from a replay.
+#[derive(Debug, Default)]
+pub struct BrpMetrics {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    methods: BTreeMap<String, Stat>,
+    recent: VecDeque<Duration>,
+}
+
+#[derive(Debug, Default)]
+struct Stat {
+    requests: u64,
+    errors: u64,
+    bytes_sent: u64,
+    recent: VecDeque<Duration>,
+}
+
+/// One method's share of a [`MetricsSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MethodMetrics {
+    pub method: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub bytes_sent: u64,
+    /// Over the most recent requests.
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+}
+
+/// What [`BrpMetrics`] has counted so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSnapshot {
+    pub requests: u64,
+    pub errors: u64,
+    /// Params as JSON, before any compression and without the token.
+    pub bytes_sent: u64,
+    pub p50_ms: f64,
+    pub p99_ms: f64,
+    /// By method name.
+    pub methods: Vec<MethodMetrics>,
+}
+
+impl MetricsSnapshot {
+    pub fn error_rate(&self) -> f64 {
+        self.errors as f64 / self.requests.max(1) as f64
+    }
+}
+
+impl BrpMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let methods: Vec<MethodMetrics> = state
+            .methods
+            .iter()
+            .map(|(method, stat)| {
+                let (p50_ms, p99_ms) = percentiles(&stat.recent);
+                MethodMetrics {
+                    method: method.clone(),
+                    requests: stat.requests,
+                    errors: stat.errors,
+                    bytes_sent: stat.bytes_sent,
+                    p50_ms,
+                    p99_ms,
+                }
+            })
+            .collect();
+        let (p50_ms, p99_ms) = percentiles(&state.recent);
+        MetricsSnapshot {
+            requests: methods.iter().map(|m| m.requests).sum(),
+            errors: methods.iter().map(|m| m.errors).sum(),
+            bytes_sent: methods.iter().map(|m| m.bytes_sent).sum(),
+            p50_ms,
+            p99_ms,
+            methods,
+        }
+    }
+
+    /// Start counting afresh.
+    pub fn reset(&self) {
+        *self.state.lock().unwrap_or_else(|e| e.into_inner()) = State::default();
+    }
+}
+
+impl BrpMiddleware for BrpMetrics {
+    fn on_request(&self, request: &RpcRequest<'_>, _headers: &mut HeaderMap) {
+        let bytes = request.params.map_or(0, json_len);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.methods.entry(request.method.to_string()).or_default().bytes_sent += bytes;
+    }
+
+    fn on_response(&self, request: &RpcRequest<'_>, outcome: Result<&Value, &BrpError>, elapsed: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        push_recent(&mut state.recent, elapsed);
+        let stat = state.methods.entry(request.method.to_string()).or_default();
+        stat.requests += 1;
+        if outcome.is_err() {
+            stat.errors += 1;
+        }
+        push_recent(&mut stat.recent, elapsed);
+    }
+}
+
+fn push_recent(recent: &mut VecDeque<Duration>, elapsed: Duration) {
+    if recent.len() == RECENT_SAMPLES {
+        recent.pop_front();
+    }
+    recent.push_back(elapsed);
+}
+
+/// p50 and p99 in milliseconds, zero without samples.
+fn percentiles(recent: &VecDeque<Duration>) -> (f64, f64) {
+    let mut sorted: Vec<Duration> = recent.iter().copied().collect();
+    sorted.sort();
+    let percentile = |p: f64| {
+        let index = ((sorted.len() as f64 - 1.0) * p).round() as usize;
+        sorted.get(index).map_or(0.0, |d| d.as_secs_f64() * 1000.0)
+    };
+    (percentile(0.5), percentile(0.99))
+}
+
+/// Length of `value` as JSON, without building the string.
+fn json_len(value: &Value) -> u64 {
+    struct Counter(u64);
+    impl io::Write for Counter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0 += buf.len() as u64;
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut counter = Counter(0);
+    let _ = serde_json::to_writer(&mut counter, value);
+    counter.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+    use crate::BrpClient;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_counts_requests_errors_and_bytes_by_method() {
+        let server = MockBrpServer::start();
+        server.reply("world.query", json!([]));
+        let metrics = Arc::new(BrpMetrics::new());
+        let client = BrpClient::new(server.config()).with_middleware(metrics.clone());
+
+        let params = json!({ "data": {} });
+        client.send_rpc("world.query", Some(params.clone())).await.unwrap();
+        client.send_rpc("world.query", Some(params.clone())).await.unwrap();
+        assert!(client.send_rpc("world.spawn_entity", None).await.is_err());
+
+        let snapshot = metrics.snapshot();
+        let query = snapshot.methods.iter().find(|m| m.method == "world.query").unwrap();
+        assert_eq!((query.requests, query.errors), (2, 0));
+        assert_eq!(query.bytes_sent, 2 * params.to_string().len() as u64);
+        let spawn = snapshot.methods.iter().find(|m| m.method == "world.spawn_entity").unwrap();
+        assert_eq!((spawn.requests, spawn.errors), (1, 1));
+        // So are the handshake, and the discovery the unknown method set off
+        assert_eq!((snapshot.requests, snapshot.errors), (6, 3));
+        assert_eq!(snapshot.error_rate(), 0.5);
+        assert!(snapshot.p99_ms >= snapshot.p50_ms && snapshot.p50_ms > 0.0);
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot(), MetricsSnapshot::default());
+    }
+
+    #[test]
+    fn test_percentiles_of_recent_latencies() {
+        let recent: VecDeque<Duration> = (1..=100).map(Duration::from_millis).collect();
+        assert_eq!(percentiles(&recent), (51.0, 99.0));
+        assert_eq!(percentiles(&VecDeque::new()), (0.0, 0.0));
+    }
+}
@@ -0,0 +1,78 @@
+//! Hooks around every JSON-RPC request a [`BrpClient`](crate::BrpClient)
+//! sends to the game, for logging traffic, timing requests or adding headers
+//! a proxy in front of the game wants.
+//!
+//! Hooks see what goes over the wire: discovery and handshake requests too,
+//! each request of a batch, but nothing answered from a replay. The token is
+//! never in the params they are shown. Streams (`+watch`) only go through
+//! [`BrpMiddleware::on_request`].
+
+use crate::BrpError;
+use reqwest::header::HeaderMap;
+use serde_json::Value;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A request on its way to the game.
+#[derive(Debug, Clone, Copy)]
+pub struct RpcRequest<'a> {
+    pub method: &'a str,
+    pub id: u64,
+    pub params: Option<&'a Value>,
+}
+
+/// Install with [`BrpClient::with_middleware`](crate::BrpClient::with_middleware).
+/// Both hooks do nothing by default.
+pub trait BrpMiddleware: Send + Sync {
+    /// Before `request` is sent; `headers` go out with it. A batch calls
+    /// this once per request, with the same headers.
+    fn on_request(&self, request: &RpcRequest<'_>, headers: &mut HeaderMap) {
+        let _ = (request, headers);
+    }
+
+    /// Once `request` was answered or failed, `elapsed` after it was sent.
+    fn on_response(&self, request: &RpcRequest<'_>, outcome: Result<&Value, &BrpError>, elapsed: Duration) {
+        let _ = (request, outcome, elapsed);
+    }
+}
+
+/// So a middleware that collects something, such as latencies, can be
+/// installed while the caller keeps a handle to read it.
+impl<T: BrpMiddleware + ?Sized> BrpMiddleware for Arc<T> {
+    fn on_request(&self, request: &RpcRequest<'_>, headers: &mut HeaderMap) {
+        (**self).on_request(request, headers);
+    }
+
+    fn on_response(&self, request: &RpcRequest<'_>, outcome: Result<&Value, &BrpError>, elapsed: Duration) {
+        (**self).on_response(request, outcome, elapsed);
+    }
+}
+
+/// The middleware of one client, run in the order added.
+#[derive(Clone, Default)]
+pub(crate) struct Chain(Vec<Arc<dyn BrpMiddleware>>);
+
+impl Chain {
+    pub(crate) fn push(&mut self, middleware: Arc<dyn BrpMiddleware>) {
+        self.0.push(middleware);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub(crate) fn on_request(&self, request: &RpcRequest<'_>, headers: &mut HeaderMap) {
+        self.0.iter().for_each(|m| m.on_request(request, headers));
+    }
+
+    pub(crate) fn on_response(&self, request: &RpcRequest<'_>, outcome: Result<&Value, &BrpError>, elapsed: Duration) {
+        self.0.iter().for_each(|m| m.on_response(request, outcome, elapsed));
+    }
+}
+
+impl fmt::Debug for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Chain({} middleware)", self.0.len())
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{BrpClient, Result};
+use crate::types::{RemoteAsset, SpawnResponse};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+
+/// BRP method registered by `bevy_ai_remote`.
+pub const METHOD: &str = "axiom.list_assets";
+
+/// List the files previously uploaded to the game's `_remote_cache`.
+pub async fn list_assets(client: &BrpClient) -> Result<Vec<RemoteAsset>> {
+    let result = client.send_rpc(METHOD, None).await?;
+    parse_assets(&result)
+}
+
+/// Spawn a model that is already in `_remote_cache` without uploading it again.
+pub async fn spawn_cached(client: &BrpClient, path: &str, translation: [f32; 3]) -> Result<SpawnResponse> {
+    let params = json!({
+        "components": {
+            "bevy_ai_remote::AxiomCachedAsset": {
+                "path": path
+            },
+            "bevy_ai_remote::AxiomSpawned": {},
+            "bevy_transform::components::transform::Transform": {
+                "translation": translation,
+                "rotation": [0.0, 0.0, 0.0, 1.0],
+                "scale": [1.0, 1.0, 1.0]
+            }
+        }
+    });
+
+    let result = client.send_rpc("world.spawn_entity", Some(params)).await?;
+
+    let entity_id = result.get("entity")
+        .cloned()
+        .ok_or_else(|| crate::BrpError::InvalidResponse(
+            "Missing 'entity' in spawn response".into()
+        ))?;
+    let entity_id = serde_json::from_value(entity_id)?;
+
+    Ok(SpawnResponse { entity_id })
+}
+
+fn parse_assets(result: &Value) -> Result<Vec<RemoteAsset>> {
+    let entries = result
+        .get("assets")
+        .and_then(Value::as_array)
+        .ok_or_else(|| crate::BrpError::InvalidResponse("Missing 'assets' in list_assets response".into()))?;
+
+    entries
+        .iter()
+        .map(|entry| {
+            let path = entry
+                .get("path")
+                .and_then(Value::as_str)
+                .ok_or_else(|| crate::BrpError::InvalidResponse("Asset entry without 'path'".into()))?;
+            let image = match entry.get("image_base64").and_then(Value::as_str) {
+                Some(data) => Some(
+                    BASE64
+                        .decode(data)
+                        .map_err(|e| crate::BrpError::InvalidResponse(format!("Invalid image data for {}: {}", path, e)))?,
+                ),
+                None => None,
+            };
+            Ok(RemoteAsset {
+                path: path.to_string(),
+                size: entry.get("size").and_then(Value::as_u64).unwrap_or(0),
+                image,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_assets() {
+        let result = json!({
+            "assets": [
+                { "path": "road.glb", "size": 2048 },
+                { "path": "Textures/colormap.png", "size": 9, "image_base64": "dGVzdCBkYXRh" }
+            ]
+        });
+        let assets = parse_assets(&result).unwrap();
+        assert_eq!(assets.len(), 2);
+        assert_eq!(assets[0].path, "road.glb");
+        assert_eq!(assets[0].size, 2048);
+        assert!(assets[0].image.is_none());
+        assert_eq!(assets[1].image.as_deref(), Some(&b"test data"[..]));
+    }
+
+    #[test]
+    fn test_parse_assets_empty() {
+        let result = json!({ "assets": [] });
+        assert!(parse_assets(&result).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_assets_missing_list() {
+        let result = json!({});
+        assert!(matches!(
+            parse_assets(&result),
+            Err(crate::BrpError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_assets_entry_without_path() {
+        let result = json!({ "assets": [{ "size": 1 }] });
+        assert!(parse_assets(&result).is_err());
+    }
+}
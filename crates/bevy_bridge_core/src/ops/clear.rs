@@ -2,6 +2,14 @@ use crate::{BrpClient, Result};
 use crate::types::{ClearResponse, ClearTarget};
 use serde_json::json;
 
+/// `bevy_ai_remote`'s group marker, which the spawn and upload ops insert
+/// when given a group.
+pub const GROUP: &str = "bevy_ai_remote::AxiomGroup";
+
+/// Despawns sent per batch. The game answers one per frame, so this keeps a
+/// batch well within the request timeout.
+pub(crate) const DESPAWN_BATCH: usize = 256;
+
 pub async fn clear(client: &BrpClient, target: ClearTarget) -> Result<ClearResponse> {
     let mut all_entities = Vec::new();
     
@@ -51,18 +59,36 @@ pub async fn clear(client: &BrpClient, target: ClearTarget) -> Result<ClearRespo
                 .ok_or_else(|| crate::BrpError::InvalidResponse("Expected array from world.query".into()))?
                 .clone();
         }
+        ClearTarget::Group(group) => {
+            let params = json!({
+                "data": {
+                    "components": [GROUP]
+                },
+                "filter": {
+                    "with": ["bevy_ai_remote::AxiomSpawned"]
+                }
+            });
+            let result = client.send_rpc("world.query", Some(params)).await?;
+            all_entities = result
+                .as_array()
+                .ok_or_else(|| crate::BrpError::InvalidResponse("Expected array from world.query".into()))?
+                .iter()
+                .filter(|entity_obj| entity_obj["components"][GROUP].as_str() == Some(group.as_str()))
+                .cloned()
+                .collect();
+        }
     }
     
+    let despawns: Vec<_> = all_entities
+        .iter()
+        .filter_map(|entity_obj| entity_obj.get("entity"))
+        .map(|entity_id| ("world.despawn_entity", Some(json!({ "entity": entity_id }))))
+        .collect();
+
     let mut count = 0;
-    
-    for entity_obj in all_entities {
-        if let Some(entity_id) = entity_obj.get("entity") {
-            let despawn_params = json!({
-                "entity": entity_id
-            });
-            let _ = client.send_rpc("world.despawn_entity", Some(despawn_params)).await;
-            count += 1;
-        }
+    for chunk in despawns.chunks(DESPAWN_BATCH) {
+        let results = client.send_batch(chunk.to_vec()).await?;
+        count += results.iter().filter(|result| result.is_ok()).count();
     }
     
     Ok(ClearResponse { entities_removed: count })
@@ -88,6 +114,28 @@ mod tests {
         assert!(params["filter"]["with"].is_array());
     }
 
+    #[tokio::test]
+    async fn test_clear_group_removes_only_that_group() {
+        let server = crate::testing::MockBrpServer::start();
+        server
+            .reply(
+                "world.query",
+                json!([
+                    { "entity": 1, "components": { GROUP: "level1" } },
+                    { "entity": 2, "components": { GROUP: "level2" } },
+                    { "entity": 3, "components": { GROUP: "level1" } }
+                ]),
+            )
+            .reply("world.despawn_entity", json!(null));
+
+        let response = clear(&server.client(), ClearTarget::Group("level1".into())).await.unwrap();
+        assert_eq!(response.entities_removed, 2);
+        assert_eq!(
+            server.calls_to("world.despawn_entity"),
+            vec![Some(json!({ "entity": 1 })), Some(json!({ "entity": 3 }))]
+        );
+    }
+
     #[test]
     fn test_clear_despawn_params_structure() {
         let entity_id = json!(4294967298u64);
@@ -0,0 +1,57 @@
+use crate::types::{ComponentsResponse, EntityId};
+use crate::{BrpClient, Result};
+use serde_json::{json, Map, Value};
+
+pub const INSERT_METHOD: &str = "world.insert_components";
+pub const REMOVE_METHOD: &str = "world.remove_components";
+
+/// Insert `components`, type path to reflected value, replacing any the
+/// entity already has.
+pub async fn insert_components(client: &BrpClient, entity: EntityId, components: Map<String, Value>) -> Result<ComponentsResponse> {
+    let inserted = components.keys().cloned().collect();
+    client.send_rpc(INSERT_METHOD, Some(insert_params(entity, components))).await?;
+    Ok(ComponentsResponse { entity, components: inserted })
+}
+
+pub async fn insert_component(client: &BrpClient, entity: EntityId, type_path: &str, value: Value) -> Result<ComponentsResponse> {
+    let mut components = Map::new();
+    components.insert(type_path.to_string(), value);
+    insert_components(client, entity, components).await
+}
+
+/// Remove `type_paths` from the entity; ones it doesn't have are ignored.
+pub async fn remove_components(client: &BrpClient, entity: EntityId, type_paths: Vec<String>) -> Result<ComponentsResponse> {
+    client.send_rpc(REMOVE_METHOD, Some(remove_params(entity, &type_paths))).await?;
+    Ok(ComponentsResponse { entity, components: type_paths })
+}
+
+pub async fn remove_component(client: &BrpClient, entity: EntityId, type_path: &str) -> Result<ComponentsResponse> {
+    remove_components(client, entity, vec![type_path.to_string()]).await
+}
+
+fn insert_params(entity: EntityId, components: Map<String, Value>) -> Value {
+    json!({ "entity": entity, "components": components })
+}
+
+fn remove_params(entity: EntityId, type_paths: &[String]) -> Value {
+    json!({ "entity": entity, "components": type_paths })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_component_params() {
+        let mut components = Map::new();
+        components.insert("game::Health".to_string(), json!({ "current": 10 }));
+        assert_eq!(
+            insert_params(EntityId(42), components),
+            json!({ "entity": 42, "components": { "game::Health": { "current": 10 } } })
+        );
+        assert_eq!(
+            remove_params(EntityId(42), &["game::Health".to_string()]),
+            json!({ "entity": 42, "components": ["game::Health"] })
+        );
+    }
+}
@@ -0,0 +1,77 @@
+use super::hierarchy::{self, REPARENT_METHOD};
+use crate::types::{DespawnResponse, EntityId};
+use crate::{BrpClient, Result};
+use serde_json::json;
+
+pub const DESPAWN_METHOD: &str = "world.despawn_entity";
+
+/// Despawn the entity. Recursively, its descendants go too, deepest first;
+/// otherwise its children are detached first and stay in the world.
+pub async fn despawn(client: &BrpClient, entity_id: EntityId, recursive: bool) -> Result<DespawnResponse> {
+    if !recursive {
+        let children = hierarchy::get_children(client, entity_id).await?;
+        if !children.is_empty() {
+            client.send_rpc(REPARENT_METHOD, Some(json!({ "entities": children }))).await?;
+        }
+        client.send_rpc(DESPAWN_METHOD, Some(json!({ "entity": entity_id }))).await?;
+        return Ok(DespawnResponse { entities_removed: 1 });
+    }
+
+    let mut entities = vec![entity_id];
+    let mut next = 0;
+    while next < entities.len() {
+        let children = hierarchy::get_children(client, entities[next]).await?;
+        entities.extend(children);
+        next += 1;
+    }
+    // Breadth-first reversed, so children go before their parents
+    let batch = entities
+        .iter()
+        .rev()
+        .map(|entity| (DESPAWN_METHOD, Some(json!({ "entity": entity }))))
+        .collect();
+    let mut results = client.send_batch(batch).await?;
+    // The entity itself is last; its failure is the request's
+    if let Some(Err(e)) = results.pop() {
+        return Err(e);
+    }
+    Ok(DespawnResponse { entities_removed: 1 + results.iter().filter(|r| r.is_ok()).count() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::get::GET_METHOD;
+    use crate::testing::MockBrpServer;
+    use hierarchy::CHILDREN;
+
+    #[tokio::test]
+    async fn test_recursive_despawn_removes_children_first() {
+        let server = MockBrpServer::start();
+        server
+            .reply(GET_METHOD, json!({ "components": { CHILDREN: [2] }, "errors": {} }))
+            .reply(GET_METHOD, json!({ "components": {}, "errors": {} }))
+            .reply(DESPAWN_METHOD, json!(null));
+
+        let response = despawn(&server.client(), EntityId(1), true).await.unwrap();
+        assert_eq!(response.entities_removed, 2);
+        assert_eq!(
+            server.calls_to(DESPAWN_METHOD),
+            vec![Some(json!({ "entity": 2 })), Some(json!({ "entity": 1 }))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_despawn_detaches_children_otherwise() {
+        let server = MockBrpServer::start();
+        server
+            .reply(GET_METHOD, json!({ "components": { CHILDREN: [2, 3] }, "errors": {} }))
+            .reply(REPARENT_METHOD, json!(null))
+            .reply(DESPAWN_METHOD, json!(null));
+
+        let response = despawn(&server.client(), EntityId(1), false).await.unwrap();
+        assert_eq!(response.entities_removed, 1);
+        assert_eq!(server.calls_to(REPARENT_METHOD), vec![Some(json!({ "entities": [2, 3] }))]);
+        assert_eq!(server.calls_to(DESPAWN_METHOD), vec![Some(json!({ "entity": 1 }))]);
+    }
+}
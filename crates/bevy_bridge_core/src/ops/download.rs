@@ -0,0 +1,55 @@
+use super::upload::TRANSFER_TIMEOUT;
+use crate::{BrpClient, BrpError, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+
+/// BRP method registered by `bevy_ai_remote`.
+pub const METHOD: &str = "axiom.download";
+
+/// The bytes of `relative_path` under the game's `assets` directory, such as
+/// a scene the game saved. Large files are read a range per request.
+pub async fn download(client: &BrpClient, relative_path: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    loop {
+        let params = json!({ "path": relative_path, "offset": bytes.len() });
+        let result = client.send_rpc_with_timeout(METHOD, Some(params), client.config().timeout.max(TRANSFER_TIMEOUT)).await?;
+        let (size, data) = parse_range(&result)?;
+        // A file that shrank mid-download ends early rather than looping
+        let done = data.is_empty();
+        bytes.extend(data);
+        if done || bytes.len() as u64 >= size {
+            break;
+        }
+        tracing::debug!("Downloaded {} of {} bytes of {}", bytes.len(), size, relative_path);
+    }
+    Ok(bytes)
+}
+
+fn parse_range(result: &Value) -> Result<(u64, Vec<u8>)> {
+    let size = result
+        .get("size")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| BrpError::InvalidResponse("Missing 'size' in download response".into()))?;
+    let data = result
+        .get("data_base64")
+        .and_then(Value::as_str)
+        .ok_or_else(|| BrpError::InvalidResponse("Missing 'data_base64' in download response".into()))?;
+    let data = BASE64
+        .decode(data)
+        .map_err(|e| BrpError::InvalidResponse(format!("Invalid download data: {}", e)))?;
+    Ok((size, data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        let result = json!({ "size": 9, "offset": 0, "data_base64": "dGVzdCBkYXRh" });
+        let (size, data) = parse_range(&result).unwrap();
+        assert_eq!(size, 9);
+        assert_eq!(data, b"test data");
+        assert!(parse_range(&json!({ "size": 9 })).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::types::EntityId;
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+
+/// Bevy's `Name`, which the spawn and upload ops insert when given a name.
+pub const NAME: &str = "bevy_ecs::name::Name";
+
+/// Every entity named exactly `name`, in the order the game lists them.
+/// Names aren't unique, so this may be more than one.
+pub async fn by_name(client: &BrpClient, name: &str) -> Result<Vec<EntityId>> {
+    let params = json!({ "data": { "components": [NAME] } });
+    let rows = client.send_rpc("world.query", Some(params)).await?;
+    named(&rows, name)
+}
+
+fn named(rows: &Value, name: &str) -> Result<Vec<EntityId>> {
+    let rows = rows
+        .as_array()
+        .ok_or_else(|| BrpError::InvalidResponse("Expected an array of entities from world.query".into()))?;
+    rows.iter()
+        .filter(|row| row["components"][NAME].as_str() == Some(name))
+        .map(|row| {
+            row.get("entity")
+                .and_then(Value::as_u64)
+                .map(EntityId)
+                .ok_or_else(|| BrpError::InvalidResponse(format!("Entity without an id in {}", row)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+
+    #[tokio::test]
+    async fn test_by_name_matches_the_whole_name() {
+        let server = MockBrpServer::start();
+        server.reply(
+            "world.query",
+            json!([
+                { "entity": 1, "components": { NAME: "player_start" } },
+                { "entity": 2, "components": { NAME: "player_start_2" } },
+                { "entity": 3, "components": { NAME: "player_start" } }
+            ]),
+        );
+
+        let found = by_name(&server.client(), "player_start").await.unwrap();
+        assert_eq!(found, vec![EntityId(1), EntityId(3)]);
+        assert!(by_name(&server.client(), "exit").await.unwrap().is_empty());
+        assert_eq!(server.calls_to("world.query")[0], Some(json!({ "data": { "components": [NAME] } })));
+    }
+}
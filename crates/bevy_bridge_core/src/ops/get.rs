@@ -0,0 +1,47 @@
+use crate::types::{EntityDetail, EntityId, GetRequest};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+
+pub const GET_METHOD: &str = "world.get_components";
+pub const LIST_METHOD: &str = "world.list_components";
+
+/// `components` of the entity, or all of them when empty.
+pub async fn get_entity(client: &BrpClient, entity_id: EntityId, components: Vec<String>) -> Result<EntityDetail> {
+    let components = if components.is_empty() {
+        let listed = client.send_rpc(LIST_METHOD, Some(json!({ "entity": entity_id }))).await?;
+        serde_json::from_value(listed)?
+    } else {
+        components
+    };
+    let params = GetRequest { entity: entity_id, components, strict: false };
+    let result = client.send_rpc(GET_METHOD, Some(serde_json::to_value(params)?)).await?;
+    detail(entity_id, result)
+}
+
+fn detail(entity_id: EntityId, result: Value) -> Result<EntityDetail> {
+    if !result.is_object() {
+        return Err(BrpError::InvalidResponse(format!("Expected an object from {}", GET_METHOD)));
+    }
+    let mut detail: EntityDetail = serde_json::from_value(result)?;
+    detail.entity = entity_id;
+    Ok(detail)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detail_from_lenient_response() {
+        let result = json!({
+            "components": { "bevy_ecs::name::Name": "level" },
+            "errors": { "game::Missing": { "code": -23402, "message": "Component not present" } }
+        });
+        let entity = detail(EntityId(7), result).unwrap();
+        assert_eq!(entity.entity, EntityId(7));
+        assert_eq!(entity.components["bevy_ecs::name::Name"], "level");
+        assert!(entity.errors.contains_key("game::Missing"));
+
+        assert!(matches!(detail(EntityId(7), json!([])), Err(BrpError::InvalidResponse(_))));
+    }
+}
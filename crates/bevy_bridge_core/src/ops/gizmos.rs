@@ -0,0 +1,71 @@
+//! Shapes drawn over the scene for a while, to point things out, such as
+//! the entity about to be deleted. The game draws them only if it has bevy's
+//! gizmo plugin, as with `DefaultPlugins`.
+
+use crate::types::{GizmoRequest, GizmoShape};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// BRP methods registered by `bevy_ai_remote`.
+pub const DRAW_METHOD: &str = "axiom.draw_gizmo";
+pub const CLEAR_METHOD: &str = "axiom.clear_gizmos";
+
+/// Draw `shape` for `ttl`, answering its id for [`clear`].
+pub async fn draw(client: &BrpClient, shape: GizmoShape, color: [f32; 4], ttl: Duration) -> Result<u64> {
+    let request = GizmoRequest { shape, color, ttl_secs: ttl.as_secs_f32() };
+    let result = client.send_rpc(DRAW_METHOD, Some(serde_json::to_value(request)?)).await?;
+    result
+        .get("id")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| BrpError::InvalidResponse(format!("Missing 'id' in {} response", DRAW_METHOD)))
+}
+
+pub async fn draw_line(client: &BrpClient, start: [f32; 3], end: [f32; 3], color: [f32; 4], ttl: Duration) -> Result<u64> {
+    draw(client, GizmoShape::Line { start, end }, color, ttl).await
+}
+
+/// A box from its lowest to its highest corner.
+pub async fn draw_aabb(client: &BrpClient, min: [f32; 3], max: [f32; 3], color: [f32; 4], ttl: Duration) -> Result<u64> {
+    draw(client, GizmoShape::Aabb { min, max }, color, ttl).await
+}
+
+pub async fn draw_sphere(client: &BrpClient, center: [f32; 3], radius: f32, color: [f32; 4], ttl: Duration) -> Result<u64> {
+    draw(client, GizmoShape::Sphere { center, radius }, color, ttl).await
+}
+
+/// Stop drawing the shape `id`, or every shape; answers how many went.
+pub async fn clear(client: &BrpClient, id: Option<u64>) -> Result<usize> {
+    let params = id.map(|id| json!({ "id": id }));
+    let result = client.send_rpc(CLEAR_METHOD, params).await?;
+    result
+        .get("removed")
+        .and_then(Value::as_u64)
+        .map(|removed| removed as usize)
+        .ok_or_else(|| BrpError::InvalidResponse(format!("Missing 'removed' in {} response", CLEAR_METHOD)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+
+    #[tokio::test]
+    async fn test_draw_and_clear() {
+        let server = MockBrpServer::start();
+        server.reply(DRAW_METHOD, json!({ "id": 3 }));
+        server.reply(CLEAR_METHOD, json!({ "removed": 1 }));
+        let client = server.client();
+
+        let id = draw_line(&client, [0.0; 3], [0.0, 1.0, 0.0], [1.0; 4], Duration::from_millis(1500)).await.unwrap();
+        assert_eq!(id, 3);
+        assert_eq!(
+            server.calls_to(DRAW_METHOD),
+            vec![Some(json!({ "kind": "line", "start": [0.0, 0.0, 0.0], "end": [0.0, 1.0, 0.0], "color": [1.0, 1.0, 1.0, 1.0], "ttl_secs": 1.5 }))]
+        );
+
+        assert_eq!(clear(&client, Some(id)).await.unwrap(), 1);
+        clear(&client, None).await.unwrap();
+        assert_eq!(server.calls_to(CLEAR_METHOD), vec![Some(json!({ "id": 3 })), None]);
+    }
+}
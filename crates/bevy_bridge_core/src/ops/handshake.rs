@@ -0,0 +1,90 @@
+//! Checking that the game's `bevy_ai_remote` speaks the editor's protocol.
+//! [`BrpClient`] does this before its first request; [`handshake`] is for
+//! asking explicitly.
+
+use crate::types::{ProtocolVersion, ResourceResponse};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Must equal `bevy_ai_remote::PROTOCOL_VERSION`.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+pub const PROTOCOL_RESOURCE: &str = "bevy_ai_remote::AxiomProtocolVersion";
+
+const GET_RESOURCES_METHOD: &str = "world.get_resources";
+
+/// `bevy_remote`'s codes for a resource type it can't reflect and for one
+/// missing from the world.
+pub(crate) const RESOURCE_ERROR: i32 = -23501;
+pub(crate) const RESOURCE_NOT_PRESENT: i32 = -23502;
+
+/// The plugin's version, `None` for a game without `bevy_ai_remote`, or
+/// [`BrpError::VersionMismatch`].
+pub async fn handshake(client: &BrpClient) -> Result<Option<ProtocolVersion>> {
+    let result = client.send_rpc(GET_RESOURCES_METHOD, Some(json!({ "resource": PROTOCOL_RESOURCE }))).await;
+    check(result)
+}
+
+/// [`handshake`] without going through `send_rpc`, for the client's own check.
+pub(crate) async fn check_with(client: &BrpClient, timeout: Duration) -> Result<Option<ProtocolVersion>> {
+    let params = json!({ "resource": PROTOCOL_RESOURCE });
+    check(client.request(GET_RESOURCES_METHOD, Some(params), timeout).await)
+}
+
+fn check(result: Result<Value>) -> Result<Option<ProtocolVersion>> {
+    let result = match result {
+        Ok(result) => result,
+        // Not registered, or not inserted: plain bevy_remote, which the
+        // built-in ops still work with
+        Err(BrpError::JsonRpc { code, .. }) if code == RESOURCE_ERROR || code == RESOURCE_NOT_PRESENT => {
+            return Ok(None)
+        }
+        // Bevy 0.15 names it differently and predates the plugin's protocol
+        Err(BrpError::MethodNotFound { .. }) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if result.get("value").is_none() {
+        return Err(BrpError::InvalidResponse(format!("Missing 'value' for {}", PROTOCOL_RESOURCE)));
+    }
+    let ResourceResponse::<ProtocolVersion> { value: version } = serde_json::from_value(result)?;
+    if version.protocol != PROTOCOL_VERSION {
+        return Err(BrpError::VersionMismatch {
+            editor: PROTOCOL_VERSION,
+            game: version.protocol,
+            plugin: version.plugin,
+        });
+    }
+    Ok(Some(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_accepts_the_same_version() {
+        let result = json!({ "value": { "protocol": PROTOCOL_VERSION, "plugin": "0.1.0" } });
+        let version = check(Ok(result)).unwrap().unwrap();
+        assert_eq!(version, ProtocolVersion { protocol: PROTOCOL_VERSION, plugin: "0.1.0".into() });
+    }
+
+    #[test]
+    fn test_check_reports_drift() {
+        let result = json!({ "value": { "protocol": PROTOCOL_VERSION + 1, "plugin": "0.2.0" } });
+        match check(Ok(result)) {
+            Err(BrpError::VersionMismatch { editor, game, plugin }) => {
+                assert_eq!((editor, game), (PROTOCOL_VERSION, PROTOCOL_VERSION + 1));
+                assert_eq!(plugin, "0.2.0");
+            }
+            other => panic!("Expected VersionMismatch, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_check_allows_games_without_the_plugin() {
+        let missing = BrpError::json_rpc(RESOURCE_ERROR, "Unknown resource type");
+        assert!(check(Err(missing)).unwrap().is_none());
+        assert!(check(Err(BrpError::Timeout(Duration::from_secs(1)))).is_err());
+    }
+}
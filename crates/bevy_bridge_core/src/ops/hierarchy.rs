@@ -0,0 +1,86 @@
+use super::find::NAME;
+use crate::types::{EntityId, HierarchyNode};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+
+pub const REPARENT_METHOD: &str = "world.reparent_entities";
+pub const CHILDREN: &str = "bevy_ecs::hierarchy::Children";
+
+/// Make `child` a child of `new_parent`, leaving any previous parent.
+pub async fn reparent(client: &BrpClient, child: EntityId, new_parent: EntityId) -> Result<()> {
+    client.send_rpc(REPARENT_METHOD, Some(reparent_params(child, Some(new_parent)))).await?;
+    Ok(())
+}
+
+/// Remove `child` from its parent, making it a root entity.
+pub async fn detach(client: &BrpClient, child: EntityId) -> Result<()> {
+    client.send_rpc(REPARENT_METHOD, Some(reparent_params(child, None))).await?;
+    Ok(())
+}
+
+/// Direct children of `parent`, in order; empty when it has none.
+pub async fn get_children(client: &BrpClient, parent: EntityId) -> Result<Vec<EntityId>> {
+    let detail = super::get::get_entity(client, parent, vec![CHILDREN.to_string()]).await?;
+    children(detail.components.get(CHILDREN))
+}
+
+/// `root` and its descendants, with their names; one request per entity.
+pub async fn tree(client: &BrpClient, root: EntityId) -> Result<HierarchyNode> {
+    let detail = super::get::get_entity(client, root, vec![NAME.to_string(), CHILDREN.to_string()]).await?;
+    let mut node = HierarchyNode {
+        entity: root,
+        name: detail.components.get(NAME).and_then(Value::as_str).map(str::to_string),
+        children: Vec::new(),
+    };
+    for child in children(detail.components.get(CHILDREN))? {
+        node.children.push(Box::pin(tree(client, child)).await?);
+    }
+    Ok(node)
+}
+
+fn reparent_params(child: EntityId, parent: Option<EntityId>) -> Value {
+    match parent {
+        Some(parent) => json!({ "entities": [child], "parent": parent }),
+        None => json!({ "entities": [child] }),
+    }
+}
+
+fn children(value: Option<&Value>) -> Result<Vec<EntityId>> {
+    let Some(value) = value else {
+        return Ok(Vec::new());
+    };
+    serde_json::from_value(value.clone())
+        .map_err(|_| BrpError::InvalidResponse(format!("Expected entity ids in {}, got {}", CHILDREN, value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reparent_params() {
+        assert_eq!(reparent_params(EntityId(4), Some(EntityId(2))), json!({ "entities": [4], "parent": 2 }));
+        assert_eq!(reparent_params(EntityId(4), None), json!({ "entities": [4] }));
+    }
+
+    #[tokio::test]
+    async fn test_tree_walks_children_in_order() {
+        let server = crate::testing::MockBrpServer::start();
+        server.reply(super::super::get::GET_METHOD, json!({ "components": { NAME: "level", CHILDREN: [2, 3] } }));
+        server.reply(super::super::get::GET_METHOD, json!({ "components": { NAME: "crate" } }));
+        server.reply(super::super::get::GET_METHOD, json!({ "components": {} }));
+
+        let tree = tree(&server.client(), EntityId(1)).await.unwrap();
+        assert_eq!(tree.name.as_deref(), Some("level"));
+        assert_eq!(tree.children[0].name.as_deref(), Some("crate"));
+        assert_eq!(tree.children[1].name, None);
+        assert_eq!(tree.entities(), [EntityId(1), EntityId(2), EntityId(3)]);
+    }
+
+    #[test]
+    fn test_children() {
+        assert_eq!(children(Some(&json!([4294967300u64, 5]))).unwrap(), vec![EntityId(4294967300), EntityId(5)]);
+        assert!(children(None).unwrap().is_empty());
+        assert!(children(Some(&json!({ "not": "a list" }))).is_err());
+    }
+}
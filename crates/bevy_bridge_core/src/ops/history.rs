@@ -0,0 +1,51 @@
+use crate::{BrpClient, Result};
+use crate::types::{History, HistoryStep};
+
+/// BRP methods registered by `bevy_ai_remote`.
+pub const HISTORY_METHOD: &str = "axiom.history";
+pub const UNDO_METHOD: &str = "axiom.undo";
+pub const REDO_METHOD: &str = "axiom.redo";
+
+/// The game's journal of changes to editor-spawned entities.
+pub async fn history(client: &BrpClient) -> Result<History> {
+    let result = client.send_rpc(HISTORY_METHOD, None).await?;
+    Ok(serde_json::from_value(result)?)
+}
+
+/// Revert the latest applied entry; a JSON-RPC error if there is none.
+pub async fn undo(client: &BrpClient) -> Result<HistoryStep> {
+    let result = client.send_rpc(UNDO_METHOD, None).await?;
+    Ok(serde_json::from_value(result)?)
+}
+
+/// Reapply the latest undone entry; a JSON-RPC error if there is none.
+pub async fn redo(client: &BrpClient) -> Result<HistoryStep> {
+    let result = client.send_rpc(REDO_METHOD, None).await?;
+    Ok(serde_json::from_value(result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_history_step() {
+        let result = json!({
+            "label": "Spawn 2, move 1",
+            "history": {
+                "entries": [
+                    { "id": 1, "label": "Spawn cube", "changes": 1 },
+                    { "id": 2, "label": "Spawn 2, move 1", "changes": 3 }
+                ],
+                "cursor": 1
+            }
+        });
+        let step: HistoryStep = serde_json::from_value(result).unwrap();
+        assert_eq!(step.label, "Spawn 2, move 1");
+        assert_eq!(step.history.entries[1].changes, 3);
+        assert!(step.history.can_undo());
+        assert!(step.history.can_redo());
+        assert!(!History::default().can_undo());
+    }
+}
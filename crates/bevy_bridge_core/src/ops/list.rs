@@ -0,0 +1,42 @@
+use crate::{BrpClient, BrpError, Result};
+use serde_json::Value;
+
+pub const LIST_COMPONENTS_METHOD: &str = "world.list_components";
+pub const LIST_RESOURCES_METHOD: &str = "world.list_resources";
+
+/// Type paths of every component registered in the game.
+pub async fn list_components(client: &BrpClient) -> Result<Vec<String>> {
+    let result = client.send_rpc(LIST_COMPONENTS_METHOD, None).await?;
+    type_paths(LIST_COMPONENTS_METHOD, result)
+}
+
+/// Type paths of every resource registered in the game.
+pub async fn list_resources(client: &BrpClient) -> Result<Vec<String>> {
+    let result = client.send_rpc(LIST_RESOURCES_METHOD, None).await?;
+    type_paths(LIST_RESOURCES_METHOD, result)
+}
+
+/// Sorted and deduplicated.
+fn type_paths(method: &str, result: Value) -> Result<Vec<String>> {
+    let mut paths: Vec<String> = serde_json::from_value(result)
+        .map_err(|_| BrpError::InvalidResponse(format!("Expected type paths from {}", method)))?;
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_type_paths_are_sorted_and_unique() {
+        let result = json!(["game::Health", "bevy_ecs::name::Name", "game::Health"]);
+        assert_eq!(
+            type_paths(LIST_COMPONENTS_METHOD, result).unwrap(),
+            vec!["bevy_ecs::name::Name", "game::Health"]
+        );
+        assert!(type_paths(LIST_COMPONENTS_METHOD, json!({})).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+//! The running game's recent log output, kept by `bevy_ai_remote` once the
+//! game installs its `log_layer`; otherwise there are no records.
+
+use crate::types::{LogLevel, LogRecord};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+
+/// BRP method registered by `bevy_ai_remote`.
+pub const METHOD: &str = "axiom.logs";
+
+/// The newest `limit` records at least as severe as `level`, oldest first.
+/// The game keeps the last thousand.
+pub async fn tail(client: &BrpClient, level: LogLevel, limit: usize) -> Result<Vec<LogRecord>> {
+    fetch(client, json!({ "level": level, "limit": limit })).await
+}
+
+/// Like [`tail`], but only records newer than `seq`, to follow the log.
+pub async fn since(client: &BrpClient, seq: u64, level: LogLevel, limit: usize) -> Result<Vec<LogRecord>> {
+    fetch(client, json!({ "level": level, "limit": limit, "after": seq })).await
+}
+
+async fn fetch(client: &BrpClient, params: Value) -> Result<Vec<LogRecord>> {
+    let mut result = client.send_rpc(METHOD, Some(params)).await?;
+    let records = result
+        .get_mut("records")
+        .map(Value::take)
+        .ok_or_else(|| BrpError::InvalidResponse(format!("Missing 'records' in {} response", METHOD)))?;
+    Ok(serde_json::from_value(records)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+
+    #[tokio::test]
+    async fn test_tail_parses_records() {
+        let server = MockBrpServer::start();
+        server.reply(
+            METHOD,
+            json!({ "records": [
+                { "seq": 4, "timestamp_ms": 1700000000000u64, "level": "warn", "target": "game::ai", "message": "stuck", "fields": { "entity": 12 } },
+                { "seq": 9, "timestamp_ms": 1700000000500u64, "level": "error", "target": "game", "message": "no path" }
+            ] }),
+        );
+        let records = tail(&server.client(), LogLevel::Warn, 50).await.unwrap();
+        assert_eq!(server.calls_to(METHOD), vec![Some(json!({ "level": "warn", "limit": 50 }))]);
+        assert_eq!(records.len(), 2);
+        assert_eq!((records[0].level, records[0].fields["entity"].clone()), (LogLevel::Warn, json!(12)));
+        assert_eq!((records[1].seq, records[1].message.as_str()), (9, "no path"));
+        assert!(records[1].fields.is_empty());
+    }
+
+    #[test]
+    fn test_levels_order_by_severity() {
+        assert!(LogLevel::Error > LogLevel::Warn && LogLevel::Warn > LogLevel::Info && LogLevel::Trace < LogLevel::Debug);
+    }
+}
@@ -4,3 +4,23 @@ pub mod spawn;
 pub mod upload;
 pub mod clear;
 pub mod raw;
+pub mod screenshot;
+pub mod assets;
+pub mod scene;
+pub mod history;
+pub mod profile;
+pub mod watch;
+pub mod components;
+pub mod get;
+pub mod transform;
+pub mod hierarchy;
+pub mod despawn;
+pub mod list;
+pub mod logs;
+pub mod resources;
+pub mod sim;
+pub mod download;
+pub mod handshake;
+pub mod find;
+pub mod gizmos;
+pub mod pick;
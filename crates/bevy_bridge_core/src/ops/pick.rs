@@ -0,0 +1,44 @@
+use crate::types::RaycastHit;
+use crate::{BrpClient, Result};
+use serde_json::{json, Value};
+
+/// BRP method registered by `bevy_ai_remote`.
+pub const RAYCAST_METHOD: &str = "axiom.raycast";
+
+/// The nearest mesh hit by the ray from `ray_origin` along `ray_dir`, in
+/// world space, or `None` when it hits nothing.
+pub async fn raycast(client: &BrpClient, ray_origin: [f32; 3], ray_dir: [f32; 3]) -> Result<Option<RaycastHit>> {
+    let params = json!({ "origin": ray_origin, "direction": ray_dir });
+    let result = client.send_rpc(RAYCAST_METHOD, Some(params)).await?;
+    parse_hit(result)
+}
+
+fn parse_hit(result: Value) -> Result<Option<RaycastHit>> {
+    Ok(serde_json::from_value(result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+    use crate::types::EntityId;
+
+    #[test]
+    fn test_parse_hit_or_miss() {
+        assert_eq!(parse_hit(Value::Null).unwrap(), None);
+        let hit = parse_hit(json!({ "entity": 7, "point": [0.0, 0.5, 0.0], "normal": [0.0, 1.0, 0.0], "distance": 4.5 }))
+            .unwrap()
+            .unwrap();
+        assert_eq!((hit.entity, hit.point, hit.distance), (EntityId(7), [0.0, 0.5, 0.0], 4.5));
+        assert!(parse_hit(json!({ "entity": 7 })).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_raycast_sends_the_ray() {
+        let server = MockBrpServer::start();
+        server.reply(RAYCAST_METHOD, Value::Null);
+        let hit = raycast(&server.client(), [0.0, 5.0, 0.0], [0.0, -1.0, 0.0]).await.unwrap();
+        assert_eq!(hit, None);
+        assert_eq!(server.calls_to(RAYCAST_METHOD), vec![Some(json!({ "origin": [0.0, 5.0, 0.0], "direction": [0.0, -1.0, 0.0] }))]);
+    }
+}
@@ -1,11 +1,75 @@
-use crate::{BrpClient, Result};
-use crate::types::PingResponse;
+use super::handshake::{RESOURCE_ERROR, RESOURCE_NOT_PRESENT};
+use super::resources::get_resource;
+use crate::types::{GameStatus, PingResponse};
+use crate::{BrpClient, BrpError, Result};
+use serde_json::Value;
+use std::time::Instant;
 
+pub const STATUS_RESOURCE: &str = "bevy_ai_remote::AxiomStatus";
+
+/// Whether the game answers, how fast, and what it runs.
 pub async fn ping(client: &BrpClient) -> Result<PingResponse> {
+    // First, so a new client's handshake isn't in the latency
+    let status = status(client).await?;
+
+    let started = Instant::now();
     let result = client.send_rpc("rpc.discover", None).await?;
-    
+    let latency_ms = started.elapsed().as_secs_f64() * 1000.0;
+
     Ok(PingResponse {
         alive: true,
+        latency_ms,
+        method_count: result.get("methods").and_then(Value::as_array).map_or(0, Vec::len),
+        bevy_version: result.pointer("/info/version").and_then(Value::as_str).map(str::to_string),
+        plugin_version: status.as_ref().map(|s| s.plugin.clone()),
+        frames: status.as_ref().map(|s| s.frames),
+        uptime_secs: status.map(|s| s.uptime_secs),
         methods: result,
     })
 }
+
+/// The plugin's status resource, `None` for a game without one.
+pub async fn status(client: &BrpClient) -> Result<Option<GameStatus>> {
+    match get_resource(client, STATUS_RESOURCE).await {
+        Ok(value) => Ok(Some(serde_json::from_value(value)?)),
+        Err(BrpError::JsonRpc { code, .. }) if code == RESOURCE_ERROR || code == RESOURCE_NOT_PRESENT => Ok(None),
+        // Bevy 0.15 names the method differently
+        Err(BrpError::MethodNotFound { .. }) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_ping_reports_versions_and_status() {
+        let server = MockBrpServer::start();
+        server.reply(
+            "rpc.discover",
+            json!({ "info": { "title": "Bevy Remote Protocol", "version": "0.18.1" }, "methods": [{ "name": "world.query" }, { "name": "axiom.undo" }] }),
+        );
+        server.reply("world.get_resources", json!({ "value": { "plugin": "0.1.0", "frames": 42, "uptime_secs": 1.5 } }));
+
+        let ping = ping(&server.client()).await.unwrap();
+        assert_eq!(ping.method_count, 2);
+        assert_eq!(ping.bevy_version.as_deref(), Some("0.18.1"));
+        assert_eq!(ping.plugin_version.as_deref(), Some("0.1.0"));
+        assert_eq!((ping.frames, ping.uptime_secs), (Some(42), Some(1.5)));
+        assert!(ping.latency_ms >= 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_ping_without_the_plugin() {
+        let server = MockBrpServer::start();
+        server.reply("rpc.discover", json!({ "methods": [] }));
+        server.reply_error("world.get_resources", RESOURCE_ERROR, "Unknown resource type");
+
+        let ping = ping(&server.client()).await.unwrap();
+        assert!(ping.alive);
+        assert_eq!((ping.plugin_version, ping.bevy_version, ping.frames), (None, None, None));
+    }
+}
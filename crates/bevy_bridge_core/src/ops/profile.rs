@@ -0,0 +1,73 @@
+use crate::{BrpClient, Result};
+use crate::types::ProfileCapture;
+use serde_json::json;
+
+/// BRP method registered by `bevy_ai_remote`.
+pub const PROFILE_METHOD: &str = "axiom.profile";
+
+/// Start recording frame timings, dropping earlier ones. `capacity` is how
+/// many frames the game keeps; it defaults to 600.
+pub async fn start(client: &BrpClient, capacity: Option<usize>) -> Result<ProfileCapture> {
+    let mut params = json!({ "action": "start" });
+    if let Some(capacity) = capacity {
+        params["capacity"] = json!(capacity);
+    }
+    let result = client.send_rpc(PROFILE_METHOD, Some(params)).await?;
+    Ok(serde_json::from_value(result)?)
+}
+
+/// Stop recording; the frames recorded so far are kept and returned.
+pub async fn stop(client: &BrpClient) -> Result<ProfileCapture> {
+    let result = client.send_rpc(PROFILE_METHOD, Some(json!({ "action": "stop" }))).await?;
+    Ok(serde_json::from_value(result)?)
+}
+
+/// The recorded frames, while recording or after.
+pub async fn read(client: &BrpClient) -> Result<ProfileCapture> {
+    let result = client.send_rpc(PROFILE_METHOD, Some(json!({ "action": "read" }))).await?;
+    Ok(serde_json::from_value(result)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_averages_and_ranks() {
+        let result = json!({
+            "recording": true,
+            "system_timing": true,
+            "frames": [
+                {
+                    "frame": 1, "total_ms": 10.0, "entities": 10,
+                    "schedules": [{ "name": "Update", "ms": 6.0 }, { "name": "PostUpdate", "ms": 2.0 }],
+                    "systems": [{ "name": "game::spin", "ms": 4.0, "schedule": "Update" }]
+                },
+                {
+                    "frame": 2, "total_ms": 30.0, "entities": 210,
+                    "schedules": [{ "name": "Update", "ms": 20.0 }, { "name": "PostUpdate", "ms": 6.0 }],
+                    "systems": [
+                        { "name": "game::spin", "ms": 16.0, "schedule": "Update" },
+                        { "name": "game::hud", "ms": 1.0, "schedule": "Update" }
+                    ]
+                }
+            ]
+        });
+        let capture: ProfileCapture = serde_json::from_value(result).unwrap();
+        let summary = capture.summary(1);
+        assert_eq!(summary.frames, 2);
+        assert_eq!(summary.avg_frame_ms, 20.0);
+        assert_eq!(summary.max_frame_ms, 30.0);
+        assert_eq!(summary.fps, 50.0);
+        assert_eq!(summary.slowest_frame, Some(2));
+        assert_eq!(summary.entities, (10, 210));
+        let schedules: Vec<(&str, f64)> = summary.schedules.iter().map(|t| (t.name.as_str(), t.ms)).collect();
+        assert_eq!(schedules, vec![("Update", 13.0), ("PostUpdate", 4.0)]);
+        assert_eq!(summary.systems.len(), 1);
+        assert_eq!(summary.systems[0].name, "game::spin");
+        assert_eq!(summary.systems[0].ms, 10.0);
+
+        let empty = ProfileCapture::default().summary(5);
+        assert_eq!((empty.frames, empty.fps, empty.slowest_frame), (0, 0.0, None));
+    }
+}
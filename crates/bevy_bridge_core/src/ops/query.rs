@@ -1,27 +1,175 @@
+use super::{clear::GROUP, find::NAME, hierarchy::CHILDREN, transform::TRANSFORM};
 use crate::{BrpClient, Result};
-use crate::types::QueryResponse;
-use serde_json::json;
+use crate::types::{short_name, QueryOptions, QueryResponse};
+use serde_json::{json, Value};
 
-pub async fn query(client: &BrpClient, components: Vec<String>) -> Result<QueryResponse> {
-    let params = json!({
-        "data": {
-            "components": components
+/// Types a short name in a [`Filter`](crate::types::Filter) may stand for when it isn't one of the
+/// queried components.
+const WELL_KNOWN: [&str; 4] = [TRANSFORM, NAME, GROUP, CHILDREN];
+
+/// Entities with all of `components`, with their values, narrowed by
+/// `options`. The game filters by `with` and `without`; the filter and paging
+/// are applied here, so the game still sends every match but callers, such
+/// as an LLM, only get the page they asked for.
+pub async fn query(client: &BrpClient, components: Vec<String>, options: QueryOptions) -> Result<QueryResponse> {
+    // The filter needs the values of the components it tests
+    let mut fetched = components.clone();
+    if let Some(filter) = &options.filter {
+        for component in filter.components() {
+            let component = resolve(component, &fetched, &options.with);
+            if !fetched.contains(&component) {
+                fetched.push(component);
+            }
         }
-    });
-    
-    let result = client.send_rpc("world.query", Some(params)).await?;
+    }
+
+    let result = client.send_rpc("world.query", Some(query_params(fetched, &options))).await?;
     
     let entities = result
         .as_array()
         .ok_or_else(|| crate::BrpError::InvalidResponse("Expected array from world.query".into()))?
         .clone();
     
-    Ok(QueryResponse { entities })
+    Ok(narrow(entities, &components, &options))
+}
+
+fn query_params(components: Vec<String>, options: &QueryOptions) -> Value {
+    let mut params = json!({
+        "data": {
+            "components": components
+        }
+    });
+    if !options.with.is_empty() || !options.without.is_empty() {
+        params["filter"] = json!({ "with": options.with, "without": options.without });
+    }
+    params
+}
+
+/// Apply the filter and the page, and drop the components fetched only for
+/// the filter.
+fn narrow(entities: Vec<Value>, components: &[String], options: &QueryOptions) -> QueryResponse {
+    let matching: Vec<Value> = entities
+        .into_iter()
+        .filter(|row| options.filter.as_ref().is_none_or(|f| f.matches(row)))
+        .collect();
+    let total = matching.len();
+    let mut page: Vec<Value> = matching
+        .into_iter()
+        .skip(options.offset)
+        .take(options.limit.unwrap_or(usize::MAX))
+        .collect();
+    for row in &mut page {
+        if let Some(values) = row.get_mut("components").and_then(Value::as_object_mut) {
+            values.retain(|name, _| components.contains(name));
+        }
+    }
+    QueryResponse { entities: page, total }
+}
+
+/// The type a [`Filter`](crate::types::Filter) means by `component`: a queried or well-known one
+/// with that short name, else as written.
+fn resolve(component: &str, fetched: &[String], with: &[String]) -> String {
+    if component.contains("::") {
+        return component.to_string();
+    }
+    fetched
+        .iter()
+        .chain(with)
+        .map(String::as_str)
+        .chain(WELL_KNOWN)
+        .find(|path| short_name(path) == component)
+        .unwrap_or(component)
+        .to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Filter;
+
+    const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+    fn row(entity: u64, y: f64, kind: &str) -> Value {
+        json!({
+            "entity": entity,
+            "components": {
+                "bevy_ai_remote::AxiomPrimitive": { "primitive_type": kind },
+                TRANSFORM: { "translation": [0.0, y, 0.0] }
+            }
+        })
+    }
+
+    #[test]
+    fn test_query_params_filter_only_when_asked() {
+        let options = QueryOptions::default();
+        assert_eq!(query_params(vec!["A".into()], &options), json!({ "data": { "components": ["A"] } }));
+
+        let options = QueryOptions { without: vec!["B".into()], ..Default::default() };
+        assert_eq!(
+            query_params(vec!["A".into()], &options),
+            json!({ "data": { "components": ["A"] }, "filter": { "with": [], "without": ["B"] } })
+        );
+    }
+
+    #[test]
+    fn test_narrow_applies_filter_then_page() {
+        let rows = vec![row(1, 2.0, "cube"), row(2, -1.0, "cube"), row(3, 5.0, "sphere"), row(4, 7.0, "cube")];
+        let options = QueryOptions {
+            filter: Some(Filter::field("Transform.translation.y").gt(0).and(Filter::field("AxiomPrimitive.primitive_type").eq("cube"))),
+            offset: 1,
+            limit: Some(5),
+            ..Default::default()
+        };
+        let response = narrow(rows, &["bevy_ai_remote::AxiomPrimitive".to_string()], &options);
+        assert_eq!(response.total, 2);
+        assert_eq!(response.entities.len(), 1);
+        assert_eq!(response.entities[0]["entity"], 4);
+        // Transform was only fetched for the filter
+        assert!(response.entities[0]["components"].get(TRANSFORM).is_none());
+    }
+
+    #[test]
+    fn test_filter_reads_dotted_fields_by_short_name() {
+        let rows = vec![row(1, 12.0, "cube"), row(2, 3.0, "cube"), row(3, 15.0, "sphere")];
+        let high = Filter::field("Transform.translation.y").gt(10.0);
+        let cube = Filter::field("AxiomPrimitive.primitive_type").eq("cube");
+        let options = QueryOptions { filter: Some(high.clone().and(!cube.clone()).or(Filter::field("Transform.translation.1").lt(5))), ..Default::default() };
+        let response = narrow(rows.clone(), &[], &options);
+        let entities: Vec<&Value> = response.entities.iter().map(|row| &row["entity"]).collect();
+        assert_eq!(entities, [2, 3]);
+
+        assert!(!Filter::field("Transform.translation.q").gt(0).matches(&rows[0]));
+        assert!(!Filter::field("Missing.value").ne(0).matches(&rows[0]));
+        assert_eq!(!!cube.clone(), cube);
+        assert_eq!(high.and(cube).components(), ["Transform", "AxiomPrimitive"]);
+    }
+
+    #[test]
+    fn test_short_names_resolve_to_queried_or_well_known_types() {
+        let fetched = vec!["bevy_ai_remote::AxiomPrimitive".to_string()];
+        assert_eq!(resolve("AxiomPrimitive", &fetched, &[]), "bevy_ai_remote::AxiomPrimitive");
+        assert_eq!(resolve("Transform", &fetched, &[]), TRANSFORM);
+        assert_eq!(resolve("my_game::Health", &fetched, &[]), "my_game::Health");
+        assert_eq!(resolve("Health", &fetched, &[]), "Health");
+        assert_eq!(short_name("bevy_asset::handle::Handle<bevy_mesh::mesh::Mesh>"), "Handle");
+    }
+
+    #[test]
+    fn test_deserialize_components() {
+        use crate::types::{EntityId, SceneTransform};
+
+        let mut rows = vec![json!({
+            "entity": 7,
+            "components": { TRANSFORM: { "translation": [1.0, 2.0, 3.0], "rotation": [0.0, 0.0, 0.0, 1.0], "scale": [1.0, 1.0, 1.0] } }
+        })];
+        rows.push(json!({ "entity": 8, "components": {} }));
+        let response = QueryResponse { entities: rows, total: 2 };
+        let transforms = response.deserialize_components::<SceneTransform>().unwrap();
+        assert_eq!(transforms, vec![(EntityId(7), SceneTransform { translation: [1.0, 2.0, 3.0], ..Default::default() })]);
+
+        let bad = QueryResponse { entities: vec![json!({ "entity": 9, "components": { TRANSFORM: "nope" } })], total: 1 };
+        assert!(bad.deserialize_components::<SceneTransform>().is_err());
+    }
 
     #[test]
     fn test_query_wraps_params_in_data_key() {
@@ -0,0 +1,20 @@
+use crate::types::ResourceResponse;
+use crate::{BrpClient, BrpError, Result};
+use serde_json::{json, Value};
+
+pub const GET_METHOD: &str = "world.get_resources";
+pub const INSERT_METHOD: &str = "world.insert_resources";
+
+/// The reflected value of the resource, such as `bevy_time::time::Time<()>`.
+pub async fn get_resource(client: &BrpClient, type_path: &str) -> Result<Value> {
+    let result = client.send_rpc(GET_METHOD, Some(json!({ "resource": type_path }))).await?;
+    let response: ResourceResponse = serde_json::from_value(result)
+        .map_err(|_| BrpError::InvalidResponse(format!("Expected a value from {}", GET_METHOD)))?;
+    Ok(response.value)
+}
+
+/// Insert the resource, replacing its current value.
+pub async fn insert_resource(client: &BrpClient, type_path: &str, value: Value) -> Result<()> {
+    client.send_rpc(INSERT_METHOD, Some(json!({ "resource": type_path, "value": value }))).await?;
+    Ok(())
+}
@@ -0,0 +1,317 @@
+//! The editor-spawned part of the scene as plain data: exported with one
+//! query, compared between exports and restored by respawning it.
+
+use super::clear::GROUP;
+use super::find::NAME;
+use super::spawn::{label, primitive_component};
+use super::transform::TRANSFORM;
+use crate::{BrpClient, BrpError, Result};
+use crate::types::{ComponentDiff, EntityId, ModifiedEntity, SceneDiff, SceneEntity, SceneObject, SceneTransform};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const SPAWNED: &str = "bevy_ai_remote::AxiomSpawned";
+const PRIMITIVE: &str = "bevy_ai_remote::AxiomPrimitive";
+const REMOTE_ASSET: &str = "bevy_ai_remote::AxiomRemoteAsset";
+const CACHED_ASSET: &str = "bevy_ai_remote::AxiomCachedAsset";
+
+/// Transforms closer than this count as unchanged.
+const EPSILON: f32 = 1e-4;
+
+/// Entities of one kind and name, before and after.
+type Group<'a> = (Vec<&'a SceneEntity>, Vec<&'a SceneEntity>);
+
+/// Every primitive and model the editor spawned. Other spawned entities,
+/// such as uploaded textures, have nothing to restore and are left out.
+pub async fn export(client: &BrpClient) -> Result<Vec<SceneEntity>> {
+    let params = json!({
+        "data": {
+            "components": [TRANSFORM],
+            "option": [PRIMITIVE, REMOTE_ASSET, CACHED_ASSET, NAME, GROUP]
+        },
+        "filter": {
+            "with": [SPAWNED]
+        }
+    });
+    let result = client.send_rpc("world.query", Some(params)).await?;
+    let rows = result
+        .as_array()
+        .ok_or_else(|| BrpError::InvalidResponse("Expected array from world.query".into()))?;
+
+    let mut entities: Vec<SceneEntity> = rows.iter().filter_map(parse_entity).collect();
+    entities.sort_by_key(|e| e.entity);
+    Ok(entities)
+}
+
+fn parse_entity(row: &Value) -> Option<SceneEntity> {
+    let components = row.get("components")?;
+    let material = components
+        .get(PRIMITIVE)
+        .and_then(|primitive| serde_json::from_value(primitive.clone()).ok())
+        .unwrap_or_default();
+    let object = if let Some(primitive) = components.get(PRIMITIVE) {
+        SceneObject::Primitive { primitive_type: primitive.get("primitive_type")?.as_str()?.to_string() }
+    } else if let Some(cached) = components.get(CACHED_ASSET) {
+        SceneObject::Model { path: cached.get("path")?.as_str()?.to_string() }
+    } else if let Some(upload) = components.get(REMOTE_ASSET) {
+        let filename = upload.get("filename")?.as_str()?;
+        let path = match upload.get("subdir").and_then(Value::as_str).filter(|s| !s.is_empty()) {
+            Some(subdir) => format!("{}/{}", subdir, filename),
+            None => filename.to_string(),
+        };
+        SceneObject::Model { path }
+    } else {
+        return None;
+    };
+
+    Some(SceneEntity {
+        entity: EntityId(row.get("entity")?.as_u64()?),
+        name: components.get(NAME).and_then(Value::as_str).map(str::to_string),
+        group: components.get(GROUP).and_then(Value::as_str).map(str::to_string),
+        object,
+        transform: serde_json::from_value(components.get(TRANSFORM)?.clone()).ok()?,
+        material,
+    })
+}
+
+pub(crate) fn spawn_params(entity: &SceneEntity) -> Value {
+    let mut components = json!({
+        SPAWNED: {},
+        TRANSFORM: entity.transform,
+    });
+    match &entity.object {
+        SceneObject::Primitive { primitive_type } => {
+            components[PRIMITIVE] = primitive_component(primitive_type, &entity.material);
+        }
+        SceneObject::Model { path } => {
+            components[CACHED_ASSET] = json!({ "path": path });
+        }
+    }
+    label(&mut components, entity.name.as_deref(), entity.group.as_deref());
+    json!({ "components": components })
+}
+
+/// Replace everything the editor spawned with `entities`; models are loaded
+/// from `_remote_cache`. Returns how many entities were spawned.
+pub async fn restore(client: &BrpClient, entities: &[SceneEntity]) -> Result<usize> {
+    super::clear::clear(client, crate::types::ClearTarget::All).await?;
+    for entity in entities {
+        client.send_rpc("world.spawn_entity", Some(spawn_params(entity))).await?;
+    }
+    Ok(entities.len())
+}
+
+fn same_transform(a: &SceneTransform, b: &SceneTransform) -> bool {
+    let close = |x: &[f32], y: &[f32]| x.iter().zip(y).all(|(x, y)| (x - y).abs() <= EPSILON);
+    close(&a.translation, &b.translation) && close(&a.rotation, &b.rotation) && close(&a.scale, &b.scale)
+}
+
+fn distance(a: &SceneTransform, b: &SceneTransform) -> f32 {
+    a.translation.iter().zip(&b.translation).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+/// What changed in the live scene since `snapshot` was exported, e.g. to
+/// show what happened since the last save.
+pub async fn diff(client: &BrpClient, snapshot: &[SceneEntity]) -> Result<SceneDiff> {
+    let live = export(client).await?;
+    Ok(compare(snapshot, &live))
+}
+
+/// What changed from `before` to `after`. Entity ids change on restore, so
+/// entities are matched by what they show and their name: unchanged ones
+/// first, then each remaining one in `before` with the nearest in `after`.
+pub fn compare(before: &[SceneEntity], after: &[SceneEntity]) -> SceneDiff {
+    let mut groups: HashMap<(&SceneObject, &Option<String>), Group> = HashMap::new();
+    let mut keys = Vec::new();
+    for (side, entities) in [(0, before), (1, after)] {
+        for entity in entities {
+            let key = (&entity.object, &entity.name);
+            let group = groups.entry(key).or_insert_with(|| {
+                keys.push(key);
+                Default::default()
+            });
+            let list = if side == 0 { &mut group.0 } else { &mut group.1 };
+            list.push(entity);
+        }
+    }
+
+    let mut result = SceneDiff::default();
+    for key in keys {
+        let (mut old, mut new) = groups.remove(&key).unwrap_or_default();
+        old.retain(|o| match new.iter().position(|n| changes(o, n).is_empty()) {
+            Some(i) => {
+                new.remove(i);
+                result.unchanged += 1;
+                false
+            }
+            None => true,
+        });
+        for o in old {
+            let nearest = new
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| distance(&o.transform, &a.transform).total_cmp(&distance(&o.transform, &b.transform)))
+                .map(|(i, _)| i);
+            match nearest {
+                Some(i) => {
+                    let n = new.remove(i);
+                    let changes = changes(o, n);
+                    result.modified.push(ModifiedEntity { before: o.clone(), after: n.clone(), changes });
+                }
+                None => result.removed.push(o.clone()),
+            }
+        }
+        result.added.extend(new.into_iter().cloned());
+    }
+    result
+}
+
+/// The components that differ between two entities showing the same thing.
+fn changes(before: &SceneEntity, after: &SceneEntity) -> Vec<ComponentDiff> {
+    let mut changes = Vec::new();
+    if !same_transform(&before.transform, &after.transform) {
+        changes.push(ComponentDiff {
+            component: TRANSFORM.to_string(),
+            before: Some(json!(before.transform)),
+            after: Some(json!(after.transform)),
+        });
+    }
+    if let SceneObject::Primitive { primitive_type } = &after.object {
+        if before.material != after.material {
+            changes.push(ComponentDiff {
+                component: PRIMITIVE.to_string(),
+                before: Some(primitive_component(primitive_type, &before.material)),
+                after: Some(primitive_component(primitive_type, &after.material)),
+            });
+        }
+    }
+    if before.group != after.group {
+        changes.push(ComponentDiff {
+            component: GROUP.to_string(),
+            before: before.group.as_ref().map(|g| json!(g)),
+            after: after.group.as_ref().map(|g| json!(g)),
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn primitive(entity: u64, kind: &str, translation: [f32; 3]) -> SceneEntity {
+        SceneEntity {
+            entity: EntityId(entity),
+            name: None,
+            group: None,
+            object: SceneObject::Primitive { primitive_type: kind.to_string() },
+            transform: SceneTransform { translation, ..Default::default() },
+            material: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_parse_entity_kinds() {
+        let transform = json!({ "translation": [1.0, 2.0, 3.0], "rotation": [0.0, 0.0, 0.0, 1.0], "scale": [1.0, 1.0, 1.0] });
+        let cube = json!({ "entity": 7, "components": { PRIMITIVE: { "primitive_type": "cube" }, TRANSFORM: transform, NAME: "Box" } });
+        let upload = json!({
+            "entity": 8,
+            "components": { REMOTE_ASSET: { "filename": "tree.glb", "data_base64": "", "subdir": "props" }, TRANSFORM: transform }
+        });
+        let cached = json!({ "entity": 9, "components": { CACHED_ASSET: { "path": "road.glb" }, TRANSFORM: transform } });
+        let texture = json!({ "entity": 10, "components": { TRANSFORM: transform } });
+
+        let cube = parse_entity(&cube).unwrap();
+        assert_eq!(cube.object, SceneObject::Primitive { primitive_type: "cube".into() });
+        assert_eq!(cube.name.as_deref(), Some("Box"));
+        assert_eq!(cube.transform.translation, [1.0, 2.0, 3.0]);
+        assert_eq!(parse_entity(&upload).unwrap().object, SceneObject::Model { path: "props/tree.glb".into() });
+        assert_eq!(parse_entity(&cached).unwrap().object, SceneObject::Model { path: "road.glb".into() });
+        assert!(parse_entity(&texture).is_none());
+    }
+
+    #[test]
+    fn test_spawn_params_round_trip() {
+        let mut tree = primitive(3, "cone", [0.0, 1.0, 0.0]);
+        tree.name = Some("Tree".into());
+        tree.group = Some("forest".into());
+        tree.material.color = Some([0.1, 0.5, 0.1, 1.0]);
+        let params = spawn_params(&tree);
+        let components = &params["components"];
+        assert!(components.get(SPAWNED).is_some());
+        assert_eq!(components[NAME], "Tree");
+        assert_eq!(components[GROUP], "forest");
+
+        let row = json!({ "entity": 3, "components": components });
+        assert_eq!(parse_entity(&row).unwrap(), tree);
+
+        let model = SceneEntity { object: SceneObject::Model { path: "a/b.glb".into() }, ..tree };
+        assert_eq!(spawn_params(&model)["components"][CACHED_ASSET]["path"], "a/b.glb");
+    }
+
+    #[test]
+    fn test_diff_matches_by_content_not_id() {
+        let before = vec![
+            primitive(1, "cube", [0.0, 0.0, 0.0]),
+            primitive(2, "cube", [5.0, 0.0, 0.0]),
+            primitive(3, "sphere", [0.0, 2.0, 0.0]),
+        ];
+        // Restored under new ids, one cube moved, the sphere replaced by a cone
+        let after = vec![
+            primitive(11, "cube", [5.0, 0.0, 0.0]),
+            primitive(12, "cube", [0.5, 0.0, 0.0]),
+            primitive(13, "cone", [0.0, 2.0, 0.0]),
+        ];
+
+        let diff = compare(&before, &after);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].before.entity, EntityId(1));
+        assert_eq!(diff.modified[0].after.entity, EntityId(12));
+        assert!(diff.modified[0].moved());
+        assert_eq!(diff.removed, vec![before[2].clone()]);
+        assert_eq!(diff.added, vec![after[2].clone()]);
+        assert_eq!(diff.summary(), "1 added, 1 removed, 1 modified, 1 unchanged");
+    }
+
+    #[test]
+    fn test_diff_pairs_nearest_and_ignores_rounding() {
+        let before = vec![primitive(1, "cube", [0.0, 0.0, 0.0]), primitive(2, "cube", [10.0, 0.0, 0.0])];
+        let after = vec![primitive(3, "cube", [10.00001, 0.0, 0.0]), primitive(4, "cube", [1.0, 0.0, 0.0])];
+
+        let diff = compare(&before, &after);
+        assert_eq!(diff.unchanged, 1);
+        assert_eq!(diff.modified.len(), 1);
+        assert_eq!(diff.modified[0].after.transform.translation, [1.0, 0.0, 0.0]);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert!(compare(&after, &after).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_each_changed_component() {
+        let before = vec![primitive(1, "cube", [0.0; 3])];
+        let mut recolored = primitive(2, "cube", [0.0; 3]);
+        recolored.material.color = Some([1.0, 0.0, 0.0, 1.0]);
+        recolored.group = Some("level1".into());
+
+        let diff = compare(&before, &[recolored]);
+        assert_eq!(diff.modified.len(), 1);
+        let modified = &diff.modified[0];
+        assert!(!modified.moved());
+        let components: Vec<&str> = modified.changes.iter().map(|c| c.component.as_str()).collect();
+        assert_eq!(components, vec![PRIMITIVE, GROUP]);
+        assert_eq!(modified.changes[0].after, Some(json!({ "primitive_type": "cube", "color": [1.0, 0.0, 0.0, 1.0] })));
+        assert_eq!((modified.changes[1].before.clone(), modified.changes[1].after.clone()), (None, Some(json!("level1"))));
+    }
+
+    #[tokio::test]
+    async fn test_diff_compares_with_the_live_scene() {
+        let server = crate::testing::MockBrpServer::start();
+        let transform = json!({ "translation": [0.0, 1.0, 0.0], "rotation": [0.0, 0.0, 0.0, 1.0], "scale": [1.0, 1.0, 1.0] });
+        server.reply("world.query", json!([{ "entity": 5, "components": { PRIMITIVE: { "primitive_type": "cube" }, TRANSFORM: transform } }]));
+
+        let diff = diff(&server.client(), &[primitive(1, "cube", [0.0; 3])]).await.unwrap();
+        assert_eq!(diff.summary(), "0 added, 0 removed, 1 modified, 0 unchanged");
+        assert_eq!(diff.modified[0].changes[0].after.as_ref().unwrap()["translation"], json!([0.0, 1.0, 0.0]));
+    }
+}
@@ -0,0 +1,145 @@
+use super::despawn::DESPAWN_METHOD;
+use super::get::get_entity;
+use crate::{BrpClient, BrpError, Result};
+use crate::types::{EntityId, ScreenshotResponse};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+use std::time::{Duration, Instant};
+
+/// BRP method registered by `bevy_ai_remote`.
+pub const METHOD: &str = "axiom.screenshot";
+
+/// Component `bevy_ai_remote` answers with a capture of the main camera.
+pub const REQUEST_COMPONENT: &str = "bevy_ai_remote::AxiomScreenshotRequest";
+
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Fetch the latest capture of the game window. Each call also schedules a
+/// fresh capture, so `Ok(None)` (nothing captured yet) resolves on a later call.
+pub async fn screenshot(client: &BrpClient) -> Result<Option<ScreenshotResponse>> {
+    let result = client.send_rpc(METHOD, None).await?;
+    parse_screenshot(&result)
+}
+
+/// Render the main camera now and return it as a PNG no larger than `width`
+/// × `height`, keeping the aspect ratio; 0 keeps the window's size.
+pub async fn capture(client: &BrpClient, width: u32, height: u32) -> Result<ScreenshotResponse> {
+    let request = json!({ "components": { REQUEST_COMPONENT: { "width": width, "height": height } } });
+    let spawned = client.send_rpc("world.spawn_entity", Some(request)).await?;
+    let entity = spawned
+        .get("entity")
+        .and_then(Value::as_u64)
+        .map(EntityId)
+        .ok_or_else(|| BrpError::InvalidResponse("No entity id in spawn response".into()))?;
+
+    let result = poll_capture(client, entity).await;
+    // Best effort; a request left behind is harmless
+    let _ = client.send_rpc(DESPAWN_METHOD, Some(json!({ "entity": entity }))).await;
+    result
+}
+
+async fn poll_capture(client: &BrpClient, entity: EntityId) -> Result<ScreenshotResponse> {
+    let started = Instant::now();
+    loop {
+        let detail = get_entity(client, entity, vec![REQUEST_COMPONENT.to_string()]).await?;
+        let request = detail.components.get(REQUEST_COMPONENT).ok_or_else(|| {
+            let reason = detail.errors.get(REQUEST_COMPONENT).map_or_else(String::new, |e| format!(": {}", e));
+            BrpError::InvalidResponse(format!("Missing {}{}", REQUEST_COMPONENT, reason))
+        })?;
+        if let Some(shot) = parse_request(request)? {
+            return Ok(shot);
+        }
+        if started.elapsed() >= CAPTURE_TIMEOUT {
+            return Err(BrpError::Timeout(CAPTURE_TIMEOUT));
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// The capture in an `AxiomScreenshotRequest`, `None` until it's taken.
+fn parse_request(request: &Value) -> Result<Option<ScreenshotResponse>> {
+    if let Some(error) = request.get("error").and_then(Value::as_str) {
+        return Err(BrpError::InvalidResponse(format!("Screenshot failed: {}", error)));
+    }
+    if request.get("png_base64").and_then(Value::as_str).is_none() {
+        return Ok(None);
+    }
+    parse_screenshot(request)
+}
+
+fn parse_screenshot(result: &Value) -> Result<Option<ScreenshotResponse>> {
+    if result.get("pending").and_then(Value::as_bool).unwrap_or(false) {
+        return Ok(None);
+    }
+
+    let data = result
+        .get("png_base64")
+        .and_then(Value::as_str)
+        .ok_or_else(|| crate::BrpError::InvalidResponse("Missing 'png_base64' in screenshot response".into()))?;
+    let png = BASE64
+        .decode(data)
+        .map_err(|e| crate::BrpError::InvalidResponse(format!("Invalid screenshot data: {}", e)))?;
+
+    let dimension = |key: &str| result.get(key).and_then(Value::as_u64).unwrap_or(0) as u32;
+    Ok(Some(ScreenshotResponse {
+        png,
+        width: dimension("width"),
+        height: dimension("height"),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_pending_screenshot() {
+        let result = json!({ "pending": true });
+        assert!(parse_screenshot(&result).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_screenshot_payload() {
+        let result = json!({
+            "png_base64": "dGVzdCBkYXRh",
+            "width": 1280,
+            "height": 720
+        });
+        let shot = parse_screenshot(&result).unwrap().unwrap();
+        assert_eq!(shot.png, b"test data");
+        assert_eq!((shot.width, shot.height), (1280, 720));
+    }
+
+    #[test]
+    fn test_parse_screenshot_missing_data() {
+        let result = json!({ "width": 1280 });
+        assert!(matches!(
+            parse_screenshot(&result),
+            Err(crate::BrpError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_capture_request() {
+        let waiting = json!({ "width": 640, "height": 0, "png_base64": null, "error": null });
+        assert!(parse_request(&waiting).unwrap().is_none());
+
+        let taken = json!({ "width": 640, "height": 360, "png_base64": "dGVzdCBkYXRh", "error": null });
+        let shot = parse_request(&taken).unwrap().unwrap();
+        assert_eq!((shot.width, shot.height), (640, 360));
+
+        let failed = json!({ "width": 0, "height": 0, "png_base64": null, "error": "no window" });
+        assert!(matches!(
+            parse_request(&failed),
+            Err(crate::BrpError::InvalidResponse(msg)) if msg.contains("no window")
+        ));
+    }
+
+    #[test]
+    fn test_parse_screenshot_invalid_base64() {
+        let result = json!({ "png_base64": "not base64!" });
+        assert!(parse_screenshot(&result).is_err());
+    }
+}
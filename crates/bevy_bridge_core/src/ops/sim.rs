@@ -0,0 +1,79 @@
+//! Pausing, stepping and slowing the game, to inspect or edit it while it
+//! stands still. Paused, the game keeps rendering frames but its virtual time,
+//! and so anything driven by it, stands still.
+
+use super::resources::{get_resource, insert_resource};
+use crate::types::SimState;
+use crate::{BrpClient, Result};
+
+/// Resource registered by `bevy_ai_remote`.
+pub const RESOURCE: &str = "bevy_ai_remote::AxiomSimControl";
+
+pub async fn pause(client: &BrpClient) -> Result<()> {
+    set(client, |sim| {
+        sim.paused = true;
+        sim.step_frames = 0;
+    })
+    .await
+}
+
+/// Resume, cancelling any step under way.
+pub async fn resume(client: &BrpClient) -> Result<()> {
+    set(client, |sim| {
+        sim.paused = false;
+        sim.step_frames = 0;
+    })
+    .await
+}
+
+/// Run `n_frames` frames, then pause.
+pub async fn step(client: &BrpClient, n_frames: u32) -> Result<()> {
+    set(client, |sim| sim.step_frames = n_frames).await
+}
+
+/// Run virtual time at `scale` times real time; the game ignores a negative
+/// or non-finite scale.
+pub async fn set_time_scale(client: &BrpClient, scale: f32) -> Result<()> {
+    set(client, |sim| sim.time_scale = scale).await
+}
+
+pub async fn state(client: &BrpClient) -> Result<SimState> {
+    Ok(serde_json::from_value(get_resource(client, RESOURCE).await?)?)
+}
+
+/// Write the whole resource at once. The game handles one request per frame,
+/// so fields written separately could land either side of a step ending.
+async fn set(client: &BrpClient, update: impl FnOnce(&mut SimState)) -> Result<()> {
+    let mut sim = state(client).await?;
+    update(&mut sim);
+    insert_resource(client, RESOURCE, serde_json::to_value(sim)?).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::resources::{GET_METHOD, INSERT_METHOD};
+    use crate::testing::MockBrpServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_resume_writes_the_whole_resource() {
+        let server = MockBrpServer::start();
+        server
+            .reply(GET_METHOD, json!({ "value": { "paused": true, "step_frames": 1, "time_scale": 0.5 } }))
+            .reply(INSERT_METHOD, json!(null));
+        resume(&server.client()).await.unwrap();
+        assert_eq!(
+            server.calls_to(INSERT_METHOD),
+            vec![Some(json!({ "resource": RESOURCE, "value": { "paused": false, "step_frames": 0, "time_scale": 0.5 } }))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_state_reads_the_resource() {
+        let server = MockBrpServer::start();
+        server.reply("world.get_resources", json!({ "value": { "paused": true, "step_frames": 2, "time_scale": 0.5 } }));
+        let state = state(&server.client()).await.unwrap();
+        assert_eq!(state, SimState { paused: true, step_frames: 2, time_scale: 0.5 });
+    }
+}
@@ -1,35 +1,73 @@
+use super::clear::GROUP;
+use super::find::NAME;
 use crate::{BrpClient, Result};
-use crate::types::SpawnResponse;
-use serde_json::json;
-
-pub async fn spawn(
-    client: &BrpClient,
-    primitive_type: &str,
-    position: [f32; 3],
-    rotation: [f32; 4],
-    scale: [f32; 3],
-) -> Result<SpawnResponse> {
-    let params = json!({
-        "components": {
-            "bevy_ai_remote::AxiomPrimitive": {
-                "primitive_type": primitive_type
-            },
-            "bevy_ai_remote::AxiomSpawned": {},
-            "bevy_transform::components::transform::Transform": {
-                "translation": position,
-                "rotation": rotation,
-                "scale": scale
-            }
+use crate::types::{PrimitiveMaterial, SpawnRequest, SpawnResponse};
+use serde_json::{json, Value};
+
+/// Spawns sent per batch. The game answers one per frame, so this keeps a
+/// batch well within the request timeout.
+const SPAWN_BATCH: usize = 256;
+
+/// Spawn a primitive, named `request.name` if given so
+/// [`super::find::by_name`] can find it again, and in `request.group` if given
+/// so [`ClearTarget::Group`](crate::types::ClearTarget::Group) can clear it.
+pub async fn spawn(client: &BrpClient, request: &SpawnRequest) -> Result<SpawnResponse> {
+    let result = client.send_rpc("world.spawn_entity", Some(spawn_params(request))).await?;
+    spawn_response(result)
+}
+
+/// Spawn every primitive in `requests` with batched requests rather than one
+/// each. Results are in the order given; the outer error is for a batch not
+/// getting through.
+pub async fn spawn_batch(client: &BrpClient, requests: Vec<SpawnRequest>) -> Result<Vec<Result<SpawnResponse>>> {
+    let spawns: Vec<_> = requests.iter().map(|r| ("world.spawn_entity", Some(spawn_params(r)))).collect();
+    let mut responses = Vec::with_capacity(spawns.len());
+    for chunk in spawns.chunks(SPAWN_BATCH) {
+        let results = client.send_batch(chunk.to_vec()).await?;
+        responses.extend(results.into_iter().map(|result| result.and_then(spawn_response)));
+    }
+    Ok(responses)
+}
+
+fn spawn_params(request: &SpawnRequest) -> Value {
+    let mut components = json!({
+        "bevy_ai_remote::AxiomPrimitive": primitive_component(&request.primitive, &request.material),
+        "bevy_ai_remote::AxiomSpawned": {},
+        "bevy_transform::components::transform::Transform": {
+            "translation": request.position,
+            "rotation": request.rotation,
+            "scale": request.scale
         }
     });
-    
-    let result = client.send_rpc("world.spawn_entity", Some(params)).await?;
-    
+    label(&mut components, request.name.as_deref(), request.group.as_deref());
+    json!({ "components": components })
+}
+
+/// Add the `Name` and `AxiomGroup` components that are given.
+pub(crate) fn label(components: &mut Value, name: Option<&str>, group: Option<&str>) {
+    if let Some(name) = name {
+        components[NAME] = json!(name);
+    }
+    if let Some(group) = group {
+        components[GROUP] = json!(group);
+    }
+}
+
+/// The `AxiomPrimitive` component; unset material fields are left out for
+/// the game to default.
+pub(crate) fn primitive_component(primitive_type: &str, material: &PrimitiveMaterial) -> Value {
+    let mut component = serde_json::to_value(material).unwrap_or_else(|_| json!({}));
+    component["primitive_type"] = json!(primitive_type);
+    component
+}
+
+fn spawn_response(result: Value) -> Result<SpawnResponse> {
     let entity_id = result.get("entity")
+        .cloned()
         .ok_or_else(|| crate::BrpError::InvalidResponse(
             "Missing 'entity' in spawn response".into()
-        ))?
-        .to_string();
+        ))?;
+    let entity_id = serde_json::from_value(entity_id)?;
     
     Ok(SpawnResponse { entity_id })
 }
@@ -115,4 +153,77 @@ mod tests {
         assert!(components.as_object().unwrap().contains_key("bevy_ai_remote::AxiomSpawned"));
         assert!(components.as_object().unwrap().contains_key("bevy_transform::components::transform::Transform"));
     }
+
+    #[tokio::test]
+    async fn test_spawn_batch_keeps_order_and_failures() {
+        let server = crate::testing::MockBrpServer::start();
+        server
+            .reply("world.spawn_entity", json!({ "entity": 10 }))
+            .reply_error("world.spawn_entity", crate::error::codes::COMPONENT_ERROR, "Unknown primitive")
+            .reply("world.spawn_entity", json!({ "entity": 12 }));
+
+        let requests = (0..3)
+            .map(|i| SpawnRequest {
+                primitive: "cube".into(),
+                position: [i as f32, 0.0, 0.0],
+                rotation: [0.0, 0.0, 0.0, 1.0],
+                scale: [1.0, 1.0, 1.0],
+                material: PrimitiveMaterial::default(),
+                name: (i == 0).then(|| "first".to_string()),
+                group: Some("row".into()),
+            })
+            .collect();
+        let results = spawn_batch(&server.client(), requests).await.unwrap();
+        assert_eq!(results[0].as_ref().unwrap().entity_id.bits(), 10);
+        assert!(matches!(results[1], Err(crate::BrpError::ComponentNotRegistered { .. })));
+        assert_eq!(results[2].as_ref().unwrap().entity_id.bits(), 12);
+
+        let spawns = server.calls_to("world.spawn_entity");
+        assert_eq!(spawns.len(), 3);
+        let transform = &spawns[2].as_ref().unwrap()["components"]["bevy_transform::components::transform::Transform"];
+        assert_eq!(transform["translation"], json!([2.0, 0.0, 0.0]));
+        assert_eq!(spawns[0].as_ref().unwrap()["components"][NAME], "first");
+        assert!(spawns[2].as_ref().unwrap()["components"].get(NAME).is_none());
+        assert_eq!(spawns[2].as_ref().unwrap()["components"][GROUP], "row");
+    }
+
+    fn cube(material: PrimitiveMaterial) -> SpawnRequest {
+        SpawnRequest {
+            primitive: "cube".into(),
+            position: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+            material,
+            name: None,
+            group: None,
+        }
+    }
+
+    #[test]
+    fn test_material_is_forwarded_in_the_primitive() {
+        let material = PrimitiveMaterial { color: Some([1.0, 0.0, 0.0, 1.0]), unlit: true, ..Default::default() };
+        let params = spawn_params(&SpawnRequest { name: Some("goal".into()), ..cube(material) });
+        assert_eq!(
+            params["components"]["bevy_ai_remote::AxiomPrimitive"],
+            json!({ "primitive_type": "cube", "color": [1.0, 0.0, 0.0, 1.0], "unlit": true })
+        );
+        assert_eq!(params["components"][NAME], "goal");
+
+        let plain = spawn_params(&cube(PrimitiveMaterial::default()));
+        assert_eq!(plain["components"]["bevy_ai_remote::AxiomPrimitive"], json!({ "primitive_type": "cube" }));
+        assert!(plain["components"].get(NAME).is_none() && plain["components"].get(GROUP).is_none());
+    }
+
+    #[test]
+    fn test_material_color_takes_rgb_or_rgba() {
+        let rgb: PrimitiveMaterial = serde_json::from_value(json!({ "color": [0.2, 0.4, 0.6] })).unwrap();
+        assert_eq!(rgb.color, Some([0.2, 0.4, 0.6, 1.0]));
+        // As the game reports it, with every field present
+        let reported: PrimitiveMaterial = serde_json::from_value(json!({
+            "primitive_type": "cube", "color": null, "metallic": 0.9, "roughness": null, "emissive": null, "unlit": false
+        }))
+        .unwrap();
+        assert_eq!(reported, PrimitiveMaterial { metallic: Some(0.9), ..Default::default() });
+        assert!(serde_json::from_value::<PrimitiveMaterial>(json!({ "color": [1.0, 0.0] })).is_err());
+    }
 }
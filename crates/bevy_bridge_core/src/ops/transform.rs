@@ -0,0 +1,59 @@
+use crate::types::EntityId;
+use crate::{BrpClient, Result};
+use serde_json::{json, Value};
+
+pub const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+pub const MUTATE_METHOD: &str = "world.mutate_components";
+
+/// Set the parts of the entity's `Transform` that are given; rotation is a
+/// quaternion `[x, y, z, w]`. With all three the component is inserted whole,
+/// otherwise only the given fields change, in one batch.
+pub async fn set_transform(
+    client: &BrpClient,
+    entity_id: EntityId,
+    translation: Option<[f32; 3]>,
+    rotation: Option<[f32; 4]>,
+    scale: Option<[f32; 3]>,
+) -> Result<()> {
+    if let (Some(translation), Some(rotation), Some(scale)) = (translation, rotation, scale) {
+        let value = json!({ "translation": translation, "rotation": rotation, "scale": scale });
+        super::components::insert_component(client, entity_id, TRANSFORM, value).await?;
+        return Ok(());
+    }
+
+    let fields = [
+        ("translation", translation.map(|t| json!(t))),
+        ("rotation", rotation.map(|r| json!(r))),
+        ("scale", scale.map(|s| json!(s))),
+    ];
+    let batch: Vec<_> = fields
+        .into_iter()
+        .filter_map(|(field, value)| Some((MUTATE_METHOD, Some(mutate_params(entity_id, field, value?)))))
+        .collect();
+    for result in client.send_batch(batch).await? {
+        result?;
+    }
+    Ok(())
+}
+
+fn mutate_params(entity_id: EntityId, field: &str, value: Value) -> Value {
+    json!({ "entity": entity_id, "component": TRANSFORM, "path": format!(".{}", field), "value": value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutate_params_target_one_field() {
+        assert_eq!(
+            mutate_params(EntityId(42), "translation", json!([1.0, 2.0, 3.0])),
+            json!({
+                "entity": 42,
+                "component": "bevy_transform::components::transform::Transform",
+                "path": ".translation",
+                "value": [1.0, 2.0, 3.0]
+            })
+        );
+    }
+}
@@ -1,49 +1,172 @@
-use crate::{BrpClient, Result};
-use crate::types::UploadResponse;
+use super::spawn::label;
+use crate::{BrpClient, BrpError, Result};
+use crate::types::{UploadOptions, UploadResponse};
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
-use serde_json::json;
+use ring::digest::{digest, SHA256};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-pub async fn upload(
+/// BRP methods registered by `bevy_ai_remote`.
+pub const UPLOAD_MANIFEST_METHOD: &str = "axiom.upload_manifest";
+pub const UPLOAD_CHUNK_METHOD: &str = "axiom.upload_chunk";
+pub const UPLOAD_COMMIT_METHOD: &str = "axiom.upload_commit";
+
+/// Files larger than this go in chunks of this size, each its own request.
+pub const CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Least time allowed for a request carrying file data, or for the game to
+/// hash a large cached file.
+pub const TRANSFER_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Write `bytes` to the game's `_remote_cache` and spawn an entity there,
+/// placed, named and grouped as `options` says. Models get a scene; other
+/// files are only written. Nothing is sent when the cache already holds the
+/// same bytes.
+pub async fn upload(client: &BrpClient, filename: &str, bytes: &[u8], options: &UploadOptions) -> Result<UploadResponse> {
+    let sha256 = sha256(bytes);
+    let manifest = json!({ "size": bytes.len(), "sha256": sha256, "filename": filename, "subdir": options.subdir });
+    let known = match client.send_rpc_with_timeout(UPLOAD_MANIFEST_METHOD, Some(manifest), transfer_timeout(client)).await {
+        Ok(result) => result.get("path").and_then(Value::as_str).map(str::to_string),
+        // Games built before manifests always take the bytes
+        Err(BrpError::MethodNotFound { .. }) => None,
+        Err(e) => return Err(e),
+    };
+    if let Some(path) = known {
+        tracing::debug!("{} is already cached as {}", filename, path);
+        let mut response = spawn_stored(client, filename, &path, options).await?;
+        response.cached = true;
+        return Ok(response);
+    }
+
+    if bytes.len() > CHUNK_BYTES {
+        return upload_in_chunks(client, filename, bytes, options, CHUNK_BYTES).await;
+    }
+
+    let b64_data = BASE64.encode(bytes);
+    
+    let mut components = json!({
+        "bevy_ai_remote::AxiomRemoteAsset": {
+            "filename": filename,
+            "data_base64": b64_data,
+            "subdir": options.subdir
+        },
+        "bevy_ai_remote::AxiomSpawned": {},
+        "bevy_transform::components::transform::Transform": {
+            "translation": options.translation,
+            "rotation": options.rotation,
+            "scale": [1.0, 1.0, 1.0]
+        }
+    });
+    label(&mut components, options.name.as_deref(), options.group.as_deref());
+    
+    let params = json!({ "components": components });
+    let result = client.send_rpc_with_timeout("world.spawn_entity", Some(params), transfer_timeout(client)).await?;
+    entity_of(&result)
+}
+
+/// [`upload`] as `chunk_bytes` pieces sent one after another, then committed
+/// into the cache, so no request carries the whole file. The model is spawned
+/// from the cache like [`super::assets::spawn_cached`].
+pub async fn upload_in_chunks(
     client: &BrpClient,
     filename: &str,
     bytes: &[u8],
-    subdir: Option<&str>,
-    translation: [f32; 3],
-    rotation: [f32; 4],
+    options: &UploadOptions,
+    chunk_bytes: usize,
 ) -> Result<UploadResponse> {
-    let b64_data = BASE64.encode(bytes);
-    
+    let upload = upload_id();
+    // An empty file is still one (empty) chunk
+    let chunks = bytes.chunks(chunk_bytes.max(1));
+    let chunks: Vec<&[u8]> = if bytes.is_empty() { vec![bytes] } else { chunks.collect() };
+    let mut offset = 0;
+    for (i, chunk) in chunks.iter().enumerate() {
+        let params = json!({ "upload": upload, "offset": offset, "data_base64": BASE64.encode(chunk) });
+        client.send_rpc_with_timeout(UPLOAD_CHUNK_METHOD, Some(params), transfer_timeout(client)).await?;
+        offset += chunk.len();
+        tracing::debug!("Uploaded chunk {}/{} of {}", i + 1, chunks.len(), filename);
+    }
+
     let params = json!({
-        "components": {
-            "bevy_ai_remote::AxiomRemoteAsset": {
-                "filename": filename,
-                "data_base64": b64_data,
-                "subdir": subdir
-            },
-            "bevy_ai_remote::AxiomSpawned": {},
-            "bevy_transform::components::transform::Transform": {
-                "translation": translation,
-                "rotation": rotation,
-                "scale": [1.0, 1.0, 1.0]
-            }
+        "upload": upload,
+        "size": bytes.len(),
+        "sha256": sha256(bytes),
+        "filename": filename,
+        "subdir": options.subdir
+    });
+    let result = client.send_rpc_with_timeout(UPLOAD_COMMIT_METHOD, Some(params), transfer_timeout(client)).await?;
+    let path = result
+        .get("path")
+        .and_then(Value::as_str)
+        .ok_or_else(|| BrpError::InvalidResponse("Missing 'path' in upload commit response".into()))?;
+    spawn_stored(client, filename, path, options).await
+}
+
+/// Spawn an upload already written to `path` in the cache.
+async fn spawn_stored(client: &BrpClient, filename: &str, path: &str, options: &UploadOptions) -> Result<UploadResponse> {
+    let mut components = json!({
+        "bevy_ai_remote::AxiomSpawned": {},
+        "bevy_transform::components::transform::Transform": {
+            "translation": options.translation,
+            "rotation": options.rotation,
+            "scale": [1.0, 1.0, 1.0]
         }
     });
-    
-    let result = client.send_rpc("world.spawn_entity", Some(params)).await?;
-    
+    if is_model(filename) {
+        components["bevy_ai_remote::AxiomCachedAsset"] = json!({ "path": path });
+    }
+    label(&mut components, options.name.as_deref(), options.group.as_deref());
+    let result = client.send_rpc("world.spawn_entity", Some(json!({ "components": components }))).await?;
+    entity_of(&result)
+}
+
+fn entity_of(result: &Value) -> Result<UploadResponse> {
     let entity_id = result.get("entity")
-        .ok_or_else(|| crate::BrpError::InvalidResponse(
+        .cloned()
+        .ok_or_else(|| BrpError::InvalidResponse(
             "Missing 'entity' in spawn response".into()
-        ))?
-        .to_string();
+        ))?;
+    let entity_id = serde_json::from_value(entity_id)?;
     
-    Ok(UploadResponse { entity_id })
+    Ok(UploadResponse { entity_id, cached: false })
+}
+
+/// Lowercase hex, as the game compares it.
+fn sha256(bytes: &[u8]) -> String {
+    digest(&SHA256, bytes).as_ref().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn transfer_timeout(client: &BrpClient) -> Duration {
+    client.config().timeout.max(TRANSFER_TIMEOUT)
+}
+
+fn is_model(filename: &str) -> bool {
+    filename.ends_with(".glb") || filename.ends_with(".gltf")
+}
+
+/// Unique among this process's uploads and unlikely to meet another's.
+fn upload_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+    format!("{}-{}-{}", std::process::id(), nanos, NEXT.fetch_add(1, Ordering::Relaxed))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_sha256_is_lowercase_hex() {
+        assert_eq!(sha256(b""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+    }
+
+    #[test]
+    fn test_upload_ids_are_unique_and_plain() {
+        let (a, b) = (upload_id(), upload_id());
+        assert_ne!(a, b);
+        assert!(a.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'), "{}", a);
+    }
+
     #[test]
     fn test_base64_encoding() {
         let bytes = b"test data";
@@ -0,0 +1,145 @@
+//! Push-based change notifications from BRP's `+watch` methods.
+//!
+//! `bevy_remote` answers a watch request with a `text/event-stream` that
+//! carries one JSON-RPC response for each tick in which something changed,
+//! and keeps it open until the client drops it. Watches are per entity; there
+//! is no `world.query+watch`, so [`entities`] merges one watch per entity.
+//!
+//! Only changes made after the watch opens are reported; read the current
+//! values first if they are needed too.
+
+use crate::types::{EntityChange, EntityId, WatchEvent};
+use crate::{BrpClient, Result};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use serde_json::json;
+use std::collections::VecDeque;
+
+pub const GET_COMPONENTS_WATCH_METHOD: &str = "world.get_components+watch";
+pub const LIST_COMPONENTS_WATCH_METHOD: &str = "world.list_components+watch";
+
+/// Changes as the game reports them. An error, such as the entity being
+/// despawned, is the last item.
+pub type ChangeStream = BoxStream<'static, Result<EntityChange>>;
+
+/// Values of `components` on `entity` whenever one is added or changed, and
+/// the names of those removed.
+pub async fn components(client: &BrpClient, entity: EntityId, components: Vec<String>) -> Result<ChangeStream> {
+    let params = json!({ "entity": entity, "components": components });
+    let response = client.open_stream(GET_COMPONENTS_WATCH_METHOD, Some(params)).await?;
+    Ok(changes(response, entity))
+}
+
+/// Names of the components added to or removed from `entity`.
+pub async fn component_list(client: &BrpClient, entity: EntityId) -> Result<ChangeStream> {
+    let params = json!({ "entity": entity });
+    let response = client.open_stream(LIST_COMPONENTS_WATCH_METHOD, Some(params)).await?;
+    Ok(changes(response, entity))
+}
+
+/// [`components`] for each of `entities`, merged in the order changes arrive.
+pub async fn entities(client: &BrpClient, entities: &[EntityId], components: Vec<String>) -> Result<ChangeStream> {
+    let mut streams = Vec::with_capacity(entities.len());
+    for &entity in entities {
+        streams.push(self::components(client, entity, components.clone()).await?);
+    }
+    Ok(stream::select_all(streams).boxed())
+}
+
+struct Events {
+    response: Option<reqwest::Response>,
+    buffer: Vec<u8>,
+    ready: VecDeque<Result<EntityChange>>,
+}
+
+fn changes(response: reqwest::Response, entity: EntityId) -> ChangeStream {
+    let events = Events { response: Some(response), buffer: Vec::new(), ready: VecDeque::new() };
+    stream::unfold(events, move |mut events| async move {
+        loop {
+            if let Some(change) = events.ready.pop_front() {
+                if change.is_err() {
+                    // Dropping the response closes the watch in the game
+                    events.response = None;
+                    events.ready.clear();
+                }
+                return Some((change, events));
+            }
+            let response = events.response.as_mut()?;
+            match response.chunk().await {
+                Ok(Some(chunk)) => {
+                    events.buffer.extend_from_slice(&chunk);
+                    events.ready.extend(take_events(&mut events.buffer, entity));
+                }
+                Ok(None) => return None,
+                Err(e) => {
+                    events.response = None;
+                    return Some((Err(e.into()), events));
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+/// Parse the complete events at the start of `buffer`, leaving a partial one.
+fn take_events(buffer: &mut Vec<u8>, entity: EntityId) -> Vec<Result<EntityChange>> {
+    let mut changes = Vec::new();
+    while let Some(end) = buffer.windows(2).position(|w| w == b"\n\n") {
+        let event: Vec<u8> = buffer.drain(..end + 2).collect();
+        let event = String::from_utf8_lossy(&event);
+        let data: Vec<&str> = event
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(str::trim_start)
+            .collect();
+        if !data.is_empty() {
+            changes.push(parse_change(&data.join("\n"), entity));
+        }
+    }
+    changes
+}
+
+fn parse_change(data: &str, entity: EntityId) -> Result<EntityChange> {
+    let event: WatchEvent = serde_json::from_str(data)?;
+    let mut change = event.into_result()?;
+    change.entity = entity;
+    Ok(change)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BrpError;
+
+    #[test]
+    fn test_take_events_parses_complete_events_only() {
+        let mut buffer = concat!(
+            r#"data: {"jsonrpc":"2.0","id":3,"result":{"components":{"bevy_transform::components::transform::Transform":{"scale":[1.0,1.0,1.0]}},"removed":[],"errors":{}}}"#,
+            "\n\n",
+            r#"data: {"jsonrpc":"2.0","id":3,"result":{"added":["game::Health"],"removed":["game::Shield"]}}"#,
+            "\n\n",
+            r#"data: {"jsonrpc":"2.0","id":3,"res"#,
+        )
+        .as_bytes()
+        .to_vec();
+
+        let changes: Vec<EntityChange> = take_events(&mut buffer, EntityId(42)).into_iter().map(|c| c.unwrap()).collect();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].entity, EntityId(42));
+        assert_eq!(changes[0].components["bevy_transform::components::transform::Transform"]["scale"], json!([1.0, 1.0, 1.0]));
+        assert_eq!(changes[1].added, vec!["game::Health"]);
+        assert_eq!(changes[1].removed, vec!["game::Shield"]);
+        assert_eq!(buffer, br#"data: {"jsonrpc":"2.0","id":3,"res"#);
+
+        buffer.extend_from_slice(b"ult\":{\"removed\":[\"game::Health\"]}}\n\n");
+        let changes = take_events(&mut buffer, EntityId(42));
+        assert_eq!(changes.len(), 1);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_take_events_reports_errors() {
+        let mut buffer = b"data: {\"jsonrpc\":\"2.0\",\"id\":3,\"error\":{\"code\":-23401,\"message\":\"Entity 42 not found\"}}\n\n".to_vec();
+        let changes = take_events(&mut buffer, EntityId(42));
+        assert!(matches!(&changes[..], [Err(BrpError::EntityNotFound { .. })]), "{:?}", changes);
+    }
+}
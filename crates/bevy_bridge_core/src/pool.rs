@@ -0,0 +1,140 @@
+//! Clients for several running games at once, such as the server and a
+//! client of a multiplayer game, each under a name.
+//!
+//! Ops take a single [`BrpClient`]; [`BrpClientPool::get`] picks the one for
+//! an instance, and [`BrpClientPool::broadcast`] runs an op on all of them
+//! concurrently.
+
+use crate::ops;
+use crate::types::{ClearResponse, ClearTarget};
+use crate::{BrpClient, BrpConfig, BrpError, Result};
+use futures_util::future::join_all;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+
+#[derive(Debug, Clone, Default)]
+pub struct BrpClientPool {
+    clients: BTreeMap<String, BrpClient>,
+}
+
+impl BrpClientPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A client for each `(name, endpoint)`, otherwise configured as `base`.
+    pub fn from_endpoints<N, E>(base: &BrpConfig, endpoints: impl IntoIterator<Item = (N, E)>) -> Self
+    where
+        N: Into<String>,
+        E: Into<String>,
+    {
+        let mut pool = Self::new();
+        for (name, endpoint) in endpoints {
+            let config = BrpConfig { endpoint: endpoint.into(), ..base.clone() };
+            pool.insert(name, BrpClient::new(config));
+        }
+        pool
+    }
+
+    /// `BRP_ENDPOINTS` as `name=url` pairs separated by commas, e.g.
+    /// `server=http://127.0.0.1:15721,client=http://127.0.0.1:15722`, with
+    /// the rest of [`BrpConfig::from_env`]. Without it, the one endpoint
+    /// from the environment as `default`.
+    pub fn from_env() -> Self {
+        let base = BrpConfig::from_env();
+        let endpoints: Vec<(String, String)> = std::env::var("BRP_ENDPOINTS")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, url)| (name.trim().to_string(), url.trim().to_string()))
+            .filter(|(name, url)| !name.is_empty() && !url.is_empty())
+            .collect();
+        if endpoints.is_empty() {
+            return Self::from_endpoints(&base, [("default", base.endpoint.clone())]);
+        }
+        Self::from_endpoints(&base, endpoints)
+    }
+
+    /// Add `client` as `name`, replacing any instance of that name.
+    pub fn insert(&mut self, name: impl Into<String>, client: BrpClient) -> Option<BrpClient> {
+        self.clients.insert(name.into(), client)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<BrpClient> {
+        self.clients.remove(name)
+    }
+
+    /// The client for instance `name`, or [`BrpError::UnknownInstance`].
+    pub fn get(&self, name: &str) -> Result<&BrpClient> {
+        self.clients.get(name).ok_or_else(|| BrpError::UnknownInstance(name.to_string()))
+    }
+
+    /// Instance names, sorted.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Send a request to instance `name`.
+    pub async fn send_rpc(&self, name: &str, method: &str, params: Option<Value>) -> Result<Value> {
+        self.get(name)?.send_rpc(method, params).await
+    }
+
+    /// Run `op` against every instance at once. Each gets a clone of its
+    /// client; results are by instance name, in name order.
+    pub async fn broadcast<F, Fut, T>(&self, op: F) -> Vec<(String, Result<T>)>
+    where
+        F: Fn(BrpClient) -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let runs = self.clients.values().map(|client| op(client.clone()));
+        self.clients.keys().cloned().zip(join_all(runs).await).collect()
+    }
+
+    /// [`ops::clear::clear`] on every instance.
+    pub async fn clear_all(&self, target: ClearTarget) -> Vec<(String, Result<ClearResponse>)> {
+        self.broadcast(|client| {
+            let target = target.clone();
+            async move { ops::clear::clear(&client, target).await }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::MockBrpServer;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_routes_by_name_and_broadcasts() {
+        let (server, client) = (MockBrpServer::start(), MockBrpServer::start());
+        server.reply("world.query", json!([{ "entity": 1 }, { "entity": 2 }]));
+        client.reply("world.query", json!([{ "entity": 3 }]));
+        server.reply("world.despawn_entity", json!(null));
+        client.reply("world.despawn_entity", json!(null));
+
+        let mut pool = BrpClientPool::new();
+        pool.insert("server", server.client());
+        pool.insert("client", client.client());
+        assert_eq!(pool.names().collect::<Vec<_>>(), vec!["client", "server"]);
+
+        let entities = pool.send_rpc("client", "world.query", None).await.unwrap();
+        assert_eq!(entities, json!([{ "entity": 3 }]));
+        assert!(matches!(pool.get("observer"), Err(BrpError::UnknownInstance(name)) if name == "observer"));
+
+        let cleared = pool.clear_all(ClearTarget::All).await;
+        let removed: Vec<_> = cleared.iter().map(|(name, r)| (name.as_str(), r.as_ref().unwrap().entities_removed)).collect();
+        assert_eq!(removed, vec![("client", 1), ("server", 2)]);
+        assert_eq!(server.calls_to("world.despawn_entity").len(), 2);
+    }
+}
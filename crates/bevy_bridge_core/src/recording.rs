@@ -0,0 +1,282 @@
+//! Recording and replaying BRP traffic.
+//!
+//! With [`Traffic::Record`] every request a [`BrpClient`](crate::BrpClient)
+//! sends is appended, with its result or error, to a JSONL file. With
+//! [`Traffic::Replay`] the client never contacts the game and answers from
+//! such a file instead, for debugging without a running game and for
+//! regression fixtures.
+//!
+//! Background requests (connection checks, preview screenshots) interleave
+//! with the rest depending on timing, so replay doesn't insist on the
+//! recorded order: each request takes the first unused exchange for the same
+//! method and params, or failing that the first for the same method.
+
+use crate::{BrpError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Where a client's requests go.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Traffic {
+    /// Straight to the game.
+    #[default]
+    Live,
+    /// To the game, appending every exchange to the file.
+    Record(PathBuf),
+    /// Answered from a recording; the game is never contacted.
+    Replay(PathBuf),
+}
+
+impl Traffic {
+    /// `BRP_REPLAY=<file>` if set, else `BRP_RECORD`: `1` or `true` records
+    /// to [`default_recording_path`], any other value is the file to record to.
+    pub fn from_env() -> Self {
+        if let Ok(path) = std::env::var("BRP_REPLAY") {
+            if !path.trim().is_empty() {
+                return Self::Replay(PathBuf::from(path));
+            }
+        }
+        match std::env::var("BRP_RECORD") {
+            Ok(value) if value == "1" || value == "true" => Self::Record(default_recording_path()),
+            Ok(value) if !value.trim().is_empty() && value != "0" && value != "false" => {
+                Self::Record(PathBuf::from(value))
+            }
+            _ => Self::Live,
+        }
+    }
+}
+
+/// `.axiom/recordings/brp-<timestamp>.jsonl`, the same for the whole run so
+/// every client records to one file.
+pub fn default_recording_path() -> PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| {
+        Path::new(".axiom")
+            .join("recordings")
+            .join(format!("brp-{}.jsonl", chrono::Local::now().format("%Y%m%d-%H%M%S")))
+    })
+    .clone()
+}
+
+/// One request and what came back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Exchange {
+    pub timestamp: String,
+    pub method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RecordedError>,
+    pub duration_ms: u64,
+}
+
+/// A failed request. `code` is set for JSON-RPC errors and missing for
+/// transport failures (connection refused, timeouts, bad responses).
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RecordedError {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<i32>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl Exchange {
+    pub fn new(method: &str, params: Option<Value>, result: &Result<Value>, duration: Duration) -> Self {
+        match result {
+            Ok(value) => Self::with_outcome(method, params, Some(value.clone()), None, duration),
+            Err(e) => Self::failed(method, params, e, duration),
+        }
+    }
+
+    /// An exchange that got `error` instead of a result.
+    pub fn failed(method: &str, params: Option<Value>, error: &BrpError, duration: Duration) -> Self {
+        let error = match error.json_rpc_parts() {
+            Some((code, message, data)) => {
+                RecordedError { code: Some(code), message: message.to_string(), data: data.cloned() }
+            }
+            None => RecordedError { code: None, message: error.to_string(), data: None },
+        };
+        Self::with_outcome(method, params, None, Some(error), duration)
+    }
+
+    fn with_outcome(
+        method: &str,
+        params: Option<Value>,
+        result: Option<Value>,
+        error: Option<RecordedError>,
+        duration: Duration,
+    ) -> Self {
+        Self {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            method: method.to_string(),
+            params,
+            result,
+            error,
+            duration_ms: duration.as_millis() as u64,
+        }
+    }
+
+    /// What the client returns for this exchange when replaying it.
+    pub fn into_result(self) -> Result<Value> {
+        match (self.result, self.error) {
+            (Some(result), _) => Ok(result),
+            (None, Some(RecordedError { code: Some(code), message, data })) => {
+                Err(BrpError::from_json_rpc(code, message, data))
+            }
+            (None, Some(error)) => Err(BrpError::Replay(format!("recorded failure: {}", error.message))),
+            (None, None) => Err(BrpError::Replay(format!("exchange for {} has no result", self.method))),
+        }
+    }
+}
+
+/// Process-wide state per recording file.
+type ByPath<T> = Mutex<HashMap<PathBuf, Arc<Mutex<T>>>>;
+
+// --- Recording ---
+
+/// Open recordings by path, shared by every client writing to them.
+fn recording_file(path: &Path) -> Result<Arc<Mutex<File>>> {
+    static FILES: OnceLock<ByPath<File>> = OnceLock::new();
+    let mut files = FILES.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(file) = files.get(path) {
+        return Ok(file.clone());
+    }
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    tracing::info!("Recording BRP traffic to {}", path.display());
+    let file = Arc::new(Mutex::new(file));
+    files.insert(path.to_path_buf(), file.clone());
+    Ok(file)
+}
+
+/// Append `exchange` to the recording at `path`. Failures are logged, not
+/// returned, so recording never breaks the request itself.
+pub fn record(path: &Path, exchange: &Exchange) {
+    let result = recording_file(path).and_then(|file| {
+        let line = serde_json::to_string(exchange)?;
+        let mut file = file.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(file, "{}", line)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        tracing::warn!("Failed to record BRP exchange to {}: {}", path.display(), e);
+    }
+}
+
+// --- Replay ---
+
+/// Read every exchange in a recording.
+pub fn load(path: &Path) -> Result<Vec<Exchange>> {
+    let file = File::open(path)?;
+    let mut exchanges = Vec::new();
+    for (i, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let exchange = serde_json::from_str(&line)
+            .map_err(|e| BrpError::Replay(format!("{}:{}: invalid exchange: {}", path.display(), i + 1, e)))?;
+        exchanges.push(exchange);
+    }
+    tracing::info!("Loaded {} BRP exchange(s) from {}", exchanges.len(), path.display());
+    Ok(exchanges)
+}
+
+/// Exchanges not yet replayed, by recording. Shared so clients created
+/// mid-run continue from the same position.
+fn replay_queue(path: &Path) -> Result<Arc<Mutex<Vec<Exchange>>>> {
+    static QUEUES: OnceLock<ByPath<Vec<Exchange>>> = OnceLock::new();
+    let mut queues = QUEUES.get_or_init(Default::default).lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(queue) = queues.get(path) {
+        return Ok(queue.clone());
+    }
+    let queue = Arc::new(Mutex::new(load(path)?));
+    queues.insert(path.to_path_buf(), queue.clone());
+    Ok(queue)
+}
+
+/// Take the exchange answering `method` with `params` out of `exchanges`.
+fn take(exchanges: &mut Vec<Exchange>, method: &str, params: Option<&Value>) -> Option<Exchange> {
+    let index = exchanges
+        .iter()
+        .position(|e| e.method == method && e.params.as_ref() == params)
+        .or_else(|| {
+            let index = exchanges.iter().position(|e| e.method == method)?;
+            tracing::warn!("BRP request diverged from the recording: {} with different params", method);
+            Some(index)
+        })?;
+    Some(exchanges.remove(index))
+}
+
+/// Answer a request from the recording at `path`.
+pub fn replay(path: &Path, method: &str, params: Option<&Value>) -> Result<Value> {
+    let queue = replay_queue(path)?;
+    let exchange = take(&mut queue.lock().unwrap_or_else(|e| e.into_inner()), method, params)
+        .ok_or_else(|| BrpError::Replay(format!("no recorded exchange left for {}", method)))?;
+    tracing::debug!("Replaying {} recorded at {}", method, exchange.timestamp);
+    exchange.into_result()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("brp_recording_{}_{}.jsonl", std::process::id(), name))
+    }
+
+    fn exchange(method: &str, params: Option<Value>, result: Result<Value>) -> Exchange {
+        Exchange::new(method, params, &result, Duration::from_millis(3))
+    }
+
+    #[test]
+    fn test_recorded_exchanges_replay() {
+        let path = temp_path("roundtrip");
+        let _ = std::fs::remove_file(&path);
+        record(&path, &exchange("world.query", Some(json!({"data": {}})), Ok(json!([{"entity": 1}]))));
+        record(&path, &exchange("rpc.discover", None, Err(BrpError::json_rpc(-32601, "Method not found"))));
+        record(&path, &exchange("world.spawn_entity", None, Err(BrpError::Timeout(Duration::from_secs(1)))));
+
+        assert_eq!(load(&path).unwrap().len(), 3);
+        let result = replay(&path, "world.query", Some(&json!({"data": {}}))).unwrap();
+        assert_eq!(result, json!([{"entity": 1}]));
+        match replay(&path, "rpc.discover", None) {
+            Err(BrpError::MethodNotFound { message, .. }) => assert_eq!(message, "Method not found"),
+            other => panic!("Expected MethodNotFound error, got {:?}", other),
+        }
+        assert!(matches!(replay(&path, "world.spawn_entity", None), Err(BrpError::Replay(_))));
+        // Each exchange answers once
+        assert!(matches!(replay(&path, "world.query", None), Err(BrpError::Replay(_))));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_take_prefers_matching_params() {
+        let mut exchanges = vec![
+            exchange("world.get_components", Some(json!({"entity": 1})), Ok(json!("first"))),
+            exchange("axiom.screenshot", None, Ok(json!("shot"))),
+            exchange("world.get_components", Some(json!({"entity": 2})), Ok(json!("second"))),
+        ];
+
+        let second = take(&mut exchanges, "world.get_components", Some(&json!({"entity": 2}))).unwrap();
+        assert_eq!(second.result, Some(json!("second")));
+        // Out of order and with other params, the first for the method
+        let first = take(&mut exchanges, "world.get_components", Some(&json!({"entity": 9}))).unwrap();
+        assert_eq!(first.result, Some(json!("first")));
+        assert!(take(&mut exchanges, "world.get_components", None).is_none());
+        assert_eq!(exchanges.len(), 1);
+    }
+}
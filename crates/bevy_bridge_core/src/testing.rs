@@ -0,0 +1,273 @@
+//! A stand-in for a game's BRP endpoint, for testing code that talks to one
+//! without launching Bevy. Enabled by the `testing` feature.
+//!
+//! [`MockBrpServer`] answers JSON-RPC over HTTP on a local port, single
+//! requests and batches alike, with the replies scripted for each method,
+//! and keeps every call it received. It answers a new client's handshake
+//! as a game with the current `bevy_ai_remote` would; other methods
+//! without a script answer "method not found". Streams (`+watch`) aren't
+//! supported.
+//!
+//! ```no_run
+//! # async fn example() -> bevy_bridge_core::Result<()> {
+//! use bevy_bridge_core::testing::MockBrpServer;
+//! use serde_json::json;
+//!
+//! let server = MockBrpServer::start();
+//! server.reply("world.query", json!([]));
+//! let client = server.client();
+//! client.send_rpc("world.query", Some(json!({ "data": {} }))).await?;
+//! assert_eq!(server.calls_to("world.query").len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::codes;
+use crate::ops::handshake::{PROTOCOL_RESOURCE, PROTOCOL_VERSION};
+use crate::{BrpClient, BrpConfig};
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// What the server answers a call with.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockReply {
+    Result(Value),
+    Error { code: i32, message: String, data: Option<Value> },
+}
+
+/// One JSON-RPC call the server received.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    pub method: String,
+    /// As sent, so including the token if the client has one.
+    pub params: Option<Value>,
+    /// HTTP headers of the request carrying it, with lowercase names.
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Default)]
+struct State {
+    replies: HashMap<String, VecDeque<MockReply>>,
+    calls: Vec<MockCall>,
+}
+
+impl State {
+    /// Each reply is used once, except the last for a method, which answers
+    /// every call after it too.
+    fn next_reply(&mut self, method: &str, params: Option<&Value>) -> MockReply {
+        let resource = params.and_then(|p| p.get("resource")).and_then(Value::as_str);
+        if method == "world.get_resources" && resource == Some(PROTOCOL_RESOURCE) {
+            let version = json!({ "protocol": PROTOCOL_VERSION, "plugin": "mock" });
+            return MockReply::Result(json!({ "value": version }));
+        }
+        match self.replies.get_mut(method) {
+            Some(queue) if queue.len() > 1 => queue.pop_front().unwrap(),
+            Some(queue) if !queue.is_empty() => queue[0].clone(),
+            _ => MockReply::Error {
+                code: codes::METHOD_NOT_FOUND,
+                message: format!("Method `{}` not found", method),
+                data: None,
+            },
+        }
+    }
+}
+
+/// A scripted BRP endpoint on `127.0.0.1`, stopped when dropped.
+pub struct MockBrpServer {
+    endpoint: String,
+    state: Arc<Mutex<State>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MockBrpServer {
+    /// Listen on a free local port.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind the mock BRP server");
+        let address = listener.local_addr().expect("Mock BRP server has no address");
+        let state = Arc::new(Mutex::new(State::default()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let (accept_state, accept_stopped) = (state.clone(), stopped.clone());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if accept_stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Ok(stream) = stream else { continue };
+                let state = accept_state.clone();
+                thread::spawn(move || serve(stream, state));
+            }
+        });
+
+        Self { endpoint: format!("http://{}", address), state, stopped }
+    }
+
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Config for a client of this server, with a short timeout.
+    pub fn config(&self) -> BrpConfig {
+        BrpConfig::new(self.endpoint.clone(), Duration::from_secs(5))
+    }
+
+    pub fn client(&self) -> BrpClient {
+        BrpClient::new(self.config())
+    }
+
+    /// Answer the next call to `method` with `result`; see [`Self::script`].
+    pub fn reply(&self, method: &str, result: Value) -> &Self {
+        self.script(method, MockReply::Result(result))
+    }
+
+    /// Answer the next call to `method` with a JSON-RPC error.
+    pub fn reply_error(&self, method: &str, code: i32, message: impl Into<String>) -> &Self {
+        self.script(method, MockReply::Error { code, message: message.into(), data: None })
+    }
+
+    /// Queue `reply` for `method`. Replies are used in the order queued and
+    /// the last one keeps answering once the others are used up.
+    pub fn script(&self, method: &str, reply: MockReply) -> &Self {
+        let mut state = self.state.lock().unwrap();
+        state.replies.entry(method.to_string()).or_default().push_back(reply);
+        self
+    }
+
+    /// Every call received so far, in order, including a new client's
+    /// handshake.
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.state.lock().unwrap().calls.clone()
+    }
+
+    /// The params of each call to `method`.
+    pub fn calls_to(&self, method: &str) -> Vec<Option<Value>> {
+        self.calls().into_iter().filter(|call| call.method == method).map(|call| call.params).collect()
+    }
+
+    /// Forget the calls received so far; the scripts stay.
+    pub fn clear_calls(&self) {
+        self.state.lock().unwrap().calls.clear();
+    }
+}
+
+impl Drop for MockBrpServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        // Wake the accept loop so it sees the flag
+        let _ = TcpStream::connect(self.endpoint.trim_start_matches("http://"));
+    }
+}
+
+/// Answer requests on one connection until the client closes it.
+fn serve(stream: TcpStream, state: Arc<Mutex<State>>) {
+    let Ok(mut writer) = stream.try_clone() else { return };
+    let mut reader = BufReader::new(stream);
+    while let Some((headers, body)) = read_request(&mut reader) {
+        let reply = match serde_json::from_slice::<Value>(&body) {
+            Ok(Value::Array(batch)) => {
+                Value::Array(batch.iter().map(|request| answer(request, &headers, &state)).collect())
+            }
+            Ok(request) => answer(&request, &headers, &state),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": { "code": -32700, "message": e.to_string() }
+            }),
+        };
+        let body = reply.to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn answer(request: &Value, headers: &HashMap<String, String>, state: &Mutex<State>) -> Value {
+    let method = request.get("method").and_then(Value::as_str).unwrap_or_default().to_string();
+    let mut state = state.lock().unwrap();
+    let params = request.get("params").cloned();
+    let reply = state.next_reply(&method, params.as_ref());
+    state.calls.push(MockCall { method, params, headers: headers.clone() });
+
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    match reply {
+        MockReply::Result(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        MockReply::Error { code, message, data } => {
+            let mut error = json!({ "code": code, "message": message });
+            if let Some(data) = data {
+                error["data"] = data;
+            }
+            json!({ "jsonrpc": "2.0", "id": id, "error": error })
+        }
+    }
+}
+
+/// The headers and body of the next HTTP request, or `None` once the
+/// connection is closed.
+fn read_request(reader: &mut impl BufRead) -> Option<(HashMap<String, String>, Vec<u8>)> {
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).ok()? == 0 {
+        return None;
+    }
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    let length = headers.get("content-length").and_then(|l| l.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).ok()?;
+    Some((headers, body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scripted_replies_and_recorded_calls() {
+        let server = MockBrpServer::start();
+        server.reply("world.query", json!([1])).reply("world.query", json!([2]));
+        let client = server.client();
+
+        assert_eq!(client.send_rpc("world.query", None).await.unwrap(), json!([1]));
+        assert_eq!(client.send_rpc("world.query", None).await.unwrap(), json!([2]));
+        // The last reply keeps answering
+        assert_eq!(client.send_rpc("world.query", Some(json!({ "a": 1 }))).await.unwrap(), json!([2]));
+        assert_eq!(server.calls_to("world.query"), vec![None, None, Some(json!({ "a": 1 }))]);
+
+        let unscripted = client.send_rpc("world.spawn_entity", None).await;
+        assert!(matches!(unscripted, Err(crate::BrpError::MethodNotFound { .. })), "{:?}", unscripted);
+    }
+
+    #[tokio::test]
+    async fn test_batches_are_answered_per_request() {
+        let server = MockBrpServer::start();
+        server.reply("world.query", json!([])).reply_error("world.despawn_entity", codes::ENTITY_NOT_FOUND, "gone");
+
+        let results = server
+            .client()
+            .send_batch(vec![("world.query", None), ("world.despawn_entity", Some(json!({ "entity": 7 })))])
+            .await
+            .unwrap();
+        assert_eq!(results[0].as_ref().unwrap(), &json!([]));
+        assert!(matches!(results[1], Err(crate::BrpError::EntityNotFound { .. })), "{:?}", results[1]);
+    }
+}
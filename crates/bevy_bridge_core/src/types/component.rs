@@ -0,0 +1,22 @@
+use super::SceneTransform;
+use serde::de::DeserializeOwned;
+
+/// A game component with a serde mirror on the editor side, for reading typed
+/// values out of query results. Derive `Deserialize` with the field names of
+/// the game's type and name its type path:
+///
+/// ```ignore
+/// #[derive(Deserialize)]
+/// struct Health { current: f32, max: f32 }
+///
+/// impl BrpComponent for Health {
+///     const TYPE_PATH: &'static str = "my_game::Health";
+/// }
+/// ```
+pub trait BrpComponent: DeserializeOwned {
+    const TYPE_PATH: &'static str;
+}
+
+impl BrpComponent for SceneTransform {
+    const TYPE_PATH: &'static str = "bevy_transform::components::transform::Transform";
+}
@@ -0,0 +1,149 @@
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// An entity as BRP sends it: the bits of bevy's `Entity`, with the index in
+/// the low 32 bits (stored inverted, as bevy keeps it non-max) and the
+/// generation in the high 32.
+///
+/// Displays as `{index}v{generation}#{bits}` and parses from that, from
+/// bevy's own `{index}v{generation}` or from the bare bits. Serializes as
+/// the bits, which is what BRP methods take.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EntityId(pub u64);
+
+impl EntityId {
+    pub fn from_parts(index: u32, generation: u32) -> Self {
+        Self(((generation as u64) << 32) | (!index) as u64)
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub fn index(self) -> u32 {
+        !(self.0 as u32)
+    }
+
+    pub fn generation(self) -> u32 {
+        (self.0 >> 32) as u32
+    }
+}
+
+impl From<u64> for EntityId {
+    fn from(bits: u64) -> Self {
+        Self(bits)
+    }
+}
+
+impl From<EntityId> for u64 {
+    fn from(id: EntityId) -> Self {
+        id.0
+    }
+}
+
+impl fmt::Display for EntityId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}v{}#{}", self.index(), self.generation(), self.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseEntityIdError(String);
+
+impl fmt::Display for ParseEntityIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid entity id '{}': expected bits, '<index>v<generation>' or both as '<index>v<generation>#<bits>'", self.0)
+    }
+}
+
+impl std::error::Error for ParseEntityIdError {}
+
+impl FromStr for EntityId {
+    type Err = ParseEntityIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseEntityIdError(s.to_string());
+        let s = s.trim();
+        // The bits are authoritative when both forms are given
+        if let Some((_, bits)) = s.split_once('#') {
+            return bits.parse().map(Self).map_err(|_| invalid());
+        }
+        if let Some((index, generation)) = s.split_once('v') {
+            let index = index.parse().map_err(|_| invalid())?;
+            let generation = generation.parse().map_err(|_| invalid())?;
+            return Ok(Self::from_parts(index, generation));
+        }
+        s.parse().map(Self).map_err(|_| invalid())
+    }
+}
+
+impl Serialize for EntityId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for EntityId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl de::Visitor<'_> for Visitor {
+            type Value = EntityId;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("entity bits or an entity id string")
+            }
+
+            fn visit_u64<E: de::Error>(self, bits: u64) -> Result<EntityId, E> {
+                Ok(EntityId(bits))
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<EntityId, E> {
+                s.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parts_round_trip() {
+        let id = EntityId::from_parts(25, 3);
+        assert_eq!(id.index(), 25);
+        assert_eq!(id.generation(), 3);
+        assert_eq!(EntityId(4294967270), EntityId::from_parts(25, 0));
+    }
+
+    #[test]
+    fn test_parse_forms() {
+        assert_eq!("4294967270".parse(), Ok(EntityId(4294967270)));
+        assert_eq!("25v0".parse(), Ok(EntityId(4294967270)));
+        assert_eq!("0v1#4294967298".parse(), Ok(EntityId(4294967298)));
+        assert!("".parse::<EntityId>().is_err());
+        assert!("25v".parse::<EntityId>().is_err());
+        assert!("cube".parse::<EntityId>().is_err());
+    }
+
+    #[test]
+    fn test_display_parses_back() {
+        let id = EntityId::from_parts(7, 2);
+        assert_eq!(id.to_string(), format!("7v2#{}", id.bits()));
+        assert_eq!(id.to_string().parse(), Ok(id));
+    }
+
+    #[test]
+    fn test_serde_accepts_numbers_and_strings() {
+        let id = EntityId(4294967270);
+        assert_eq!(serde_json::to_value(id).unwrap(), json!(4294967270u64));
+        assert_eq!(serde_json::from_value::<EntityId>(json!(4294967270u64)).unwrap(), id);
+        assert_eq!(serde_json::from_value::<EntityId>(json!("25v0")).unwrap(), id);
+        assert!(serde_json::from_value::<EntityId>(json!(-1)).is_err());
+    }
+}
@@ -0,0 +1,228 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A condition on the component values of query rows, built from
+/// [`Filter::field`]s and combined with [`and`](Filter::and),
+/// [`or`](Filter::or) and `!`.
+///
+/// ```
+/// use bevy_bridge_core::types::Filter;
+///
+/// let high = Filter::field("Transform.translation.y").gt(10.0);
+/// let crates = Filter::field("Name").contains("Crate");
+/// let filter = high.and(!crates);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    Compare { component: String, fields: Vec<String>, op: Comparison, value: Value },
+    All(Vec<Filter>),
+    Any(Vec<Filter>),
+    Not(Box<Filter>),
+}
+
+/// A field of a component, for comparing in a [`Filter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    component: String,
+    fields: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    /// A string containing, or an array holding, the value.
+    Contains,
+}
+
+impl Filter {
+    /// `path` is a component's type, or its short name such as `Transform`,
+    /// then the fields into its value separated by dots. `x`, `y`, `z` and
+    /// `w` also index arrays, as vectors and quaternions are sent.
+    pub fn field(path: &str) -> Field {
+        let mut parts = path.split('.');
+        Field {
+            component: parts.next().unwrap_or_default().to_string(),
+            fields: parts.map(str::to_string).collect(),
+        }
+    }
+
+    pub fn and(self, other: Filter) -> Filter {
+        match self {
+            Filter::All(mut all) => {
+                all.push(other);
+                Filter::All(all)
+            }
+            first => Filter::All(vec![first, other]),
+        }
+    }
+
+    pub fn or(self, other: Filter) -> Filter {
+        match self {
+            Filter::Any(mut any) => {
+                any.push(other);
+                Filter::Any(any)
+            }
+            first => Filter::Any(vec![first, other]),
+        }
+    }
+
+    /// Whether a `world.query` row satisfies this. Fields the row lacks
+    /// never compare true.
+    pub fn matches(&self, row: &Value) -> bool {
+        match self {
+            Filter::Compare { component, fields, op, value } => component_value(row, component)
+                .and_then(|root| fields.iter().try_fold(root, |value, field| child(value, field)))
+                .is_some_and(|actual| op.holds(actual, value)),
+            Filter::All(all) => all.iter().all(|f| f.matches(row)),
+            Filter::Any(any) => any.iter().any(|f| f.matches(row)),
+            Filter::Not(filter) => !filter.matches(row),
+        }
+    }
+
+    /// The components this reads, as written.
+    pub fn components(&self) -> Vec<&str> {
+        match self {
+            Filter::Compare { component, .. } => vec![component.as_str()],
+            Filter::All(filters) | Filter::Any(filters) => filters.iter().flat_map(Filter::components).collect(),
+            Filter::Not(filter) => filter.components(),
+        }
+    }
+}
+
+impl std::ops::Not for Filter {
+    type Output = Filter;
+
+    fn not(self) -> Filter {
+        match self {
+            Filter::Not(filter) => *filter,
+            filter => Filter::Not(Box::new(filter)),
+        }
+    }
+}
+
+impl Field {
+    fn compare(self, op: Comparison, value: impl Into<Value>) -> Filter {
+        Filter::Compare { component: self.component, fields: self.fields, op, value: value.into() }
+    }
+
+    pub fn eq(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Eq, value)
+    }
+
+    pub fn ne(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Ne, value)
+    }
+
+    pub fn lt(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Lt, value)
+    }
+
+    pub fn le(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Le, value)
+    }
+
+    pub fn gt(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Gt, value)
+    }
+
+    pub fn ge(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Ge, value)
+    }
+
+    /// A string containing, or an array holding, `value`.
+    pub fn contains(self, value: impl Into<Value>) -> Filter {
+        self.compare(Comparison::Contains, value)
+    }
+}
+
+impl Comparison {
+    /// Whether `actual` compares to `expected` this way. Orderings between
+    /// values that aren't both numbers or both strings never hold.
+    pub fn holds(self, actual: &Value, expected: &Value) -> bool {
+        let ordering = match (actual, expected) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            _ => None,
+        };
+        match self {
+            Comparison::Eq => ordering.map_or(actual == expected, |o| o.is_eq()),
+            Comparison::Ne => ordering.map_or(actual != expected, |o| o.is_ne()),
+            Comparison::Lt => ordering.is_some_and(|o| o.is_lt()),
+            Comparison::Le => ordering.is_some_and(|o| o.is_le()),
+            Comparison::Gt => ordering.is_some_and(|o| o.is_gt()),
+            Comparison::Ge => ordering.is_some_and(|o| o.is_ge()),
+            Comparison::Contains => match (actual, expected) {
+                (Value::String(a), Value::String(b)) => a.contains(b.as_str()),
+                (Value::Array(items), value) => items.contains(value),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// The value of `component` in `row`, found by its type or short name.
+fn component_value<'a>(row: &'a Value, component: &str) -> Option<&'a Value> {
+    let components = row.get("components")?.as_object()?;
+    components
+        .get(component)
+        .or_else(|| components.iter().find(|(path, _)| short_name(path) == component).map(|(_, value)| value))
+}
+
+fn child<'a>(value: &'a Value, field: &str) -> Option<&'a Value> {
+    match value {
+        Value::Object(fields) => fields.get(field),
+        Value::Array(items) => {
+            let index = match field {
+                "x" => 0,
+                "y" => 1,
+                "z" => 2,
+                "w" => 3,
+                _ => field.parse().ok()?,
+            };
+            items.get(index)
+        }
+        _ => None,
+    }
+}
+
+/// `Transform` for `bevy_transform::components::transform::Transform`.
+pub(crate) fn short_name(type_path: &str) -> &str {
+    let base = type_path.split('<').next().unwrap_or(type_path);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+    fn row() -> Value {
+        json!({
+            "entity": 1,
+            "components": {
+                "bevy_ai_remote::AxiomPrimitive": { "primitive_type": "cube" },
+                TRANSFORM: { "translation": [0.0, 2.0, 0.0] }
+            }
+        })
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let row = row();
+        assert!(Filter::field("Transform.translation.y").ge(2).matches(&row));
+        assert!(!Filter::field("Transform.translation.1").lt(2).matches(&row));
+        assert!(Filter::field("Transform.translation").contains(2.0).matches(&row));
+        assert!(!Filter::field("Transform.translation.y").gt("a").matches(&row));
+        assert!(!Filter::field("Transform.scale").eq(1).matches(&row));
+        assert!(Filter::field(&format!("{}.translation.y", TRANSFORM)).eq(2.0).matches(&row));
+    }
+}
@@ -1,5 +1,13 @@
+pub mod component;
+pub mod entity;
+pub mod filter;
 pub mod requests;
 pub mod responses;
+pub mod scene;
 
+pub use component::*;
+pub use entity::*;
+pub use filter::*;
 pub use requests::*;
 pub use responses::*;
+pub use scene::*;
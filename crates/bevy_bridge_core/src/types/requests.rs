@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::{EntityId, Filter};
+
 /// Request to upload an asset with base64-encoded data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UploadRequest {
@@ -14,14 +16,75 @@ pub struct SpawnRequest {
     pub position: [f32; 3],
     pub rotation: [f32; 4],
     pub scale: [f32; 3],
+    #[serde(default)]
+    pub material: PrimitiveMaterial,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub group: Option<String>,
+}
+
+/// Where an upload goes in the cache and how its entity is spawned.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UploadOptions {
+    /// Directory under `_remote_cache`.
+    pub subdir: Option<String>,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub name: Option<String>,
+    pub group: Option<String>,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self { subdir: None, translation: [0.0; 3], rotation: [0.0, 0.0, 0.0, 1.0], name: None, group: None }
+    }
+}
+
+/// How a spawned primitive looks. Fields left unset keep the game's
+/// defaults: a beige, non-metallic, half-rough lit material.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrimitiveMaterial {
+    /// sRGB `[r, g, b, a]`, each 0 to 1; `[r, g, b]` is read as opaque.
+    #[serde(skip_serializing_if = "Option::is_none", deserialize_with = "rgb_or_rgba")]
+    pub color: Option<[f32; 4]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metallic: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub roughness: Option<f32>,
+    /// Linear `[r, g, b]`; above 1 glows brighter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub emissive: Option<[f32; 3]>,
+    /// Ignore lighting and show the color as is.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub unlit: bool,
+}
+
+impl PrimitiveMaterial {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+fn rgb_or_rgba<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<[f32; 4]>, D::Error> {
+    match Option::<Vec<f32>>::deserialize(deserializer)?.as_deref() {
+        None => Ok(None),
+        Some(&[r, g, b]) => Ok(Some([r, g, b, 1.0])),
+        Some(&[r, g, b, a]) => Ok(Some([r, g, b, a])),
+        Some(other) => Err(serde::de::Error::invalid_length(other.len(), &"3 or 4 color channels")),
+    }
 }
 
 /// Target for clear operation
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum ClearTarget {
     All,
     Assets,
     Primitives,
+    /// Everything spawned with this group.
+    Group(String),
 }
 
 /// Request to clear entities from the scene
@@ -30,8 +93,120 @@ pub struct ClearRequest {
     pub target: ClearTarget,
 }
 
+/// Params of `world.get_components`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GetRequest {
+    pub entity: EntityId,
+    /// Type paths to read.
+    pub components: Vec<String>,
+    /// Fail on a component the entity lacks, rather than listing it in
+    /// [`EntityDetail::errors`](crate::types::EntityDetail::errors).
+    #[serde(default)]
+    pub strict: bool,
+}
+
+/// A shape `bevy_ai_remote` draws over the scene, in world space.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GizmoShape {
+    Line { start: [f32; 3], end: [f32; 3] },
+    Aabb { min: [f32; 3], max: [f32; 3] },
+    Sphere { center: [f32; 3], radius: f32 },
+}
+
+/// Params of `axiom.draw_gizmo`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GizmoRequest {
+    #[serde(flatten)]
+    pub shape: GizmoShape,
+    /// sRGBA.
+    pub color: [f32; 4],
+    /// Seconds until the shape is no longer drawn; at most an hour.
+    pub ttl_secs: f32,
+}
+
 /// Request to query entities by component types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryRequest {
     pub components: Vec<String>,
 }
+
+/// Narrowing and paging for [`crate::ops::query::query`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QueryOptions {
+    /// Components entities must have, without their values being returned.
+    #[serde(default)]
+    pub with: Vec<String>,
+    /// Components entities must not have.
+    #[serde(default)]
+    pub without: Vec<String>,
+    /// A condition on component values, e.g.
+    /// `Filter::field("Transform.translation.y").gt(0.0)`.
+    #[serde(default)]
+    pub filter: Option<Filter>,
+    /// Matches to skip.
+    #[serde(default)]
+    pub offset: usize,
+    /// Most matches to return.
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_get_request_matches_brp_params() {
+        let request = GetRequest { entity: EntityId(7), components: vec!["bevy_ecs::name::Name".into()], strict: false };
+        let params = serde_json::to_value(&request).unwrap();
+        assert_eq!(params, json!({ "entity": 7, "components": ["bevy_ecs::name::Name"], "strict": false }));
+        assert_eq!(serde_json::from_value::<GetRequest>(json!({ "entity": 7, "components": ["bevy_ecs::name::Name"] })).unwrap(), request);
+    }
+
+    #[test]
+    fn test_query_options_round_trip_with_filter() {
+        let options = QueryOptions {
+            with: vec!["bevy_ai_remote::AxiomSpawned".into()],
+            filter: Some(Filter::field("Transform.translation.y").gt(10.0).or(!Filter::field("Name").contains("Crate"))),
+            limit: Some(5),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&options).unwrap();
+        let back: QueryOptions = serde_json::from_value(json).unwrap();
+        assert_eq!((back.with, back.filter, back.limit), (options.with, options.filter, options.limit));
+    }
+
+    #[test]
+    fn test_gizmo_request_is_flat() {
+        let request = GizmoRequest {
+            shape: GizmoShape::Sphere { center: [0.0, 1.0, 0.0], radius: 0.5 },
+            color: [1.0, 0.0, 0.0, 1.0],
+            ttl_secs: 2.0,
+        };
+        let params = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            params,
+            json!({ "kind": "sphere", "center": [0.0, 1.0, 0.0], "radius": 0.5, "color": [1.0, 0.0, 0.0, 1.0], "ttl_secs": 2.0 })
+        );
+        assert_eq!(serde_json::from_value::<GizmoRequest>(params).unwrap(), request);
+    }
+
+    #[test]
+    fn test_spawn_request_defaults_and_rgb_color() {
+        let request: SpawnRequest = serde_json::from_value(json!({
+            "primitive": "cube",
+            "position": [0.0, 1.0, 0.0],
+            "rotation": [0.0, 0.0, 0.0, 1.0],
+            "scale": [1.0, 1.0, 1.0],
+            "material": { "color": [1.0, 0.0, 0.0] }
+        }))
+        .unwrap();
+        assert_eq!(request.material.color, Some([1.0, 0.0, 0.0, 1.0]));
+        assert_eq!(request.name, None);
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["material"], json!({ "color": [1.0, 0.0, 0.0, 1.0] }));
+        assert!(serde_json::from_value::<PrimitiveMaterial>(json!({ "color": [1.0] })).is_err());
+    }
+}
@@ -1,14 +1,26 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use super::EntityId;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UploadResponse {
-    pub entity_id: String,
+    pub entity_id: EntityId,
+    /// The game already had the file; no bytes were sent.
+    #[serde(default)]
+    pub cached: bool,
+}
+
+/// The game plugin's `AxiomProtocolVersion`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersion {
+    pub protocol: u32,
+    pub plugin: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpawnResponse {
-    pub entity_id: String,
+    pub entity_id: EntityId,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,10 +31,418 @@ pub struct ClearResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryResponse {
     pub entities: Vec<Value>,
+    /// Matches before `offset` and `limit` were applied.
+    #[serde(default)]
+    pub total: usize,
+}
+
+impl QueryResponse {
+    /// `T` of each entity that has it, with the entity's id. The query must
+    /// have asked for `T::TYPE_PATH`.
+    pub fn deserialize_components<T: super::BrpComponent>(&self) -> crate::Result<Vec<(EntityId, T)>> {
+        self.entities
+            .iter()
+            .filter_map(|row| {
+                let value = row.get("components")?.get(T::TYPE_PATH)?;
+                let entity = row.get("entity").and_then(Value::as_u64).map(EntityId);
+                Some((entity, value))
+            })
+            .map(|(entity, value)| {
+                let entity = entity.ok_or_else(|| crate::BrpError::InvalidResponse("Query row without an entity".into()))?;
+                Ok((entity, T::deserialize(value)?))
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PingResponse {
     pub alive: bool,
+    /// The game's `rpc.discover` document.
     pub methods: Value,
+    /// Round trip of the discovery request.
+    pub latency_ms: f64,
+    pub method_count: usize,
+    /// As `bevy_remote` reports it, e.g. `0.18.1`.
+    pub bevy_version: Option<String>,
+    /// `None` for a game without `bevy_ai_remote`, or with one too old to
+    /// report its status; so are the fields below.
+    pub plugin_version: Option<String>,
+    pub frames: Option<u64>,
+    pub uptime_secs: Option<f64>,
+}
+
+/// The game plugin's `AxiomStatus`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameStatus {
+    pub plugin: String,
+    pub frames: u64,
+    pub uptime_secs: f64,
+}
+
+/// A PNG capture of the game's primary window.
+#[derive(Debug, Clone)]
+pub struct ScreenshotResponse {
+    pub png: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// A file in the game's `assets/_remote_cache`.
+#[derive(Debug, Clone)]
+pub struct RemoteAsset {
+    /// Relative to `_remote_cache`, with `/` separators.
+    pub path: String,
+    pub size: u64,
+    /// Contents of small images, for thumbnails.
+    pub image: Option<Vec<u8>>,
+}
+
+/// A step in the game's undo journal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: u64,
+    pub label: String,
+    /// Entities spawned, despawned or moved by this step.
+    pub changes: usize,
+}
+
+/// The game's undo journal, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    pub entries: Vec<HistoryEntry>,
+    /// Entries before this are applied; the rest have been undone.
+    pub cursor: usize,
+}
+
+impl History {
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.cursor < self.entries.len()
+    }
+}
+
+/// Result of an undo or redo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryStep {
+    /// The entry that was undone or redone.
+    pub label: String,
+    pub history: History,
+}
+
+/// Time spent in one schedule or system during a frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Timing {
+    pub name: String,
+    pub ms: f64,
+    /// The schedule a system ran in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+}
+
+/// One frame recorded by the game's profiler.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FrameTiming {
+    pub frame: u64,
+    pub total_ms: f64,
+    /// Entities in the world at the end of the frame.
+    pub entities: u32,
+    /// In the order they ran.
+    pub schedules: Vec<Timing>,
+    /// Empty unless the game records per-system timings.
+    #[serde(default)]
+    pub systems: Vec<Timing>,
+}
+
+/// The game's profiler state and the frames it holds, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileCapture {
+    pub recording: bool,
+    /// Whether systems are timed as well as schedules.
+    #[serde(default)]
+    pub system_timing: bool,
+    pub frames: Vec<FrameTiming>,
+}
+
+/// Averages over a capture, for answering performance questions without
+/// reading every frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileSummary {
+    pub frames: usize,
+    pub avg_frame_ms: f64,
+    pub max_frame_ms: f64,
+    pub fps: f64,
+    pub slowest_frame: Option<u64>,
+    /// Entity count at the first and last frame.
+    pub entities: (u32, u32),
+    /// Average per frame, slowest first.
+    pub schedules: Vec<Timing>,
+    pub systems: Vec<Timing>,
+}
+
+impl ProfileCapture {
+    /// Averages per frame, keeping the `top` slowest systems.
+    pub fn summary(&self, top: usize) -> ProfileSummary {
+        let count = self.frames.len();
+        let total: f64 = self.frames.iter().map(|f| f.total_ms).sum();
+        let slowest = self.frames.iter().max_by(|a, b| a.total_ms.total_cmp(&b.total_ms));
+        let avg_frame_ms = if count == 0 { 0.0 } else { total / count as f64 };
+        let mut systems = average(self.frames.iter().flat_map(|f| &f.systems), count);
+        systems.truncate(top);
+        ProfileSummary {
+            frames: count,
+            avg_frame_ms,
+            max_frame_ms: slowest.map_or(0.0, |f| f.total_ms),
+            fps: if avg_frame_ms > 0.0 { 1000.0 / avg_frame_ms } else { 0.0 },
+            slowest_frame: slowest.map(|f| f.frame),
+            entities: (
+                self.frames.first().map_or(0, |f| f.entities),
+                self.frames.last().map_or(0, |f| f.entities),
+            ),
+            schedules: average(self.frames.iter().flat_map(|f| &f.schedules), count),
+            systems,
+        }
+    }
+}
+
+/// Sum of each name's time over `frames` frames, slowest first.
+fn average<'a>(timings: impl Iterator<Item = &'a Timing>, frames: usize) -> Vec<Timing> {
+    let mut sums: Vec<Timing> = Vec::new();
+    for timing in timings {
+        match sums.iter_mut().find(|t| t.name == timing.name && t.schedule == timing.schedule) {
+            Some(sum) => sum.ms += timing.ms,
+            None => sums.push(timing.clone()),
+        }
+    }
+    sums.iter_mut().for_each(|t| t.ms /= frames.max(1) as f64);
+    sums.sort_by(|a, b| b.ms.total_cmp(&a.ms));
+    sums
+}
+
+/// What changed on a watched entity in one game tick.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityChange {
+    /// Entity bits, as in `entity_id`.
+    #[serde(default)]
+    pub entity: EntityId,
+    /// Watched components added or changed, with their new values.
+    #[serde(default)]
+    pub components: serde_json::Map<String, Value>,
+    /// Components added to the entity, when watching its component list.
+    #[serde(default)]
+    pub added: Vec<String>,
+    /// Components removed from the entity.
+    #[serde(default)]
+    pub removed: Vec<String>,
+    /// Watched components that couldn't be read, with the reason.
+    #[serde(default)]
+    pub errors: serde_json::Map<String, Value>,
+}
+
+/// One event of a `+watch` stream: a JSON-RPC response carrying a change,
+/// or the error that ended the watch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchEvent {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<EntityChange>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+impl WatchEvent {
+    /// The change, or the error as a [`BrpError`](crate::BrpError).
+    pub fn into_result(self) -> crate::Result<EntityChange> {
+        match (self.result, self.error) {
+            (_, Some(error)) => Err(error.into()),
+            (Some(change), None) => Ok(change),
+            (None, None) => Err(crate::BrpError::InvalidResponse("Watch event without a result".into())),
+        }
+    }
+}
+
+/// A JSON-RPC error as the game sends it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i32,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+/// What `world.get_resources` answers: the resource's value, as `T` if it
+/// has a serde mirror.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResourceResponse<T = Value> {
+    pub value: T,
+}
+
+/// An entity and everything below it in the hierarchy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HierarchyNode {
+    pub entity: EntityId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// In order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<HierarchyNode>,
+}
+
+impl HierarchyNode {
+    /// This entity and its descendants, depth first.
+    pub fn entities(&self) -> Vec<EntityId> {
+        let mut entities = vec![self.entity];
+        entities.extend(self.children.iter().flat_map(HierarchyNode::entities));
+        entities
+    }
+}
+
+/// Components inserted on or removed from an entity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComponentsResponse {
+    pub entity: EntityId,
+    /// Type paths, in the order given.
+    pub components: Vec<String>,
+}
+
+/// An entity's components as the game reflects them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EntityDetail {
+    /// Entity bits, as in `entity_id`.
+    #[serde(default)]
+    pub entity: EntityId,
+    /// Type path to value.
+    #[serde(default)]
+    pub components: serde_json::Map<String, Value>,
+    /// Requested components that couldn't be read, such as ones the entity
+    /// doesn't have or that aren't reflected, with the reason.
+    #[serde(default)]
+    pub errors: serde_json::Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DespawnResponse {
+    pub entities_removed: usize,
+}
+
+/// Severity of a log record, least severe first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    #[default]
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A record of the game's log, from `axiom.logs`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LogRecord {
+    /// Increasing; pass the last one seen to read only newer records.
+    pub seq: u64,
+    /// Unix time.
+    pub timestamp_ms: u64,
+    pub level: LogLevel,
+    /// Usually the module that logged it.
+    pub target: String,
+    pub message: String,
+    /// The record's other fields.
+    #[serde(default)]
+    pub fields: serde_json::Map<String, Value>,
+}
+
+/// The game's `bevy_ai_remote::AxiomSimControl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimState {
+    pub paused: bool,
+    /// Frames left to run before pausing again.
+    pub step_frames: u32,
+    pub time_scale: f32,
+}
+
+/// The nearest mesh a ray hits.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RaycastHit {
+    /// The entity with the mesh, which for an uploaded model is a
+    /// descendant of the spawned entity.
+    pub entity: EntityId,
+    /// In world space.
+    pub point: [f32; 3],
+    /// Facing back towards the ray's origin.
+    pub normal: [f32; 3],
+    /// Along the ray, in units of its direction.
+    pub distance: f32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BrpError;
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(value: T) {
+        let json = serde_json::to_value(&value).unwrap();
+        assert_eq!(serde_json::from_value::<T>(json).unwrap(), value);
+    }
+
+    #[test]
+    fn test_watch_event_is_a_change_or_an_error() {
+        let event: WatchEvent = serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "result": { "components": { "game::Health": 10 }, "removed": ["game::Shield"] }
+        }))
+        .unwrap();
+        let change = event.clone().into_result().unwrap();
+        assert_eq!(change.components["game::Health"], 10);
+        assert_eq!(change.removed, ["game::Shield"]);
+        round_trip(event);
+
+        let event: WatchEvent = serde_json::from_value(json!({ "error": { "code": -23401, "message": "Entity 42 not found" } })).unwrap();
+        assert!(matches!(event.clone().into_result(), Err(BrpError::EntityNotFound { .. })));
+        round_trip(event);
+
+        let empty = WatchEvent { result: None, error: None };
+        assert!(matches!(empty.into_result(), Err(BrpError::InvalidResponse(_))));
+    }
+
+    #[test]
+    fn test_rpc_error_keeps_data() {
+        let error = RpcError { code: -32602, message: "Invalid params".into(), data: Some(json!({ "field": "entity" })) };
+        round_trip(error.clone());
+        let brp: BrpError = RpcError { data: None, ..error }.into();
+        assert!(matches!(brp, BrpError::JsonRpc { code: -32602, data: None, .. }));
+    }
+
+    #[test]
+    fn test_resource_response_as_value_or_type() {
+        let result = json!({ "value": { "protocol": 1, "plugin": "0.1.0" } });
+        let typed: ResourceResponse<ProtocolVersion> = serde_json::from_value(result.clone()).unwrap();
+        assert_eq!(typed.value.plugin, "0.1.0");
+        round_trip(typed);
+        let untyped: ResourceResponse = serde_json::from_value(result).unwrap();
+        assert_eq!(untyped.value["protocol"], 1);
+        assert!(serde_json::from_value::<ResourceResponse>(json!({})).is_err());
+    }
+
+    #[test]
+    fn test_hierarchy_node_omits_empty_fields() {
+        let leaf = HierarchyNode { entity: EntityId(2), name: None, children: Vec::new() };
+        assert_eq!(serde_json::to_value(&leaf).unwrap(), json!({ "entity": 2 }));
+        let root = HierarchyNode { entity: EntityId(1), name: Some("level".into()), children: vec![leaf] };
+        round_trip(root);
+    }
+
+    #[test]
+    fn test_entity_detail_and_change_default_missing_fields() {
+        let detail: EntityDetail = serde_json::from_value(json!({ "components": { "bevy_ecs::name::Name": "level" } })).unwrap();
+        assert!(detail.errors.is_empty());
+        round_trip(detail);
+        round_trip(EntityChange { entity: EntityId(7), added: vec!["game::Health".into()], ..Default::default() });
+    }
 }
@@ -0,0 +1,169 @@
+use super::{EntityId, PrimitiveMaterial};
+use crate::ops::transform::TRANSFORM;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+
+/// What an editor-spawned entity shows.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SceneObject {
+    Primitive { primitive_type: String },
+    /// A model in `_remote_cache`, by its path there.
+    Model { path: String },
+}
+
+impl fmt::Display for SceneObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SceneObject::Primitive { primitive_type } => write!(f, "{}", primitive_type),
+            SceneObject::Model { path } => write!(f, "{}", path),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SceneTransform {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl Default for SceneTransform {
+    fn default() -> Self {
+        Self {
+            translation: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            scale: [1.0; 3],
+        }
+    }
+}
+
+/// One entity of an exported scene.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneEntity {
+    /// Id in the game at export time; restoring spawns new entities.
+    pub entity: EntityId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The [`ClearTarget::Group`](crate::types::ClearTarget::Group) it was spawned in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    #[serde(flatten)]
+    pub object: SceneObject,
+    pub transform: SceneTransform,
+    /// A primitive's material.
+    #[serde(default, skip_serializing_if = "PrimitiveMaterial::is_default")]
+    pub material: PrimitiveMaterial,
+}
+
+impl SceneEntity {
+    /// The name if it has one, else what it shows.
+    pub fn label(&self) -> String {
+        match &self.name {
+            Some(name) => format!("{} ({})", name, self.object),
+            None => self.object.to_string(),
+        }
+    }
+}
+
+/// A named export of a scene, as saved to compare or restore later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SceneSnapshot {
+    pub name: String,
+    pub created: chrono::DateTime<chrono::Local>,
+    pub entities: Vec<SceneEntity>,
+}
+
+impl SceneSnapshot {
+    pub fn new(name: &str, entities: Vec<SceneEntity>) -> Self {
+        Self { name: name.to_string(), created: chrono::Local::now(), entities }
+    }
+}
+
+/// A component that differs on an entity between two scenes, by its type
+/// path, with its BRP value on each side; `None` where the entity lacks it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComponentDiff {
+    pub component: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+/// An entity present in both scenes, moved or otherwise changed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModifiedEntity {
+    pub before: SceneEntity,
+    pub after: SceneEntity,
+    /// At least one.
+    pub changes: Vec<ComponentDiff>,
+}
+
+impl ModifiedEntity {
+    /// Whether its transform is among the changes.
+    pub fn moved(&self) -> bool {
+        self.changes.iter().any(|change| change.component == TRANSFORM)
+    }
+}
+
+/// Changes from one scene to another.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SceneDiff {
+    pub added: Vec<SceneEntity>,
+    pub removed: Vec<SceneEntity>,
+    pub modified: Vec<ModifiedEntity>,
+    pub unchanged: usize,
+}
+
+impl SceneDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+
+    /// E.g. `2 added, 1 removed, 3 modified, 10 unchanged`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} added, {} removed, {} modified, {} unchanged",
+            self.added.len(),
+            self.removed.len(),
+            self.modified.len(),
+            self.unchanged
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entity() -> SceneEntity {
+        SceneEntity {
+            entity: EntityId(7),
+            name: Some("crate".into()),
+            group: None,
+            object: SceneObject::Model { path: "models/crate.glb".into() },
+            transform: SceneTransform { translation: [1.0, 2.0, 3.0], ..Default::default() },
+            material: PrimitiveMaterial::default(),
+        }
+    }
+
+    #[test]
+    fn test_scene_entity_flattens_its_object() {
+        let json = serde_json::to_value(entity()).unwrap();
+        assert_eq!(json["kind"], "model");
+        assert_eq!(json["path"], "models/crate.glb");
+        assert!(json.get("group").is_none() && json.get("material").is_none());
+        assert_eq!(serde_json::from_value::<SceneEntity>(json).unwrap(), entity());
+    }
+
+    #[test]
+    fn test_scene_snapshot_round_trip() {
+        let snapshot = SceneSnapshot::new("before lighting", vec![entity()]);
+        let json = serde_json::to_string(&snapshot).unwrap();
+        assert_eq!(serde_json::from_str::<SceneSnapshot>(&json).unwrap(), snapshot);
+
+        let saved = json!({ "name": "old", "created": "2026-01-02T03:04:05+01:00", "entities": [] });
+        assert!(serde_json::from_value::<SceneSnapshot>(saved).unwrap().entities.is_empty());
+    }
+}
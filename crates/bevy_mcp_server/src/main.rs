@@ -9,17 +9,62 @@ use rmcp::{
 };
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use bevy_bridge_core::{BrpClient, BrpConfig, ops, types};
+use bevy_bridge_core::{BrpClient, BrpConfig, BrpMetrics, ops, types};
+use std::sync::Arc;
 use base64::Engine;
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct PingParams {}
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct MetricsParams {
+    /// Start counting afresh after reading.
+    #[serde(default)]
+    reset: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct QueryParams {
     components: Vec<String>,
+    /// Components entities must also have, without returning their values.
+    #[serde(default)]
+    with: Vec<String>,
+    /// Components entities must not have.
+    #[serde(default)]
+    without: Vec<String>,
+    /// Conditions on component values, all of which must hold.
+    #[serde(default, rename = "where")]
+    predicates: Vec<PredicateParams>,
+    #[serde(default)]
+    offset: usize,
+    /// Most entities to return; 100 by default.
+    #[serde(default = "default_query_limit")]
+    limit: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct PredicateParams {
+    /// The component's type, or a short name such as "Transform" for one
+    /// queried or well known.
+    component: String,
+    /// Fields into the component separated by dots, e.g. "translation.y";
+    /// empty for the whole value.
+    #[serde(default)]
+    path: String,
+    /// "eq", "ne", "lt", "le", "gt", "ge" or "contains".
+    op: String,
+    #[schemars(schema_with = "any_json_value")]
+    value: serde_json::Value,
 }
 
+/// A number, string, boolean, array or object; spelled out since some
+/// clients reject the bare `true` schema `Value` gets.
+fn any_json_value(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+    schemars::json_schema!({ "type": ["number", "string", "boolean", "array", "object"] })
+}
+
+fn default_query_limit() -> usize { 100 }
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct SpawnPrimitiveParams {
     primitive_type: String,
@@ -28,6 +73,45 @@ struct SpawnPrimitiveParams {
     rotation: [f32; 4],
     #[serde(default = "default_scale")]
     scale: [f32; 3],
+    /// sRGB [r, g, b] or [r, g, b, a], each 0 to 1. Beige if omitted.
+    color: Option<Vec<f32>>,
+    /// 0 (dielectric) to 1 (metal).
+    metallic: Option<f32>,
+    /// 0 (glossy) to 1 (matte).
+    roughness: Option<f32>,
+    /// Linear [r, g, b] glow; values above 1 glow brighter.
+    emissive: Option<[f32; 3]>,
+    /// Ignore lighting and show the color as is.
+    #[serde(default)]
+    unlit: bool,
+    /// Name to find the entity by later, e.g. "player_start".
+    name: Option<String>,
+    /// Group to clear it with later, e.g. "level1".
+    group: Option<String>,
+}
+
+impl SpawnPrimitiveParams {
+    fn material(&self) -> Result<types::PrimitiveMaterial, McpError> {
+        let color = match self.color.as_deref() {
+            None => None,
+            Some(&[r, g, b]) => Some([r, g, b, 1.0]),
+            Some(&[r, g, b, a]) => Some([r, g, b, a]),
+            Some(_) => return Err(McpError::invalid_params("color takes 3 or 4 channels", None)),
+        };
+        Ok(types::PrimitiveMaterial {
+            color,
+            metallic: self.metallic,
+            roughness: self.roughness,
+            emissive: self.emissive,
+            unlit: self.unlit,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct SpawnPrimitivesParams {
+    /// Primitives to spawn in one go, e.g. the cells of a grid.
+    primitives: Vec<SpawnPrimitiveParams>,
 }
 
 fn default_rotation() -> [f32; 4] { [0.0, 0.0, 0.0, 1.0] }
@@ -42,71 +126,170 @@ struct UploadAssetParams {
     translation: [f32; 3],
     #[serde(default = "default_rotation")]
     rotation: [f32; 4],
+    /// Name to find the entity by later, e.g. "player_start".
+    name: Option<String>,
+    /// Group to clear it with later, e.g. "level1".
+    group: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct FindByNameParams {
+    /// The whole name, as given when spawning.
+    name: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct ClearSceneParams {
+    /// "all", "assets", "primitives" or "group".
     #[serde(default = "default_target")]
     target: String,
+    /// The group to clear when target is "group".
+    group: Option<String>,
 }
 
 fn default_target() -> String { "all".to_string() }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct LogsParams {
+    /// Least severe level returned: "trace", "debug", "info", "warn" or "error".
+    #[serde(default = "default_log_level")]
+    level: String,
+    /// Most records to return, newest; 50 by default.
+    #[serde(default = "default_log_limit")]
+    limit: usize,
+}
+
+fn default_log_level() -> String { "info".to_string() }
+fn default_log_limit() -> usize { 50 }
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct ScreenshotParams {
+    /// Largest width wanted; 0 or absent keeps the window's.
+    #[serde(default)]
+    width: u32,
+    #[serde(default)]
+    height: u32,
+}
+
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct RpcRawParams {
     method: String,
     params: Option<serde_json::Map<String, serde_json::Value>>,
 }
 
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct ProfileParams {
+    /// "start", "stop" or "read".
+    #[serde(default = "default_profile_action")]
+    action: String,
+    /// Frames the game keeps once started; 600 by default.
+    capacity: Option<usize>,
+    /// How many of the slowest systems to list.
+    #[serde(default = "default_top_systems")]
+    top: usize,
+}
+
+fn default_profile_action() -> String { "read".to_string() }
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct ListTypesParams {
+    /// "components", "resources" or "all".
+    #[serde(default = "default_list_kind")]
+    kind: String,
+}
+
+fn default_list_kind() -> String { "all".to_string() }
+fn default_top_systems() -> usize { 10 }
+
 #[derive(Clone)]
 struct BevyMcpServer {
     tool_router: ToolRouter<Self>,
     client: BrpClient,
+    metrics: Arc<BrpMetrics>,
 }
 
 #[tool_router]
 impl BevyMcpServer {
     fn new() -> Self {
-        let config = BrpConfig::from_env();
-        let client = BrpClient::new(config);
-        
+        Self::with_client(BrpClient::new(BrpConfig::from_env()))
+    }
+
+    fn with_client(client: BrpClient) -> Self {
+        let metrics = Arc::new(BrpMetrics::new());
         Self {
             tool_router: Self::tool_router(),
-            client,
+            client: client.with_middleware(metrics.clone()),
+            metrics,
         }
     }
 
-    #[tool(description = "Check connectivity to Bevy BRP server")]
+    #[tool(description = "Check connectivity to Bevy BRP server; reports latency, method count, Bevy and plugin versions, frames and uptime")]
     async fn bevy_ping(&self, _params: Parameters<PingParams>) -> Result<CallToolResult, McpError> {
         let response = ops::ping::ping(&self.client).await
             .map_err(|e| McpError::internal_error(format!("Ping failed: {}", e), None))?;
         
         Ok(CallToolResult::structured(serde_json::json!({
             "alive": response.alive,
+            "latency_ms": response.latency_ms,
+            "method_count": response.method_count,
+            "bevy_version": response.bevy_version,
+            "plugin_version": response.plugin_version,
+            "frames": response.frames,
+            "uptime_secs": response.uptime_secs,
             "methods": response.methods
         })))
     }
 
-    #[tool(description = "Query entities by component types")]
+    #[tool(description = "Requests this server sent the game so far: counts and errors by method, bytes sent and p50/p99 latency")]
+    async fn bevy_metrics(&self, params: Parameters<MetricsParams>) -> Result<CallToolResult, McpError> {
+        let snapshot = self.metrics.snapshot();
+        if params.0.reset {
+            self.metrics.reset();
+        }
+        let output = serde_json::to_value(snapshot)
+            .map_err(|e| McpError::internal_error(format!("Metrics failed: {}", e), None))?;
+        Ok(CallToolResult::structured(output))
+    }
+
+    #[tool(description = "Query entities by component types, optionally filtered by other components and component values, a page at a time (100 entities unless limit says otherwise)")]
     async fn bevy_query(&self, params: Parameters<QueryParams>) -> Result<CallToolResult, McpError> {
-        let response = ops::query::query(&self.client, params.0.components.clone()).await
+        let params = params.0;
+        let predicates = params.predicates.into_iter()
+            .map(|p| {
+                let op = serde_json::from_value(serde_json::Value::String(p.op.clone()))
+                    .map_err(|_| McpError::invalid_params(format!("Unknown op `{}`; expected eq, ne, lt, le, gt, ge or contains", p.op), None))?;
+                let fields = p.path.split('.').filter(|field| !field.is_empty()).map(str::to_string).collect();
+                Ok(types::Filter::Compare { component: p.component, fields, op, value: p.value })
+            })
+            .collect::<Result<Vec<_>, McpError>>()?;
+        let options = types::QueryOptions {
+            with: params.with,
+            without: params.without,
+            filter: predicates.into_iter().reduce(types::Filter::and),
+            offset: params.offset,
+            limit: Some(params.limit),
+        };
+        let response = ops::query::query(&self.client, params.components, options).await
             .map_err(|e| McpError::internal_error(format!("Query failed: {}", e), None))?;
         
         Ok(CallToolResult::structured(serde_json::json!({
-            "entities": response.entities
+            "entities": response.entities,
+            "total": response.total
         })))
     }
 
      #[tool(description = "Spawn a primitive object in the Bevy scene")]
      async fn bevy_spawn_primitive(&self, params: Parameters<SpawnPrimitiveParams>) -> Result<CallToolResult, McpError> {
-         let primitive_type = params.0.primitive_type.to_lowercase();
-         let response = ops::spawn::spawn(
-             &self.client,
-             &primitive_type,
-             params.0.position,
-             params.0.rotation,
-             params.0.scale,
-         ).await
+         let request = types::SpawnRequest {
+             material: params.0.material()?,
+             primitive: params.0.primitive_type.to_lowercase(),
+             position: params.0.position,
+             rotation: params.0.rotation,
+             scale: params.0.scale,
+             name: params.0.name,
+             group: params.0.group,
+         };
+         let response = ops::spawn::spawn(&self.client, &request).await
              .map_err(|e| McpError::internal_error(format!("Spawn failed: {}", e), None))?;
         
         Ok(CallToolResult::structured(serde_json::json!({
@@ -114,32 +297,80 @@ impl BevyMcpServer {
         })))
     }
 
+    #[tool(description = "Spawn many primitive objects in one call, e.g. to lay out a grid or a row; prefer it over repeated bevy_spawn_primitive")]
+    async fn bevy_spawn_primitives(&self, params: Parameters<SpawnPrimitivesParams>) -> Result<CallToolResult, McpError> {
+        let requests = params.0.primitives.into_iter()
+            .map(|p| Ok(types::SpawnRequest {
+                material: p.material()?,
+                primitive: p.primitive_type.to_lowercase(),
+                position: p.position,
+                rotation: p.rotation,
+                scale: p.scale,
+                name: p.name,
+                group: p.group,
+            }))
+            .collect::<Result<_, McpError>>()?;
+        let results = ops::spawn::spawn_batch(&self.client, requests).await
+            .map_err(|e| McpError::internal_error(format!("Spawn failed: {}", e), None))?;
+
+        let mut entity_ids = Vec::with_capacity(results.len());
+        let mut errors = Vec::new();
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(response) => entity_ids.push(serde_json::json!(response.entity_id)),
+                Err(e) => {
+                    entity_ids.push(serde_json::Value::Null);
+                    errors.push(serde_json::json!({ "index": index, "error": e.to_string() }));
+                }
+            }
+        }
+        Ok(CallToolResult::structured(serde_json::json!({
+            "entity_ids": entity_ids,
+            "errors": errors
+        })))
+    }
+
     #[tool(description = "Upload an asset (GLB, texture) to the Bevy runtime")]
     async fn bevy_upload_asset(&self, params: Parameters<UploadAssetParams>) -> Result<CallToolResult, McpError> {
         let bytes = base64::engine::general_purpose::STANDARD
             .decode(&params.0.data_base64)
             .map_err(|e| McpError::invalid_params(format!("Invalid base64: {}", e), None))?;
         
-        let response = ops::upload::upload(
-            &self.client,
-            &params.0.filename,
-            &bytes,
-            params.0.subdir.as_deref(),
-            params.0.translation,
-            params.0.rotation,
-        ).await
+        let options = types::UploadOptions {
+            subdir: params.0.subdir,
+            translation: params.0.translation,
+            rotation: params.0.rotation,
+            name: params.0.name,
+            group: params.0.group,
+        };
+        let response = ops::upload::upload(&self.client, &params.0.filename, &bytes, &options).await
             .map_err(|e| McpError::internal_error(format!("Upload failed: {}", e), None))?;
         
         Ok(CallToolResult::structured(serde_json::json!({
-            "entity_id": response.entity_id
+            "entity_id": response.entity_id,
+            "cached": response.cached
         })))
     }
 
-    #[tool(description = "Clear scene entities (all, assets, or primitives)")]
+    #[tool(description = "Find the entities with a given name, as set when spawning or uploading, to edit them without remembering their ids")]
+    async fn bevy_find_by_name(&self, params: Parameters<FindByNameParams>) -> Result<CallToolResult, McpError> {
+        let entity_ids = ops::find::by_name(&self.client, &params.0.name).await
+            .map_err(|e| McpError::internal_error(format!("Find failed: {}", e), None))?;
+
+        Ok(CallToolResult::structured(serde_json::json!({
+            "entity_ids": entity_ids
+        })))
+    }
+
+    #[tool(description = "Clear scene entities (all, assets, primitives, or one group)")]
     async fn bevy_clear_scene(&self, params: Parameters<ClearSceneParams>) -> Result<CallToolResult, McpError> {
         let target = match params.0.target.as_str() {
             "assets" => types::ClearTarget::Assets,
             "primitives" => types::ClearTarget::Primitives,
+            "group" => match params.0.group {
+                Some(group) => types::ClearTarget::Group(group),
+                None => return Err(McpError::invalid_params("target \"group\" needs a group", None)),
+            },
             _ => types::ClearTarget::All,
         };
         
@@ -151,6 +382,59 @@ impl BevyMcpServer {
         })))
     }
 
+    #[tool(description = "Profile the game's frames: start recording, let the game run, then read or stop. Returns average and worst frame time, FPS, entity counts and the slowest schedules and systems")]
+    async fn bevy_profile(&self, params: Parameters<ProfileParams>) -> Result<CallToolResult, McpError> {
+        let capture = match params.0.action.as_str() {
+            "start" => ops::profile::start(&self.client, params.0.capacity).await,
+            "stop" => ops::profile::stop(&self.client).await,
+            "read" => ops::profile::read(&self.client).await,
+            other => return Err(McpError::invalid_params(format!("Unknown action `{}`; expected start, stop or read", other), None)),
+        }
+        .map_err(|e| McpError::internal_error(format!("Profile failed: {}", e), None))?;
+
+        Ok(CallToolResult::structured(serde_json::json!({
+            "recording": capture.recording,
+            "system_timing": capture.system_timing,
+            "summary": capture.summary(params.0.top),
+        })))
+    }
+
+    #[tool(description = "List the component and resource types registered in the running game, as full type paths for bevy_query and bevy_rpc_raw")]
+    async fn bevy_list_types(&self, params: Parameters<ListTypesParams>) -> Result<CallToolResult, McpError> {
+        let (components, resources) = match params.0.kind.as_str() {
+            "components" => (true, false),
+            "resources" => (false, true),
+            "all" => (true, true),
+            other => return Err(McpError::invalid_params(format!("Unknown kind `{}`; expected components, resources or all", other), None)),
+        };
+        let to_mcp = |e: bevy_bridge_core::BrpError| McpError::internal_error(format!("Listing types failed: {}", e), None);
+        let mut result = serde_json::Map::new();
+        if components {
+            result.insert("components".into(), ops::list::list_components(&self.client).await.map_err(to_mcp)?.into());
+        }
+        if resources {
+            result.insert("resources".into(), ops::list::list_resources(&self.client).await.map_err(to_mcp)?.into());
+        }
+        Ok(CallToolResult::structured(serde_json::Value::Object(result)))
+    }
+
+    #[tool(description = "The running game's most recent log records at or above a level, e.g. the last 50 warnings. Empty unless the game installs bevy_ai_remote::log_layer")]
+    async fn bevy_logs(&self, params: Parameters<LogsParams>) -> Result<CallToolResult, McpError> {
+        let level = serde_json::from_value(serde_json::Value::String(params.0.level.clone()))
+            .map_err(|_| McpError::invalid_params(format!("Unknown level `{}`; expected trace, debug, info, warn or error", params.0.level), None))?;
+        let records = ops::logs::tail(&self.client, level, params.0.limit).await
+            .map_err(|e| McpError::internal_error(format!("Reading logs failed: {}", e), None))?;
+        Ok(CallToolResult::structured(serde_json::json!({ "records": records })))
+    }
+
+    #[tool(description = "Render the game's main camera and return it as a PNG image, to see the result of edits. Scaled down to fit width and height if given")]
+    async fn bevy_screenshot(&self, params: Parameters<ScreenshotParams>) -> Result<CallToolResult, McpError> {
+        let shot = ops::screenshot::capture(&self.client, params.0.width, params.0.height).await
+            .map_err(|e| McpError::internal_error(format!("Screenshot failed: {}", e), None))?;
+        let data = base64::engine::general_purpose::STANDARD.encode(&shot.png);
+        Ok(CallToolResult::success(vec![Content::image(data, "image/png")]))
+    }
+
     #[tool(description = "Raw BRP RPC call (advanced users only - no parameter wrapping)")]
     async fn bevy_rpc_raw(&self, params: Parameters<RpcRawParams>) -> Result<CallToolResult, McpError> {
         let result = ops::raw::raw(&self.client, &params.0.method, params.0.params.clone().map(serde_json::Value::Object)).await
@@ -175,6 +459,12 @@ impl ServerHandler for BevyMcpServer {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
+
+    // axiom.toml fills in what the environment leaves unset
+    match axiom_config::AxiomConfig::load(std::env::current_dir().ok().as_deref()) {
+        Ok(config) => config.apply_env(),
+        Err(e) => tracing::warn!("{:#}", e),
+    }
     
     let server = BevyMcpServer::new();
     let transport = transport::stdio();
@@ -200,4 +490,32 @@ mod tests {
             serde_json::to_string_pretty(&schema).unwrap()
         );
     }
+
+    #[test]
+    fn query_params_schema_has_no_bare_true() {
+        let schema = schemars::schema_for!(QueryParams);
+        let json = serde_json::to_string(&schema).unwrap();
+        assert!(!json.contains("\"value\":true"), "{}", serde_json::to_string_pretty(&schema).unwrap());
+    }
+
+    #[tokio::test]
+    async fn query_filters_on_component_values() {
+        let game = bevy_bridge_core::testing::MockBrpServer::start();
+        game.reply("world.query", serde_json::json!([
+            { "entity": 1, "components": { "game::Health": 10 } },
+            { "entity": 2, "components": { "game::Health": 90 } }
+        ]));
+        let server = BevyMcpServer::with_client(game.client());
+
+        let params: QueryParams = serde_json::from_value(serde_json::json!({
+            "components": ["game::Health"],
+            "where": [{ "component": "game::Health", "op": "gt", "value": 50 }]
+        }))
+        .unwrap();
+        let result = server.bevy_query(Parameters(params)).await.unwrap();
+        let output = result.structured_content.unwrap();
+        assert_eq!(output["total"], 1);
+        assert_eq!(output["entities"][0]["entity"], 2);
+        assert_eq!(game.calls_to("world.query").len(), 1);
+    }
 }
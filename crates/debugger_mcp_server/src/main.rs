@@ -38,10 +38,13 @@ const OUTPUT_EVENT_WAIT_TIMEOUT: Duration = Duration::from_millis(300);
 const MAX_RECENT_OUTPUT_EVENTS: usize = 1024;
 const READ_MEMORY_MAX_COUNT: u32 = 64 * 1024;
 const AXIOM_DEBUG_PROBE_SNAPSHOT_CAPACITY: usize = 4096;
+const GAME_LAUNCH_POLL_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Debug, Serialize, Deserialize, JsonSchema)]
 struct DebuggerAttachParams {
-    pid: u32,
+    /// Defaults to the game the editor launched last.
+    #[serde(default)]
+    pid: Option<u32>,
     #[serde(default)]
     program: Option<String>,
     #[serde(default)]
@@ -809,13 +812,22 @@ impl DebuggerMcpServer {
             ));
         }
 
+        let (pid, program) = match params.pid {
+            Some(pid) => (pid, params.program.clone()),
+            None => {
+                let launch = launched_game()?;
+                let program = params.program.clone().or_else(|| Some(launch.program.to_string_lossy().into_owned()));
+                (launch.pid, program)
+            }
+        };
+
         let adapter_path = params
             .adapter_path
             .clone()
             .or_else(|| std::env::var("CODELLDB_ADAPTER_PATH").ok())
             .ok_or_else(|| {
                 to_mcp_error(
-                    "Missing CodeLLDB adapter path. Set CODELLDB_ADAPTER_PATH or pass adapter_path.",
+                    "Missing CodeLLDB adapter path. Set CODELLDB_ADAPTER_PATH, debugger.adapter_path in axiom.toml, or pass adapter_path.",
                 )
             })?;
 
@@ -843,7 +855,7 @@ impl DebuggerMcpServer {
         let recent_output_events = Arc::new(Mutex::new(VecDeque::new()));
         let initialized_seen = Arc::new(Mutex::new(false));
         let initialized_notify = Arc::new(Notify::new());
-        let audit = Arc::new(AuditLogger::new(params.pid).await.map_err(to_mcp_error)?);
+        let audit = Arc::new(AuditLogger::new(pid).await.map_err(to_mcp_error)?);
         let reader_task = tokio::spawn(reader_loop(
             stdout,
             pending.clone(),
@@ -865,7 +877,7 @@ impl DebuggerMcpServer {
             initialized_seen,
             initialized_notify,
             next_seq: 0,
-            attached_pid: params.pid,
+            attached_pid: pid,
             configuration_done_sent: false,
             reader_task,
             audit: audit.clone(),
@@ -906,7 +918,7 @@ impl DebuggerMcpServer {
         }
 
         let (attach_seq, attach_rx) = match session
-            .send_request_begin("attach", attach_args(params.pid, params.program.clone()))
+            .send_request_begin("attach", attach_args(pid, program))
             .await
         {
             Ok(value) => value,
@@ -1624,11 +1636,69 @@ impl ServerHandler for DebuggerMcpServer {
     }
 }
 
+/// The game the editor started from the project in the working directory.
+fn launched_game() -> Result<axiom_config::GameLaunch, McpError> {
+    let root = std::env::current_dir().map_err(|e| to_mcp_error(e.to_string()))?;
+    axiom_config::GameLaunch::load(&root)
+        .map_err(|e| to_mcp_error(format!("{e:#}")))?
+        .ok_or_else(|| to_mcp_error("Missing pid, and no game launched from the editor. Pass pid or start the game in Axiom."))
+}
+
+/// Attach to every game the editor launches, replacing the previous session.
+async fn follow_launched_games(server: DebuggerMcpServer, root: PathBuf) {
+    let mut followed = None;
+    loop {
+        sleep(GAME_LAUNCH_POLL_INTERVAL).await;
+        let launch = match axiom_config::GameLaunch::load(&root) {
+            Ok(Some(launch)) => launch,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::debug!("{:#}", e);
+                continue;
+            }
+        };
+        if followed == Some(launch.pid) {
+            continue;
+        }
+        followed = Some(launch.pid);
+
+        let detach = DebuggerDetachParams { terminate_debuggee: false };
+        if let Err(e) = server.debugger_detach(Parameters(detach)).await {
+            tracing::warn!("Failed to detach from the previous game: {}", e.message);
+        }
+        let attach = DebuggerAttachParams {
+            pid: Some(launch.pid),
+            program: Some(launch.program.to_string_lossy().into_owned()),
+            adapter_path: None,
+        };
+        match server.debugger_attach(Parameters(attach)).await {
+            Ok(_) => tracing::info!("Attached to the game (pid {})", launch.pid),
+            Err(e) => tracing::warn!("Failed to attach to the game (pid {}): {}", launch.pid, e.message),
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     tracing_subscriber::fmt::init();
 
+    // axiom.toml fills in what the environment leaves unset
+    let cwd = std::env::current_dir().ok();
+    let config = match axiom_config::AxiomConfig::load(cwd.as_deref()) {
+        Ok(config) => {
+            config.apply_env();
+            config
+        }
+        Err(e) => {
+            tracing::warn!("{:#}", e);
+            axiom_config::AxiomConfig::default()
+        }
+    };
+
     let server = DebuggerMcpServer::new();
+    if let (true, Some(root)) = (config.game.auto_attach_debugger, cwd) {
+        tokio::spawn(follow_launched_games(server.clone(), root));
+    }
     let transport = transport::stdio();
 
     tracing::info!("Starting Debugger MCP Server on stdio...");
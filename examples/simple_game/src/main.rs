@@ -26,7 +26,7 @@ fn main() {
                 }),
         )
         .insert_resource(WinitSettings::game())
-        // .add_plugins(BevyAiRemotePlugin)
+        // .add_plugins(BevyAiRemotePlugin::default())
         .add_systems(Startup, setup)
         .add_plugins(PlayerPlugin)
         // .add_systems(Update, (draw_gizmos, camera_controller))